@@ -49,6 +49,7 @@ use structopt::{StructOpt, clap::AppSettings};
 pub use structopt::clap::App;
 use params::{
 	RunCmd, PurgeChainCmd, RevertCmd, ImportBlocksCmd, ExportBlocksCmd, BuildSpecCmd,
+	DbStatsCmd, DbCompactCmd,
 	NetworkConfigurationParams, MergeParameters, TransactionPoolParams,
 	NodeKeyParams, NodeKeyType, Cors,
 };
@@ -234,6 +235,10 @@ where
 			purge_chain::<F, _>(params, spec_factory, version).map(|_| None),
 		params::CoreParams::Revert(params) =>
 			revert_chain::<F, _>(params, spec_factory, version).map(|_| None),
+		params::CoreParams::DbStats(params) =>
+			db_stats::<F, _>(params, spec_factory, version).map(|_| None),
+		params::CoreParams::DbCompact(params) =>
+			db_compact::<F, _>(params, spec_factory, version).map(|_| None),
 		params::CoreParams::Custom(params) => Ok(Some(params)),
 	}
 }
@@ -407,13 +412,13 @@ where
 	config.database_path =
 		db_path(&base_path, config.chain_spec.id()).to_string_lossy().into();
 	config.database_cache_size = cli.database_cache_size;
+	config.database_backend = cli.shared_params.database_backend.into();
 	config.state_cache_size = cli.state_cache_size;
 	config.pruning = match cli.pruning {
 		Some(ref s) if s == "archive" => PruningMode::ArchiveAll,
+		Some(ref s) if s == "archive-canonical" => PruningMode::ArchiveCanonical,
 		None => PruningMode::default(),
-		Some(s) => PruningMode::keep_blocks(
-			s.parse().map_err(|_| error::Error::Input("Invalid pruning mode specified".to_string()))?
-		),
+		Some(s) => parse_pruning_constraints(&s)?,
 	};
 
 	let role =
@@ -496,6 +501,7 @@ where
 			"https://substrate-ui.parity.io".into(),
 		])
 	}).into();
+	config.rpc_methods = cli.rpc_methods.into();
 
 	// Override telemetry
 	if cli.no_telemetry {
@@ -595,6 +601,7 @@ where
 
 	let mut config = service::Configuration::default_with_spec(spec.clone());
 	config.database_path = db_path(&base_path, spec.id()).to_string_lossy().into();
+	config.database_backend = cli.database_backend.into();
 
 	Ok(config)
 }
@@ -664,6 +671,76 @@ where
 	Ok(service::chain_ops::revert_chain::<F>(config, blocks.into())?)
 }
 
+fn db_stats<F, S>(
+	cli: DbStatsCmd,
+	spec_factory: S,
+	version: &VersionInfo,
+) -> error::Result<()>
+where
+	F: ServiceFactory,
+	S: FnOnce(&str) -> Result<Option<ChainSpec<FactoryGenesis<F>>>, String>,
+{
+	let config = create_config_with_db_path::<F, _>(spec_factory, &cli.shared_params, version)?;
+
+	info!("DB path: {}", config.database_path);
+	let stats = match service::chain_ops::database_stats::<F>(config)? {
+		Some(stats) => stats,
+		None => {
+			println!("Database statistics are not available.");
+			return Ok(());
+		},
+	};
+
+	println!("{:<16} {:>12} {:>16}", "Column", "Keys", "Size (bytes)");
+	for column in &stats.columns {
+		println!("{:<16} {:>12} {:>16}", column.name, column.keys, column.size);
+	}
+	println!();
+
+	let state_db = &stats.state_db;
+	match state_db.pruning_window_start {
+		Some(start) => println!(
+			"Pruning window: #{} .. #{} ({} blocks)",
+			start,
+			start + state_db.pruning_window_size,
+			state_db.pruning_window_size,
+		),
+		None => println!("Pruning window: none (archive)"),
+	}
+	match state_db.last_canonical {
+		Some(number) => println!("Last canonical block: #{}", number),
+		None => println!("Last canonical block: none"),
+	}
+	println!(
+		"Non-canonical overlay: {} blocks, {} nodes, {} bytes",
+		state_db.non_canonical_blocks,
+		state_db.non_canonical_values,
+		state_db.non_canonical_size,
+	);
+	println!("Pinned states: {}", state_db.pinned_states);
+
+	Ok(())
+}
+
+fn db_compact<F, S>(
+	cli: DbCompactCmd,
+	spec_factory: S,
+	version: &VersionInfo,
+) -> error::Result<()>
+where
+	F: ServiceFactory,
+	S: FnOnce(&str) -> Result<Option<ChainSpec<FactoryGenesis<F>>>, String>,
+{
+	let config = create_config_with_db_path::<F, _>(spec_factory, &cli.shared_params, version)?;
+
+	Ok(service::chain_ops::compact_database::<F>(
+		config,
+		cli.column.as_ref().map(|c| c.as_str()),
+		cli.from.as_ref().map(|k| k.as_slice()),
+		cli.to.as_ref().map(|k| k.as_slice()),
+	)?)
+}
+
 fn purge_chain<F, S>(
 	cli: PurgeChainCmd,
 	spec_factory: S,
@@ -720,6 +797,24 @@ fn parse_address(
 	Ok(address)
 }
 
+/// Parse `<blocks>` or `<blocks>,checkpoints=<interval>` pruning mode.
+fn parse_pruning_constraints(s: &str) -> error::Result<PruningMode> {
+	let invalid = || error::Error::Input("Invalid pruning mode specified".to_string());
+	let mut parts = s.split(',');
+	let blocks: u32 = parts.next().ok_or_else(invalid)?.parse().map_err(|_| invalid())?;
+	match parts.next() {
+		None => Ok(PruningMode::keep_blocks(blocks)),
+		Some(p) if p.starts_with("checkpoints=") && parts.next().is_none() => {
+			let interval: u32 = p["checkpoints=".len()..].parse().map_err(|_| invalid())?;
+			if interval == 0 {
+				return Err(invalid());
+			}
+			Ok(PruningMode::keep_blocks_with_checkpoints(blocks, interval))
+		},
+		_ => Err(invalid()),
+	}
+}
+
 fn keystore_path(base_path: &Path, chain_id: &str) -> PathBuf {
 	let mut path = base_path.to_owned();
 	path.push("chains");
@@ -828,6 +923,28 @@ mod tests {
 		assert!(is_node_name_valid("email@domain").is_err());
 	}
 
+	#[test]
+	fn parses_pruning_constraints() {
+		assert_eq!(parse_pruning_constraints("256").unwrap(), PruningMode::keep_blocks(256));
+		assert_eq!(
+			parse_pruning_constraints("256,checkpoints=1000").unwrap(),
+			PruningMode::keep_blocks_with_checkpoints(256, 1000),
+		);
+	}
+
+	#[test]
+	fn rejects_invalid_pruning_constraints() {
+		assert!(parse_pruning_constraints("").is_err());
+		assert!(parse_pruning_constraints("-1").is_err());
+		assert!(parse_pruning_constraints("many").is_err());
+		assert!(parse_pruning_constraints("256,").is_err());
+		assert!(parse_pruning_constraints("256,checkpoints=").is_err());
+		assert!(parse_pruning_constraints("256,checkpoints=0").is_err());
+		assert!(parse_pruning_constraints("256,checkpoints=x").is_err());
+		assert!(parse_pruning_constraints("256,interval=1000").is_err());
+		assert!(parse_pruning_constraints("256,checkpoints=1000,checkpoints=10").is_err());
+	}
+
 	#[test]
 	fn test_node_key_config_input() {
 		fn secret_input(net_config_dir: Option<String>) -> error::Result<()> {
@@ -55,6 +55,44 @@ impl Into<client::ExecutionStrategy> for ExecutionStrategy {
 	}
 }
 
+arg_enum! {
+	/// Key-value database backend
+	#[derive(Debug, Clone, Copy)]
+	pub enum DatabaseBackend {
+		RocksDb,
+		AppendLog,
+	}
+}
+
+impl Into<service::DatabaseBackend> for DatabaseBackend {
+	fn into(self) -> service::DatabaseBackend {
+		match self {
+			DatabaseBackend::RocksDb => service::DatabaseBackend::RocksDb,
+			DatabaseBackend::AppendLog => service::DatabaseBackend::AppendLog,
+		}
+	}
+}
+
+arg_enum! {
+	/// RPC methods to expose
+	#[derive(Debug, Copy, Clone, PartialEq)]
+	pub enum RpcMethods {
+		Auto,
+		Safe,
+		Unsafe,
+	}
+}
+
+impl Into<service::RpcMethods> for RpcMethods {
+	fn into(self) -> service::RpcMethods {
+		match self {
+			RpcMethods::Auto => service::RpcMethods::Auto,
+			RpcMethods::Safe => service::RpcMethods::Safe,
+			RpcMethods::Unsafe => service::RpcMethods::Unsafe,
+		}
+	}
+}
+
 arg_enum! {
 	/// How to execute blocks
 	#[derive(Debug, Clone)]
@@ -83,6 +121,18 @@ pub struct SharedParams {
 	/// Sets a custom logging filter
 	#[structopt(short = "l", long = "log", value_name = "LOG_PATTERN")]
 	pub log: Option<String>,
+
+	/// Specify the key-value database backend
+	#[structopt(
+		long = "db-backend",
+		value_name = "BACKEND",
+		raw(
+			possible_values = "&DatabaseBackend::variants()",
+			case_insensitive = "true",
+			default_value = r#""RocksDb""#
+		)
+	)]
+	pub database_backend: DatabaseBackend,
 }
 
 impl GetLogFilter for SharedParams {
@@ -345,7 +395,23 @@ pub struct RunCmd {
 	#[structopt(long = "rpc-cors", value_name = "ORIGINS", parse(try_from_str = "parse_cors"))]
 	pub rpc_cors: Option<Cors>,
 
-	/// Specify the pruning mode, a number of blocks to keep or 'archive'. Default is 256.
+	/// RPC methods to expose.
+	/// 'Unsafe' exposes every method, 'Safe' only a safe subset. 'Auto' exposes every method
+	/// on servers listening on a loopback interface and only the safe ones otherwise.
+	#[structopt(
+		long = "rpc-methods",
+		value_name = "METHOD SET",
+		raw(
+			possible_values = "&RpcMethods::variants()",
+			case_insensitive = "true",
+			default_value = r#""Auto""#
+		)
+	)]
+	pub rpc_methods: RpcMethods,
+
+	/// Specify the pruning mode, a number of blocks to keep, 'archive' or 'archive-canonical'. Default is 256.
+	/// The state of every N-th finalized block can be kept in addition to the recent blocks
+	/// with '<blocks>,checkpoints=<N>'.
 	#[structopt(long = "pruning", value_name = "PRUNING_MODE")]
 	pub pruning: Option<String>,
 
@@ -621,6 +687,44 @@ pub struct PurgeChainCmd {
 
 impl_get_log_filter!(PurgeChainCmd);
 
+/// The `db-stats` command used to print database usage statistics.
+#[derive(Debug, StructOpt, Clone)]
+pub struct DbStatsCmd {
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl_get_log_filter!(DbStatsCmd);
+
+/// The `db-compact` command used to compact the database.
+#[derive(Debug, StructOpt, Clone)]
+pub struct DbCompactCmd {
+	/// Name of the column to compact. All columns are compacted if unspecified.
+	#[structopt(long = "column", value_name = "NAME")]
+	pub column: Option<String>,
+
+	/// First key of the range to compact, hex encoded.
+	#[structopt(long = "from", value_name = "KEY", parse(try_from_str = "parse_hex_key"))]
+	pub from: Option<Vec<u8>>,
+
+	/// Key the range to compact ends before, hex encoded.
+	#[structopt(long = "to", value_name = "KEY", parse(try_from_str = "parse_hex_key"))]
+	pub to: Option<Vec<u8>>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl_get_log_filter!(DbCompactCmd);
+
+/// Parse hex encoded key, with or without the `0x` prefix.
+fn parse_hex_key(s: &str) -> Result<Vec<u8>, String> {
+	let s = if s.starts_with("0x") { &s[2..] } else { s };
+	hex::decode(s).map_err(|e| format!("Invalid hex key: {}", e))
+}
+
 /// All core commands that are provided by default.
 ///
 /// The core commands are split into multiple subcommands and `Run` is the default subcommand. From
@@ -646,6 +750,12 @@ pub enum CoreParams<CC, RP> {
 	/// Remove the whole chain data.
 	PurgeChain(PurgeChainCmd),
 
+	/// Print database usage statistics.
+	DbStats(DbStatsCmd),
+
+	/// Compact the database.
+	DbCompact(DbCompactCmd),
+
 	/// Further custom subcommands.
 	Custom(CC),
 }
@@ -679,6 +789,14 @@ impl<CC, RP> StructOpt for CoreParams<CC, RP> where
 			PurgeChainCmd::augment_clap(SubCommand::with_name("purge-chain"))
 				.about("Remove the whole chain data.")
 		)
+		.subcommand(
+			DbStatsCmd::augment_clap(SubCommand::with_name("db-stats"))
+				.about("Print database usage statistics.")
+		)
+		.subcommand(
+			DbCompactCmd::augment_clap(SubCommand::with_name("db-compact"))
+				.about("Compact the database.")
+		)
 	}
 
 	fn from_clap(matches: &::structopt::clap::ArgMatches) -> Self {
@@ -692,6 +810,8 @@ impl<CC, RP> StructOpt for CoreParams<CC, RP> where
 			("revert", Some(matches)) => CoreParams::Revert(RevertCmd::from_clap(matches)),
 			("purge-chain", Some(matches)) =>
 				CoreParams::PurgeChain(PurgeChainCmd::from_clap(matches)),
+			("db-stats", Some(matches)) => CoreParams::DbStats(DbStatsCmd::from_clap(matches)),
+			("db-compact", Some(matches)) => CoreParams::DbCompact(DbCompactCmd::from_clap(matches)),
 			(_, None) => CoreParams::Run(MergeParameters::from_clap(matches)),
 			_ => CoreParams::Custom(CC::from_clap(matches)),
 		}
@@ -707,6 +827,8 @@ impl<CC, RP> GetLogFilter for CoreParams<CC, RP> where CC: GetLogFilter {
 			CoreParams::ImportBlocks(c) => c.get_log_filter(),
 			CoreParams::PurgeChain(c) => c.get_log_filter(),
 			CoreParams::Revert(c) => c.get_log_filter(),
+			CoreParams::DbStats(c) => c.get_log_filter(),
+			CoreParams::DbCompact(c) => c.get_log_filter(),
 			CoreParams::Custom(c) => c.get_log_filter(),
 		}
 	}
@@ -0,0 +1,635 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Embedded key-value database backed by a single append-only log file.
+//!
+//! Every committed transaction is appended to the log as one record:
+//!
+//! ```text
+//! record = body_len: u32 (LE) ++ checksum: u32 (LE) ++ body
+//! body   = op*
+//! op     = 0u8 ++ column ++ key_len: u32 ++ key ++ value_len: u32 ++ value  (insert)
+//!        | 1u8 ++ column ++ key_len: u32 ++ key                             (delete)
+//! column = u32 (LE), `u32::max_value()` for the default column
+//! ```
+//!
+//! `checksum` is the CRC-32 (IEEE) of the body.
+//!
+//! Only keys and the positions of their values in the log are kept in memory;
+//! values are read from the file on demand. On open the log is replayed to rebuild
+//! this index. A final record that has not been written completely (e.g. because
+//! the node has crashed) is discarded, so transactions are atomic. A damaged record
+//! that is followed by other records can't be the result of a torn write, so the
+//! database refuses to open instead of silently dropping the committed data after it.
+//!
+//! Records that are no longer referenced are reclaimed by rewriting the log when the
+//! database is opened and the amount of stale data exceeds `COMPACTION_THRESHOLD`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use kvdb::{KeyValueDB, DBTransaction, DBOp, DBValue};
+use log::{debug, warn};
+use parking_lot::{Mutex, RwLock};
+
+/// Name of the log file within the database directory.
+const LOG_FILE_NAME: &str = "db.log";
+/// Name of the file the compacted log is written to before it replaces the log.
+const COMPACTED_LOG_FILE_NAME: &str = "db.log.compact";
+/// Stale data (in bytes) that triggers compaction on open.
+const COMPACTION_THRESHOLD: u64 = 64 * 1024 * 1024;
+/// Maximal size of the record written during compaction.
+const COMPACTION_RECORD_SIZE: usize = 16 * 1024 * 1024;
+
+/// Size of the record header: body length and checksum.
+const RECORD_HEADER_SIZE: u64 = 8;
+
+const OP_INSERT: u8 = 0;
+const OP_DELETE: u8 = 1;
+const DEFAULT_COLUMN: u32 = u32::max_value();
+
+/// Position of the value in the log file.
+#[derive(Clone, Copy, Debug)]
+struct ValueLocation {
+	offset: u64,
+	len: u32,
+}
+
+type Column = BTreeMap<Vec<u8>, ValueLocation>;
+
+/// Operation decoded from the log record.
+enum LogOp<'a> {
+	Insert { col: Option<u32>, key: &'a [u8], value_offset: usize, value_len: u32 },
+	Delete { col: Option<u32>, key: &'a [u8] },
+}
+
+struct Log {
+	file: File,
+	/// Size of the valid part of the log. New records are written at this offset.
+	size: u64,
+}
+
+/// Key-value database that stores all the data in an append-only log.
+pub struct AppendLogDb {
+	path: PathBuf,
+	columns: RwLock<HashMap<Option<u32>, Column>>,
+	/// Values are read with positional reads, so readers only need a shared lock.
+	log: RwLock<Log>,
+	/// Error of the buffered write, reported by the next `flush`.
+	pending_error: Mutex<Option<io::Error>>,
+}
+
+impl AppendLogDb {
+	/// Open (or create) the database in given directory.
+	pub fn open(path: &Path, num_columns: u32) -> io::Result<Self> {
+		fs::create_dir_all(path)?;
+		let log_path = path.join(LOG_FILE_NAME);
+
+		let (mut log, mut columns) = open_log(&log_path, num_columns)?;
+		let live_size = columns.values()
+			.flat_map(|column| column.iter())
+			.map(|(key, location)| record_overhead(key) + location.len as u64)
+			.sum::<u64>();
+		let stale_size = log.size.saturating_sub(live_size);
+		if stale_size > COMPACTION_THRESHOLD && stale_size > live_size {
+			debug!(target: "db", "Compacting append log: {} of {} bytes are stale", stale_size, log.size);
+			compact(path, &log, &columns)?;
+			let (compacted_log, compacted_columns) = open_log(&log_path, num_columns)?;
+			log = compacted_log;
+			columns = compacted_columns;
+		}
+
+		Ok(AppendLogDb {
+			path: path.to_owned(),
+			columns: RwLock::new(columns),
+			log: RwLock::new(log),
+			pending_error: Mutex::new(None),
+		})
+	}
+
+	/// Path to the directory of the database.
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Rewrite the log, reclaiming the space taken by overwritten and deleted entries.
+	pub fn compact(&self) -> io::Result<()> {
+		let mut log = self.log.write();
+		let mut columns = self.columns.write();
+		compact(&self.path, &log, &columns)?;
+
+		let num_columns = columns.len() as u32 - 1;
+		let (compacted_log, compacted_columns) = open_log(&self.path.join(LOG_FILE_NAME), num_columns)?;
+		*log = compacted_log;
+		*columns = compacted_columns;
+		Ok(())
+	}
+
+	fn read_value(&self, location: ValueLocation) -> io::Result<DBValue> {
+		let log = self.log.read();
+		read_at(&log.file, location).map(|value| DBValue::from_slice(&value))
+	}
+
+	fn read_pairs<'a>(
+		&'a self,
+		pairs: Vec<(Vec<u8>, ValueLocation)>,
+	) -> Box<dyn Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+		Box::new(pairs.into_iter().filter_map(move |(key, location)| {
+			match self.read_value(location) {
+				Ok(value) => Some((key.into_boxed_slice(), value.to_vec().into_boxed_slice())),
+				Err(e) => {
+					warn!(target: "db", "Failed to read value from the append log: {}", e);
+					None
+				},
+			}
+		}))
+	}
+
+	fn append(&self, transaction: DBTransaction, sync: bool) -> io::Result<()> {
+		let body = encode_ops(&transaction.ops);
+		let ops = decode_ops(&body).expect("ops have just been encoded; qed");
+		if body.len() > u32::max_value() as usize {
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, "Transaction is too large"));
+		}
+
+		let mut log = self.log.write();
+		let mut columns = self.columns.write();
+		if let Some(col) = ops.iter().map(op_column).find(|col| !columns.contains_key(col)) {
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Unknown column: {:?}", col)));
+		}
+
+		let record_offset = log.size;
+		let body_offset = record_offset + RECORD_HEADER_SIZE;
+		log.file.seek(SeekFrom::Start(record_offset))?;
+		write_record(&mut log.file, &body)?;
+		if sync {
+			log.file.sync_data()?;
+		}
+		log.size = body_offset + body.len() as u64;
+
+		apply_ops(ops, body_offset, &mut columns);
+		Ok(())
+	}
+}
+
+impl KeyValueDB for AppendLogDb {
+	fn get(&self, col: Option<u32>, key: &[u8]) -> io::Result<Option<DBValue>> {
+		let location = match self.columns.read().get(&col) {
+			Some(column) => column.get(key).cloned(),
+			None => return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Unknown column: {:?}", col))),
+		};
+
+		match location {
+			Some(location) => self.read_value(location).map(Some),
+			None => Ok(None),
+		}
+	}
+
+	fn get_by_prefix(&self, col: Option<u32>, prefix: &[u8]) -> Option<Box<[u8]>> {
+		self.iter_from_prefix(col, prefix).next().map(|(_, value)| value)
+	}
+
+	fn write_buffered(&self, transaction: DBTransaction) {
+		if let Err(e) = self.append(transaction, false) {
+			*self.pending_error.lock() = Some(e);
+		}
+	}
+
+	fn write(&self, transaction: DBTransaction) -> io::Result<()> {
+		self.flush()?;
+		self.append(transaction, true)
+	}
+
+	fn flush(&self) -> io::Result<()> {
+		if let Some(e) = self.pending_error.lock().take() {
+			return Err(e);
+		}
+		self.log.read().file.sync_data()
+	}
+
+	fn iter<'a>(&'a self, col: Option<u32>) -> Box<dyn Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+		let pairs = match self.columns.read().get(&col) {
+			Some(column) => column.iter().map(|(key, location)| (key.clone(), *location)).collect(),
+			None => Vec::new(),
+		};
+		self.read_pairs(pairs)
+	}
+
+	fn iter_from_prefix<'a>(
+		&'a self,
+		col: Option<u32>,
+		prefix: &'a [u8],
+	) -> Box<dyn Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+		let pairs = match self.columns.read().get(&col) {
+			Some(column) => column.range(prefix.to_vec()..)
+				.take_while(|(key, _)| key.starts_with(prefix))
+				.map(|(key, location)| (key.clone(), *location))
+				.collect(),
+			None => Vec::new(),
+		};
+		self.read_pairs(pairs)
+	}
+
+	fn restore(&self, _new_db: &str) -> io::Result<()> {
+		Err(io::Error::new(io::ErrorKind::Other, "Restore is not supported by the append log database"))
+	}
+}
+
+/// Open the log file and replay it.
+///
+/// The final record is dropped if it is incomplete or damaged. A damaged record in the
+/// middle of the log is reported as an `InvalidData` error.
+fn open_log(log_path: &Path, num_columns: u32) -> io::Result<(Log, HashMap<Option<u32>, Column>)> {
+	let mut columns = HashMap::new();
+	columns.insert(None, Column::new());
+	for col in 0..num_columns {
+		columns.insert(Some(col), Column::new());
+	}
+
+	let mut file = OpenOptions::new().read(true).write(true).create(true).open(log_path)?;
+	let file_size = file.metadata()?.len();
+	let mut size = 0u64;
+	{
+		let mut reader = io::BufReader::new(&mut file);
+		while size + RECORD_HEADER_SIZE <= file_size {
+			let mut header = [0u8; RECORD_HEADER_SIZE as usize];
+			reader.read_exact(&mut header)?;
+			let mut input = &header[..];
+			let len = decode_u32(&mut input).expect("header is 8 bytes long; qed") as u64;
+			let checksum = decode_u32(&mut input).expect("header is 8 bytes long; qed");
+			let record_end = size + RECORD_HEADER_SIZE + len;
+			if record_end > file_size {
+				break;
+			}
+
+			let mut body = vec![0u8; len as usize];
+			reader.read_exact(&mut body)?;
+			let ops = if crc32(&body) == checksum { decode_ops(&body) } else { None };
+			match ops {
+				Some(ops) => apply_ops(ops, size + RECORD_HEADER_SIZE, &mut columns),
+				None if record_end == file_size => break,
+				None => return Err(io::Error::new(
+					io::ErrorKind::InvalidData,
+					format!("Corrupted record at offset {} of {}", size, log_path.display()),
+				)),
+			}
+			size = record_end;
+		}
+	}
+
+	if size != file_size {
+		warn!(
+			target: "db",
+			"Discarding {} bytes of incomplete record at the end of {}",
+			file_size - size,
+			log_path.display(),
+		);
+		file.set_len(size)?;
+		file.sync_all()?;
+	}
+
+	Ok((Log { file, size }, columns))
+}
+
+/// Write all live entries into the new log file and replace the current log with it.
+fn compact(path: &Path, log: &Log, columns: &HashMap<Option<u32>, Column>) -> io::Result<()> {
+	let compacted_path = path.join(COMPACTED_LOG_FILE_NAME);
+	let mut compacted = File::create(&compacted_path)?;
+
+	let mut body = Vec::new();
+	for (col, column) in columns {
+		for (key, location) in column {
+			let value = read_at(&log.file, *location)?;
+			encode_insert(&mut body, *col, key, &value);
+			if body.len() >= COMPACTION_RECORD_SIZE {
+				write_record(&mut compacted, &body)?;
+				body.clear();
+			}
+		}
+	}
+	if !body.is_empty() {
+		write_record(&mut compacted, &body)?;
+	}
+
+	compacted.sync_all()?;
+	fs::rename(&compacted_path, path.join(LOG_FILE_NAME))
+}
+
+fn write_record(file: &mut File, body: &[u8]) -> io::Result<()> {
+	let mut header = Vec::with_capacity(RECORD_HEADER_SIZE as usize);
+	header.extend_from_slice(&(body.len() as u32).to_le_bytes());
+	header.extend_from_slice(&crc32(body).to_le_bytes());
+	file.write_all(&header)?;
+	file.write_all(body)
+}
+
+/// Read the value without moving the file cursor, so that it only needs a shared reference.
+#[cfg(unix)]
+fn read_at(file: &File, location: ValueLocation) -> io::Result<Vec<u8>> {
+	use std::os::unix::fs::FileExt;
+
+	let mut value = vec![0u8; location.len as usize];
+	file.read_exact_at(&mut value, location.offset)?;
+	Ok(value)
+}
+
+/// Read the value without moving the file cursor, so that it only needs a shared reference.
+#[cfg(windows)]
+fn read_at(file: &File, location: ValueLocation) -> io::Result<Vec<u8>> {
+	use std::os::windows::fs::FileExt;
+
+	let mut value = vec![0u8; location.len as usize];
+	let mut read = 0;
+	while read < value.len() {
+		match file.seek_read(&mut value[read..], location.offset + read as u64)? {
+			0 => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Value is out of the log bounds")),
+			n => read += n,
+		}
+	}
+	Ok(value)
+}
+
+/// CRC-32 (IEEE 802.3) lookup table, processing 4 bits at a time.
+const CRC32_TABLE: [u32; 16] = [
+	0x00000000, 0x1db71064, 0x3b6e20c8, 0x26d930ac, 0x76dc4190, 0x6b6b51f4, 0x4db26158, 0x5005713c,
+	0xedb88320, 0xf00f9344, 0xd6d6a3e8, 0xcb61b38c, 0x9b64c2b0, 0x86d3d2d4, 0xa00ae278, 0xbdbdf21c,
+];
+
+fn crc32(data: &[u8]) -> u32 {
+	let mut crc = !0u32;
+	for byte in data {
+		crc = CRC32_TABLE[((crc ^ *byte as u32) & 0x0f) as usize] ^ (crc >> 4);
+		crc = CRC32_TABLE[((crc ^ (*byte as u32 >> 4)) & 0x0f) as usize] ^ (crc >> 4);
+	}
+	!crc
+}
+
+/// Approximate size of the log record entry, excluding value.
+fn record_overhead(key: &[u8]) -> u64 {
+	1 + 4 + 4 + key.len() as u64 + 4
+}
+
+fn op_column(op: &LogOp) -> Option<u32> {
+	match *op {
+		LogOp::Insert { col, .. } | LogOp::Delete { col, .. } => col,
+	}
+}
+
+fn encode_column(body: &mut Vec<u8>, col: Option<u32>) {
+	body.extend_from_slice(&col.unwrap_or(DEFAULT_COLUMN).to_le_bytes());
+}
+
+fn encode_bytes(body: &mut Vec<u8>, bytes: &[u8]) {
+	body.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+	body.extend_from_slice(bytes);
+}
+
+fn encode_insert(body: &mut Vec<u8>, col: Option<u32>, key: &[u8], value: &[u8]) {
+	body.push(OP_INSERT);
+	encode_column(body, col);
+	encode_bytes(body, key);
+	encode_bytes(body, value);
+}
+
+fn encode_ops(ops: &[DBOp]) -> Vec<u8> {
+	let mut body = Vec::new();
+	for op in ops {
+		match *op {
+			DBOp::Insert { col, ref key, ref value } => encode_insert(&mut body, col, key, value),
+			DBOp::Delete { col, ref key } => {
+				body.push(OP_DELETE);
+				encode_column(&mut body, col);
+				encode_bytes(&mut body, key);
+			},
+		}
+	}
+	body
+}
+
+fn decode_u32(input: &mut &[u8]) -> Option<u32> {
+	if input.len() < 4 {
+		return None;
+	}
+	let mut bytes = [0u8; 4];
+	bytes.copy_from_slice(&input[..4]);
+	*input = &input[4..];
+	Some(u32::from_le_bytes(bytes))
+}
+
+fn decode_bytes<'a>(input: &mut &'a [u8]) -> Option<&'a [u8]> {
+	let len = decode_u32(input)? as usize;
+	if input.len() < len {
+		return None;
+	}
+	let bytes = &input[..len];
+	*input = &input[len..];
+	Some(bytes)
+}
+
+/// Decode operations of the record body. Returns `None` if the body is malformed.
+fn decode_ops(body: &[u8]) -> Option<Vec<LogOp>> {
+	let mut ops = Vec::new();
+	let mut input = body;
+	while !input.is_empty() {
+		let tag = input[0];
+		input = &input[1..];
+		let col = match decode_u32(&mut input)? {
+			DEFAULT_COLUMN => None,
+			col => Some(col),
+		};
+		let key = decode_bytes(&mut input)?;
+		match tag {
+			OP_INSERT => {
+				let value = decode_bytes(&mut input)?;
+				let value_offset = body.len() - input.len() - value.len();
+				ops.push(LogOp::Insert { col, key, value_offset, value_len: value.len() as u32 });
+			},
+			OP_DELETE => ops.push(LogOp::Delete { col, key }),
+			_ => return None,
+		}
+	}
+	Some(ops)
+}
+
+/// Update the index with operations of the record whose body starts at `body_offset`.
+fn apply_ops(ops: Vec<LogOp>, body_offset: u64, columns: &mut HashMap<Option<u32>, Column>) {
+	for op in ops {
+		match op {
+			LogOp::Insert { col, key, value_offset, value_len } => {
+				if let Some(column) = columns.get_mut(&col) {
+					column.insert(key.to_vec(), ValueLocation {
+						offset: body_offset + value_offset as u64,
+						len: value_len,
+					});
+				}
+			},
+			LogOp::Delete { col, key } => {
+				if let Some(column) = columns.get_mut(&col) {
+					column.remove(key);
+				}
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempdir::TempDir;
+
+	fn insert(db: &AppendLogDb, col: Option<u32>, key: &[u8], value: &[u8]) {
+		let mut transaction = DBTransaction::new();
+		transaction.put(col, key, value);
+		db.write(transaction).unwrap();
+	}
+
+	#[test]
+	fn data_survives_reopen() {
+		let dir = TempDir::new("append-log").unwrap();
+		{
+			let db = AppendLogDb::open(dir.path(), 2).unwrap();
+			insert(&db, Some(0), b"key1", b"value1");
+			insert(&db, Some(1), b"key1", b"value2");
+			insert(&db, Some(0), b"key2", b"value3");
+
+			let mut transaction = DBTransaction::new();
+			transaction.delete(Some(0), b"key2");
+			transaction.put(Some(0), b"key1", b"value4");
+			db.write(transaction).unwrap();
+		}
+
+		let db = AppendLogDb::open(dir.path(), 2).unwrap();
+		assert_eq!(db.get(Some(0), b"key1").unwrap().unwrap().to_vec(), b"value4".to_vec());
+		assert_eq!(db.get(Some(1), b"key1").unwrap().unwrap().to_vec(), b"value2".to_vec());
+		assert!(db.get(Some(0), b"key2").unwrap().is_none());
+	}
+
+	#[test]
+	fn incomplete_record_is_discarded() {
+		let dir = TempDir::new("append-log").unwrap();
+		{
+			let db = AppendLogDb::open(dir.path(), 1).unwrap();
+			insert(&db, Some(0), b"key1", b"value1");
+			insert(&db, Some(0), b"key2", b"value2");
+		}
+
+		let log_path = dir.path().join(LOG_FILE_NAME);
+		let size = fs::metadata(&log_path).unwrap().len();
+		OpenOptions::new().write(true).open(&log_path).unwrap().set_len(size - 3).unwrap();
+
+		let db = AppendLogDb::open(dir.path(), 1).unwrap();
+		assert_eq!(db.get(Some(0), b"key1").unwrap().unwrap().to_vec(), b"value1".to_vec());
+		assert!(db.get(Some(0), b"key2").unwrap().is_none());
+
+		// log is writable after recovery
+		insert(&db, Some(0), b"key3", b"value3");
+		drop(db);
+		let db = AppendLogDb::open(dir.path(), 1).unwrap();
+		assert_eq!(db.get(Some(0), b"key3").unwrap().unwrap().to_vec(), b"value3".to_vec());
+	}
+
+	#[test]
+	fn damaged_final_record_is_discarded() {
+		let dir = TempDir::new("append-log").unwrap();
+		{
+			let db = AppendLogDb::open(dir.path(), 1).unwrap();
+			insert(&db, Some(0), b"key1", b"value1");
+			insert(&db, Some(0), b"key2", b"value2");
+		}
+
+		// flip the last byte of the final record, keeping the file size
+		let log_path = dir.path().join(LOG_FILE_NAME);
+		let mut file = OpenOptions::new().read(true).write(true).open(&log_path).unwrap();
+		file.seek(SeekFrom::End(-1)).unwrap();
+		file.write_all(b"X").unwrap();
+		drop(file);
+
+		let db = AppendLogDb::open(dir.path(), 1).unwrap();
+		assert_eq!(db.get(Some(0), b"key1").unwrap().unwrap().to_vec(), b"value1".to_vec());
+		assert!(db.get(Some(0), b"key2").unwrap().is_none());
+	}
+
+	#[test]
+	fn damaged_record_in_the_middle_is_an_error() {
+		let dir = TempDir::new("append-log").unwrap();
+		{
+			let db = AppendLogDb::open(dir.path(), 1).unwrap();
+			insert(&db, Some(0), b"key1", b"value1");
+			insert(&db, Some(0), b"key2", b"value2");
+		}
+
+		// damage the value of the first record
+		let log_path = dir.path().join(LOG_FILE_NAME);
+		let mut file = OpenOptions::new().read(true).write(true).open(&log_path).unwrap();
+		file.seek(SeekFrom::Start(RECORD_HEADER_SIZE + record_overhead(b"key1"))).unwrap();
+		file.write_all(b"X").unwrap();
+		drop(file);
+
+		let err = AppendLogDb::open(dir.path(), 1).err().unwrap();
+		assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+		// nothing has been truncated
+		assert!(fs::metadata(&log_path).unwrap().len() > 0);
+	}
+
+	#[test]
+	fn crc32_matches_reference() {
+		assert_eq!(crc32(b""), 0);
+		assert_eq!(crc32(b"123456789"), 0xcbf43926);
+	}
+
+	#[test]
+	fn iterates_in_key_order() {
+		let dir = TempDir::new("append-log").unwrap();
+		let db = AppendLogDb::open(dir.path(), 1).unwrap();
+		insert(&db, Some(0), b"b2", b"3");
+		insert(&db, Some(0), b"a", b"1");
+		insert(&db, Some(0), b"b1", b"2");
+		insert(&db, Some(0), b"c", b"4");
+
+		let all: Vec<_> = db.iter(Some(0)).map(|(k, _)| k.to_vec()).collect();
+		assert_eq!(all, vec![b"a".to_vec(), b"b1".to_vec(), b"b2".to_vec(), b"c".to_vec()]);
+
+		let prefixed: Vec<_> = db.iter_from_prefix(Some(0), b"b").map(|(_, v)| v.to_vec()).collect();
+		assert_eq!(prefixed, vec![b"2".to_vec(), b"3".to_vec()]);
+		assert_eq!(db.get_by_prefix(Some(0), b"b").unwrap().to_vec(), b"2".to_vec());
+	}
+
+	#[test]
+	fn compaction_preserves_live_data() {
+		let dir = TempDir::new("append-log").unwrap();
+		let size_before = {
+			let db = AppendLogDb::open(dir.path(), 1).unwrap();
+			insert(&db, Some(0), b"key1", b"old");
+			insert(&db, Some(0), b"key1", b"new");
+			insert(&db, None, b"key2", b"value");
+
+			let size = db.log.read().size;
+			db.compact().unwrap();
+			// the database stays usable after compaction
+			assert_eq!(db.get(Some(0), b"key1").unwrap().unwrap().to_vec(), b"new".to_vec());
+			insert(&db, Some(0), b"key3", b"value");
+			size
+		};
+
+		let db = AppendLogDb::open(dir.path(), 1).unwrap();
+		assert_eq!(db.get(Some(0), b"key1").unwrap().unwrap().to_vec(), b"new".to_vec());
+		assert_eq!(db.get(None, b"key2").unwrap().unwrap().to_vec(), b"value".to_vec());
+		assert_eq!(db.get(Some(0), b"key3").unwrap().unwrap().to_vec(), b"value".to_vec());
+		assert!(db.log.read().size < size_before);
+	}
+}
@@ -26,8 +26,10 @@
 
 pub mod light;
 
+mod append_log;
 mod cache;
 mod storage_cache;
+mod upgrade;
 mod utils;
 
 use std::sync::Arc;
@@ -35,7 +37,7 @@ use std::path::PathBuf;
 use std::io;
 use std::collections::HashMap;
 
-use client::backend::NewBlockState;
+use client::backend::{NewBlockState, DatabaseStats, StateDbStats, StateCacheStats};
 use client::blockchain::HeaderBackend;
 use client::ExecutionStrategies;
 use client::backend::{StorageCollection, ChildStorageCollection};
@@ -64,11 +66,17 @@ use consensus_common::well_known_cache_keys;
 use crate::storage_cache::{CachingState, SharedCache, new_shared_cache};
 use log::{trace, debug, warn};
 pub use state_db::PruningMode;
+pub use crate::append_log::AppendLogDb;
+pub use crate::utils::NUM_COLUMNS;
 
 #[cfg(feature = "test-helpers")]
 use client::in_mem::Backend as InMemoryBackend;
 
 const CANONICALIZATION_DELAY: u64 = 4096;
+/// Maximum number of states pinned with `pin_state` at the same time.
+const MAX_PINNED_STATES: u64 = 16;
+/// Number of finalized blocks after which a state pinned with `pin_state` is released.
+const PINNED_STATE_LIFETIME: u64 = 4096;
 const MIN_BLOCKS_TO_KEEP_CHANGES_TRIES_FOR: u32 = 32768;
 
 /// Default value for storage cache child ratio.
@@ -180,6 +188,26 @@ pub struct DatabaseSettings {
 	pub path: PathBuf,
 	/// Pruning mode.
 	pub pruning: PruningMode,
+	/// Key-value database the client data is stored in.
+	pub backend: DatabaseBackend,
+}
+
+/// Key-value database implementation used by the client.
+#[derive(Clone)]
+pub enum DatabaseBackend {
+	/// RocksDB database at the configured path.
+	RocksDb,
+	/// Embedded append-only log database at the configured path. See `AppendLogDb`.
+	AppendLog,
+	/// Externally provided database. It must have at least `NUM_COLUMNS` columns.
+	/// The configured path is ignored.
+	Custom(Arc<dyn KeyValueDB>),
+}
+
+impl Default for DatabaseBackend {
+	fn default() -> Self {
+		DatabaseBackend::RocksDb
+	}
 }
 
 /// Create an instance of db-backed client.
@@ -213,6 +241,71 @@ mod columns {
 	pub const JUSTIFICATION: Option<u32> = Some(6);
 	pub const CHANGES_TRIE: Option<u32> = Some(7);
 	pub const AUX: Option<u32> = Some(8);
+
+	/// Column names, indexed by column number.
+	pub const NAMES: [&str; crate::utils::NUM_COLUMNS as usize] = [
+		"meta",
+		"state",
+		"state_meta",
+		"key_lookup",
+		"header",
+		"body",
+		"justification",
+		"changes_trie",
+		"aux",
+	];
+}
+
+/// Compact the given key range of the client database.
+///
+/// The range is limited to the column called `column` (see `columns::NAMES` for the full
+/// client and `light::columns::NAMES` for the light client database), or covers all columns
+/// if `None`. Keys are compared lexicographically; `start` is inclusive and `end` is
+/// exclusive. The append log backend has to rewrite the whole log to reclaim space, so it
+/// always compacts everything.
+///
+/// The database must not be in use by another process.
+pub fn compact_database(
+	settings: &DatabaseSettings,
+	column: Option<&str>,
+	start: Option<&[u8]>,
+	end: Option<&[u8]>,
+) -> Result<(), client::error::Error> {
+	match settings.backend {
+		DatabaseBackend::AppendLog => {
+			let db = AppendLogDb::open(&settings.path, NUM_COLUMNS).map_err(db_err)?;
+			column_index(&db, column)?;
+			debug!(target: "db", "Compacting append log at {}", db.path().display());
+			db.compact().map_err(db_err)
+		},
+		DatabaseBackend::RocksDb => {
+			let column = {
+				let db = utils::open_rocksdb(settings)?;
+				column_index(&*db, column)?
+			};
+			debug!(target: "db", "Compacting column {:?} in range {:?}..{:?}", column, start, end);
+			utils::compact_rocksdb(settings, column, start, end)
+		},
+		DatabaseBackend::Custom(_) => Err(client::error::Error::Backend(
+			"Compaction is not supported by custom database backends".into()
+		)),
+	}
+}
+
+/// Find the number of the named column in the full or light client database.
+fn column_index(db: &dyn KeyValueDB, name: Option<&str>) -> Result<Option<u32>, client::error::Error> {
+	let name = match name {
+		Some(name) => name,
+		None => return Ok(None),
+	};
+	let names: &[&str] = match db.get(utils::COLUMN_META, meta_keys::TYPE).map_err(db_err)? {
+		Some(ref db_type) if &db_type[..] == b"full" => &columns::NAMES,
+		Some(ref db_type) if &db_type[..] == b"light" => &light::columns::NAMES,
+		_ => return Err(client::error::Error::Backend("Unknown database type".into())),
+	};
+	names.iter().position(|n| *n == name)
+		.map(|column| Some(column as u32))
+		.ok_or_else(|| client::error::Error::Backend(format!("Unknown database column: {}", name)))
 }
 
 struct PendingBlock<Block: BlockT> {
@@ -690,28 +783,14 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 	///
 	/// The pruning window is how old a block must be before the state is pruned.
 	pub fn new(config: DatabaseSettings, canonicalization_delay: u64) -> Result<Self, client::error::Error> {
-		Self::new_inner(config, canonicalization_delay)
-	}
-
-	#[cfg(feature = "kvdb-rocksdb")]
-	fn new_inner(config: DatabaseSettings, canonicalization_delay: u64) -> Result<Self, client::error::Error> {
 		let db = crate::utils::open_database(&config, columns::META, "full")?;
-		Backend::from_kvdb(db as Arc<_>, canonicalization_delay, &config)
-	}
-
-	#[cfg(not(feature = "kvdb-rocksdb"))]
-	fn new_inner(config: DatabaseSettings, canonicalization_delay: u64) -> Result<Self, client::error::Error> {
-		log::warn!("Running without the RocksDB feature. The database will NOT be saved.");
-		let db = Arc::new(kvdb_memorydb::create(crate::utils::NUM_COLUMNS));
-		Backend::from_kvdb(db as Arc<_>, canonicalization_delay, &config)
+		Backend::from_kvdb(db, canonicalization_delay, &config)
 	}
 
 	#[cfg(any(test, feature = "test-helpers"))]
 	pub fn new_test(keep_blocks: u32, canonicalization_delay: u64) -> Self {
-		use utils::NUM_COLUMNS;
-
-		let db = Arc::new(::kvdb_memorydb::create(NUM_COLUMNS));
-		Self::new_test_db(keep_blocks, canonicalization_delay, db as Arc<_>)
+		let db = crate::utils::test_database();
+		Self::new_test_db(keep_blocks, canonicalization_delay, db)
 	}
 
 	#[cfg(any(test, feature = "test-helpers"))]
@@ -723,6 +802,7 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 			state_cache_child_ratio: Some((50, 100)),
 			path: Default::default(),
 			pruning: PruningMode::keep_blocks(keep_blocks),
+			backend: DatabaseBackend::Custom(db.clone()),
 		};
 		Backend::from_kvdb(
 			db,
@@ -942,6 +1022,22 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 		Ok((*hash, number, false, true))
 	}
 
+	// writes state-db changes made outside of block import and finalization.
+	fn write_state_commit(&self, commit: state_db::CommitSet<Vec<u8>>) -> Result<(), client::error::Error> {
+		let mut transaction = DBTransaction::new();
+		apply_state_commit(&mut transaction, commit);
+		match self.storage.db.write(transaction).map_err(db_err) {
+			Ok(()) => {
+				self.storage.state_db.apply_pending();
+				Ok(())
+			},
+			Err(e) => {
+				self.storage.state_db.revert_pending();
+				Err(e)
+			},
+		}
+	}
+
 	// performs forced canonicaliziation with a delay after importing a non-finalized block.
 	fn force_delayed_canonicalize(
 		&self,
@@ -968,7 +1064,7 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 			};
 
 			trace!(target: "db", "Canonicalize block #{} ({:?})", new_canonical, hash);
-			let commit = self.storage.state_db.canonicalize_block(&hash)
+			let commit = self.storage.state_db.canonicalize_block(&hash, &StateMetaDb(&*self.storage.db))
 				.map_err(|e: state_db::Error<io::Error>| client::error::Error::from(format!("State database error: {:?}", e)))?;
 			apply_state_commit(transaction, commit);
 		};
@@ -1168,7 +1264,7 @@ impl<Block: BlockT<Hash=H256>> Backend<Block> {
 			let lookup_key = utils::number_and_hash_to_lookup_key(f_num, f_hash.clone());
 			transaction.put(columns::META, meta_keys::FINALIZED_BLOCK, &lookup_key);
 
-			let commit = self.storage.state_db.canonicalize_block(&f_hash)
+			let commit = self.storage.state_db.canonicalize_block(&f_hash, &StateMetaDb(&*self.storage.db))
 				.map_err(|e: state_db::Error<io::Error>| client::error::Error::from(format!("State database error: {:?}", e)))?;
 			apply_state_commit(transaction, commit);
 
@@ -1341,6 +1437,59 @@ impl<Block> client::backend::Backend<Block, Blake2Hasher> for Backend<Block> whe
 		Ok(n)
 	}
 
+	fn pin_state(&self, hash: &Block::Hash) -> Result<(), client::error::Error> {
+		use client::blockchain::HeaderBackend as BcHeaderBackend;
+
+		let _import_lock = self.import_lock.lock();
+		let number = self.blockchain.number(*hash)?
+			.ok_or_else(|| client::error::Error::UnknownBlock(format!("{:?}", hash)))?;
+		let info = self.blockchain.info();
+		if number > info.finalized_number {
+			return Err(client::error::Error::Msg(format!("Can't pin the state of non-finalized block {:?}", hash)));
+		}
+		let number = number.saturated_into::<u64>();
+		if self.storage.state_db.is_pruned(hash, number) {
+			return Err(client::error::Error::UnknownBlock(format!("State already discarded for {:?}", hash)));
+		}
+		if self.storage.state_db.stats().pinned_states >= MAX_PINNED_STATES {
+			return Err(client::error::Error::Msg(format!("Too many pinned states (max {})", MAX_PINNED_STATES)));
+		}
+
+		let expires_at = info.finalized_number.saturated_into::<u64>() + PINNED_STATE_LIFETIME;
+		let commit = self.storage.state_db.pin_state(hash, expires_at, &StateMetaDb(&*self.storage.db))
+			.map_err(|e: state_db::Error<io::Error>| client::error::Error::from(format!("State database error: {:?}", e)))?;
+		self.write_state_commit(commit)
+	}
+
+	fn unpin_state(&self, hash: &Block::Hash) -> Result<(), client::error::Error> {
+		let _import_lock = self.import_lock.lock();
+		let commit = self.storage.state_db.unpin_state(hash);
+		self.write_state_commit(commit)
+	}
+
+	fn database_stats(&self) -> Result<Option<DatabaseStats>, client::error::Error> {
+		let state_db = self.storage.state_db.stats();
+		let state_cache = self.shared_cache.lock().stats();
+		Ok(Some(DatabaseStats {
+			columns: crate::utils::column_stats(&*self.storage.db, &columns::NAMES),
+			state_db: StateDbStats {
+				pruning_window_start: state_db.pruning_window_start,
+				pruning_window_size: state_db.pruning_window_size,
+				last_canonical: state_db.last_canonical,
+				non_canonical_blocks: state_db.non_canonical_blocks,
+				non_canonical_values: state_db.non_canonical_values,
+				non_canonical_size: state_db.non_canonical_size,
+				pinned_blocks: state_db.pinned_blocks,
+				pinned_states: state_db.pinned_states,
+			},
+			state_cache: StateCacheStats {
+				hits: state_cache.hits,
+				misses: state_cache.misses,
+				used_size: state_cache.used_size,
+			},
+		}))
+	}
+
 	fn blockchain(&self) -> &BlockchainDb<Block> {
 		&self.blockchain
 	}
@@ -2151,4 +2300,38 @@ mod tests {
 			backend.commit_operation(op).unwrap_err();
 		}
 	}
+
+	#[test]
+	fn database_stats_counts_column_entries() {
+		let backend = Backend::<Block>::new_test(10, 10);
+		let block0 = insert_header(&backend, 0, Default::default(), Default::default(), Default::default());
+		insert_header(&backend, 1, block0, Default::default(), Default::default());
+
+		let stats = backend.database_stats().unwrap().unwrap();
+		assert_eq!(stats.columns.len(), NUM_COLUMNS as usize);
+		let headers = stats.columns.iter().find(|column| column.name == "header").unwrap();
+		assert_eq!(headers.keys, 2);
+		assert!(headers.size > 0);
+		let bodies = stats.columns.iter().find(|column| column.name == "body").unwrap();
+		assert_eq!(bodies.keys, 0);
+		assert_eq!(bodies.size, 0);
+	}
+
+	#[test]
+	fn compact_database_resolves_column_names_by_database_type() {
+		let dir = tempdir::TempDir::new("substrate-compact-db").unwrap();
+		let settings = || DatabaseSettings {
+			cache_size: None,
+			state_cache_size: 0,
+			state_cache_child_ratio: None,
+			path: dir.path().into(),
+			pruning: PruningMode::ArchiveAll,
+			backend: DatabaseBackend::AppendLog,
+		};
+		drop(crate::light::LightStorage::<Block>::new(settings()).unwrap());
+
+		assert!(compact_database(&settings(), None, None, None).is_ok());
+		assert!(compact_database(&settings(), Some("cht"), None, None).is_ok());
+		assert!(compact_database(&settings(), Some("state"), None, None).is_err());
+	}
 }
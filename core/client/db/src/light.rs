@@ -46,6 +46,16 @@ pub(crate) mod columns {
 	pub const CACHE: Option<u32> = Some(3);
 	pub const CHT: Option<u32> = Some(4);
 	pub const AUX: Option<u32> = Some(5);
+
+	/// Column names, indexed by column number.
+	pub const NAMES: [&str; 6] = [
+		"meta",
+		"key_lookup",
+		"header",
+		"cache",
+		"cht",
+		"aux",
+	];
 }
 
 /// Prefix for headers CHT.
@@ -68,29 +78,14 @@ impl<Block> LightStorage<Block>
 {
 	/// Create new storage with given settings.
 	pub fn new(config: DatabaseSettings) -> ClientResult<Self> {
-		Self::new_inner(config)
-	}
-
-	#[cfg(feature = "kvdb-rocksdb")]
-	fn new_inner(config: DatabaseSettings) -> ClientResult<Self> {
 		let db = crate::utils::open_database(&config, columns::META, "light")?;
-		Self::from_kvdb(db as Arc<_>)
-	}
-
-	#[cfg(not(feature = "kvdb-rocksdb"))]
-	fn new_inner(_config: DatabaseSettings) -> ClientResult<Self> {
-		log::warn!("Running without the RocksDB feature. The database will NOT be saved.");
-		let db = Arc::new(kvdb_memorydb::create(crate::utils::NUM_COLUMNS));
-		Self::from_kvdb(db as Arc<_>)
+		Self::from_kvdb(db)
 	}
 
 	#[cfg(any(test, feature = "test-helpers"))]
 	pub fn new_test() -> Self {
-		use utils::NUM_COLUMNS;
-
-		let db = Arc::new(::kvdb_memorydb::create(NUM_COLUMNS));
-
-		Self::from_kvdb(db as Arc<_>).expect("failed to create test-db")
+		let db = crate::utils::test_database();
+		Self::from_kvdb(db).expect("failed to create test-db")
 	}
 
 	fn from_kvdb(db: Arc<dyn KeyValueDB>) -> ClientResult<Self> {
@@ -43,6 +43,21 @@ pub struct Cache<B: Block, H: Hasher> {
 	/// Information on the modifications in recently committed blocks; specifically which keys
 	/// changed in which block. Ordered by block number.
 	modifications: VecDeque<BlockChanges<B::Header>>,
+	/// Number of lookups answered by the shared cache.
+	hits: u64,
+	/// Number of lookups that went through the shared cache to the state backend.
+	misses: u64,
+}
+
+/// Shared cache usage counters.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheStats {
+	/// Number of lookups answered by the shared cache.
+	pub hits: u64,
+	/// Number of lookups that had to query the state backend.
+	pub misses: u64,
+	/// Used memory size of the storage cache in bytes.
+	pub used_size: u64,
 }
 
 struct LRUMap<K, V>(LinkedHashMap<K, V>, usize, usize);
@@ -151,6 +166,15 @@ impl<B: Block, H: Hasher> Cache<B, H> {
 			+ self.lru_child_storage.used_size()
 			//  ignore small hashes storage and self.lru_hashes.used_size()
 	}
+
+	/// Returns hit and miss counters together with the used storage cache size.
+	pub fn stats(&self) -> CacheStats {
+		CacheStats {
+			hits: self.hits,
+			misses: self.misses,
+			used_size: self.used_storage_cache_size() as u64,
+		}
+	}
 }
 
 pub type SharedCache<B, H> = Arc<Mutex<Cache<B, H>>>;
@@ -171,6 +195,8 @@ pub fn new_shared_cache<B: Block, H: Hasher>(
 		lru_child_storage: LRUMap(LinkedHashMap::new(), 0,
 			shared_cache_size * child_ratio.0 / child_ratio.1),
 		modifications: VecDeque::new(),
+		hits: 0,
+		misses: 0,
 	}))
 }
 
@@ -463,10 +489,12 @@ impl<H: Hasher, S: StateBackend<H>, B:Block> StateBackend<H> for CachingState<H,
 		if Self::is_allowed(Some(key), None, &self.cache.parent_hash, &cache.modifications) {
 			if let Some(entry) = cache.lru_storage.get(key).map(|a| a.clone()) {
 				trace!("Found in shared cache: {:?}", key);
+				cache.hits += 1;
 				return Ok(entry)
 			}
 		}
 		trace!("Cache miss: {:?}", key);
+		cache.misses += 1;
 		let value = self.state.storage(key)?;
 		RwLockUpgradableReadGuard::upgrade(local_cache).storage.insert(key.to_vec(), value.clone());
 		Ok(value)
@@ -482,10 +510,12 @@ impl<H: Hasher, S: StateBackend<H>, B:Block> StateBackend<H> for CachingState<H,
 		if Self::is_allowed(Some(key), None, &self.cache.parent_hash, &cache.modifications) {
 			if let Some(entry) = cache.lru_hashes.get(key).map(|a| a.0.clone()) {
 				trace!("Found hash in shared cache: {:?}", key);
+				cache.hits += 1;
 				return Ok(entry)
 			}
 		}
 		trace!("Cache hash miss: {:?}", key);
+		cache.misses += 1;
 		let hash = self.state.storage_hash(key)?;
 		RwLockUpgradableReadGuard::upgrade(local_cache).hashes.insert(key.to_vec(), hash.clone());
 		Ok(hash)
@@ -502,10 +532,12 @@ impl<H: Hasher, S: StateBackend<H>, B:Block> StateBackend<H> for CachingState<H,
 		if Self::is_allowed(None, Some(&key), &self.cache.parent_hash, &cache.modifications) {
 			if let Some(entry) = cache.lru_child_storage.get(&key).map(|a| a.clone()) {
 				trace!("Found in shared cache: {:?}", key);
+				cache.hits += 1;
 				return Ok(entry)
 			}
 		}
 		trace!("Cache miss: {:?}", key);
+		cache.misses += 1;
 		let value = self.state.child_storage(storage_key, &key.1[..])?;
 		RwLockUpgradableReadGuard::upgrade(local_cache).child_storage.insert(key, value.clone());
 		Ok(value)
@@ -701,4 +733,26 @@ mod tests {
 		// 32 key, 2 byte size
 		assert_eq!(shared.lock().used_storage_cache_size(), 34 /* bytes */);
 	}
+
+	#[test]
+	fn should_count_shared_cache_hits_and_misses() {
+		let root_parent = H256::random();
+		let shared = new_shared_cache::<Block, Blake2Hasher>(256*1024, (0,1));
+		let h0 = H256::random();
+		let key = H256::random()[..].to_vec();
+		let other_key = H256::random()[..].to_vec();
+
+		let mut s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(root_parent.clone()));
+		s.cache.sync_cache(&[], &[], vec![(key.clone(), Some(vec![1]))], vec![], Some(h0.clone()), Some(0), || true);
+
+		let s = CachingState::new(InMemory::<Blake2Hasher>::default(), shared.clone(), Some(h0.clone()));
+		assert_eq!(s.storage(&key).unwrap(), Some(vec![1]));
+		assert!(s.storage(&other_key).unwrap().is_none());
+		// answered by the local cache
+		assert!(s.storage(&other_key).unwrap().is_none());
+
+		let stats = shared.lock().stats();
+		assert_eq!(stats.hits, 1);
+		assert_eq!(stats.misses, 1);
+	}
 }
@@ -0,0 +1,158 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Database schema versioning and in-place upgrades.
+//!
+//! Every database stores the version of the schema it has been written with under
+//! `meta_keys::DB_VERSION`. Databases created before versioning was introduced have
+//! no such key and are treated as version 0.
+//!
+//! When the layout changes, `CURRENT_VERSION` is bumped and a `Migration` from the
+//! previous version is appended to `MIGRATIONS`. Columns that are added by the new
+//! layout are created when the database is opened with `NUM_COLUMNS` columns, so
+//! migrations only have to move or rewrite the data itself.
+
+use kvdb::{KeyValueDB, DBTransaction};
+use log::info;
+use parity_codec::{Decode, Encode};
+
+use client;
+use crate::utils::{db_err, meta_keys};
+
+/// Version of the database schema that is produced by this client.
+pub const CURRENT_VERSION: u32 = 1;
+
+/// Single step of the database upgrade.
+struct Migration {
+	/// Version of the database this migration applies to. After the migration
+	/// the database is at version `from + 1`.
+	from: u32,
+	/// Human-readable description, used for logging.
+	description: &'static str,
+	/// Collect changes required by the migration into the transaction.
+	///
+	/// The transaction is committed together with the version bump, so either
+	/// both or none of them are persisted.
+	apply: fn(&dyn KeyValueDB, &mut DBTransaction, &str) -> client::error::Result<()>,
+}
+
+/// All known migrations, ordered by the version they upgrade from.
+const MIGRATIONS: &[Migration] = &[
+	Migration {
+		from: 0,
+		description: "record schema version",
+		apply: migrate_0_to_1,
+	},
+];
+
+/// The layout of version 1 matches the layout used before the version was stored,
+/// so there's nothing to move.
+fn migrate_0_to_1(_db: &dyn KeyValueDB, _transaction: &mut DBTransaction, _db_type: &str) -> client::error::Result<()> {
+	Ok(())
+}
+
+/// Read the schema version of the database. Returns `0` if no version is stored.
+pub fn read_version(db: &dyn KeyValueDB, col_meta: Option<u32>) -> client::error::Result<u32> {
+	match db.get(col_meta, meta_keys::DB_VERSION).map_err(db_err)? {
+		Some(version) => u32::decode(&mut &version[..])
+			.ok_or_else(|| client::error::Error::Backend("Error decoding database version".into())),
+		None => Ok(0),
+	}
+}
+
+/// Record the current schema version in the given transaction.
+///
+/// Should be used when a new database is created.
+pub fn write_current_version(transaction: &mut DBTransaction, col_meta: Option<u32>) {
+	transaction.put(col_meta, meta_keys::DB_VERSION, &CURRENT_VERSION.encode());
+}
+
+/// Upgrade the database to `CURRENT_VERSION`, applying all pending migrations in order.
+///
+/// Fails if the database has been written by a newer client.
+pub fn upgrade_db(db: &dyn KeyValueDB, col_meta: Option<u32>, db_type: &str) -> client::error::Result<()> {
+	let mut version = read_version(db, col_meta)?;
+	if version > CURRENT_VERSION {
+		return Err(client::error::Error::DatabaseVersionTooNew(version, CURRENT_VERSION));
+	}
+
+	while version < CURRENT_VERSION {
+		let migration = MIGRATIONS.iter()
+			.find(|migration| migration.from == version)
+			.ok_or_else(|| client::error::Error::Backend(
+				format!("No migration from database version {}", version)
+			))?;
+
+		info!(
+			"Upgrading {} database from version {} to {}: {}",
+			db_type,
+			version,
+			version + 1,
+			migration.description,
+		);
+
+		let mut transaction = DBTransaction::new();
+		(migration.apply)(db, &mut transaction, db_type)?;
+		version += 1;
+		transaction.put(col_meta, meta_keys::DB_VERSION, &version.encode());
+		db.write(transaction).map_err(db_err)?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::{NUM_COLUMNS, COLUMN_META};
+
+	#[test]
+	fn migrations_are_contiguous() {
+		for (index, migration) in MIGRATIONS.iter().enumerate() {
+			assert_eq!(migration.from, index as u32);
+		}
+		assert_eq!(MIGRATIONS.len() as u32, CURRENT_VERSION);
+	}
+
+	#[test]
+	fn unversioned_database_is_upgraded() {
+		let db = ::kvdb_memorydb::create(NUM_COLUMNS);
+		assert_eq!(read_version(&db, COLUMN_META).unwrap(), 0);
+
+		upgrade_db(&db, COLUMN_META, "full").unwrap();
+		assert_eq!(read_version(&db, COLUMN_META).unwrap(), CURRENT_VERSION);
+
+		// upgrading again is a no-op
+		upgrade_db(&db, COLUMN_META, "full").unwrap();
+		assert_eq!(read_version(&db, COLUMN_META).unwrap(), CURRENT_VERSION);
+	}
+
+	#[test]
+	fn newer_database_is_rejected() {
+		let db = ::kvdb_memorydb::create(NUM_COLUMNS);
+		let mut transaction = DBTransaction::new();
+		transaction.put(COLUMN_META, meta_keys::DB_VERSION, &(CURRENT_VERSION + 1).encode());
+		db.write(transaction).unwrap();
+
+		match upgrade_db(&db, COLUMN_META, "full") {
+			Err(client::error::Error::DatabaseVersionTooNew(stored, supported)) => {
+				assert_eq!(stored, CURRENT_VERSION + 1);
+				assert_eq!(supported, CURRENT_VERSION);
+			},
+			_ => panic!("database from the future must not be opened"),
+		}
+	}
+}
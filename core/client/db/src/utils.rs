@@ -32,10 +32,15 @@ use runtime_primitives::traits::{
 	Block as BlockT, Header as HeaderT, Zero, UniqueSaturatedFrom,
 	UniqueSaturatedInto, CheckedConversion
 };
-use crate::DatabaseSettings;
+use client::backend::ColumnStats;
+use crate::{DatabaseSettings, DatabaseBackend};
+use crate::append_log::AppendLogDb;
 
 /// Number of columns in the db. Must be the same for both full && light dbs.
 /// Otherwise RocksDb will fail to open database && check its type.
+///
+/// Columns missing from an existing database are created when it is opened,
+/// before the upgrade (see `upgrade` module) is performed.
 pub const NUM_COLUMNS: u32 = 9;
 /// Meta column. The set of keys in the column is shared by full && light storages.
 pub const COLUMN_META: Option<u32> = Some(0);
@@ -44,6 +49,8 @@ pub const COLUMN_META: Option<u32> = Some(0);
 pub mod meta_keys {
 	/// Type of storage (full or light).
 	pub const TYPE: &[u8; 4] = b"type";
+	/// Version of the database schema.
+	pub const DB_VERSION: &[u8; 7] = b"version";
 	/// Best block key.
 	pub const BEST_BLOCK: &[u8; 4] = b"best";
 	/// Last finalized block key.
@@ -192,17 +199,18 @@ pub fn db_err(err: io::Error) -> client::error::Error {
 	client::error::Error::Backend(format!("{}", err))
 }
 
-/// Open RocksDB database.
-#[cfg(feature = "kvdb-rocksdb")]
+/// Open the database selected by the settings, checking its type and
+/// upgrading it to the current schema version if necessary.
 pub fn open_database(
 	config: &DatabaseSettings,
 	col_meta: Option<u32>,
 	db_type: &str
 ) -> client::error::Result<Arc<dyn KeyValueDB>> {
-	let mut db_config = DatabaseConfig::with_columns(Some(NUM_COLUMNS));
-	db_config.memory_budget = config.cache_size;
-	let path = config.path.to_str().ok_or_else(|| client::error::Error::Backend("Invalid database path".into()))?;
-	let db = Database::open(&db_config, &path).map_err(db_err)?;
+	let db: Arc<dyn KeyValueDB> = match config.backend {
+		DatabaseBackend::RocksDb => open_rocksdb(config)?,
+		DatabaseBackend::AppendLog => Arc::new(AppendLogDb::open(&config.path, NUM_COLUMNS).map_err(db_err)?),
+		DatabaseBackend::Custom(ref db) => db.clone(),
+	};
 
 	// check database type
 	match db.get(col_meta, meta_keys::TYPE).map_err(db_err)? {
@@ -211,17 +219,163 @@ pub fn open_database(
 				return Err(client::error::Error::Backend(
 					format!("Unexpected database type. Expected: {}", db_type)).into());
 			}
+
+			// existing database might need to be brought up to date
+			crate::upgrade::upgrade_db(&*db, col_meta, db_type)?;
 		},
 		None => {
 			let mut transaction = DBTransaction::new();
 			transaction.put(col_meta, meta_keys::TYPE, db_type.as_bytes());
+			crate::upgrade::write_current_version(&mut transaction, col_meta);
 			db.write(transaction).map_err(db_err)?;
 		},
 	}
 
+	Ok(db)
+}
+
+/// Count keys and their total size in each of the named columns.
+///
+/// Column `i` is expected to be called `names[i]`.
+pub fn column_stats(db: &dyn KeyValueDB, names: &[&'static str]) -> Vec<ColumnStats> {
+	names.iter().enumerate().map(|(col, name)| {
+		let (keys, size) = db.iter(Some(col as u32))
+			.fold((0, 0), |(keys, size), (key, value)| (keys + 1, size + (key.len() + value.len()) as u64));
+		ColumnStats { name, keys, size }
+	}).collect()
+}
+
+/// Open RocksDB database.
+#[cfg(feature = "kvdb-rocksdb")]
+pub fn open_rocksdb(config: &DatabaseSettings) -> client::error::Result<Arc<dyn KeyValueDB>> {
+	let mut db_config = DatabaseConfig::with_columns(Some(NUM_COLUMNS));
+	db_config.memory_budget = config.cache_size;
+	let path = config.path.to_str().ok_or_else(|| client::error::Error::Backend("Invalid database path".into()))?;
+	let db = Database::open(&db_config, &path).map_err(db_err)?;
 	Ok(Arc::new(db))
 }
 
+/// Fallback to the in-memory database when RocksDB support is not compiled in.
+#[cfg(not(feature = "kvdb-rocksdb"))]
+pub fn open_rocksdb(_config: &DatabaseSettings) -> client::error::Result<Arc<dyn KeyValueDB>> {
+	log::warn!("Running without the RocksDB feature. The database will NOT be saved.");
+	Ok(Arc::new(kvdb_memorydb::create(NUM_COLUMNS)))
+}
+
+/// Compact the key range of the given column of RocksDB database, or of all columns if `None`.
+///
+/// The database must be closed.
+#[cfg(feature = "rocksdb")]
+pub fn compact_rocksdb(
+	config: &DatabaseSettings,
+	column: Option<u32>,
+	start: Option<&[u8]>,
+	end: Option<&[u8]>,
+) -> client::error::Result<()> {
+	let path = config.path.to_str().ok_or_else(|| client::error::Error::Backend("Invalid database path".into()))?;
+	// column families are named the same way as in `kvdb-rocksdb`
+	let names: Vec<String> = (0..NUM_COLUMNS).map(|column| format!("col{}", column)).collect();
+	let db = rocksdb::DB::open_cf(
+		&rocksdb::Options::default(),
+		path,
+		&names.iter().map(|name| name.as_str()).collect::<Vec<_>>(),
+	).map_err(|e| client::error::Error::Backend(format!("{}", e)))?;
+
+	let columns = match column {
+		Some(column) => vec![column],
+		None => (0..NUM_COLUMNS).collect(),
+	};
+	for column in columns {
+		let name = &names[column as usize];
+		let cf = db.cf_handle(name)
+			.ok_or_else(|| client::error::Error::Backend(format!("Missing column family {}", name)))?;
+		debug!(target: "db", "Compacting column family {}", name);
+		db.compact_range_cf(cf, start, end);
+	}
+	Ok(())
+}
+
+/// Compaction is not available when RocksDB support is not compiled in.
+#[cfg(not(feature = "rocksdb"))]
+pub fn compact_rocksdb(
+	_config: &DatabaseSettings,
+	_column: Option<u32>,
+	_start: Option<&[u8]>,
+	_end: Option<&[u8]>,
+) -> client::error::Result<()> {
+	Err(client::error::Error::Backend("Running without the RocksDB feature, nothing to compact".into()))
+}
+
+/// Create the database for tests.
+///
+/// The in-memory database is used by default. Set `SUBSTRATE_TEST_DB_BACKEND=append-log`
+/// to run the test suite against the `AppendLogDb` in a temporary directory.
+#[cfg(test)]
+pub fn test_database() -> Arc<dyn KeyValueDB> {
+	match std::env::var("SUBSTRATE_TEST_DB_BACKEND").as_ref().map(|s| s.as_str()) {
+		Ok("append-log") => {
+			let dir = tempdir::TempDir::new("substrate-client-db")
+				.expect("failed to create temporary directory");
+			let db = AppendLogDb::open(dir.path(), NUM_COLUMNS).expect("failed to open test database");
+			Arc::new(TempAppendLogDb { db, _dir: dir })
+		},
+		Ok("memory") | Err(_) => Arc::new(kvdb_memorydb::create(NUM_COLUMNS)),
+		Ok(backend) => panic!("Unknown test database backend: {}", backend),
+	}
+}
+
+/// `AppendLogDb` that removes its temporary directory when dropped.
+#[cfg(test)]
+struct TempAppendLogDb {
+	db: AppendLogDb,
+	_dir: tempdir::TempDir,
+}
+
+#[cfg(test)]
+impl KeyValueDB for TempAppendLogDb {
+	fn get(&self, col: Option<u32>, key: &[u8]) -> io::Result<Option<kvdb::DBValue>> {
+		self.db.get(col, key)
+	}
+
+	fn get_by_prefix(&self, col: Option<u32>, prefix: &[u8]) -> Option<Box<[u8]>> {
+		self.db.get_by_prefix(col, prefix)
+	}
+
+	fn write_buffered(&self, transaction: DBTransaction) {
+		self.db.write_buffered(transaction)
+	}
+
+	fn write(&self, transaction: DBTransaction) -> io::Result<()> {
+		self.db.write(transaction)
+	}
+
+	fn flush(&self) -> io::Result<()> {
+		self.db.flush()
+	}
+
+	fn iter<'a>(&'a self, col: Option<u32>) -> Box<dyn Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+		self.db.iter(col)
+	}
+
+	fn iter_from_prefix<'a>(
+		&'a self,
+		col: Option<u32>,
+		prefix: &'a [u8],
+	) -> Box<dyn Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+		self.db.iter_from_prefix(col, prefix)
+	}
+
+	fn restore(&self, new_db: &str) -> io::Result<()> {
+		self.db.restore(new_db)
+	}
+}
+
+/// Create the in-memory database for tests.
+#[cfg(all(not(test), feature = "test-helpers"))]
+pub fn test_database() -> Arc<dyn KeyValueDB> {
+	Arc::new(kvdb_memorydb::create(NUM_COLUMNS))
+}
+
 /// Read database column entry for the given block.
 pub fn read_db<Block>(
 	db: &dyn KeyValueDB,
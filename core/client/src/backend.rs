@@ -63,6 +63,60 @@ impl NewBlockState {
 	}
 }
 
+/// Number of keys and their total size in a single database column.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnStats {
+	/// Column name.
+	pub name: &'static str,
+	/// Number of keys stored in the column.
+	pub keys: u64,
+	/// Total size of the keys and values stored in the column, in bytes.
+	pub size: u64,
+}
+
+/// Position of the state pruning window and the contents of the non-canonical overlay.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateDbStats {
+	/// Number of the oldest block in the pruning window. `None` when pruning is disabled.
+	pub pruning_window_start: Option<u64>,
+	/// Number of canonical blocks in the pruning window.
+	pub pruning_window_size: u64,
+	/// Number of the last canonicalized block.
+	pub last_canonical: Option<u64>,
+	/// Number of blocks in the non-canonical overlay.
+	pub non_canonical_blocks: u64,
+	/// Number of distinct trie nodes held by the non-canonical overlay.
+	pub non_canonical_values: u64,
+	/// Total size of the trie nodes held by the non-canonical overlay, in bytes.
+	pub non_canonical_size: u64,
+	/// Number of pinned blocks.
+	pub pinned_blocks: u64,
+	/// Number of canonical states pinned with `pin_state`.
+	pub pinned_states: u64,
+}
+
+/// Shared state cache usage.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateCacheStats {
+	/// Number of lookups answered by the cache.
+	pub hits: u64,
+	/// Number of lookups that had to query the database.
+	pub misses: u64,
+	/// Used memory size of the cache in bytes.
+	pub used_size: u64,
+}
+
+/// Database usage statistics.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatabaseStats {
+	/// Per-column key counts and sizes.
+	pub columns: Vec<ColumnStats>,
+	/// State pruning statistics.
+	pub state_db: StateDbStats,
+	/// State cache statistics.
+	pub state_cache: StateCacheStats,
+}
+
 /// Block insertion operation. Keeps hold if the inserted block state and data.
 pub trait BlockImportOperation<Block, H> where
 	Block: BlockT,
@@ -169,6 +223,24 @@ pub trait Backend<Block, H>: AuxStore + Send + Sync where
 	/// Attempts to revert the chain by `n` blocks. Returns the number of blocks that were
 	/// successfully reverted.
 	fn revert(&self, n: NumberFor<Block>) -> error::Result<NumberFor<Block>>;
+	/// Prevent the state of given finalized block from being pruned until `unpin_state` is
+	/// called. Pins do not hold back pruning of other blocks, are persisted, and may be
+	/// limited in number and expire after a number of finalized blocks. Calls are reference
+	/// counted. Backends that never discard state don't need to override this.
+	fn pin_state(&self, _hash: &Block::Hash) -> error::Result<()> {
+		Ok(())
+	}
+	/// Release the state pinned with `pin_state`.
+	fn unpin_state(&self, _hash: &Block::Hash) -> error::Result<()> {
+		Ok(())
+	}
+	/// Returns usage statistics of the local database. `None` if the backend doesn't keep one.
+	///
+	/// Column statistics are collected by iterating over every column, which may take a while
+	/// on a large database.
+	fn database_stats(&self) -> error::Result<Option<DatabaseStats>> {
+		Ok(None)
+	}
 
 	/// Insert auxiliary data into key-value store.
 	fn insert_aux<
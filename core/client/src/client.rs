@@ -54,7 +54,7 @@ use primitives::storage::well_known_keys;
 use parity_codec::{Encode, Decode};
 use state_machine::{
 	DBValue, Backend as StateBackend, CodeExecutor, ChangesTrieAnchorBlockId,
-	ExecutionStrategy, ExecutionManager, prove_read, prove_child_read,
+	ExecutionStrategy, ExecutionManager, prove_read, prove_child_read, prove_child_read_keys,
 	ChangesTrieRootsStorage, ChangesTrieStorage,
 	key_changes, key_changes_proof, OverlayedChanges, NeverOffchainExt,
 };
@@ -344,6 +344,17 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		self.backend.state_at(*block)
 	}
 
+	/// Prevent the state at a given finalized block from being pruned until `unpin_state`
+	/// is called or the pin expires.
+	pub fn pin_state(&self, hash: &Block::Hash) -> error::Result<()> {
+		self.backend.pin_state(hash)
+	}
+
+	/// Release the state pinned with `pin_state`.
+	pub fn unpin_state(&self, hash: &Block::Hash) -> error::Result<()> {
+		self.backend.unpin_state(hash)
+	}
+
 	/// Expose backend reference. To be used in tests only
 	#[doc(hidden)]
 	#[deprecated(note="Rather than relying on `client` to provide this, access \
@@ -452,6 +463,19 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 				.map_err(Into::into))
 	}
 
+	/// Reads child storage values at a given block + storage_key + keys, returning
+	/// a single read proof for all the keys.
+	pub fn read_child_proof_keys(
+		&self,
+		id: &BlockId<Block>,
+		storage_key: &[u8],
+		keys: &[Vec<u8>],
+	) -> error::Result<Vec<Vec<u8>>> {
+		self.state_at(id)
+			.and_then(|state| prove_child_read_keys(state, storage_key, keys)
+				.map_err(Into::into))
+	}
+
 	/// Execute a call to a contract on top of state in a block of given hash
 	/// AND returning execution proof.
 	///
@@ -97,6 +97,9 @@ pub enum Error {
 	/// Invalid calculated state root on block import.
 	#[display(fmt = "Calculated state root does not match.")]
 	InvalidStateRoot,
+	/// Database was written by a newer version of the client.
+	#[display(fmt = "Database version {} is newer than the latest supported version {}", _0, _1)]
+	DatabaseVersionTooNew(u32, u32),
 	/// A convenience variant for String
 	#[display(fmt = "{}", _0)]
 	Msg(String),
@@ -219,6 +219,14 @@ impl<S, F, Block, H> ClientBackend<Block, H> for Backend<S, F, H> where
 		Err(ClientError::NotAvailableOnLightClient.into())
 	}
 
+	fn pin_state(&self, _hash: &Block::Hash) -> ClientResult<()> {
+		Err(ClientError::NotAvailableOnLightClient.into())
+	}
+
+	fn unpin_state(&self, _hash: &Block::Hash) -> ClientResult<()> {
+		Err(ClientError::NotAvailableOnLightClient.into())
+	}
+
 	fn get_import_lock(&self) -> &Mutex<()> {
 		&self.import_lock
 	}
@@ -1131,6 +1131,19 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 					Ok(sandbox_primitives::ERR_OK)
 				})
 			}
+			Err(wasmi::Error::Trap(trap)) => {
+				// Tell the caller why the execution trapped. The buffer is big enough for any
+				// return value, so it also fits the trap code.
+				let trap_code = sandbox_primitives::TrapCode::from(trap.kind());
+				trap_code.using_encoded(|code| {
+					if code.len() <= return_val_len as usize {
+						this.memory
+							.set(return_val_ptr, code)
+							.map_err(|_| "Return value buffer is OOB")?;
+					}
+					Ok(sandbox_primitives::ERR_EXECUTION)
+				})
+			}
 			Err(_) => Ok(sandbox_primitives::ERR_EXECUTION),
 		}
 	},
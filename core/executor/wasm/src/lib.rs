@@ -132,6 +132,7 @@ impl_stubs!(
 			Err(sandbox::Error::Module) => 1,
 			Err(sandbox::Error::Execution) => 2,
 			Err(sandbox::Error::OutOfBounds) => 3,
+			Err(sandbox::Error::Trap(_)) => 4,
 		};
 		[code].to_vec()
 	}
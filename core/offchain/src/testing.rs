@@ -21,6 +21,8 @@ use std::{
 	sync::Arc,
 };
 use parking_lot::RwLock;
+use parity_codec::Encode;
+use primitives::{ed25519, Pair};
 use primitives::offchain::{
 	self,
 	HttpError,
@@ -61,6 +63,10 @@ pub struct PendingRequest {
 pub struct State {
 	/// A list of pending requests.
 	pub requests: BTreeMap<RequestId, PendingRequest>,
+	/// Seed of the ed25519 authority key, which signs when no key is given.
+	pub authority_key: Option<[u8; 32]>,
+	/// Persistent local storage.
+	pub local_storage: BTreeMap<Vec<u8>, Vec<u8>>,
 }
 
 impl State {
@@ -109,8 +115,11 @@ impl offchain::Externalities for TestOffchainExt {
 		unimplemented!("not needed in tests so far")
 	}
 
-	fn sign(&mut self, _key: Option<CryptoKeyId>, _data: &[u8]) -> Result<Vec<u8>, ()> {
-		unimplemented!("not needed in tests so far")
+	fn sign(&mut self, key: Option<CryptoKeyId>, data: &[u8]) -> Result<Vec<u8>, ()> {
+		assert!(key.is_none(), "only signing with the authority key is supported in tests so far");
+		let state = self.0.read();
+		let seed = state.authority_key.as_ref().ok_or(())?;
+		Ok(ed25519::Pair::from_seed(seed).sign(data).encode())
 	}
 
 	fn verify(&mut self, _key: Option<CryptoKeyId>, _msg: &[u8], _signature: &[u8]) -> Result<bool, ()> {
@@ -129,21 +138,24 @@ impl offchain::Externalities for TestOffchainExt {
 		unimplemented!("not needed in tests so far")
 	}
 
-	fn local_storage_set(&mut self, _key: &[u8], _value: &[u8]) {
-		unimplemented!("not needed in tests so far")
+	fn local_storage_set(&mut self, key: &[u8], value: &[u8]) {
+		self.0.write().local_storage.insert(key.to_vec(), value.to_vec());
 	}
 
 	fn local_storage_compare_and_set(
 		&mut self,
-		_key: &[u8],
-		_old_value: &[u8],
-		_new_value: &[u8]
+		key: &[u8],
+		old_value: &[u8],
+		new_value: &[u8]
 	) {
-		unimplemented!("not needed in tests so far")
+		let mut state = self.0.write();
+		if state.local_storage.get(key).map(|v| &v[..]) == Some(old_value) {
+			state.local_storage.insert(key.to_vec(), new_value.to_vec());
+		}
 	}
 
-	fn local_storage_get(&mut self, _key: &[u8]) -> Option<Vec<u8>> {
-		unimplemented!("not needed in tests so far")
+	fn local_storage_get(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+		self.0.read().local_storage.get(key).cloned()
 	}
 
 	fn http_request_start(&mut self, method: &str, uri: &str, meta: &[u8]) -> Result<RequestId, ()> {
@@ -121,3 +121,16 @@ pub fn twox_256(data: &[u8]) -> [u8; 32] {
 	twox_256_into(data, &mut r);
 	r
 }
+
+/// Do a SHA2 256-bit hash and place result in `dest`.
+pub fn sha2_256_into(data: &[u8], dest: &mut [u8; 32]) {
+	use sha2::Digest;
+	dest.copy_from_slice(sha2::Sha256::digest(data).as_slice());
+}
+
+/// Do a SHA2 256-bit hash and return result.
+pub fn sha2_256(data: &[u8]) -> [u8; 32] {
+	let mut r = [0; 32];
+	sha2_256_into(data, &mut r);
+	r
+}
@@ -117,6 +117,90 @@ fn return_value_encoded_max_size() {
 	assert_eq!(encoded.len(), ReturnValue::ENCODED_MAX_SIZE);
 }
 
+/// The reason why the execution of a sandboxed function trapped.
+///
+/// On `ERR_EXECUTION` the code is written to the return value buffer passed to
+/// `ext_sandbox_invoke`, which is left untouched if the execution failed without a trap.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum TrapCode {
+	/// The reason is not known. This is what a zeroed return value buffer decodes to.
+	#[codec(index = "0")]
+	Unknown,
+
+	/// An `unreachable` instruction was executed.
+	#[codec(index = "1")]
+	Unreachable,
+
+	/// The memory was accessed out of its bounds.
+	#[codec(index = "2")]
+	MemoryAccessOutOfBounds,
+
+	/// The table was accessed out of its bounds.
+	#[codec(index = "3")]
+	TableAccessOutOfBounds,
+
+	/// An uninitialized element of the table was called.
+	#[codec(index = "4")]
+	ElemUninitialized,
+
+	/// An integer was divided by zero.
+	#[codec(index = "5")]
+	DivisionByZero,
+
+	/// A float couldn't be converted to an integer, because it is NaN or out of range.
+	#[codec(index = "6")]
+	InvalidConversionToInt,
+
+	/// The call stack was exhausted.
+	#[codec(index = "7")]
+	StackOverflow,
+
+	/// An indirect call was made to a function of an unexpected signature.
+	#[codec(index = "8")]
+	UnexpectedSignature,
+
+	/// A host function returned an error.
+	#[codec(index = "9")]
+	Host,
+}
+
+impl TrapCode {
+	/// Returns a human readable description of the trap.
+	pub fn description(&self) -> &'static str {
+		match *self {
+			TrapCode::Unknown => "during execution",
+			TrapCode::Unreachable => "unreachable instruction executed",
+			TrapCode::MemoryAccessOutOfBounds => "out of bounds memory access",
+			TrapCode::TableAccessOutOfBounds => "out of bounds table access",
+			TrapCode::ElemUninitialized => "uninitialized table element called",
+			TrapCode::DivisionByZero => "integer division by zero",
+			TrapCode::InvalidConversionToInt => "invalid conversion to integer",
+			TrapCode::StackOverflow => "call stack exhausted",
+			TrapCode::UnexpectedSignature => "indirect call signature mismatch",
+			TrapCode::Host => "host function trapped",
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl<'a> From<&'a ::wasmi::TrapKind> for TrapCode {
+	fn from(kind: &'a ::wasmi::TrapKind) -> TrapCode {
+		use ::wasmi::TrapKind;
+		match *kind {
+			TrapKind::Unreachable => TrapCode::Unreachable,
+			TrapKind::MemoryAccessOutOfBounds => TrapCode::MemoryAccessOutOfBounds,
+			TrapKind::TableAccessOutOfBounds => TrapCode::TableAccessOutOfBounds,
+			TrapKind::ElemUninitialized => TrapCode::ElemUninitialized,
+			TrapKind::DivisionByZero => TrapCode::DivisionByZero,
+			TrapKind::InvalidConversionToInt => TrapCode::InvalidConversionToInt,
+			TrapKind::StackOverflow => TrapCode::StackOverflow,
+			TrapKind::UnexpectedSignature => TrapCode::UnexpectedSignature,
+			TrapKind::Host(_) => TrapCode::Host,
+		}
+	}
+}
+
 /// Describes an entity to define or import into the environment.
 #[derive(Clone, PartialEq, Eq, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Debug))]
@@ -177,6 +261,8 @@ pub const ERR_OUT_OF_BOUNDS: u32 = -2i32 as u32;
 
 /// Execution error occurred (typically trap).
 ///
+/// If the execution trapped, the `TrapCode` is written to the return value buffer.
+///
 /// For FFI purposes.
 pub const ERR_EXECUTION: u32 = -3i32 as u32;
 
@@ -217,4 +303,10 @@ mod tests {
 			],
 		});
 	}
+
+	#[test]
+	fn zeroed_return_value_buffer_decodes_to_unknown_trap() {
+		let buf = [0u8; ReturnValue::ENCODED_MAX_SIZE];
+		assert_eq!(TrapCode::decode(&mut &buf[..]), Some(TrapCode::Unknown));
+	}
 }
@@ -32,6 +32,8 @@ const WS_MAX_CONNECTIONS: usize = 100;
 
 type Metadata = apis::metadata::Metadata;
 type RpcHandler = pubsub::PubSubHandler<Metadata>;
+/// RPC methods a node exposes on top of the ones every Substrate node exposes.
+pub type RpcExtension = rpc::MetaIoHandler<Metadata>;
 pub type HttpServer = http::Server;
 pub type WsServer = ws::Server;
 
@@ -41,6 +43,7 @@ pub fn rpc_handler<Block: BlockT, ExHash, S, C, A, Y>(
 	chain: C,
 	author: A,
 	system: Y,
+	extension: RpcExtension,
 ) -> RpcHandler where
 	Block: BlockT + 'static,
 	ExHash: Send + Sync + 'static + sr_primitives::Serialize + sr_primitives::DeserializeOwned,
@@ -54,6 +57,7 @@ pub fn rpc_handler<Block: BlockT, ExHash, S, C, A, Y>(
 	io.extend_with(chain.to_delegate());
 	io.extend_with(author.to_delegate());
 	io.extend_with(system.to_delegate());
+	io.extend_with(extension);
 	io
 }
 
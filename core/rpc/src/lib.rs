@@ -20,8 +20,10 @@
 
 mod errors;
 mod helpers;
+mod policy;
 mod subscriptions;
 
+pub use policy::{DenyUnsafe, UnsafeRpcError};
 pub use subscriptions::Subscriptions;
 
 pub mod author;
@@ -19,6 +19,7 @@
 use client;
 use crate::rpc;
 use crate::errors;
+use crate::policy::UnsafeRpcError;
 
 /// State RPC Result type.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -38,12 +39,15 @@ pub enum Error {
 		/// Details of the error message.
 		details: String,
 	},
+	/// Call to an unsafe RPC was denied.
+	UnsafeRpcCalled(UnsafeRpcError),
 }
 
 impl std::error::Error for Error {
 	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
 		match self {
 			Error::Client(ref err) => Some(err),
+			Error::UnsafeRpcCalled(ref err) => Some(err),
 			_ => None,
 		}
 	}
@@ -60,6 +64,7 @@ impl From<Error> for rpc::Error {
 				message: format!("{}", e),
 				data: None,
 			},
+			Error::UnsafeRpcCalled(e) => e.into(),
 			e => errors::internal(e),
 		}
 	}
@@ -31,9 +31,11 @@ use client::{self, Client, CallExecutor, BlockchainEvents, runtime_api::Metadata
 use crate::rpc::Result as RpcResult;
 use crate::rpc::futures::{stream, Future, Sink, Stream};
 use crate::subscriptions::Subscriptions;
+use crate::policy::DenyUnsafe;
 use jsonrpc_derive::rpc;
 use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
 use log::{warn, trace};
+use serde::{Serialize, Deserialize};
 use primitives::hexdisplay::HexDisplay;
 use primitives::storage::{self, StorageKey, StorageData, StorageChangeSet};
 use primitives::{H256, Blake2Hasher, Bytes};
@@ -48,6 +50,16 @@ use state_machine::{self, ExecutionStrategy};
 
 pub use self::gen_client::Client as StateClient;
 
+/// Proof of storage entries, checked against the state root of the block.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadProof<Hash> {
+	/// Hash of the block the proof was generated at.
+	pub at: Hash,
+	/// Trie nodes that make up the proof.
+	pub proof: Vec<Bytes>,
+}
+
 /// Substrate state API
 #[rpc]
 pub trait StateApi<Hash> {
@@ -110,6 +122,18 @@ pub trait StateApi<Hash> {
 		hash: Option<Hash>
 	) -> Result<Option<u64>>;
 
+	/// Returns proof of child storage entries at a specific block's state.
+	///
+	/// The proof covers the path to the child trie root in the top trie and
+	/// the paths to all the keys in the child trie.
+	#[rpc(name = "state_getChildReadProof")]
+	fn read_child_proof(
+		&self,
+		child_storage_key: StorageKey,
+		keys: Vec<StorageKey>,
+		hash: Option<Hash>
+	) -> Result<ReadProof<Hash>>;
+
 	/// Returns the runtime metadata as an opaque blob.
 	#[rpc(name = "state_getMetadata")]
 	fn metadata(&self, hash: Option<Hash>) -> Result<Bytes>;
@@ -118,6 +142,19 @@ pub trait StateApi<Hash> {
 	#[rpc(name = "state_getRuntimeVersion", alias("chain_getRuntimeVersion"))]
 	fn runtime_version(&self, hash: Option<Hash>) -> Result<RuntimeVersion>;
 
+	/// Prevent the state of a finalized block from being pruned until `state_unpinState` is
+	/// called or the pin expires. Calls are reference counted.
+	///
+	/// This is an unsafe RPC: pinned states are kept on disk.
+	#[rpc(name = "state_pinState")]
+	fn pin_state(&self, hash: Hash) -> Result<()>;
+
+	/// Release the state pinned with `state_pinState`.
+	///
+	/// This is an unsafe RPC.
+	#[rpc(name = "state_unpinState")]
+	fn unpin_state(&self, hash: Hash) -> Result<()>;
+
 	/// Query historical storage entries (by key) starting from a block given as the second parameter.
 	///
 	/// NOTE This first returned result contains the initial state of storage for all keys.
@@ -167,6 +204,8 @@ pub struct State<B, E, Block: BlockT, RA> {
 	client: Arc<Client<B, E, Block, RA>>,
 	/// Current subscriptions.
 	subscriptions: Subscriptions,
+	/// Whether to deny unsafe calls.
+	deny_unsafe: DenyUnsafe,
 }
 
 /// Ranges to query in state_queryStorage.
@@ -189,10 +228,11 @@ impl<B, E, Block: BlockT, RA> State<B, E, Block, RA> where
 	E: CallExecutor<Block, Blake2Hasher>,
 {
 	/// Create new State API RPC handler.
-	pub fn new(client: Arc<Client<B, E, Block, RA>>, subscriptions: Subscriptions) -> Self {
+	pub fn new(client: Arc<Client<B, E, Block, RA>>, subscriptions: Subscriptions, deny_unsafe: DenyUnsafe) -> Self {
 		Self {
 			client,
 			subscriptions,
+			deny_unsafe,
 		}
 	}
 
@@ -431,11 +471,44 @@ impl<B, E, Block, RA> StateApi<Block::Hash> for State<B, E, Block, RA> where
 		Ok(self.child_storage(child_storage_key, key, block)?.map(|x| x.0.len() as u64))
 	}
 
+	fn read_child_proof(
+		&self,
+		child_storage_key: StorageKey,
+		keys: Vec<StorageKey>,
+		block: Option<Block::Hash>
+	) -> Result<ReadProof<Block::Hash>> {
+		let block = self.unwrap_or_best(block)?;
+		trace!(
+			target: "rpc", "Generating child read proof at {:?} for {} keys of {}",
+			block,
+			keys.len(),
+			HexDisplay::from(&child_storage_key.0),
+		);
+		let keys = keys.into_iter().map(|key| key.0).collect::<Vec<_>>();
+		let proof = self.client.read_child_proof_keys(&BlockId::Hash(block), &child_storage_key.0, &keys)?;
+		Ok(ReadProof {
+			at: block,
+			proof: proof.into_iter().map(Into::into).collect(),
+		})
+	}
+
 	fn metadata(&self, block: Option<Block::Hash>) -> Result<Bytes> {
 		let block = self.unwrap_or_best(block)?;
 		self.client.runtime_api().metadata(&BlockId::Hash(block)).map(Into::into).map_err(Into::into)
 	}
 
+	fn pin_state(&self, block: Block::Hash) -> Result<()> {
+		self.deny_unsafe.check_if_safe()?;
+		trace!(target: "rpc", "Pinning state at {:?}", block);
+		Ok(self.client.pin_state(&block)?)
+	}
+
+	fn unpin_state(&self, block: Block::Hash) -> Result<()> {
+		self.deny_unsafe.check_if_safe()?;
+		trace!(target: "rpc", "Unpinning state at {:?}", block);
+		Ok(self.client.unpin_state(&block)?)
+	}
+
 	fn query_storage(
 		&self,
 		keys: Vec<StorageKey>,
@@ -32,7 +32,7 @@ fn should_return_storage() {
 	let core = tokio::runtime::Runtime::new().unwrap();
 	let client = Arc::new(test_client::new());
 	let genesis_hash = client.genesis_hash();
-	let client = State::new(client, Subscriptions::new(Arc::new(core.executor())));
+	let client = State::new(client, Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
 	let key = StorageKey(b":code".to_vec());
 
 	assert_eq!(
@@ -50,6 +50,25 @@ fn should_return_storage() {
 	);
 }
 
+#[test]
+fn should_pin_state() {
+	let core = tokio::runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::new());
+	let genesis_hash = client.genesis_hash();
+	let api = State::new(client.clone(), Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
+
+	assert_matches!(api.pin_state(genesis_hash), Ok(()));
+	assert_matches!(api.unpin_state(genesis_hash), Ok(()));
+	assert_matches!(
+		api.pin_state(H256::repeat_byte(42)),
+		Err(Error::Client(client::error::Error::UnknownBlock(_)))
+	);
+
+	let api = State::new(client, Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::Yes);
+	assert_matches!(api.pin_state(genesis_hash), Err(Error::UnsafeRpcCalled(_)));
+	assert_matches!(api.unpin_state(genesis_hash), Err(Error::UnsafeRpcCalled(_)));
+}
+
 #[test]
 fn should_return_child_storage() {
 	let core = tokio::runtime::Runtime::new().unwrap();
@@ -57,7 +76,7 @@ fn should_return_child_storage() {
 		.add_child_storage("test", "key", vec![42_u8])
 		.build());
 	let genesis_hash = client.genesis_hash();
-	let client = State::new(client, Subscriptions::new(Arc::new(core.executor())));
+	let client = State::new(client, Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
 	let child_key = StorageKey(well_known_keys::CHILD_STORAGE_KEY_PREFIX.iter().chain(b"test").cloned().collect());
 	let key = StorageKey(b"key".to_vec());
 
@@ -77,12 +96,37 @@ fn should_return_child_storage() {
 	);
 }
 
+#[test]
+fn should_return_child_read_proof() {
+	let core = tokio::runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::TestClientBuilder::new()
+		.add_child_storage("test", "key", vec![42_u8])
+		.add_child_storage("test", "other", vec![43_u8])
+		.build());
+	let genesis_hash = client.genesis_hash();
+	let state_root = *client.header(&BlockId::Hash(genesis_hash)).unwrap().unwrap().state_root();
+	let api = State::new(client, Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
+	let child_key = StorageKey(well_known_keys::CHILD_STORAGE_KEY_PREFIX.iter().chain(b"test").cloned().collect());
+	let keys = vec![StorageKey(b"key".to_vec()), StorageKey(b"other".to_vec()), StorageKey(b"missing".to_vec())];
+
+	let read_proof = api.read_child_proof(child_key.clone(), keys.clone(), None).unwrap();
+	assert_eq!(read_proof.at, genesis_hash);
+
+	let values = state_machine::read_child_proof_check_keys::<Blake2Hasher, _>(
+		state_root,
+		read_proof.proof.into_iter().map(|node| node.0).collect(),
+		&child_key.0,
+		keys.iter().map(|key| &key.0),
+	).unwrap();
+	assert_eq!(values, vec![Some(vec![42]), Some(vec![43]), None]);
+}
+
 #[test]
 fn should_call_contract() {
 	let core = tokio::runtime::Runtime::new().unwrap();
 	let client = Arc::new(test_client::new());
 	let genesis_hash = client.genesis_hash();
-	let client = State::new(client, Subscriptions::new(Arc::new(core.executor())));
+	let client = State::new(client, Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
 
 	assert_matches!(
 		client.call("balanceOf".into(), Bytes(vec![1,2,3]), Some(genesis_hash).into()),
@@ -97,7 +141,7 @@ fn should_notify_about_storage_changes() {
 	let (subscriber, id, transport) = Subscriber::new_test("test");
 
 	{
-		let api = State::new(Arc::new(test_client::new()), Subscriptions::new(Arc::new(remote)));
+		let api = State::new(Arc::new(test_client::new()), Subscriptions::new(Arc::new(remote)), DenyUnsafe::No);
 
 		api.subscribe_storage(Default::default(), subscriber, None.into());
 
@@ -128,7 +172,7 @@ fn should_send_initial_storage_changes_and_notifications() {
 	let (subscriber, id, transport) = Subscriber::new_test("test");
 
 	{
-		let api = State::new(Arc::new(test_client::new()), Subscriptions::new(Arc::new(remote)));
+		let api = State::new(Arc::new(test_client::new()), Subscriptions::new(Arc::new(remote)), DenyUnsafe::No);
 
 		let alice_balance_key = blake2_256(&runtime::system::balance_of_key(AccountKeyring::Alice.into()));
 
@@ -163,7 +207,7 @@ fn should_send_initial_storage_changes_and_notifications() {
 fn should_query_storage() {
 	fn run_tests(client: Arc<TestClient>) {
 		let core = tokio::runtime::Runtime::new().unwrap();
-		let api = State::new(client.clone(), Subscriptions::new(Arc::new(core.executor())));
+		let api = State::new(client.clone(), Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
 
 		let add_block = |nonce| {
 			let mut builder = client.new_block(Default::default()).unwrap();
@@ -254,7 +298,7 @@ fn should_return_runtime_version() {
 	let core = tokio::runtime::Runtime::new().unwrap();
 
 	let client = Arc::new(test_client::new());
-	let api = State::new(client.clone(), Subscriptions::new(Arc::new(core.executor())));
+	let api = State::new(client.clone(), Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
 
 	let result = "{\"specName\":\"test\",\"implName\":\"parity-test\",\"authoringVersion\":1,\
 		\"specVersion\":1,\"implVersion\":1,\"apis\":[[\"0xdf6acb689907609b\",2],\
@@ -274,7 +318,7 @@ fn should_notify_on_runtime_version_initially() {
 
 	{
 		let client = Arc::new(test_client::new());
-		let api = State::new(client.clone(), Subscriptions::new(Arc::new(core.executor())));
+		let api = State::new(client.clone(), Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
 
 		api.subscribe_runtime_version(Default::default(), subscriber);
 
@@ -19,6 +19,7 @@
 use std::fmt;
 use serde::{Serialize, Deserialize};
 use serde_json::{Value, map::Map};
+use client::backend::DatabaseStats;
 
 /// Node properties
 pub type Properties = Map<String, Value>;
@@ -66,6 +67,73 @@ pub struct PeerInfo<Hash, Number> {
 	pub best_number: Number,
 }
 
+/// Database column usage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnStats {
+	/// Column name
+	pub name: String,
+	/// Number of keys in the column
+	pub keys: u64,
+	/// Total size of keys and values in bytes
+	pub size: u64,
+}
+
+/// Database statistics returned by the RPC
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbStats {
+	/// Per-column usage
+	pub columns: Vec<ColumnStats>,
+	/// Number of the oldest block in the pruning window
+	///
+	/// `None` if the state is not pruned.
+	pub pruning_window_start: Option<u64>,
+	/// Number of canonical blocks in the pruning window
+	pub pruning_window_size: u64,
+	/// Number of the last canonicalized block
+	pub last_canonical: Option<u64>,
+	/// Number of blocks held in the non-canonical overlay
+	pub non_canonical_blocks: u64,
+	/// Number of trie nodes held in the non-canonical overlay
+	pub non_canonical_values: u64,
+	/// Size of the trie nodes held in the non-canonical overlay in bytes
+	pub non_canonical_size: u64,
+	/// Number of blocks with pinned state
+	pub pinned_blocks: u64,
+	/// Number of states pinned with `state_pinState`
+	pub pinned_states: u64,
+	/// Number of state cache hits
+	pub state_cache_hits: u64,
+	/// Number of state cache misses
+	pub state_cache_misses: u64,
+	/// Used state cache size in bytes
+	pub state_cache_size: u64,
+}
+
+impl From<DatabaseStats> for DbStats {
+	fn from(stats: DatabaseStats) -> Self {
+		DbStats {
+			columns: stats.columns.into_iter().map(|column| ColumnStats {
+				name: column.name.into(),
+				keys: column.keys,
+				size: column.size,
+			}).collect(),
+			pruning_window_start: stats.state_db.pruning_window_start,
+			pruning_window_size: stats.state_db.pruning_window_size,
+			last_canonical: stats.state_db.last_canonical,
+			non_canonical_blocks: stats.state_db.non_canonical_blocks,
+			non_canonical_values: stats.state_db.non_canonical_values,
+			non_canonical_size: stats.state_db.non_canonical_size,
+			pinned_blocks: stats.state_db.pinned_blocks,
+			pinned_states: stats.state_db.pinned_states,
+			state_cache_hits: stats.state_cache.hits,
+			state_cache_misses: stats.state_cache.misses,
+			state_cache_size: stats.state_cache.used_size,
+		}
+	}
+}
+
 impl fmt::Display for Health {
 	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
 		write!(fmt, "{} peers ({})", self.peers, if self.is_syncing {
@@ -103,4 +171,21 @@ mod tests {
 			r#"{"peerId":"2","roles":"a","protocolVersion":2,"bestHash":5,"bestNumber":6}"#,
 		);
 	}
+
+	#[test]
+	fn should_serialize_db_stats() {
+		let stats: DbStats = DatabaseStats {
+			columns: vec![client::backend::ColumnStats { name: "state", keys: 2, size: 100 }],
+			..Default::default()
+		}.into();
+		assert_eq!(
+			::serde_json::to_string(&stats).unwrap(),
+			concat!(
+				r#"{"columns":[{"name":"state","keys":2,"size":100}],"pruningWindowStart":null,"#,
+				r#""pruningWindowSize":0,"lastCanonical":null,"nonCanonicalBlocks":0,"nonCanonicalValues":0,"#,
+				r#""nonCanonicalSize":0,"pinnedBlocks":0,"pinnedStates":0,"stateCacheHits":0,"#,
+				r#""stateCacheMisses":0,"stateCacheSize":0}"#,
+			),
+		);
+	}
 }
@@ -23,13 +23,15 @@ pub mod helpers;
 mod tests;
 
 use crate::helpers::Receiver;
+use crate::policy::DenyUnsafe;
+use jsonrpc_core::{BoxFuture, futures::future};
 use futures::sync::{mpsc, oneshot};
 use jsonrpc_derive::rpc;
 use network;
 use runtime_primitives::traits::{self, Header as HeaderT};
 
 use self::error::Result;
-pub use self::helpers::{Properties, SystemInfo, Health, PeerInfo};
+pub use self::helpers::{Properties, SystemInfo, Health, PeerInfo, DbStats, ColumnStats};
 
 pub use self::gen_client::Client as SystemClient;
 
@@ -70,12 +72,23 @@ pub trait SystemApi<Hash, Number> {
 	// TODO: make this stable and move structs https://github.com/paritytech/substrate/issues/1890
 	#[rpc(name = "system_networkState", returns = "network::NetworkState")]
 	fn system_network_state(&self) -> Receiver<network::NetworkState>;
+
+	/// Returns key counts and sizes of the database columns, the position of the state
+	/// pruning window and state cache usage. `None` if the node doesn't keep a local database.
+	///
+	/// Counting the keys requires a scan of the whole database, which may take a while,
+	/// so the result can be up to a minute old.
+	///
+	/// This is an unsafe RPC.
+	#[rpc(name = "system_dbStats", returns = "Option<DbStats>")]
+	fn system_db_stats(&self) -> BoxFuture<Option<DbStats>>;
 }
 
 /// System API implementation
 pub struct System<B: traits::Block> {
 	info: SystemInfo,
 	send_back: mpsc::UnboundedSender<Request<B>>,
+	deny_unsafe: DenyUnsafe,
 }
 
 /// Request to be processed.
@@ -86,6 +99,8 @@ pub enum Request<B: traits::Block> {
 	Peers(oneshot::Sender<Vec<PeerInfo<B::Hash, <B::Header as HeaderT>::Number>>>),
 	/// Must return the state of the network.
 	NetworkState(oneshot::Sender<network::NetworkState>),
+	/// Must return the database statistics.
+	DbStats(oneshot::Sender<Option<DbStats>>),
 }
 
 impl<B: traits::Block> System<B> {
@@ -95,11 +110,13 @@ impl<B: traits::Block> System<B> {
 	/// reading from that channel and answering the requests.
 	pub fn new(
 		info: SystemInfo,
-		send_back: mpsc::UnboundedSender<Request<B>>
+		send_back: mpsc::UnboundedSender<Request<B>>,
+		deny_unsafe: DenyUnsafe,
 	) -> Self {
 		System {
 			info,
 			send_back,
+			deny_unsafe,
 		}
 	}
 }
@@ -138,4 +155,13 @@ impl<B: traits::Block> SystemApi<B::Hash, <B::Header as HeaderT>::Number> for Sy
 		let _ = self.send_back.unbounded_send(Request::NetworkState(tx));
 		Receiver(rx)
 	}
+
+	fn system_db_stats(&self) -> BoxFuture<Option<DbStats>> {
+		if let Err(e) = self.deny_unsafe.check_if_safe() {
+			return Box::new(future::err(e.into()));
+		}
+		let (tx, rx) = oneshot::channel();
+		let _ = self.send_back.unbounded_send(Request::DbStats(tx));
+		Box::new(Receiver(rx))
+	}
 }
@@ -80,6 +80,12 @@ fn api<T: Into<Option<Status>>>(sync: T) -> System<Block> {
 						peerset: serde_json::Value::Null,
 					});
 				}
+				Request::DbStats(sender) => {
+					let _ = sender.send(Some(client::backend::DatabaseStats {
+						columns: vec![client::backend::ColumnStats { name: "header", keys: 1, size: 64 }],
+						..Default::default()
+					}.into()));
+				}
 			};
 
 			Ok(())
@@ -90,7 +96,7 @@ fn api<T: Into<Option<Status>>>(sync: T) -> System<Block> {
 		impl_version: "0.2.0".into(),
 		chain_name: "testchain".into(),
 		properties: Default::default(),
-	}, tx)
+	}, tx, DenyUnsafe::No)
 }
 
 fn wait_receiver<T>(rx: Receiver<T>) -> T {
@@ -220,3 +226,27 @@ fn system_network_state() {
 		}
 	);
 }
+
+#[test]
+fn system_db_stats() {
+	let mut runtime = tokio::runtime::current_thread::Runtime::new().unwrap();
+	let stats = runtime.block_on(api(None).system_db_stats()).unwrap().unwrap();
+	assert_eq!(stats.columns, vec![ColumnStats { name: "header".into(), keys: 1, size: 64 }]);
+	assert_eq!(stats.pruning_window_start, None);
+}
+
+#[test]
+fn system_db_stats_is_unsafe() {
+	let (tx, _rx) = mpsc::unbounded();
+	let api = System::<Block>::new(SystemInfo {
+		impl_name: "testclient".into(),
+		impl_version: "0.2.0".into(),
+		chain_name: "testchain".into(),
+		properties: Default::default(),
+	}, tx, DenyUnsafe::Yes);
+	let mut runtime = tokio::runtime::current_thread::Runtime::new().unwrap();
+	assert_eq!(
+		runtime.block_on(api.system_db_stats()).unwrap_err().code,
+		jsonrpc_core::ErrorCode::MethodNotFound,
+	);
+}
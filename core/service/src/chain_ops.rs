@@ -26,6 +26,7 @@ use consensus_common::import_queue::{ImportQueue, IncomingBlock, Link};
 use network::message;
 
 use consensus_common::BlockOrigin;
+use client::backend::{Backend, DatabaseStats};
 use crate::components::{self, Components, ServiceFactory, FactoryFullConfiguration, FactoryBlockNumber, RuntimeGenesis};
 use crate::new_client;
 use parity_codec::{Decode, Encode};
@@ -213,6 +214,37 @@ pub fn revert_chain<F>(
 	Ok(())
 }
 
+/// Collect usage statistics of the client database.
+pub fn database_stats<F>(
+	config: FactoryFullConfiguration<F>,
+) -> error::Result<Option<DatabaseStats>>
+	where F: ServiceFactory,
+{
+	let client = new_client::<F>(&config)?;
+	Ok(client.backend().database_stats()?)
+}
+
+/// Compact the client database. See `client_db::compact_database` for the meaning of the range.
+pub fn compact_database<F>(
+	config: FactoryFullConfiguration<F>,
+	column: Option<&str>,
+	from: Option<&[u8]>,
+	to: Option<&[u8]>,
+) -> error::Result<()>
+	where F: ServiceFactory,
+{
+	let db_settings = client_db::DatabaseSettings {
+		cache_size: config.database_cache_size.map(|u| u as usize),
+		state_cache_size: config.state_cache_size,
+		state_cache_child_ratio: config.state_cache_child_ratio.map(|v| (v, 100)),
+		path: config.database_path.as_str().into(),
+		pruning: config.pruning.clone(),
+		backend: config.database_backend.clone(),
+	};
+	info!("Compacting database at {}", config.database_path);
+	Ok(client_db::compact_database(&db_settings, column, from, to)?)
+}
+
 /// Build a chain spec json
 pub fn build_spec<G>(spec: ChainSpec<G>, raw: bool) -> error::Result<String>
 	where G: RuntimeGenesis,
@@ -29,7 +29,7 @@ use transaction_pool::txpool::{self, Options as TransactionPoolOptions, Pool as
 use runtime_primitives::{
 	BuildStorage, traits::{Block as BlockT, Header as HeaderT, ProvideRuntimeApi}, generic::BlockId
 };
-use crate::config::Configuration;
+use crate::config::{Configuration, RpcMethods};
 use primitives::{Blake2Hasher, H256};
 use rpc::{self, apis::system::SystemInfo};
 use parking_lot::Mutex;
@@ -149,6 +149,7 @@ pub trait StartRPC<C: Components> {
 		rpc_ws: Option<SocketAddr>,
 		rpc_ws_max_connections: Option<usize>,
 		rpc_cors: Option<Vec<String>>,
+		rpc_methods: RpcMethods,
 		task_executor: TaskExecutor,
 		transaction_pool: Arc<TransactionPool<C::TransactionPoolApi>>,
 	) -> error::Result<Self::ServersHandle>;
@@ -168,32 +169,43 @@ impl<C: Components> StartRPC<Self> for C where
 		rpc_ws: Option<SocketAddr>,
 		rpc_ws_max_connections: Option<usize>,
 		rpc_cors: Option<Vec<String>>,
+		rpc_methods: RpcMethods,
 		task_executor: TaskExecutor,
 		transaction_pool: Arc<TransactionPool<C::TransactionPoolApi>>,
 	) -> error::Result<Self::ServersHandle> {
-		let handler = || {
+		let deny_unsafe = |address: &SocketAddr| match rpc_methods {
+			RpcMethods::Safe => rpc::apis::DenyUnsafe::Yes,
+			RpcMethods::Unsafe => rpc::apis::DenyUnsafe::No,
+			RpcMethods::Auto => if address.ip().is_loopback() {
+				rpc::apis::DenyUnsafe::No
+			} else {
+				rpc::apis::DenyUnsafe::Yes
+			},
+		};
+		let handler = |deny_unsafe| {
 			let client = client.clone();
 			let subscriptions = rpc::apis::Subscriptions::new(task_executor.clone());
 			let chain = rpc::apis::chain::Chain::new(client.clone(), subscriptions.clone());
-			let state = rpc::apis::state::State::new(client.clone(), subscriptions.clone());
+			let state = rpc::apis::state::State::new(client.clone(), subscriptions.clone(), deny_unsafe);
 			let author = rpc::apis::author::Author::new(
 				client.clone(), transaction_pool.clone(), subscriptions
 			);
 			let system = rpc::apis::system::System::new(
-				rpc_system_info.clone(), system_send_back.clone()
+				rpc_system_info.clone(), system_send_back.clone(), deny_unsafe
 			);
 			rpc::rpc_handler::<ComponentBlock<C>, ComponentExHash<C>, _, _, _, _>(
 				state,
 				chain,
 				author,
 				system,
+				C::build_rpc_extensions(client),
 			)
 		};
 
 		Ok((
 			maybe_start_server(
 				rpc_http,
-				|address| rpc::start_http(address, rpc_cors.as_ref(), handler()),
+				|address| rpc::start_http(address, rpc_cors.as_ref(), handler(deny_unsafe(address))),
 			)?,
 			maybe_start_server(
 				rpc_ws,
@@ -201,7 +213,7 @@ impl<C: Components> StartRPC<Self> for C where
 					address,
 					rpc_ws_max_connections,
 					rpc_cors.as_ref(),
-					handler(),
+					handler(deny_unsafe(address)),
 				),
 			)?.map(Mutex::new),
 		))
@@ -350,6 +362,17 @@ pub trait ServiceFactory: 'static + Sized {
 		client: Arc<FullClient<Self>>,
 	) -> Result<Self::SelectChain, error::Error>;
 
+	/// Build the RPC extensions of the full client.
+	///
+	/// These are exposed on top of the RPC methods every node exposes. None by default.
+	fn build_full_rpc_extensions(_client: Arc<FullClient<Self>>) -> rpc::RpcExtension {
+		Default::default()
+	}
+	/// Build the RPC extensions of the light client. None by default.
+	fn build_light_rpc_extensions(_client: Arc<LightClient<Self>>) -> rpc::RpcExtension {
+		Default::default()
+	}
+
 	/// Build full service.
 	fn new_full(config: FactoryFullConfiguration<Self>)
 		-> Result<Self::FullService, error::Error>;
@@ -445,6 +468,9 @@ pub trait Components: Sized + 'static {
 		config: &mut FactoryFullConfiguration<Self::Factory>,
 		client: Arc<ComponentClient<Self>>
 	) -> Result<Option<Self::SelectChain>, error::Error>;
+
+	/// Build the RPC extensions of the node.
+	fn build_rpc_extensions(client: Arc<ComponentClient<Self>>) -> rpc::RpcExtension;
 }
 
 /// A struct that implement `Components` for the full client.
@@ -514,6 +540,7 @@ impl<Factory: ServiceFactory> Components for FullComponents<Factory> {
 				config.state_cache_child_ratio.map(|v| (v, 100)),
 			path: config.database_path.as_str().into(),
 			pruning: config.pruning.clone(),
+			backend: config.database_backend.clone(),
 		};
 		Ok((Arc::new(client_db::new_client(
 			db_settings,
@@ -552,6 +579,10 @@ impl<Factory: ServiceFactory> Components for FullComponents<Factory> {
 	) -> Result<Option<Arc<dyn FinalityProofProvider<<Self::Factory as ServiceFactory>::Block>>>, error::Error> {
 		Factory::build_finality_proof_provider(client)
 	}
+
+	fn build_rpc_extensions(client: Arc<ComponentClient<Self>>) -> rpc::RpcExtension {
+		Factory::build_full_rpc_extensions(client)
+	}
 }
 
 /// A struct that implement `Components` for the light client.
@@ -616,6 +647,7 @@ impl<Factory: ServiceFactory> Components for LightComponents<Factory> {
 				config.state_cache_child_ratio.map(|v| (v, 100)),
 			path: config.database_path.as_str().into(),
 			pruning: config.pruning.clone(),
+			backend: config.database_backend.clone(),
 		};
 		let db_storage = client_db::light::LightStorage::new(db_settings)?;
 		let light_blockchain = client::light::new_light_blockchain(db_storage);
@@ -651,6 +683,10 @@ impl<Factory: ServiceFactory> Components for LightComponents<Factory> {
 	) -> Result<Option<Self::SelectChain>, error::Error> {
 		Ok(None)
 	}
+
+	fn build_rpc_extensions(client: Arc<ComponentClient<Self>>) -> rpc::RpcExtension {
+		Factory::build_light_rpc_extensions(client)
+	}
 }
 
 #[cfg(test)]
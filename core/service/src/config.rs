@@ -20,7 +20,7 @@ use std::net::SocketAddr;
 use transaction_pool;
 use crate::chain_spec::ChainSpec;
 pub use client::ExecutionStrategies;
-pub use client_db::PruningMode;
+pub use client_db::{PruningMode, DatabaseBackend};
 pub use network::ExtTransport;
 pub use network::config::{NetworkConfiguration, Roles};
 use runtime_primitives::BuildStorage;
@@ -28,6 +28,24 @@ use serde::{Serialize, de::DeserializeOwned};
 use target_info::Target;
 use tel::TelemetryEndpoints;
 
+/// Available RPC methods.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RpcMethods {
+	/// Expose every RPC method when the server listens on a loopback interface,
+	/// otherwise serve only safe RPC methods.
+	Auto,
+	/// Allow only a safe subset of RPC methods.
+	Safe,
+	/// Expose every RPC method, including potentially unsafe ones.
+	Unsafe,
+}
+
+impl Default for RpcMethods {
+	fn default() -> RpcMethods {
+		RpcMethods::Auto
+	}
+}
+
 /// Service configuration.
 #[derive(Clone)]
 pub struct Configuration<C, G: Serialize + DeserializeOwned + BuildStorage> {
@@ -49,6 +67,8 @@ pub struct Configuration<C, G: Serialize + DeserializeOwned + BuildStorage> {
 	pub database_path: String,
 	/// Cache Size for internal database in MiB
 	pub database_cache_size: Option<u32>,
+	/// Key-value database backend.
+	pub database_backend: DatabaseBackend,
 	/// Size of internal state cache in Bytes
 	pub state_cache_size: usize,
 	/// Size in percent of cache size dedicated to child tries 
@@ -73,6 +93,8 @@ pub struct Configuration<C, G: Serialize + DeserializeOwned + BuildStorage> {
 	pub rpc_ws_max_connections: Option<usize>,
 	/// CORS settings for HTTP & WS servers. `None` if all origins are allowed.
 	pub rpc_cors: Option<Vec<String>>,
+	/// RPC methods to expose.
+	pub rpc_methods: RpcMethods,
 	/// Telemetry service URL. `None` if disabled.
 	pub telemetry_endpoints: Option<TelemetryEndpoints>,
 	/// External WASM transport for the telemetry. If `Some`, when connection to a telemetry
@@ -105,6 +127,7 @@ impl<C: Default, G: Serialize + DeserializeOwned + BuildStorage> Configuration<C
 			keystore_path: Default::default(),
 			database_path: Default::default(),
 			database_cache_size: Default::default(),
+			database_backend: Default::default(),
 			state_cache_size: Default::default(),
 			state_cache_child_ratio: Default::default(),
 			keys: Default::default(),
@@ -115,6 +138,7 @@ impl<C: Default, G: Serialize + DeserializeOwned + BuildStorage> Configuration<C
 			rpc_ws: None,
 			rpc_ws_max_connections: None,
 			rpc_cors: Some(vec![]),
+			rpc_methods: Default::default(),
 			telemetry_endpoints: None,
 			telemetry_external_transport: None,
 			default_heap_pages: None,
@@ -28,7 +28,7 @@ pub mod error;
 use std::io;
 use std::net::SocketAddr;
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use futures::sync::mpsc;
 use parking_lot::Mutex;
 
@@ -46,7 +46,7 @@ use sysinfo::{get_current_pid, ProcessExt, System, SystemExt};
 use tel::{telemetry, SUBSTRATE_INFO};
 
 pub use self::error::Error;
-pub use config::{Configuration, Roles, PruningMode};
+pub use config::{Configuration, Roles, PruningMode, DatabaseBackend, RpcMethods};
 pub use chain_spec::{ChainSpec, Properties};
 pub use transaction_pool::txpool::{
 	self, Pool as TransactionPool, Options as TransactionPoolOptions, ChainApi, IntoPoolError
@@ -66,6 +66,7 @@ use components::{StartRPC, MaintainTransactionPool, OffchainWorker};
 pub use std::{ops::Deref, result::Result, sync::Arc};
 #[doc(hidden)]
 pub use network::{FinalityProofProvider, OnDemand};
+pub use rpc::RpcExtension;
 #[doc(hidden)]
 pub use futures::future::Executor;
 
@@ -434,11 +435,14 @@ impl<Components: components::Components> Service<Components> {
 			config.rpc_ws,
 			config.rpc_ws_max_connections,
 			config.rpc_cors.clone(),
+			config.rpc_methods,
 			Arc::new(ExecutorWithTx(to_spawn_tx.clone())),
 			transaction_pool.clone(),
 		)?;
 		let _ = to_spawn_tx.unbounded_send(Box::new(build_system_rpc_handler::<Components>(
 			network.clone(),
+			client.clone(),
+			to_spawn_tx.clone(),
 			system_rpc_rx,
 			has_bootnodes
 		)));
@@ -758,12 +762,27 @@ impl<C: Components> network::TransactionPool<ComponentExHash<C>, ComponentBlock<
 	}
 }
 
+/// Minimum time between two database scans answering `system_dbStats`.
+const DB_STATS_CACHE_DURATION: Duration = Duration::from_secs(60);
+
+/// Database statistics shared by the `system_dbStats` requests.
+#[derive(Default)]
+struct DbStatsCache {
+	/// Statistics collected by the last scan and when it finished.
+	last: Option<(Instant, Option<rpc::apis::system::DbStats>)>,
+	/// Requests waiting for the scan in progress.
+	waiting: Vec<futures::sync::oneshot::Sender<Option<rpc::apis::system::DbStats>>>,
+}
+
 /// Builds a never-ending `Future` that answers the RPC requests coming on the receiver.
 fn build_system_rpc_handler<Components: components::Components>(
 	network: Arc<NetworkService<Components>>,
+	client: Arc<ComponentClient<Components>>,
+	to_spawn_tx: mpsc::UnboundedSender<Box<dyn Future<Item = (), Error = ()> + Send>>,
 	rx: mpsc::UnboundedReceiver<rpc::apis::system::Request<ComponentBlock<Components>>>,
 	should_have_peers: bool,
 ) -> impl Future<Item = (), Error = ()> {
+	let db_stats = Arc::new(Mutex::new(DbStatsCache::default()));
 	rx.for_each(move |request| {
 		match request {
 			rpc::apis::system::Request::Health(sender) => {
@@ -785,6 +804,38 @@ fn build_system_rpc_handler<Components: components::Components>(
 			rpc::apis::system::Request::NetworkState(sender) => {
 				let _ = sender.send(network.network_state());
 			}
+			rpc::apis::system::Request::DbStats(sender) => {
+				let mut cache = db_stats.lock();
+				match cache.last {
+					Some((at, ref stats)) if at.elapsed() < DB_STATS_CACHE_DURATION => {
+						let _ = sender.send(stats.clone());
+					},
+					_ => {
+						cache.waiting.push(sender);
+						// only one scan at a time, the other requests wait for its result
+						if cache.waiting.len() == 1 {
+							let client = client.clone();
+							let db_stats = db_stats.clone();
+							// collecting the statistics scans the whole database, so don't block other requests
+							let _ = to_spawn_tx.unbounded_send(Box::new(futures::future::lazy(move || {
+								let stats: Option<rpc::apis::system::DbStats> = match client.backend().database_stats() {
+									Ok(stats) => stats.map(Into::into),
+									Err(e) => {
+										warn!("Failed to collect database statistics: {:?}", e);
+										None
+									},
+								};
+								let mut cache = db_stats.lock();
+								for sender in cache.waiting.drain(..) {
+									let _ = sender.send(stats.clone());
+								}
+								cache.last = Some((Instant::now(), stats));
+								Ok(())
+							})));
+						}
+					},
+				}
+			}
 		};
 
 		Ok(())
@@ -897,6 +948,8 @@ macro_rules! construct_service_factory {
 			SelectChain = $select_chain:ty
 				{ $( $select_chain_init:tt )* },
 			FinalityProofProvider = { $( $finality_proof_provider_init:tt )* },
+			$( FullRpcExtensions = { $( $full_rpc_extensions_init:tt )* }, )?
+			$( LightRpcExtensions = { $( $light_rpc_extensions_init:tt )* }, )?
 		}
 	) => {
 		$( #[$attr] )*
@@ -968,6 +1021,22 @@ macro_rules! construct_service_factory {
 				( $( $finality_proof_provider_init )* ) (client)
 			}
 
+			$(
+				fn build_full_rpc_extensions(
+					client: Arc<$crate::FullClient<Self>>
+				) -> $crate::RpcExtension {
+					( $( $full_rpc_extensions_init )* ) (client)
+				}
+			)?
+
+			$(
+				fn build_light_rpc_extensions(
+					client: Arc<$crate::LightClient<Self>>
+				) -> $crate::RpcExtension {
+					( $( $light_rpc_extensions_init )* ) (client)
+				}
+			)?
+
 			fn new_light(
 				config: $crate::FactoryFullConfiguration<Self>
 			) -> $crate::Result<Self::LightService, $crate::Error>
@@ -174,6 +174,7 @@ fn node_config<F: ServiceFactory> (
 		keystore_path: root.join("key").to_str().unwrap().into(),
 		database_path: root.join("db").to_str().unwrap().into(),
 		database_cache_size: None,
+		database_backend: Default::default(),
 		state_cache_size: 16777216,
 		state_cache_child_ratio: None,
 		pruning: Default::default(),
@@ -186,6 +187,7 @@ fn node_config<F: ServiceFactory> (
 		rpc_ws: None,
 		rpc_ws_max_connections: None,
 		rpc_cors: None,
+		rpc_methods: Default::default(),
 		telemetry_endpoints: None,
 		telemetry_external_transport: None,
 		default_heap_pages: None,
@@ -216,6 +216,9 @@ export_api! {
 		/// Conduct a 256-bit Blake2 hash.
 		fn blake2_256(data: &[u8]) -> [u8; 32];
 
+		/// Conduct a 256-bit SHA2 hash.
+		fn sha2_256(data: &[u8]) -> [u8; 32];
+
 		/// Conduct four XX hashes to give a 256-bit result.
 		fn twox_256(data: &[u8]) -> [u8; 32];
 
@@ -15,7 +15,7 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 use primitives::{
-	blake2_128, blake2_256, twox_128, twox_256, twox_64, ed25519, Blake2Hasher,
+	blake2_128, blake2_256, sha2_256, twox_128, twox_256, twox_64, ed25519, Blake2Hasher,
 	sr25519, Pair
 };
 // Switch to this after PoC-3
@@ -241,6 +241,10 @@ impl HashingApi for () {
 		blake2_256(data)
 	}
 
+	fn sha2_256(data: &[u8]) -> [u8; 32] {
+		sha2_256(data)
+	}
+
 	fn twox_256(data: &[u8]) -> [u8; 32] {
 		twox_256(data)
 	}
@@ -798,6 +798,15 @@ impl HashingApi for () {
 		result
 	}
 
+	fn sha2_256(data: &[u8]) -> [u8; 32] {
+		// Hashed within the runtime rather than through the host, so that the runtime can be
+		// executed by nodes which do not provide an `ext_sha2_256` function.
+		use sha2::Digest;
+		let mut result: [u8; 32] = Default::default();
+		result.copy_from_slice(&sha2::Sha256::digest(data));
+		result
+	}
+
 	fn twox_256(data: &[u8]) -> [u8; 32] {
 		let mut result: [u8; 32] = Default::default();
 		unsafe {
@@ -0,0 +1,123 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Generic implementation of an extrinsic with signed extensions that has passed the verification
+//! stage.
+
+use rstd::marker::PhantomData;
+use crate::traits::{self, Member, SimpleArithmetic, MaybeDisplay, SignedExtension};
+use crate::weights::{DispatchInfo, GetDispatchInfo, Weighable, Weight};
+use crate::transaction_validity::ValidTransaction;
+use crate::ApplyError;
+
+/// Definition of something that the external world might want to say; its
+/// existence implies that it has been checked and is good, particularly with
+/// regards to the signature and the additional signed data of its extensions.
+///
+/// The index of the sender isn't part of the extrinsic but is checked by one of its extensions, so
+/// `index` always returns `None`.
+#[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct CheckedExtensibleExtrinsic<AccountId, Index, Call, Extra> {
+	/// Who this purports to be from and the signed extensions, if anyone (note this is not a
+	/// signature).
+	pub signed: Option<(AccountId, Extra)>,
+	/// The function that should be called.
+	pub function: Call,
+	_index: PhantomData<Index>,
+}
+
+impl<AccountId, Index, Call, Extra> CheckedExtensibleExtrinsic<AccountId, Index, Call, Extra> {
+	/// New instance of a signed extrinsic.
+	pub fn new_signed(function: Call, signed: AccountId, extra: Extra) -> Self {
+		CheckedExtensibleExtrinsic {
+			signed: Some((signed, extra)),
+			function,
+			_index: PhantomData,
+		}
+	}
+
+	/// New instance of an unsigned extrinsic.
+	pub fn new_unsigned(function: Call) -> Self {
+		CheckedExtensibleExtrinsic {
+			signed: None,
+			function,
+			_index: PhantomData,
+		}
+	}
+}
+
+impl<AccountId, Index, Call, Extra> traits::Applyable
+	for CheckedExtensibleExtrinsic<AccountId, Index, Call, Extra>
+where
+	AccountId: Member + MaybeDisplay,
+	Index: Member + MaybeDisplay + SimpleArithmetic,
+	Call: Member,
+	Extra: SignedExtension<AccountId=AccountId>,
+{
+	type Index = Index;
+	type AccountId = AccountId;
+	type Call = Call;
+
+	fn index(&self) -> Option<&Self::Index> {
+		None
+	}
+
+	fn sender(&self) -> Option<&Self::AccountId> {
+		self.signed.as_ref().map(|x| &x.0)
+	}
+
+	fn deconstruct(self) -> (Self::Call, Option<Self::AccountId>) {
+		(self.function, self.signed.map(|x| x.0))
+	}
+
+	fn validate(&self, info: DispatchInfo, len: usize) -> Result<ValidTransaction, ApplyError> {
+		match self.signed {
+			Some((ref who, ref extra)) => extra.validate(who, info, len),
+			None => Extra::validate_unsigned(info, len),
+		}
+	}
+
+	fn pre_dispatch(&self, info: DispatchInfo, len: usize) -> Result<(), ApplyError> {
+		match self.signed {
+			Some((ref who, ref extra)) => {
+				extra.pre_dispatch(who, info, len)?;
+				extra.on_dispatch(who, info, len);
+				Ok(())
+			}
+			None => Extra::pre_dispatch_unsigned(info, len),
+		}
+	}
+}
+
+impl<AccountId, Index, Call, Extra> Weighable for CheckedExtensibleExtrinsic<AccountId, Index, Call, Extra>
+where
+	Call: Weighable,
+{
+	fn weight(&self, len: usize) -> Weight {
+		self.function.weight(len)
+	}
+}
+
+impl<AccountId, Index, Call, Extra> GetDispatchInfo for CheckedExtensibleExtrinsic<AccountId, Index, Call, Extra>
+where
+	Call: GetDispatchInfo,
+{
+	fn get_dispatch_info(&self, len: usize) -> DispatchInfo {
+		self.function.get_dispatch_info(len)
+	}
+}
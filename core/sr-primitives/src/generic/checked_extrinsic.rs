@@ -17,27 +17,34 @@
 //! Generic implementation of an extrinsic that has passed the verification
 //! stage.
 
-use crate::traits::{self, Member, SimpleArithmetic, MaybeDisplay};
-use crate::weights::{Weighable, Weight};
+use crate::traits::{self, Member, SimpleArithmetic, MaybeDisplay, Tippable};
+use crate::weights::{DispatchInfo, GetDispatchInfo, Weighable, Weight};
+use super::{NoTip, Tip};
 
 /// Definition of something that the external world might want to say; its
 /// existence implies that it has been checked and is good, particularly with
 /// regards to the signature.
+///
+/// `Tip` is `Tip<Balance>` for extrinsic formats that let the sender pay a tip, and `NoTip`
+/// otherwise.
 #[derive(PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "std", derive(Debug))]
-pub struct CheckedExtrinsic<AccountId, Index, Call> {
+pub struct CheckedExtrinsic<AccountId, Index, Call, Tip = NoTip> {
 	/// Who this purports to be from and the number of extrinsics have come before
 	/// from the same signer, if anyone (note this is not a signature).
 	pub signed: Option<(AccountId, Index)>,
 	/// The function that should be called.
 	pub function: Call,
+	/// The tip paid by the signer.
+	pub tip: Tip,
 }
 
-impl<AccountId, Index, Call> traits::Applyable for CheckedExtrinsic<AccountId, Index, Call>
+impl<AccountId, Index, Call, Tip> traits::Applyable for CheckedExtrinsic<AccountId, Index, Call, Tip>
 where
 	AccountId: Member + MaybeDisplay,
 	Index: Member + MaybeDisplay + SimpleArithmetic,
 	Call: Member,
+	Tip: Send + Sync,
 {
 	type Index = Index;
 	type AccountId = AccountId;
@@ -56,7 +63,7 @@ where
 	}
 }
 
-impl<AccountId, Index, Call> Weighable for CheckedExtrinsic<AccountId, Index, Call>
+impl<AccountId, Index, Call, Tip> Weighable for CheckedExtrinsic<AccountId, Index, Call, Tip>
 where
 	Call: Weighable,
 {
@@ -64,3 +71,27 @@ where
 		self.function.weight(len)
 	}
 }
+
+impl<AccountId, Index, Call, Tip> GetDispatchInfo for CheckedExtrinsic<AccountId, Index, Call, Tip>
+where
+	Call: GetDispatchInfo,
+{
+	fn get_dispatch_info(&self, len: usize) -> DispatchInfo {
+		self.function.get_dispatch_info(len)
+	}
+}
+
+impl<AccountId, Index, Call, Balance> Tippable<Balance> for CheckedExtrinsic<AccountId, Index, Call, NoTip> {
+	fn tip(&self) -> Option<Balance> {
+		None
+	}
+}
+
+impl<AccountId, Index, Call, Balance> Tippable<Balance> for CheckedExtrinsic<AccountId, Index, Call, Tip<Balance>>
+where
+	Balance: Copy,
+{
+	fn tip(&self) -> Option<Balance> {
+		self.tip.value()
+	}
+}
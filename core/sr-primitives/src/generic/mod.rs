@@ -21,8 +21,12 @@
 mod unchecked_extrinsic;
 mod unchecked_mortal_extrinsic;
 mod unchecked_mortal_compact_extrinsic;
+mod unchecked_mortal_compact_tipped_extrinsic;
+mod unchecked_extensible_extrinsic;
 mod era;
 mod checked_extrinsic;
+mod checked_extensible_extrinsic;
+mod tip;
 mod header;
 mod block;
 mod digest;
@@ -32,8 +36,12 @@ mod tests;
 pub use self::unchecked_extrinsic::UncheckedExtrinsic;
 pub use self::unchecked_mortal_extrinsic::UncheckedMortalExtrinsic;
 pub use self::unchecked_mortal_compact_extrinsic::UncheckedMortalCompactExtrinsic;
+pub use self::unchecked_mortal_compact_tipped_extrinsic::UncheckedMortalCompactTippedExtrinsic;
+pub use self::unchecked_extensible_extrinsic::UncheckedExtensibleExtrinsic;
 pub use self::era::{Era, Phase};
 pub use self::checked_extrinsic::CheckedExtrinsic;
+pub use self::checked_extensible_extrinsic::CheckedExtensibleExtrinsic;
+pub use self::tip::{Tip, NoTip};
 pub use self::header::Header;
 pub use self::block::{Block, SignedBlock, BlockId};
 pub use self::digest::{
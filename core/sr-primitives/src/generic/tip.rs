@@ -0,0 +1,51 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The tip a sender may pay along with a transaction.
+
+/// The tip of a transaction, which its sender pays in addition to the fees to get the
+/// transaction included sooner.
+///
+/// A tip is part of the signed payload, so nobody but the sender can add or change it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Tip<Balance> {
+	/// The transaction doesn't pay a tip.
+	None,
+	/// The sender pays the given tip.
+	Sender(Balance),
+}
+
+impl<Balance> Tip<Balance> {
+	/// Returns the paid tip, if any.
+	pub fn value(self) -> Option<Balance> {
+		match self {
+			Tip::None => None,
+			Tip::Sender(tip) => Some(tip),
+		}
+	}
+}
+
+impl<Balance> Default for Tip<Balance> {
+	fn default() -> Self {
+		Tip::None
+	}
+}
+
+/// The tip of a transaction format that can't carry one.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct NoTip;
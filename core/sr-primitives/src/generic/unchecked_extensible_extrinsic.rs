@@ -0,0 +1,546 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Generic implementation of an unchecked (pre-verification) extrinsic with signed extensions.
+
+#[cfg(feature = "std")]
+use std::fmt;
+
+use rstd::prelude::*;
+use runtime_io::blake2_256;
+use crate::codec::{Decode, Encode, Input, Compact};
+use crate::traits::{self, Member, SimpleArithmetic, MaybeDisplay, CurrentHeight, BlockNumberToHash,
+	Lookup, Checkable, Extrinsic, ExtrinsicMetadata, SignedExtension, LegacyExtension, SaturatedConversion};
+use super::{CheckedExtensibleExtrinsic, Era};
+
+const TRANSACTION_VERSION: u8 = 3;
+/// The versions of `UncheckedMortalCompactTippedExtrinsic`, without and with a tip.
+const LEGACY_TRANSACTION_VERSION: u8 = 1;
+const LEGACY_TIPPED_TRANSACTION_VERSION: u8 = 2;
+
+/// A extrinsic right from the external world. This is unchecked and so
+/// can contain a signature.
+///
+/// A signed extrinsic carries the data of its signed extensions, `Extra`, which is usually a
+/// tuple of `SignedExtension`s. The signature covers the data of the extensions and their
+/// additional signed data. The extrinsic itself carries no index; the `Index` of the sender is
+/// checked by an extension, like the `CheckNonce` extension of the System module.
+///
+/// Signed extrinsics in the encoding of `UncheckedMortalCompactTippedExtrinsic` are decoded as
+/// well and keep that encoding. When checked, their signature is verified against the legacy
+/// payload and their extensions are built through `LegacyExtension`.
+#[derive(PartialEq, Eq, Clone)]
+pub struct UncheckedExtensibleExtrinsic<Address, Index, Call, Signature, Extra> {
+	/// The signature, address and the signed extensions, if this is a signed extrinsic.
+	pub signature: Option<(Address, Signature, Extra)>,
+	/// The function that should be called.
+	pub function: Call,
+	legacy: Option<LegacySignature<Address, Index, Signature>>,
+}
+
+/// The signature of an extrinsic in the legacy encoding, along with the index, era and tip it
+/// covers.
+#[derive(PartialEq, Eq, Clone)]
+struct LegacySignature<Address, Index, Signature> {
+	signed: Address,
+	signature: Signature,
+	index: Compact<Index>,
+	era: Era,
+	/// The tip, if any. The legacy encoding stores it as a compact number, which decodes the same
+	/// whatever the width of the balance type.
+	tip: Option<Compact<u128>>,
+}
+
+impl<Address, Index, Call, Signature, Extra>
+	UncheckedExtensibleExtrinsic<Address, Index, Call, Signature, Extra>
+{
+	/// New instance of a signed extrinsic aka "transaction".
+	pub fn new_signed(function: Call, signed: Address, signature: Signature, extra: Extra) -> Self {
+		UncheckedExtensibleExtrinsic {
+			signature: Some((signed, signature, extra)),
+			function,
+			legacy: None,
+		}
+	}
+
+	/// New instance of an unsigned extrinsic aka "inherent".
+	pub fn new_unsigned(function: Call) -> Self {
+		UncheckedExtensibleExtrinsic {
+			signature: None,
+			function,
+			legacy: None,
+		}
+	}
+}
+
+impl<Address: Encode, Index, Call: Encode, Signature: Encode, Extra: Encode> Extrinsic
+	for UncheckedExtensibleExtrinsic<Address, Index, Call, Signature, Extra>
+{
+	fn is_signed(&self) -> Option<bool> {
+		Some(self.signature.is_some() || self.legacy.is_some())
+	}
+}
+
+impl<Address, Index, Call, Signature, Extra> ExtrinsicMetadata
+	for UncheckedExtensibleExtrinsic<Address, Index, Call, Signature, Extra>
+where
+	Extra: SignedExtension,
+{
+	const VERSION: u8 = TRANSACTION_VERSION;
+	type SignedExtensions = Extra;
+}
+
+impl<Address, AccountId, Index, Call, Signature, Extra, Context, Hash, BlockNumber> Checkable<Context>
+	for UncheckedExtensibleExtrinsic<Address, Index, Call, Signature, Extra>
+where
+	Address: Member + MaybeDisplay,
+	Index: Member + MaybeDisplay + SimpleArithmetic,
+	Compact<Index>: Encode,
+	Call: Encode + Member,
+	Signature: Member + traits::Verify<Signer=AccountId>,
+	AccountId: Member + MaybeDisplay,
+	Extra: SignedExtension<AccountId=AccountId> + LegacyExtension<Index>,
+	BlockNumber: SimpleArithmetic,
+	Hash: Encode,
+	Context: Lookup<Source=Address, Target=AccountId>
+		+ CurrentHeight<BlockNumber=BlockNumber>
+		+ BlockNumberToHash<BlockNumber=BlockNumber, Hash=Hash>,
+{
+	type Checked = CheckedExtensibleExtrinsic<AccountId, Index, Call, Extra>;
+
+	fn check(self, context: &Context) -> Result<Self::Checked, &'static str> {
+		if let Some(legacy) = self.legacy {
+			let current_u64 = context.current_height().saturated_into::<u64>();
+			let h = context.block_number_to_hash(legacy.era.birth(current_u64).saturated_into())
+				.ok_or("transaction birth block ancient")?;
+			let signed = context.lookup(legacy.signed)?;
+			let raw_payload = (legacy.index, self.function, legacy.era, h);
+			let mut payload = raw_payload.encode();
+			if let Some(ref tip) = legacy.tip {
+				tip.encode_to(&mut payload);
+			}
+			let verified = if payload.len() > 256 {
+				legacy.signature.verify(&blake2_256(&payload)[..], &signed)
+			} else {
+				legacy.signature.verify(&payload[..], &signed)
+			};
+			if !verified {
+				return Err(crate::BAD_SIGNATURE)
+			}
+			let tip = legacy.tip.map_or(0, |tip| tip.0);
+			let extra = Extra::from_legacy(&(raw_payload.0).0, legacy.era, tip)
+				.ok_or("legacy transaction format not supported")?;
+			return Ok(CheckedExtensibleExtrinsic::new_signed(raw_payload.1, signed, extra))
+		}
+
+		Ok(match self.signature {
+			Some((signed, signature, extra)) => {
+				let additional_signed = extra.additional_signed()?;
+				let signed = context.lookup(signed)?;
+				let raw_payload = (self.function, extra, additional_signed);
+				if !raw_payload.using_encoded(|payload| {
+					if payload.len() > 256 {
+						signature.verify(&blake2_256(payload)[..], &signed)
+					} else {
+						signature.verify(payload, &signed)
+					}
+				}) {
+					return Err(crate::BAD_SIGNATURE)
+				}
+				CheckedExtensibleExtrinsic::new_signed(raw_payload.0, signed, raw_payload.1)
+			}
+			None => CheckedExtensibleExtrinsic::new_unsigned(self.function),
+		})
+	}
+}
+
+impl<Address, Index, Call, Signature, Extra> Decode
+	for UncheckedExtensibleExtrinsic<Address, Index, Call, Signature, Extra>
+where
+	Address: Decode,
+	Signature: Decode,
+	Compact<Index>: Decode,
+	Call: Decode,
+	Extra: Decode,
+{
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		// This is a little more complicated than usual since the binary format must be compatible
+		// with substrate's generic `Vec<u8>` type. Basically this just means accepting that there
+		// will be a prefix of vector length (we don't need
+		// to use this).
+		let _length_do_not_remove_me_see_above: Vec<()> = Decode::decode(input)?;
+
+		let version = input.read_byte()?;
+
+		let is_signed = version & 0b1000_0000 != 0;
+		let version = version & 0b0111_1111;
+		let is_tipped = match version {
+			TRANSACTION_VERSION => {
+				return Some(UncheckedExtensibleExtrinsic {
+					signature: if is_signed { Some(Decode::decode(input)?) } else { None },
+					function: Decode::decode(input)?,
+					legacy: None,
+				})
+			}
+			// Only transactions are created by external tools, so unsigned extrinsics are
+			// accepted in the current encoding only.
+			LEGACY_TRANSACTION_VERSION if is_signed => false,
+			LEGACY_TIPPED_TRANSACTION_VERSION if is_signed => true,
+			_ => return None,
+		};
+
+		let (signed, signature, index, era) = Decode::decode(input)?;
+		let tip = if is_tipped { Some(Decode::decode(input)?) } else { None };
+
+		Some(UncheckedExtensibleExtrinsic {
+			signature: None,
+			function: Decode::decode(input)?,
+			legacy: Some(LegacySignature { signed, signature, index, era, tip }),
+		})
+	}
+}
+
+impl<Address, Index, Call, Signature, Extra> Encode
+	for UncheckedExtensibleExtrinsic<Address, Index, Call, Signature, Extra>
+where
+	Address: Encode,
+	Signature: Encode,
+	Compact<Index>: Encode,
+	Call: Encode,
+	Extra: Encode,
+{
+	fn encode(&self) -> Vec<u8> {
+		super::encode_with_vec_prefix::<Self, _>(|v| {
+			// 1 byte version id.
+			match (self.signature.as_ref(), self.legacy.as_ref()) {
+				(Some(s), _) => {
+					v.push(TRANSACTION_VERSION | 0b1000_0000);
+					s.encode_to(v);
+				}
+				(None, Some(l)) => {
+					let version = if l.tip.is_some() {
+						LEGACY_TIPPED_TRANSACTION_VERSION
+					} else {
+						LEGACY_TRANSACTION_VERSION
+					};
+					v.push(version | 0b1000_0000);
+					l.signed.encode_to(v);
+					l.signature.encode_to(v);
+					l.index.encode_to(v);
+					l.era.encode_to(v);
+					if let Some(ref tip) = l.tip {
+						tip.encode_to(v);
+					}
+				}
+				(None, None) => {
+					v.push(TRANSACTION_VERSION & 0b0111_1111);
+				}
+			}
+			self.function.encode_to(v);
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl<Address: Encode, Index, Signature: Encode, Call: Encode, Extra: Encode> serde::Serialize
+	for UncheckedExtensibleExtrinsic<Address, Index, Call, Signature, Extra>
+	where Compact<Index>: Encode
+{
+	fn serialize<S>(&self, seq: S) -> Result<S::Ok, S::Error> where S: ::serde::Serializer {
+		self.using_encoded(|bytes| seq.serialize_bytes(bytes))
+	}
+}
+
+#[cfg(feature = "std")]
+impl<Address, Index, Call, Signature, Extra> fmt::Debug
+	for UncheckedExtensibleExtrinsic<Address, Index, Call, Signature, Extra>
+where
+	Address: fmt::Debug,
+	Index: fmt::Debug,
+	Call: fmt::Debug,
+	Extra: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.legacy.as_ref() {
+			Some(l) => write!(
+				f,
+				"UncheckedExtensibleExtrinsic({:?}, {:?})",
+				(&l.signed, &l.index, &l.tip),
+				self.function,
+			),
+			None => write!(
+				f,
+				"UncheckedExtensibleExtrinsic({:?}, {:?})",
+				self.signature.as_ref().map(|x| (&x.0, &x.2)),
+				self.function,
+			),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::codec::{Encode, Decode};
+	use crate::traits::Applyable;
+	use crate::transaction_validity::ValidTransaction;
+	use crate::weights::DispatchInfo;
+	use crate::ApplyError;
+	use crate::generic::{UncheckedMortalCompactTippedExtrinsic, Tip};
+	use serde::{Serialize, Deserialize};
+
+	struct TestContext;
+	impl Lookup for TestContext {
+		type Source = u64;
+		type Target = u64;
+		fn lookup(&self, s: u64) -> Result<u64, &'static str> { Ok(s) }
+	}
+	impl CurrentHeight for TestContext {
+		type BlockNumber = u64;
+		fn current_height(&self) -> u64 { 42 }
+	}
+	impl BlockNumberToHash for TestContext {
+		type BlockNumber = u64;
+		type Hash = u64;
+		fn block_number_to_hash(&self, n: u64) -> Option<u64> { Some(n) }
+	}
+
+	#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, Encode, Decode)]
+	struct TestSig(u64, Vec<u8>);
+	impl traits::Verify for TestSig {
+		type Signer = u64;
+		fn verify<L: traits::Lazy<[u8]>>(&self, mut msg: L, signer: &Self::Signer) -> bool {
+			*signer == self.0 && msg.get() == &self.1[..]
+		}
+	}
+
+	/// An extension that signs a constant and accepts extrinsics of a sender with an odd id only.
+	#[derive(Eq, PartialEq, Clone, Debug, Encode, Decode)]
+	struct OddSender(u8);
+	impl SignedExtension for OddSender {
+		type AccountId = u64;
+		type AdditionalSigned = u32;
+		fn additional_signed(&self) -> Result<u32, &'static str> { Ok(42) }
+		fn validate(&self, who: &u64, _: DispatchInfo, _: usize) -> Result<ValidTransaction, ApplyError> {
+			if who % 2 == 1 {
+				Ok(ValidTransaction { priority: self.0 as u64, ..Default::default() })
+			} else {
+				Err(ApplyError::Rejected)
+			}
+		}
+		fn identifier() -> Vec<&'static str> { vec!["OddSender"] }
+	}
+	impl LegacyExtension<u64> for OddSender {}
+
+	/// An extension whose additional signed data can't be constructed.
+	#[derive(Eq, PartialEq, Clone, Debug, Encode, Decode)]
+	struct Unavailable;
+	impl SignedExtension for Unavailable {
+		type AccountId = u64;
+		type AdditionalSigned = ();
+		fn additional_signed(&self) -> Result<(), &'static str> { Err("unavailable") }
+		fn identifier() -> Vec<&'static str> { vec!["Unavailable"] }
+	}
+	impl LegacyExtension<u64> for Unavailable {}
+
+	/// An extension that takes the index and the tip of legacy extrinsics.
+	#[derive(Eq, PartialEq, Clone, Debug, Encode, Decode)]
+	struct IndexAndTip(u64, u128);
+	impl SignedExtension for IndexAndTip {
+		type AccountId = u64;
+		type AdditionalSigned = ();
+		fn additional_signed(&self) -> Result<(), &'static str> { Ok(()) }
+		fn identifier() -> Vec<&'static str> { vec!["IndexAndTip"] }
+	}
+	impl LegacyExtension<u64> for IndexAndTip {
+		fn from_legacy(index: &u64, _: Era, tip: u128) -> Option<Self> {
+			Some(IndexAndTip(*index, tip))
+		}
+	}
+
+	type Extra = (OddSender, OddSender);
+	type Ex = UncheckedExtensibleExtrinsic<u64, u64, Vec<u8>, TestSig, Extra>;
+	type CEx = CheckedExtensibleExtrinsic<u64, u64, Vec<u8>, Extra>;
+	type LegacyEx = UncheckedMortalCompactTippedExtrinsic<u64, u64, Vec<u8>, TestSig, u128>;
+	type CompatEx = UncheckedExtensibleExtrinsic<u64, u64, Vec<u8>, TestSig, IndexAndTip>;
+
+	fn payload(extra: &Extra) -> Vec<u8> {
+		(vec![0u8;0], extra.clone(), (42u32, 42u32)).encode()
+	}
+
+	fn signed(who: u64, extra: Extra) -> Ex {
+		Ex::new_signed(vec![0u8;0], who, TestSig(who, payload(&extra)), extra)
+	}
+
+	fn legacy_payload(era: Era, h: u64, tip: Option<u128>) -> Vec<u8> {
+		let mut payload = (Compact(3u64), vec![0u8;0], era, h).encode();
+		if let Some(tip) = tip {
+			Compact(tip).encode_to(&mut payload);
+		}
+		payload
+	}
+
+	fn legacy(era: Era, h: u64, tip: Option<u128>, signed_tip: Option<u128>) -> Vec<u8> {
+		let sig = TestSig(1, legacy_payload(era, h, signed_tip));
+		let ux = match tip {
+			Some(tip) => LegacyEx::new_signed_with_tip(3, vec![0u8;0], 1, sig, era, Tip::Sender(tip)),
+			None => LegacyEx::new_signed(3, vec![0u8;0], 1, sig, era),
+		};
+		ux.encode()
+	}
+
+	#[test]
+	fn unsigned_codec_should_work() {
+		let ux = Ex::new_unsigned(vec![0u8;0]);
+		let encoded = ux.encode();
+		assert_eq!(Ex::decode(&mut &encoded[..]), Some(ux));
+	}
+
+	#[test]
+	fn signed_codec_should_work() {
+		let ux = signed(1, (OddSender(1), OddSender(2)));
+		let encoded = ux.encode();
+		assert_eq!(Ex::decode(&mut &encoded[..]), Some(ux));
+	}
+
+	#[test]
+	fn unsigned_check_should_work() {
+		let ux = Ex::new_unsigned(vec![0u8;0]);
+		assert!(!ux.is_signed().unwrap_or(false));
+		assert!(<Ex as Checkable<TestContext>>::check(ux, &TestContext).is_ok());
+	}
+
+	#[test]
+	fn signed_check_should_work() {
+		let extra = (OddSender(1), OddSender(2));
+		let ux = signed(1, extra.clone());
+		assert!(ux.is_signed().unwrap_or(false));
+		assert_eq!(
+			<Ex as Checkable<TestContext>>::check(ux, &TestContext),
+			Ok(CEx::new_signed(vec![0u8;0], 1, extra)),
+		);
+	}
+
+	#[test]
+	fn extensions_must_be_signed() {
+		// The signature doesn't cover the additional signed data.
+		let extra = (OddSender(1), OddSender(2));
+		let sig = TestSig(1, (vec![0u8;0], extra.clone()).encode());
+		let ux = Ex::new_signed(vec![0u8;0], 1, sig, extra.clone());
+		assert_eq!(<Ex as Checkable<TestContext>>::check(ux, &TestContext), Err(crate::BAD_SIGNATURE));
+
+		// The signature covers different extension data.
+		let sig = TestSig(1, payload(&extra));
+		let ux = Ex::new_signed(vec![0u8;0], 1, sig, (OddSender(1), OddSender(3)));
+		assert_eq!(<Ex as Checkable<TestContext>>::check(ux, &TestContext), Err(crate::BAD_SIGNATURE));
+	}
+
+	#[test]
+	fn unavailable_additional_signed_fails_check() {
+		type UnavailableEx = UncheckedExtensibleExtrinsic<u64, u64, Vec<u8>, TestSig, (OddSender, Unavailable)>;
+		let ux = UnavailableEx::new_signed(vec![0u8;0], 1, TestSig(1, vec![]), (OddSender(1), Unavailable));
+		assert_eq!(<UnavailableEx as Checkable<TestContext>>::check(ux, &TestContext), Err("unavailable"));
+	}
+
+	#[test]
+	fn extensions_are_applied_in_order() {
+		let info = DispatchInfo::default();
+		let xt = <Ex as Checkable<TestContext>>::check(signed(1, (OddSender(1), OddSender(2))), &TestContext)
+			.unwrap();
+		assert_eq!(xt.validate(info, 0), Ok(ValidTransaction { priority: 3, ..Default::default() }));
+		assert_eq!(xt.pre_dispatch(info, 0), Ok(()));
+
+		let xt = <Ex as Checkable<TestContext>>::check(signed(2, (OddSender(1), OddSender(2))), &TestContext)
+			.unwrap();
+		assert_eq!(xt.validate(info, 0), Err(ApplyError::Rejected));
+		assert_eq!(xt.pre_dispatch(info, 0), Err(ApplyError::Rejected));
+
+		let xt = <Ex as Checkable<TestContext>>::check(Ex::new_unsigned(vec![0u8;0]), &TestContext).unwrap();
+		assert_eq!(xt.validate(info, 0), Ok(Default::default()));
+		assert_eq!(xt.pre_dispatch(info, 0), Ok(()));
+	}
+
+	#[test]
+	fn metadata_lists_extensions() {
+		assert_eq!(<Ex as ExtrinsicMetadata>::VERSION, 3);
+		assert_eq!(
+			<<Ex as ExtrinsicMetadata>::SignedExtensions as SignedExtension>::identifier(),
+			vec!["OddSender", "OddSender"],
+		);
+	}
+
+	#[test]
+	fn legacy_extrinsics_keep_their_encoding() {
+		for encoded in vec![legacy(Era::immortal(), 0, None, None), legacy(Era::immortal(), 0, Some(5), Some(5))] {
+			let ux = CompatEx::decode(&mut &encoded[..]).unwrap();
+			assert!(ux.is_signed().unwrap_or(false));
+			assert_eq!(ux.encode(), encoded);
+		}
+
+		// Unsigned extrinsics are only accepted in the current encoding.
+		let encoded = LegacyEx::new_unsigned(vec![0u8;0]).encode();
+		assert_eq!(CompatEx::decode(&mut &encoded[..]), None);
+	}
+
+	#[test]
+	fn legacy_signed_check_builds_the_extensions() {
+		let encoded = legacy(Era::mortal(32, 42), 42, Some(1_000), Some(1_000));
+		let ux = CompatEx::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(
+			<CompatEx as Checkable<TestContext>>::check(ux, &TestContext),
+			Ok(CheckedExtensibleExtrinsic::new_signed(vec![0u8;0], 1, IndexAndTip(3, 1_000))),
+		);
+
+		let encoded = legacy(Era::immortal(), 0, None, None);
+		let ux = CompatEx::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(
+			<CompatEx as Checkable<TestContext>>::check(ux, &TestContext),
+			Ok(CheckedExtensibleExtrinsic::new_signed(vec![0u8;0], 1, IndexAndTip(3, 0))),
+		);
+	}
+
+	#[test]
+	fn legacy_tip_must_be_signed() {
+		let encoded = legacy(Era::immortal(), 0, Some(1_000), None);
+		let ux = CompatEx::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(<CompatEx as Checkable<TestContext>>::check(ux, &TestContext), Err(crate::BAD_SIGNATURE));
+
+		let encoded = legacy(Era::immortal(), 0, Some(1_000), Some(1));
+		let ux = CompatEx::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(<CompatEx as Checkable<TestContext>>::check(ux, &TestContext), Err(crate::BAD_SIGNATURE));
+	}
+
+	#[test]
+	fn legacy_extrinsics_need_legacy_extensions() {
+		let encoded = legacy(Era::immortal(), 0, None, None);
+		let ux = Ex::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(
+			<Ex as Checkable<TestContext>>::check(ux, &TestContext),
+			Err("legacy transaction format not supported"),
+		);
+	}
+
+	#[test]
+	fn encoding_matches_vec() {
+		let ex = signed(1, (OddSender(1), OddSender(2)));
+		let encoded = ex.encode();
+		let decoded = Ex::decode(&mut encoded.as_slice()).unwrap();
+		assert_eq!(decoded, ex);
+		let as_vec: Vec<u8> = Decode::decode(&mut encoded.as_slice()).unwrap();
+		assert_eq!(as_vec.encode(), encoded);
+	}
+}
@@ -0,0 +1,439 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Generic implementation of an unchecked (pre-verification) extrinsic that may carry a tip.
+
+#[cfg(feature = "std")]
+use std::fmt;
+
+use rstd::prelude::*;
+use runtime_io::blake2_256;
+use crate::codec::{Decode, Encode, Input, Compact};
+use crate::traits::{self, Member, SimpleArithmetic, MaybeDisplay, CurrentHeight, BlockNumberToHash,
+	Lookup, Checkable, Extrinsic, ExtrinsicMetadata, SaturatedConversion};
+use super::{CheckedExtrinsic, Era, Tip};
+
+/// The version of extrinsics without a tip. Equal to the version of
+/// `UncheckedMortalCompactExtrinsic`, whose encoding is shared.
+const TRANSACTION_VERSION: u8 = 1;
+/// The version of signed extrinsics that carry a tip.
+const TIPPED_TRANSACTION_VERSION: u8 = 2;
+
+/// A extrinsic right from the external world. This is unchecked and so
+/// can contain a signature and a tip.
+///
+/// An extrinsic without a tip is encoded and signed exactly like an
+/// `UncheckedMortalCompactExtrinsic`, so that a chain can switch to this type without breaking
+/// the extrinsics created by existing tools. A tip, if any, is encoded as a compact number after
+/// the signature and appended to the signed payload.
+#[derive(PartialEq, Eq, Clone)]
+pub struct UncheckedMortalCompactTippedExtrinsic<Address, Index, Call, Signature, Balance> {
+	/// The signature, address, number of extrinsics have come before from
+	/// the same signer and an era describing the longevity of this transaction,
+	/// if this is a signed extrinsic.
+	pub signature: Option<(Address, Signature, Compact<Index>, Era)>,
+	/// The function that should be called.
+	pub function: Call,
+	/// The tip paid by the signer. Always `Tip::None` for unsigned extrinsics.
+	pub tip: Tip<Balance>,
+}
+
+impl<Address, Index, Call, Signature, Balance>
+	UncheckedMortalCompactTippedExtrinsic<Address, Index, Call, Signature, Balance>
+{
+	/// New instance of a signed extrinsic aka "transaction" without a tip.
+	pub fn new_signed(index: Index, function: Call, signed: Address, signature: Signature, era: Era) -> Self {
+		Self::new_signed_with_tip(index, function, signed, signature, era, Tip::None)
+	}
+
+	/// New instance of a signed extrinsic aka "transaction" that pays the given tip.
+	pub fn new_signed_with_tip(
+		index: Index,
+		function: Call,
+		signed: Address,
+		signature: Signature,
+		era: Era,
+		tip: Tip<Balance>,
+	) -> Self {
+		UncheckedMortalCompactTippedExtrinsic {
+			signature: Some((signed, signature, index.into(), era)),
+			function,
+			tip,
+		}
+	}
+
+	/// New instance of an unsigned extrinsic aka "inherent".
+	pub fn new_unsigned(function: Call) -> Self {
+		UncheckedMortalCompactTippedExtrinsic {
+			signature: None,
+			function,
+			tip: Tip::None,
+		}
+	}
+}
+
+impl<Address: Encode, Index: Encode, Call: Encode, Signature: Encode, Balance> Extrinsic
+	for UncheckedMortalCompactTippedExtrinsic<Address, Index, Call, Signature, Balance>
+{
+	fn is_signed(&self) -> Option<bool> {
+		Some(self.signature.is_some())
+	}
+}
+
+impl<Address, Index, Call, Signature, Balance> ExtrinsicMetadata
+	for UncheckedMortalCompactTippedExtrinsic<Address, Index, Call, Signature, Balance>
+{
+	// The latest version; extrinsics without a tip keep the version of the legacy format.
+	const VERSION: u8 = TIPPED_TRANSACTION_VERSION;
+	type SignedExtensions = ();
+}
+
+impl<Address, AccountId, Index, Call, Signature, Balance, Context, Hash, BlockNumber> Checkable<Context>
+	for UncheckedMortalCompactTippedExtrinsic<Address, Index, Call, Signature, Balance>
+where
+	Address: Member + MaybeDisplay,
+	Index: Member + MaybeDisplay + SimpleArithmetic,
+	Compact<Index>: Encode,
+	Call: Encode + Member,
+	Signature: Member + traits::Verify<Signer=AccountId>,
+	AccountId: Member + MaybeDisplay,
+	Balance: Member,
+	Compact<Balance>: Encode,
+	BlockNumber: SimpleArithmetic,
+	Hash: Encode,
+	Context: Lookup<Source=Address, Target=AccountId>
+		+ CurrentHeight<BlockNumber=BlockNumber>
+		+ BlockNumberToHash<BlockNumber=BlockNumber, Hash=Hash>,
+{
+	type Checked = CheckedExtrinsic<AccountId, Index, Call, Tip<Balance>>;
+
+	fn check(self, context: &Context) -> Result<Self::Checked, &'static str> {
+		Ok(match self.signature {
+			Some((signed, signature, index, era)) => {
+				let current_u64 = context.current_height().saturated_into::<u64>();
+				let h = context.block_number_to_hash(era.birth(current_u64).saturated_into())
+					.ok_or("transaction birth block ancient")?;
+				let signed = context.lookup(signed)?;
+				let raw_payload = (index, self.function, era, h);
+				// Encoding a tuple concatenates the encoded items, so this is the encoding of the
+				// payload with the tip as an additional, last item.
+				let mut payload = raw_payload.encode();
+				if let Tip::Sender(ref tip) = self.tip {
+					Compact(tip.clone()).encode_to(&mut payload);
+				}
+				let verified = if payload.len() > 256 {
+					signature.verify(&blake2_256(&payload)[..], &signed)
+				} else {
+					signature.verify(&payload[..], &signed)
+				};
+				if !verified {
+					return Err(crate::BAD_SIGNATURE)
+				}
+				CheckedExtrinsic {
+					signed: Some((signed, (raw_payload.0).0)),
+					function: raw_payload.1,
+					tip: self.tip,
+				}
+			}
+			None => CheckedExtrinsic {
+				signed: None,
+				function: self.function,
+				tip: Tip::None,
+			},
+		})
+	}
+}
+
+impl<Address, Index, Call, Signature, Balance> Decode
+	for UncheckedMortalCompactTippedExtrinsic<Address, Index, Call, Signature, Balance>
+where
+	Address: Decode,
+	Signature: Decode,
+	Compact<Index>: Decode,
+	Compact<Balance>: Decode,
+	Call: Decode,
+{
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		// This is a little more complicated than usual since the binary format must be compatible
+		// with substrate's generic `Vec<u8>` type. Basically this just means accepting that there
+		// will be a prefix of vector length (we don't need
+		// to use this).
+		let _length_do_not_remove_me_see_above: Vec<()> = Decode::decode(input)?;
+
+		let version = input.read_byte()?;
+
+		let is_signed = version & 0b1000_0000 != 0;
+		let version = version & 0b0111_1111;
+		let is_tipped = match version {
+			TRANSACTION_VERSION => false,
+			// Only signed extrinsics can pay a tip.
+			TIPPED_TRANSACTION_VERSION if is_signed => true,
+			_ => return None,
+		};
+
+		let signature = if is_signed { Some(Decode::decode(input)?) } else { None };
+		let tip = if is_tipped {
+			Tip::Sender(<Compact<Balance>>::decode(input)?.0)
+		} else {
+			Tip::None
+		};
+
+		Some(UncheckedMortalCompactTippedExtrinsic {
+			signature,
+			function: Decode::decode(input)?,
+			tip,
+		})
+	}
+}
+
+impl<Address, Index, Call, Signature, Balance> Encode
+	for UncheckedMortalCompactTippedExtrinsic<Address, Index, Call, Signature, Balance>
+where
+	Address: Encode,
+	Signature: Encode,
+	Compact<Index>: Encode,
+	Balance: Clone,
+	Compact<Balance>: Encode,
+	Call: Encode,
+{
+	fn encode(&self) -> Vec<u8> {
+		super::encode_with_vec_prefix::<Self, _>(|v| {
+			// 1 byte version id, followed by the signature and the tip, if any.
+			match (self.signature.as_ref(), &self.tip) {
+				(Some(s), Tip::Sender(tip)) => {
+					v.push(TIPPED_TRANSACTION_VERSION | 0b1000_0000);
+					s.encode_to(v);
+					Compact(tip.clone()).encode_to(v);
+				}
+				(Some(s), Tip::None) => {
+					v.push(TRANSACTION_VERSION | 0b1000_0000);
+					s.encode_to(v);
+				}
+				(None, _) => {
+					v.push(TRANSACTION_VERSION & 0b0111_1111);
+				}
+			}
+			self.function.encode_to(v);
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl<Address: Encode, Index, Signature: Encode, Call: Encode, Balance: Clone> serde::Serialize
+	for UncheckedMortalCompactTippedExtrinsic<Address, Index, Call, Signature, Balance>
+	where Compact<Index>: Encode, Compact<Balance>: Encode
+{
+	fn serialize<S>(&self, seq: S) -> Result<S::Ok, S::Error> where S: ::serde::Serializer {
+		self.using_encoded(|bytes| seq.serialize_bytes(bytes))
+	}
+}
+
+#[cfg(feature = "std")]
+impl<Address, Index, Call, Signature, Balance> fmt::Debug
+	for UncheckedMortalCompactTippedExtrinsic<Address, Index, Call, Signature, Balance>
+where
+	Address: fmt::Debug,
+	Index: fmt::Debug,
+	Call: fmt::Debug,
+	Balance: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"UncheckedMortalCompactTippedExtrinsic({:?}, {:?}, {:?})",
+			self.signature.as_ref().map(|x| (&x.0, &x.2)),
+			self.function,
+			self.tip,
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::codec::{Encode, Decode};
+	use crate::generic::UncheckedMortalCompactExtrinsic;
+	use serde::{Serialize, Deserialize};
+
+	struct TestContext;
+	impl Lookup for TestContext {
+		type Source = u64;
+		type Target = u64;
+		fn lookup(&self, s: u64) -> Result<u64, &'static str> { Ok(s) }
+	}
+	impl CurrentHeight for TestContext {
+		type BlockNumber = u64;
+		fn current_height(&self) -> u64 { 42 }
+	}
+	impl BlockNumberToHash for TestContext {
+		type BlockNumber = u64;
+		type Hash = u64;
+		fn block_number_to_hash(&self, n: u64) -> Option<u64> { Some(n) }
+	}
+
+	#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, Encode, Decode)]
+	struct TestSig(u64, Vec<u8>);
+	impl traits::Verify for TestSig {
+		type Signer = u64;
+		fn verify<L: traits::Lazy<[u8]>>(&self, mut msg: L, signer: &Self::Signer) -> bool {
+			*signer == self.0 && msg.get() == &self.1[..]
+		}
+	}
+
+	const DUMMY_ACCOUNTID: u64 = 0;
+
+	type Ex = UncheckedMortalCompactTippedExtrinsic<u64, u64, Vec<u8>, TestSig, u64>;
+	type LegacyEx = UncheckedMortalCompactExtrinsic<u64, u64, Vec<u8>, TestSig>;
+	type CEx = CheckedExtrinsic<u64, u64, Vec<u8>, Tip<u64>>;
+
+	fn payload(era: Era, h: u64) -> Vec<u8> {
+		(Compact::from(0u64), vec![0u8;0], era, h).encode()
+	}
+
+	fn tipped_payload(era: Era, h: u64, tip: u64) -> Vec<u8> {
+		(Compact::from(0u64), vec![0u8;0], era, h, Compact::from(tip)).encode()
+	}
+
+	#[test]
+	fn unsigned_codec_should_work() {
+		let ux = Ex::new_unsigned(vec![0u8;0]);
+		let encoded = ux.encode();
+		assert_eq!(Ex::decode(&mut &encoded[..]), Some(ux));
+	}
+
+	#[test]
+	fn tipped_codec_should_work() {
+		let ux = Ex::new_signed_with_tip(
+			0,
+			vec![0u8;0],
+			DUMMY_ACCOUNTID,
+			TestSig(DUMMY_ACCOUNTID, tipped_payload(Era::immortal(), 0, 1_000)),
+			Era::immortal(),
+			Tip::Sender(1_000),
+		);
+		let encoded = ux.encode();
+		assert_eq!(Ex::decode(&mut &encoded[..]), Some(ux));
+	}
+
+	#[test]
+	fn encoding_without_tip_matches_legacy_extrinsic() {
+		let sig = TestSig(DUMMY_ACCOUNTID, payload(Era::immortal(), 0));
+		let legacy = LegacyEx::new_signed(0, vec![0u8;0], DUMMY_ACCOUNTID, sig.clone(), Era::immortal());
+		let ux = Ex::new_signed(0, vec![0u8;0], DUMMY_ACCOUNTID, sig, Era::immortal());
+
+		assert_eq!(ux.encode(), legacy.encode());
+		assert_eq!(Ex::decode(&mut &legacy.encode()[..]), Some(ux));
+		assert_eq!(
+			Ex::new_unsigned(vec![0u8;0]).encode(),
+			LegacyEx::new_unsigned(vec![0u8;0]).encode(),
+		);
+	}
+
+	#[test]
+	fn tipped_extrinsic_is_not_a_legacy_extrinsic() {
+		let ux = Ex::new_signed_with_tip(
+			0,
+			vec![0u8;0],
+			DUMMY_ACCOUNTID,
+			TestSig(DUMMY_ACCOUNTID, tipped_payload(Era::immortal(), 0, 1_000)),
+			Era::immortal(),
+			Tip::Sender(1_000),
+		);
+		assert_eq!(LegacyEx::decode(&mut &ux.encode()[..]), None);
+	}
+
+	#[test]
+	fn unsigned_tipped_extrinsic_should_not_decode() {
+		let mut encoded = Ex::new_unsigned(vec![0u8;0]).encode();
+		// Replace the version byte, which follows the one-byte length prefix.
+		encoded[1] = TIPPED_TRANSACTION_VERSION;
+		assert_eq!(Ex::decode(&mut &encoded[..]), None);
+	}
+
+	#[test]
+	fn legacy_signed_check_should_work() {
+		let ux = Ex::new_signed(
+			0,
+			vec![0u8;0],
+			DUMMY_ACCOUNTID,
+			TestSig(DUMMY_ACCOUNTID, payload(Era::mortal(32, 42), 42)),
+			Era::mortal(32, 42),
+		);
+		assert_eq!(
+			<Ex as Checkable<TestContext>>::check(ux, &TestContext),
+			Ok(CEx { signed: Some((DUMMY_ACCOUNTID, 0)), function: vec![0u8;0], tip: Tip::None }),
+		);
+	}
+
+	#[test]
+	fn tipped_signed_check_should_work() {
+		let ux = Ex::new_signed_with_tip(
+			0,
+			vec![0u8;0],
+			DUMMY_ACCOUNTID,
+			TestSig(DUMMY_ACCOUNTID, tipped_payload(Era::mortal(32, 42), 42, 1_000)),
+			Era::mortal(32, 42),
+			Tip::Sender(1_000),
+		);
+		assert_eq!(
+			<Ex as Checkable<TestContext>>::check(ux, &TestContext),
+			Ok(CEx { signed: Some((DUMMY_ACCOUNTID, 0)), function: vec![0u8;0], tip: Tip::Sender(1_000) }),
+		);
+	}
+
+	#[test]
+	fn tip_must_be_signed() {
+		// The signature doesn't cover the tip.
+		let ux = Ex::new_signed_with_tip(
+			0,
+			vec![0u8;0],
+			DUMMY_ACCOUNTID,
+			TestSig(DUMMY_ACCOUNTID, payload(Era::immortal(), 0)),
+			Era::immortal(),
+			Tip::Sender(1_000),
+		);
+		assert_eq!(<Ex as Checkable<TestContext>>::check(ux, &TestContext), Err(crate::BAD_SIGNATURE));
+
+		// The signature covers a different tip.
+		let ux = Ex::new_signed_with_tip(
+			0,
+			vec![0u8;0],
+			DUMMY_ACCOUNTID,
+			TestSig(DUMMY_ACCOUNTID, tipped_payload(Era::immortal(), 0, 1_000)),
+			Era::immortal(),
+			Tip::Sender(1),
+		);
+		assert_eq!(<Ex as Checkable<TestContext>>::check(ux, &TestContext), Err(crate::BAD_SIGNATURE));
+	}
+
+	#[test]
+	fn encoding_matches_vec() {
+		let ex = Ex::new_signed_with_tip(
+			0,
+			vec![0u8;0],
+			DUMMY_ACCOUNTID,
+			TestSig(DUMMY_ACCOUNTID, tipped_payload(Era::immortal(), 0, 1_000)),
+			Era::immortal(),
+			Tip::Sender(1_000),
+		);
+		let encoded = ex.encode();
+		let decoded = Ex::decode(&mut encoded.as_slice()).unwrap();
+		assert_eq!(decoded, ex);
+		let as_vec: Vec<u8> = Decode::decode(&mut encoded.as_slice()).unwrap();
+		assert_eq!(as_vec.encode(), encoded);
+	}
+}
@@ -270,7 +270,7 @@ impl From<codec::Compact<Permill>> for Permill {
 /// Perbill is parts-per-billion. It stores a value between 0 and 1 in fixed point and
 /// provides a means to multiply some other value by that.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
-#[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq)]
+#[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Perbill(u32);
 
 impl Perbill {
@@ -422,6 +422,72 @@ impl From<codec::Compact<PerU128>> for PerU128 {
 	}
 }
 
+/// Fixed point number with a billion as denominator.
+///
+/// Unlike the per-things, it is signed and can hold values above one, which makes it suitable
+/// as a relative adjustment of some other value, e.g. of transaction fees.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Fixed64(i64);
+
+/// The denominator of `Fixed64`.
+const DIV: i64 = 1_000_000_000;
+
+impl Fixed64 {
+	/// Creates a new instance equal to the integer `int`.
+	pub fn from_natural(int: i64) -> Self { Self(int.saturating_mul(DIV)) }
+
+	/// Returns the accuracy of the type, i.e. the number of parts that make up one.
+	pub fn accuracy() -> i64 { DIV }
+
+	/// From an explicitly defined number of parts of a billion.
+	pub fn from_parts(parts: i64) -> Self { Self(parts) }
+
+	/// Approximates the fraction `n/d`. A zero denominator is treated as one.
+	pub fn from_rational(n: i64, d: u64) -> Self {
+		let d = d.max(1) as i128;
+		Self((n as i128 * DIV as i128 / d).max(i64::min_value() as i128).min(i64::max_value() as i128) as i64)
+	}
+
+	/// The number of parts of a billion that make up this number.
+	pub fn into_inner(self) -> i64 { self.0 }
+
+	/// Saturating addition.
+	pub fn saturating_add(self, rhs: Self) -> Self { Self(self.0.saturating_add(rhs.0)) }
+
+	/// Saturating subtraction.
+	pub fn saturating_sub(self, rhs: Self) -> Self { Self(self.0.saturating_sub(rhs.0)) }
+
+	/// Saturating multiplication.
+	pub fn saturating_mul(self, rhs: Self) -> Self {
+		let product = self.0 as i128 * rhs.0 as i128 / DIV as i128;
+		Self(product.max(i64::min_value() as i128).min(i64::max_value() as i128) as i64)
+	}
+
+	/// Returns `int + int * self`, i.e. `int` adjusted by `self`.
+	///
+	/// Saturates at the bounds of `N`; in particular the result is zero if `self` is below minus
+	/// one.
+	pub fn saturated_multiply_accumulate<N: traits::SimpleArithmetic>(self, int: N) -> N {
+		let int = int.saturated_into::<u128>();
+		let parts = (self.0 as i128).abs() as u128;
+		let div = DIV as u128;
+
+		// `int * natural` may overflow, the rest can't: `int / div * fraction` is smaller than
+		// `int` and `int % div * fraction` is smaller than `div * div`.
+		let (natural, fraction) = (parts / div, parts % div);
+		let excess = int.saturating_mul(natural)
+			.saturating_add(int / div * fraction + int % div * fraction / div);
+
+		let adjusted = if self.0 >= 0 {
+			int.saturating_add(excess)
+		} else {
+			int.saturating_sub(excess)
+		};
+		N::saturated_from(adjusted)
+	}
+}
+
 /// Signature verify that can work with any known signature types..
 #[derive(Eq, PartialEq, Clone, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Debug))]
@@ -571,6 +637,8 @@ pub enum ApplyError {
 	Future = 2,
 	/// Sending account had too low a balance.
 	CantPay = 3,
+	/// A signed extension of the extrinsic rejected it.
+	Rejected = 4,
 	/// Block is full, no more extrinsics can be applied.
 	FullBlock = 255,
 }
@@ -864,4 +932,43 @@ mod tests {
 			((Into::<U256>::into(std::u128::MAX) * 999_999u32) / 1_000_000u32).as_u128()
 		);
 	}
+
+	#[test]
+	fn fixed64_semantics() {
+		use super::Fixed64;
+
+		assert_eq!(Fixed64::from_rational(5, 2).into_inner(), 5 * 1_000_000_000 / 2);
+		assert_eq!(Fixed64::from_rational(5, 2), Fixed64::from_rational(10, 4));
+		assert_eq!(Fixed64::from_rational(-1, 4), Fixed64::from_parts(-250_000_000));
+		assert_eq!(Fixed64::from_rational(7, 0), Fixed64::from_natural(7));
+
+		assert_eq!(
+			Fixed64::from_rational(1, 2).saturating_mul(Fixed64::from_rational(1, 2)),
+			Fixed64::from_rational(1, 4),
+		);
+		assert_eq!(
+			Fixed64::from_natural(i64::max_value()).saturating_mul(Fixed64::from_natural(2)),
+			Fixed64::from_parts(i64::max_value()),
+		);
+	}
+
+	#[test]
+	fn fixed64_multiply_accumulate() {
+		use super::Fixed64;
+
+		// zero leaves the value untouched.
+		assert_eq!(Fixed64::default().saturated_multiply_accumulate(1_000u64), 1_000);
+		// a positive number increases it.
+		assert_eq!(Fixed64::from_rational(1, 2).saturated_multiply_accumulate(1_000u64), 1_500);
+		assert_eq!(Fixed64::from_natural(2).saturated_multiply_accumulate(1_000u64), 3_000);
+		// a negative number decreases it, down to zero.
+		assert_eq!(Fixed64::from_rational(-1, 4).saturated_multiply_accumulate(1_000u64), 750);
+		assert_eq!(Fixed64::from_natural(-2).saturated_multiply_accumulate(1_000u64), 0);
+		// it saturates at the bounds of the output type.
+		assert_eq!(Fixed64::from_natural(1).saturated_multiply_accumulate(u32::max_value()), u32::max_value());
+		assert_eq!(
+			Fixed64::from_rational(1, 1_000_000_000).saturated_multiply_accumulate(u128::max_value() / 2),
+			u128::max_value() / 2 + u128::max_value() / 2 / 1_000_000_000,
+		);
+	}
 }
@@ -19,9 +19,11 @@
 use serde::{Serialize, Serializer, Deserialize, de::Error as DeError, Deserializer};
 use std::{fmt::Debug, ops::Deref, fmt};
 use crate::codec::{Codec, Encode, Decode};
-use crate::traits::{self, Checkable, Applyable, BlakeTwo256, OpaqueKeys};
+use crate::traits::{self, Checkable, Applyable, BlakeTwo256, OpaqueKeys, SignedExtension};
 use crate::generic;
-use crate::weights::{Weighable, Weight};
+use crate::weights::{DispatchInfo, GetDispatchInfo, Weighable, Weight};
+use crate::transaction_validity::ValidTransaction;
+use crate::ApplyError;
 pub use substrate_primitives::H256;
 use substrate_primitives::U256;
 use substrate_primitives::ed25519::{Public as AuthorityId};
@@ -178,50 +180,74 @@ impl<'a, Xt> Deserialize<'a> for Block<Xt> where Block<Xt>: Decode {
 	}
 }
 
-/// Test transaction, tuple of (sender, index, call)
-/// with index only used if sender is some.
+/// Test transaction, tuple of (sender and signed extensions, call).
 ///
 /// If sender is some then the transaction is signed otherwise it is unsigned.
 #[derive(PartialEq, Eq, Clone, Encode, Decode)]
-pub struct TestXt<Call>(pub Option<u64>, pub u64, pub Call);
+pub struct TestXt<Call, Extra>(pub Option<(u64, Extra)>, pub Call);
 
-impl<Call> Serialize for TestXt<Call> where TestXt<Call>: Encode
+impl<Call, Extra> Serialize for TestXt<Call, Extra> where TestXt<Call, Extra>: Encode
 {
 	fn serialize<S>(&self, seq: S) -> Result<S::Ok, S::Error> where S: Serializer {
 		self.using_encoded(|bytes| seq.serialize_bytes(bytes))
 	}
 }
 
-impl<Call> Debug for TestXt<Call> {
+impl<Call, Extra> Debug for TestXt<Call, Extra> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "TestXt({:?}, {:?})", self.0, self.1)
+		write!(f, "TestXt({:?}, ...)", self.0.as_ref().map(|x| &x.0))
 	}
 }
 
-impl<Call: Codec + Sync + Send, Context> Checkable<Context> for TestXt<Call> {
+impl<Call: Codec + Sync + Send, Extra, Context> Checkable<Context> for TestXt<Call, Extra> {
 	type Checked = Self;
 	fn check(self, _: &Context) -> Result<Self::Checked, &'static str> { Ok(self) }
 }
-impl<Call: Codec + Sync + Send> traits::Extrinsic for TestXt<Call> {
+impl<Call: Codec + Sync + Send, Extra> traits::Extrinsic for TestXt<Call, Extra> {
 	fn is_signed(&self) -> Option<bool> {
 		Some(self.0.is_some())
 	}
 }
-impl<Call> Applyable for TestXt<Call> where
+impl<Call, Extra> Applyable for TestXt<Call, Extra> where
 	Call: 'static + Sized + Send + Sync + Clone + Eq + Codec + Debug,
+	Extra: SignedExtension<AccountId=u64>,
 {
 	type AccountId = u64;
 	type Index = u64;
 	type Call = Call;
-	fn sender(&self) -> Option<&u64> { self.0.as_ref() }
-	fn index(&self) -> Option<&u64> { self.0.as_ref().map(|_| &self.1) }
+	fn sender(&self) -> Option<&u64> { self.0.as_ref().map(|x| &x.0) }
+	fn index(&self) -> Option<&u64> { None }
 	fn deconstruct(self) -> (Self::Call, Option<Self::AccountId>) {
-		(self.2, self.0)
+		(self.1, self.0.map(|x| x.0))
+	}
+
+	fn validate(&self, info: DispatchInfo, len: usize) -> Result<ValidTransaction, ApplyError> {
+		match self.0 {
+			Some((ref who, ref extra)) => extra.validate(who, info, len),
+			None => Extra::validate_unsigned(info, len),
+		}
+	}
+
+	fn pre_dispatch(&self, info: DispatchInfo, len: usize) -> Result<(), ApplyError> {
+		match self.0 {
+			Some((ref who, ref extra)) => {
+				extra.pre_dispatch(who, info, len)?;
+				extra.on_dispatch(who, info, len);
+				Ok(())
+			}
+			None => Extra::pre_dispatch_unsigned(info, len),
+		}
 	}
 }
-impl<Call> Weighable for TestXt<Call> {
+impl<Call, Extra> Weighable for TestXt<Call, Extra> {
 	fn weight(&self, len: usize) -> Weight {
 		// for testing: weight == size.
 		len as Weight
 	}
 }
+impl<Call: GetDispatchInfo, Extra> GetDispatchInfo for TestXt<Call, Extra> {
+	fn get_dispatch_info(&self, len: usize) -> DispatchInfo {
+		// for testing: the dispatch info of the call, whose weight usually depends on the size.
+		self.1.get_dispatch_info(len)
+	}
+}
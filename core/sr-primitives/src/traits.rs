@@ -23,8 +23,10 @@ use runtime_io;
 #[cfg(feature = "std")] use serde::{Serialize, Deserialize, de::DeserializeOwned};
 use substrate_primitives::{self, Hasher, Blake2Hasher};
 use crate::codec::{Codec, Encode, Decode, HasCompact};
-use crate::transaction_validity::TransactionValidity;
-use crate::generic::{Digest, DigestItem};
+use crate::transaction_validity::{TransactionValidity, ValidTransaction};
+use crate::generic::{Digest, DigestItem, Era};
+use crate::weights::DispatchInfo;
+use crate::ApplyError;
 pub use integer_sqrt::IntegerSquareRoot;
 pub use num_traits::{
 	Zero, One, Bounded, CheckedAdd, CheckedSub, CheckedMul, CheckedDiv,
@@ -162,6 +164,12 @@ impl<T> Convert<T, T> for Identity {
 	fn convert(a: T) -> T { a }
 }
 
+/// A structure that performs standard conversion using the standard Rust conversion traits.
+pub struct ConvertInto;
+impl<A: Into<B>, B> Convert<A, B> for ConvertInto {
+	fn convert(a: A) -> B { a.into() }
+}
+
 /// A meta trait for arithmetic.
 ///
 /// Arithmetic types do all the usual stuff you'd expect numbers to do. They are guaranteed to
@@ -745,6 +753,213 @@ pub trait Applyable: Sized + Send + Sync {
 	fn sender(&self) -> Option<&Self::AccountId>;
 	/// Deconstructs into function call and sender.
 	fn deconstruct(self) -> (Self::Call, Option<Self::AccountId>);
+
+	/// Checks the validity of this piece of information for the transaction queue.
+	fn validate(&self, _info: DispatchInfo, _len: usize) -> Result<ValidTransaction, ApplyError> {
+		Ok(Default::default())
+	}
+
+	/// Checks this piece of information right before it is dispatched and does anything that must
+	/// happen beforehand, like bumping the nonce of the sender. Leaves the state untouched if any
+	/// of the checks fails.
+	fn pre_dispatch(&self, _info: DispatchInfo, _len: usize) -> Result<(), ApplyError> {
+		Ok(())
+	}
+}
+
+/// Something that may carry a tip, i.e. an amount of `Balance` that the sender of a transaction
+/// pays in addition to the fees to get the transaction included sooner.
+pub trait Tippable<Balance> {
+	/// Returns the tip, if any.
+	fn tip(&self) -> Option<Balance>;
+}
+
+/// Additional data and checks that come along with a signed extrinsic.
+///
+/// The data of a signed extension is part of the extrinsic and is covered by its signature, as is
+/// the `AdditionalSigned` data, which isn't part of the extrinsic but is derived from the state
+/// of the chain by the signer and by the runtime alike (e.g. the genesis hash). Extensions can be
+/// combined by putting them into a tuple, in which case they are checked in order.
+///
+/// Right before an extrinsic is dispatched, the `pre_dispatch` checks of all its extensions run
+/// first and only if all of them pass, the extensions change the state in `on_dispatch`. So an
+/// extrinsic that fails a check, e.g. because its sender can't pay the fees, leaves no trace,
+/// like a bumped nonce, behind.
+pub trait SignedExtension: Codec + MaybeDebug + Sync + Send + Clone + Eq + PartialEq {
+	/// The type that identifies the sender.
+	type AccountId;
+
+	/// Any additional data that goes into the signed payload, without being part of the extrinsic.
+	type AdditionalSigned: Encode;
+
+	/// Constructs the additional data that goes into the signed payload.
+	///
+	/// Fails if the data can't be constructed, in which case the extrinsic can't be checked.
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, &'static str>;
+
+	/// Validates a signed extrinsic of `who` for the transaction queue.
+	fn validate(
+		&self,
+		_who: &Self::AccountId,
+		_info: DispatchInfo,
+		_len: usize,
+	) -> Result<ValidTransaction, ApplyError> {
+		Ok(Default::default())
+	}
+
+	/// Checks a signed extrinsic of `who` right before it is dispatched. This must not change any
+	/// state, which is left to `on_dispatch`.
+	///
+	/// By default, this makes the same checks as `validate`.
+	fn pre_dispatch(
+		&self,
+		who: &Self::AccountId,
+		info: DispatchInfo,
+		len: usize,
+	) -> Result<(), ApplyError> {
+		self.validate(who, info, len).map(|_| ())
+	}
+
+	/// Does anything that must happen right before a signed extrinsic of `who` is dispatched, once
+	/// the `pre_dispatch` checks of all extensions of the extrinsic passed.
+	fn on_dispatch(&self, _who: &Self::AccountId, _info: DispatchInfo, _len: usize) {}
+
+	/// Validates an unsigned extrinsic for the transaction queue.
+	fn validate_unsigned(_info: DispatchInfo, _len: usize) -> Result<ValidTransaction, ApplyError> {
+		Ok(Default::default())
+	}
+
+	/// Checks an unsigned extrinsic right before it is dispatched. This must not change any state.
+	///
+	/// By default, this makes the same checks as `validate_unsigned`.
+	fn pre_dispatch_unsigned(info: DispatchInfo, len: usize) -> Result<(), ApplyError> {
+		Self::validate_unsigned(info, len).map(|_| ())
+	}
+
+	/// The names of the extensions, in the order they appear in the extrinsic, as described in
+	/// the metadata.
+	fn identifier() -> Vec<&'static str>;
+}
+
+/// No extensions, for extrinsic formats that have none.
+impl SignedExtension for () {
+	type AccountId = u64;
+	type AdditionalSigned = ();
+
+	fn additional_signed(&self) -> Result<(), &'static str> { Ok(()) }
+
+	fn identifier() -> Vec<&'static str> { Vec::new() }
+}
+
+macro_rules! signed_extension_tuple_impl {
+	($first:ident, $($rest:ident,)+) => {
+		signed_extension_tuple_impl!([$first] [$($rest)+]);
+	};
+	([$($direct:ident)+] []) => {
+		impl<
+			AccountId,
+			$($direct: SignedExtension<AccountId=AccountId>),+
+		> SignedExtension for ($($direct),+,) {
+			type AccountId = AccountId;
+			type AdditionalSigned = ($($direct::AdditionalSigned),+,);
+
+			fn additional_signed(&self) -> Result<Self::AdditionalSigned, &'static str> {
+				let ($($direct),+,) = self;
+				Ok(($($direct.additional_signed()?),+,))
+			}
+
+			fn validate(
+				&self,
+				who: &Self::AccountId,
+				info: DispatchInfo,
+				len: usize,
+			) -> Result<ValidTransaction, ApplyError> {
+				let ($($direct),+,) = self;
+				let valid = ValidTransaction::default();
+				$( let valid = valid.combine_with($direct.validate(who, info, len)?); )+
+				Ok(valid)
+			}
+
+			fn pre_dispatch(
+				&self,
+				who: &Self::AccountId,
+				info: DispatchInfo,
+				len: usize,
+			) -> Result<(), ApplyError> {
+				let ($($direct),+,) = self;
+				$( $direct.pre_dispatch(who, info, len)?; )+
+				Ok(())
+			}
+
+			fn on_dispatch(&self, who: &Self::AccountId, info: DispatchInfo, len: usize) {
+				let ($($direct),+,) = self;
+				$( $direct.on_dispatch(who, info, len); )+
+			}
+
+			fn validate_unsigned(info: DispatchInfo, len: usize) -> Result<ValidTransaction, ApplyError> {
+				let valid = ValidTransaction::default();
+				$( let valid = valid.combine_with($direct::validate_unsigned(info, len)?); )+
+				Ok(valid)
+			}
+
+			fn pre_dispatch_unsigned(info: DispatchInfo, len: usize) -> Result<(), ApplyError> {
+				$( $direct::pre_dispatch_unsigned(info, len)?; )+
+				Ok(())
+			}
+
+			fn identifier() -> Vec<&'static str> {
+				let mut identifier = Vec::new();
+				$( identifier.extend($direct::identifier()); )+
+				identifier
+			}
+		}
+
+		impl<
+			Index,
+			$($direct: LegacyExtension<Index>),+
+		> LegacyExtension<Index> for ($($direct),+,) {
+			fn from_legacy(index: &Index, era: Era, tip: u128) -> Option<Self> {
+				Some(($($direct::from_legacy(index, era, tip)?),+,))
+			}
+		}
+	};
+	([$($direct:ident)+] [$first:ident $($rest:ident)*]) => {
+		signed_extension_tuple_impl!([$($direct)+] []);
+		signed_extension_tuple_impl!([$($direct)+ $first] [$($rest)*]);
+	};
+}
+
+#[allow(non_snake_case)]
+signed_extension_tuple_impl!(A, B, C, D, E, F, G, H, I, J, K, L,);
+
+/// A signed extension that can be built from the fields of an extrinsic in the legacy encoding of
+/// `UncheckedMortalCompactTippedExtrinsic`, whose signature covers the index, era and tip of the
+/// sender instead of signed extensions.
+///
+/// This lets `UncheckedExtensibleExtrinsic` accept extrinsics that existing tools create in the
+/// legacy encoding. An extension that can't be built, which is the default, makes such extrinsics
+/// fail the check.
+pub trait LegacyExtension<Index>: Sized {
+	/// Builds the extension of a legacy extrinsic with the given index, era and tip. The tip is
+	/// zero if the extrinsic doesn't pay one.
+	fn from_legacy(_index: &Index, _era: Era, _tip: u128) -> Option<Self> {
+		None
+	}
+}
+
+impl<Index> LegacyExtension<Index> for () {
+	fn from_legacy(_index: &Index, _era: Era, _tip: u128) -> Option<Self> {
+		Some(())
+	}
+}
+
+/// Something that describes the format of an extrinsic in the runtime metadata.
+pub trait ExtrinsicMetadata {
+	/// The version of the extrinsic format.
+	const VERSION: u8;
+
+	/// The signed extensions of the extrinsic, `()` if it has none.
+	type SignedExtensions: SignedExtension;
 }
 
 /// Auxiliary wrapper that holds an api instance and binds it to the given lifetime.
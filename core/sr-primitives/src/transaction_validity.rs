@@ -70,6 +70,64 @@ pub enum TransactionValidity {
 	Unknown(i8),
 }
 
+/// Information on a valid transaction, as far as a single check of it is concerned.
+///
+/// The validity of a transaction that goes through several checks is obtained by combining the
+/// results of all of them with `combine_with`.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ValidTransaction {
+	/// Priority of the transaction.
+	pub priority: TransactionPriority,
+	/// Tags that must be provided by other transactions before this one can be included.
+	pub requires: Vec<TransactionTag>,
+	/// Tags provided by this transaction.
+	pub provides: Vec<TransactionTag>,
+	/// Minimum number of blocks the validity is correct.
+	pub longevity: TransactionLongevity,
+	/// Whether the transaction should be propagated to other peers.
+	pub propagate: bool,
+}
+
+impl Default for ValidTransaction {
+	fn default() -> Self {
+		ValidTransaction {
+			priority: 0,
+			requires: vec![],
+			provides: vec![],
+			longevity: TransactionLongevity::max_value(),
+			propagate: true,
+		}
+	}
+}
+
+impl ValidTransaction {
+	/// Combine the validity of two checks of the same transaction.
+	///
+	/// Priorities are added, tags are merged, the shorter longevity is kept and the transaction is
+	/// only propagated if both checks allow it.
+	pub fn combine_with(mut self, mut other: ValidTransaction) -> Self {
+		self.priority = self.priority.saturating_add(other.priority);
+		self.requires.append(&mut other.requires);
+		self.provides.append(&mut other.provides);
+		self.longevity = self.longevity.min(other.longevity);
+		self.propagate = self.propagate && other.propagate;
+		self
+	}
+}
+
+impl From<ValidTransaction> for TransactionValidity {
+	fn from(v: ValidTransaction) -> Self {
+		TransactionValidity::Valid {
+			priority: v.priority,
+			requires: v.requires,
+			provides: v.provides,
+			longevity: v.longevity,
+			propagate: v.propagate,
+		}
+	}
+}
+
 impl Decode for TransactionValidity {
 	fn decode<I: crate::codec::Input>(value: &mut I) -> Option<Self> {
 		match value.read_byte()? {
@@ -129,4 +187,31 @@ mod tests {
 		// decode back
 		assert_eq!(TransactionValidity::decode(&mut &*encoded), Some(v));
 	}
+
+	#[test]
+	fn valid_transactions_should_combine() {
+		let a = ValidTransaction {
+			priority: 5,
+			requires: vec![vec![1]],
+			provides: vec![vec![2]],
+			longevity: 42,
+			propagate: true,
+		};
+		let b = ValidTransaction {
+			priority: 3,
+			requires: vec![vec![3]],
+			provides: vec![],
+			longevity: 64,
+			propagate: false,
+		};
+
+		assert_eq!(a.clone().combine_with(Default::default()), a);
+		assert_eq!(a.combine_with(b), ValidTransaction {
+			priority: 8,
+			requires: vec![vec![1], vec![3]],
+			provides: vec![vec![2]],
+			longevity: 42,
+			propagate: false,
+		});
+	}
 }
@@ -19,15 +19,55 @@
 //! Each dispatch function within `decl_module!` can now have an optional
 //! `#[weight = $x]` attribute. $x can be any object that implements the
 //! `Weighable` trait. By default, All transactions are annotated by
-//! `#[weight = TransactionWeight::default()]`.
+//! `#[weight = TransactionWeight::default()]`. The expression may refer to the
+//! parameters of the dispatch function, which are bound by reference.
 //!
 //! Note that the decl_module macro _cannot_ enforce this and will simply fail
 //! if an invalid struct is passed in.
+//!
+//! Besides the weight, every dispatch has a [`DispatchClass`]. The weight of `Normal` dispatches
+//! may only fill a portion of a block, while `Operational` dispatches may use the whole block, so
+//! that they can still be included when the block is full of normal transactions.
 
 /// The final type that each `#[weight = $x:expr]`'s
 /// expression must evaluate to.
 pub type Weight = u32;
 
+/// A generalized group of dispatch types.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum DispatchClass {
+	/// A normal dispatch, which may only use the portion of the block weight available to normal
+	/// dispatches.
+	Normal,
+	/// An operational dispatch, which may use the whole block weight, including the portion that
+	/// is reserved for operational dispatches.
+	Operational,
+}
+
+impl Default for DispatchClass {
+	fn default() -> Self {
+		DispatchClass::Normal
+	}
+}
+
+/// A bundle of static information collected from the `#[weight = $x]` attribute of a dispatch.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct DispatchInfo {
+	/// The weight of the dispatch.
+	pub weight: Weight,
+	/// The class of the dispatch.
+	pub class: DispatchClass,
+}
+
+impl DispatchInfo {
+	/// Returns whether the dispatch is operational.
+	pub fn is_operational(&self) -> bool {
+		self.class == DispatchClass::Operational
+	}
+}
+
 /// A `Call` enum (aka transaction) that can be weighted using the custom weight attribute of
 /// its dispatchable functions. Is implemented by default in the `decl_module!`.
 ///
@@ -39,6 +79,23 @@ pub trait Weighable {
 	fn weight(&self, len: usize) -> Weight;
 }
 
+/// Something that determines the [`DispatchClass`] of a dispatch.
+pub trait ClassifyDispatch {
+	/// Return the class of the dispatch.
+	fn class(&self) -> DispatchClass;
+}
+
+/// A `Call` enum (aka transaction) that provides the [`DispatchInfo`] of its dispatchable
+/// functions. Is implemented by default in the `decl_module!`.
+///
+/// Just like [`Weighable`], both the outer Call enum and the per-module individual ones implement
+/// this.
+pub trait GetDispatchInfo {
+	/// Return the dispatch info of this call.
+	/// The `len` argument is the encoded length of the transaction/call.
+	fn get_dispatch_info(&self, len: usize) -> DispatchInfo;
+}
+
 /// Default type used as the weight representative in a `#[weight = x]` attribute.
 ///
 /// A user may pass in any other type that implements [`Weighable`]. If not, the `Default`
@@ -54,6 +111,10 @@ pub enum TransactionWeight {
 	/// Free. The transaction does not increase the total weight
 	/// (i.e. is not included in weight calculation).
 	Free,
+	/// Operational transaction with basic weight (base, byte).
+	/// Unlike `Basic`, the transaction may use the portion of the block reserved for operational
+	/// transactions.
+	Operational(Weight, Weight),
 }
 
 impl Weighable for TransactionWeight {
@@ -62,6 +123,16 @@ impl Weighable for TransactionWeight {
 			TransactionWeight::Basic(base, byte) => base + byte * len as Weight,
 			TransactionWeight::Max => 3 * 1024 * 1024,
 			TransactionWeight::Free => 0,
+			TransactionWeight::Operational(base, byte) => base + byte * len as Weight,
+		}
+	}
+}
+
+impl ClassifyDispatch for TransactionWeight {
+	fn class(&self) -> DispatchClass {
+		match self {
+			TransactionWeight::Operational(..) => DispatchClass::Operational,
+			_ => DispatchClass::Normal,
 		}
 	}
 }
@@ -40,7 +40,7 @@
 
 use rstd::prelude::*;
 
-pub use primitives::sandbox::{TypedValue, ReturnValue, HostError};
+pub use primitives::sandbox::{TypedValue, ReturnValue, HostError, TrapCode};
 
 mod imp {
 	#[cfg(feature = "std")]
@@ -64,6 +64,9 @@ pub enum Error {
 
 	/// Failed to invoke an exported function for some reason.
 	Execution,
+
+	/// The invoked function trapped for the given reason.
+	Trap(TrapCode),
 }
 
 impl From<Error> for HostError {
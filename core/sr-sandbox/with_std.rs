@@ -299,6 +299,7 @@ impl<T> Instance<T> {
 		match result {
 			Ok(None) => Ok(ReturnValue::Unit),
 			Ok(Some(val)) => Ok(ReturnValue::Value(val.into())),
+			Err(wasmi::Error::Trap(trap)) => Err(Error::Trap(trap.kind().into())),
 			Err(_err) => Err(Error::Execution),
 		}
 	}
@@ -307,7 +308,9 @@ impl<T> Instance<T> {
 #[cfg(test)]
 mod tests {
 	use wabt;
-	use crate::{Error, TypedValue, ReturnValue, HostError, EnvironmentDefinitionBuilder, Instance};
+	use crate::{
+		Error, TypedValue, ReturnValue, HostError, TrapCode, EnvironmentDefinitionBuilder, Instance,
+	};
 	use assert_matches::assert_matches;
 
 	fn execute_sandboxed(code: &[u8], args: &[TypedValue]) -> Result<ReturnValue, HostError> {
@@ -475,6 +478,61 @@ mod tests {
 		// But this fails since we imported a function that returns i32 as if it returned i64.
 		assert_matches!(
 			instance.invoke(b"call", &[], &mut ()),
+			Err(Error::Trap(TrapCode::UnexpectedSignature))
+		);
+	}
+
+	#[test]
+	fn trap_reports_its_reason() {
+		let code = wabt::wat2wasm(r#"
+		(module
+			(import "env" "assert" (func $assert (param i32)))
+
+			(func (export "unreachable")
+				(unreachable)
+			)
+			(func (export "divide_by_zero")
+				(drop
+					(i32.div_u
+						(i32.const 1)
+						(i32.const 0)
+					)
+				)
+			)
+			(func (export "host")
+				(call $assert
+					(i32.const 0)
+				)
+			)
+		)
+		"#).unwrap();
+
+		fn env_assert(_e: &mut (), args: &[TypedValue]) -> Result<ReturnValue, HostError> {
+			match args[0].as_i32() {
+				Some(0) | None => Err(HostError),
+				Some(_) => Ok(ReturnValue::Unit),
+			}
+		}
+
+		let mut env_builder = EnvironmentDefinitionBuilder::new();
+		env_builder.add_host_func("env", "assert", env_assert);
+		let mut instance = Instance::new(&code, &env_builder, &mut ()).unwrap();
+
+		assert_matches!(
+			instance.invoke(b"unreachable", &[], &mut ()),
+			Err(Error::Trap(TrapCode::Unreachable))
+		);
+		assert_matches!(
+			instance.invoke(b"divide_by_zero", &[], &mut ()),
+			Err(Error::Trap(TrapCode::DivisionByZero))
+		);
+		assert_matches!(
+			instance.invoke(b"host", &[], &mut ()),
+			Err(Error::Trap(TrapCode::Host))
+		);
+		// Failing to invoke a function is not a trap.
+		assert_matches!(
+			instance.invoke(b"missing", &[], &mut ()),
 			Err(Error::Execution)
 		);
 	}
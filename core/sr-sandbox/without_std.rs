@@ -307,7 +307,13 @@ impl<T> Instance<T> {
 					.ok_or(Error::Execution)?;
 				Ok(return_val)
 			}
-			sandbox_primitives::ERR_EXECUTION => Err(Error::Execution),
+			sandbox_primitives::ERR_EXECUTION => {
+				// The trap code is left zeroed, i.e. unknown, if the execution failed without a trap.
+				match sandbox_primitives::TrapCode::decode(&mut &return_val[..]) {
+					Some(sandbox_primitives::TrapCode::Unknown) | None => Err(Error::Execution),
+					Some(trap_code) => Err(Error::Trap(trap_code)),
+				}
+			}
 			_ => unreachable!(),
 		}
 	}
@@ -27,7 +27,7 @@
 //!
 //! # Pruning.
 //! See `RefWindow` for pruning algorithm details. `StateDb` prunes on each canonicalization until pruning
-//! constraints are satisfied.
+//! constraints are satisfied. The state of checkpoint blocks (if configured) is never pruned.
 
 mod noncanonical;
 mod pruning;
@@ -80,6 +80,8 @@ pub enum Error<E: fmt::Debug> {
 	InvalidParent,
 	/// Canonicalization would discard pinned state.
 	DiscardingPinned,
+	/// Trying to pin the state of a block that is not in the pruning window.
+	InvalidPin,
 }
 
 impl<E: fmt::Debug> fmt::Debug for Error<E> {
@@ -91,6 +93,7 @@ impl<E: fmt::Debug> fmt::Debug for Error<E> {
 			Error::InvalidBlockNumber => write!(f, "Trying to insert block with invalid number"),
 			Error::InvalidParent => write!(f, "Trying to insert block with unknown parent"),
 			Error::DiscardingPinned => write!(f, "Trying to discard pinned state"),
+			Error::InvalidPin => write!(f, "Trying to pin state that is not in the pruning window"),
 		}
 	}
 }
@@ -114,6 +117,39 @@ pub struct CommitSet<H: Hash> {
 	pub meta: ChangeSet<Vec<u8>>,
 }
 
+impl<H: Hash> CommitSet<H> {
+	/// Append changes of the other commit.
+	fn append(&mut self, other: CommitSet<H>) {
+		self.data.inserted.extend(other.data.inserted);
+		self.data.deleted.extend(other.data.deleted);
+		self.meta.inserted.extend(other.meta.inserted);
+		self.meta.deleted.extend(other.meta.deleted);
+	}
+}
+
+/// State database bookkeeping statistics.
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+pub struct Stats {
+	/// Number of the oldest block in the pruning window. `None` when pruning is disabled.
+	pub pruning_window_start: Option<u64>,
+	/// Number of canonical blocks in the pruning window.
+	pub pruning_window_size: u64,
+	/// Number of the last canonicalized block.
+	pub last_canonical: Option<u64>,
+	/// Number of block levels in the non-canonical overlay.
+	pub non_canonical_levels: u64,
+	/// Number of blocks in the non-canonical overlay.
+	pub non_canonical_blocks: u64,
+	/// Number of distinct nodes held in memory by the non-canonical overlay.
+	pub non_canonical_values: u64,
+	/// Total size of the nodes held by the non-canonical overlay, in bytes.
+	pub non_canonical_size: u64,
+	/// Number of pinned blocks.
+	pub pinned_blocks: u64,
+	/// Number of canonical states pinned with `pin_state`.
+	pub pinned_states: u64,
+}
+
 /// Pruning constraints. If none are specified pruning is
 #[derive(Default, Debug, Clone, Eq, PartialEq)]
 pub struct Constraints {
@@ -121,6 +157,8 @@ pub struct Constraints {
 	pub max_blocks: Option<u32>,
 	/// Maximum memory in the pruning overlay.
 	pub max_mem: Option<usize>,
+	/// Keep the state of every canonical block with number divisible by this value.
+	pub checkpoint_interval: Option<u32>,
 }
 
 /// Pruning mode.
@@ -140,6 +178,17 @@ impl PruningMode {
 		PruningMode::Constrained(Constraints {
 			max_blocks: Some(n),
 			max_mem: None,
+			checkpoint_interval: None,
+		})
+	}
+
+	/// Create a mode that keeps given number of blocks and the state of every
+	/// `interval`-th canonical block.
+	pub fn keep_blocks_with_checkpoints(n: u32, interval: u32) -> PruningMode {
+		PruningMode::Constrained(Constraints {
+			max_blocks: Some(n),
+			max_mem: None,
+			checkpoint_interval: Some(interval),
 		})
 	}
 
@@ -182,7 +231,10 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 				max_mem: Some(_),
 				..
 			}) => unimplemented!(),
-			PruningMode::Constrained(_) => Some(RefWindow::new(db)?),
+			PruningMode::Constrained(ref constraints) => Some(RefWindow::with_checkpoints(
+				db,
+				constraints.checkpoint_interval.map(u64::from),
+			)?),
 			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => None,
 		};
 		Ok(StateDbSync {
@@ -210,7 +262,11 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 		}
 	}
 
-	pub fn canonicalize_block<E: fmt::Debug>(&mut self, hash: &BlockHash) -> Result<CommitSet<Key>, Error<E>> {
+	pub fn canonicalize_block<D: MetaDb>(
+		&mut self,
+		hash: &BlockHash,
+		db: &D,
+	) -> Result<CommitSet<Key>, Error<D::Error>> {
 		let mut commit = CommitSet::default();
 		if self.mode == PruningMode::ArchiveAll {
 			return Ok(commit)
@@ -220,11 +276,12 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 			if self.pinned.contains_key(&hash) {
 				break;
 			}
-			match self.non_canonical.canonicalize(&hash, &self.pinned, &mut commit) {
+			let mut block_commit = CommitSet::default();
+			match self.non_canonical.canonicalize(&hash, &self.pinned, &mut block_commit) {
 				Ok(()) => {
 					self.canonicalization_queue.pop_front();
 					if self.mode == PruningMode::ArchiveCanonical {
-						commit.data.deleted.clear();
+						block_commit.data.deleted.clear();
 					}
 				}
 				Err(Error::DiscardingPinned) => {
@@ -233,8 +290,10 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 				Err(e) => return Err(e),
 			};
 			if let Some(ref mut pruning) = self.pruning {
-				pruning.note_canonical(&hash, &mut commit);
+				pruning.retain_checkpoint_nodes(&mut block_commit, db)?;
+				pruning.note_canonical(&hash, &mut block_commit);
 			}
+			commit.append(block_commit);
 		}
 		self.prune(&mut commit);
 		Ok(commit)
@@ -251,7 +310,15 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 				if self.best_canonical().map(|c| number > c).unwrap_or(true) {
 					!self.non_canonical.have_block(hash)
 				} else {
-					self.pruning.as_ref().map_or(false, |pruning| number < pruning.pending() || !pruning.have_block(hash))
+					self.pruning.as_ref().map_or(false, |pruning| {
+						if pruning.is_pinned(hash) {
+							false
+						} else if number < pruning.pending() {
+							!pruning.is_checkpoint_block(hash, number)
+						} else {
+							!pruning.have_block(hash)
+						}
+					})
 				}
 			}
 		}
@@ -311,6 +378,33 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 		}
 	}
 
+	pub fn pin_state<D: MetaDb>(
+		&mut self,
+		hash: &BlockHash,
+		expires_at: u64,
+		db: &D,
+	) -> Result<CommitSet<Key>, Error<D::Error>> {
+		let mut commit = CommitSet::default();
+		match self.mode {
+			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => Ok(commit),
+			PruningMode::Constrained(_) => {
+				let pruning = self.pruning.as_mut().ok_or(Error::InvalidPin)?;
+				pruning.pin(hash, expires_at, db, &mut commit)?;
+				trace!(target: "state-db", "Pinned state: {:?}", hash);
+				Ok(commit)
+			},
+		}
+	}
+
+	pub fn unpin_state(&mut self, hash: &BlockHash) -> CommitSet<Key> {
+		let mut commit = CommitSet::default();
+		if let Some(pruning) = &mut self.pruning {
+			pruning.unpin(hash, &mut commit);
+			trace!(target: "state-db", "Unpinned state: {:?}", hash);
+		}
+		commit
+	}
+
 	pub fn get<D: NodeDb>(&self, key: &Key, db: &D) -> Result<Option<DBValue>, Error<D::Error>>
 		where Key: AsRef<D::Key>
 	{
@@ -320,6 +414,19 @@ impl<BlockHash: Hash, Key: Hash> StateDbSync<BlockHash, Key> {
 		db.get(key.as_ref()).map_err(|e| Error::Db(e))
 	}
 
+	pub fn stats(&self) -> Stats {
+		let mut stats = Stats {
+			pruning_window_start: self.pruning.as_ref().map(|p| p.pending()),
+			pruning_window_size: self.pruning.as_ref().map_or(0, |p| p.window_size()),
+			last_canonical: self.best_canonical(),
+			pinned_blocks: self.pinned.len() as u64,
+			pinned_states: self.pruning.as_ref().map_or(0, |p| p.pinned_count() as u64),
+			..Default::default()
+		};
+		self.non_canonical.stats(&mut stats);
+		stats
+	}
+
 	pub fn apply_pending(&mut self) {
 		self.non_canonical.apply_pending();
 		if let Some(pruning) = &mut self.pruning {
@@ -362,8 +469,12 @@ impl<BlockHash: Hash, Key: Hash> StateDb<BlockHash, Key> {
 	}
 
 	/// Finalize a previously inserted block.
-	pub fn canonicalize_block<E: fmt::Debug>(&self, hash: &BlockHash) -> Result<CommitSet<Key>, Error<E>> {
-		self.db.write().canonicalize_block(hash)
+	pub fn canonicalize_block<D: MetaDb>(
+		&self,
+		hash: &BlockHash,
+		db: &D,
+	) -> Result<CommitSet<Key>, Error<D::Error>> {
+		self.db.write().canonicalize_block(hash, db)
 	}
 
 	/// Prevents pruning of specified block and its descendants.
@@ -376,6 +487,23 @@ impl<BlockHash: Hash, Key: Hash> StateDb<BlockHash, Key> {
 		self.db.write().unpin(hash)
 	}
 
+	/// Keep the state of a canonical block in the pruning window until it is unpinned
+	/// or a block with number `expires_at` is canonicalized. Unlike `pin` this does not
+	/// stop pruning and is persisted. Returns a database commit.
+	pub fn pin_state<D: MetaDb>(
+		&self,
+		hash: &BlockHash,
+		expires_at: u64,
+		db: &D,
+	) -> Result<CommitSet<Key>, Error<D::Error>> {
+		self.db.write().pin_state(hash, expires_at, db)
+	}
+
+	/// Release a state pinned with `pin_state`. Returns a database commit.
+	pub fn unpin_state(&self, hash: &BlockHash) -> CommitSet<Key> {
+		self.db.write().unpin_state(hash)
+	}
+
 	/// Get a value from non-canonical/pruning overlay or the backing DB.
 	pub fn get<D: NodeDb>(&self, key: &Key, db: &D) -> Result<Option<DBValue>, Error<D::Error>>
 		where Key: AsRef<D::Key>
@@ -400,6 +528,11 @@ impl<BlockHash: Hash, Key: Hash> StateDb<BlockHash, Key> {
 		return self.db.read().is_pruned(hash, number)
 	}
 
+	/// Returns statistics of the in-memory overlays and the pruning window.
+	pub fn stats(&self) -> Stats {
+		self.db.read().stats()
+	}
+
 	/// Apply all pending changes
 	pub fn apply_pending(&self) {
 		self.db.write().apply_pending();
@@ -415,7 +548,7 @@ impl<BlockHash: Hash, Key: Hash> StateDb<BlockHash, Key> {
 mod tests {
 	use std::io;
 	use primitives::H256;
-	use crate::{StateDb, PruningMode, Constraints};
+	use crate::{StateDb, PruningMode, Constraints, Stats};
 	use crate::test::{make_db, make_changeset, TestDb};
 
 	fn make_test_db(settings: PruningMode) -> (TestDb, StateDb<H256, H256>) {
@@ -463,7 +596,7 @@ mod tests {
 				.unwrap(),
 		);
 		state_db.apply_pending();
-		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(1)).unwrap());
+		db.commit(&state_db.canonicalize_block(&H256::from_low_u64_be(1), &db).unwrap());
 		state_db.apply_pending();
 		db.commit(
 			&state_db
@@ -476,9 +609,9 @@ mod tests {
 				.unwrap(),
 		);
 		state_db.apply_pending();
-		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(21)).unwrap());
+		db.commit(&state_db.canonicalize_block(&H256::from_low_u64_be(21), &db).unwrap());
 		state_db.apply_pending();
-		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(3)).unwrap());
+		db.commit(&state_db.canonicalize_block(&H256::from_low_u64_be(3), &db).unwrap());
 		state_db.apply_pending();
 
 		(db, state_db)
@@ -502,6 +635,7 @@ mod tests {
 		let (db, _) = make_test_db(PruningMode::Constrained(Constraints {
 			max_blocks: Some(0),
 			max_mem: None,
+			checkpoint_interval: None,
 		}));
 		assert!(db.data_eq(&make_db(&[21, 3, 922, 94])));
 	}
@@ -511,6 +645,7 @@ mod tests {
 		let (db, sdb) = make_test_db(PruningMode::Constrained(Constraints {
 			max_blocks: Some(1),
 			max_mem: None,
+			checkpoint_interval: None,
 		}));
 		assert!(sdb.is_pruned(&H256::from_low_u64_be(0), 0));
 		assert!(sdb.is_pruned(&H256::from_low_u64_be(1), 1));
@@ -519,11 +654,32 @@ mod tests {
 		assert!(db.data_eq(&make_db(&[21, 3, 922, 93, 94])));
 	}
 
+	#[test]
+	fn stats_reflect_window_and_overlay() {
+		let (_, sdb) = make_test_db(PruningMode::keep_blocks(1));
+		sdb.pin(&H256::from_low_u64_be(4));
+		assert_eq!(sdb.stats(), Stats {
+			pruning_window_start: Some(2),
+			pruning_window_size: 1,
+			last_canonical: Some(3),
+			non_canonical_levels: 1,
+			non_canonical_blocks: 1,
+			non_canonical_values: 1,
+			non_canonical_size: 32,
+			pinned_blocks: 1,
+			pinned_states: 0,
+		});
+
+		let (_, sdb) = make_test_db(PruningMode::ArchiveCanonical);
+		assert_eq!(sdb.stats().pruning_window_start, None);
+	}
+
 	#[test]
 	fn prune_window_2() {
 		let (db, sdb) = make_test_db(PruningMode::Constrained(Constraints {
 			max_blocks: Some(2),
 			max_mem: None,
+			checkpoint_interval: None,
 		}));
 		assert!(sdb.is_pruned(&H256::from_low_u64_be(0), 0));
 		assert!(sdb.is_pruned(&H256::from_low_u64_be(1), 1));
@@ -531,4 +687,86 @@ mod tests {
 		assert!(sdb.is_pruned(&H256::from_low_u64_be(22), 2));
 		assert!(db.data_eq(&make_db(&[1, 21, 3, 921, 922, 93, 94])));
 	}
+
+	#[test]
+	fn prune_keeps_checkpoints() {
+		let mut db = make_db(&[]);
+		let state_db: StateDb<H256, H256> = StateDb::new(PruningMode::keep_blocks_with_checkpoints(0, 2), &db).unwrap();
+		let changes: [(&[u64], &[u64]); 7] = [
+			(&[0], &[]),
+			(&[1], &[0]),
+			(&[2], &[1]),
+			(&[3], &[2]),
+			(&[4], &[3]),
+			// re-inserted node of checkpoint 0 is not pruned again
+			(&[0, 5], &[4]),
+			(&[6], &[0, 5]),
+		];
+		for (number, (inserted, deleted)) in changes.iter().enumerate() {
+			let hash = H256::from_low_u64_be(100 + number as u64);
+			let parent = H256::from_low_u64_be(99 + number as u64);
+			db.commit(
+				&state_db
+					.insert_block::<io::Error>(&hash, number as u64, &parent, make_changeset(inserted, deleted))
+					.unwrap(),
+			);
+			state_db.apply_pending();
+			db.commit(&state_db.canonicalize_block(&hash, &db).unwrap());
+			state_db.apply_pending();
+		}
+
+		assert!(db.data_eq(&make_db(&[0, 2, 4, 6])));
+		assert!(!state_db.is_pruned(&H256::from_low_u64_be(100), 0));
+		assert!(state_db.is_pruned(&H256::from_low_u64_be(101), 1));
+		assert!(!state_db.is_pruned(&H256::from_low_u64_be(102), 2));
+		assert!(state_db.is_pruned(&H256::from_low_u64_be(103), 3));
+		assert!(!state_db.is_pruned(&H256::from_low_u64_be(104), 4));
+		// a non-canonical block at a checkpoint height
+		assert!(state_db.is_pruned(&H256::from_low_u64_be(1002), 2));
+
+		// the checkpoint blocks are remembered across restarts
+		let state_db: StateDb<H256, H256> = StateDb::new(PruningMode::keep_blocks_with_checkpoints(0, 2), &db).unwrap();
+		assert!(!state_db.is_pruned(&H256::from_low_u64_be(102), 2));
+		assert!(state_db.is_pruned(&H256::from_low_u64_be(1002), 2));
+	}
+
+	#[test]
+	fn pinned_state_does_not_stop_pruning() {
+		let mut db = make_db(&[]);
+		let state_db: StateDb<H256, H256> = StateDb::new(PruningMode::keep_blocks(1), &db).unwrap();
+		let changes: [(&[u64], &[u64]); 5] = [
+			(&[0], &[]),
+			(&[1], &[0]),
+			(&[2], &[1]),
+			(&[3], &[2]),
+			(&[4], &[3]),
+		];
+		for (number, (inserted, deleted)) in changes.iter().enumerate() {
+			let hash = H256::from_low_u64_be(100 + number as u64);
+			let parent = H256::from_low_u64_be(99 + number as u64);
+			db.commit(
+				&state_db
+					.insert_block::<io::Error>(&hash, number as u64, &parent, make_changeset(inserted, deleted))
+					.unwrap(),
+			);
+			state_db.apply_pending();
+			db.commit(&state_db.canonicalize_block(&hash, &db).unwrap());
+			state_db.apply_pending();
+			if number == 1 {
+				db.commit(&state_db.pin_state(&hash, 100, &db).unwrap());
+				state_db.apply_pending();
+			}
+		}
+
+		assert_eq!(state_db.stats().pruning_window_start, Some(4));
+		assert_eq!(state_db.stats().pinned_states, 1);
+		assert!(!state_db.is_pruned(&H256::from_low_u64_be(101), 1));
+		assert!(state_db.is_pruned(&H256::from_low_u64_be(102), 2));
+		assert!(db.data_eq(&make_db(&[1, 3, 4])));
+
+		db.commit(&state_db.unpin_state(&H256::from_low_u64_be(101)));
+		state_db.apply_pending();
+		assert!(state_db.is_pruned(&H256::from_low_u64_be(101), 1));
+		assert!(db.data_eq(&make_db(&[3, 4])));
+	}
 }
@@ -22,7 +22,7 @@
 
 use std::fmt;
 use std::collections::{HashMap, VecDeque, hash_map::Entry};
-use super::{Error, DBValue, ChangeSet, CommitSet, MetaDb, Hash, to_meta_key};
+use super::{Error, DBValue, ChangeSet, CommitSet, MetaDb, Hash, Stats, to_meta_key};
 use crate::codec::{Encode, Decode};
 use log::trace;
 
@@ -396,6 +396,14 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 		}
 	}
 
+	/// Fill in the overlay part of the statistics.
+	pub fn stats(&self, stats: &mut Stats) {
+		stats.non_canonical_levels = self.levels.len() as u64;
+		stats.non_canonical_blocks = self.levels.iter().map(|level| level.len() as u64).sum();
+		stats.non_canonical_values = self.values.len() as u64;
+		stats.non_canonical_size = self.values.values().map(|(_, value)| value.len() as u64).sum();
+	}
+
 	/// Apply all pending changes
 	pub fn apply_pending(&mut self) {
 		self.apply_canonicalizations();
@@ -21,6 +21,28 @@
 //! If a node is re-inserted into the window it gets removed from
 //! the death list.
 //! The changes are journaled in the DB.
+//!
+//! # Checkpoints.
+//! Optionally the state of every block with number divisible by the checkpoint interval
+//! is kept. The window tracks the keys inserted by the canonical blocks after the last
+//! checkpoint. A key that is deleted but is not tracked belongs to the state of the
+//! last checkpoint: it is never added to the death list and is marked in the DB
+//! instead, so that it is never deleted afterwards even if it is re-inserted.
+//! Markers are only looked up for the tracked keys that are deleted, since only a
+//! re-inserted checkpoint node can be both tracked and marked.
+//!
+//! The tracked keys of the blocks that have already been pruned are not journaled.
+//! After a restart such keys are treated as belonging to the checkpoint, so some
+//! extra nodes might be kept.
+//!
+//! # Pinned states.
+//! The state of a block in the window can be pinned, which keeps it until the pin is
+//! released or expires without holding back the pruning of other blocks. Similarly to
+//! checkpoints, each pin tracks the keys inserted by the canonical blocks after the
+//! pinned one. When a death row is pruned, the keys that are not tracked by a pin of an
+//! older block belong to the pinned state and are retained instead of being deleted.
+//! Retained keys that are still dead when the pin is released are deleted then.
+//! Pins and their changes are journaled in the DB.
 
 use std::collections::{HashMap, HashSet, VecDeque};
 use crate::codec::{Encode, Decode};
@@ -29,6 +51,10 @@ use log::{trace, warn};
 
 const LAST_PRUNED: &[u8] = b"last_pruned";
 const PRUNING_JOURNAL: &[u8] = b"pruning_journal";
+const CHECKPOINT_NODE: &[u8] = b"checkpoint_node";
+const PINNED_STATES: &[u8] = b"pinned_states";
+const PIN_JOURNAL: &[u8] = b"pin_journal";
+const CHECKPOINT_BLOCKS: &[u8] = b"checkpoint_blocks";
 
 /// See module documentation.
 pub struct RefWindow<BlockHash: Hash, Key: Hash> {
@@ -44,6 +70,49 @@ pub struct RefWindow<BlockHash: Hash, Key: Hash> {
 	/// Number of calls of `prune_one` after
 	/// last call `apply_pending` or `revert_pending`
 	pending_prunings: usize,
+	/// Keep the state of every block with number divisible by this value.
+	checkpoint_interval: Option<u64>,
+	/// Maps keys inserted by the canonical blocks after the last checkpoint
+	/// to the number of the inserting block.
+	checkpoint_index: HashMap<Key, u64>,
+	/// Keys marked as checkpoint nodes after
+	/// last call `apply_pending` or `revert_pending`
+	pending_checkpoint_nodes: HashSet<Key>,
+	/// Hashes of the pruned checkpoint blocks, by number.
+	checkpoint_blocks: HashMap<u64, BlockHash>,
+	/// Numbers of the checkpoint blocks pruned after
+	/// last call `apply_pending` or `revert_pending`
+	pending_checkpoint_blocks: Vec<u64>,
+	/// States kept until they are unpinned.
+	pinned: HashMap<BlockHash, PinnedState<Key>>,
+	/// Changes of the pinned states after last call `apply_pending` or `revert_pending`,
+	/// in the order they have been made.
+	pending_pin_changes: Vec<PinChange<BlockHash, Key>>,
+}
+
+/// State of a canonical block that is kept until it is unpinned.
+struct PinnedState<Key: Hash> {
+	number: u64,
+	refs: u32,
+	/// The pin is released when a block with this number is canonicalized.
+	expires_at: u64,
+	/// Keys inserted by the canonical blocks after the pinned one.
+	inserted: HashSet<Key>,
+	/// Keys of the pinned state that have been retained by pruning, mapped to `true`
+	/// while they are not a part of the latest canonical state.
+	retained: HashMap<Key, bool>,
+	/// Number of journal records of this pin.
+	journal_len: u64,
+}
+
+/// Undo record of a pinned state change.
+enum PinChange<BlockHash: Hash, Key: Hash> {
+	Pinned(BlockHash),
+	Updated(BlockHash, u32, u64),
+	Released(BlockHash, PinnedState<Key>),
+	Inserted(BlockHash, Key),
+	Retained(BlockHash, Key, Option<bool>),
+	Journaled(BlockHash),
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -60,12 +129,76 @@ struct JournalRecord<BlockHash: Hash, Key: Hash> {
 	deleted: Vec<Key>,
 }
 
+#[derive(Encode, Decode)]
+struct PinRecord<BlockHash: Hash> {
+	hash: BlockHash,
+	number: u64,
+	refs: u32,
+	expires_at: u64,
+}
+
+#[derive(Encode, Decode, Default)]
+struct PinJournalRecord<Key: Hash> {
+	/// Keys inserted after the pinned block.
+	inserted: Vec<Key>,
+	/// Retained keys that have been pruned.
+	deleted: Vec<Key>,
+	/// Retained keys that have been re-inserted.
+	reinserted: Vec<Key>,
+}
+
+impl<Key: Hash> PinJournalRecord<Key> {
+	fn is_empty(&self) -> bool {
+		self.inserted.is_empty() && self.deleted.is_empty() && self.reinserted.is_empty()
+	}
+}
+
 fn to_journal_key(block: u64) -> Vec<u8> {
 	to_meta_key(PRUNING_JOURNAL, &block)
 }
 
+fn to_checkpoint_node_key<Key: Hash>(key: &Key) -> Vec<u8> {
+	to_meta_key(CHECKPOINT_NODE, key)
+}
+
+fn to_pin_journal_key<BlockHash: Hash>(hash: &BlockHash, index: u64) -> Vec<u8> {
+	to_meta_key(PIN_JOURNAL, &(hash.clone(), index))
+}
+
+impl<Key: Hash> PinnedState<Key> {
+	fn apply_journal_record(&mut self, record: PinJournalRecord<Key>) {
+		self.inserted.extend(record.inserted);
+		self.retained.extend(record.deleted.into_iter().map(|key| (key, true)));
+		self.retained.extend(record.reinserted.into_iter().map(|key| (key, false)));
+		self.journal_len += 1;
+	}
+
+	/// Push the journal record to `commit` unless it is empty.
+	fn journal<BlockHash: Hash>(
+		&mut self,
+		hash: &BlockHash,
+		record: PinJournalRecord<Key>,
+		commit: &mut CommitSet<Key>,
+		changes: &mut Vec<PinChange<BlockHash, Key>>,
+	) {
+		if !record.is_empty() {
+			commit.meta.inserted.push((to_pin_journal_key(hash, self.journal_len), record.encode()));
+			self.journal_len += 1;
+			changes.push(PinChange::Journaled(hash.clone()));
+		}
+	}
+}
+
 impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 	pub fn new<D: MetaDb>(db: &D) -> Result<RefWindow<BlockHash, Key>, Error<D::Error>> {
+		Self::with_checkpoints(db, None)
+	}
+
+	/// Create a window that keeps the state of every block with number divisible by `checkpoint_interval`.
+	pub fn with_checkpoints<D: MetaDb>(
+		db: &D,
+		checkpoint_interval: Option<u64>,
+	) -> Result<RefWindow<BlockHash, Key>, Error<D::Error>> {
 		let last_pruned = db.get_meta(&to_meta_key(LAST_PRUNED, &()))
 			.map_err(|e| Error::Db(e))?;
 		let pending_number: u64 = match last_pruned {
@@ -79,24 +212,86 @@ impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 			pending_number: pending_number,
 			pending_canonicalizations: 0,
 			pending_prunings: 0,
+			checkpoint_interval: checkpoint_interval.filter(|interval| *interval > 0),
+			checkpoint_index: Default::default(),
+			pending_checkpoint_nodes: Default::default(),
+			checkpoint_blocks: Default::default(),
+			pending_checkpoint_blocks: Default::default(),
+			pinned: Default::default(),
+			pending_pin_changes: Default::default(),
 		};
 		// read the journal
 		trace!(target: "state-db", "Reading pruning journal. Pending #{}", pending_number);
+		let mut inserted_in_window = Vec::new();
 		loop {
 			let journal_key = to_journal_key(block);
 			match db.get_meta(&journal_key).map_err(|e| Error::Db(e))? {
 				Some(record) => {
 					let record: JournalRecord<BlockHash, Key> = Decode::decode(&mut record.as_slice()).ok_or(Error::Decoding)?;
 					trace!(target: "state-db", "Pruning journal entry {} ({} inserted, {} deleted)", block, record.inserted.len(), record.deleted.len());
+					if pruning.checkpoint_interval.is_some() {
+						inserted_in_window.push((block, record.inserted.clone()));
+					}
 					pruning.import(&record.hash, journal_key, record.inserted.into_iter(), record.deleted);
 				},
 				None => break,
 			}
 			block += 1;
 		}
+
+		// restore the keys inserted after the last checkpoint that are still in the window
+		if let Some(last_checkpoint) = block.checked_sub(1).and_then(|last| pruning.last_checkpoint(last)) {
+			for (block, inserted) in inserted_in_window.into_iter().filter(|(block, _)| *block > last_checkpoint) {
+				pruning.checkpoint_index.extend(inserted.into_iter().map(|key| (key, block)));
+			}
+		}
+
+		// read the hashes of the pruned checkpoint blocks
+		if let Some(blocks) = db.get_meta(&to_meta_key(CHECKPOINT_BLOCKS, &())).map_err(|e| Error::Db(e))? {
+			let blocks: Vec<(u64, BlockHash)> = Decode::decode(&mut blocks.as_slice()).ok_or(Error::Decoding)?;
+			pruning.checkpoint_blocks.extend(blocks);
+		}
+
+		// read the pinned states
+		if let Some(pins) = db.get_meta(&to_meta_key(PINNED_STATES, &())).map_err(|e| Error::Db(e))? {
+			let pins: Vec<PinRecord<BlockHash>> = Decode::decode(&mut pins.as_slice()).ok_or(Error::Decoding)?;
+			for pin in pins {
+				let mut state = PinnedState {
+					number: pin.number,
+					refs: pin.refs,
+					expires_at: pin.expires_at,
+					inserted: Default::default(),
+					retained: Default::default(),
+					journal_len: 0,
+				};
+				while let Some(record) = db.get_meta(&to_pin_journal_key(&pin.hash, state.journal_len))
+					.map_err(|e| Error::Db(e))?
+				{
+					let record = Decode::decode(&mut record.as_slice()).ok_or(Error::Decoding)?;
+					state.apply_journal_record(record);
+				}
+				trace!(target: "state-db", "Pinned state {:?} ({} retained)", pin.hash, state.retained.len());
+				pruning.pinned.insert(pin.hash, state);
+			}
+		}
 		Ok(pruning)
 	}
 
+	/// Returns the number of the last checkpoint block at or before given block.
+	fn last_checkpoint(&self, number: u64) -> Option<u64> {
+		self.checkpoint_interval.map(|interval| number - number % interval)
+	}
+
+	/// Returns true if the state of the canonical block with given number is kept as a checkpoint.
+	pub fn is_checkpoint(&self, number: u64) -> bool {
+		self.checkpoint_interval.map_or(false, |interval| number % interval == 0)
+	}
+
+	/// Returns true if given block is a pruned checkpoint block, whose state is kept.
+	pub fn is_checkpoint_block(&self, hash: &BlockHash, number: u64) -> bool {
+		self.checkpoint_blocks.get(&number) == Some(hash)
+	}
+
 	fn import<I: IntoIterator<Item=Key>>(&mut self, hash: &BlockHash, journal_key: Vec<u8>, inserted: I, deleted: Vec<Key>) {
 		// remove all re-inserted keys from death rows
 		for k in inserted {
@@ -139,12 +334,156 @@ impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 		self.death_rows.iter().skip(self.pending_prunings).any(|r| r.hash == *hash)
 	}
 
+	/// Returns true if the state of given block is pinned.
+	pub fn is_pinned(&self, hash: &BlockHash) -> bool {
+		self.pinned.contains_key(hash)
+	}
+
+	/// Returns the number of pinned states.
+	pub fn pinned_count(&self) -> usize {
+		self.pinned.len()
+	}
+
+	/// Keep the state of the block in the window until it is released with `unpin`
+	/// or a block with number `expires_at` is canonicalized. Calls are reference counted,
+	/// repeated calls extend the expiration. Must not be called while there are pending
+	/// canonicalizations or prunings. Adds changes to `commit`.
+	pub fn pin<D: MetaDb>(
+		&mut self,
+		hash: &BlockHash,
+		expires_at: u64,
+		db: &D,
+		commit: &mut CommitSet<Key>,
+	) -> Result<(), Error<D::Error>> {
+		if let Some(pin) = self.pinned.get_mut(hash) {
+			self.pending_pin_changes.push(PinChange::Updated(hash.clone(), pin.refs, pin.expires_at));
+			pin.refs += 1;
+			pin.expires_at = ::std::cmp::max(pin.expires_at, expires_at);
+			self.journal_pins(commit);
+			return Ok(());
+		}
+
+		let offset = match self.death_rows.iter().position(|r| r.hash == *hash) {
+			Some(offset) if offset >= self.pending_prunings => offset,
+			_ => return Err(Error::InvalidPin),
+		};
+		let mut state = PinnedState {
+			number: self.pending_number + offset as u64,
+			refs: 1,
+			expires_at,
+			inserted: Default::default(),
+			retained: Default::default(),
+			journal_len: 0,
+		};
+		for row in self.death_rows.iter().skip(offset + 1) {
+			let record = db.get_meta(&row.journal_key).map_err(|e| Error::Db(e))?.ok_or(Error::InvalidPin)?;
+			let record: JournalRecord<BlockHash, Key> = Decode::decode(&mut record.as_slice()).ok_or(Error::Decoding)?;
+			state.inserted.extend(record.inserted);
+		}
+		trace!(target: "state-db", "Pinning state {:?} ({} keys inserted after it)", hash, state.inserted.len());
+
+		let record = PinJournalRecord {
+			inserted: state.inserted.iter().cloned().collect(),
+			..Default::default()
+		};
+		state.journal(hash, record, commit, &mut self.pending_pin_changes);
+		self.pinned.insert(hash.clone(), state);
+		self.pending_pin_changes.push(PinChange::Pinned(hash.clone()));
+		self.journal_pins(commit);
+		Ok(())
+	}
+
+	/// Release the state pinned with `pin`. Adds changes to `commit`.
+	pub fn unpin(&mut self, hash: &BlockHash, commit: &mut CommitSet<Key>) {
+		match self.pinned.get_mut(hash) {
+			Some(pin) if pin.refs > 1 => {
+				self.pending_pin_changes.push(PinChange::Updated(hash.clone(), pin.refs, pin.expires_at));
+				pin.refs -= 1;
+			},
+			Some(_) => self.release(hash, commit),
+			None => return,
+		}
+		self.journal_pins(commit);
+	}
+
+	/// Release all pins that expire at given block.
+	fn expire_pins(&mut self, number: u64, commit: &mut CommitSet<Key>) {
+		let expired: Vec<_> = self.pinned.iter()
+			.filter(|(_, pin)| pin.expires_at <= number)
+			.map(|(hash, _)| hash.clone())
+			.collect();
+		if expired.is_empty() {
+			return;
+		}
+		for hash in expired {
+			trace!(target: "state-db", "Pinned state {:?} expired", hash);
+			self.release(&hash, commit);
+		}
+		self.journal_pins(commit);
+	}
+
+	/// Remove the pin regardless of its references, deleting the retained keys that are
+	/// not used by any other pinned state.
+	fn release(&mut self, hash: &BlockHash, commit: &mut CommitSet<Key>) {
+		let pin = match self.pinned.remove(hash) {
+			Some(pin) => pin,
+			None => return,
+		};
+		trace!(target: "state-db", "Releasing pinned state {:?} ({} retained)", hash, pin.retained.len());
+		for (key, dead) in pin.retained.iter() {
+			if *dead && !self.pinned.values().any(|other| other.retained.contains_key(key)) {
+				commit.data.deleted.push(key.clone());
+			}
+		}
+		for index in 0..pin.journal_len {
+			commit.meta.deleted.push(to_pin_journal_key(hash, index));
+		}
+		self.pending_pin_changes.push(PinChange::Released(hash.clone(), pin));
+	}
+
+	fn journal_pins(&self, commit: &mut CommitSet<Key>) {
+		let pins: Vec<_> = self.pinned.iter().map(|(hash, pin)| PinRecord {
+			hash: hash.clone(),
+			number: pin.number,
+			refs: pin.refs,
+			expires_at: pin.expires_at,
+		}).collect();
+		commit.meta.inserted.push((to_meta_key(PINNED_STATES, &()), pins.encode()));
+	}
+
 	/// Prune next block. Expects at least one block in the window. Adds changes to `commit`.
 	pub fn prune_one(&mut self, commit: &mut CommitSet<Key>) {
 		if let Some(pruned) = self.death_rows.get(self.pending_prunings) {
 			trace!(target: "state-db", "Pruning {:?} ({} deleted)", pruned.hash, pruned.deleted.len());
 			let index = self.pending_number + self.pending_prunings as u64;
-			commit.data.deleted.extend(pruned.deleted.iter().cloned());
+			let mut records: HashMap<BlockHash, PinJournalRecord<Key>> = HashMap::new();
+			for key in pruned.deleted.iter() {
+				let mut retained = false;
+				// the key is a part of the state of the pinned block if it has not been inserted after it
+				for (hash, pin) in self.pinned.iter_mut().filter(|(_, pin)| pin.number < index) {
+					if pin.retained.contains_key(key) || !pin.inserted.contains(key) {
+						let previous = pin.retained.insert(key.clone(), true);
+						self.pending_pin_changes.push(PinChange::Retained(hash.clone(), key.clone(), previous));
+						records.entry(hash.clone()).or_default().deleted.push(key.clone());
+						retained = true;
+					}
+				}
+				if !retained {
+					commit.data.deleted.push(key.clone());
+				}
+			}
+			for (hash, record) in records {
+				if let Some(pin) = self.pinned.get_mut(&hash) {
+					pin.journal(&hash, record, commit, &mut self.pending_pin_changes);
+				}
+			}
+			if self.is_checkpoint(index) {
+				self.checkpoint_blocks.insert(index, pruned.hash.clone());
+				self.pending_checkpoint_blocks.push(index);
+				let mut blocks: Vec<_> = self.checkpoint_blocks.iter().map(|(n, h)| (*n, h.clone())).collect();
+				blocks.sort_by_key(|(number, _)| *number);
+				commit.meta.inserted.push((to_meta_key(CHECKPOINT_BLOCKS, &()), blocks.encode()));
+			}
 			commit.meta.inserted.push((to_meta_key(LAST_PRUNED, &()), index.encode()));
 			commit.meta.deleted.push(pruned.journal_key.clone());
 			self.pending_prunings += 1;
@@ -153,6 +492,50 @@ impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 		}
 	}
 
+	/// Remove the nodes of the last checkpoint state from the nodes deleted by the block that is
+	/// about to be added to the window, and mark them as checkpoint nodes in `commit`.
+	/// `commit` must contain changes of this block only. Does nothing if checkpoints are disabled.
+	pub fn retain_checkpoint_nodes<D: MetaDb>(
+		&mut self,
+		commit: &mut CommitSet<Key>,
+		db: &D,
+	) -> Result<(), Error<D::Error>> {
+		let number = self.pending_number + self.death_rows.len() as u64;
+		if self.checkpoint_interval.is_none() {
+			return Ok(());
+		}
+
+		// the deleted nodes were a part of the parent state
+		let last_checkpoint = number.checked_sub(1).and_then(|parent| self.last_checkpoint(parent));
+		let deleted = ::std::mem::replace(&mut commit.data.deleted, Vec::new());
+		for key in deleted {
+			let inserted_after_checkpoint = match (self.checkpoint_index.get(&key), last_checkpoint) {
+				(Some(inserted), Some(last_checkpoint)) => *inserted > last_checkpoint,
+				(_, None) => true,
+				(None, Some(_)) => false,
+			};
+			if !inserted_after_checkpoint {
+				if self.pending_checkpoint_nodes.insert(key.clone()) {
+					trace!(target: "state-db", "Keeping checkpoint node {:?}", key);
+					commit.meta.inserted.push((to_checkpoint_node_key(&key), Vec::new()));
+				}
+			} else if last_checkpoint.is_some() && self.is_checkpoint_node(&key, db)? {
+				// a checkpoint node that has been re-inserted after the last checkpoint
+				trace!(target: "state-db", "Keeping re-inserted checkpoint node {:?}", key);
+			} else {
+				commit.data.deleted.push(key);
+			}
+		}
+
+		self.checkpoint_index.extend(commit.data.inserted.iter().map(|(key, _)| (key.clone(), number)));
+		Ok(())
+	}
+
+	fn is_checkpoint_node<D: MetaDb>(&self, key: &Key, db: &D) -> Result<bool, Error<D::Error>> {
+		Ok(self.pending_checkpoint_nodes.contains(key)
+			|| db.get_meta(&to_checkpoint_node_key(key)).map_err(|e| Error::Db(e))?.is_some())
+	}
+
 	/// Add a change set to the window. Creates a journal record and pushes it to `commit`
 	pub fn note_canonical(&mut self, hash: &BlockHash, commit: &mut CommitSet<Key>) {
 		trace!(target: "state-db", "Adding to pruning window: {:?} ({} inserted, {} deleted)", hash, commit.data.inserted.len(), commit.data.deleted.len());
@@ -166,13 +549,43 @@ impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 		let block = self.pending_number + self.death_rows.len() as u64;
 		let journal_key = to_journal_key(block);
 		commit.meta.inserted.push((journal_key.clone(), journal_record.encode()));
+		self.note_pinned_insertions(&journal_record.inserted, commit);
 		self.import(&journal_record.hash, journal_key, journal_record.inserted.into_iter(), journal_record.deleted);
 		self.pending_canonicalizations += 1;
+		self.expire_pins(block, commit);
+	}
+
+	/// Track the keys inserted by a new canonical block in the pinned states.
+	fn note_pinned_insertions(&mut self, inserted: &[Key], commit: &mut CommitSet<Key>) {
+		for (hash, pin) in self.pinned.iter_mut() {
+			let mut record = PinJournalRecord::default();
+			for key in inserted {
+				match pin.retained.get_mut(key) {
+					Some(dead) => if *dead {
+						self.pending_pin_changes.push(PinChange::Retained(hash.clone(), key.clone(), Some(true)));
+						*dead = false;
+						record.reinserted.push(key.clone());
+					},
+					None => if pin.inserted.insert(key.clone()) {
+						self.pending_pin_changes.push(PinChange::Inserted(hash.clone(), key.clone()));
+						record.inserted.push(key.clone());
+					},
+				}
+			}
+			pin.journal(hash, record, commit, &mut self.pending_pin_changes);
+		}
 	}
 
 	/// Apply all pending changes
 	pub fn apply_pending(&mut self) {
 		self.pending_canonicalizations = 0;
+		self.pending_checkpoint_nodes.clear();
+		self.pending_checkpoint_blocks.clear();
+		self.pending_pin_changes.clear();
+		let next_block = self.pending_number + self.death_rows.len() as u64;
+		if let Some(last_checkpoint) = next_block.checked_sub(1).and_then(|last| self.last_checkpoint(last)) {
+			self.checkpoint_index.retain(|_, block| *block > last_checkpoint);
+		}
 		for _ in 0 .. self.pending_prunings {
 			let pruned = self.death_rows.pop_front().expect("pending_prunings is always < death_rows.len()");
 			trace!(target: "state-db", "Applying pruning {:?} ({} deleted)", pruned.hash, pruned.deleted.len());
@@ -193,8 +606,44 @@ impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 		self.death_rows.truncate(self.death_rows.len() - self.pending_canonicalizations);
 		let new_max_block = self.death_rows.len() as u64 + self.pending_number;
 		self.death_index.retain(|_, block| *block < new_max_block);
+		self.checkpoint_index.retain(|_, block| *block < new_max_block);
+		self.pending_checkpoint_nodes.clear();
+		for number in self.pending_checkpoint_blocks.drain(..) {
+			self.checkpoint_blocks.remove(&number);
+		}
 		self.pending_canonicalizations = 0;
 		self.pending_prunings = 0;
+		self.revert_pin_changes();
+	}
+
+	fn revert_pin_changes(&mut self) {
+		let changes = ::std::mem::replace(&mut self.pending_pin_changes, Vec::new());
+		for change in changes.into_iter().rev() {
+			match change {
+				PinChange::Pinned(hash) => {
+					self.pinned.remove(&hash);
+				},
+				PinChange::Released(hash, pin) => {
+					self.pinned.insert(hash, pin);
+				},
+				PinChange::Updated(hash, refs, expires_at) => if let Some(pin) = self.pinned.get_mut(&hash) {
+					pin.refs = refs;
+					pin.expires_at = expires_at;
+				},
+				PinChange::Inserted(hash, key) => if let Some(pin) = self.pinned.get_mut(&hash) {
+					pin.inserted.remove(&key);
+				},
+				PinChange::Retained(hash, key, previous) => if let Some(pin) = self.pinned.get_mut(&hash) {
+					match previous {
+						Some(dead) => pin.retained.insert(key, dead),
+						None => pin.retained.remove(&key),
+					};
+				},
+				PinChange::Journaled(hash) => if let Some(pin) = self.pinned.get_mut(&hash) {
+					pin.journal_len -= 1;
+				},
+			}
+		}
 	}
 }
 
@@ -376,4 +825,126 @@ mod tests {
 		pruning.apply_pending();
 		assert_eq!(pruning.pending_number, 3);
 	}
+
+	fn canonicalize(pruning: &mut RefWindow<H256, H256>, db: &mut TestDb, inserted: &[u64], deleted: &[u64]) -> H256 {
+		let hash = H256::random();
+		let mut commit = make_commit(inserted, deleted);
+		pruning.note_canonical(&hash, &mut commit);
+		db.commit(&commit);
+		pruning.apply_pending();
+		hash
+	}
+
+	fn prune(pruning: &mut RefWindow<H256, H256>, db: &mut TestDb) {
+		let mut commit = CommitSet::default();
+		pruning.prune_one(&mut commit);
+		db.commit(&commit);
+		pruning.apply_pending();
+	}
+
+	fn check_pins(pruning: &RefWindow<H256, H256>, db: &TestDb) {
+		let restored: RefWindow<H256, H256> = RefWindow::new(db).unwrap();
+		assert_eq!(pruning.pinned.len(), restored.pinned.len());
+		for (hash, pin) in pruning.pinned.iter() {
+			let other = &restored.pinned[hash];
+			assert_eq!((pin.number, pin.refs, pin.expires_at), (other.number, other.refs, other.expires_at));
+			assert_eq!(pin.inserted, other.inserted);
+			assert_eq!(pin.retained, other.retained);
+			assert_eq!(pin.journal_len, other.journal_len);
+		}
+	}
+
+	#[test]
+	fn pinned_state_is_kept_while_pruning_continues() {
+		let mut db = make_db(&[1, 2, 3]);
+		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db).unwrap();
+		let h0 = canonicalize(&mut pruning, &mut db, &[4], &[1]);
+		canonicalize(&mut pruning, &mut db, &[5], &[2]);
+
+		let mut commit = CommitSet::default();
+		pruning.pin(&h0, 100, &db, &mut commit).unwrap();
+		db.commit(&commit);
+		pruning.apply_pending();
+		canonicalize(&mut pruning, &mut db, &[6], &[4]);
+		check_pins(&pruning, &db);
+
+		prune(&mut pruning, &mut db);
+		prune(&mut pruning, &mut db);
+		prune(&mut pruning, &mut db);
+		assert_eq!(pruning.pending_number, 3);
+		assert!(pruning.is_pinned(&h0));
+		assert!(db.data_eq(&make_db(&[2, 3, 4, 5, 6])));
+		check_pins(&pruning, &db);
+
+		let mut commit = CommitSet::default();
+		pruning.unpin(&h0, &mut commit);
+		db.commit(&commit);
+		pruning.apply_pending();
+		assert!(!pruning.is_pinned(&h0));
+		assert!(db.data_eq(&make_db(&[3, 5, 6])));
+		assert!(db.meta.keys().all(|key| !key.ends_with(super::PIN_JOURNAL)));
+		check_pins(&pruning, &db);
+	}
+
+	#[test]
+	fn pinned_state_keeps_reinserted_keys() {
+		let mut db = make_db(&[1, 2, 3]);
+		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db).unwrap();
+		let h0 = canonicalize(&mut pruning, &mut db, &[], &[]);
+		let mut commit = CommitSet::default();
+		pruning.pin(&h0, 100, &db, &mut commit).unwrap();
+		db.commit(&commit);
+		pruning.apply_pending();
+
+		canonicalize(&mut pruning, &mut db, &[], &[2]);
+		prune(&mut pruning, &mut db);
+		prune(&mut pruning, &mut db);
+		canonicalize(&mut pruning, &mut db, &[2], &[]);
+		check_pins(&pruning, &db);
+
+		let mut commit = CommitSet::default();
+		pruning.unpin(&h0, &mut commit);
+		db.commit(&commit);
+		pruning.apply_pending();
+		assert!(db.data_eq(&make_db(&[1, 2, 3])));
+	}
+
+	#[test]
+	fn pinned_state_expires() {
+		let mut db = make_db(&[1, 2, 3]);
+		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db).unwrap();
+		let h0 = canonicalize(&mut pruning, &mut db, &[4], &[1]);
+		let mut commit = CommitSet::default();
+		pruning.pin(&h0, 2, &db, &mut commit).unwrap();
+		pruning.pin(&h0, 1, &db, &mut commit).unwrap();
+		db.commit(&commit);
+		pruning.apply_pending();
+
+		canonicalize(&mut pruning, &mut db, &[5], &[2]);
+		prune(&mut pruning, &mut db);
+		prune(&mut pruning, &mut db);
+		assert!(db.data_eq(&make_db(&[2, 3, 4, 5])));
+		assert!(pruning.is_pinned(&h0));
+
+		canonicalize(&mut pruning, &mut db, &[], &[]);
+		assert!(!pruning.is_pinned(&h0));
+		assert!(db.data_eq(&make_db(&[3, 4, 5])));
+		check_pins(&pruning, &db);
+	}
+
+	#[test]
+	fn pin_reverts() {
+		let mut db = make_db(&[1, 2, 3]);
+		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db).unwrap();
+		let h0 = canonicalize(&mut pruning, &mut db, &[4], &[1]);
+		let mut commit = CommitSet::default();
+		pruning.pin(&h0, 100, &db, &mut commit).unwrap();
+		assert!(pruning.is_pinned(&h0));
+		pruning.revert_pending();
+		assert!(!pruning.is_pinned(&h0));
+
+		prune(&mut pruning, &mut db);
+		let mut commit = CommitSet::default();
+		assert!(pruning.pin(&h0, 100, &db, &mut commit).is_err());
+	}
 }
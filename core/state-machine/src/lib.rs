@@ -818,6 +818,27 @@ where
 	prove_child_read_on_trie_backend(trie_backend, storage_key, key)
 }
 
+/// Generate child storage read proof for multiple keys of the same child trie.
+///
+/// The proof contains the path to the child trie root in the top trie and the paths
+/// to all the `keys` in the child trie, so all the values can be checked against the
+/// block state root with `read_child_proof_check_keys`.
+pub fn prove_child_read_keys<B, H, I>(
+	mut backend: B,
+	storage_key: &[u8],
+	keys: I,
+) -> Result<Vec<Vec<u8>>, Box<dyn Error>>
+where
+	B: Backend<H>,
+	H: Hasher,
+	H::Out: Ord,
+	I: IntoIterator,
+	I::Item: AsRef<[u8]>,
+{
+	let trie_backend = backend.as_trie_backend()
+		.ok_or_else(|| Box::new(ExecutionError::UnableToGenerateProof) as Box<dyn Error>)?;
+	prove_child_read_keys_on_trie_backend(trie_backend, storage_key, keys)
+}
 
 /// Generate storage read proof on pre-created trie backend.
 pub fn prove_read_on_trie_backend<S, H>(
@@ -850,6 +871,28 @@ where
 	Ok((result, proving_backend.extract_proof()))
 }
 
+/// Generate child storage read proof for multiple keys on pre-created trie backend.
+pub fn prove_child_read_keys_on_trie_backend<S, H, I>(
+	trie_backend: &TrieBackend<S, H>,
+	storage_key: &[u8],
+	keys: I,
+) -> Result<Vec<Vec<u8>>, Box<dyn Error>>
+where
+	S: trie_backend_essence::TrieBackendStorage<H>,
+	H: Hasher,
+	H::Out: Ord,
+	I: IntoIterator,
+	I::Item: AsRef<[u8]>,
+{
+	let proving_backend = proving_backend::ProvingBackend::<_, H>::new(trie_backend);
+	for key in keys {
+		proving_backend
+			.child_storage(storage_key, key.as_ref())
+			.map_err(|e| Box::new(e) as Box<dyn Error>)?;
+	}
+	Ok(proving_backend.extract_proof())
+}
+
 /// Check storage read proof, generated by `prove_read` call.
 pub fn read_proof_check<H>(
 	root: H::Out,
@@ -879,6 +922,27 @@ where
 	read_child_proof_check_on_proving_backend(&proving_backend, storage_key, key)
 }
 
+/// Check child storage read proof, generated by `prove_child_read_keys` call.
+///
+/// Returns values of the `keys`, in the same order. Fails if any of the keys
+/// isn't covered by the proof.
+pub fn read_child_proof_check_keys<H, I>(
+	root: H::Out,
+	proof: Vec<Vec<u8>>,
+	storage_key: &[u8],
+	keys: I,
+) -> Result<Vec<Option<Vec<u8>>>, Box<dyn Error>>
+where
+	H: Hasher,
+	H::Out: Ord,
+	I: IntoIterator,
+	I::Item: AsRef<[u8]>,
+{
+	let proving_backend = create_proof_check_backend::<H>(root, proof)?;
+	keys.into_iter()
+		.map(|key| read_child_proof_check_on_proving_backend(&proving_backend, storage_key, key.as_ref()))
+		.collect()
+}
 
 /// Check storage read proof on pre-created proving backend.
 pub fn read_proof_check_on_proving_backend<H>(
@@ -1226,6 +1290,37 @@ mod tests {
 		assert_eq!(local_result2, None);
 	}
 
+	#[test]
+	fn prove_child_read_keys_and_check() {
+		let child_storage_key = b":child_storage:default:sub1";
+		let remote_backend = trie_backend::tests::test_trie();
+		let remote_root = remote_backend.storage_root(::std::iter::empty()).0;
+		let remote_proof = prove_child_read_keys(
+			remote_backend,
+			child_storage_key,
+			&[&b"value3"[..], &b"value4"[..], &b"value5"[..]],
+		).unwrap();
+
+		// all the keys are checked against the top trie root
+		assert_eq!(
+			read_child_proof_check_keys::<Blake2Hasher, _>(
+				remote_root,
+				remote_proof.clone(),
+				child_storage_key,
+				&[&b"value4"[..], &b"value3"[..], &b"value5"[..]],
+			).unwrap(),
+			vec![Some(vec![124]), Some(vec![142]), None],
+		);
+
+		// the proof is rejected for the other state root
+		assert!(read_child_proof_check_keys::<Blake2Hasher, _>(
+			Default::default(),
+			remote_proof,
+			child_storage_key,
+			&[&b"value3"[..]],
+		).is_err());
+	}
+
 	#[test]
 	fn cannot_change_changes_trie_config() {
 		assert!(new(
@@ -71,8 +71,10 @@ mod tests {
 
 	use runtime_io::with_externalities;
 	use primitives::{H256, Blake2Hasher};
-	use support::{impl_outer_origin, assert_ok};
-	use runtime_primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use support::{impl_outer_origin, assert_ok, parameter_types};
+	use runtime_primitives::{
+		Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header, weights::Weight,
+	};
 
 	impl_outer_origin! {
 		pub enum Origin for Test {}
@@ -83,6 +85,10 @@ mod tests {
 	// configuration traits of modules we want to use.
 	#[derive(Clone, Eq, PartialEq)]
 	pub struct Test;
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
 	impl system::Trait for Test {
 		type Origin = Origin;
 		type Index = u64;
@@ -93,6 +99,9 @@ mod tests {
 		type Lookup = IdentityLookup<Self::AccountId>;
 		type Header = Header;
 		type Event = ();
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
 	}
 	impl Trait for Test {
 		type Event = ();
@@ -19,9 +19,9 @@
 use primitives::{ed25519, sr25519, Pair, crypto::UncheckedInto};
 use node_primitives::{AccountId, AuraId, Balance};
 use node_runtime::{
-	AuraConfig, BalancesConfig, ContractsConfig, CouncilSeatsConfig, DemocracyConfig,
-	GrandpaConfig, IndicesConfig, SessionConfig, StakingConfig, SudoConfig,
-	SystemConfig, TimestampConfig,
+	AuraConfig, BalancesConfig, ContractsConfig, CouncilConfig, CouncilSeatsConfig, DemocracyConfig,
+	GrandpaConfig, ImOnlineConfig, IndicesConfig, SessionConfig, StakingConfig, SudoConfig,
+	SystemConfig, TechnicalCommitteeConfig, TimestampConfig, VestingConfig,
 	Perbill, SessionKeys, StakerStatus,
 	DAYS, DOLLARS, MILLICENTS, SECS_PER_BLOCK,
 };
@@ -114,7 +114,10 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
 		}),
 		session: Some(SessionConfig {
 			validators: initial_authorities.iter().map(|x| x.1.clone()).collect(),
-			keys: initial_authorities.iter().map(|x| (x.1.clone(), SessionKeys(x.2.clone(),x.2.clone()))).collect::<Vec<_>>(),
+			keys: initial_authorities.iter().map(|x| {
+				// Nodes sign their heartbeats with their ed25519 authority key.
+				(x.1.clone(), SessionKeys(x.2.clone(), x.2.clone(), x.2.clone().into()))
+			}).collect::<Vec<_>>(),
 		}),
 		staking: Some(StakingConfig {
 			current_era: 0,
@@ -128,6 +131,14 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
 			invulnerables: initial_authorities.iter().map(|x| x.1.clone()).collect(),
 		}),
 		democracy: Some(DemocracyConfig::default()),
+		collective_Instance1: Some(CouncilConfig {
+			members: vec![],
+			phantom: Default::default(),
+		}),
+		collective_Instance2: Some(TechnicalCommitteeConfig {
+			members: vec![],
+			phantom: Default::default(),
+		}),
 		council_seats: Some(CouncilSeatsConfig {
 			active_council: vec![],
 			presentation_duration: 1 * DAYS,
@@ -150,6 +161,12 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
 		grandpa: Some(GrandpaConfig {
 			authorities: initial_authorities.iter().map(|x| (x.3.clone(), 1)).collect(),
 		}),
+		vesting: Some(VestingConfig {
+			vesting: vec![],
+		}),
+		im_online: Some(ImOnlineConfig {
+			keys: initial_authorities.iter().map(|x| (x.1.clone(), x.2.clone().into())).collect(),
+		}),
 	}
 }
 
@@ -227,6 +244,10 @@ pub fn testnet_genesis(
 	const STASH: Balance = 100 * DOLLARS;
 
 	let council_desired_seats = (endowed_accounts.len() / 2 - initial_authorities.len()) as u32;
+	let council_members: Vec<AccountId> = endowed_accounts.iter()
+		.filter(|&endowed| initial_authorities.iter().find(|&(_, controller, ..)| controller == endowed).is_none())
+		.cloned()
+		.collect();
 
 	GenesisConfig {
 		system: Some(SystemConfig {
@@ -242,7 +263,10 @@ pub fn testnet_genesis(
 		}),
 		session: Some(SessionConfig {
 			validators: initial_authorities.iter().map(|x| x.1.clone()).collect(),
-			keys: initial_authorities.iter().map(|x| (x.1.clone(), SessionKeys(x.2.clone(), x.2.clone()))).collect::<Vec<_>>(),
+			keys: initial_authorities.iter().map(|x| {
+				// Nodes sign their heartbeats with their ed25519 authority key.
+				(x.1.clone(), SessionKeys(x.2.clone(), x.2.clone(), x.2.clone().into()))
+			}).collect::<Vec<_>>(),
 		}),
 		staking: Some(StakingConfig {
 			current_era: 0,
@@ -256,10 +280,16 @@ pub fn testnet_genesis(
 			invulnerables: initial_authorities.iter().map(|x| x.1.clone()).collect(),
 		}),
 		democracy: Some(DemocracyConfig::default()),
+		collective_Instance1: Some(CouncilConfig {
+			members: council_members.clone(),
+			phantom: Default::default(),
+		}),
+		collective_Instance2: Some(TechnicalCommitteeConfig {
+			members: council_members.clone(),
+			phantom: Default::default(),
+		}),
 		council_seats: Some(CouncilSeatsConfig {
-			active_council: endowed_accounts.iter()
-				.filter(|&endowed| initial_authorities.iter().find(|&(_, controller, ..)| controller == endowed).is_none())
-				.map(|a| (a.clone(), 1000000)).collect(),
+			active_council: council_members.into_iter().map(|a| (a, 1000000)).collect(),
 			presentation_duration: 10,
 			term_duration: 1000000,
 			desired_seats: council_desired_seats,
@@ -283,6 +313,12 @@ pub fn testnet_genesis(
 		grandpa: Some(GrandpaConfig {
 			authorities: initial_authorities.iter().map(|x| (x.3.clone(), 1)).collect(),
 		}),
+		vesting: Some(VestingConfig {
+			vesting: vec![],
+		}),
+		im_online: Some(ImOnlineConfig {
+			keys: initial_authorities.iter().map(|x| (x.1.clone(), x.2.clone().into())).collect(),
+		}),
 	}
 }
 
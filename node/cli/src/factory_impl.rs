@@ -23,13 +23,12 @@ use rand::rngs::StdRng;
 
 use parity_codec::Decode;
 use keyring::sr25519::Keyring;
-use node_primitives::Hash;
-use node_runtime::{Call, CheckedExtrinsic, UncheckedExtrinsic, BalancesCall};
+use node_runtime::{Call, CheckedExtrinsic, UncheckedExtrinsic, SignedExtra, BalancesCall};
 use primitives::sr25519;
 use primitives::crypto::Pair;
 use parity_codec::Encode;
 use sr_primitives::generic::Era;
-use sr_primitives::traits::{Block as BlockT, Header as HeaderT};
+use sr_primitives::traits::{Block as BlockT, Header as HeaderT, SignedExtension};
 use substrate_service::ServiceFactory;
 use transaction_factory::RuntimeAdapter;
 use transaction_factory::modes::Mode;
@@ -126,22 +125,30 @@ impl RuntimeAdapter for FactoryState<Number> {
 		key: &Self::Secret,
 		destination: &Self::AccountId,
 		amount: &Self::Number,
+		genesis_hash: &<Self::Block as BlockT>::Hash,
 		prior_block_hash: &<Self::Block as BlockT>::Hash,
 	) -> <Self::Block as BlockT>::Extrinsic {
 		let index = self.extract_index(&sender, prior_block_hash);
 		let phase = self.extract_phase(*prior_block_hash);
 
-		sign::<service::Factory, Self>(CheckedExtrinsic {
-			signed: Some((sender.clone(), index)),
-			function: Call::Balances(
+		sign::<service::Factory, Self>(CheckedExtrinsic::new_signed(
+			Call::Balances(
 				BalancesCall::transfer(
 					indices::address::Address::Id(
 						destination.clone().into()
 					),
 					(*amount).into()
 				)
-			)
-		}, key, &prior_block_hash, phase)
+			),
+			sender.clone(),
+			(
+				system::CheckGenesis::new(),
+				system::CheckEra::from(Era::mortal(256, phase)),
+				system::CheckNonce::from(index),
+				system::CheckWeight::new(),
+				balances::TakeFees::from(0),
+			),
+		), key, (genesis_hash.clone(), prior_block_hash.clone(), (), (), ()))
 	}
 
 	fn inherent_extrinsics(&self) -> InherentData {
@@ -229,29 +236,24 @@ fn gen_seed_bytes(seed: u64) -> [u8; 32] {
 fn sign<F: ServiceFactory, RA: RuntimeAdapter>(
 	xt: CheckedExtrinsic,
 	key: &sr25519::Pair,
-	prior_block_hash: &Hash,
-	phase: u64,
+	additional_signed: <SignedExtra as SignedExtension>::AdditionalSigned,
 ) -> <RA::Block as BlockT>::Extrinsic {
 	let s = match xt.signed {
-		Some((signed, index)) => {
-			let era = Era::mortal(256, phase);
-			let payload = (index.into(), xt.function, era, prior_block_hash);
-			let signature = payload.using_encoded(|b| {
-				if b.len() > 256 {
-					key.sign(&sr_io::blake2_256(b))
-				} else {
-					key.sign(b)
-				}
+		Some((signed, extra)) => {
+			let raw_payload = (xt.function, extra, additional_signed);
+			let signature = raw_payload.using_encoded(|payload| if payload.len() > 256 {
+				key.sign(&sr_io::blake2_256(payload))
+			} else {
+				key.sign(payload)
 			}).into();
-			UncheckedExtrinsic {
-				signature: Some((indices::address::Address::Id(signed), signature, payload.0, era)),
-				function: payload.1,
-			}
+			UncheckedExtrinsic::new_signed(
+				raw_payload.0,
+				indices::address::Address::Id(signed),
+				signature,
+				raw_payload.1,
+			)
 		}
-		None => UncheckedExtrinsic {
-			signature: None,
-			function: xt.function,
-		},
+		None => UncheckedExtrinsic::new_unsigned(xt.function),
 	};
 
 	let e = Encode::encode(&s);
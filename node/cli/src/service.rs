@@ -21,7 +21,9 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use balances_rpc::{Payment, PaymentApi};
 use client::{self, LongestChain};
+use contracts_rpc::{Contracts, ContractsApi};
 use consensus::{import_queue, start_aura, AuraImportQueue, SlotDuration};
 use grandpa::{self, FinalityProofProvider as GrandpaFinalityProofProvider};
 use node_executor;
@@ -31,7 +33,7 @@ use node_primitives::Block;
 use node_runtime::{GenesisConfig, RuntimeApi};
 use substrate_service::{
 	FactoryFullConfiguration, LightComponents, FullComponents, FullBackend,
-	FullClient, LightClient, LightBackend, FullExecutor, LightExecutor,
+	FullClient, LightClient, LightBackend, FullExecutor, LightExecutor, RpcExtension,
 	error::{Error as ServiceError},
 };
 use transaction_pool::{self, txpool::{Pool as TransactionPool}};
@@ -208,6 +210,18 @@ construct_service_factory! {
 		FinalityProofProvider = { |client: Arc<FullClient<Self>>| {
 			Ok(Some(Arc::new(GrandpaFinalityProofProvider::new(client.clone(), client)) as _))
 		}},
+		FullRpcExtensions = { |client: Arc<FullClient<Self>>| {
+			let mut io = RpcExtension::default();
+			io.extend_with(ContractsApi::to_delegate(Contracts::new(client.clone())));
+			io.extend_with(PaymentApi::to_delegate(Payment::new(client)));
+			io
+		}},
+		LightRpcExtensions = { |client: Arc<LightClient<Self>>| {
+			let mut io = RpcExtension::default();
+			io.extend_with(ContractsApi::to_delegate(Contracts::new(client.clone())));
+			io.extend_with(PaymentApi::to_delegate(Payment::new(client)));
+			io
+		}},
 	}
 }
 
@@ -219,7 +233,7 @@ mod tests {
 	use consensus_common::{Environment, Proposer, ImportBlock, BlockOrigin, ForkChoiceStrategy};
 	use node_primitives::DigestItem;
 	use node_runtime::{BalancesCall, Call, CENTS, UncheckedExtrinsic};
-	use parity_codec::{Compact, Encode, Decode};
+	use parity_codec::{Encode, Decode};
 	use primitives::{
 		crypto::Pair as CryptoPair, ed25519::Pair, blake2_256,
 		sr25519::Public as AddressPublic, H256,
@@ -266,18 +280,21 @@ mod tests {
 			}
 		};
 		let extrinsic_factory = |service: &SyncService<<Factory as service::ServiceFactory>::FullService>| {
+			let genesis_hash = service.client().genesis_hash();
 			let payload = (
-				0,
 				Call::Balances(BalancesCall::transfer(RawAddress::Id(bob.public().0.into()), 69.into())),
-				Era::immortal(),
-				service.client().genesis_hash()
+				(
+					system::CheckGenesis::new(),
+					system::CheckEra::from(Era::immortal()),
+					system::CheckNonce::from(0),
+					system::CheckWeight::new(),
+					balances::TakeFees::from(0),
+				),
+				(genesis_hash, genesis_hash, (), (), ()),
 			);
 			let signature = alice.sign(&payload.encode()).into();
 			let id = alice.public().0.into();
-			let xt = UncheckedExtrinsic {
-				signature: Some((RawAddress::Id(id), signature, payload.0, Era::immortal())),
-				function: payload.1,
-			}.encode();
+			let xt = UncheckedExtrinsic::new_signed(payload.0, RawAddress::Id(id), signature, payload.1).encode();
 			let v: Vec<u8> = Decode::decode(&mut xt.as_slice()).unwrap();
 			OpaqueExtrinsic(v)
 		};
@@ -357,19 +374,24 @@ mod tests {
 			let signer = charlie.clone();
 
 			let function = Call::Balances(BalancesCall::transfer(to.into(), amount));
-			let era = Era::immortal();
-			let raw_payload = (Compact(index), function, era, genesis_hash);
+			let extra = (
+				system::CheckGenesis::new(),
+				system::CheckEra::from(Era::immortal()),
+				system::CheckNonce::from(index),
+				system::CheckWeight::new(),
+				balances::TakeFees::from(0),
+			);
+			let raw_payload = (function, extra, (genesis_hash, genesis_hash, (), (), ()));
 			let signature = raw_payload.using_encoded(|payload| if payload.len() > 256 {
 				signer.sign(&blake2_256(payload)[..])
 			} else {
 				signer.sign(payload)
 			});
 			let xt = UncheckedExtrinsic::new_signed(
-				index,
-				raw_payload.1,
+				raw_payload.0,
 				from.into(),
 				signature.into(),
-				era,
+				raw_payload.1,
 			).encode();
 			let v: Vec<u8> = Decode::decode(&mut xt.as_slice()).unwrap();
 
@@ -42,21 +42,23 @@ mod tests {
 	use substrate_executor::{WasmExecutor, NativeExecutionDispatch};
 	use parity_codec::{Encode, Decode, Joiner};
 	use keyring::{AuthorityKeyring, AccountKeyring};
-	use runtime_support::{Hashable, StorageValue, StorageMap, traits::Currency};
+	use runtime_support::{Hashable, StorageValue, StorageMap, traits::{Currency, Get}};
 	use state_machine::{CodeExecutor, Externalities, TestExternalities as CoreTestExternalities};
 	use primitives::{twox_128, blake2_256, Blake2Hasher, ChangesTrieConfiguration, NeverNativeValue,
 		NativeOrEncoded};
-	use node_primitives::{Hash, BlockNumber, AccountId};
-	use runtime_primitives::traits::{Header as HeaderT, Hash as HashT};
-	use runtime_primitives::{generic::Era, ApplyOutcome, ApplyError, ApplyResult, Perbill};
+	use node_primitives::{Hash, BlockNumber, AccountId, Balance, Index};
+	use runtime_primitives::traits::{Header as HeaderT, Hash as HashT, Convert};
+	use runtime_primitives::{generic::Era, ApplyOutcome, ApplyError, ApplyResult, Fixed64, Perbill};
+	use runtime_primitives::weights::GetDispatchInfo;
 	use {balances, contracts, indices, staking, system, timestamp};
 	use contracts::ContractAddressFor;
 	use system::{EventRecord, Phase};
 	use node_runtime::{
-		Header, Block, UncheckedExtrinsic, CheckedExtrinsic, Call, Runtime, Balances,
+		Header, Block, UncheckedExtrinsic, CheckedExtrinsic, SignedExtra, Call, Runtime, Balances,
 		BuildStorage, GenesisConfig, BalancesConfig, SessionConfig, StakingConfig,
 		System, SystemConfig, GrandpaConfig, IndicesConfig, ContractsConfig, Event,
-		SessionKeys, Treasury, CENTS, DOLLARS, MILLICENTS
+		SessionKeys, Treasury, CENTS, DOLLARS, MILLICENTS, TransactionBaseFee, TransactionByteFee,
+		TransferFee, WeightToFee,
 	};
 	use wabt;
 	use primitives::map;
@@ -80,8 +82,6 @@ mod tests {
 
 	const GENESIS_HASH: [u8; 32] = [69u8; 32];
 
-	const TX_FEE: u128 = 3 * CENTS + 460 * MILLICENTS;
-
 	type TestExternalities<H> = CoreTestExternalities<H, u64>;
 
 	fn alice() -> AccountId {
@@ -108,36 +108,55 @@ mod tests {
 		AccountKeyring::Ferdie.into()
 	}
 
+	fn signed_extra(nonce: Index, tip: Balance) -> SignedExtra {
+		(
+			system::CheckGenesis::new(),
+			system::CheckEra::from(Era::mortal(256, 0)),
+			system::CheckNonce::from(nonce),
+			system::CheckWeight::new(),
+			balances::TakeFees::from(tip),
+		)
+	}
+
 	fn sign(xt: CheckedExtrinsic) -> UncheckedExtrinsic {
 		match xt.signed {
-			Some((signed, index)) => {
-				let era = Era::mortal(256, 0);
-				let payload = (index.into(), xt.function, era, GENESIS_HASH);
+			Some((signed, extra)) => {
+				let additional_signed = (GENESIS_HASH, GENESIS_HASH, (), (), ());
+				let raw_payload = (xt.function, extra, additional_signed);
 				let key = AccountKeyring::from_public(&signed).unwrap();
-				let signature = payload.using_encoded(|b| {
-					if b.len() > 256 {
-						key.sign(&runtime_io::blake2_256(b))
-					} else {
-						key.sign(b)
-					}
+				let signature = raw_payload.using_encoded(|payload| if payload.len() > 256 {
+					key.sign(&runtime_io::blake2_256(payload))
+				} else {
+					key.sign(payload)
 				}).into();
-				UncheckedExtrinsic {
-					signature: Some((indices::address::Address::Id(signed), signature, payload.0, era)),
-					function: payload.1,
-				}
+				UncheckedExtrinsic::new_signed(
+					raw_payload.0,
+					indices::address::Address::Id(signed),
+					signature,
+					raw_payload.1,
+				)
 			}
-			None => UncheckedExtrinsic {
-				signature: None,
-				function: xt.function,
-			},
+			None => UncheckedExtrinsic::new_unsigned(xt.function),
 		}
 	}
 
 	fn xt() -> UncheckedExtrinsic {
-		sign(CheckedExtrinsic {
-			signed: Some((alice(), 0)),
-			function: Call::Balances(balances::Call::transfer::<Runtime>(bob().into(), 69 * DOLLARS)),
-		})
+		sign(CheckedExtrinsic::new_signed(
+			Call::Balances(balances::Call::transfer::<Runtime>(bob().into(), 69 * DOLLARS)),
+			alice(),
+			signed_extra(0, 0),
+		))
+	}
+
+	/// The fees charged for a transfer of the given extrinsic with the given fee multiplier.
+	fn transfer_fee(extrinsic: &UncheckedExtrinsic, fee_multiplier: Fixed64) -> Balance {
+		let len = extrinsic.encode().len();
+		let weight = extrinsic.function.get_dispatch_info(len).weight;
+		let length_fee = TransactionByteFee::get() * len as Balance;
+		let weight_fee = WeightToFee::convert(weight);
+		TransactionBaseFee::get()
+			+ fee_multiplier.saturated_multiply_accumulate(length_fee + weight_fee)
+			+ TransferFee::get()
 	}
 
 	fn from_block_number(n: u64) -> Header {
@@ -251,7 +270,7 @@ mod tests {
 		assert!(r.is_ok());
 
 		runtime_io::with_externalities(&mut t, || {
-			assert_eq!(Balances::total_balance(&alice()), 42 * DOLLARS - 1 * TX_FEE);
+			assert_eq!(Balances::total_balance(&alice()), 42 * DOLLARS - transfer_fee(&xt(), Fixed64::default()));
 			assert_eq!(Balances::total_balance(&bob()), 69 * DOLLARS);
 		});
 	}
@@ -287,13 +306,14 @@ mod tests {
 		assert!(r.is_ok());
 
 		runtime_io::with_externalities(&mut t, || {
-			assert_eq!(Balances::total_balance(&alice()), 42 * DOLLARS - 1 * TX_FEE);
+			assert_eq!(Balances::total_balance(&alice()), 42 * DOLLARS - transfer_fee(&xt(), Fixed64::default()));
 			assert_eq!(Balances::total_balance(&bob()), 69 * DOLLARS);
 		});
 	}
 
 	fn to_session_keys(ring: &AuthorityKeyring) -> SessionKeys {
-		SessionKeys(ring.to_owned().into(), ring.to_owned().into())
+		let key: primitives::ed25519::Public = ring.to_owned().into();
+		SessionKeys(key.clone(), key.clone(), key.into())
 	}
 
 	fn new_test_ext(code: &[u8], support_changes_trie: bool) -> TestExternalities<Blake2Hasher> {
@@ -345,6 +365,8 @@ mod tests {
 				invulnerables: vec![alice(), bob(), charlie()],
 			}),
 			democracy: Some(Default::default()),
+			collective_Instance1: Some(Default::default()),
+			collective_Instance2: Some(Default::default()),
 			council_seats: Some(Default::default()),
 			timestamp: Some(Default::default()),
 			contracts: Some(ContractsConfig {
@@ -355,6 +377,8 @@ mod tests {
 			grandpa: Some(GrandpaConfig {
 				authorities: vec![],
 			}),
+			vesting: Some(Default::default()),
+			im_online: Some(Default::default()),
 		}.build_storage().unwrap().0);
 		ext.changes_trie_storage().insert(0, GENESIS_HASH.into(), Default::default());
 		ext
@@ -425,14 +449,12 @@ mod tests {
 			1,
 			GENESIS_HASH.into(),
 			vec![
-				CheckedExtrinsic {
-					signed: None,
-					function: Call::Timestamp(timestamp::Call::set(42)),
-				},
-				CheckedExtrinsic {
-					signed: Some((alice(), 0)),
-					function: Call::Balances(balances::Call::transfer(bob().into(), 69 * DOLLARS)),
-				},
+				CheckedExtrinsic::new_unsigned(Call::Timestamp(timestamp::Call::set(42))),
+				CheckedExtrinsic::new_signed(
+					Call::Balances(balances::Call::transfer(bob().into(), 69 * DOLLARS)),
+					alice(),
+					signed_extra(0, 0),
+				),
 			]
 		)
 	}
@@ -447,14 +469,12 @@ mod tests {
 			1,
 			GENESIS_HASH.into(),
 			vec![
-				CheckedExtrinsic {
-					signed: None,
-					function: Call::Timestamp(timestamp::Call::set(42)),
-				},
-				CheckedExtrinsic {
-					signed: Some((alice(), 0)),
-					function: Call::Balances(balances::Call::transfer(bob().into(), 69 * DOLLARS)),
-				},
+				CheckedExtrinsic::new_unsigned(Call::Timestamp(timestamp::Call::set(42))),
+				CheckedExtrinsic::new_signed(
+					Call::Balances(balances::Call::transfer(bob().into(), 69 * DOLLARS)),
+					alice(),
+					signed_extra(0, 0),
+				),
 			]
 		);
 		let block2 = construct_block(
@@ -462,18 +482,17 @@ mod tests {
 			2,
 			block1.1.clone(),
 			vec![
-				CheckedExtrinsic {
-					signed: None,
-					function: Call::Timestamp(timestamp::Call::set(52)),
-				},
-				CheckedExtrinsic {
-					signed: Some((bob(), 0)),
-					function: Call::Balances(balances::Call::transfer(alice().into(), 5 * DOLLARS)),
-				},
-				CheckedExtrinsic {
-					signed: Some((alice(), 1)),
-					function: Call::Balances(balances::Call::transfer(bob().into(), 15 * DOLLARS)),
-				}
+				CheckedExtrinsic::new_unsigned(Call::Timestamp(timestamp::Call::set(52))),
+				CheckedExtrinsic::new_signed(
+					Call::Balances(balances::Call::transfer(alice().into(), 5 * DOLLARS)),
+					bob(),
+					signed_extra(0, 0),
+				),
+				CheckedExtrinsic::new_signed(
+					Call::Balances(balances::Call::transfer(bob().into(), 15 * DOLLARS)),
+					alice(),
+					signed_extra(1, 0),
+				)
 			]
 		);
 
@@ -491,14 +510,12 @@ mod tests {
 			1,
 			GENESIS_HASH.into(),
 			vec![
-				CheckedExtrinsic {
-					signed: None,
-					function: Call::Timestamp(timestamp::Call::set(42)),
-				},
-				CheckedExtrinsic {
-					signed: Some((alice(), 0)),
-					function: Call::System(system::Call::remark(vec![0; 120000])),
-				}
+				CheckedExtrinsic::new_unsigned(Call::Timestamp(timestamp::Call::set(42))),
+				CheckedExtrinsic::new_signed(
+					Call::System(system::Call::remark(vec![0; 120000])),
+					alice(),
+					signed_extra(0, 0),
+				)
 			]
 		)
 	}
@@ -520,7 +537,7 @@ mod tests {
 		runtime_io::with_externalities(&mut t, || {
 			// block1 transfers from alice 69 to bob.
 			// -1 is the default fee
-			assert_eq!(Balances::total_balance(&alice()), 42 * DOLLARS - 1 * TX_FEE);
+			assert_eq!(Balances::total_balance(&alice()), 42 * DOLLARS - transfer_fee(&xt(), Fixed64::default()));
 			assert_eq!(Balances::total_balance(&bob()), 169 * DOLLARS);
 			let events = vec![
 				EventRecord {
@@ -547,6 +564,8 @@ mod tests {
 			assert_eq!(System::events(), events);
 		});
 
+		let fee_multiplier = runtime_io::with_externalities(&mut t, || System::next_fee_multiplier());
+
 		executor().call::<_, NeverNativeValue, fn() -> _>(
 			&mut t,
 			"Core_execute_block",
@@ -558,9 +577,14 @@ mod tests {
 		runtime_io::with_externalities(&mut t, || {
 			// bob sends 5, alice sends 15 | bob += 10, alice -= 10
 			// 111 - 69 - 10 = 32
-			assert_eq!(Balances::total_balance(&alice()), 32 * DOLLARS - 2 * TX_FEE);
+			// The transfers of block2 are charged with the fee multiplier computed in block1.
+			let fee = transfer_fee(&xt(), fee_multiplier);
+			assert_eq!(
+				Balances::total_balance(&alice()),
+				32 * DOLLARS - transfer_fee(&xt(), Fixed64::default()) - fee,
+			);
 			// 100 + 69 + 10 = 179
-			assert_eq!(Balances::total_balance(&bob()), 179 * DOLLARS - 1 * TX_FEE);
+			assert_eq!(Balances::total_balance(&bob()), 179 * DOLLARS - fee);
 			let events = vec![
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(0),
@@ -616,18 +640,25 @@ mod tests {
 
 		runtime_io::with_externalities(&mut t, || {
 			// block1 transfers from alice 69 to bob.
-			assert_eq!(Balances::total_balance(&alice()), 42 * DOLLARS - 1 * TX_FEE);
+			assert_eq!(Balances::total_balance(&alice()), 42 * DOLLARS - transfer_fee(&xt(), Fixed64::default()));
 			assert_eq!(Balances::total_balance(&bob()), 169 * DOLLARS);
 		});
 
+		let fee_multiplier = runtime_io::with_externalities(&mut t, || System::next_fee_multiplier());
+
 		WasmExecutor::new().call(&mut t, 8, COMPACT_CODE, "Core_execute_block", &block2.0).unwrap();
 
 		runtime_io::with_externalities(&mut t, || {
 			// bob sends 5, alice sends 15 | bob += 10, alice -= 10
 			// 111 - 69 - 10 = 32
-			assert_eq!(Balances::total_balance(&alice()), 32 * DOLLARS - 2 * TX_FEE);
+			// The transfers of block2 are charged with the fee multiplier computed in block1.
+			let fee = transfer_fee(&xt(), fee_multiplier);
+			assert_eq!(
+				Balances::total_balance(&alice()),
+				32 * DOLLARS - transfer_fee(&xt(), Fixed64::default()) - fee,
+			);
 			// 100 + 69 + 10 = 179
-			assert_eq!(Balances::total_balance(&bob()), 179 * DOLLARS - 1 * TX_FEE);
+			assert_eq!(Balances::total_balance(&bob()), 179 * DOLLARS - fee);
 		});
 	}
 
@@ -740,25 +771,23 @@ mod tests {
 			1,
 			GENESIS_HASH.into(),
 			vec![
-				CheckedExtrinsic {
-					signed: None,
-					function: Call::Timestamp(timestamp::Call::set(42)),
-				},
-				CheckedExtrinsic {
-					signed: Some((charlie(), 0)),
-					function: Call::Contracts(
+				CheckedExtrinsic::new_unsigned(Call::Timestamp(timestamp::Call::set(42))),
+				CheckedExtrinsic::new_signed(
+					Call::Contracts(
 						contracts::Call::put_code::<Runtime>(10_000, transfer_code)
 					),
-				},
-				CheckedExtrinsic {
-					signed: Some((charlie(), 1)),
-					function: Call::Contracts(
+					charlie(),
+					signed_extra(0, 0),
+				),
+				CheckedExtrinsic::new_signed(
+					Call::Contracts(
 						contracts::Call::create::<Runtime>(1 * DOLLARS, 10_000, transfer_ch, Vec::new())
 					),
-				},
-				CheckedExtrinsic {
-					signed: Some((charlie(), 2)),
-					function: Call::Contracts(
+					charlie(),
+					signed_extra(1, 0),
+				),
+				CheckedExtrinsic::new_signed(
+					Call::Contracts(
 						contracts::Call::call::<Runtime>(
 							indices::address::Address::Id(addr.clone()),
 							10,
@@ -766,7 +795,9 @@ mod tests {
 							vec![0x00, 0x01, 0x02, 0x03]
 						)
 					),
-				},
+					charlie(),
+					signed_extra(2, 0),
+				),
 			]
 		);
 
@@ -873,7 +904,7 @@ mod tests {
 		assert_eq!(r, Ok(ApplyOutcome::Success));
 
 		runtime_io::with_externalities(&mut t, || {
-			assert_eq!(Balances::total_balance(&alice()), 42 * DOLLARS - 1 * TX_FEE);
+			assert_eq!(Balances::total_balance(&alice()), 42 * DOLLARS - transfer_fee(&xt(), Fixed64::default()));
 			assert_eq!(Balances::total_balance(&bob()), 69 * DOLLARS);
 		});
 	}
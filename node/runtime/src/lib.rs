@@ -21,8 +21,10 @@
 #![recursion_limit="256"]
 
 use rstd::prelude::*;
+use parity_codec::Encode;
 use support::{
-	construct_runtime, parameter_types, traits::{SplitTwoWays, Currency, OnUnbalanced}
+	construct_runtime, parameter_types, EnumerableStorageMap,
+	traits::{SplitTwoWays, Currency, Get, OnUnbalanced, Contains},
 };
 use substrate_primitives::u32_trait::{_1, _2, _3, _4};
 use node_primitives::{
@@ -34,13 +36,15 @@ use client::{
 	block_builder::api::{self as block_builder_api, InherentData, CheckInherentsResult},
 	runtime_api as client_api, impl_runtime_apis
 };
-use runtime_primitives::{ApplyResult, generic, create_runtime_str};
+use runtime_primitives::{ApplyResult, Fixed64, generic, create_runtime_str};
 use runtime_primitives::transaction_validity::TransactionValidity;
+use runtime_primitives::weights::{Weight, GetDispatchInfo};
 use runtime_primitives::traits::{
-	BlakeTwo256, Block as BlockT, DigestFor, NumberFor, StaticLookup, Convert,
+	BlakeTwo256, Block as BlockT, DigestFor, NumberFor, StaticLookup, Convert, ConvertInto,
+	OnInitialize, OnFinalize, OffchainWorker,
 };
 use version::RuntimeVersion;
-use council::{motions as council_motions, VoteIndex};
+use council::VoteIndex;
 #[cfg(feature = "std")]
 use council::seats as council_seats;
 #[cfg(any(feature = "std", test))]
@@ -48,6 +52,10 @@ use version::NativeVersion;
 use substrate_primitives::OpaqueMetadata;
 use grandpa::{AuthorityId as GrandpaId, AuthorityWeight as GrandpaWeight};
 use finality_tracker::{DEFAULT_REPORT_LATENCY, DEFAULT_WINDOW_SIZE};
+use contracts_rpc_runtime_api::{
+	ContractExecResult, ContractFrameKind, ContractFrameTrace, ContractTraceEntry, ContractTraceResult,
+};
+use balances_rpc_runtime_api::RuntimeDispatchInfo;
 
 #[cfg(any(feature = "std", test))]
 pub use runtime_primitives::BuildStorage;
@@ -63,8 +71,8 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node"),
 	impl_name: create_runtime_str!("substrate-node"),
 	authoring_version: 10,
-	spec_version: 101,
-	impl_version: 101,
+	spec_version: 121,
+	impl_version: 121,
 	apis: RUNTIME_API_VERSIONS,
 };
 
@@ -103,6 +111,38 @@ pub const MINUTES: Moment = 60 / SECS_PER_BLOCK;
 pub const HOURS: Moment = MINUTES * 60;
 pub const DAYS: Moment = HOURS * 24;
 
+parameter_types! {
+	pub const MaximumBlockWeight: Weight = 4 * 1024 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	pub const TargetBlockFullness: Perbill = Perbill::from_percent(25);
+}
+
+/// Updates the fee multiplier depending on how far the weight of a block was from the target
+/// fullness.
+///
+/// With `diff` being the difference between the weight of the block and the target weight, as a
+/// fraction of the maximum block weight, and `v = 0.00004`, the fees of the next block are those of
+/// the current block times `1 + v * diff + (v * diff)^2 / 2`. Fees thus rise exponentially while
+/// blocks are fuller than the target and fall while they are emptier, but never drop below zero.
+pub struct FeeMultiplierUpdateHandler;
+
+impl Convert<(Weight, Fixed64), Fixed64> for FeeMultiplierUpdateHandler {
+	fn convert((block_weight, multiplier): (Weight, Fixed64)) -> Fixed64 {
+		let max_weight = MaximumBlockWeight::get();
+		let target_weight = TargetBlockFullness::get() * max_weight;
+		let diff = Fixed64::from_rational(block_weight as i64 - target_weight as i64, max_weight as u64);
+		let v = Fixed64::from_rational(4, 100_000);
+
+		let first_term = v.saturating_mul(diff);
+		let second_term = Fixed64::from_parts(first_term.saturating_mul(first_term).into_inner() / 2);
+		let factor = first_term.saturating_add(second_term);
+
+		// The multiplier `m` scales fees by `1 + m`, so `1 + m'` = `(1 + m) * (1 + factor)`.
+		let next = multiplier.saturating_add(factor).saturating_add(multiplier.saturating_mul(factor));
+		next.max(Fixed64::from_natural(-1))
+	}
+}
+
 impl system::Trait for Runtime {
 	type Origin = Origin;
 	type Index = Index;
@@ -113,10 +153,13 @@ impl system::Trait for Runtime {
 	type Lookup = Indices;
 	type Header = generic::Header<BlockNumber, BlakeTwo256>;
 	type Event = Event;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type FeeMultiplierUpdate = FeeMultiplierUpdateHandler;
 }
 
 impl aura::Trait for Runtime {
-	type HandleReport = aura::StakingSlasher<Runtime>;
+	type HandleReport = aura::OffenceReporter<Runtime, Offences>;
 	type AuthorityId = AuraId;
 }
 
@@ -133,6 +176,16 @@ parameter_types! {
 	pub const CreationFee: Balance = 1 * CENTS;
 	pub const TransactionBaseFee: Balance = 1 * CENTS;
 	pub const TransactionByteFee: Balance = 10 * MILLICENTS;
+	pub const WeightFeeCoefficient: Balance = 1_000;
+}
+
+/// Charges `WeightFeeCoefficient` per unit of weight.
+pub struct WeightToFee;
+
+impl Convert<Weight, Balance> for WeightToFee {
+	fn convert(weight: Weight) -> Balance {
+		Balance::from(weight).saturating_mul(WeightFeeCoefficient::get())
+	}
 }
 
 impl balances::Trait for Runtime {
@@ -148,6 +201,7 @@ impl balances::Trait for Runtime {
 	type CreationFee = CreationFee;
 	type TransactionBaseFee = TransactionBaseFee;
 	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = WeightToFee;
 }
 
 impl timestamp::Trait for Runtime {
@@ -159,12 +213,11 @@ parameter_types! {
 	pub const UncleGenerations: u64 = 0;
 }
 
-// TODO: #2986 implement this properly
 impl authorship::Trait for Runtime {
-	type FindAuthor = ();
+	type FindAuthor = session::FindAccountFromAuthorIndex<Self, Aura>;
 	type UncleGenerations = UncleGenerations;
 	type FilterUncle = ();
-	type EventHandler = ();
+	type EventHandler = Staking;
 }
 
 parameter_types! {
@@ -172,9 +225,9 @@ parameter_types! {
 	pub const Offset: BlockNumber = 0;
 }
 
-type SessionHandlers = (Grandpa, Aura);
+type SessionHandlers = (Grandpa, Aura, ImOnline);
 impl_opaque_keys! {
-	pub struct SessionKeys(grandpa::AuthorityId, AuraId);
+	pub struct SessionKeys(grandpa::AuthorityId, AuraId, im_online::ed25519::AuthorityId);
 }
 
 // NOTE: `SessionHandler` and `SessionKeys` are co-dependent: One key will be used for each handler.
@@ -184,16 +237,45 @@ impl_opaque_keys! {
 // should be easy, since OneSessionHandler trait provides the `Key` as an associated type. #2858
 
 impl session::Trait for Runtime {
-	type OnSessionEnding = Staking;
+	type OnSessionEnding = session::historical::NoteHistoricalRoot<Runtime, Staking>;
 	type SessionHandler = SessionHandlers;
 	type ShouldEndSession = session::PeriodicSessions<Period, Offset>;
 	type Event = Event;
 	type Keys = SessionKeys;
 }
 
+impl session::historical::Trait for Runtime {
+	type FullIdentification = staking::Exposure<AccountId, Balance>;
+	type FullIdentificationOf = staking::ExposureOf<Runtime>;
+}
+
+/// The layout of `SessionKeys` before the im-online key was added.
+type SessionKeysV0 = (grandpa::AuthorityId, AuraId);
+
+/// Upgrades the session keys of the current validators and the validator candidates to the
+/// current `SessionKeys` layout, once, at the start of the first block of the new runtime. This is
+/// the only place where the candidates are enumerated on-chain.
+///
+/// Nodes sign their heartbeats with the same ed25519 key they author blocks with, so the Aura key
+/// is used as the im-online key until the validator sets new keys.
+pub struct UpgradeSessionKeys;
+impl OnInitialize<BlockNumber> for UpgradeSessionKeys {
+	fn on_initialize(_n: BlockNumber) {
+		let accounts = Session::validators()
+			.into_iter()
+			.chain(<staking::Validators<Runtime>>::enumerate().map(|(stash, _)| stash));
+		Session::upgrade_keys::<SessionKeysV0, _, _>(1, accounts, |_, (grandpa, aura)| {
+			SessionKeys(grandpa, aura.clone(), aura.into())
+		});
+	}
+}
+impl OnFinalize<BlockNumber> for UpgradeSessionKeys {}
+impl OffchainWorker<BlockNumber> for UpgradeSessionKeys {}
+
 parameter_types! {
 	pub const SessionsPerEra: session::SessionIndex = 6;
 	pub const BondingDuration: staking::EraIndex = 24 * 28;
+	pub const SlashDeferDuration: staking::EraIndex = 24 * 7; // 1/4 the bonding duration.
 }
 
 pub struct CurrencyToVoteHandler;
@@ -219,6 +301,9 @@ impl staking::Trait for Runtime {
 	type Reward = ();
 	type SessionsPerEra = SessionsPerEra;
 	type BondingDuration = BondingDuration;
+	type SlashDeferDuration = SlashDeferDuration;
+	/// A super-majority of the council can cancel the slash.
+	type SlashCancelOrigin = collective::EnsureProportionAtLeast<_3, _4, AccountId, CouncilCollective>;
 }
 
 parameter_types! {
@@ -239,11 +324,17 @@ impl democracy::Trait for Runtime {
 	type VotingPeriod = VotingPeriod;
 	type EmergencyVotingPeriod = EmergencyVotingPeriod;
 	type MinimumDeposit = MinimumDeposit;
-	type ExternalOrigin = council_motions::EnsureProportionAtLeast<_1, _2, AccountId>;
-	type ExternalMajorityOrigin = council_motions::EnsureProportionAtLeast<_2, _3, AccountId>;
-	type EmergencyOrigin = council_motions::EnsureProportionAtLeast<_1, _1, AccountId>;
-	type CancellationOrigin = council_motions::EnsureProportionAtLeast<_2, _3, AccountId>;
-	type VetoOrigin = council_motions::EnsureMember<AccountId>;
+	/// A straight majority of the council can decide what their next motion is.
+	type ExternalOrigin = collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>;
+	/// A super-majority can have the next scheduled referendum be a straight majority-carries vote.
+	type ExternalMajorityOrigin = collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+	/// A unanimous council can have an emergency referendum.
+	type EmergencyOrigin = collective::EnsureProportionAtLeast<_1, _1, AccountId, CouncilCollective>;
+	// To cancel a proposal which has been passed, 2/3 of the council must agree to it.
+	type CancellationOrigin = collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+	// Any single council member may veto a coming council proposal, however they can
+	// only do it once and it lasts only for the cooloff period.
+	type VetoOrigin = collective::EnsureMember<AccountId, CouncilCollective>;
 	type CooloffPeriod = CooloffPeriod;
 }
 
@@ -259,13 +350,25 @@ parameter_types! {
 	pub const DecayRatio: u32 = 0;
 }
 
+parameter_types! {
+	pub const CouncilMotionDuration: BlockNumber = 5 * DAYS;
+}
+
+type CouncilCollective = collective::Instance1;
+impl collective::Trait<CouncilCollective> for Runtime {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = CouncilMotionDuration;
+}
+
 impl council::Trait for Runtime {
 	type Event = Event;
 	type BadPresentation = ();
 	type BadReaper = ();
 	type BadVoterIndex = ();
 	type LoserCandidate = ();
-	type OnMembersChanged = CouncilMotions;
+	type ChangeMembers = Council;
 	type CandidacyBond = CandidacyBond;
 	type VotingBond = VotingBond;
 	type VotingFee = VotingFee;
@@ -276,10 +379,16 @@ impl council::Trait for Runtime {
 	type DecayRatio = DecayRatio;
 }
 
-impl council::motions::Trait for Runtime {
+parameter_types! {
+	pub const TechnicalMotionDuration: BlockNumber = 5 * DAYS;
+}
+
+type TechnicalCollective = collective::Instance2;
+impl collective::Trait<TechnicalCollective> for Runtime {
 	type Origin = Origin;
 	type Proposal = Call;
 	type Event = Event;
+	type MotionDuration = TechnicalMotionDuration;
 }
 
 parameter_types! {
@@ -291,8 +400,8 @@ parameter_types! {
 
 impl treasury::Trait for Runtime {
 	type Currency = Balances;
-	type ApproveOrigin = council_motions::EnsureMembers<_4, AccountId>;
-	type RejectOrigin = council_motions::EnsureMembers<_2, AccountId>;
+	type ApproveOrigin = collective::EnsureMembers<_4, AccountId, CouncilCollective>;
+	type RejectOrigin = collective::EnsureMembers<_2, AccountId, CouncilCollective>;
 	type Event = Event;
 	type MintedForSpending = ();
 	type ProposalRejection = ();
@@ -314,6 +423,7 @@ parameter_types! {
 	pub const ContractTransactionBaseFee: Balance = 1 * CENTS;
 	pub const ContractTransactionByteFee: Balance = 10 * MILLICENTS;
 	pub const ContractFee: Balance = 1 * CENTS;
+	pub const ContractCodeDepositPerByte: Balance = 1 * MILLICENTS;
 	pub const CallBaseFee: Gas = 1000;
 	pub const CreateBaseFee: Gas = 1000;
 	pub const MaxDepth: u32 = 1024;
@@ -343,6 +453,84 @@ impl contracts::Trait for Runtime {
 	type CreateBaseFee = CreateBaseFee;
 	type MaxDepth = MaxDepth;
 	type BlockGasLimit = BlockGasLimit;
+	type RuntimeStorageWhitelist = ContractsRuntimeStorageWhitelist;
+	type CodeDepositPerByte = ContractCodeDepositPerByte;
+	type CallFilter = ContractsCallFilter;
+	type ChainExtension = ();
+}
+
+/// The runtime storage entries that contracts may read with `ext_get_runtime_storage`.
+///
+/// At the moment this is only the total issuance of the balances module.
+pub struct ContractsRuntimeStorageWhitelist;
+impl Contains<Vec<u8>> for ContractsRuntimeStorageWhitelist {
+	fn contains(key: &Vec<u8>) -> bool {
+		key[..] == runtime_io::twox_128(<balances::TotalIssuance<Runtime>>::key())[..]
+	}
+}
+
+/// The calls that contracts may dispatch with `ext_dispatch_call`.
+///
+/// At the moment contracts are only allowed to transfer balances.
+pub struct ContractsCallFilter;
+impl Contains<Call> for ContractsCallFilter {
+	fn contains(call: &Call) -> bool {
+		match call {
+			Call::Balances(balances::Call::transfer(..)) => true,
+			_ => false,
+		}
+	}
+}
+
+parameter_types! {
+	pub const MinVestedTransfer: Balance = 100 * DOLLARS;
+}
+
+impl vesting::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type BlockNumberToBalance = ConvertInto;
+	type MinVestedTransfer = MinVestedTransfer;
+}
+
+/// Submits unsigned extrinsics to the transaction pool from offchain workers.
+pub struct SubmitTransaction;
+
+impl system::offchain::SubmitUnsignedTransaction<Call> for SubmitTransaction {
+	fn submit_unsigned(call: Call) -> Result<(), ()> {
+		runtime_io::submit_transaction(&UncheckedExtrinsic::new_unsigned(call))
+	}
+}
+
+impl im_online::Trait for Runtime {
+	type AuthorityId = im_online::ed25519::AuthorityId;
+	type AuthoritySignature = im_online::ed25519::AuthoritySignature;
+	type Event = Event;
+	type Call = Call;
+	type SubmitTransaction = SubmitTransaction;
+	type ReportUnresponsiveness = Offences;
+}
+
+parameter_types! {
+	// Offenders can be slashed for as long as their stake stays bonded.
+	pub const OffenceSlashingWindow: session::SessionIndex =
+		BondingDuration::get() * SessionsPerEra::get();
+}
+
+/// The index of the current session, as kept by the session module.
+pub struct CurrentSession;
+impl Get<session::SessionIndex> for CurrentSession {
+	fn get() -> session::SessionIndex {
+		Session::current_index()
+	}
+}
+
+impl offences::Trait for Runtime {
+	type Event = Event;
+	type IdentificationTuple = session::historical::IdentificationTuple<Self>;
+	type OnOffenceHandler = Staking;
+	type CurrentSession = CurrentSession;
+	type SlashingWindow = OffenceSlashingWindow;
 }
 
 impl sudo::Trait for Runtime {
@@ -380,14 +568,18 @@ construct_runtime!(
 		Session: session::{Module, Call, Storage, Event, Config<T>},
 		Staking: staking::{default, OfflineWorker},
 		Democracy: democracy::{Module, Call, Storage, Config, Event<T>},
-		Council: council::{Module, Call, Storage, Event<T>},
-		CouncilMotions: council_motions::{Module, Call, Storage, Event<T>, Origin<T>},
+		Council: collective::<Instance1>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
+		TechnicalCommittee: collective::<Instance2>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
+		Elections: council::{Module, Call, Storage, Event<T>},
 		CouncilSeats: council_seats::{Config<T>},
 		FinalityTracker: finality_tracker::{Module, Call, Inherent},
 		Grandpa: grandpa::{Module, Call, Storage, Config, Event},
 		Treasury: treasury::{Module, Call, Storage, Event<T>},
 		Contracts: contracts,
 		Sudo: sudo,
+		Vesting: vesting::{Module, Call, Storage, Event<T>, Config<T>},
+		ImOnline: im_online::{Module, Call, Storage, Event<T>, ValidateUnsigned, Config<T>},
+		Offences: offences::{Module, Call, Storage, Event},
 	}
 );
 
@@ -401,12 +593,71 @@ pub type Block = generic::Block<Header, UncheckedExtrinsic>;
 pub type SignedBlock = generic::SignedBlock<Block>;
 /// BlockId type as expected by this runtime.
 pub type BlockId = generic::BlockId<Block>;
+/// The signed extensions of a transaction, checked in this order.
+pub type SignedExtra = (
+	system::CheckGenesis<Runtime>,
+	system::CheckEra<Runtime>,
+	system::CheckNonce<Runtime>,
+	system::CheckWeight<Runtime>,
+	balances::TakeFees<Runtime>,
+);
 /// Unchecked extrinsic type as expected by this runtime.
-pub type UncheckedExtrinsic = generic::UncheckedMortalCompactExtrinsic<Address, Index, Call, Signature>;
+pub type UncheckedExtrinsic = generic::UncheckedExtensibleExtrinsic<Address, Index, Call, Signature, SignedExtra>;
 /// Extrinsic type that has already been checked.
-pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Index, Call>;
-/// Executive: handles dispatch to the various modules.
-pub type Executive = executive::Executive<Runtime, Block, system::ChainContext<Runtime>, Balances, Runtime, AllModules>;
+pub type CheckedExtrinsic = generic::CheckedExtensibleExtrinsic<AccountId, Index, Call, SignedExtra>;
+/// Executive: handles dispatch to the various modules, after upgrading the session keys.
+pub type Executive = executive::Executive<
+	Runtime,
+	Block,
+	system::ChainContext<Runtime>,
+	Runtime,
+	(UpgradeSessionKeys, AllModules),
+>;
+
+/// Converts the outcome of a contract call into the result returned by the `ContractsApi`.
+fn contract_exec_result(outcome: contracts::BareCallOutcome<Runtime>) -> ContractExecResult {
+	match outcome.result {
+		Ok(data) => ContractExecResult::Success {
+			data,
+			gas_consumed: outcome.gas_consumed,
+			events: outcome.events.iter().map(Encode::encode).collect(),
+		},
+		Err(message) => ContractExecResult::Error {
+			message: message.as_bytes().to_vec(),
+			gas_consumed: outcome.gas_consumed,
+		},
+	}
+}
+
+/// Converts an entry of a contract execution trace into the one returned by the `ContractsApi`.
+fn contract_trace_entry(entry: contracts::TraceEntry<AccountId, Balance>) -> ContractTraceEntry {
+	match entry {
+		contracts::TraceEntry::HostCall(host_call) => ContractTraceEntry::HostCall {
+			name: host_call.name.as_bytes().to_vec(),
+			args: host_call.args,
+			result: host_call.result,
+			trapped: host_call.trapped,
+			gas_left: host_call.gas_left,
+		},
+		contracts::TraceEntry::StorageRead { key, value } =>
+			ContractTraceEntry::StorageRead { key, value },
+		contracts::TraceEntry::StorageWrite { key, value } =>
+			ContractTraceEntry::StorageWrite { key, value },
+		contracts::TraceEntry::Frame(frame) => ContractTraceEntry::Frame(ContractFrameTrace {
+			kind: match frame.kind {
+				contracts::FrameKind::Call => ContractFrameKind::Call,
+				contracts::FrameKind::Instantiate => ContractFrameKind::Instantiate,
+			},
+			address: frame.address.encode(),
+			value: frame.value.encode(),
+			input_data: frame.input_data,
+			entries: frame.entries.into_iter().map(contract_trace_entry).collect(),
+			result: frame.result.map_err(|message| message.as_bytes().to_vec()),
+			gas_left: frame.gas_left,
+		}),
+		contracts::TraceEntry::Truncated => ContractTraceEntry::Truncated,
+	}
+}
 
 impl_runtime_apis! {
 	impl client_api::Core<Block> for Runtime {
@@ -481,6 +732,44 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl contracts_rpc_runtime_api::ContractsApi<Block, AccountId, Balance> for Runtime {
+		fn call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: u64,
+			input_data: Vec<u8>,
+		) -> ContractExecResult {
+			contract_exec_result(Contracts::bare_call(origin, dest, value, gas_limit, input_data))
+		}
+
+		fn trace_call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: u64,
+			input_data: Vec<u8>,
+		) -> ContractTraceResult {
+			let (outcome, trace) = Contracts::trace_call(origin, dest, value, gas_limit, input_data);
+			ContractTraceResult {
+				result: contract_exec_result(outcome),
+				trace: trace.into_iter().map(contract_trace_entry).collect(),
+			}
+		}
+	}
+
+	impl balances_rpc_runtime_api::TransactionPaymentApi<Block> for Runtime {
+		fn query_info(uxt: <Block as BlockT>::Extrinsic) -> RuntimeDispatchInfo {
+			let len = uxt.encode().len();
+			let info = uxt.function.get_dispatch_info(len);
+			RuntimeDispatchInfo {
+				weight: info.weight,
+				operational: info.is_operational(),
+				partial_fee: Balances::compute_fee(len as u32, info.weight),
+			}
+		}
+	}
+
 	impl consensus_aura::AuraApi<Block, AuraId> for Runtime {
 		fn slot_duration() -> u64 {
 			Aura::slot_duration()
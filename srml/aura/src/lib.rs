@@ -29,11 +29,17 @@
 //!
 //! - `slot_duration` - Determine the Aura slot-duration based on the Timestamp module configuration.
 //!
+//! The module also implements `FindAuthor`, recovering the index of a block's author among the
+//! current authorities from the slot number in the block's pre-runtime digest.
+//!
 //! ## Related Modules
 //!
 //! - [Staking](../srml_staking/index.html): The Staking module is called in Aura to enforce slashing
 //!  if validators miss a certain number of slots (see the [`StakingSlasher`](./struct.StakingSlasher.html)
 //!  struct and associated method).
+//! - [Offences](../srml_offences/index.html): Alternatively, the validators which missed their slots
+//!  can be reported as a [`SkippedSlotsOffence`](./struct.SkippedSlotsOffence.html) (see the
+//!  [`OffenceReporter`](./struct.OffenceReporter.html) struct).
 //! - [Timestamp](../srml_timestamp/index.html): The Timestamp module is used in Aura to track
 //! consensus rounds (via `slots`).
 //! - [Consensus](../srml_consensus/index.html): The Consensus module does not relate directly to Aura,
@@ -51,9 +57,15 @@
 pub use timestamp;
 
 use rstd::{result, prelude::*};
-use parity_codec::Encode;
-use srml_support::{decl_storage, decl_module, Parameter, storage::StorageValue};
-use primitives::{traits::{SaturatedConversion, Saturating, Zero, One, Member}, generic::DigestItem};
+use parity_codec::{Encode, Decode};
+use srml_support::{
+	decl_storage, decl_module, Parameter, storage::StorageValue, traits::FindAuthor,
+	traits::offence::{Offence, ReportOffence, Kind}, ConsensusEngineId,
+};
+use primitives::{
+	traits::{SaturatedConversion, Saturating, Zero, One, Member, Convert}, generic::DigestItem, Perbill,
+};
+use session::{SessionIndex, historical::IdentificationTuple};
 use timestamp::OnTimestampSet;
 #[cfg(feature = "std")]
 use timestamp::TimestampInherentData;
@@ -61,8 +73,6 @@ use inherents::{RuntimeString, InherentIdentifier, InherentData, ProvideInherent
 #[cfg(feature = "std")]
 use inherents::{InherentDataProviders, ProvideInherentData};
 use substrate_consensus_aura_primitives::{AURA_ENGINE_ID, ConsensusLog};
-#[cfg(feature = "std")]
-use parity_codec::Decode;
 
 mod mock;
 mod tests;
@@ -182,6 +192,25 @@ impl<T: Trait> Module<T> {
 	}
 }
 
+impl<T: Trait> FindAuthor<u32> for Module<T> {
+	fn find_author<'a, I>(digests: I) -> Option<u32> where
+		I: 'a + IntoIterator<Item=(ConsensusEngineId, &'a [u8])>
+	{
+		for (id, mut data) in digests.into_iter() {
+			if id == AURA_ENGINE_ID {
+				let slot_num = u64::decode(&mut data)?;
+				let author_count = Self::authorities().len() as u64;
+				if author_count == 0 {
+					return None
+				}
+				return Some((slot_num % author_count) as u32)
+			}
+		}
+
+		None
+	}
+}
+
 impl<T: Trait> session::OneSessionHandler<T::AccountId> for Module<T> {
 	type Key = T::AuthorityId;
 	fn on_new_session<'a, I: 'a>(changed: bool, validators: I)
@@ -286,6 +315,79 @@ impl<T: staking::Trait + Trait> HandleReport for StakingSlasher<T> {
 	}
 }
 
+/// An offence reported when authorities did not author a block in their slots.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct SkippedSlotsOffence<Offender> {
+	/// The first skipped slot; the time slot of the offence.
+	pub slot: u64,
+	/// The session in which the slots were skipped.
+	pub session_index: SessionIndex,
+	/// The size of the validator set in that session.
+	pub validator_set_count: u32,
+	/// The authorities of the skipped slots.
+	pub offenders: Vec<Offender>,
+}
+
+impl<Offender: Clone> Offence<Offender> for SkippedSlotsOffence<Offender> {
+	const ID: Kind = *b"aura:skippedslot";
+	type TimeSlot = u64;
+
+	fn offenders(&self) -> Vec<Offender> {
+		self.offenders.clone()
+	}
+
+	fn session_index(&self) -> SessionIndex {
+		self.session_index
+	}
+
+	fn validator_set_count(&self) -> u32 {
+		self.validator_set_count
+	}
+
+	fn time_slot(&self) -> Self::TimeSlot {
+		self.slot
+	}
+
+	/// Up to 1%, in proportion to the part of the validator set which skipped its slots.
+	fn slash_fraction(offenders_count: u32, validator_set_count: u32) -> Perbill {
+		Perbill::from_rational_approximation(offenders_count, validator_set_count.max(1).saturating_mul(100))
+	}
+}
+
+/// A type for reporting the authorities of skipped slots as an offence to `R`, usually the
+/// offences module.
+pub struct OffenceReporter<T, R>(::rstd::marker::PhantomData<(T, R)>);
+
+impl<T, R> HandleReport for OffenceReporter<T, R> where
+	T: Trait + session::historical::Trait,
+	R: ReportOffence<T::AccountId, IdentificationTuple<T>, SkippedSlotsOffence<IdentificationTuple<T>>>,
+{
+	fn handle_report(report: AuraReport) {
+		let validators = session::Module::<T>::validators();
+
+		let mut offenders = Vec::new();
+		report.punish(
+			validators.len(),
+			|idx, _| {
+				let v = validators[idx].clone();
+				if let Some(full_identification) = T::FullIdentificationOf::convert(v.clone()) {
+					offenders.push((v, full_identification));
+				}
+			}
+		);
+
+		if !offenders.is_empty() {
+			R::report_offence(vec![], SkippedSlotsOffence {
+				slot: report.start_slot as u64,
+				session_index: session::Module::<T>::current_index(),
+				validator_set_count: validators.len() as u32,
+				offenders,
+			});
+		}
+	}
+}
+
 impl<T: Trait> ProvideInherent for Module<T> {
 	type Call = timestamp::Call<T>;
 	type Error = MakeFatalError<RuntimeString>;
@@ -18,8 +18,8 @@
 
 #![cfg(test)]
 
-use primitives::{traits::IdentityLookup, testing::{Header, UintAuthorityId}};
-use srml_support::impl_outer_origin;
+use primitives::{Perbill, traits::IdentityLookup, testing::{Header, UintAuthorityId}, weights::Weight};
+use srml_support::{impl_outer_origin, parameter_types};
 use runtime_io;
 use substrate_primitives::{H256, Blake2Hasher};
 use crate::{Trait, Module, GenesisConfig};
@@ -32,6 +32,10 @@ impl_outer_origin!{
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Test;
 
+parameter_types! {
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
 impl system::Trait for Test {
 	type Origin = Origin;
 	type Index = u64;
@@ -42,6 +46,9 @@ impl system::Trait for Test {
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
 	type Event = ();
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type FeeMultiplierUpdate = ();
 }
 
 impl timestamp::Trait for Test {
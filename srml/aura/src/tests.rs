@@ -23,7 +23,12 @@ use crate::mock::{System, Aura, new_test_ext};
 use primitives::traits::Header;
 use runtime_io::with_externalities;
 use parking_lot::Mutex;
-use crate::{AuraReport, HandleReport};
+use crate::{AuraReport, HandleReport, SkippedSlotsOffence};
+use srml_support::traits::offence::Offence;
+use primitives::Perbill;
+use srml_support::traits::FindAuthor;
+use substrate_consensus_aura_primitives::AURA_ENGINE_ID;
+use parity_codec::Encode;
 
 #[test]
 fn aura_report_gets_skipped_correctly() {
@@ -90,3 +95,21 @@ fn aura_reports_offline() {
 		assert_eq!(SLASH_COUNTS.lock().as_slice(), &[0, 0, 1, 1]);
 	});
 }
+
+#[test]
+fn find_author_uses_the_slot_of_the_pre_runtime_digest() {
+	with_externalities(&mut new_test_ext(vec![0, 1, 2, 3]), || {
+		let slot = 6u64.encode();
+		assert_eq!(Aura::find_author(vec![(*b"othr", &slot[..]), (AURA_ENGINE_ID, &slot[..])]), Some(2));
+		assert_eq!(Aura::find_author(vec![(*b"othr", &slot[..])]), None);
+		assert_eq!(Aura::find_author(vec![(AURA_ENGINE_ID, &[1u8][..])]), None);
+	});
+}
+
+#[test]
+fn skipped_slots_slash_fraction_is_proportional_to_the_offenders() {
+	type O = SkippedSlotsOffence<u64>;
+	assert_eq!(O::slash_fraction(1, 10), Perbill::from_rational_approximation(1u32, 1000));
+	assert_eq!(O::slash_fraction(5, 10), Perbill::from_rational_approximation(5u32, 1000));
+	assert_eq!(O::slash_fraction(10, 10), Perbill::from_percent(1));
+}
@@ -326,6 +326,7 @@ mod tests {
 	use primitives::traits::{BlakeTwo256, IdentityLookup};
 	use primitives::testing::Header;
 	use primitives::generic::DigestItem;
+	use primitives::{Perbill, weights::Weight};
 	use srml_support::{parameter_types, impl_outer_origin, ConsensusEngineId};
 
 	impl_outer_origin!{
@@ -335,6 +336,10 @@ mod tests {
 	#[derive(Clone, Eq, PartialEq)]
 	pub struct Test;
 
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
 	impl system::Trait for Test {
 		type Origin = Origin;
 		type Index = u64;
@@ -345,6 +350,9 @@ mod tests {
 		type Lookup = IdentityLookup<Self::AccountId>;
 		type Header = Header;
 		type Event = ();
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
 	}
 
 	impl Trait for Test {
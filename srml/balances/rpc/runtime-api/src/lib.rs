@@ -0,0 +1,57 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition required by the transaction payment RPC.
+//!
+//! This API should be imported and implemented by the runtime, of a node that wants to use the
+//! `payment_queryInfo` RPC.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+
+use client::decl_runtime_apis;
+use codec::{Encode, Decode};
+
+/// The name of the runtime function backing `TransactionPaymentApi::query_info`, as seen by the
+/// executor.
+pub const QUERY_INFO_METHOD: &str = "TransactionPaymentApi_query_info";
+
+/// The information about an extrinsic that is relevant for the fee it pays.
+#[derive(Eq, PartialEq, Encode, Decode, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct RuntimeDispatchInfo {
+	/// The weight of the extrinsic.
+	pub weight: u32,
+	/// Whether the extrinsic is operational, i.e. may use the portion of the block reserved for
+	/// operational extrinsics.
+	pub operational: bool,
+	/// The fee the extrinsic would pay if it was included in the next block.
+	///
+	/// The fee is given in the smallest unit of the balance type of the runtime. It doesn't
+	/// include fees charged by the dispatched call itself, like the fee of a transfer.
+	pub partial_fee: u128,
+}
+
+decl_runtime_apis! {
+	/// The API to query the fees of extrinsics.
+	pub trait TransactionPaymentApi {
+		/// Returns the dispatch information and the fee of the given extrinsic.
+		///
+		/// The fee is computed against the state the API is called at. An unsigned extrinsic
+		/// doesn't pay a fee, but the fee it would pay if it was signed is returned anyway.
+		fn query_info(uxt: Block::Extrinsic) -> RuntimeDispatchInfo;
+	}
+}
@@ -0,0 +1,66 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Error helpers for Payment RPC module.
+
+use client;
+use jsonrpc_core as rpc;
+use log::warn;
+
+/// Payment RPC Result type.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Payment RPC errors.
+#[derive(Debug, derive_more::Display, derive_more::From)]
+pub enum Error {
+	/// Client error.
+	Client(client::error::Error),
+	/// The runtime returned something that isn't dispatch information.
+	#[display(fmt="Unable to decode the dispatch information")]
+	InvalidResult,
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Client(ref err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+/// Base error code for all payment errors.
+const BASE_ERROR: i64 = 6000;
+
+impl From<Error> for rpc::Error {
+	fn from(e: Error) -> Self {
+		match e {
+			Error::InvalidResult => rpc::Error {
+				code: rpc::ErrorCode::ServerError(BASE_ERROR + 1),
+				message: "Unable to decode the dispatch information".into(),
+				data: Some("The runtime probably doesn't implement the `TransactionPaymentApi`".into()),
+			},
+			e => {
+				warn!("Unknown error: {:?}", e);
+				rpc::Error {
+					code: rpc::ErrorCode::InternalError,
+					message: "Unknown error occured".into(),
+					data: Some(format!("{:?}", e).into()),
+				}
+			},
+		}
+	}
+}
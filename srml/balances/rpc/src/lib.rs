@@ -0,0 +1,117 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC interface for the transaction payment of the balances module.
+//!
+//! Nodes whose runtime implements the `TransactionPaymentApi` runtime API can add this to their
+//! RPC extensions.
+
+#![warn(missing_docs)]
+
+pub mod error;
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::Arc;
+
+use balances_rpc_runtime_api::{RuntimeDispatchInfo, QUERY_INFO_METHOD};
+use client::{self, Client, CallExecutor};
+use jsonrpc_derive::rpc;
+use log::trace;
+use parity_codec::Decode;
+use primitives::hexdisplay::HexDisplay;
+use primitives::{H256, Blake2Hasher, Bytes, U256};
+use sr_primitives::generic::BlockId;
+use sr_primitives::traits::Block as BlockT;
+use serde::{Serialize, Deserialize};
+use state_machine::{self, ExecutionStrategy};
+use self::error::{Error, Result};
+
+pub use self::gen_client::Client as PaymentClient;
+
+/// The information about an extrinsic that is relevant for the fee it pays.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcDispatchInfo {
+	/// The weight of the extrinsic.
+	pub weight: u32,
+	/// Whether the extrinsic is operational.
+	pub operational: bool,
+	/// The fee the extrinsic would pay if it was included in the next block, in the smallest unit
+	/// of the balance type of the runtime.
+	pub partial_fee: U256,
+}
+
+impl From<RuntimeDispatchInfo> for RpcDispatchInfo {
+	fn from(info: RuntimeDispatchInfo) -> Self {
+		RpcDispatchInfo {
+			weight: info.weight,
+			operational: info.operational,
+			partial_fee: info.partial_fee.into(),
+		}
+	}
+}
+
+/// Substrate transaction payment API
+#[rpc]
+pub trait PaymentApi<Hash> {
+	/// Returns the weight and the fee of an encoded extrinsic.
+	///
+	/// The fee is computed against the state of the given block (or the best block), including
+	/// the fee multiplier that adjusts fees to the fullness of recent blocks. The extrinsic
+	/// doesn't need to be valid, e.g. it may be unsigned.
+	#[rpc(name = "payment_queryInfo")]
+	fn query_info(&self, extrinsic: Bytes, at: Option<Hash>) -> Result<RpcDispatchInfo>;
+}
+
+/// Payment API implementation.
+pub struct Payment<B, E, Block: BlockT, RA> {
+	/// Substrate client.
+	client: Arc<Client<B, E, Block, RA>>,
+}
+
+impl<B, E, Block: BlockT, RA> Payment<B, E, Block, RA> {
+	/// Create new Payment API RPC handler.
+	pub fn new(client: Arc<Client<B, E, Block, RA>>) -> Self {
+		Self {
+			client,
+		}
+	}
+}
+
+impl<B, E, Block, RA> PaymentApi<Block::Hash> for Payment<B, E, Block, RA> where
+	Block: BlockT<Hash=H256> + 'static,
+	B: client::backend::Backend<Block, Blake2Hasher> + Send + Sync + 'static,
+	E: CallExecutor<Block, Blake2Hasher> + Send + Sync + 'static,
+	RA: Send + Sync + 'static,
+{
+	fn query_info(&self, extrinsic: Bytes, at: Option<Block::Hash>) -> Result<RpcDispatchInfo> {
+		let at = at.unwrap_or_else(|| self.client.info().chain.best_hash);
+
+		// The only argument of the runtime API is the extrinsic, which is passed as it is encoded.
+		trace!(target: "rpc", "Calling {} at {:?} ({})", QUERY_INFO_METHOD, at, HexDisplay::from(&extrinsic.0));
+		let return_data = self.client
+			.executor()
+			.call(
+				&BlockId::Hash(at),
+				QUERY_INFO_METHOD, &extrinsic.0, ExecutionStrategy::NativeElseWasm,
+				state_machine::NeverOffchainExt::new(),
+			)?;
+		let info = RuntimeDispatchInfo::decode(&mut &return_data[..]).ok_or(Error::InvalidResult)?;
+		Ok(info.into())
+	}
+}
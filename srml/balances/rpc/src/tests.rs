@@ -0,0 +1,43 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use assert_matches::assert_matches;
+use test_client::prelude::*;
+
+#[test]
+fn should_serialize_dispatch_info() {
+	let info: RpcDispatchInfo = RuntimeDispatchInfo {
+		weight: 146,
+		operational: false,
+		partial_fee: 1_000_000_000_000,
+	}.into();
+	assert_eq!(
+		serde_json::to_string(&info).unwrap(),
+		r#"{"weight":146,"operational":false,"partialFee":"0xe8d4a51000"}"#,
+	);
+}
+
+#[test]
+fn should_fail_if_runtime_has_no_payment_api() {
+	let client = Payment::new(Arc::new(test_client::new()));
+
+	assert_matches!(client.query_info(Bytes(vec![0]), None), Err(Error::Client(_)));
+	assert_matches!(
+		client.query_info(Bytes(vec![0]), Some(H256::repeat_byte(42))),
+		Err(Error::Client(_))
+	);
+}
@@ -56,7 +56,8 @@
 //! - **Lock:** A freeze on a specified amount of an account's free balance until a specified block number. Multiple
 //! locks always operate over the same funds, so they "overlay" rather than "stack".
 //! - **Vesting:** Similar to a lock, this is another, but independent, liquidity restriction that reduces linearly
-//! over time.
+//! over time. Vesting is now provided by the Vesting module; schedules set at genesis by this module are still
+//! enforced here until that module migrates them into locks.
 //!
 //! ### Implementations
 //!
@@ -77,6 +78,9 @@
 //! - [`IsDeadAccount`](../srml_system/trait.IsDeadAccount.html): Determiner to say whether a
 //! given account is unused.
 //!
+//! The Balances module also defines the [`TakeFees`](./struct.TakeFees.html) signed extension,
+//! which pays the fees of a transaction and an optional tip from the free balance of its sender.
+//!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
@@ -150,7 +154,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use rstd::prelude::*;
-use rstd::{cmp, result, mem};
+use rstd::{cmp, result, mem, convert::TryFrom};
 use parity_codec::{Codec, Encode, Decode};
 use srml_support::{StorageValue, StorageMap, Parameter, decl_event, decl_storage, decl_module};
 use srml_support::traits::{
@@ -161,8 +165,11 @@ use srml_support::traits::{
 use srml_support::{dispatch::Result, traits::Get};
 use primitives::traits::{
 	Zero, SimpleArithmetic, StaticLookup, Member, CheckedAdd, CheckedSub,
-	MaybeSerializeDebug, Saturating, Bounded
+	MaybeSerializeDebug, Saturating, Bounded, Convert, SignedExtension, LegacyExtension, SaturatedConversion,
 };
+use primitives::weights::{DispatchInfo, Weight};
+use primitives::transaction_validity::{ValidTransaction, TransactionPriority};
+use primitives::ApplyError;
 use system::{IsDeadAccount, OnNewAccount, ensure_signed};
 
 mod mock;
@@ -204,6 +211,9 @@ pub trait Subtrait<I: Instance = DefaultInstance>: system::Trait {
 
 	/// The fee to be paid for making a transaction; the per-byte portion.
 	type TransactionByteFee: Get<Self::Balance>;
+
+	/// Converts the weight of a transaction into the fee to be paid for it; the per-weight portion.
+	type WeightToFee: Convert<Weight, Self::Balance>;
 }
 
 pub trait Trait<I: Instance = DefaultInstance>: system::Trait {
@@ -247,6 +257,9 @@ pub trait Trait<I: Instance = DefaultInstance>: system::Trait {
 
 	/// The fee to be paid for making a transaction; the per-byte portion.
 	type TransactionByteFee: Get<Self::Balance>;
+
+	/// Converts the weight of a transaction into the fee to be paid for it; the per-weight portion.
+	type WeightToFee: Convert<Weight, Self::Balance>;
 }
 
 impl<T: Trait<I>, I: Instance> Subtrait<I> for T {
@@ -258,6 +271,7 @@ impl<T: Trait<I>, I: Instance> Subtrait<I> for T {
 	type CreationFee = T::CreationFee;
 	type TransactionBaseFee = T::TransactionBaseFee;
 	type TransactionByteFee = T::TransactionByteFee;
+	type WeightToFee = T::WeightToFee;
 }
 
 decl_event!(
@@ -314,6 +328,9 @@ decl_storage! {
 		}): T::Balance;
 
 		/// Information regarding the vesting of a given account.
+		///
+		/// Legacy: new schedules are kept by the Vesting module, which takes the schedules here
+		/// and turns them into locks. Until it does, they are enforced by this module.
 		pub Vesting get(vesting) build(|config: &GenesisConfig<T, I>| {
 			config.vesting.iter().filter_map(|&(ref who, begin, length)| {
 				let begin = <T::Balance as From<T::BlockNumber>>::from(begin);
@@ -744,6 +761,9 @@ impl<T: Subtrait<I>, I: Instance> system::Trait for ElevatedTrait<T, I> {
 	type Lookup = T::Lookup;
 	type Header = T::Header;
 	type Event = ();
+	type MaximumBlockWeight = T::MaximumBlockWeight;
+	type AvailableBlockRatio = T::AvailableBlockRatio;
+	type FeeMultiplierUpdate = T::FeeMultiplierUpdate;
 }
 impl<T: Subtrait<I>, I: Instance> Trait<I> for ElevatedTrait<T, I> {
 	type Balance = T::Balance;
@@ -758,6 +778,7 @@ impl<T: Subtrait<I>, I: Instance> Trait<I> for ElevatedTrait<T, I> {
 	type CreationFee = T::CreationFee;
 	type TransactionBaseFee = T::TransactionBaseFee;
 	type TransactionByteFee = T::TransactionByteFee;
+	type WeightToFee = T::WeightToFee;
 }
 
 impl<T: Trait<I>, I: Instance> Currency<T::AccountId> for Module<T, I>
@@ -887,16 +908,9 @@ where
 		reason: WithdrawReason,
 		liveness: ExistenceRequirement,
 	) -> result::Result<Self::NegativeImbalance, &'static str> {
-		if let Some(new_balance) = Self::free_balance(who).checked_sub(&value) {
-			if liveness == ExistenceRequirement::KeepAlive && new_balance < T::ExistentialDeposit::get() {
-				return Err("payment would kill account")
-			}
-			Self::ensure_can_withdraw(who, value, reason, new_balance)?;
-			Self::set_free_balance(who, new_balance);
-			Ok(NegativeImbalance::new(value))
-		} else {
-			Err("too few free funds in account")
-		}
+		let new_balance = Self::withdrawable(who, value, reason, liveness)?;
+		Self::set_free_balance(who, new_balance);
+		Ok(NegativeImbalance::new(value))
 	}
 
 	fn slash(
@@ -1127,10 +1141,47 @@ where
 	}
 }
 
+impl<T: Trait<I>, I: Instance> Module<T, I> {
+	/// The fee charged for a transaction of the given encoded length and weight.
+	///
+	/// The per-byte and the per-weight portions are adjusted by the fee multiplier of `system`,
+	/// so that they rise while blocks are congested. The base fee is charged as is.
+	pub fn compute_fee(encoded_len: u32, weight: Weight) -> T::Balance {
+		let len_fee = T::TransactionByteFee::get().saturating_mul(T::Balance::from(encoded_len));
+		let weight_fee = T::WeightToFee::convert(weight);
+		let adjusted_fee = <system::Module<T>>::next_fee_multiplier()
+			.saturated_multiply_accumulate(len_fee.saturating_add(weight_fee));
+		T::TransactionBaseFee::get().saturating_add(adjusted_fee)
+	}
+
+	/// Checks that `value` can be withdrawn from the free balance of `who`, without withdrawing it,
+	/// and returns the free balance that would be left.
+	fn withdrawable(
+		who: &T::AccountId,
+		value: T::Balance,
+		reason: WithdrawReason,
+		liveness: ExistenceRequirement,
+	) -> result::Result<T::Balance, &'static str> {
+		let new_balance = Self::free_balance(who).checked_sub(&value).ok_or("too few free funds in account")?;
+		if liveness == ExistenceRequirement::KeepAlive && new_balance < T::ExistentialDeposit::get() {
+			return Err("payment would kill account")
+		}
+		Self::ensure_can_withdraw(who, value, reason, new_balance)?;
+		Ok(new_balance)
+	}
+}
+
 impl<T: Trait<I>, I: Instance> MakePayment<T::AccountId> for Module<T, I> {
-	fn make_payment(transactor: &T::AccountId, encoded_len: usize) -> Result {
-		let encoded_len = T::Balance::from(encoded_len as u32);
-		let transaction_fee = T::TransactionBaseFee::get() + T::TransactionByteFee::get() * encoded_len;
+	type Balance = T::Balance;
+
+	fn make_payment(
+		transactor: &T::AccountId,
+		encoded_len: usize,
+		weight: Weight,
+		tip: Option<T::Balance>,
+	) -> Result {
+		let transaction_fee = Self::compute_fee(encoded_len as u32, weight)
+			.saturating_add(tip.unwrap_or_else(Zero::zero));
 		let imbalance = Self::withdraw(
 			transactor,
 			transaction_fee,
@@ -1142,6 +1193,85 @@ impl<T: Trait<I>, I: Instance> MakePayment<T::AccountId> for Module<T, I> {
 	}
 }
 
+/// Signed extension that pays the fee of a transaction and a tip from the free balance of its
+/// sender. The tip buys priority in the transaction queue.
+///
+/// The fee and the tip are only withdrawn once the checks of all extensions of the transaction
+/// passed.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct TakeFees<T: Trait>(#[codec(compact)] pub T::Balance);
+
+impl<T: Trait> TakeFees<T> {
+	/// Creates a new instance of the extension for the given tip.
+	pub fn from(tip: T::Balance) -> Self {
+		TakeFees(tip)
+	}
+
+	/// Checks that `who` can pay the fee and the tip of a transaction with the given dispatch info
+	/// and length.
+	fn check_payment(&self, who: &T::AccountId, info: DispatchInfo, len: usize) -> result::Result<(), ApplyError> {
+		let fee = <Module<T>>::compute_fee(len as u32, info.weight).saturating_add(self.0);
+		<Module<T>>::withdrawable(who, fee, WithdrawReason::TransactionPayment, ExistenceRequirement::KeepAlive)
+			.map(|_| ())
+			.map_err(|_| ApplyError::CantPay)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<T: Trait> rstd::fmt::Debug for TakeFees<T> {
+	fn fmt(&self, f: &mut rstd::fmt::Formatter) -> rstd::fmt::Result {
+		write!(f, "TakeFees({:?})", self.0)
+	}
+}
+
+impl<T: Trait> SignedExtension for TakeFees<T> {
+	type AccountId = T::AccountId;
+	type AdditionalSigned = ();
+
+	fn additional_signed(&self) -> result::Result<(), &'static str> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		info: DispatchInfo,
+		len: usize,
+	) -> result::Result<ValidTransaction, ApplyError> {
+		self.check_payment(who, info, len)?;
+
+		Ok(ValidTransaction {
+			priority: self.0.saturated_into::<TransactionPriority>(),
+			..Default::default()
+		})
+	}
+
+	fn pre_dispatch(
+		&self,
+		who: &Self::AccountId,
+		info: DispatchInfo,
+		len: usize,
+	) -> result::Result<(), ApplyError> {
+		self.check_payment(who, info, len)
+	}
+
+	fn on_dispatch(&self, who: &Self::AccountId, info: DispatchInfo, len: usize) {
+		// Can't fail, `pre_dispatch` checked the payment and the other extensions don't touch the
+		// free balance.
+		let _ = <Module<T> as MakePayment<T::AccountId>>::make_payment(who, len, info.weight, Some(self.0));
+	}
+
+	fn identifier() -> Vec<&'static str> {
+		vec!["TakeFees"]
+	}
+}
+
+impl<T: Trait, Index> LegacyExtension<Index> for TakeFees<T> {
+	fn from_legacy(_index: &Index, _era: primitives::generic::Era, tip: u128) -> Option<Self> {
+		T::Balance::try_from(tip).ok().map(Self::from)
+	}
+}
+
 impl<T: Trait<I>, I: Instance> IsDeadAccount<T::AccountId> for Module<T, I>
 where
 	T::Balance: MaybeSerializeDebug
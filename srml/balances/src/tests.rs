@@ -21,6 +21,7 @@
 use super::*;
 use mock::{Balances, ExtBuilder, Runtime, System};
 use runtime_io::with_externalities;
+use parity_codec::Compact;
 use srml_support::{
 	assert_noop, assert_ok, assert_err,
 	traits::{LockableCurrency, LockIdentifier, WithdrawReason, WithdrawReasons,
@@ -118,7 +119,7 @@ fn lock_reasons_should_work() {
 			"account liquidity restrictions prevent withdrawal"
 		);
 		assert_ok!(<Balances as ReservableCurrency<_>>::reserve(&1, 1));
-		assert_ok!(<Balances as MakePayment<_>>::make_payment(&1, 1));
+		assert_ok!(<Balances as MakePayment<_>>::make_payment(&1, 1, 0, None));
 
 		Balances::set_lock(ID_1, &1, 10, u64::max_value(), WithdrawReason::Reserve.into());
 		assert_ok!(<Balances as Currency<_>>::transfer(&1, &2, 1));
@@ -126,13 +127,13 @@ fn lock_reasons_should_work() {
 			<Balances as ReservableCurrency<_>>::reserve(&1, 1),
 			"account liquidity restrictions prevent withdrawal"
 		);
-		assert_ok!(<Balances as MakePayment<_>>::make_payment(&1, 1));
+		assert_ok!(<Balances as MakePayment<_>>::make_payment(&1, 1, 0, None));
 
 		Balances::set_lock(ID_1, &1, 10, u64::max_value(), WithdrawReason::TransactionPayment.into());
 		assert_ok!(<Balances as Currency<_>>::transfer(&1, &2, 1));
 		assert_ok!(<Balances as ReservableCurrency<_>>::reserve(&1, 1));
 		assert_noop!(
-			<Balances as MakePayment<_>>::make_payment(&1, 1),
+			<Balances as MakePayment<_>>::make_payment(&1, 1, 0, None),
 			"account liquidity restrictions prevent withdrawal"
 		);
 	});
@@ -559,9 +560,95 @@ fn transfer_overflow_isnt_exploitable() {
 	);
 }
 
+#[test]
+fn compute_fee_works_with_weight_and_multiplier() {
+	with_externalities(
+		&mut ExtBuilder::default().transaction_fees(10, 1).weight_fee(2).build(),
+		|| {
+			// base + len * byte_fee + weight * weight_fee
+			assert_eq!(Balances::compute_fee(0, 0), 10);
+			assert_eq!(Balances::compute_fee(100, 0), 110);
+			assert_eq!(Balances::compute_fee(100, 50), 210);
+
+			// Everything but the base fee is adjusted by the multiplier.
+			system::NextFeeMultiplier::put(primitives::Fixed64::from_rational(1, 2));
+			assert_eq!(Balances::compute_fee(100, 50), 10 + 300);
+			system::NextFeeMultiplier::put(primitives::Fixed64::from_rational(-1, 4));
+			assert_eq!(Balances::compute_fee(100, 50), 10 + 150);
+		}
+	);
+}
+
+#[test]
+fn make_payment_charges_weight_fee() {
+	with_externalities(
+		&mut ExtBuilder::default().existential_deposit(1).monied(true).transaction_fees(0, 1).weight_fee(1).build(),
+		|| {
+			assert_eq!(Balances::free_balance(&2), 20);
+			assert_ok!(<Balances as MakePayment<_>>::make_payment(&2, 3, 5, None));
+			assert_eq!(Balances::free_balance(&2), 12);
+		}
+	);
+}
+
+#[test]
+fn make_payment_charges_tip() {
+	with_externalities(
+		&mut ExtBuilder::default().existential_deposit(1).monied(true).transaction_fees(1, 1).build(),
+		|| {
+			assert_eq!(Balances::free_balance(&4), 40);
+			assert_ok!(<Balances as MakePayment<_>>::make_payment(&4, 3, 0, Some(5)));
+			// base fee + byte fee + tip
+			assert_eq!(Balances::free_balance(&4), 40 - 1 - 3 - 5);
+			// The tip isn't subject to the fee multiplier.
+			system::NextFeeMultiplier::put(primitives::Fixed64::from_rational(1, 1));
+			assert_ok!(<Balances as MakePayment<_>>::make_payment(&4, 3, 0, Some(5)));
+			assert_eq!(Balances::free_balance(&4), 31 - 1 - 6 - 5);
+			assert_noop!(
+				<Balances as MakePayment<_>>::make_payment(&4, 0, 0, Some(1_000)),
+				"too few free funds in account"
+			);
+		}
+	);
+}
+
+#[test]
+fn take_fees_charges_fee_and_tip() {
+	with_externalities(
+		&mut ExtBuilder::default().existential_deposit(1).monied(true).transaction_fees(1, 1).build(),
+		|| {
+			let info = DispatchInfo::default();
+			let validity = TakeFees::<Runtime>::from(5).validate(&4, info, 3).unwrap();
+			// The tip buys priority.
+			assert_eq!(validity.priority, 5);
+
+			// The checks don't withdraw anything.
+			assert_ok!(TakeFees::<Runtime>::from(5).pre_dispatch(&4, info, 3));
+			assert_eq!(Balances::free_balance(&4), 40);
+			TakeFees::<Runtime>::from(5).on_dispatch(&4, info, 3);
+			// base fee + byte fee + tip
+			assert_eq!(Balances::free_balance(&4), 40 - 1 - 3 - 5);
+
+			assert_eq!(TakeFees::<Runtime>::from(1_000).validate(&4, info, 0), Err(ApplyError::CantPay));
+			assert_eq!(
+				TakeFees::<Runtime>::from(1_000).pre_dispatch(&4, info, 0),
+				Err(ApplyError::CantPay),
+			);
+			assert_eq!(Balances::free_balance(&4), 31);
+		}
+	);
+}
+
+#[test]
+fn take_fees_encodes_the_tip_as_compact() {
+	assert_eq!(TakeFees::<Runtime>::from(0).encode(), vec![0]);
+	assert_eq!(TakeFees::<Runtime>::from(1_000).encode(), Compact(1_000u64).encode());
+	assert_eq!(TakeFees::<Runtime>::decode(&mut &[4u8][..]), Some(TakeFees::from(1)));
+}
+
 #[test]
 fn check_vesting_status() {
-		with_externalities(
+	with_externalities(
 		&mut ExtBuilder::default()
 			.existential_deposit(256)
 			.monied(true)
@@ -0,0 +1,853 @@
+// Copyright 2017-2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Collective system: Members of a set of account IDs can make their collective feelings known
+//! through dispatched calls from one of two specialised origins.
+//!
+//! The membership can be provided in one of two ways: either directly, using the Root-dispatchable
+//! function `set_members`, or indirectly, through implementing the `ChangeMembers` trait (e.g. by
+//! an election module).
+//!
+//! A "prime" member may be set, whose vote acts as the default vote of any member who abstains
+//! from a motion once its `MotionDuration` has passed and it is closed.
+//!
+//! The module is instantiable, so a runtime may host several independent collectives (e.g. a
+//! council and a technical committee), each with its own members, proposals and origin.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![recursion_limit="128"]
+
+use rstd::{prelude::*, result};
+use substrate_primitives::u32_trait::Value as U32;
+use primitives::traits::{Hash, EnsureOrigin};
+use srml_support::{
+	dispatch::{Dispatchable, Parameter}, codec::{Encode, Decode}, traits::{ChangeMembers, Get},
+	StorageValue, StorageMap, decl_module, decl_event, decl_storage, ensure
+};
+use system::{self, ensure_signed};
+
+/// Simple index type for proposal counting.
+pub type ProposalIndex = u32;
+/// A number of members.
+///
+/// This also serves as a number of voting members, and since for motions, each member may
+/// vote exactly once, therefore also the number of votes for any given motion.
+pub type MemberCount = u32;
+
+pub trait Trait<I: Instance = DefaultInstance>: system::Trait {
+	/// The outer origin type.
+	type Origin: From<RawOrigin<Self::AccountId, I>>;
+
+	/// The outer call dispatch type.
+	type Proposal: Parameter + Dispatchable<Origin=<Self as Trait<I>>::Origin>;
+
+	/// The outer event type.
+	type Event: From<Event<Self, I>> + Into<<Self as system::Trait>::Event>;
+
+	/// The time after which a motion may be closed, counting abstentions as the prime's vote.
+	type MotionDuration: Get<Self::BlockNumber>;
+}
+
+/// Origin for the collective module.
+#[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum RawOrigin<AccountId, I> {
+	/// It has been condoned by a given number of members of the collective from a given total.
+	Members(MemberCount, MemberCount),
+	/// It has been condoned by a single member of the collective.
+	Member(AccountId),
+	/// Dummy to manage the fact we have instancing.
+	_Phantom(rstd::marker::PhantomData<I>),
+}
+
+/// Origin for the collective module.
+pub type Origin<T, I=DefaultInstance> = RawOrigin<<T as system::Trait>::AccountId, I>;
+
+#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+/// Info for keeping track of a motion being voted on.
+pub struct Votes<AccountId, BlockNumber> {
+	/// The proposal's unique index.
+	index: ProposalIndex,
+	/// The number of approval votes that are needed to pass the motion.
+	threshold: MemberCount,
+	/// The current set of voters that approved it.
+	ayes: Vec<AccountId>,
+	/// The current set of voters that rejected it.
+	nays: Vec<AccountId>,
+	/// The hard end time of this vote.
+	end: BlockNumber,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait<I>, I: Instance=DefaultInstance> as Collective {
+		/// The hashes of the active proposals.
+		pub Proposals get(proposals): Vec<T::Hash>;
+		/// Actual proposal for a given hash, if it's current.
+		pub ProposalOf get(proposal_of): map T::Hash => Option<<T as Trait<I>>::Proposal>;
+		/// Votes on a given proposal, if it is ongoing.
+		pub Voting get(voting): map T::Hash => Option<Votes<T::AccountId, T::BlockNumber>>;
+		/// Proposals so far.
+		pub ProposalCount get(proposal_count): u32;
+		/// The current members of the collective. This is stored sorted (just by value).
+		pub Members get(members) build(|config: &GenesisConfig<T, I>| {
+			let mut members = config.members.clone();
+			members.sort();
+			members
+		}): Vec<T::AccountId>;
+		/// The member who provides the default vote for any other members that do not vote before
+		/// the timeout. If `None`, then no member has that privilege.
+		pub Prime get(prime): Option<T::AccountId>;
+	}
+	add_extra_genesis {
+		config(phantom): rstd::marker::PhantomData<I>;
+		config(members): Vec<T::AccountId>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T, I=DefaultInstance> where
+		<T as system::Trait>::Hash,
+		<T as system::Trait>::AccountId
+	{
+		/// A motion (given hash) has been proposed (by given account) with a threshold (given
+		/// `MemberCount`).
+		Proposed(AccountId, ProposalIndex, Hash, MemberCount),
+		/// A motion (given hash) has been voted on by given account, leaving
+		/// a tally (yes votes and no votes given respectively as `MemberCount`).
+		Voted(AccountId, Hash, bool, MemberCount, MemberCount),
+		/// A motion was approved by the required threshold.
+		Approved(Hash),
+		/// A motion was not approved by the required threshold.
+		Disapproved(Hash),
+		/// A motion was executed; `bool` is true if returned without error.
+		Executed(Hash, bool),
+		/// A single member did some action; `bool` is true if returned without error.
+		MemberExecuted(Hash, bool),
+		/// A proposal was closed after its duration was up, with the final tally (yes votes and
+		/// no votes, including abstentions counted as the prime's vote).
+		Closed(Hash, MemberCount, MemberCount),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait<I>, I: Instance=DefaultInstance> for enum Call where origin: <T as system::Trait>::Origin {
+		fn deposit_event<T, I>() = default;
+
+		/// Set the collective's membership manually to `new_members`. Be nice to the chain and
+		/// provide it pre-sorted.
+		///
+		/// `prime`, if given, must be one of `new_members`.
+		///
+		/// Requires root origin.
+		fn set_members(new_members: Vec<T::AccountId>, prime: Option<T::AccountId>) {
+			let mut new_members = new_members;
+			new_members.sort();
+			if let Some(ref p) = prime {
+				ensure!(new_members.binary_search(p).is_ok(), "prime must be a member");
+			}
+			let old = Self::members();
+			<Self as ChangeMembers<T::AccountId>>::set_members_sorted(&new_members[..], &old[..]);
+			<Self as ChangeMembers<T::AccountId>>::set_prime(prime);
+		}
+
+		/// Dispatch a proposal from a member using the `Member` origin.
+		///
+		/// Origin must be a member of the collective.
+		fn execute(origin, proposal: Box<<T as Trait<I>>::Proposal>) {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_member(&who), "proposer not a member");
+
+			let proposal_hash = T::Hashing::hash_of(&proposal);
+			let ok = proposal.dispatch(RawOrigin::Member(who).into()).is_ok();
+			Self::deposit_event(RawEvent::MemberExecuted(proposal_hash, ok));
+		}
+
+		/// # <weight>
+		/// - Bounded storage reads and writes.
+		/// - Argument `threshold` has bearing on weight.
+		/// # </weight>
+		fn propose(origin, #[compact] threshold: MemberCount, proposal: Box<<T as Trait<I>>::Proposal>) {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_member(&who), "proposer not a member");
+
+			let proposal_hash = T::Hashing::hash_of(&proposal);
+
+			ensure!(!<ProposalOf<T, I>>::exists(proposal_hash), "duplicate proposals not allowed");
+
+			if threshold < 2 {
+				let seats = Self::members().len() as MemberCount;
+				let ok = proposal.dispatch(RawOrigin::Members(1, seats).into()).is_ok();
+				Self::deposit_event(RawEvent::Executed(proposal_hash, ok));
+			} else {
+				let index = Self::proposal_count();
+				<ProposalCount<I>>::mutate(|i| *i += 1);
+				<Proposals<T, I>>::mutate(|proposals| proposals.push(proposal_hash));
+				<ProposalOf<T, I>>::insert(proposal_hash, *proposal);
+				let end = <system::Module<T>>::block_number() + T::MotionDuration::get();
+				let votes = Votes { index, threshold, ayes: vec![who.clone()], nays: vec![], end };
+				<Voting<T, I>>::insert(proposal_hash, votes);
+
+				Self::deposit_event(RawEvent::Proposed(who, index, proposal_hash, threshold));
+			}
+		}
+
+		/// # <weight>
+		/// - Bounded storage read and writes.
+		/// - Will be slightly heavier if the proposal is approved / disapproved after the vote.
+		/// # </weight>
+		fn vote(origin, proposal: T::Hash, #[compact] index: ProposalIndex, approve: bool) {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_member(&who), "voter not a member");
+
+			let mut voting = Self::voting(&proposal).ok_or("proposal must exist")?;
+			ensure!(voting.index == index, "mismatched index");
+
+			let position_yes = voting.ayes.iter().position(|a| a == &who);
+			let position_no = voting.nays.iter().position(|a| a == &who);
+
+			if approve {
+				if position_yes.is_none() {
+					voting.ayes.push(who.clone());
+				} else {
+					return Err("duplicate vote ignored")
+				}
+				if let Some(pos) = position_no {
+					voting.nays.swap_remove(pos);
+				}
+			} else {
+				if position_no.is_none() {
+					voting.nays.push(who.clone());
+				} else {
+					return Err("duplicate vote ignored")
+				}
+				if let Some(pos) = position_yes {
+					voting.ayes.swap_remove(pos);
+				}
+			}
+
+			let yes_votes = voting.ayes.len() as MemberCount;
+			let no_votes = voting.nays.len() as MemberCount;
+			Self::deposit_event(RawEvent::Voted(who, proposal, approve, yes_votes, no_votes));
+
+			let seats = Self::members().len() as MemberCount;
+			let approved = yes_votes >= voting.threshold;
+			let disapproved = seats.saturating_sub(no_votes) < voting.threshold;
+			if approved || disapproved {
+				Self::finalize_proposal(approved, seats, voting, proposal);
+			} else {
+				// update voting
+				<Voting<T, I>>::insert(&proposal, voting);
+			}
+		}
+
+		/// Close a vote whose `MotionDuration` has passed. Members who have not voted are counted
+		/// as voting the same way as the prime member, or against if there is no prime or the
+		/// prime did not approve.
+		///
+		/// Origin must be a member of the collective.
+		fn close(origin, proposal: T::Hash, #[compact] index: ProposalIndex) {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_member(&who), "closer not a member");
+
+			let voting = Self::voting(&proposal).ok_or("proposal must exist")?;
+			ensure!(voting.index == index, "mismatched index");
+			ensure!(<system::Module<T>>::block_number() >= voting.end, "motion has not ended yet");
+
+			let mut yes_votes = voting.ayes.len() as MemberCount;
+			let mut no_votes = voting.nays.len() as MemberCount;
+			let seats = Self::members().len() as MemberCount;
+			let abstentions = seats.saturating_sub(yes_votes).saturating_sub(no_votes);
+			let prime_approves = Self::prime()
+				.map_or(false, |prime| voting.ayes.iter().any(|a| a == &prime));
+			if prime_approves {
+				yes_votes += abstentions;
+			} else {
+				no_votes += abstentions;
+			}
+			Self::deposit_event(RawEvent::Closed(proposal, yes_votes, no_votes));
+
+			let approved = yes_votes >= voting.threshold;
+			Self::finalize_proposal(approved, seats, voting, proposal);
+		}
+	}
+}
+
+impl<T: Trait<I>, I: Instance> Module<T, I> {
+	pub fn is_member(who: &T::AccountId) -> bool {
+		Self::members().binary_search(who).is_ok()
+	}
+
+	/// Execute the proposal if `approved`, and in any case remove it and its votes from storage.
+	fn finalize_proposal(
+		approved: bool,
+		seats: MemberCount,
+		voting: Votes<T::AccountId, T::BlockNumber>,
+		proposal: T::Hash,
+	) {
+		if approved {
+			Self::deposit_event(RawEvent::Approved(proposal));
+
+			// execute motion, assuming it exists.
+			if let Some(p) = <ProposalOf<T, I>>::take(&proposal) {
+				let origin = RawOrigin::Members(voting.threshold, seats).into();
+				let ok = p.dispatch(origin).is_ok();
+				Self::deposit_event(RawEvent::Executed(proposal, ok));
+			}
+		} else {
+			// disapproved
+			<ProposalOf<T, I>>::remove(&proposal);
+			Self::deposit_event(RawEvent::Disapproved(proposal));
+		}
+
+		// remove vote
+		<Voting<T, I>>::remove(&proposal);
+		<Proposals<T, I>>::mutate(|proposals| proposals.retain(|h| h != &proposal));
+	}
+}
+
+impl<T: Trait<I>, I: Instance> ChangeMembers<T::AccountId> for Module<T, I> {
+	fn change_members_sorted(
+		_incoming: &[T::AccountId],
+		outgoing: &[T::AccountId],
+		new: &[T::AccountId],
+	) {
+		// remove accounts from all current voting in motions.
+		let mut outgoing = outgoing.to_vec();
+		outgoing.sort_unstable();
+		for h in Self::proposals().into_iter() {
+			<Voting<T, I>>::mutate(h, |v|
+				if let Some(mut votes) = v.take() {
+					votes.ayes = votes.ayes.into_iter()
+						.filter(|i| outgoing.binary_search(i).is_err())
+						.collect();
+					votes.nays = votes.nays.into_iter()
+						.filter(|i| outgoing.binary_search(i).is_err())
+						.collect();
+					*v = Some(votes);
+				}
+			);
+		}
+		<Members<T, I>>::put(new.to_vec());
+		if Self::prime().map_or(false, |prime| new.binary_search(&prime).is_err()) {
+			<Prime<T, I>>::kill();
+		}
+	}
+
+	fn set_prime(prime: Option<T::AccountId>) {
+		match prime {
+			Some(p) => <Prime<T, I>>::put(p),
+			None => <Prime<T, I>>::kill(),
+		}
+	}
+}
+
+/// Ensure that the origin `o` represents at least `n` members. Returns `Ok` or an `Err`
+/// otherwise.
+pub fn ensure_members<OuterOrigin, AccountId, I>(o: OuterOrigin, n: MemberCount)
+	-> result::Result<MemberCount, &'static str>
+	where OuterOrigin: Into<result::Result<RawOrigin<AccountId, I>, OuterOrigin>>
+{
+	match o.into() {
+		Ok(RawOrigin::Members(x, _)) if x >= n => Ok(n),
+		_ => Err("bad origin: expected to be a threshold number of members"),
+	}
+}
+
+pub struct EnsureMember<AccountId, I=DefaultInstance>(rstd::marker::PhantomData<(AccountId, I)>);
+impl<
+	O: Into<result::Result<RawOrigin<AccountId, I>, O>> + From<RawOrigin<AccountId, I>>,
+	AccountId,
+	I,
+> EnsureOrigin<O> for EnsureMember<AccountId, I> {
+	type Success = AccountId;
+	fn try_origin(o: O) -> result::Result<Self::Success, O> {
+		o.into().and_then(|o| match o {
+			RawOrigin::Member(id) => Ok(id),
+			r => Err(O::from(r)),
+		})
+	}
+}
+
+pub struct EnsureMembers<N: U32, AccountId, I=DefaultInstance>(
+	rstd::marker::PhantomData<(N, AccountId, I)>
+);
+impl<
+	O: Into<result::Result<RawOrigin<AccountId, I>, O>> + From<RawOrigin<AccountId, I>>,
+	N: U32,
+	AccountId,
+	I,
+> EnsureOrigin<O> for EnsureMembers<N, AccountId, I> {
+	type Success = (MemberCount, MemberCount);
+	fn try_origin(o: O) -> result::Result<Self::Success, O> {
+		o.into().and_then(|o| match o {
+			RawOrigin::Members(n, m) if n >= N::VALUE => Ok((n, m)),
+			r => Err(O::from(r)),
+		})
+	}
+}
+
+pub struct EnsureProportionMoreThan<N: U32, D: U32, AccountId, I=DefaultInstance>(
+	rstd::marker::PhantomData<(N, D, AccountId, I)>
+);
+impl<
+	O: Into<result::Result<RawOrigin<AccountId, I>, O>> + From<RawOrigin<AccountId, I>>,
+	N: U32,
+	D: U32,
+	AccountId,
+	I,
+> EnsureOrigin<O> for EnsureProportionMoreThan<N, D, AccountId, I> {
+	type Success = ();
+	fn try_origin(o: O) -> result::Result<Self::Success, O> {
+		o.into().and_then(|o| match o {
+			RawOrigin::Members(n, m) if n * D::VALUE > N::VALUE * m => Ok(()),
+			r => Err(O::from(r)),
+		})
+	}
+}
+
+pub struct EnsureProportionAtLeast<N: U32, D: U32, AccountId, I=DefaultInstance>(
+	rstd::marker::PhantomData<(N, D, AccountId, I)>
+);
+impl<
+	O: Into<result::Result<RawOrigin<AccountId, I>, O>> + From<RawOrigin<AccountId, I>>,
+	N: U32,
+	D: U32,
+	AccountId,
+	I,
+> EnsureOrigin<O> for EnsureProportionAtLeast<N, D, AccountId, I> {
+	type Success = ();
+	fn try_origin(o: O) -> result::Result<Self::Success, O> {
+		o.into().and_then(|o| match o {
+			RawOrigin::Members(n, m) if n * D::VALUE >= N::VALUE * m => Ok(()),
+			r => Err(O::from(r)),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use runtime_io::with_externalities;
+	use srml_support::{
+		Hashable, assert_ok, assert_noop, impl_outer_origin, impl_outer_event, impl_outer_dispatch,
+		parameter_types,
+	};
+	use substrate_primitives::{H256, Blake2Hasher, u32_trait::{_1, _2}};
+	use primitives::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header, weights::Weight};
+	use system::{EventRecord, Phase};
+	use hex_literal::hex;
+
+	mod collective {
+		pub use crate::{Event, Origin, Instance1, DefaultInstance};
+	}
+
+	impl_outer_origin! {
+		pub enum Origin for Test {
+			collective Instance1 <T>,
+			collective <T>,
+		}
+	}
+
+	impl_outer_event! {
+		pub enum TestEvent for Test {
+			balances<T>, collective Instance1<T>, collective<T>,
+		}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			balances::Balances,
+		}
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = TestEvent;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const TransactionBaseFee: u64 = 0;
+		pub const TransactionByteFee: u64 = 0;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = TestEvent;
+		type TransactionPayment = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+		type TransactionBaseFee = TransactionBaseFee;
+		type TransactionByteFee = TransactionByteFee;
+		type WeightToFee = ();
+	}
+	parameter_types! {
+		pub const MotionDuration: u64 = 3;
+	}
+	impl Trait<Instance1> for Test {
+		type Origin = Origin;
+		type Proposal = Call;
+		type Event = TestEvent;
+		type MotionDuration = MotionDuration;
+	}
+	impl Trait for Test {
+		type Origin = Origin;
+		type Proposal = Call;
+		type Event = TestEvent;
+		type MotionDuration = MotionDuration;
+	}
+
+	type System = system::Module<Test>;
+	type Balances = balances::Module<Test>;
+	type Collective = Module<Test, Instance1>;
+	type DefaultCollective = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap().0;
+		t.extend(GenesisConfig::<Test, Instance1> {
+			members: vec![3, 1, 2],
+			phantom: Default::default(),
+		}.build_storage().unwrap().0);
+		t.extend(GenesisConfig::<Test> {
+			members: vec![],
+			phantom: Default::default(),
+		}.build_storage().unwrap().0);
+		t.into()
+	}
+
+	fn set_balance_proposal(value: u64) -> Call {
+		Call::Balances(balances::Call::set_balance(42, value.into(), 0))
+	}
+
+	fn collective_events() -> Vec<RawEvent<H256, u64, Instance1>> {
+		System::events().into_iter().filter_map(|r| match r.event {
+			TestEvent::collective_Instance1(e) => Some(e),
+			_ => None,
+		}).collect()
+	}
+
+	#[test]
+	fn basic_environment_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_eq!(Collective::members(), vec![1, 2, 3]);
+			assert_eq!(Collective::proposals(), Vec::<H256>::new());
+			assert_eq!(DefaultCollective::members(), Vec::<u64>::new());
+		});
+	}
+
+	#[test]
+	fn instances_are_independent() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_ok!(DefaultCollective::set_members(vec![4, 5], None));
+			assert_eq!(Collective::members(), vec![1, 2, 3]);
+			assert_eq!(DefaultCollective::members(), vec![4, 5]);
+
+			let proposal = set_balance_proposal(42);
+			assert_noop!(
+				DefaultCollective::propose(Origin::signed(1), 2, Box::new(proposal.clone())),
+				"proposer not a member"
+			);
+			assert_ok!(Collective::propose(Origin::signed(1), 2, Box::new(proposal)));
+			assert_eq!(DefaultCollective::proposals(), Vec::<H256>::new());
+		});
+	}
+
+	#[test]
+	fn origins_are_instance_specific() {
+		let members = |n, m| Origin::from(RawOrigin::<u64, Instance1>::Members(n, m));
+		let member = |who| Origin::from(RawOrigin::<u64, Instance1>::Member(who));
+
+		assert!(EnsureProportionMoreThan::<_1, _2, u64, Instance1>::try_origin(members(2, 3)).is_ok());
+		assert!(EnsureProportionMoreThan::<_1, _2, u64, Instance1>::try_origin(members(1, 2)).is_err());
+		assert!(EnsureProportionAtLeast::<_1, _2, u64, Instance1>::try_origin(members(1, 2)).is_ok());
+		assert!(EnsureMembers::<_2, u64, Instance1>::try_origin(members(2, 3)).is_ok());
+		assert!(EnsureMembers::<_2, u64, Instance1>::try_origin(member(1)).is_err());
+		assert_eq!(EnsureMember::<u64, Instance1>::try_origin(member(1)).ok(), Some(1));
+		// an origin of one collective is never accepted on behalf of another.
+		assert!(EnsureProportionAtLeast::<_1, _2, u64, DefaultInstance>::try_origin(members(3, 3)).is_err());
+		assert!(EnsureMember::<u64>::try_origin(member(1)).is_err());
+	}
+
+	#[test]
+	fn removal_of_old_voters_votes_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = set_balance_proposal(42);
+			let hash = BlakeTwo256::hash_of(&proposal);
+			let end = 4;
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone())));
+			assert_ok!(Collective::vote(Origin::signed(2), hash.clone(), 0, true));
+			assert_eq!(
+				Collective::voting(&hash),
+				Some(Votes { index: 0, threshold: 3, ayes: vec![1, 2], nays: vec![], end })
+			);
+			Collective::change_members_sorted(&[4], &[1], &[2, 3, 4]);
+			assert_eq!(
+				Collective::voting(&hash),
+				Some(Votes { index: 0, threshold: 3, ayes: vec![2], nays: vec![], end })
+			);
+
+			let proposal = set_balance_proposal(69);
+			let hash = BlakeTwo256::hash_of(&proposal);
+			assert_ok!(Collective::propose(Origin::signed(2), 2, Box::new(proposal.clone())));
+			assert_ok!(Collective::vote(Origin::signed(3), hash.clone(), 1, false));
+			assert_eq!(
+				Collective::voting(&hash),
+				Some(Votes { index: 1, threshold: 2, ayes: vec![2], nays: vec![3], end })
+			);
+			Collective::change_members_sorted(&[], &[3], &[2, 4]);
+			assert_eq!(
+				Collective::voting(&hash),
+				Some(Votes { index: 1, threshold: 2, ayes: vec![2], nays: vec![], end })
+			);
+		});
+	}
+
+	#[test]
+	fn removal_of_old_voters_votes_works_with_set_members() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = set_balance_proposal(42);
+			let hash = BlakeTwo256::hash_of(&proposal);
+			let end = 4;
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone())));
+			assert_ok!(Collective::vote(Origin::signed(2), hash.clone(), 0, true));
+			assert_ok!(Collective::set_members(vec![2, 3, 4], None));
+			assert_eq!(Collective::members(), vec![2, 3, 4]);
+			assert_eq!(
+				Collective::voting(&hash),
+				Some(Votes { index: 0, threshold: 3, ayes: vec![2], nays: vec![], end })
+			);
+			assert_noop!(
+				Collective::vote(Origin::signed(1), hash.clone(), 0, true),
+				"voter not a member"
+			);
+		});
+	}
+
+	#[test]
+	fn set_members_checks_and_clears_prime() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(Collective::set_members(vec![1, 2], Some(3)), "prime must be a member");
+			assert_ok!(Collective::set_members(vec![3, 1, 2], Some(3)));
+			assert_eq!(Collective::members(), vec![1, 2, 3]);
+			assert_eq!(Collective::prime(), Some(3));
+			// the prime is cleared when they leave the collective.
+			Collective::change_members(&[], &[3], vec![2, 1]);
+			assert_eq!(Collective::members(), vec![1, 2]);
+			assert_eq!(Collective::prime(), None);
+		});
+	}
+
+	#[test]
+	fn close_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = set_balance_proposal(42);
+			let hash = BlakeTwo256::hash_of(&proposal);
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone())));
+			assert_ok!(Collective::vote(Origin::signed(2), hash.clone(), 0, true));
+
+			assert_noop!(Collective::close(Origin::signed(4), hash.clone(), 0), "closer not a member");
+			assert_noop!(Collective::close(Origin::signed(2), hash.clone(), 0), "motion has not ended yet");
+
+			System::set_block_number(4);
+			assert_ok!(Collective::close(Origin::signed(2), hash.clone(), 0));
+			assert_eq!(Collective::proposals(), Vec::<H256>::new());
+			assert_eq!(Collective::proposal_of(&hash), None);
+			assert_eq!(collective_events(), vec![
+				RawEvent::Proposed(1, 0, hash.clone(), 3),
+				RawEvent::Voted(2, hash.clone(), true, 2, 0),
+				RawEvent::Closed(hash.clone(), 2, 1),
+				RawEvent::Disapproved(hash.clone()),
+			]);
+		});
+	}
+
+	#[test]
+	fn close_with_prime_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_ok!(Collective::set_members(vec![1, 2, 3], Some(1)));
+			let proposal = set_balance_proposal(42);
+			let hash = BlakeTwo256::hash_of(&proposal);
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone())));
+			assert_ok!(Collective::vote(Origin::signed(2), hash.clone(), 0, true));
+
+			System::set_block_number(4);
+			assert_ok!(Collective::close(Origin::signed(2), hash.clone(), 0));
+			assert_eq!(collective_events(), vec![
+				RawEvent::Proposed(1, 0, hash.clone(), 3),
+				RawEvent::Voted(2, hash.clone(), true, 2, 0),
+				RawEvent::Closed(hash.clone(), 3, 0),
+				RawEvent::Approved(hash.clone()),
+				RawEvent::Executed(hash.clone(), false),
+			]);
+		});
+	}
+
+	#[test]
+	fn propose_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = set_balance_proposal(42);
+			let hash = proposal.blake2_256().into();
+			let end = 4;
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone())));
+			assert_eq!(Collective::proposals(), vec![hash]);
+			assert_eq!(Collective::proposal_of(&hash), Some(proposal));
+			assert_eq!(
+				Collective::voting(&hash),
+				Some(Votes { index: 0, threshold: 3, ayes: vec![1], nays: vec![], end })
+			);
+
+			assert_eq!(System::events(), vec![
+				EventRecord {
+					phase: Phase::ApplyExtrinsic(0),
+					event: TestEvent::collective_Instance1(RawEvent::Proposed(
+						1,
+						0,
+						hex!["cd0b662a49f004093b80600415cf4126399af0d27ed6c185abeb1469c17eb5bf"].into(),
+						3,
+					)),
+					topics: vec![],
+				}
+			]);
+		});
+	}
+
+	#[test]
+	fn motions_ignoring_non_collective_proposals_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = set_balance_proposal(42);
+			assert_noop!(
+				Collective::propose(Origin::signed(42), 3, Box::new(proposal.clone())),
+				"proposer not a member"
+			);
+		});
+	}
+
+	#[test]
+	fn motions_ignoring_non_collective_votes_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = set_balance_proposal(42);
+			let hash: H256 = proposal.blake2_256().into();
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone())));
+			assert_noop!(Collective::vote(Origin::signed(42), hash.clone(), 0, true), "voter not a member");
+		});
+	}
+
+	#[test]
+	fn motions_ignoring_bad_index_collective_vote_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(3);
+			let proposal = set_balance_proposal(42);
+			let hash: H256 = proposal.blake2_256().into();
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone())));
+			assert_noop!(Collective::vote(Origin::signed(2), hash.clone(), 1, true), "mismatched index");
+		});
+	}
+
+	#[test]
+	fn motions_revoting_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = set_balance_proposal(42);
+			let hash: H256 = proposal.blake2_256().into();
+			let end = 4;
+			assert_ok!(Collective::propose(Origin::signed(1), 2, Box::new(proposal.clone())));
+			assert_eq!(
+				Collective::voting(&hash),
+				Some(Votes { index: 0, threshold: 2, ayes: vec![1], nays: vec![], end })
+			);
+			assert_noop!(Collective::vote(Origin::signed(1), hash.clone(), 0, true), "duplicate vote ignored");
+			assert_ok!(Collective::vote(Origin::signed(1), hash.clone(), 0, false));
+			assert_eq!(
+				Collective::voting(&hash),
+				Some(Votes { index: 0, threshold: 2, ayes: vec![], nays: vec![1], end })
+			);
+			assert_noop!(Collective::vote(Origin::signed(1), hash.clone(), 0, false), "duplicate vote ignored");
+
+			assert_eq!(collective_events(), vec![
+				RawEvent::Proposed(1, 0, hash.clone(), 2),
+				RawEvent::Voted(1, hash.clone(), false, 0, 1),
+			]);
+		});
+	}
+
+	#[test]
+	fn motions_disapproval_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = set_balance_proposal(42);
+			let hash: H256 = proposal.blake2_256().into();
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone())));
+			assert_ok!(Collective::vote(Origin::signed(2), hash.clone(), 0, false));
+
+			assert_eq!(collective_events(), vec![
+				RawEvent::Proposed(1, 0, hash.clone(), 3),
+				RawEvent::Voted(2, hash.clone(), false, 1, 1),
+				RawEvent::Disapproved(hash.clone()),
+			]);
+			assert_eq!(Collective::proposal_of(&hash), None);
+		});
+	}
+
+	#[test]
+	fn motions_approval_works() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let proposal = set_balance_proposal(42);
+			let hash: H256 = proposal.blake2_256().into();
+			assert_ok!(Collective::propose(Origin::signed(1), 2, Box::new(proposal.clone())));
+			assert_ok!(Collective::vote(Origin::signed(2), hash.clone(), 0, true));
+
+			assert_eq!(collective_events(), vec![
+				RawEvent::Proposed(1, 0, hash.clone(), 2),
+				RawEvent::Voted(2, hash.clone(), true, 2, 0),
+				RawEvent::Approved(hash.clone()),
+				RawEvent::Executed(hash.clone(), false),
+			]);
+		});
+	}
+}
@@ -0,0 +1,166 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition required by the contracts RPC.
+//!
+//! This API should be imported and implemented by the runtime, of a node that wants to use the
+//! `contracts_call` and `contracts_trace` RPCs.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+
+use client::decl_runtime_apis;
+use codec::{Codec, Encode, Decode};
+use rstd::vec::Vec;
+
+/// The name of the runtime function backing `ContractsApi::call`, as seen by the executor.
+pub const CALL_METHOD: &str = "ContractsApi_call";
+
+/// The name of the runtime function backing `ContractsApi::trace_call`, as seen by the executor.
+pub const TRACE_CALL_METHOD: &str = "ContractsApi_trace_call";
+
+/// A result of execution of a contract.
+#[derive(Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum ContractExecResult {
+	/// The contract returned successfully.
+	Success {
+		/// The output buffer the contract returned.
+		data: Vec<u8>,
+		/// The amount of gas that was consumed by the call.
+		gas_consumed: u64,
+		/// The SCALE-encoded events of the contracts module that the call would have deposited.
+		events: Vec<Vec<u8>>,
+	},
+	/// The contract execution either trapped or returned an error.
+	Error {
+		/// A description of the failure.
+		message: Vec<u8>,
+		/// The amount of gas that was consumed before the failure.
+		gas_consumed: u64,
+	},
+}
+
+/// The way a frame of a contract execution trace was entered.
+#[derive(Eq, PartialEq, Clone, Copy, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum ContractFrameKind {
+	/// The contract was called.
+	Call,
+	/// The contract was instantiated.
+	Instantiate,
+}
+
+/// The execution of a single contract.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ContractFrameTrace {
+	/// Whether the contract was called or instantiated.
+	pub kind: ContractFrameKind,
+	/// The SCALE-encoded address of the contract.
+	pub address: Vec<u8>,
+	/// The SCALE-encoded value transferred to the contract.
+	pub value: Vec<u8>,
+	/// The input data passed to the contract.
+	pub input_data: Vec<u8>,
+	/// Everything that happened during the execution of the contract, in order.
+	pub entries: Vec<ContractTraceEntry>,
+	/// The output of the contract, or a description of the failure.
+	pub result: Result<Vec<u8>, Vec<u8>>,
+	/// The gas left after the execution finished.
+	pub gas_left: u64,
+}
+
+/// An entry in the trace of a contract execution.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum ContractTraceEntry {
+	/// A host function was invoked by the contract.
+	HostCall {
+		/// The name of the host function.
+		name: Vec<u8>,
+		/// The arguments of the invocation. 32-bit values are zero-extended.
+		args: Vec<u64>,
+		/// The value returned by the function, if any.
+		result: Option<u64>,
+		/// Whether the function trapped.
+		trapped: bool,
+		/// The gas left after the function finished.
+		gas_left: u64,
+	},
+	/// A value was read from the storage of the contract.
+	StorageRead {
+		/// The key of the value.
+		key: [u8; 32],
+		/// The value that was read, if any.
+		value: Option<Vec<u8>>,
+	},
+	/// A value was written to the storage of the contract.
+	StorageWrite {
+		/// The key of the value.
+		key: [u8; 32],
+		/// The value that was written, `None` if the value was removed.
+		value: Option<Vec<u8>>,
+	},
+	/// Another contract was called or instantiated.
+	Frame(ContractFrameTrace),
+	/// The trace reached its maximum size, the remaining entries of the frame were dropped.
+	Truncated,
+}
+
+/// A result of a traced execution of a contract.
+#[derive(Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ContractTraceResult {
+	/// The result of the execution, just like `ContractsApi::call` would return it.
+	pub result: ContractExecResult,
+	/// The trace of the execution. Holds the frame of the called contract, unless the call failed
+	/// before the contract was entered.
+	pub trace: Vec<ContractTraceEntry>,
+}
+
+decl_runtime_apis! {
+	/// The API to interact with contracts without using executive.
+	#[api_version(2)]
+	pub trait ContractsApi<AccountId, Balance> where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// Perform a call from a specified account to a given contract.
+		///
+		/// See the contracts' `call` dispatchable function for more details. Nothing the call
+		/// does is committed to storage.
+		fn call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: u64,
+			input_data: Vec<u8>,
+		) -> ContractExecResult;
+
+		/// Perform a call like `call` and record a trace of its execution.
+		///
+		/// The trace lists the host functions invoked by the contracts, the storage they accessed
+		/// and the contracts they called or instantiated.
+		fn trace_call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: u64,
+			input_data: Vec<u8>,
+		) -> ContractTraceResult;
+	}
+}
@@ -0,0 +1,335 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC interface for the contracts module.
+//!
+//! Nodes whose runtime implements the `ContractsApi` runtime API can add this to their RPC
+//! extensions.
+
+#![warn(missing_docs)]
+
+pub mod error;
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::Arc;
+
+use client::{self, Client, CallExecutor};
+use contracts_rpc_runtime_api::{
+	ContractExecResult, ContractFrameKind, ContractFrameTrace, ContractTraceEntry, ContractTraceResult,
+	CALL_METHOD, TRACE_CALL_METHOD,
+};
+use jsonrpc_derive::rpc;
+use log::trace;
+use parity_codec::{Decode, Encode};
+use primitives::hexdisplay::HexDisplay;
+use primitives::{H256, Blake2Hasher, Bytes};
+use sr_primitives::generic::BlockId;
+use sr_primitives::traits::Block as BlockT;
+use serde::{Serialize, Deserialize};
+use state_machine::{self, ExecutionStrategy};
+use self::error::{Error, Result};
+
+pub use self::gen_client::Client as ContractsClient;
+
+/// A request to call a contract.
+///
+/// The RPC layer doesn't know the account and balance types of the runtime, so `origin`,
+/// `dest` and `value` are expected to be SCALE-encoded by the caller.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct CallRequest {
+	/// The encoded account the call is made from.
+	pub origin: Bytes,
+	/// The encoded account of the contract to call.
+	pub dest: Bytes,
+	/// The encoded value transferred along with the call.
+	pub value: Bytes,
+	/// The maximum amount of gas the call may consume.
+	pub gas_limit: u64,
+	/// The input data passed to the contract.
+	pub input_data: Bytes,
+}
+
+/// The result of a contract call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcContractExecResult {
+	/// The contract returned successfully.
+	#[serde(rename_all = "camelCase")]
+	Success {
+		/// The output buffer the contract returned.
+		data: Bytes,
+		/// The amount of gas that was consumed by the call.
+		gas_consumed: u64,
+		/// The encoded events the call would have deposited.
+		events: Vec<Bytes>,
+	},
+	/// The contract execution either trapped or returned an error.
+	#[serde(rename_all = "camelCase")]
+	Error {
+		/// A description of the failure.
+		message: String,
+		/// The amount of gas that was consumed before the failure.
+		gas_consumed: u64,
+	},
+}
+
+impl From<ContractExecResult> for RpcContractExecResult {
+	fn from(r: ContractExecResult) -> Self {
+		match r {
+			ContractExecResult::Success { data, gas_consumed, events } => RpcContractExecResult::Success {
+				data: data.into(),
+				gas_consumed,
+				events: events.into_iter().map(Into::into).collect(),
+			},
+			ContractExecResult::Error { message, gas_consumed } => RpcContractExecResult::Error {
+				message: String::from_utf8_lossy(&message).into_owned(),
+				gas_consumed,
+			},
+		}
+	}
+}
+
+/// The way a traced contract was entered.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcFrameKind {
+	/// The contract was called.
+	Call,
+	/// The contract was instantiated.
+	Instantiate,
+}
+
+/// The outcome of a traced contract execution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcFrameResult {
+	/// The contract returned the given output buffer.
+	Success(Bytes),
+	/// The execution failed for the given reason.
+	Error(String),
+}
+
+/// The execution of a single contract.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcFrameTrace {
+	/// Whether the contract was called or instantiated.
+	pub kind: RpcFrameKind,
+	/// The encoded address of the contract.
+	pub address: Bytes,
+	/// The encoded value transferred to the contract.
+	pub value: Bytes,
+	/// The input data passed to the contract.
+	pub input_data: Bytes,
+	/// Everything that happened during the execution of the contract, in order.
+	pub entries: Vec<RpcTraceEntry>,
+	/// The outcome of the execution.
+	pub result: RpcFrameResult,
+	/// The gas left after the execution finished.
+	pub gas_left: u64,
+}
+
+/// An entry in the trace of a contract execution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcTraceEntry {
+	/// A host function was invoked by the contract.
+	#[serde(rename_all = "camelCase")]
+	HostCall {
+		/// The name of the host function.
+		name: String,
+		/// The arguments of the invocation. 32-bit values are zero-extended.
+		args: Vec<u64>,
+		/// The value returned by the function, if any.
+		result: Option<u64>,
+		/// Whether the function trapped.
+		trapped: bool,
+		/// The gas left after the function finished.
+		gas_left: u64,
+	},
+	/// A value was read from the storage of the contract.
+	StorageRead {
+		/// The key of the value.
+		key: H256,
+		/// The value that was read, if any.
+		value: Option<Bytes>,
+	},
+	/// A value was written to the storage of the contract.
+	StorageWrite {
+		/// The key of the value.
+		key: H256,
+		/// The value that was written, `null` if the value was removed.
+		value: Option<Bytes>,
+	},
+	/// Another contract was called or instantiated.
+	Frame(RpcFrameTrace),
+	/// The trace reached its maximum size, the remaining entries of the frame were dropped.
+	Truncated,
+}
+
+impl From<ContractTraceEntry> for RpcTraceEntry {
+	fn from(e: ContractTraceEntry) -> Self {
+		match e {
+			ContractTraceEntry::HostCall { name, args, result, trapped, gas_left } => RpcTraceEntry::HostCall {
+				name: String::from_utf8_lossy(&name).into_owned(),
+				args,
+				result,
+				trapped,
+				gas_left,
+			},
+			ContractTraceEntry::StorageRead { key, value } => RpcTraceEntry::StorageRead {
+				key: key.into(),
+				value: value.map(Into::into),
+			},
+			ContractTraceEntry::StorageWrite { key, value } => RpcTraceEntry::StorageWrite {
+				key: key.into(),
+				value: value.map(Into::into),
+			},
+			ContractTraceEntry::Frame(frame) => RpcTraceEntry::Frame(frame.into()),
+			ContractTraceEntry::Truncated => RpcTraceEntry::Truncated,
+		}
+	}
+}
+
+impl From<ContractFrameTrace> for RpcFrameTrace {
+	fn from(f: ContractFrameTrace) -> Self {
+		RpcFrameTrace {
+			kind: match f.kind {
+				ContractFrameKind::Call => RpcFrameKind::Call,
+				ContractFrameKind::Instantiate => RpcFrameKind::Instantiate,
+			},
+			address: f.address.into(),
+			value: f.value.into(),
+			input_data: f.input_data.into(),
+			entries: f.entries.into_iter().map(Into::into).collect(),
+			result: match f.result {
+				Ok(data) => RpcFrameResult::Success(data.into()),
+				Err(message) => RpcFrameResult::Error(String::from_utf8_lossy(&message).into_owned()),
+			},
+			gas_left: f.gas_left,
+		}
+	}
+}
+
+/// The result of a traced contract call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContractTraceResult {
+	/// The result of the call, as returned by `contracts_call`.
+	pub result: RpcContractExecResult,
+	/// The trace of the call. Holds the frame of the called contract, unless the call failed
+	/// before the contract was entered.
+	pub trace: Vec<RpcTraceEntry>,
+}
+
+impl From<ContractTraceResult> for RpcContractTraceResult {
+	fn from(r: ContractTraceResult) -> Self {
+		RpcContractTraceResult {
+			result: r.result.into(),
+			trace: r.trace.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// Substrate contracts API
+#[rpc]
+pub trait ContractsApi<Hash> {
+	/// Executes a call to a contract.
+	///
+	/// The call is executed against the state of the given block (or the best block) and none
+	/// of its effects are persisted. No fee is charged, so this is the way to query read-only
+	/// contract methods. The gas limit is capped at the block gas limit of the runtime.
+	#[rpc(name = "contracts_call")]
+	fn call(&self, call_request: CallRequest, at: Option<Hash>) -> Result<RpcContractExecResult>;
+
+	/// Executes a call to a contract like `contracts_call` and returns a trace of the execution.
+	///
+	/// The trace lists every host function invoked by the contracts with its arguments, its
+	/// return value and the gas left afterwards, the storage they read and wrote and the
+	/// contracts they called or instantiated, along with the reason of a trap, if any.
+	#[rpc(name = "contracts_trace")]
+	fn trace(&self, call_request: CallRequest, at: Option<Hash>) -> Result<RpcContractTraceResult>;
+}
+
+/// Contracts API implementation.
+pub struct Contracts<B, E, Block: BlockT, RA> {
+	/// Substrate client.
+	client: Arc<Client<B, E, Block, RA>>,
+}
+
+impl<B, E, Block: BlockT, RA> Contracts<B, E, Block, RA> {
+	/// Create new Contracts API RPC handler.
+	pub fn new(client: Arc<Client<B, E, Block, RA>>) -> Self {
+		Self {
+			client,
+		}
+	}
+}
+
+impl<B, E, Block, RA> Contracts<B, E, Block, RA> where
+	Block: BlockT<Hash=H256>,
+	B: client::backend::Backend<Block, Blake2Hasher>,
+	E: CallExecutor<Block, Blake2Hasher>,
+{
+	fn unwrap_or_best(&self, hash: Option<Block::Hash>) -> Block::Hash {
+		hash.unwrap_or_else(|| self.client.info().chain.best_hash)
+	}
+
+	/// Calls the given `ContractsApi` method with the arguments of the request and decodes its result.
+	fn execute<R: Decode>(&self, method: &str, call_request: CallRequest, at: Option<Block::Hash>) -> Result<R> {
+		let at = self.unwrap_or_best(at);
+		let CallRequest { origin, dest, value, gas_limit, input_data } = call_request;
+
+		// Runtime API arguments are passed as an encoded tuple, which is the concatenation of
+		// the encoded arguments.
+		let mut args = Vec::with_capacity(origin.0.len() + dest.0.len() + value.0.len() + input_data.0.len() + 16);
+		args.extend_from_slice(&origin.0);
+		args.extend_from_slice(&dest.0);
+		args.extend_from_slice(&value.0);
+		gas_limit.encode_to(&mut args);
+		input_data.0.encode_to(&mut args);
+
+		trace!(target: "rpc", "Calling {} at {:?} ({})", method, at, HexDisplay::from(&args));
+		let return_data = self.client
+			.executor()
+			.call(
+				&BlockId::Hash(at),
+				method, &args, ExecutionStrategy::NativeElseWasm, state_machine::NeverOffchainExt::new(),
+			)?;
+		R::decode(&mut &return_data[..]).ok_or(Error::InvalidResult)
+	}
+}
+
+impl<B, E, Block, RA> ContractsApi<Block::Hash> for Contracts<B, E, Block, RA> where
+	Block: BlockT<Hash=H256> + 'static,
+	B: client::backend::Backend<Block, Blake2Hasher> + Send + Sync + 'static,
+	E: CallExecutor<Block, Blake2Hasher> + Send + Sync + 'static,
+	RA: Send + Sync + 'static,
+{
+	fn call(&self, call_request: CallRequest, at: Option<Block::Hash>) -> Result<RpcContractExecResult> {
+		let result: ContractExecResult = self.execute(CALL_METHOD, call_request, at)?;
+		Ok(result.into())
+	}
+
+	fn trace(&self, call_request: CallRequest, at: Option<Block::Hash>) -> Result<RpcContractTraceResult> {
+		let result: ContractTraceResult = self.execute(TRACE_CALL_METHOD, call_request, at)?;
+		Ok(result.into())
+	}
+}
@@ -0,0 +1,131 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use assert_matches::assert_matches;
+use test_client::prelude::*;
+
+#[test]
+fn should_deserialize_call_request() {
+	let request: CallRequest = serde_json::from_str(r#"{
+		"origin": "0x01",
+		"dest": "0x02",
+		"value": "0x2a",
+		"gasLimit": 100000,
+		"inputData": "0x0a0b"
+	}"#).unwrap();
+
+	assert_eq!(request, CallRequest {
+		origin: Bytes(vec![1]),
+		dest: Bytes(vec![2]),
+		value: Bytes(vec![42]),
+		gas_limit: 100_000,
+		input_data: Bytes(vec![10, 11]),
+	});
+	assert!(serde_json::from_str::<CallRequest>(r#"{
+		"origin": "0x01",
+		"dest": "0x02",
+		"value": "0x2a",
+		"gasLimit": 100000,
+		"inputData": "0x0a0b",
+		"extra": 1
+	}"#).is_err());
+}
+
+#[test]
+fn should_serialize_exec_result() {
+	let success: RpcContractExecResult = ContractExecResult::Success {
+		data: vec![1, 2],
+		gas_consumed: 5,
+		events: vec![vec![3]],
+	}.into();
+	assert_eq!(
+		serde_json::to_string(&success).unwrap(),
+		r#"{"success":{"data":"0x0102","gasConsumed":5,"events":["0x03"]}}"#,
+	);
+
+	let error: RpcContractExecResult = ContractExecResult::Error {
+		message: b"contract trapped during execution".to_vec(),
+		gas_consumed: 7,
+	}.into();
+	assert_eq!(
+		serde_json::to_string(&error).unwrap(),
+		r#"{"error":{"message":"contract trapped during execution","gasConsumed":7}}"#,
+	);
+}
+
+#[test]
+fn should_serialize_trace_result() {
+	let result: RpcContractTraceResult = ContractTraceResult {
+		result: ContractExecResult::Success {
+			data: vec![1],
+			gas_consumed: 5,
+			events: vec![],
+		},
+		trace: vec![ContractTraceEntry::Frame(ContractFrameTrace {
+			kind: ContractFrameKind::Call,
+			address: vec![2],
+			value: vec![0],
+			input_data: vec![],
+			entries: vec![
+				ContractTraceEntry::StorageRead {
+					key: [0x11; 32],
+					value: None,
+				},
+				ContractTraceEntry::HostCall {
+					name: b"ext_get_storage".to_vec(),
+					args: vec![4],
+					result: Some(1),
+					trapped: false,
+					gas_left: 90,
+				},
+				ContractTraceEntry::Truncated,
+			],
+			result: Ok(vec![1]),
+			gas_left: 95,
+		})],
+	}.into();
+	assert_eq!(
+		serde_json::to_string(&result).unwrap(),
+		concat!(
+			r#"{"result":{"success":{"data":"0x01","gasConsumed":5,"events":[]}},"#,
+			r#""trace":[{"frame":{"kind":"call","address":"0x02","value":"0x00","inputData":"0x","entries":["#,
+			r#"{"storageRead":{"key":"0x1111111111111111111111111111111111111111111111111111111111111111","value":null}},"#,
+			r#"{"hostCall":{"name":"ext_get_storage","args":[4],"result":1,"trapped":false,"gasLeft":90}},"#,
+			r#""truncated""#,
+			r#"],"result":{"success":"0x01"},"gasLeft":95}}]}"#,
+		),
+	);
+}
+
+#[test]
+fn should_fail_if_runtime_has_no_contracts_api() {
+	let client = Contracts::new(Arc::new(test_client::new()));
+	let request = CallRequest {
+		origin: Bytes(vec![1]),
+		dest: Bytes(vec![2]),
+		value: Bytes(vec![0]),
+		gas_limit: 100_000,
+		input_data: Bytes(vec![]),
+	};
+
+	assert_matches!(client.call(request.clone(), None), Err(Error::Client(_)));
+	assert_matches!(client.trace(request.clone(), None), Err(Error::Client(_)));
+	assert_matches!(
+		client.call(request, Some(H256::repeat_byte(42))),
+		Err(Error::Client(_))
+	);
+}
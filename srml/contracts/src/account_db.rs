@@ -20,6 +20,7 @@ use super::{
 	AliveContractInfo, BalanceOf, CodeHash, ContractInfo, ContractInfoOf, Trait, TrieId,
 	TrieIdGenerator,
 };
+use crate::wasm::{increment_refcount, decrement_refcount};
 use crate::exec::StorageKey;
 use rstd::cell::RefCell;
 use rstd::collections::btree_map::{BTreeMap, Entry};
@@ -39,6 +40,8 @@ pub struct ChangeEntry<T: Trait> {
 	code_hash: Option<CodeHash<T>>,
 	rent_allowance: Option<BalanceOf<T>>,
 	storage: BTreeMap<StorageKey, Option<Vec<u8>>>,
+	/// If true, the contract was destroyed and its info and storage must be removed.
+	destroyed: bool,
 }
 
 // Cannot derive(Default) since it erroneously bounds T by Default.
@@ -49,6 +52,7 @@ impl<T: Trait> Default for ChangeEntry<T> {
 			balance: Default::default(),
 			code_hash: Default::default(),
 			storage: Default::default(),
+			destroyed: false,
 		}
 	}
 }
@@ -98,6 +102,16 @@ impl<T: Trait> AccountDb<T> for DirectAccountDb {
 	fn commit(&mut self, s: ChangeSet<T>) {
 		let mut total_imbalance = SignedImbalance::zero();
 		for (address, changed) in s.into_iter() {
+			if changed.destroyed {
+				// The contract destroyed itself. Its remaining balance was already transferred away,
+				// so only the info and the storage of the contract need to be removed here.
+				if let Some(ContractInfo::Alive(info)) = <ContractInfoOf<T>>::get(&address) {
+					<ContractInfoOf<T>>::remove(&address);
+					child::kill_storage(&info.trie_id);
+					decrement_refcount::<T>(&info.code_hash);
+				}
+			}
+
 			if let Some(balance) = changed.balance {
 				let (imbalance, outcome) = T::Currency::make_free_balance_be(&address, balance);
 				total_imbalance = total_imbalance.merge(imbalance);
@@ -123,6 +137,7 @@ impl<T: Trait> AccountDb<T> for DirectAccountDb {
 				let mut new_info = if let Some(info) = old_info.clone() {
 					info
 				} else if let Some(code_hash) = changed.code_hash {
+					increment_refcount::<T>(&code_hash);
 					AliveContractInfo::<T> {
 						code_hash,
 						storage_size: T::StorageSizeOffset::get(),
@@ -227,6 +242,18 @@ impl<'a, T: Trait> OverlayAccountDb<'a, T> {
 
 		Ok(())
 	}
+	/// Mark the contract as destroyed.
+	///
+	/// Its info and storage are removed once the changes are committed.
+	pub fn destroy_contract(&mut self, account: &T::AccountId) {
+		let mut local = self.local.borrow_mut();
+		let contract = local.entry(account.clone()).or_insert_with(|| Default::default());
+
+		contract.destroyed = true;
+		contract.code_hash = None;
+		contract.rent_allowance = None;
+		contract.storage.clear();
+	}
 	/// Assume contract exists
 	pub fn set_rent_allowance(&mut self, account: &T::AccountId, rent_allowance: BalanceOf<T>) {
 		self.local
@@ -251,26 +278,31 @@ impl<'a, T: Trait> AccountDb<T> for OverlayAccountDb<'a, T> {
 		trie_id: Option<&TrieId>,
 		location: &StorageKey
 	) -> Option<Vec<u8>> {
-		self.local
-			.borrow()
-			.get(account)
-			.and_then(|a| a.storage.get(location))
-			.cloned()
-			.unwrap_or_else(|| self.underlying.get_storage(account, trie_id, location))
+		match self.local.borrow().get(account) {
+			Some(changes) => match changes.storage.get(location) {
+				Some(value) => value.clone(),
+				// Nothing is left from the storage of a destroyed contract.
+				None if changes.destroyed => None,
+				None => self.underlying.get_storage(account, trie_id, location),
+			},
+			None => self.underlying.get_storage(account, trie_id, location),
+		}
 	}
 	fn get_code_hash(&self, account: &T::AccountId) -> Option<CodeHash<T>> {
-		self.local
-			.borrow()
-			.get(account)
-			.and_then(|changes| changes.code_hash)
-			.or_else(|| self.underlying.get_code_hash(account))
+		match self.local.borrow().get(account) {
+			Some(changes) if changes.destroyed => changes.code_hash,
+			changes => changes
+				.and_then(|changes| changes.code_hash)
+				.or_else(|| self.underlying.get_code_hash(account)),
+		}
 	}
 	fn get_rent_allowance(&self, account: &T::AccountId) -> Option<BalanceOf<T>> {
-		self.local
-			.borrow()
-			.get(account)
-			.and_then(|changes| changes.rent_allowance)
-			.or_else(|| self.underlying.get_rent_allowance(account))
+		match self.local.borrow().get(account) {
+			Some(changes) if changes.destroyed => changes.rent_allowance,
+			changes => changes
+				.and_then(|changes| changes.rent_allowance)
+				.or_else(|| self.underlying.get_rent_allowance(account)),
+		}
 	}
 	fn contract_exists(&self, account: &T::AccountId) -> bool {
 		self.local
@@ -293,6 +325,12 @@ impl<'a, T: Trait> AccountDb<T> for OverlayAccountDb<'a, T> {
 			match local.entry(address) {
 				Entry::Occupied(e) => {
 					let mut value = e.into_mut();
+					if changed.destroyed {
+						value.destroyed = true;
+						value.code_hash = None;
+						value.rent_allowance = None;
+						value.storage.clear();
+					}
 					value.balance = changed.balance.or(value.balance);
 					value.code_hash = changed.code_hash.or(value.code_hash);
 					value.rent_allowance = changed.rent_allowance.or(value.rent_allowance);
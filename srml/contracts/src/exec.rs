@@ -15,9 +15,10 @@
 // along with Substrate. If not, see <http://www.gnu.org/licenses/>.
 
 use super::{CodeHash, Config, ContractAddressFor, Event, RawEvent, Trait,
-	TrieId, BalanceOf, ContractInfoOf};
+	TrieId, BalanceOf, ContractInfoOf, ChainExtension};
 use crate::account_db::{AccountDb, DirectAccountDb, OverlayAccountDb};
 use crate::gas::{Gas, GasMeter, Token, approx_gas_for_balance};
+use crate::trace::{FrameKind, FrameTrace, TraceEntry};
 
 use rstd::prelude::*;
 use runtime_primitives::traits::{Bounded, CheckedAdd, CheckedSub, Zero};
@@ -28,6 +29,8 @@ pub type AccountIdOf<T> = <T as system::Trait>::AccountId;
 pub type CallOf<T> = <T as Trait>::Call;
 pub type MomentOf<T> = <T as timestamp::Trait>::Moment;
 pub type SeedOf<T> = <T as system::Trait>::Hash;
+pub type BlockNumberOf<T> = <T as system::Trait>::BlockNumber;
+pub type TraceEntryOf<T> = TraceEntry<AccountIdOf<T>, BalanceOf<T>>;
 
 /// A type that represents a topic of an event. At the moment a hash is used.
 pub type TopicOf<T> = <T as system::Trait>::Hash;
@@ -109,6 +112,24 @@ pub trait Ext {
 	/// Returns a random number for the current block with the given subject.
 	fn random(&self, subject: &[u8]) -> SeedOf<Self::T>;
 
+	/// Returns the current block number.
+	fn block_number(&self) -> BlockNumberOf<Self::T>;
+
+	/// Returns the runtime storage entry under the given raw `key`.
+	///
+	/// The caller is responsible for checking the key against `Trait::RuntimeStorageWhitelist`.
+	fn get_runtime_storage(&self, key: &[u8]) -> Option<Vec<u8>>;
+
+	/// Returns the gas charged for calling the function `func_id` of `Trait::ChainExtension` with
+	/// the given `input`, or `None` if there is no such function.
+	fn chain_extension_gas_cost(&self, func_id: u32, input: &[u8]) -> Option<Gas>;
+
+	/// Call the read-only function `func_id` of `Trait::ChainExtension` on behalf of the current
+	/// contract.
+	///
+	/// The caller is responsible for charging `chain_extension_gas_cost` beforehand.
+	fn query_chain_extension(&self, func_id: u32, input: Vec<u8>) -> Result<Vec<u8>, &'static str>;
+
 	/// Deposit an event with the given topics.
 	///
 	/// There should not be any duplicates in `topics`.
@@ -119,6 +140,24 @@ pub trait Ext {
 
 	/// Rent allowance of the contract
 	fn rent_allowance(&self) -> BalanceOf<Self::T>;
+
+	/// Returns whether the execution is traced.
+	fn is_tracing(&self) -> bool;
+
+	/// Record an entry in the trace of the current contract.
+	///
+	/// Does nothing if the execution is not traced.
+	fn trace(&mut self, entry: TraceEntryOf<Self::T>);
+
+	/// Transfer all funds of the current contract to `beneficiary` and destroy the contract.
+	///
+	/// The info and the storage of the contract are removed when the changes are committed.
+	/// The contract must not execute any code after a successful termination.
+	fn terminate(
+		&mut self,
+		beneficiary: &AccountIdOf<Self::T>,
+		gas_meter: &mut GasMeter<Self::T>,
+	) -> Result<(), &'static str>;
 }
 
 /// Loader is a companion of the `Vm` trait. It loads an appropriate abstract
@@ -252,8 +291,14 @@ pub struct ExecutionContext<'a, T: Trait + 'a, V, L> {
 	pub self_trie_id: Option<TrieId>,
 	pub overlay: OverlayAccountDb<'a, T>,
 	pub depth: usize,
+	/// The accounts executing further up the call stack, outermost first.
+	pub callers: Vec<T::AccountId>,
 	pub events: Vec<IndexedEvent<T>>,
 	pub calls: Vec<(T::AccountId, T::Call)>,
+	/// The trace of the execution of the current contract. `None` unless tracing is enabled.
+	pub trace: Option<Vec<TraceEntryOf<T>>>,
+	/// The number of entries that can still be recorded in the trace, including nested frames.
+	pub trace_capacity: usize,
 	pub config: &'a Config<T>,
 	pub vm: &'a V,
 	pub loader: &'a L,
@@ -275,8 +320,11 @@ where
 			self_account: origin,
 			overlay: OverlayAccountDb::<T>::new(&DirectAccountDb),
 			depth: 0,
+			callers: Vec::new(),
 			events: Vec::new(),
 			calls: Vec::new(),
+			trace: None,
+			trace_capacity: 0,
 			config: &cfg,
 			vm: &vm,
 			loader: &loader,
@@ -284,20 +332,57 @@ where
 	}
 
 	fn nested(&self, overlay: OverlayAccountDb<'a, T>, dest: T::AccountId) -> Self {
+		let mut callers = self.callers.clone();
+		callers.push(self.self_account.clone());
 		ExecutionContext {
 			self_trie_id: <ContractInfoOf<T>>::get(&dest)
 				.and_then(|i| i.as_alive().map(|i| i.trie_id.clone())),
 			self_account: dest,
 			overlay,
 			depth: self.depth + 1,
+			callers,
 			events: Vec::new(),
 			calls: Vec::new(),
+			trace: self.trace.as_ref().map(|_| Vec::new()),
+			// One entry is reserved for the frame of the nested contract.
+			trace_capacity: self.trace_capacity.saturating_sub(1),
 			config: self.config,
 			vm: self.vm,
 			loader: self.loader,
 		}
 	}
 
+	/// Record a trace of the execution, which can be taken from `trace` afterwards.
+	///
+	/// The trace holds at most `max_entries` entries, counting the entries of nested frames.
+	pub fn enable_tracing(&mut self, max_entries: usize) {
+		self.trace = Some(Vec::new());
+		self.trace_capacity = max_entries;
+	}
+
+	/// Record an entry in the trace, if tracing is enabled.
+	///
+	/// The entry is dropped if the trace is full, which is marked by `TraceEntry::Truncated` at
+	/// the end of the trace of the current contract.
+	fn record_trace(&mut self, entry: TraceEntryOf<T>) {
+		if let Some(trace) = self.trace.as_mut() {
+			if self.trace_capacity > 0 {
+				self.trace_capacity -= 1;
+				trace.push(entry);
+			} else if !trace.last().map_or(false, |last| *last == TraceEntry::Truncated) {
+				trace.push(TraceEntry::Truncated);
+			}
+		}
+	}
+
+	/// Take back the trace capacity left over by a nested frame, including the entry reserved for
+	/// its own frame.
+	fn reclaim_trace_capacity(&mut self, nested_trace_capacity: usize) {
+		if self.trace_capacity > 0 {
+			self.trace_capacity = nested_trace_capacity + 1;
+		}
+	}
+
 	/// Make a call to the specified address, optionally transfering some funds.
 	pub fn call(
 		&mut self,
@@ -323,47 +408,67 @@ where
 		// cannot be changed before the first call
 		crate::rent::pay_rent::<T>(&dest);
 
-		let mut output_data = Vec::new();
-
-		let (change_set, events, calls) = {
+		let (result, nested_trace) = {
 			let mut nested = self.nested(
 				OverlayAccountDb::new(&self.overlay),
 				dest.clone()
 			);
 
-			if value > BalanceOf::<T>::zero() {
-				transfer(
-					gas_meter,
-					TransferCause::Call,
-					&self.self_account,
-					&dest,
-					value,
-					&mut nested,
-				)?;
-			}
-
-			if let Some(dest_code_hash) = self.overlay.get_code_hash(&dest) {
-				let executable = self.loader.load_main(&dest_code_hash)?;
-				output_data = self
-					.vm
-					.execute(
-						&executable,
-						&mut CallContext {
-							ctx: &mut nested,
-							caller: self.self_account.clone(),
-							value_transferred: value,
-							timestamp: timestamp::Module::<T>::now(),
-						},
-						input_data,
-						empty_output_buf,
+			let output = (|| -> Result<Vec<u8>, &'static str> {
+				if value > BalanceOf::<T>::zero() {
+					transfer(
 						gas_meter,
-					)
-					.into_result()?;
-			}
-
-			(nested.overlay.into_change_set(), nested.events, nested.calls)
+						TransferCause::Call,
+						&self.self_account,
+						&dest,
+						value,
+						&mut nested,
+					)?;
+				}
+
+				let mut output_data = Vec::new();
+				if let Some(dest_code_hash) = self.overlay.get_code_hash(&dest) {
+					let executable = self.loader.load_main(&dest_code_hash)?;
+					output_data = self
+						.vm
+						.execute(
+							&executable,
+							&mut CallContext {
+								ctx: &mut nested,
+								caller: self.self_account.clone(),
+								value_transferred: value,
+								timestamp: timestamp::Module::<T>::now(),
+							},
+							input_data,
+							empty_output_buf,
+							gas_meter,
+						)
+						.into_result()?;
+				}
+				Ok(output_data)
+			})();
+
+			let nested_trace = nested.trace.take().map(|trace| (trace, nested.trace_capacity));
+			let result = output.map(|output_data| {
+				(output_data, nested.overlay.into_change_set(), nested.events, nested.calls)
+			});
+			(result, nested_trace)
 		};
 
+		if let Some((entries, nested_trace_capacity)) = nested_trace {
+			self.reclaim_trace_capacity(nested_trace_capacity);
+			self.record_trace(TraceEntry::Frame(FrameTrace {
+				kind: FrameKind::Call,
+				address: dest,
+				value,
+				input_data: input_data.to_vec(),
+				entries,
+				result: result.as_ref().map(|(output_data, ..)| output_data.clone()).map_err(|e| *e),
+				gas_left: gas_meter.gas_left(),
+			}));
+		}
+
+		let (output_data, change_set, events, calls) = result?;
 		self.overlay.commit(change_set);
 		self.events.extend(events);
 		self.calls.extend(calls);
@@ -395,49 +500,70 @@ where
 			&self.self_account,
 		);
 
-		let (change_set, events, calls) = {
+		let (result, nested_trace) = {
 			let mut overlay = OverlayAccountDb::new(&self.overlay);
 
 			overlay.create_contract(&dest, code_hash.clone())?;
 
 			let mut nested = self.nested(overlay, dest.clone());
 
-			// Send funds unconditionally here. If the `endowment` is below existential_deposit
-			// then error will be returned here.
-			transfer(
-				gas_meter,
-				TransferCause::Instantiate,
-				&self.self_account,
-				&dest,
-				endowment,
-				&mut nested,
-			)?;
-
-			let executable = self.loader.load_init(&code_hash)?;
-			self.vm
-				.execute(
-					&executable,
-					&mut CallContext {
-						ctx: &mut nested,
-						caller: self.self_account.clone(),
-						value_transferred: endowment,
-						timestamp: timestamp::Module::<T>::now(),
-					},
-					input_data,
-					EmptyOutputBuf::new(),
+			let output = (|| -> Result<(), &'static str> {
+				// Send funds unconditionally here. If the `endowment` is below existential_deposit
+				// then error will be returned here.
+				transfer(
 					gas_meter,
-				)
-				.into_result()?;
+					TransferCause::Instantiate,
+					&self.self_account,
+					&dest,
+					endowment,
+					&mut nested,
+				)?;
 
-			// Deposit an instantiation event.
-			nested.events.push(IndexedEvent {
-				event: RawEvent::Instantiated(self.self_account.clone(), dest.clone()),
-				topics: Vec::new(),
+				let executable = self.loader.load_init(&code_hash)?;
+				self.vm
+					.execute(
+						&executable,
+						&mut CallContext {
+							ctx: &mut nested,
+							caller: self.self_account.clone(),
+							value_transferred: endowment,
+							timestamp: timestamp::Module::<T>::now(),
+						},
+						input_data,
+						EmptyOutputBuf::new(),
+						gas_meter,
+					)
+					.into_result()?;
+				Ok(())
+			})();
+
+			let nested_trace = nested.trace.take().map(|trace| (trace, nested.trace_capacity));
+			let result = output.map(|()| {
+				// Deposit an instantiation event.
+				nested.events.push(IndexedEvent {
+					event: RawEvent::Instantiated(self.self_account.clone(), dest.clone()),
+					topics: Vec::new(),
+				});
+
+				(nested.overlay.into_change_set(), nested.events, nested.calls)
 			});
-
-			(nested.overlay.into_change_set(), nested.events, nested.calls)
+			(result, nested_trace)
 		};
 
+		if let Some((entries, nested_trace_capacity)) = nested_trace {
+			self.reclaim_trace_capacity(nested_trace_capacity);
+			self.record_trace(TraceEntry::Frame(FrameTrace {
+				kind: FrameKind::Instantiate,
+				address: dest.clone(),
+				value: endowment,
+				input_data: input_data.to_vec(),
+				entries,
+				result: result.as_ref().map(|_| Vec::new()).map_err(|e| *e),
+				gas_left: gas_meter.gas_left(),
+			}));
+		}
+
+		let (change_set, events, calls) = result?;
 		self.overlay.commit(change_set);
 		self.events.extend(events);
 		self.calls.extend(calls);
@@ -479,6 +605,7 @@ impl<T: Trait> Token<T> for TransferFeeToken<BalanceOf<T>> {
 enum TransferCause {
 	Call,
 	Instantiate,
+	Terminate,
 }
 
 /// Transfer some funds from `transactor` to `dest`.
@@ -525,7 +652,7 @@ fn transfer<'a, T: Trait, V: Vm<T>, L: Loader<T>>(
 
 			// Otherwise the fee depends on whether we create a new account or transfer
 			// to an existing one.
-			Call => if would_create {
+			Call | Terminate => if would_create {
 				TransferFeeKind::AccountCreate
 			} else {
 				TransferFeeKind::Transfer
@@ -627,6 +754,14 @@ where
 		&self.ctx.self_account
 	}
 
+	fn is_tracing(&self) -> bool {
+		self.ctx.trace.is_some()
+	}
+
+	fn trace(&mut self, entry: TraceEntryOf<T>) {
+		self.ctx.record_trace(entry);
+	}
+
 	fn caller(&self) -> &T::AccountId {
 		&self.caller
 	}
@@ -647,6 +782,22 @@ where
 		&self.timestamp
 	}
 
+	fn block_number(&self) -> T::BlockNumber {
+		system::Module::<T>::block_number()
+	}
+
+	fn get_runtime_storage(&self, key: &[u8]) -> Option<Vec<u8>> {
+		runtime_io::storage(key)
+	}
+
+	fn chain_extension_gas_cost(&self, func_id: u32, input: &[u8]) -> Option<Gas> {
+		T::ChainExtension::gas_cost(func_id, input)
+	}
+
+	fn query_chain_extension(&self, func_id: u32, input: Vec<u8>) -> Result<Vec<u8>, &'static str> {
+		T::ChainExtension::query(func_id, &self.ctx.self_account, input)
+	}
+
 	fn deposit_event(&mut self, topics: Vec<T::Hash>, data: Vec<u8>) {
 		self.ctx.events.push(IndexedEvent {
 			topics,
@@ -662,6 +813,34 @@ where
 		self.ctx.overlay.get_rent_allowance(&self.ctx.self_account)
 			.unwrap_or(<BalanceOf<T>>::max_value()) // Must never be triggered actually
 	}
+
+	fn terminate(
+		&mut self,
+		beneficiary: &T::AccountId,
+		gas_meter: &mut GasMeter<T>,
+	) -> Result<(), &'static str> {
+		let self_id = self.ctx.self_account.clone();
+		if beneficiary == &self_id {
+			return Err("contract cannot be the beneficiary of its own termination");
+		}
+		// An outer invocation of the contract would keep executing code of a destroyed contract.
+		if self.ctx.callers.contains(&self_id) {
+			return Err("contract cannot be terminated while it is further up the call stack");
+		}
+
+		let value = self.ctx.overlay.get_balance(&self_id);
+		transfer(
+			gas_meter,
+			TransferCause::Terminate,
+			&self_id,
+			beneficiary,
+			value,
+			self.ctx,
+		)?;
+		self.ctx.overlay.destroy_contract(&self_id);
+
+		Ok(())
+	}
 }
 
 /// These tests exercise the executive layer.
@@ -681,6 +860,7 @@ mod tests {
 		Vm, VmExecResult, InstantiateReceipt, RawEvent, IndexedEvent,
 	};
 	use crate::account_db::AccountDb;
+	use crate::trace::{FrameKind, FrameTrace, TraceEntry};
 	use crate::gas::GasMeter;
 	use crate::tests::{ExtBuilder, Test};
 	use crate::{CodeHash, Config};
@@ -1187,6 +1367,59 @@ mod tests {
 		assert_eq!(&*witnessed_caller_charlie.borrow(), &Some(dest));
 	}
 
+	#[test]
+	fn terminate_fails_if_contract_is_further_up_the_call_stack() {
+		let vm = MockVm::new();
+
+		let witnessed_termination = RefCell::new(None);
+
+		let mut loader = MockLoader::empty();
+		let bob_ch = loader.insert(|ctx| {
+			if ctx.input_data.is_empty() {
+				// Call into CHARLIE, which calls back into BOB.
+				assert_matches!(
+					ctx.ext
+						.call(&CHARLIE, 0, ctx.gas_meter, &[], EmptyOutputBuf::new()),
+					Ok(_)
+				);
+			} else {
+				*witnessed_termination.borrow_mut() = Some(ctx.ext.terminate(&ALICE, ctx.gas_meter));
+			}
+			VmExecResult::Ok
+		});
+		let charlie_ch = loader.insert(|ctx| {
+			assert_matches!(
+				ctx.ext
+					.call(&BOB, 0, ctx.gas_meter, &[1], EmptyOutputBuf::new()),
+				Ok(_)
+			);
+			VmExecResult::Ok
+		});
+
+		with_externalities(&mut ExtBuilder::default().build(), || {
+			let cfg = Config::preload();
+			let mut ctx = ExecutionContext::top_level(ALICE, &cfg, &vm, &loader);
+			ctx.overlay.create_contract(&BOB, bob_ch).unwrap();
+			ctx.overlay.create_contract(&CHARLIE, charlie_ch).unwrap();
+
+			let result = ctx.call(
+				BOB,
+				0,
+				&mut GasMeter::<Test>::with_limit(10000, 1),
+				&[],
+				EmptyOutputBuf::new(),
+			);
+
+			assert_matches!(result, Ok(_));
+			assert!(ctx.overlay.contract_exists(&BOB));
+		});
+
+		assert_eq!(
+			*witnessed_termination.borrow(),
+			Some(Err("contract cannot be terminated while it is further up the call stack")),
+		);
+	}
+
 	#[test]
 	fn address_returns_proper_values() {
 		let vm = MockVm::new();
@@ -1429,4 +1662,62 @@ mod tests {
 			assert_matches!(result, Ok(_));
 		});
 	}
+
+	#[test]
+	fn trace_is_capped_across_frames() {
+		fn read(key: u8) -> TraceEntry<u64, u64> {
+			TraceEntry::StorageRead { key: [key; 32], value: None }
+		}
+
+		let vm = MockVm::new();
+		let mut loader = MockLoader::empty();
+		let charlie_ch = loader.insert(|ctx| {
+			for key in 10..13 {
+				ctx.ext.trace(read(key));
+			}
+			VmExecResult::Ok
+		});
+		let bob_ch = loader.insert(|ctx| {
+			ctx.ext.trace(read(1));
+			assert_matches!(
+				ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, &[], EmptyOutputBuf::new()),
+				Ok(_)
+			);
+			ctx.ext.trace(read(2));
+			VmExecResult::Ok
+		});
+
+		with_externalities(&mut ExtBuilder::default().build(), || {
+			let cfg = Config::preload();
+			let mut ctx = ExecutionContext::top_level(ALICE, &cfg, &vm, &loader);
+			ctx.overlay.create_contract(&BOB, bob_ch).unwrap();
+			ctx.overlay.create_contract(&CHARLIE, charlie_ch).unwrap();
+			ctx.enable_tracing(4);
+
+			let mut gas_meter = GasMeter::<Test>::with_limit(10000, 1);
+			assert_matches!(
+				ctx.call(BOB, 0, &mut gas_meter, &[], EmptyOutputBuf::new()),
+				Ok(_)
+			);
+
+			// The frames are recorded even though their contracts filled the trace.
+			let frame = |address, entries| TraceEntry::Frame(FrameTrace {
+				kind: FrameKind::Call,
+				address,
+				value: 0,
+				input_data: Vec::new(),
+				entries,
+				result: Ok(Vec::new()),
+				gas_left: gas_meter.gas_left(),
+			});
+			assert_eq!(
+				ctx.trace.take().unwrap(),
+				vec![frame(BOB, vec![
+					read(1),
+					frame(CHARLIE, vec![read(10), TraceEntry::Truncated]),
+					TraceEntry::Truncated,
+				])],
+			);
+		});
+	}
 }
@@ -36,7 +36,15 @@
 //! or call other smart-contracts.
 //!
 //! Finally, when an account is reaped, its associated code and storage of the smart-contract account
-//! will also be deleted.
+//! will also be deleted. A contract can also remove itself with `ext_terminate`, sending its remaining
+//! balance to a beneficiary.
+//!
+//! Contracts can ask the runtime to dispatch a `Call` with `ext_dispatch_call` once their execution has
+//! finished. Only the calls allowed by the `CallFilter` of the runtime can be dispatched this way. In
+//! addition, the runtime can expose its own read-only functions to contracts through a `ChainExtension`.
+//!
+//! Storing code reserves a deposit from the sender. Once no contract is instantiated from the code anymore,
+//! the sender can remove it and get the deposit back.
 //!
 //! ### Gas
 //!
@@ -59,6 +67,7 @@
 //! ### Dispatchable functions
 //!
 //! * `put_code` - Stores the given binary Wasm code into the chain's storage and returns its `code_hash`.
+//! * `remove_code` - Removes unused code stored by the sender and refunds its deposit.
 //! * `create` - Deploys a new contract from the given `code_hash`, optionally transferring some balance.
 //! This creates a new smart contract account and calls its contract deploy handler to initialize the contract.
 //! * `call` - Makes a call to an account, optionally transferring some balance.
@@ -84,13 +93,17 @@ mod account_db;
 mod exec;
 mod wasm;
 mod rent;
+mod trace;
 
 #[cfg(test)]
 mod tests;
+#[cfg(test)]
+mod schedule_benchmark;
 
 use crate::exec::ExecutionContext;
 use crate::account_db::{AccountDb, DirectAccountDb};
 pub use crate::gas::Gas;
+pub use crate::trace::{TraceEntry, HostCallTrace, FrameTrace, FrameKind, MAX_TRACE_ENTRIES};
 
 #[cfg(feature = "std")]
 use serde::{Serialize, Deserialize};
@@ -103,9 +116,12 @@ use runtime_primitives::traits::{
 };
 use srml_support::dispatch::{Result, Dispatchable};
 use srml_support::{
-	Parameter, StorageMap, StorageValue, decl_module, decl_event, decl_storage, storage::child
+	Parameter, StorageMap, StorageValue, decl_module, decl_event, decl_storage, storage::child,
+	storage::hashed::{self, generator::{StorageHasher, Twox128}},
+};
+use srml_support::traits::{
+	OnFreeBalanceZero, OnUnbalanced, Currency, ReservableCurrency, Get, Contains,
 };
-use srml_support::traits::{OnFreeBalanceZero, OnUnbalanced, Currency, Get};
 use system::{ensure_signed, RawOrigin};
 use substrate_primitives::storage::well_known_keys::CHILD_STORAGE_KEY_PREFIX;
 use timestamp;
@@ -123,6 +139,39 @@ pub trait ComputeDispatchFee<Call, Balance> {
 	fn compute_dispatch_fee(call: &Call) -> Balance;
 }
 
+/// Runtime functions that contracts can call synchronously with `ext_call_chain_extension`.
+///
+/// This allows a runtime to expose, for instance, queries of the balances of the `assets` module
+/// to contracts. The functions are identified by a `func_id` that is defined by the runtime. Input
+/// and output are opaque byte buffers, usually SCALE encoded.
+///
+/// The functions must be read-only. The changes made by a contract are kept in an overlay that is
+/// discarded if the contract fails, whereas a write of an extension would go straight to the runtime
+/// storage and could not be reverted. Contracts change the state of the runtime by dispatching
+/// calls with `ext_dispatch_call` instead.
+pub trait ChainExtension<AccountId> {
+	/// The gas charged for calling the function `func_id` with the given `input`, or `None` if
+	/// there is no such function.
+	///
+	/// The gas is charged before the function is called, so it must cover its worst case.
+	fn gas_cost(func_id: u32, input: &[u8]) -> Option<Gas>;
+
+	/// Call the read-only function `func_id` on behalf of the `contract` with the given `input`.
+	///
+	/// An error traps the contract.
+	fn query(func_id: u32, contract: &AccountId, input: Vec<u8>) -> rstd::result::Result<Vec<u8>, &'static str>;
+}
+
+impl<AccountId> ChainExtension<AccountId> for () {
+	fn gas_cost(_: u32, _: &[u8]) -> Option<Gas> {
+		None
+	}
+
+	fn query(_: u32, _: &AccountId, _: Vec<u8>) -> rstd::result::Result<Vec<u8>, &'static str> {
+		Err("chain extensions are not supported")
+	}
+}
+
 /// Information for managing an acocunt and its sub trie abstraction.
 /// This is the required info to cache for an account
 #[derive(Encode, Decode)]
@@ -207,6 +256,21 @@ pub struct RawAliveContractInfo<CodeHash, Balance, BlockNumber> {
 	pub last_write: Option<BlockNumber>,
 }
 
+pub type CodeInfo<T> =
+	RawCodeInfo<<T as system::Trait>::AccountId, BalanceOf<T>>;
+
+/// Bookkeeping information of a code stored with `put_code`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct RawCodeInfo<AccountId, Balance> {
+	/// The account that stored the code and paid the deposit for it.
+	pub owner: AccountId,
+	/// The deposit reserved from the owner, refunded when the code is removed.
+	pub deposit: Balance,
+	/// The number of contracts that are instantiated from the code.
+	pub refcount: u32,
+}
+
 pub type TombstoneContractInfo<T> =
 	RawTombstoneContractInfo<<T as system::Trait>::Hash, <T as system::Trait>::Hashing>;
 
@@ -296,7 +360,7 @@ pub const DEFAULT_MAX_DEPTH: u32 = 100;
 pub const DEFAULT_BLOCK_GAS_LIMIT: u32 = 10_000_000;
 
 pub trait Trait: timestamp::Trait {
-	type Currency: Currency<Self::AccountId>;
+	type Currency: ReservableCurrency<Self::AccountId>;
 
 	/// The outer call dispatch type.
 	type Call: Parameter + Dispatchable<Origin=<Self as system::Trait>::Origin>;
@@ -379,6 +443,20 @@ pub trait Trait: timestamp::Trait {
 	/// The maximum amount of gas that could be expended per block. A reasonable
 	/// default value is 10_000_000.
 	type BlockGasLimit: Get<Gas>;
+
+	/// The deposit reserved per byte of code stored with `put_code`. It is refunded
+	/// when the code is removed with `remove_code`.
+	type CodeDepositPerByte: Get<BalanceOf<Self>>;
+
+	/// The raw runtime storage keys that contracts are allowed to read with
+	/// `ext_get_runtime_storage`.
+	type RuntimeStorageWhitelist: Contains<Vec<u8>>;
+
+	/// The calls that contracts are allowed to dispatch with `ext_dispatch_call`.
+	type CallFilter: Contains<<Self as Trait>::Call>;
+
+	/// Custom runtime functions exposed to contracts. Use `()` if there are none.
+	type ChainExtension: ChainExtension<Self::AccountId>;
 }
 
 /// Simple contract address determiner.
@@ -404,8 +482,9 @@ where
 	}
 }
 
-/// The default dispatch fee computor computes the fee in the same way that
-/// the implementation of `MakePayment` for the Balances module does.
+/// The default dispatch fee computor computes the fee from the encoded length of the call, in
+/// the same way that the implementation of `MakePayment` for the Balances module charges the
+/// length of an extrinsic. Weight and the fee multiplier are not taken into account.
 pub struct DefaultDispatchFeeComputor<T: Trait>(PhantomData<T>);
 impl<T: Trait> ComputeDispatchFee<T::Call, BalanceOf<T>> for DefaultDispatchFeeComputor<T> {
 	fn compute_dispatch_fee(call: &T::Call) -> BalanceOf<T> {
@@ -480,13 +559,17 @@ decl_module! {
 		/// default value is 10_000_000.
 		const BlockGasLimit: Gas = T::BlockGasLimit::get();
 
+		/// The deposit reserved per byte of code stored with `put_code`. It is refunded
+		/// when the code is removed with `remove_code`.
+		const CodeDepositPerByte: BalanceOf<T> = T::CodeDepositPerByte::get();
+
 		fn deposit_event<T>() = default;
 
 		/// Updates the schedule for metering contracts.
 		///
 		/// The schedule must have a greater version than the stored schedule.
 		pub fn update_schedule(schedule: Schedule) -> Result {
-			if <Module<T>>::current_schedule().version >= schedule.version {
+			if <Module<T>>::current_schedule_version() >= schedule.version {
 				return Err("new schedule must have a greater version than current");
 			}
 
@@ -498,6 +581,9 @@ decl_module! {
 
 		/// Stores the given binary Wasm code into the chain's storage and returns its `codehash`.
 		/// You can instantiate contracts only with stored code.
+		///
+		/// A deposit of `CodeDepositPerByte` for each byte of the code is reserved from the sender
+		/// unless the same code is already stored.
 		pub fn put_code(
 			origin,
 			#[compact] gas_limit: Gas,
@@ -508,7 +594,7 @@ decl_module! {
 
 			let (mut gas_meter, imbalance) = gas::buy_gas::<T>(&origin, gas_limit)?;
 
-			let result = wasm::save_code::<T>(code, &mut gas_meter, &schedule);
+			let result = wasm::save_code::<T>(code, &origin, &mut gas_meter, &schedule);
 			if let Ok(code_hash) = result {
 				Self::deposit_event(RawEvent::CodeStored(code_hash));
 			}
@@ -518,6 +604,20 @@ decl_module! {
 			result.map(|_| ())
 		}
 
+		/// Removes the code with the given `code_hash` from the chain's storage and refunds the
+		/// deposit reserved for it.
+		///
+		/// Only the account that stored the code can remove it, and only once no contract is
+		/// instantiated from it anymore.
+		pub fn remove_code(origin, code_hash: CodeHash<T>) -> Result {
+			let origin = ensure_signed(origin)?;
+
+			wasm::remove_code::<T>(&origin, &code_hash)?;
+			Self::deposit_event(RawEvent::CodeRemoved(code_hash));
+
+			Ok(())
+		}
+
 		/// Makes a call to an account, optionally transferring some balance.
 		///
 		/// * If the account is a smart-contract account, the associated code will be
@@ -731,7 +831,9 @@ decl_module! {
 				.map(|(_, value)| value.len() as u32)
 				.sum::<u32>();
 
+			// The restored contract takes over the reference the tombstone held on `code_hash`.
 			<ContractInfoOf<T>>::remove(&origin);
+			wasm::decrement_refcount::<T>(&origin_contract.code_hash);
 			<ContractInfoOf<T>>::insert(&dest, ContractInfo::Alive(RawAliveContractInfo {
 				trie_id: origin_contract.trie_id,
 				storage_size: origin_contract.storage_size,
@@ -752,6 +854,109 @@ decl_module! {
 	}
 }
 
+/// The outcome of a contract call performed through `Module::bare_call`.
+pub struct BareCallOutcome<T: Trait> {
+	/// The output buffer returned by the contract, or the reason why the call failed.
+	pub result: rstd::result::Result<Vec<u8>, &'static str>,
+	/// The amount of gas that was consumed by the call.
+	pub gas_consumed: Gas,
+	/// The events the call would have deposited. Empty if the call failed.
+	pub events: Vec<Event<T>>,
+}
+
+impl<T: Trait> Module<T> {
+	/// The current cost schedule for contracts.
+	///
+	/// A schedule stored in the layout of `ScheduleV0` is converted, so that chains which
+	/// started before the current layout keep their schedule until it is updated.
+	pub fn current_schedule() -> Schedule {
+		let raw = match hashed::get_raw(&Twox128::hash, <CurrentSchedule as StorageValue<Schedule>>::key()) {
+			Some(raw) => raw,
+			None => return Schedule::default(),
+		};
+		Schedule::decode(&mut &raw[..])
+			.or_else(|| ScheduleV0::decode(&mut &raw[..]).map(Into::into))
+			.unwrap_or_default()
+	}
+
+	/// The version of the current schedule, whatever the layout it is stored in.
+	fn current_schedule_version() -> u32 {
+		// The version comes first in every layout of the schedule.
+		hashed::get(&Twox128::hash, <CurrentSchedule as StorageValue<Schedule>>::key()).unwrap_or(0)
+	}
+
+	/// Perform a call to a specified contract without committing any of its effects.
+	///
+	/// Unlike the `call` dispatchable, no gas is bought from `origin`, the changes made by the
+	/// contract are not committed, no events are deposited and calls recorded by the contract are
+	/// not dispatched. Only the rent of `dest` may be collected, just like for any other call. This
+	/// makes it suitable for querying contracts, e.g. through the `ContractsApi` runtime API, where
+	/// all state changes are discarded anyway. The gas limit is capped at `T::BlockGasLimit`, the
+	/// most gas a dispatched call could ever consume.
+	pub fn bare_call(
+		origin: T::AccountId,
+		dest: T::AccountId,
+		value: BalanceOf<T>,
+		gas_limit: Gas,
+		input_data: Vec<u8>,
+	) -> BareCallOutcome<T> {
+		Self::execute_bare_call(origin, dest, value, gas_limit, input_data, false).0
+	}
+
+	/// Perform a call to a specified contract like `bare_call` and record a trace of its execution.
+	///
+	/// The trace holds the frame of `dest`, which lists every host function invoked by the
+	/// contract together with its arguments, its return value and the gas left afterwards, the
+	/// storage accessed by the contract and the frames of the contracts it called or instantiated.
+	/// The trace is empty if the call failed before `dest` was entered, e.g. because the gas limit
+	/// doesn't cover the base call fee. It holds at most `MAX_TRACE_ENTRIES` entries.
+	pub fn trace_call(
+		origin: T::AccountId,
+		dest: T::AccountId,
+		value: BalanceOf<T>,
+		gas_limit: Gas,
+		input_data: Vec<u8>,
+	) -> (BareCallOutcome<T>, Vec<TraceEntry<T::AccountId, BalanceOf<T>>>) {
+		Self::execute_bare_call(origin, dest, value, gas_limit, input_data, true)
+	}
+
+	fn execute_bare_call(
+		origin: T::AccountId,
+		dest: T::AccountId,
+		value: BalanceOf<T>,
+		gas_limit: Gas,
+		input_data: Vec<u8>,
+		trace: bool,
+	) -> (BareCallOutcome<T>, Vec<TraceEntry<T::AccountId, BalanceOf<T>>>) {
+		let gas_limit = rstd::cmp::min(gas_limit, T::BlockGasLimit::get());
+		let mut gas_meter = gas::GasMeter::with_limit(gas_limit, Self::gas_price());
+
+		let cfg = Config::preload();
+		let vm = crate::wasm::WasmVm::new(&cfg.schedule);
+		let loader = crate::wasm::WasmLoader::new(&cfg.schedule);
+		let mut ctx = ExecutionContext::top_level(origin, &cfg, &vm, &loader);
+		if trace {
+			ctx.enable_tracing(MAX_TRACE_ENTRIES);
+		}
+
+		let result = ctx.call(dest, value, &mut gas_meter, &input_data, exec::EmptyOutputBuf::new())
+			.map(|receipt| receipt.output_data);
+		let trace = ctx.trace.take().unwrap_or_default();
+		let events = if result.is_ok() {
+			ctx.events.into_iter().map(|indexed_event| indexed_event.event).collect()
+		} else {
+			Vec::new()
+		};
+
+		let outcome = BareCallOutcome {
+			result,
+			gas_consumed: gas_meter.spent(),
+			events,
+		};
+		(outcome, trace)
+	}
+}
+
 decl_event! {
 	pub enum Event<T>
 	where
@@ -768,6 +973,9 @@ decl_event! {
 		/// Code with the specified hash has been stored.
 		CodeStored(Hash),
 
+		/// Code with the specified hash has been removed.
+		CodeRemoved(Hash),
+
 		/// Triggered when the current schedule is updated.
 		ScheduleUpdated(u32),
 
@@ -785,11 +993,16 @@ decl_storage! {
 		/// Gas spent so far in this block.
 		GasSpent get(gas_spent): Gas;
 		/// Current cost schedule for contracts.
-		CurrentSchedule get(current_schedule) config(): Schedule = Schedule::default();
+		///
+		/// Read through `Module::current_schedule`, which also understands the layout of
+		/// `ScheduleV0`.
+		CurrentSchedule config(current_schedule): Schedule = Schedule::default();
 		/// A mapping from an original code hash to the original code, untouched by instrumentation.
 		pub PristineCode: map CodeHash<T> => Option<Vec<u8>>;
 		/// A mapping between an original code hash and instrumented wasm code, ready for execution.
 		pub CodeStorage: map CodeHash<T> => Option<wasm::PrefabWasmModule>;
+		/// The owner, deposit and reference count of a code stored with `put_code`.
+		pub CodeInfoOf: map CodeHash<T> => Option<CodeInfo<T>>;
 		/// The subtrie counter.
 		pub AccountCounter: u64 = 0;
 		/// The code associated with a given account.
@@ -803,7 +1016,10 @@ impl<T: Trait> OnFreeBalanceZero<T::AccountId> for Module<T> {
 	fn on_free_balance_zero(who: &T::AccountId) {
 		if let Some(ContractInfo::Alive(info)) = <ContractInfoOf<T>>::get(who) {
 			child::kill_storage(&info.trie_id);
+			wasm::decrement_refcount::<T>(&info.code_hash);
 		}
+		// A removed tombstone keeps its reference, as the code hash cannot be recovered from it.
+		// Its code can therefore never be removed, which is the safe side to err on.
 		<ContractInfoOf<T>>::remove(who);
 	}
 }
@@ -835,6 +1051,9 @@ impl<T: Trait> Config<T> {
 }
 
 /// Definition of the cost schedule and other parameterizations for wasm vm.
+///
+/// The costs can be calibrated for the hardware at hand with the `suggest_schedule` benchmark of this
+/// crate, which suggests a schedule based on the measured execution times.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
 #[derive(Clone, Encode, Decode, PartialEq, Eq)]
 pub struct Schedule {
@@ -847,9 +1066,45 @@ pub struct Schedule {
 	/// Gas cost of a growing memory by single page.
 	pub grow_mem_cost: Gas,
 
-	/// Gas cost of a regular operation.
+	/// Gas cost of a regular operation, i.e. of an instruction without a more specific cost below.
 	pub regular_op_cost: Gas,
 
+	/// Gas cost of an instruction loading from the memory.
+	pub load_op_cost: Gas,
+
+	/// Gas cost of an instruction storing to the memory.
+	pub store_op_cost: Gas,
+
+	/// Gas cost of a control flow instruction, such as a branch or a call.
+	pub control_flow_op_cost: Gas,
+
+	/// Gas cost of an instruction accessing a local variable.
+	pub local_op_cost: Gas,
+
+	/// Gas cost of an instruction accessing a global variable.
+	pub global_op_cost: Gas,
+
+	/// Gas cost of an integer constant.
+	pub const_op_cost: Gas,
+
+	/// Gas cost of an integer addition or subtraction.
+	pub add_op_cost: Gas,
+
+	/// Gas cost of an integer multiplication.
+	pub mul_op_cost: Gas,
+
+	/// Gas cost of an integer division or remainder.
+	pub div_op_cost: Gas,
+
+	/// Gas cost of a bitwise integer instruction, such as a shift or a rotation.
+	pub bit_op_cost: Gas,
+
+	/// Gas cost of an integer comparison.
+	pub comparison_op_cost: Gas,
+
+	/// Gas cost of a conversion between integer types.
+	pub conversion_op_cost: Gas,
+
 	/// Gas cost per one byte returned.
 	pub return_data_per_byte_cost: Gas,
 
@@ -892,6 +1147,37 @@ pub struct Schedule {
 
 	/// The maximum length of a subject used for PRNG generation.
 	pub max_subject_len: u32,
+
+	/// Gas cost of computing a BLAKE2 256-bit hash; the base.
+	pub hash_blake2_256_base_cost: Gas,
+
+	/// Gas cost of computing a BLAKE2 256-bit hash; the per-byte portion.
+	pub hash_blake2_256_per_byte_cost: Gas,
+
+	/// Gas cost of computing a KECCAK 256-bit hash; the base.
+	pub hash_keccak_256_base_cost: Gas,
+
+	/// Gas cost of computing a KECCAK 256-bit hash; the per-byte portion.
+	pub hash_keccak_256_per_byte_cost: Gas,
+
+	/// Gas cost of computing a SHA2 256-bit hash; the base.
+	pub hash_sha2_256_base_cost: Gas,
+
+	/// Gas cost of computing a SHA2 256-bit hash; the per-byte portion.
+	pub hash_sha2_256_per_byte_cost: Gas,
+
+	/// Gas cost of querying the current block number.
+	pub block_number_cost: Gas,
+
+	/// Gas cost of reading a whitelisted runtime storage entry; the base.
+	pub get_runtime_storage_base_cost: Gas,
+
+	/// Gas cost of reading a whitelisted runtime storage entry; the per-byte portion of the value.
+	pub get_runtime_storage_per_byte_cost: Gas,
+
+	/// Gas cost of calling a function of the chain extension, in addition to the gas
+	/// used by the function itself.
+	pub chain_extension_base_cost: Gas,
 }
 
 impl Default for Schedule {
@@ -901,6 +1187,18 @@ impl Default for Schedule {
 			put_code_per_byte_cost: 1,
 			grow_mem_cost: 1,
 			regular_op_cost: 1,
+			load_op_cost: 1,
+			store_op_cost: 1,
+			control_flow_op_cost: 1,
+			local_op_cost: 1,
+			global_op_cost: 1,
+			const_op_cost: 1,
+			add_op_cost: 1,
+			mul_op_cost: 1,
+			div_op_cost: 1,
+			bit_op_cost: 1,
+			comparison_op_cost: 1,
+			conversion_op_cost: 1,
 			return_data_per_byte_cost: 1,
 			event_data_per_byte_cost: 1,
 			event_per_topic_cost: 1,
@@ -914,6 +1212,79 @@ impl Default for Schedule {
 			max_memory_pages: 16,
 			enable_println: false,
 			max_subject_len: 32,
+			hash_blake2_256_base_cost: 20,
+			hash_blake2_256_per_byte_cost: 1,
+			hash_keccak_256_base_cost: 25,
+			hash_keccak_256_per_byte_cost: 1,
+			hash_sha2_256_base_cost: 25,
+			hash_sha2_256_per_byte_cost: 1,
+			block_number_cost: 1,
+			get_runtime_storage_base_cost: 100,
+			get_runtime_storage_per_byte_cost: 1,
+			chain_extension_base_cost: 100,
+		}
+	}
+}
+
+/// The layout of `Schedule` before the costs of specific instruction classes, of the hash,
+/// block number and runtime storage functions and of chain extensions were added.
+#[derive(Decode)]
+struct ScheduleV0 {
+	version: u32,
+	put_code_per_byte_cost: Gas,
+	grow_mem_cost: Gas,
+	regular_op_cost: Gas,
+	return_data_per_byte_cost: Gas,
+	event_data_per_byte_cost: Gas,
+	event_per_topic_cost: Gas,
+	event_base_cost: Gas,
+	call_base_cost: Gas,
+	instantiate_base_cost: Gas,
+	sandbox_data_read_cost: Gas,
+	sandbox_data_write_cost: Gas,
+	max_event_topics: u32,
+	max_stack_height: u32,
+	max_memory_pages: u32,
+	enable_println: bool,
+	max_subject_len: u32,
+}
+
+impl From<ScheduleV0> for Schedule {
+	/// Every instruction keeps costing `regular_op_cost`, as it did before; the costs of the
+	/// functions which did not exist yet are the default ones.
+	fn from(old: ScheduleV0) -> Schedule {
+		let op_cost = old.regular_op_cost;
+		Schedule {
+			version: old.version,
+			put_code_per_byte_cost: old.put_code_per_byte_cost,
+			grow_mem_cost: old.grow_mem_cost,
+			regular_op_cost: op_cost,
+			load_op_cost: op_cost,
+			store_op_cost: op_cost,
+			control_flow_op_cost: op_cost,
+			local_op_cost: op_cost,
+			global_op_cost: op_cost,
+			const_op_cost: op_cost,
+			add_op_cost: op_cost,
+			mul_op_cost: op_cost,
+			div_op_cost: op_cost,
+			bit_op_cost: op_cost,
+			comparison_op_cost: op_cost,
+			conversion_op_cost: op_cost,
+			return_data_per_byte_cost: old.return_data_per_byte_cost,
+			event_data_per_byte_cost: old.event_data_per_byte_cost,
+			event_per_topic_cost: old.event_per_topic_cost,
+			event_base_cost: old.event_base_cost,
+			call_base_cost: old.call_base_cost,
+			instantiate_base_cost: old.instantiate_base_cost,
+			sandbox_data_read_cost: old.sandbox_data_read_cost,
+			sandbox_data_write_cost: old.sandbox_data_write_cost,
+			max_event_topics: old.max_event_topics,
+			max_stack_height: old.max_stack_height,
+			max_memory_pages: old.max_memory_pages,
+			enable_println: old.enable_println,
+			max_subject_len: old.max_subject_len,
+			..Schedule::default()
 		}
 	}
 }
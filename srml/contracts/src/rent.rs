@@ -15,6 +15,7 @@
 // along with Substrate. If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{BalanceOf, ContractInfo, ContractInfoOf, TombstoneContractInfo, Trait};
+use crate::wasm;
 use runtime_primitives::traits::{Bounded, CheckedDiv, CheckedMul, Saturating, Zero,
 	SaturatedConversion};
 use srml_support::traits::{Currency, ExistenceRequirement, Get, WithdrawReason};
@@ -99,6 +100,7 @@ fn try_evict_or_and_pay_rent<T: Trait>(
 		// The contract cannot afford to leave a tombstone, so remove the contract info altogether.
 		<ContractInfoOf<T>>::remove(account);
 		runtime_io::kill_child_storage(&contract.trie_id);
+		wasm::decrement_refcount::<T>(&contract.code_hash);
 		return RentOutcome::Evicted;
 	}
 
@@ -151,6 +153,7 @@ fn try_evict_or_and_pay_rent<T: Trait>(
 		);
 		<ContractInfoOf<T>>::insert(account, ContractInfo::Tombstone(tombstone));
 
+		// The tombstone keeps referencing the code, since the contract can be restored with it.
 		runtime_io::kill_child_storage(&contract.trie_id);
 
 		return RentOutcome::Evicted;
@@ -0,0 +1,531 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks the costs of the `Schedule` on the current hardware.
+//!
+//! Every instruction class and host function is measured by calling calibrated fixtures with
+//! `Module::bare_call` on the mock runtime of the tests, i.e. through the real host functions and
+//! the `sr-sandbox` executor. A fixture executes the measured code in a loop and is compared
+//! against the very same loop with an empty body.
+//!
+//! The cheapest instruction class is priced at one gas, all the other costs are derived from the
+//! time it takes. The suggested `Schedule` is printed to stdout as JSON:
+//!
+//! ```sh
+//! cargo test -p srml-contracts --release suggest_schedule -- --ignored --nocapture
+//! ```
+//!
+//! Note that the `version` of the schedule has to be increased before passing it
+//! to `update_schedule`.
+
+use crate::tests::{Balances, Contract, ExtBuilder, Origin, Test, ALICE, WHITELISTED_RUNTIME_KEY};
+use crate::{Gas, Schedule};
+use parity_codec::Encode;
+use runtime_io::with_externalities;
+use runtime_primitives::testing::H256;
+use runtime_primitives::traits::Hash;
+use srml_support::traits::Currency;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// The first account that deploys a fixture. The mock runtime puts the contract at the next
+/// address, so every fixture is deployed by a new account two addresses further.
+const FIRST_DEPLOYER: usize = 1_000;
+static NEXT_DEPLOYER: AtomicUsize = AtomicUsize::new(FIRST_DEPLOYER);
+
+/// Enough balance to never run out of it while deploying and calling the fixtures.
+const ENDOWMENT: u64 = 1 << 60;
+
+/// Enough gas to never run out of it while executing the fixtures.
+const GAS_LIMIT: Gas = 1 << 50;
+
+/// The gas limit of the extrinsics used to deploy the fixtures.
+const DEPLOY_GAS_LIMIT: Gas = 100_000_000;
+
+/// How many times every fixture is called. The median of the calls is taken.
+const RUNS: usize = 21;
+
+/// How many times a fixture executes a host function in a loop.
+const HOST_FN_REPEAT: u32 = 100;
+
+/// How many times a fixture executes instructions in a loop.
+const INSTRUCTION_REPEAT: u32 = 10_000;
+
+// The layout of the fixture memory.
+//
+// The input of a call is copied to `INPUT_PTR` and can be up to `BUFFER_LEN` bytes long. The
+// data passed to the host functions are taken from a zeroed buffer of `BUFFER_LEN` bytes at
+// `BUFFER_PTR`.
+const INPUT_PTR: u32 = 0;
+const BUFFER_PTR: u32 = 16 * 1024;
+const BUFFER_LEN: u32 = 16 * 1024;
+const HASH_OUTPUT_PTR: u32 = 32 * 1024;
+const VALUE_PTR: u32 = 33 * 1024;
+const KEY_PTR: u32 = 34 * 1024;
+const TOPICS_PTR: u32 = 40 * 1024;
+
+/// The number of topics of the `ext_deposit_event` fixture.
+const TOPIC_COUNT: u32 = 4;
+
+/// The maximum number of memory pages available to the fixtures.
+fn max_memory_pages() -> u32 {
+	Contract::current_schedule().max_memory_pages
+}
+
+/// Renders the bytes as a string literal of the wat text format.
+fn escape(bytes: &[u8]) -> String {
+	bytes.iter().map(|byte| format!("\\{:02x}", byte)).collect()
+}
+
+/// Returns a contract that executes `body` `repeat` times in a loop.
+///
+/// The input of the call is copied to `INPUT_PTR` before the loop starts. The body can use the
+/// `i32` local `$x`, the `i64` local `$y`, the mutable `i32` global `$g` and the function `$noop`.
+/// The host functions used by the body, except for `ext_scratch_size` and `ext_scratch_copy`, have
+/// to be declared in `imports`. `data` can contain additional data segments.
+fn looped_contract(imports: &str, body: &str, repeat: u32, data: &str) -> String {
+	format!(
+		r#"
+(module
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_copy" (func $ext_scratch_copy (param i32 i32 i32)))
+	{imports}
+	(import "env" "memory" (memory 1 {max_pages}))
+
+	(global $g (mut i32) (i32.const 0))
+
+	(func $noop)
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(local $i i32)
+		(local $x i32)
+		(local $y i64)
+		(call $ext_scratch_copy
+			(i32.const {input_ptr})
+			(i32.const 0)
+			(call $ext_scratch_size)
+		)
+		(set_local $i (i32.const {repeat}))
+		(block $done
+			(loop $loop
+				(br_if $done (i32.eqz (get_local $i)))
+				{body}
+				(set_local $i (i32.sub (get_local $i) (i32.const 1)))
+				(br $loop)
+			)
+		)
+	)
+	{data}
+)
+"#,
+		imports = imports,
+		max_pages = max_memory_pages(),
+		input_ptr = INPUT_PTR,
+		repeat = repeat,
+		body = body,
+		data = data,
+	)
+}
+
+/// Returns a contract that doesn't do anything.
+fn empty_contract() -> String {
+	looped_contract("", "", 0, "")
+}
+
+/// Stores and instantiates the contract and returns its address and code hash.
+fn deploy(wat: &str) -> (u64, H256) {
+	let wasm = wabt::wat2wasm(wat).expect("fixtures are valid wat; qed");
+	let code_hash = <Test as system::Trait>::Hashing::hash(&wasm);
+
+	let deployer = NEXT_DEPLOYER.fetch_add(2, Ordering::SeqCst) as u64;
+	Balances::deposit_creating(&deployer, ENDOWMENT);
+	Contract::put_code(Origin::signed(deployer), DEPLOY_GAS_LIMIT, wasm)
+		.expect("fixtures are valid contracts; qed");
+	Contract::create(Origin::signed(deployer), 1 << 40, DEPLOY_GAS_LIMIT, code_hash, Vec::new())
+		.expect("the deployer has enough funds to instantiate the fixture; qed");
+
+	(deployer + 1, code_hash)
+}
+
+/// Runs `run` `RUNS` times and returns the median time it took in nanoseconds.
+///
+/// `setup` prepares the input of every run and is not measured.
+fn median_ns<I>(mut setup: impl FnMut() -> I, mut run: impl FnMut(I)) -> f64 {
+	let mut samples = (0..RUNS)
+		.map(|_| {
+			let input = setup();
+			let started = Instant::now();
+			run(input);
+			started.elapsed().as_nanos() as f64
+		})
+		.collect::<Vec<_>>();
+	samples.sort_by(|a, b| a.partial_cmp(b).expect("durations are never NaN; qed"));
+	samples[samples.len() / 2]
+}
+
+/// Returns the median time of calling the contract with the given input in nanoseconds.
+fn call_ns(contract: u64, input: &[u8]) -> f64 {
+	median_ns(
+		|| input.to_vec(),
+		|input| {
+			let outcome = Contract::bare_call(ALICE, contract, 0, GAS_LIMIT, input);
+			if let Err(err) = outcome.result {
+				panic!("fixture {} failed: {}", contract, err);
+			}
+		},
+	)
+}
+
+/// Returns the time of a single execution of `body` in nanoseconds.
+///
+/// The loop overhead is excluded by subtracting the time of the same loop with an empty body.
+fn looped_ns(imports: &str, body: &str, repeat: u32, data: &str, input: &[u8]) -> f64 {
+	let (fixture, _) = deploy(&looped_contract(imports, body, repeat, data));
+	let (baseline, _) = deploy(&looped_contract(imports, "", repeat, data));
+	let ns = call_ns(fixture, input) - call_ns(baseline, input);
+	ns.max(0.0) / repeat as f64
+}
+
+/// Returns the time of a single instruction of `body`, which executes `count` instructions.
+fn instructions_ns(body: &str, count: u32) -> f64 {
+	looped_ns("", body, INSTRUCTION_REPEAT, "", &[]) / count as f64
+}
+
+/// Returns the time per byte of `measure`, which measures the time for the given number of bytes.
+fn per_byte_ns(len: u32, measure: impl Fn(u32) -> f64) -> f64 {
+	((measure(len) - measure(0)) / len as f64).max(0.0)
+}
+
+/// Prints the measured time to stderr and returns it.
+fn report(name: &str, ns: f64) -> f64 {
+	eprintln!("{:>36}: {:>12.2} ns", name, ns);
+	ns
+}
+
+/// Returns the time to store code in nanoseconds per byte.
+fn put_code_per_byte_ns() -> f64 {
+	// Every stored code has to be unique, otherwise it is not instrumented again.
+	let mut nonce = 0u32;
+	let mut put_code_ns = |instructions: usize| {
+		median_ns(
+			|| {
+				nonce += 1;
+				let code = format!(
+					r#"
+(module
+	(import "env" "memory" (memory 1 1))
+	(func (export "deploy"))
+	(func (export "call")
+		(drop (i32.const {nonce}))
+		{body}
+	)
+)
+"#,
+					nonce = nonce,
+					body = "(drop (i32.const 1))".repeat(instructions),
+				);
+				wabt::wat2wasm(code).expect("fixtures are valid wat; qed")
+			},
+			|wasm| {
+				Contract::put_code(Origin::signed(ALICE), DEPLOY_GAS_LIMIT, wasm)
+					.expect("fixtures are valid contracts; qed");
+			},
+		)
+	};
+
+	let small = put_code_ns(0);
+	let big = put_code_ns(5_000);
+	// Each of the instructions is encoded as `i32.const 1` (2 bytes) and `drop` (1 byte).
+	((big - small) / (5_000 * 3) as f64).max(0.0)
+}
+
+/// Converts the time to gas, rounding up.
+fn to_gas(ns: f64, ns_per_gas: f64) -> Gas {
+	(ns / ns_per_gas).ceil() as Gas
+}
+
+/// Measures the current hardware and returns the suggested schedule.
+fn suggest_schedule() -> Schedule {
+	// The instructions of a class are measured together with the locals and constants needed to
+	// feed them, which are measured first and subtracted.
+	let local_ns = report("local op", instructions_ns("(set_local $x (get_local $x))", 2));
+	let const_ns = report(
+		"const op",
+		(instructions_ns("(set_local $x (i32.const 1))", 1) - local_ns).max(0.0),
+	);
+	let binary_ns = |name: &str, op: &str| report(
+		name,
+		(instructions_ns(&format!("(set_local $x ({} (get_local $x) (i32.const 1)))", op), 1)
+			- 2.0 * local_ns - const_ns).max(0.0),
+	);
+	let add_ns = binary_ns("add op", "i32.add");
+	let mul_ns = binary_ns("mul op", "i32.mul");
+	let div_ns = binary_ns("div op", "i32.div_u");
+	let bit_ns = binary_ns("bit op", "i32.rotl");
+	let comparison_ns = binary_ns("comparison op", "i32.lt_u");
+	let conversion_ns = report(
+		"conversion op",
+		(instructions_ns("(set_local $y (i64.extend_u/i32 (get_local $x)))", 1) - 2.0 * local_ns)
+			.max(0.0),
+	);
+	let global_ns = report("global op", instructions_ns("(set_global $g (get_global $g))", 2));
+	let load_ns = report(
+		"load op",
+		(instructions_ns(&format!("(set_local $x (i32.load (i32.const {})))", BUFFER_PTR), 1)
+			- local_ns - const_ns).max(0.0),
+	);
+	let store_ns = report(
+		"store op",
+		(instructions_ns(&format!("(i32.store (i32.const {}) (get_local $x))", BUFFER_PTR), 1)
+			- local_ns - const_ns).max(0.0),
+	);
+	// A branch to the end of a block executes `block`, `br` and `end`, a call executes `call`
+	// and the `end` of the callee. Both share the cost of a control flow instruction.
+	let branch_ns = report("branch", instructions_ns("(block $b (br $b))", 3));
+	let call_ns = report("call", instructions_ns("(call $noop)", 2));
+	let control_flow_ns = branch_ns.max(call_ns);
+	let regular_ns = report("regular op", instructions_ns("(nop)", 1));
+
+	let instruction_ns = [
+		regular_ns, load_ns, store_ns, control_flow_ns, local_ns, global_ns, const_ns,
+		add_ns, mul_ns, div_ns, bit_ns, comparison_ns, conversion_ns,
+	];
+	let ns_per_gas = instruction_ns
+		.iter()
+		.cloned()
+		.filter(|ns| *ns > 0.0)
+		.fold(std::f64::INFINITY, f64::min);
+	assert!(ns_per_gas.is_finite(), "at least one instruction class takes measurable time");
+	let gas = |ns: f64| to_gas(ns, ns_per_gas);
+	// Instructions are never free, otherwise a contract could loop forever.
+	let instruction_gas = |ns: f64| gas(ns).max(1);
+
+	let grow_mem_ns = report(
+		"grow memory per page",
+		looped_ns("", "(drop (grow_memory (i32.const 1)))", max_memory_pages() - 1, "", &[]),
+	);
+
+	let set_rent_allowance = |len: u32| looped_ns(
+		r#"(import "env" "ext_set_rent_allowance" (func $ext_set_rent_allowance (param i32 i32)))"#,
+		&format!("(call $ext_set_rent_allowance (i32.const {}) (i32.const {}))", BUFFER_PTR, 8 + len),
+		HOST_FN_REPEAT,
+		"",
+		&[],
+	);
+	let read_ns = report("sandbox data read per byte", per_byte_ns(BUFFER_LEN - 8, set_rent_allowance));
+
+	let write_input = vec![0u8; BUFFER_LEN as usize];
+	let scratch_copy = |len: u32| looped_ns(
+		"",
+		&format!("(call $ext_scratch_copy (i32.const {}) (i32.const 0) (i32.const {}))", BUFFER_PTR, len),
+		HOST_FN_REPEAT,
+		"",
+		&write_input,
+	);
+	let write_ns = report("sandbox data write per byte", per_byte_ns(BUFFER_LEN, scratch_copy));
+
+	let return_data = |len: u32| looped_ns(
+		r#"(import "env" "ext_return" (func $ext_return (param i32 i32)))"#,
+		&format!("(call $ext_return (i32.const {}) (i32.const {}))", BUFFER_PTR, len),
+		1,
+		"",
+		&[],
+	);
+	let return_data_ns = report(
+		"return data per byte",
+		(per_byte_ns(BUFFER_LEN, return_data) - read_ns).max(0.0),
+	);
+
+	// The topics are distinct, as duplicates are not allowed.
+	let mut topics = Vec::new();
+	for topic in 0..TOPIC_COUNT {
+		let mut topic_bytes = [0u8; 32];
+		topic_bytes[0] = topic as u8 + 1;
+		topics.push(H256::from(topic_bytes));
+	}
+	let encoded_topics = topics.encode();
+	let deposit_event = |topics_len: usize, data_len: u32| looped_ns(
+		r#"(import "env" "ext_deposit_event" (func $ext_deposit_event (param i32 i32 i32 i32)))"#,
+		&format!(
+			"(call $ext_deposit_event (i32.const {}) (i32.const {}) (i32.const {}) (i32.const {}))",
+			TOPICS_PTR, topics_len, BUFFER_PTR, data_len,
+		),
+		HOST_FN_REPEAT,
+		&format!("(data (i32.const {}) \"{}\")", TOPICS_PTR, escape(&encoded_topics)),
+		&[],
+	);
+	let event_base_ns = report("event base", deposit_event(0, 0));
+	let event_data_ns = report(
+		"event data per byte",
+		(per_byte_ns(BUFFER_LEN, |len| deposit_event(0, len)) - read_ns).max(0.0),
+	);
+	let event_topic_ns = report(
+		"event per topic",
+		((deposit_event(encoded_topics.len(), 0) - event_base_ns) / TOPIC_COUNT as f64
+			- read_ns * encoded_topics.len() as f64 / TOPIC_COUNT as f64).max(0.0),
+	);
+
+	let (callee, callee_code_hash) = deploy(&empty_contract());
+	let call_base_ns = report(
+		"call base",
+		(looped_ns(
+			r#"(import "env" "ext_call" (func $ext_call (param i32 i32 i64 i32 i32 i32 i32) (result i32)))"#,
+			&format!(
+				"(if (call $ext_call (i32.const {}) (i32.const 8) (i64.const 0) (i32.const {}) (i32.const 8) \
+					(i32.const 0) (i32.const 0)) (then (unreachable)))",
+				INPUT_PTR, VALUE_PTR,
+			),
+			HOST_FN_REPEAT,
+			"",
+			&callee.encode(),
+		) - read_ns * 16.0).max(0.0),
+	);
+	let instantiate_ns = report(
+		"instantiate base",
+		(looped_ns(
+			r#"(import "env" "ext_create" (func $ext_create (param i32 i32 i64 i32 i32 i32 i32) (result i32)))"#,
+			&format!(
+				"(if (call $ext_create (i32.const {}) (i32.const 32) (i64.const 0) (i32.const {}) (i32.const 8) \
+					(i32.const 0) (i32.const 0)) (then (unreachable)))",
+				INPUT_PTR, VALUE_PTR,
+			),
+			HOST_FN_REPEAT,
+			"",
+			&callee_code_hash.encode(),
+		) - read_ns * 40.0).max(0.0),
+	);
+
+	let hash = |name: &str, len: u32| looped_ns(
+		&format!(r#"(import "env" "{name}" (func ${name} (param i32 i32 i32)))"#, name = name),
+		&format!("(call ${} (i32.const {}) (i32.const {}) (i32.const {}))", name, BUFFER_PTR, len, HASH_OUTPUT_PTR),
+		HOST_FN_REPEAT,
+		"",
+		&[],
+	);
+	let hash_base = |name: &str| report(&format!("{} base", name), (hash(name, 0) - write_ns * 32.0).max(0.0));
+	let hash_per_byte = |name: &str| report(
+		&format!("{} per byte", name),
+		(per_byte_ns(BUFFER_LEN, |len| hash(name, len)) - read_ns).max(0.0),
+	);
+	let blake2_base_ns = hash_base("ext_hash_blake2_256");
+	let blake2_per_byte_ns = hash_per_byte("ext_hash_blake2_256");
+	let keccak_base_ns = hash_base("ext_hash_keccak_256");
+	let keccak_per_byte_ns = hash_per_byte("ext_hash_keccak_256");
+	let sha2_base_ns = hash_base("ext_hash_sha2_256");
+	let sha2_per_byte_ns = hash_per_byte("ext_hash_sha2_256");
+
+	let block_number_ns = report(
+		"block number",
+		looped_ns(
+			r#"(import "env" "ext_block_number" (func $ext_block_number))"#,
+			"(call $ext_block_number)",
+			HOST_FN_REPEAT,
+			"",
+			&[],
+		),
+	);
+
+	let get_runtime_storage = |len: u32| {
+		runtime_io::set_storage(WHITELISTED_RUNTIME_KEY, &vec![0u8; len as usize]);
+		looped_ns(
+			r#"(import "env" "ext_get_runtime_storage" (func $ext_get_runtime_storage (param i32 i32) (result i32)))"#,
+			&format!(
+				"(drop (call $ext_get_runtime_storage (i32.const {}) (i32.const {})))",
+				KEY_PTR, WHITELISTED_RUNTIME_KEY.len(),
+			),
+			HOST_FN_REPEAT,
+			&format!("(data (i32.const {}) \"{}\")", KEY_PTR, escape(WHITELISTED_RUNTIME_KEY)),
+			&[],
+		)
+	};
+	let get_runtime_storage_base_ns = report(
+		"get runtime storage base",
+		(get_runtime_storage(0) - read_ns * WHITELISTED_RUNTIME_KEY.len() as f64).max(0.0),
+	);
+	let get_runtime_storage_per_byte_ns = report(
+		"get runtime storage per byte",
+		per_byte_ns(BUFFER_LEN, get_runtime_storage),
+	);
+
+	// The function `0` of the chain extension of the mock runtime is free and echoes its input.
+	let chain_extension_ns = report(
+		"chain extension base",
+		looped_ns(
+			r#"(import "env" "ext_call_chain_extension" (func $ext_call_chain_extension (param i32 i32 i32)))"#,
+			&format!("(call $ext_call_chain_extension (i32.const 0) (i32.const {}) (i32.const 0))", BUFFER_PTR),
+			HOST_FN_REPEAT,
+			"",
+			&[],
+		),
+	);
+
+	let put_code_ns = report("put code per byte", put_code_per_byte_ns());
+
+	Schedule {
+		put_code_per_byte_cost: gas(put_code_ns),
+		grow_mem_cost: gas(grow_mem_ns),
+		regular_op_cost: instruction_gas(regular_ns),
+		load_op_cost: instruction_gas(load_ns),
+		store_op_cost: instruction_gas(store_ns),
+		control_flow_op_cost: instruction_gas(control_flow_ns),
+		local_op_cost: instruction_gas(local_ns),
+		global_op_cost: instruction_gas(global_ns),
+		const_op_cost: instruction_gas(const_ns),
+		add_op_cost: instruction_gas(add_ns),
+		mul_op_cost: instruction_gas(mul_ns),
+		div_op_cost: instruction_gas(div_ns),
+		bit_op_cost: instruction_gas(bit_ns),
+		comparison_op_cost: instruction_gas(comparison_ns),
+		conversion_op_cost: instruction_gas(conversion_ns),
+		return_data_per_byte_cost: gas(return_data_ns),
+		event_data_per_byte_cost: gas(event_data_ns),
+		event_per_topic_cost: gas(event_topic_ns),
+		event_base_cost: gas(event_base_ns),
+		call_base_cost: gas(call_base_ns),
+		instantiate_base_cost: gas(instantiate_ns),
+		sandbox_data_read_cost: gas(read_ns),
+		sandbox_data_write_cost: gas(write_ns),
+		hash_blake2_256_base_cost: gas(blake2_base_ns),
+		hash_blake2_256_per_byte_cost: gas(blake2_per_byte_ns),
+		hash_keccak_256_base_cost: gas(keccak_base_ns),
+		hash_keccak_256_per_byte_cost: gas(keccak_per_byte_ns),
+		hash_sha2_256_base_cost: gas(sha2_base_ns),
+		hash_sha2_256_per_byte_cost: gas(sha2_per_byte_ns),
+		block_number_cost: gas(block_number_ns),
+		get_runtime_storage_base_cost: gas(get_runtime_storage_base_ns),
+		get_runtime_storage_per_byte_cost: gas(get_runtime_storage_per_byte_ns),
+		chain_extension_base_cost: gas(chain_extension_ns),
+		..Contract::current_schedule()
+	}
+}
+
+#[test]
+#[ignore]
+fn suggest_schedule_for_current_hardware() {
+	with_externalities(
+		&mut ExtBuilder::default().block_gas_limit(Gas::max_value()).build(),
+		|| {
+			Balances::deposit_creating(&ALICE, ENDOWMENT);
+			let schedule = suggest_schedule();
+			println!(
+				"{}",
+				serde_json::to_string_pretty(&schedule).expect("schedule is serializable; qed")
+			);
+		},
+	);
+}
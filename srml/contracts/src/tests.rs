@@ -21,8 +21,10 @@
 
 use crate::account_db::{AccountDb, DirectAccountDb, OverlayAccountDb};
 use crate::{
-	BalanceOf, ComputeDispatchFee, ContractAddressFor, ContractInfo, ContractInfoOf, GenesisConfig,
-	Module, RawAliveContractInfo, RawEvent, Trait, TrieId, TrieIdFromParentCounter, TrieIdGenerator,
+	BalanceOf, ChainExtension, CodeInfoOf, CodeStorage, ComputeDispatchFee, ContractAddressFor,
+	ContractInfo, ContractInfoOf, CurrentSchedule, FrameKind, FrameTrace, Gas, GenesisConfig, Module,
+	PristineCode, RawAliveContractInfo, RawCodeInfo, RawEvent, Schedule, TraceEntry, Trait, TrieId,
+	TrieIdFromParentCounter, TrieIdGenerator,
 };
 use assert_matches::assert_matches;
 use hex_literal::*;
@@ -30,11 +32,13 @@ use parity_codec::{Decode, Encode, KeyedVec};
 use runtime_io;
 use runtime_io::with_externalities;
 use runtime_primitives::testing::{Digest, DigestItem, Header, UintAuthorityId, H256};
-use runtime_primitives::traits::{BlakeTwo256, IdentityLookup};
-use runtime_primitives::BuildStorage;
+use runtime_primitives::traits::{BlakeTwo256, Hash, IdentityLookup};
+use runtime_primitives::weights::Weight;
+use runtime_primitives::{BuildStorage, Perbill};
 use srml_support::{
 	assert_ok, assert_err, impl_outer_dispatch, impl_outer_event, impl_outer_origin, parameter_types,
-	storage::child,	StorageMap, StorageValue, traits::{Currency, Get},
+	storage::child,	StorageMap, StorageValue, traits::{Currency, Get, Contains},
+	storage::hashed::{self, generator::{StorageHasher, Twox128}},
 };
 use std::cell::RefCell;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -70,6 +74,7 @@ thread_local! {
 	static TRANSFER_FEE: RefCell<u64> = RefCell::new(0);
 	static CREATION_FEE: RefCell<u64> = RefCell::new(0);
 	static BLOCK_GAS_LIMIT: RefCell<u64> = RefCell::new(0);
+	static CODE_DEPOSIT_PER_BYTE: RefCell<u64> = RefCell::new(0);
 }
 
 pub struct ExistentialDeposit;
@@ -92,8 +97,17 @@ impl Get<u64> for BlockGasLimit {
 	fn get() -> u64 { BLOCK_GAS_LIMIT.with(|v| *v.borrow()) }
 }
 
+pub struct CodeDepositPerByte;
+impl Get<u64> for CodeDepositPerByte {
+	fn get() -> u64 { CODE_DEPOSIT_PER_BYTE.with(|v| *v.borrow()) }
+}
+
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct Test;
+parameter_types! {
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
 impl system::Trait for Test {
 	type Origin = Origin;
 	type Index = u64;
@@ -104,6 +118,9 @@ impl system::Trait for Test {
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
 	type Event = MetaEvent;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type FeeMultiplierUpdate = ();
 }
 parameter_types! {
 	pub const BalancesTransactionBaseFee: u64 = 0;
@@ -122,6 +139,7 @@ impl balances::Trait for Test {
 	type CreationFee = CreationFee;
 	type TransactionBaseFee = BalancesTransactionBaseFee;
 	type TransactionByteFee = BalancesTransactionByteFee;
+	type WeightToFee = ();
 }
 impl timestamp::Trait for Test {
 	type Moment = u64;
@@ -164,10 +182,14 @@ impl Trait for Test {
 	type CreateBaseFee = CreateBaseFee;
 	type MaxDepth = MaxDepth;
 	type BlockGasLimit = BlockGasLimit;
+	type RuntimeStorageWhitelist = DummyRuntimeStorageWhitelist;
+	type CodeDepositPerByte = CodeDepositPerByte;
+	type CallFilter = DummyCallFilter;
+	type ChainExtension = DummyChainExtension;
 }
 
-type Balances = balances::Module<Test>;
-type Contract = Module<Test>;
+pub type Balances = balances::Module<Test>;
+pub type Contract = Module<Test>;
 type System = system::Module<Test>;
 
 pub struct DummyContractAddressFor;
@@ -197,6 +219,39 @@ impl TrieIdGenerator<u64> for DummyTrieIdGenerator {
 	}
 }
 
+/// The raw runtime storage key that contracts are allowed to read in tests.
+pub const WHITELISTED_RUNTIME_KEY: &[u8] = b":test:oracle_price";
+
+pub struct DummyRuntimeStorageWhitelist;
+impl Contains<Vec<u8>> for DummyRuntimeStorageWhitelist {
+	fn contains(key: &Vec<u8>) -> bool {
+		&key[..] == WHITELISTED_RUNTIME_KEY
+	}
+}
+
+/// Contracts are only allowed to dispatch calls of the balances module in tests.
+pub struct DummyCallFilter;
+impl Contains<Call> for DummyCallFilter {
+	fn contains(call: &Call) -> bool {
+		match call {
+			Call::Balances(_) => true,
+			_ => false,
+		}
+	}
+}
+
+/// The function `0` of the chain extension is free and echoes its input, other functions are unknown.
+pub struct DummyChainExtension;
+impl ChainExtension<u64> for DummyChainExtension {
+	fn gas_cost(func_id: u32, _input: &[u8]) -> Option<Gas> {
+		if func_id == 0 { Some(0) } else { None }
+	}
+
+	fn query(func_id: u32, _contract: &u64, input: Vec<u8>) -> Result<Vec<u8>, &'static str> {
+		if func_id == 0 { Ok(input) } else { Err("unknown chain extension function") }
+	}
+}
+
 pub struct DummyComputeDispatchFee;
 impl ComputeDispatchFee<Call, u64> for DummyComputeDispatchFee {
 	fn compute_dispatch_fee(call: &Call) -> u64 {
@@ -204,7 +259,7 @@ impl ComputeDispatchFee<Call, u64> for DummyComputeDispatchFee {
 	}
 }
 
-const ALICE: u64 = 1;
+pub const ALICE: u64 = 1;
 const BOB: u64 = 2;
 const CHARLIE: u64 = 3;
 const DJANGO: u64 = 4;
@@ -215,6 +270,7 @@ pub struct ExtBuilder {
 	block_gas_limit: u64,
 	transfer_fee: u64,
 	creation_fee: u64,
+	code_deposit_per_byte: u64,
 }
 impl Default for ExtBuilder {
 	fn default() -> Self {
@@ -224,6 +280,7 @@ impl Default for ExtBuilder {
 			block_gas_limit: 100_000_000,
 			transfer_fee: 0,
 			creation_fee: 0,
+			code_deposit_per_byte: 0,
 		}
 	}
 }
@@ -248,11 +305,16 @@ impl ExtBuilder {
 		self.creation_fee = creation_fee;
 		self
 	}
+	pub fn code_deposit_per_byte(mut self, code_deposit_per_byte: u64) -> Self {
+		self.code_deposit_per_byte = code_deposit_per_byte;
+		self
+	}
 	pub fn set_associated_consts(&self) {
 		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.existential_deposit);
 		TRANSFER_FEE.with(|v| *v.borrow_mut() = self.transfer_fee);
 		CREATION_FEE.with(|v| *v.borrow_mut() = self.creation_fee);
 		BLOCK_GAS_LIMIT.with(|v| *v.borrow_mut() = self.block_gas_limit);
+		CODE_DEPOSIT_PER_BYTE.with(|v| *v.borrow_mut() = self.code_deposit_per_byte);
 	}
 	pub fn build(self) -> runtime_io::TestExternalities<Blake2Hasher> {
 		self.set_associated_consts();
@@ -293,6 +355,38 @@ fn refunds_unused_gas() {
 	});
 }
 
+#[test]
+fn schedule_of_the_old_layout_is_converted() {
+	with_externalities(&mut ExtBuilder::default().build(), || {
+		// A schedule of version 7 in the layout of `ScheduleV0`, with every cost set to 2.
+		let mut old = 7u32.encode();
+		for _ in 0..11 {
+			old.extend(2u64.encode());
+		}
+		for limit in &[4u32, 1024, 16] {
+			old.extend(limit.encode());
+		}
+		old.extend(true.encode());
+		old.extend(32u32.encode());
+		hashed::put_raw(&Twox128::hash, <CurrentSchedule as StorageValue<Schedule>>::key(), &old);
+
+		let schedule = Contract::current_schedule();
+		assert_eq!(schedule.version, 7);
+		assert_eq!((schedule.regular_op_cost, schedule.div_op_cost, schedule.call_base_cost), (2, 2, 2));
+		assert_eq!((schedule.max_stack_height, schedule.max_subject_len), (1024, 32));
+		assert!(schedule.enable_println);
+		assert_eq!(schedule.hash_sha2_256_base_cost, Schedule::default().hash_sha2_256_base_cost);
+
+		// The version is compared without decoding the stored schedule.
+		assert_err!(
+			Contract::update_schedule(Schedule { version: 7, ..Schedule::default() }),
+			"new schedule must have a greater version than current"
+		);
+		assert_ok!(Contract::update_schedule(Schedule { version: 8, ..Schedule::default() }));
+		assert_eq!(Contract::current_schedule(), Schedule { version: 8, ..Schedule::default() });
+	});
+}
+
 #[test]
 fn account_removal_removes_storage() {
 	with_externalities(
@@ -453,6 +547,255 @@ fn instantiate_and_call_and_deposit_event() {
 	);
 }
 
+const CODE_RETURN_WITH_EVENT: &str = r#"
+(module
+	(import "env" "ext_return" (func $ext_return (param i32 i32)))
+	(import "env" "ext_deposit_event" (func $ext_deposit_event (param i32 i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "call")
+		(call $ext_deposit_event
+			(i32.const 0) ;; The topics buffer
+			(i32.const 0) ;; The topics buffer's length
+			(i32.const 8) ;; The data buffer
+			(i32.const 4) ;; The data buffer's length
+		)
+		(call $ext_return
+			(i32.const 8)
+			(i32.const 4)
+		)
+		(unreachable)
+	)
+	(func (export "deploy"))
+
+	(data (i32.const 8) "\01\02\03\04")
+)
+"#;
+
+#[test]
+fn bare_call_returns_output_without_committing() {
+	let wasm = wabt::wat2wasm(CODE_RETURN_WITH_EVENT).unwrap();
+
+	with_externalities(
+		&mut ExtBuilder::default().existential_deposit(50).gas_price(2).build(),
+		|| {
+			Balances::deposit_creating(&ALICE, 1_000_000);
+			let code_hash = <Test as system::Trait>::Hashing::hash(&wasm);
+			assert_ok!(Contract::put_code(Origin::signed(ALICE), 100_000, wasm));
+			assert_ok!(Contract::create(Origin::signed(ALICE), 100, 100_000, code_hash, vec![]));
+
+			let alice_balance = Balances::free_balance(&ALICE);
+			let events = System::events();
+
+			let outcome = Contract::bare_call(ALICE, BOB, 0, 100_000, vec![]);
+			assert_eq!(outcome.result, Ok(vec![1, 2, 3, 4]));
+			assert_eq!(outcome.events, vec![RawEvent::Contract(BOB, vec![1, 2, 3, 4])]);
+			assert!(outcome.gas_consumed > 0 && outcome.gas_consumed < 100_000);
+
+			// No gas was paid for and nothing was deposited.
+			assert_eq!(Balances::free_balance(&ALICE), alice_balance);
+			assert_eq!(System::events(), events);
+
+			// Running out of gas is reported together with the gas consumed.
+			let outcome = Contract::bare_call(ALICE, BOB, 0, 1, vec![]);
+			assert!(outcome.result.is_err());
+			assert_eq!(outcome.gas_consumed, 1);
+			assert!(outcome.events.is_empty());
+		},
+	);
+}
+
+const CODE_LOOP: &str = r#"
+(module
+	(func (export "call")
+		(loop $inf (br $inf))
+	)
+	(func (export "deploy"))
+)
+"#;
+
+#[test]
+fn bare_call_gas_limit_is_capped_at_block_gas_limit() {
+	let wasm = wabt::wat2wasm(CODE_LOOP).unwrap();
+
+	with_externalities(
+		&mut ExtBuilder::default().existential_deposit(50).block_gas_limit(1_000_000).build(),
+		|| {
+			Balances::deposit_creating(&ALICE, 1_000_000);
+			let code_hash = <Test as system::Trait>::Hashing::hash(&wasm);
+			assert_ok!(Contract::put_code(Origin::signed(ALICE), 100_000, wasm));
+			assert_ok!(Contract::create(Origin::signed(ALICE), 100, 100_000, code_hash, vec![]));
+
+			let outcome = Contract::bare_call(ALICE, BOB, 0, u64::max_value(), vec![]);
+			assert!(outcome.result.is_err());
+			assert_eq!(outcome.gas_consumed, 1_000_000);
+		},
+	);
+}
+
+#[test]
+fn trace_call_records_frame_with_host_calls() {
+	let wasm = wabt::wat2wasm(CODE_RETURN_WITH_EVENT).unwrap();
+
+	with_externalities(
+		&mut ExtBuilder::default().existential_deposit(50).gas_price(2).build(),
+		|| {
+			Balances::deposit_creating(&ALICE, 1_000_000);
+			let code_hash = <Test as system::Trait>::Hashing::hash(&wasm);
+			assert_ok!(Contract::put_code(Origin::signed(ALICE), 100_000, wasm));
+			assert_ok!(Contract::create(Origin::signed(ALICE), 100, 100_000, code_hash, vec![]));
+
+			let (outcome, trace) = Contract::trace_call(ALICE, BOB, 0, 100_000, vec![7]);
+			assert_eq!(outcome.result, Ok(vec![1, 2, 3, 4]));
+			assert_eq!(
+				outcome.gas_consumed,
+				Contract::bare_call(ALICE, BOB, 0, 100_000, vec![7]).gas_consumed,
+			);
+
+			assert_eq!(trace.len(), 1);
+			let frame = match &trace[0] {
+				TraceEntry::Frame(frame) => frame,
+				entry => panic!("expected a frame, got {:?}", entry),
+			};
+			assert_matches!(
+				frame,
+				FrameTrace {
+					kind: FrameKind::Call,
+					address: BOB,
+					value: 0,
+					result: Ok(_),
+					..
+				}
+			);
+			assert_eq!(frame.input_data, vec![7]);
+			assert_eq!(frame.gas_left, 100_000 - outcome.gas_consumed);
+
+			let names: Vec<_> = frame.entries.iter().filter_map(|entry| match entry {
+				TraceEntry::HostCall(host_call) => Some(host_call.name),
+				_ => None,
+			}).collect();
+			assert_eq!(names, vec!["ext_deposit_event", "ext_return"]);
+
+			// A call that fails before entering the contract leaves the trace empty.
+			let (outcome, trace) = Contract::trace_call(ALICE, BOB, 0, 1, vec![]);
+			assert!(outcome.result.is_err());
+			assert!(trace.is_empty());
+		},
+	);
+}
+
+const CODE_DIVIDE_BY_ZERO: &str = r#"
+(module
+	(func (export "call")
+		(drop (i32.div_u (i32.const 1) (i32.const 0)))
+	)
+	(func (export "deploy"))
+)
+"#;
+
+#[test]
+fn trace_call_records_trap_reason() {
+	let wasm = wabt::wat2wasm(CODE_DIVIDE_BY_ZERO).unwrap();
+
+	with_externalities(
+		&mut ExtBuilder::default().existential_deposit(50).build(),
+		|| {
+			Balances::deposit_creating(&ALICE, 1_000_000);
+			let code_hash = <Test as system::Trait>::Hashing::hash(&wasm);
+			assert_ok!(Contract::put_code(Origin::signed(ALICE), 100_000, wasm));
+			assert_ok!(Contract::create(Origin::signed(ALICE), 100, 100_000, code_hash, vec![]));
+
+			let (outcome, trace) = Contract::trace_call(ALICE, BOB, 0, 100_000, vec![]);
+			assert_eq!(outcome.result, Err("integer division by zero"));
+			assert_matches!(
+				&trace[..],
+				[TraceEntry::Frame(FrameTrace { result: Err("integer division by zero"), .. })]
+			);
+		},
+	);
+}
+
+const CODE_SELF_DESTRUCT: &str = r#"
+(module
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_copy" (func $ext_scratch_copy (param i32 i32 i32)))
+	(import "env" "ext_caller" (func $ext_caller))
+	(import "env" "ext_terminate" (func $ext_terminate (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "deploy"))
+
+	;; Terminate the contract and send all of its funds to the caller.
+	(func (export "call")
+		(call $ext_caller)
+		(call $ext_scratch_copy
+			(i32.const 8)
+			(i32.const 0)
+			(call $ext_scratch_size)
+		)
+		(call $ext_terminate
+			(i32.const 8)
+			(call $ext_scratch_size)
+		)
+		(unreachable)
+	)
+)
+"#;
+
+#[test]
+fn self_destruct_removes_contract_and_releases_code() {
+	let wasm = wabt::wat2wasm(CODE_SELF_DESTRUCT).unwrap();
+
+	with_externalities(
+		&mut ExtBuilder::default().existential_deposit(50).code_deposit_per_byte(2).build(),
+		|| {
+			Balances::deposit_creating(&ALICE, 1_000_000);
+			Balances::deposit_creating(&CHARLIE, 1_000_000);
+			let code_hash = <Test as system::Trait>::Hashing::hash(&wasm);
+			let code_deposit = 2 * wasm.len() as u64;
+
+			// Storing the code reserves the deposit from the owner.
+			assert_ok!(Contract::put_code(Origin::signed(ALICE), 100_000, wasm));
+			assert_eq!(Balances::reserved_balance(&ALICE), code_deposit);
+			assert_eq!(
+				CodeInfoOf::<Test>::get(code_hash),
+				Some(RawCodeInfo { owner: ALICE, deposit: code_deposit, refcount: 0 }),
+			);
+
+			assert_ok!(Contract::create(Origin::signed(ALICE), 100_000, 100_000, code_hash, vec![]));
+			assert_eq!(CodeInfoOf::<Test>::get(code_hash).unwrap().refcount, 1);
+
+			// The code cannot be removed while a contract is using it.
+			assert_err!(
+				Contract::remove_code(Origin::signed(ALICE), code_hash),
+				"code is still used by contracts"
+			);
+
+			// Calling the contract makes it transfer everything to the caller and destroy itself.
+			assert_ok!(Contract::call(Origin::signed(CHARLIE), BOB, 0, 100_000, vec![]));
+			assert!(ContractInfoOf::<Test>::get(BOB).is_none());
+			assert_eq!(Balances::free_balance(&BOB), 0);
+			assert!(System::events().contains(&EventRecord {
+				phase: Phase::ApplyExtrinsic(0),
+				event: MetaEvent::contract(RawEvent::Transfer(BOB, CHARLIE, 100_000)),
+				topics: vec![],
+			}));
+			assert_eq!(CodeInfoOf::<Test>::get(code_hash).unwrap().refcount, 0);
+
+			// Only the owner can remove the code, which returns the deposit.
+			assert_err!(
+				Contract::remove_code(Origin::signed(CHARLIE), code_hash),
+				"only the owner can remove the code"
+			);
+			assert_ok!(Contract::remove_code(Origin::signed(ALICE), code_hash));
+			assert!(CodeInfoOf::<Test>::get(code_hash).is_none());
+			assert!(PristineCode::<Test>::get(code_hash).is_none());
+			assert!(CodeStorage::<Test>::get(code_hash).is_none());
+			assert_eq!(Balances::reserved_balance(&ALICE), 0);
+		},
+	);
+}
+
 const CODE_DISPATCH_CALL: &str = r#"
 (module
 	(import "env" "ext_dispatch_call" (func $ext_dispatch_call (param i32 i32)))
@@ -574,6 +917,57 @@ fn dispatch_call() {
 	);
 }
 
+const CODE_DISPATCH_FILTERED_CALL: &str = r#"
+(module
+	(import "env" "ext_dispatch_call" (func $ext_dispatch_call (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "call")
+		(call $ext_dispatch_call
+			(i32.const 8) ;; Pointer to the start of encoded call buffer
+			(i32.const 11) ;; Length of the buffer
+		)
+	)
+	(func (export "deploy"))
+
+	(data (i32.const 8) "\01\05\02\00\00\00\00\00\00\00\00")
+)
+"#;
+
+#[test]
+fn dispatch_call_not_allowed_by_call_filter_traps() {
+	// This test can fail due to the encoding changes. In case it becomes too annoying
+	// let's rewrite so as we use this module controlled call or we serialize it in runtime.
+	let encoded = Encode::encode(&Call::Contract(super::Call::claim_surcharge(BOB, None)));
+	assert_eq!(&encoded[..], &hex!("0105020000000000000000")[..]);
+	assert!(!DummyCallFilter::contains(&Call::Contract(super::Call::claim_surcharge(BOB, None))));
+
+	let wasm = wabt::wat2wasm(CODE_DISPATCH_FILTERED_CALL).unwrap();
+
+	with_externalities(
+		&mut ExtBuilder::default().existential_deposit(50).build(),
+		|| {
+			Balances::deposit_creating(&ALICE, 1_000_000);
+			let code_hash = <Test as system::Trait>::Hashing::hash(&wasm);
+			assert_ok!(Contract::put_code(Origin::signed(ALICE), 100_000, wasm));
+			assert_ok!(Contract::create(Origin::signed(ALICE), 100, 100_000, code_hash, vec![]));
+
+			assert_err!(
+				Contract::call(Origin::signed(ALICE), BOB, 0, 100_000, vec![]),
+				"host function trapped"
+			);
+
+			// Nothing was dispatched.
+			assert!(!System::events().iter().any(|record|
+				match record.event {
+					MetaEvent::contract(RawEvent::Dispatched(..)) => true,
+					_ => false,
+				}
+			));
+		},
+	);
+}
+
 const CODE_DISPATCH_CALL_THEN_TRAP: &str = r#"
 (module
 	(import "env" "ext_dispatch_call" (func $ext_dispatch_call (param i32 i32)))
@@ -642,7 +1036,7 @@ fn dispatch_call_not_dispatched_after_top_level_transaction_failure() {
 					100_000,
 					vec![],
 				),
-				"during execution"
+				"unreachable instruction executed"
 			);
 			assert_eq!(System::events(), vec![
 				EventRecord {
@@ -1371,6 +1765,13 @@ fn restoration(test_different_storage: bool, test_restore_to_with_dirty_storage:
 			assert_ok!(Contract::call(Origin::signed(ALICE), BOB, 0, 100_000, call::null()));
 			assert!(ContractInfoOf::<Test>::get(BOB).unwrap().get_tombstone().is_some());
 
+			// The tombstone still references the code it can be restored with.
+			assert_eq!(CodeInfoOf::<Test>::get(H256::from(HASH_SET_RENT)).unwrap().refcount, 1);
+			assert_err!(
+				Contract::remove_code(Origin::signed(ALICE), HASH_SET_RENT.into()),
+				"code is still used by contracts"
+			);
+
 			/// Create another account with the address `DJANGO` with `CODE_RESTORATION`.
 			///
 			/// Note that we can't use `ALICE` for creating `DJANGO` so we create yet another
@@ -1412,6 +1813,8 @@ fn restoration(test_different_storage: bool, test_restore_to_with_dirty_storage:
 				assert_eq!(django_contract.storage_size, 16);
 				assert_eq!(django_contract.trie_id, django_trie_id);
 				assert_eq!(django_contract.deduct_block, System::block_number());
+				assert_eq!(CodeInfoOf::<Test>::get(H256::from(HASH_SET_RENT)).unwrap().refcount, 1);
+				assert_eq!(CodeInfoOf::<Test>::get(H256::from(HASH_RESTORATION)).unwrap().refcount, 1);
 			} else {
 				// Here we expect that the restoration is succeeded. Check that the restoration
 				// contract `DJANGO` ceased to exist and that `BOB` returned back.
@@ -1422,6 +1825,10 @@ fn restoration(test_different_storage: bool, test_restore_to_with_dirty_storage:
 				assert_eq!(bob_contract.trie_id, django_trie_id);
 				assert_eq!(bob_contract.deduct_block, System::block_number());
 				assert!(ContractInfoOf::<Test>::get(DJANGO).is_none());
+
+				// The restored contract took over the reference of the tombstone.
+				assert_eq!(CodeInfoOf::<Test>::get(H256::from(HASH_SET_RENT)).unwrap().refcount, 1);
+				assert_eq!(CodeInfoOf::<Test>::get(H256::from(HASH_RESTORATION)).unwrap().refcount, 0);
 			}
 		}
 	);
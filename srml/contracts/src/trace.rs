@@ -0,0 +1,109 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate. If not, see <http://www.gnu.org/licenses/>.
+
+//! The trace of a contract execution recorded by `Module::trace_call`.
+//!
+//! Every executed contract gets a frame, which lists the host functions the contract invoked, the
+//! storage it accessed and the frames of the contracts it called or instantiated, in the order
+//! these happened.
+//!
+//! A trace holds at most `MAX_TRACE_ENTRIES` entries, counting the entries of all frames, so that
+//! recording it can't exhaust the memory of the runtime.
+
+use crate::gas::Gas;
+use rstd::prelude::*;
+
+/// The maximum number of entries recorded in the trace of a contract execution.
+///
+/// Entries recorded after this are dropped and a frame that lost entries ends with
+/// `TraceEntry::Truncated`.
+pub const MAX_TRACE_ENTRIES: usize = 10_000;
+
+/// An entry in the trace of a contract execution.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum TraceEntry<AccountId, Balance> {
+	/// A host function was invoked by the contract.
+	HostCall(HostCallTrace),
+	/// A value was read from the storage of the contract.
+	StorageRead {
+		/// The key of the value.
+		key: [u8; 32],
+		/// The value that was read, if any.
+		value: Option<Vec<u8>>,
+	},
+	/// A value was written to the storage of the contract.
+	StorageWrite {
+		/// The key of the value.
+		key: [u8; 32],
+		/// The value that was written, `None` if the value was removed.
+		value: Option<Vec<u8>>,
+	},
+	/// Another contract was called or instantiated.
+	///
+	/// The frame precedes the entry of the `ext_call` or `ext_create` host function that made it.
+	Frame(FrameTrace<AccountId, Balance>),
+	/// The trace reached `MAX_TRACE_ENTRIES`, the remaining entries of the frame were dropped.
+	Truncated,
+}
+
+/// An invocation of a host function.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct HostCallTrace {
+	/// The name of the host function.
+	pub name: &'static str,
+	/// The arguments the function was invoked with. 32-bit values are zero-extended.
+	pub args: Vec<u64>,
+	/// The value returned by the function, if any.
+	pub result: Option<u64>,
+	/// Whether the function trapped.
+	///
+	/// Halting the execution with `ext_return` or `ext_terminate` is not considered a trap.
+	pub trapped: bool,
+	/// The gas left after the function finished.
+	pub gas_left: Gas,
+}
+
+/// The way a frame was entered.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum FrameKind {
+	/// The contract was called.
+	Call,
+	/// The contract was instantiated.
+	Instantiate,
+}
+
+/// The execution of a single contract.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct FrameTrace<AccountId, Balance> {
+	/// Whether the contract was called or instantiated.
+	pub kind: FrameKind,
+	/// The address of the contract.
+	pub address: AccountId,
+	/// The value transferred to the contract.
+	pub value: Balance,
+	/// The input data passed to the contract.
+	pub input_data: Vec<u8>,
+	/// Everything that happened during the execution of the contract.
+	pub entries: Vec<TraceEntry<AccountId, Balance>>,
+	/// The output of the contract, or the reason why the execution failed.
+	pub result: rstd::result::Result<Vec<u8>, &'static str>,
+	/// The gas left after the execution finished.
+	pub gas_left: Gas,
+}
@@ -28,10 +28,11 @@
 
 use crate::gas::{Gas, GasMeter, Token};
 use crate::wasm::{prepare, runtime::Env, PrefabWasmModule};
-use crate::{CodeHash, CodeStorage, PristineCode, Schedule, Trait};
+use crate::{CodeHash, CodeInfoOf, CodeStorage, PristineCode, RawCodeInfo, Schedule, Trait, BalanceOf};
 use rstd::prelude::*;
-use runtime_primitives::traits::{Hash, Bounded};
+use runtime_primitives::traits::{Hash, Bounded, Saturating};
 use srml_support::StorageMap;
+use srml_support::traits::{Get, ReservableCurrency};
 
 /// Gas metering token that used for charging storing code into the code storage.
 ///
@@ -54,9 +55,11 @@ impl<T: Trait> Token<T> for PutCodeToken {
 /// Put code in the storage. The hash of code is used as a key and is returned
 /// as a result of this function.
 ///
-/// This function instruments the given code and caches it in the storage.
+/// This function instruments the given code and caches it in the storage. The deposit for the
+/// code is reserved from `owner`, unless the code is already stored.
 pub fn save<T: Trait>(
 	original_code: Vec<u8>,
+	owner: &T::AccountId,
 	gas_meter: &mut GasMeter<T>,
 	schedule: &Schedule,
 ) -> Result<CodeHash<T>, &'static str> {
@@ -69,15 +72,73 @@ pub fn save<T: Trait>(
 		return Err("there is not enough gas for storing the code");
 	}
 
-	let prefab_module = prepare::prepare_contract::<Env>(&original_code, schedule)?;
 	let code_hash = T::Hashing::hash(&original_code);
+	if <CodeStorage<T>>::exists(&code_hash) {
+		// The very same code is already stored, don't overwrite its bookkeeping.
+		return Ok(code_hash);
+	}
+
+	let prefab_module = prepare::prepare_contract::<Env>(&original_code, schedule)?;
+	let deposit = T::CodeDepositPerByte::get()
+		.saturating_mul(<BalanceOf<T>>::from(original_code.len() as u32));
+	T::Currency::reserve(owner, deposit)
+		.map_err(|_| "not enough free balance to reserve the code deposit")?;
 
 	<CodeStorage<T>>::insert(code_hash, prefab_module);
 	<PristineCode<T>>::insert(code_hash, original_code);
+	<CodeInfoOf<T>>::insert(code_hash, RawCodeInfo {
+		owner: owner.clone(),
+		deposit,
+		refcount: 0,
+	});
 
 	Ok(code_hash)
 }
 
+/// Remove the code with the given code hash and refund its deposit to the owner.
+///
+/// Fails if `origin` is not the owner of the code or there are contracts instantiated from it.
+pub fn remove<T: Trait>(origin: &T::AccountId, code_hash: &CodeHash<T>) -> Result<(), &'static str> {
+	let info = <CodeInfoOf<T>>::get(code_hash).ok_or_else(|| "code is not found")?;
+	if &info.owner != origin {
+		return Err("only the owner can remove the code");
+	}
+	if info.refcount != 0 {
+		return Err("code is still used by contracts");
+	}
+
+	<CodeInfoOf<T>>::remove(code_hash);
+	<CodeStorage<T>>::remove(code_hash);
+	<PristineCode<T>>::remove(code_hash);
+	T::Currency::unreserve(&info.owner, info.deposit);
+
+	Ok(())
+}
+
+/// Note that a contract was instantiated from the code with the given code hash.
+///
+/// The reference is held until the contract is removed. A tombstone left by an evicted contract
+/// keeps holding it, since the contract can be restored with the code.
+///
+/// Code stored before the reference counting was introduced doesn't have any bookkeeping and
+/// is left as is. Such code can never be removed.
+pub fn increment_refcount<T: Trait>(code_hash: &CodeHash<T>) {
+	<CodeInfoOf<T>>::mutate(code_hash, |info| {
+		if let Some(info) = info {
+			info.refcount = info.refcount.saturating_add(1);
+		}
+	});
+}
+
+/// Note that a contract instantiated from the code with the given code hash was removed.
+pub fn decrement_refcount<T: Trait>(code_hash: &CodeHash<T>) {
+	<CodeInfoOf<T>>::mutate(code_hash, |info| {
+		if let Some(info) = info {
+			info.refcount = info.refcount.saturating_sub(1);
+		}
+	});
+}
+
 /// Load code with the given code hash.
 ///
 /// If the module was instrumented with a lower version of schedule than
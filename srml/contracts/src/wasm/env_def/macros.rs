@@ -128,14 +128,18 @@ macro_rules! define_func {
 			$ctx: &mut $crate::wasm::Runtime<E>,
 			args: &[sandbox::TypedValue],
 		) -> Result<sandbox::ReturnValue, sandbox::HostError> {
-			#[allow(unused)]
-			let mut args = args.iter();
-
-			unmarshall_then_body_then_marshall!(
-				args,
-				$ctx,
-				( $( $names : $params ),* ) $( -> $returns )* => $body
-			)
+			let result = $crate::wasm::env_def::macros::constrain_closure::<sandbox::ReturnValue, _>(|| {
+				#[allow(unused)]
+				let mut args = args.iter();
+
+				unmarshall_then_body_then_marshall!(
+					args,
+					$ctx,
+					( $( $names : $params ),* ) $( -> $returns )* => $body
+				)
+			})();
+			$crate::wasm::runtime::trace_host_call($ctx, stringify!($name), args, &result);
+			result
 		}
 	};
 }
@@ -35,7 +35,9 @@ mod runtime;
 use self::runtime::{to_execution_result, Runtime};
 use self::code_cache::load as load_code;
 
-pub use self::code_cache::save as save_code;
+pub use self::code_cache::{
+	save as save_code, remove as remove_code, increment_refcount, decrement_refcount,
+};
 
 /// A prepared wasm module ready for execution.
 #[derive(Clone, Encode, Decode)]
@@ -172,13 +174,15 @@ impl<'a, T: Trait> crate::exec::Vm<T> for WasmVm<'a> {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use std::cell::RefCell;
 	use std::collections::HashMap;
 	use substrate_primitives::H256;
 	use crate::exec::{CallReceipt, Ext, InstantiateReceipt, EmptyOutputBuf, StorageKey};
 	use crate::gas::{Gas, GasMeter};
-	use crate::tests::{Test, Call};
+	use crate::tests::{Test, Call, WHITELISTED_RUNTIME_KEY};
 	use crate::wasm::prepare::prepare_contract;
 	use crate::CodeHash;
+	use crate::trace::{HostCallTrace, TraceEntry};
 	use wabt;
 	use hex_literal::hex;
 
@@ -198,6 +202,15 @@ mod tests {
 		data: Vec<u8>,
 		gas_left: u64,
 	}
+	#[derive(Debug, PartialEq, Eq)]
+	struct ChainExtensionEntry {
+		func_id: u32,
+		input: Vec<u8>,
+	}
+	#[derive(Debug, PartialEq, Eq)]
+	struct TerminationEntry {
+		beneficiary: u64,
+	}
 	#[derive(Default)]
 	pub struct MockExt {
 		storage: HashMap<StorageKey, Vec<u8>>,
@@ -208,6 +221,10 @@ mod tests {
 		// (topics, data)
 		events: Vec<(Vec<H256>, Vec<u8>)>,
 		next_account_id: u64,
+		runtime_storage: HashMap<Vec<u8>, Vec<u8>>,
+		terminations: Vec<TerminationEntry>,
+		chain_extension_calls: RefCell<Vec<ChainExtensionEntry>>,
+		trace: Option<Vec<TraceEntry<u64, u64>>>,
 	}
 	impl Ext for MockExt {
 		type T = Test;
@@ -280,6 +297,35 @@ mod tests {
 			H256::from_slice(subject)
 		}
 
+		fn block_number(&self) -> u64 {
+			121
+		}
+
+		fn get_runtime_storage(&self, key: &[u8]) -> Option<Vec<u8>> {
+			self.runtime_storage.get(key).cloned()
+		}
+
+		fn chain_extension_gas_cost(&self, func_id: u32, _input: &[u8]) -> Option<u64> {
+			// The function 1 reverses its input, the function 2 always fails, the other
+			// ones don't exist.
+			match func_id {
+				1 => Some(1_000),
+				2 => Some(10),
+				_ => None,
+			}
+		}
+
+		fn query_chain_extension(&self, func_id: u32, input: Vec<u8>) -> Result<Vec<u8>, &'static str> {
+			self.chain_extension_calls.borrow_mut().push(ChainExtensionEntry {
+				func_id,
+				input: input.clone(),
+			});
+			match func_id {
+				1 => Ok(input.into_iter().rev().collect()),
+				_ => Err("function failed"),
+			}
+		}
+
 		fn deposit_event(&mut self, topics: Vec<H256>, data: Vec<u8>) {
 			self.events.push((topics, data))
 		}
@@ -291,6 +337,27 @@ mod tests {
 		fn rent_allowance(&self) -> u64 {
 			self.rent_allowance
 		}
+
+		fn is_tracing(&self) -> bool {
+			self.trace.is_some()
+		}
+
+		fn trace(&mut self, entry: TraceEntry<u64, u64>) {
+			if let Some(trace) = self.trace.as_mut() {
+				trace.push(entry);
+			}
+		}
+
+		fn terminate(
+			&mut self,
+			beneficiary: &u64,
+			_gas_meter: &mut GasMeter<Test>,
+		) -> Result<(), &'static str> {
+			self.terminations.push(TerminationEntry {
+				beneficiary: *beneficiary,
+			});
+			Ok(())
+		}
 	}
 
 	fn execute<E: Ext>(
@@ -599,6 +666,54 @@ mod tests {
 		assert_eq!(return_buf, [0x22; 32].to_vec());
 	}
 
+	/// Strips the gas left from the host calls in the trace, since it depends on the schedule.
+	fn host_calls(trace: &[TraceEntry<u64, u64>]) -> Vec<(&'static str, Vec<u64>, Option<u64>, bool)> {
+		trace
+			.iter()
+			.filter_map(|entry| match entry {
+				TraceEntry::HostCall(HostCallTrace { name, args, result, trapped, .. }) =>
+					Some((*name, args.clone(), *result, *trapped)),
+				_ => None,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn trace_records_host_calls_and_storage_reads() {
+		let mut mock_ext = MockExt::default();
+		mock_ext
+			.storage
+			.insert([0x11; 32], [0x22; 32].to_vec());
+		mock_ext.trace = Some(Vec::new());
+
+		execute(
+			CODE_GET_STORAGE,
+			&[],
+			&mut Vec::new(),
+			&mut mock_ext,
+			&mut GasMeter::with_limit(50_000, 1),
+		)
+		.unwrap();
+
+		let trace = mock_ext.trace.unwrap();
+		assert_eq!(
+			trace[0],
+			TraceEntry::StorageRead {
+				key: [0x11; 32],
+				value: Some([0x22; 32].to_vec()),
+			}
+		);
+		assert_eq!(
+			host_calls(&trace),
+			vec![
+				("ext_get_storage", vec![4], Some(0), false),
+				("ext_scratch_size", vec![], Some(32), false),
+				("ext_scratch_copy", vec![36, 0, 32], None, false),
+				("ext_return", vec![36, 32], None, false),
+			]
+		);
+	}
+
 	/// calls `ext_caller`, loads the address from the scratch buffer and
 	/// compares it with the constant 42.
 	const CODE_CALLER: &str = r#"
@@ -1206,6 +1321,465 @@ mod tests {
 		);
 	}
 
+	const CODE_TERMINATE: &str = r#"
+(module
+	;; ext_terminate(
+	;;     beneficiary_ptr: u32,
+	;;     beneficiary_len: u32,
+	;; )
+	(import "env" "ext_terminate" (func $ext_terminate (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+	(func (export "call")
+		(call $ext_terminate
+			(i32.const 4)  ;; Pointer to "beneficiary" address.
+			(i32.const 8)  ;; Length of "beneficiary" address.
+		)
+		;; The execution never continues past the termination.
+		(unreachable)
+	)
+	(func (export "deploy"))
+
+	;; Beneficiary AccountId to transfer the funds.
+	;; Represented by u64 (8 bytes long) in little endian.
+	(data (i32.const 4) "\09\00\00\00\00\00\00\00")
+)
+"#;
+
+	#[test]
+	fn contract_terminate() {
+		let mut mock_ext = MockExt::default();
+		execute(
+			CODE_TERMINATE,
+			&[],
+			&mut Vec::new(),
+			&mut mock_ext,
+			&mut GasMeter::with_limit(50_000, 1),
+		)
+		.unwrap();
+
+		assert_eq!(
+			&mock_ext.terminations,
+			&[TerminationEntry {
+				beneficiary: 0x09,
+			}]
+		);
+	}
+
+	const CODE_BLOCK_NUMBER: &str = r#"
+(module
+	(import "env" "ext_block_number" (func $ext_block_number))
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_copy" (func $ext_scratch_copy (param i32 i32 i32)))
+	(import "env" "ext_return" (func $ext_return (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		;; This stores the block number in the scratch buffer
+		(call $ext_block_number)
+
+		;; assert $ext_scratch_size == 8
+		(call $assert
+			(i32.eq
+				(call $ext_scratch_size)
+				(i32.const 8)
+			)
+		)
+
+		;; copy contents of the scratch buffer into the contract's memory.
+		(call $ext_scratch_copy
+			(i32.const 8)		;; Pointer in memory to the place where to copy.
+			(i32.const 0)		;; Offset from the start of the scratch buffer.
+			(i32.const 8)		;; Count of bytes to copy.
+		)
+
+		;; return the block number from the contract
+		(call $ext_return
+			(i32.const 8)
+			(i32.const 8)
+		)
+	)
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn block_number() {
+		let mut mock_ext = MockExt::default();
+		let mut gas_meter = GasMeter::with_limit(50_000, 1);
+
+		let mut return_buf = Vec::new();
+		execute(
+			CODE_BLOCK_NUMBER,
+			&[],
+			&mut return_buf,
+			&mut mock_ext,
+			&mut gas_meter,
+		)
+		.unwrap();
+
+		assert_eq!(return_buf, 121u64.encode());
+	}
+
+	/// Hashes the input data with `ext_hash_$NAME` and returns the hash.
+	const CODE_HASH: &str = r#"
+(module
+	(import "env" "ext_hash_$NAME" (func $ext_hash (param i32 i32 i32)))
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_copy" (func $ext_scratch_copy (param i32 i32 i32)))
+	(import "env" "ext_return" (func $ext_return (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "call")
+		(local $input_len i32)
+		(set_local $input_len
+			(call $ext_scratch_size)
+		)
+
+		;; copy the input data into the contract's memory.
+		(call $ext_scratch_copy
+			(i32.const 32)			;; Pointer in memory to the place where to copy.
+			(i32.const 0)			;; Offset from the start of the scratch buffer.
+			(get_local $input_len)	;; Count of bytes to copy.
+		)
+
+		;; hash the input and put the result at [0, 32).
+		(call $ext_hash
+			(i32.const 32)			;; Pointer to the input data.
+			(get_local $input_len)	;; Length of the input data.
+			(i32.const 0)			;; Pointer to the output buffer.
+		)
+
+		;; return the hash from the contract
+		(call $ext_return
+			(i32.const 0)
+			(i32.const 32)
+		)
+	)
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn hash_functions() {
+		let input = b"_DEAD_BEEF";
+		let hash_fns: [(&str, fn(&[u8]) -> [u8; 32]); 3] = [
+			("blake2_256", runtime_io::blake2_256),
+			("keccak_256", runtime_io::keccak_256),
+			("sha2_256", runtime_io::sha2_256),
+		];
+
+		for (name, hash_fn) in hash_fns.iter() {
+			let mut mock_ext = MockExt::default();
+			let mut gas_meter = GasMeter::with_limit(50_000, 1);
+
+			let mut return_buf = Vec::new();
+			execute(
+				&CODE_HASH.replace("$NAME", name),
+				&input[..],
+				&mut return_buf,
+				&mut mock_ext,
+				&mut gas_meter,
+			)
+			.unwrap();
+
+			assert_eq!(&return_buf[..], &hash_fn(&input[..])[..], "{} returned a wrong hash", name);
+		}
+	}
+
+	#[test]
+	fn hash_functions_charge_gas() {
+		let input = vec![0u8; 256];
+		let schedule = crate::Schedule::default();
+
+		let mut mock_ext = MockExt::default();
+		let mut gas_meter = GasMeter::with_limit(50_000, 1);
+		execute(
+			&CODE_HASH.replace("$NAME", "sha2_256"),
+			&input,
+			&mut Vec::new(),
+			&mut mock_ext,
+			&mut gas_meter,
+		)
+		.unwrap();
+		let gas_left_short = gas_meter.gas_left();
+
+		let input = vec![0u8; 512];
+		let mut gas_meter = GasMeter::with_limit(50_000, 1);
+		execute(
+			&CODE_HASH.replace("$NAME", "sha2_256"),
+			&input,
+			&mut Vec::new(),
+			&mut mock_ext,
+			&mut gas_meter,
+		)
+		.unwrap();
+
+		// Hashing 256 more bytes costs the per byte hashing fee on top of reading and copying them.
+		assert_eq!(
+			gas_left_short - gas_meter.gas_left(),
+			256 * (
+				schedule.hash_sha2_256_per_byte_cost
+					+ schedule.sandbox_data_read_cost
+					+ schedule.sandbox_data_write_cost
+			),
+		);
+	}
+
+	/// Reads the runtime storage entry under the key passed as the input data and returns the
+	/// result code of `ext_get_runtime_storage` followed by the contents of the scratch buffer.
+	const CODE_GET_RUNTIME_STORAGE: &str = r#"
+(module
+	(import "env" "ext_get_runtime_storage" (func $ext_get_runtime_storage (param i32 i32) (result i32)))
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_copy" (func $ext_scratch_copy (param i32 i32 i32)))
+	(import "env" "ext_return" (func $ext_return (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "call")
+		(local $input_len i32)
+		(local $value_len i32)
+		(set_local $input_len
+			(call $ext_scratch_size)
+		)
+
+		;; copy the key passed as the input data into the contract's memory.
+		(call $ext_scratch_copy
+			(i32.const 0)			;; Pointer in memory to the place where to copy.
+			(i32.const 0)			;; Offset from the start of the scratch buffer.
+			(get_local $input_len)	;; Count of bytes to copy.
+		)
+
+		;; store the result code at 256.
+		(i32.store8
+			(i32.const 256)
+			(call $ext_get_runtime_storage
+				(i32.const 0)			;; Pointer to the key.
+				(get_local $input_len)	;; Length of the key.
+			)
+		)
+
+		;; copy the value right after the result code.
+		(set_local $value_len
+			(call $ext_scratch_size)
+		)
+		(call $ext_scratch_copy
+			(i32.const 257)
+			(i32.const 0)
+			(get_local $value_len)
+		)
+
+		(call $ext_return
+			(i32.const 256)
+			(i32.add
+				(get_local $value_len)
+				(i32.const 1)
+			)
+		)
+	)
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn get_runtime_storage() {
+		let mut mock_ext = MockExt::default();
+		mock_ext.runtime_storage.insert(WHITELISTED_RUNTIME_KEY.to_vec(), vec![42, 43, 44]);
+
+		let mut return_buf = Vec::new();
+		execute(
+			CODE_GET_RUNTIME_STORAGE,
+			WHITELISTED_RUNTIME_KEY,
+			&mut return_buf,
+			&mut mock_ext,
+			&mut GasMeter::with_limit(50_000, 1),
+		)
+		.unwrap();
+		assert_eq!(return_buf, vec![0, 42, 43, 44]);
+
+		// A missing entry is signalled with the return code 1.
+		mock_ext.runtime_storage.clear();
+		execute(
+			CODE_GET_RUNTIME_STORAGE,
+			WHITELISTED_RUNTIME_KEY,
+			&mut return_buf,
+			&mut mock_ext,
+			&mut GasMeter::with_limit(50_000, 1),
+		)
+		.unwrap();
+		assert_eq!(return_buf, vec![1]);
+	}
+
+	#[test]
+	fn get_runtime_storage_traps_on_keys_not_whitelisted() {
+		let mut mock_ext = MockExt::default();
+		mock_ext.runtime_storage.insert(b":test:secret".to_vec(), vec![42]);
+
+		assert_eq!(
+			execute(
+				CODE_GET_RUNTIME_STORAGE,
+				b":test:secret",
+				&mut Vec::new(),
+				&mut mock_ext,
+				&mut GasMeter::with_limit(50_000, 1),
+			),
+			Err("host function trapped"),
+		);
+	}
+
+	const CODE_CALL_CHAIN_EXTENSION: &str = r#"
+(module
+	(import "env" "ext_call_chain_extension" (func $ext_call_chain_extension (param i32 i32 i32)))
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_copy" (func $ext_scratch_copy (param i32 i32 i32)))
+	(import "env" "ext_return" (func $ext_return (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "call")
+		(local $input_len i32)
+		(local $output_len i32)
+		(set_local $input_len
+			(call $ext_scratch_size)
+		)
+
+		;; copy the input data into the contract's memory. The first byte is the function id,
+		;; the rest is passed to the function.
+		(call $ext_scratch_copy
+			(i32.const 0)			;; Pointer in memory to the place where to copy.
+			(i32.const 0)			;; Offset from the start of the scratch buffer.
+			(get_local $input_len)	;; Count of bytes to copy.
+		)
+
+		(call $ext_call_chain_extension
+			(i32.load8_u (i32.const 0))			;; The function id.
+			(i32.const 1)						;; Pointer to the input of the function.
+			(i32.sub (get_local $input_len) (i32.const 1))	;; Length of the input.
+		)
+
+		;; return the output of the function.
+		(set_local $output_len
+			(call $ext_scratch_size)
+		)
+		(call $ext_scratch_copy
+			(i32.const 256)
+			(i32.const 0)
+			(get_local $output_len)
+		)
+		(call $ext_return
+			(i32.const 256)
+			(get_local $output_len)
+		)
+	)
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn call_chain_extension() {
+		let mut mock_ext = MockExt::default();
+		let mut return_buf = Vec::new();
+		let mut gas_meter = GasMeter::with_limit(50_000, 1);
+		execute(
+			CODE_CALL_CHAIN_EXTENSION,
+			&[1, 10, 20, 30],
+			&mut return_buf,
+			&mut mock_ext,
+			&mut gas_meter,
+		)
+		.unwrap();
+
+		assert_eq!(return_buf, vec![30, 20, 10]);
+		assert_eq!(
+			&*mock_ext.chain_extension_calls.borrow(),
+			&[ChainExtensionEntry {
+				func_id: 1,
+				input: vec![10, 20, 30],
+			}]
+		);
+
+		// The gas cost of the function is charged on top of the base cost.
+		let schedule = Schedule::default();
+		assert!(gas_meter.spent() > schedule.chain_extension_base_cost + 1_000);
+	}
+
+	#[test]
+	fn call_chain_extension_traps_on_failure() {
+		let mut mock_ext = MockExt::default();
+		assert_eq!(
+			execute(
+				CODE_CALL_CHAIN_EXTENSION,
+				&[2, 10],
+				&mut Vec::new(),
+				&mut mock_ext,
+				&mut GasMeter::with_limit(50_000, 1),
+			),
+			Err("host function trapped"),
+		);
+		assert_eq!(mock_ext.chain_extension_calls.borrow().len(), 1);
+	}
+
+	#[test]
+	fn call_chain_extension_traps_before_calling_unknown_function() {
+		let mut mock_ext = MockExt::default();
+		assert_eq!(
+			execute(
+				CODE_CALL_CHAIN_EXTENSION,
+				&[3, 10],
+				&mut Vec::new(),
+				&mut mock_ext,
+				&mut GasMeter::with_limit(50_000, 1),
+			),
+			Err("host function trapped"),
+		);
+		assert!(mock_ext.chain_extension_calls.borrow().is_empty());
+	}
+
+	#[test]
+	fn call_chain_extension_traps_before_calling_function_without_enough_gas() {
+		let mut mock_ext = MockExt::default();
+		// The gas cost of the function alone exceeds the gas limit.
+		assert_eq!(
+			execute(
+				CODE_CALL_CHAIN_EXTENSION,
+				&[1, 10, 20, 30],
+				&mut Vec::new(),
+				&mut mock_ext,
+				&mut GasMeter::with_limit(1_000, 1),
+			),
+			Err("host function trapped"),
+		);
+		assert!(mock_ext.chain_extension_calls.borrow().is_empty());
+	}
+
+	#[test]
+	fn trace_records_trapped_host_call() {
+		let mut mock_ext = MockExt::default();
+		mock_ext.trace = Some(Vec::new());
+		assert!(
+			execute(
+				CODE_CALL_CHAIN_EXTENSION,
+				&[2, 10],
+				&mut Vec::new(),
+				&mut mock_ext,
+				&mut GasMeter::with_limit(50_000, 1),
+			).is_err()
+		);
+
+		let trace = mock_ext.trace.unwrap();
+		assert_eq!(
+			host_calls(&trace).last(),
+			Some(&("ext_call_chain_extension", vec![2, 1, 1], None, true)),
+		);
+	}
+
 	const CODE_DEPOSIT_EVENT: &str = r#"
 (module
 	(import "env" "ext_deposit_event" (func $ext_deposit_event (param i32 i32 i32 i32)))
@@ -1295,7 +1869,7 @@ mod tests {
 				&mut mock_ext,
 				&mut gas_meter
 			),
-			Err("during execution"),
+			Err("host function trapped"),
 		);
 	}
 
@@ -1339,7 +1913,7 @@ mod tests {
 				&mut mock_ext,
 				&mut gas_meter
 			),
-			Err("during execution"),
+			Err("host function trapped"),
 		);
 	}
 }
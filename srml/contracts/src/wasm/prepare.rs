@@ -24,7 +24,7 @@ use crate::Schedule;
 
 use parity_wasm::elements::{self, Internal, External, MemoryType, Type};
 use pwasm_utils;
-use pwasm_utils::rules;
+use pwasm_utils::rules::{self, InstructionType, Metering};
 use rstd::prelude::*;
 use runtime_primitives::traits::{SaturatedConversion};
 
@@ -75,12 +75,27 @@ impl<'a> ContractModule<'a> {
 	}
 
 	fn inject_gas_metering(self) -> Result<Self, &'static str> {
+		let schedule = self.schedule;
+		let fixed = |cost: u64| Metering::Fixed(cost.saturated_into());
 		let gas_rules =
 			rules::Set::new(
-				self.schedule.regular_op_cost.clone().saturated_into(),
-				Default::default(),
+				schedule.regular_op_cost.saturated_into(),
+				vec![
+					(InstructionType::Load, fixed(schedule.load_op_cost)),
+					(InstructionType::Store, fixed(schedule.store_op_cost)),
+					(InstructionType::ControlFlow, fixed(schedule.control_flow_op_cost)),
+					(InstructionType::Local, fixed(schedule.local_op_cost)),
+					(InstructionType::Global, fixed(schedule.global_op_cost)),
+					(InstructionType::Const, fixed(schedule.const_op_cost)),
+					(InstructionType::Add, fixed(schedule.add_op_cost)),
+					(InstructionType::Mul, fixed(schedule.mul_op_cost)),
+					(InstructionType::Div, fixed(schedule.div_op_cost)),
+					(InstructionType::Bit, fixed(schedule.bit_op_cost)),
+					(InstructionType::IntegerComparsion, fixed(schedule.comparison_op_cost)),
+					(InstructionType::Conversion, fixed(schedule.conversion_op_cost)),
+				].into_iter().collect(),
 			)
-			.with_grow_cost(self.schedule.grow_mem_cost.clone().saturated_into())
+			.with_grow_cost(schedule.grow_mem_cost.saturated_into())
 			.with_forbidden_floats();
 
 		let contract_module = pwasm_utils::inject_gas_counter(self.module, &gas_rules)
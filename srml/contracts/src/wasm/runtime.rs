@@ -17,11 +17,13 @@
 //! Environment definition of the wasm smart-contract runtime.
 
 use crate::{Schedule, Trait, CodeHash, ComputeDispatchFee, BalanceOf};
+use srml_support::traits::Contains;
 use crate::exec::{
 	Ext, VmExecResult, OutputBuf, EmptyOutputBuf, CallReceipt, InstantiateReceipt, StorageKey,
 	TopicOf,
 };
 use crate::gas::{Gas, GasMeter, Token, GasMeterResult, approx_gas_for_balance};
+use crate::trace::{HostCallTrace, TraceEntry};
 use sandbox;
 use system;
 use rstd::prelude::*;
@@ -36,6 +38,9 @@ use runtime_primitives::traits::{Bounded, SaturatedConversion};
 enum SpecialTrap {
 	/// Signals that trap was generated in response to call `ext_return` host function.
 	Return(OutputBuf),
+	/// Signals that trap was generated in response to a successful call to the
+	/// `ext_terminate` host function.
+	Termination,
 }
 
 /// Can only be used for one call.
@@ -86,15 +91,56 @@ pub(crate) fn to_execution_result<E: Ext>(
 		// No traps were generated. Proceed normally.
 		(None, None) => VmExecResult::Ok,
 		// Special case. The trap was the result of the execution `return` host function.
-		(Some(sandbox::Error::Execution), Some(SpecialTrap::Return(buf))) => VmExecResult::Returned(buf),
-		// Any other kind of a trap should result in a failure.
-		(Some(_), _) => VmExecResult::Trap("during execution"),
+		// The kind of the trap is not checked, since an older host reports every trap as
+		// `Error::Execution`.
+		(Some(_), Some(SpecialTrap::Return(buf))) => VmExecResult::Returned(buf),
+		// The contract terminated itself. This is a successful execution without output.
+		(Some(_), Some(SpecialTrap::Termination)) => VmExecResult::Ok,
+		// Any other kind of a trap should result in a failure, reporting why the contract trapped.
+		(Some(sandbox::Error::Trap(trap_code)), None) => VmExecResult::Trap(trap_code.description()),
+		(Some(_), None) => VmExecResult::Trap("during execution"),
 		// Any other case (such as special trap flag without actual trap) signifies
 		// a logic error.
 		_ => unreachable!(),
 	}
 }
 
+/// Record the invocation of a host function in the trace of the contract, if it is traced.
+///
+/// Successful invocations of `gas` are not recorded, since they are injected by the
+/// instrumentation and would only clutter the trace.
+pub(crate) fn trace_host_call<E: Ext>(
+	ctx: &mut Runtime<E>,
+	name: &'static str,
+	args: &[sandbox::TypedValue],
+	result: &Result<sandbox::ReturnValue, sandbox::HostError>,
+) {
+	if !ctx.ext.is_tracing() || (name == "gas" && result.is_ok()) {
+		return;
+	}
+
+	fn to_u64(value: &sandbox::TypedValue) -> u64 {
+		match *value {
+			sandbox::TypedValue::I32(v) => v as u32 as u64,
+			sandbox::TypedValue::I64(v) => v as u64,
+			sandbox::TypedValue::F32(v) => v as u32 as u64,
+			sandbox::TypedValue::F64(v) => v as u64,
+		}
+	}
+
+	let entry = TraceEntry::HostCall(HostCallTrace {
+		name,
+		args: args.iter().map(to_u64).collect(),
+		result: match result {
+			Ok(sandbox::ReturnValue::Value(value)) => Some(to_u64(value)),
+			_ => None,
+		},
+		trapped: result.is_err() && ctx.special_trap.is_none(),
+		gas_left: ctx.gas_meter.gas_left(),
+	});
+	ctx.ext.trace(entry);
+}
+
 #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 #[derive(Copy, Clone)]
 pub enum RuntimeToken {
@@ -113,6 +159,18 @@ pub enum RuntimeToken {
 	/// (topic_count, data_bytes): A buffer of the given size is posted as an event indexed with the
 	/// given number of topics.
 	DepositEvent(u32, u32),
+	/// BLAKE2 256-bit hash of the given number of bytes is computed.
+	HashBlake256(u32),
+	/// KECCAK 256-bit hash of the given number of bytes is computed.
+	HashKeccak256(u32),
+	/// SHA2 256-bit hash of the given number of bytes is computed.
+	HashSha256(u32),
+	/// The current block number is queried.
+	BlockNumber,
+	/// A runtime storage value of the given number of bytes is read.
+	GetRuntimeStorage(u32),
+	/// A function of the chain extension with the given gas cost is called.
+	ChainExtension(Gas),
 }
 
 impl<T: Trait> Token<T> for RuntimeToken {
@@ -151,6 +209,26 @@ impl<T: Trait> Token<T> for RuntimeToken {
 					)
 			},
 			ComputedDispatchFee(gas) => Some(gas),
+			HashBlake256(byte_count) => metadata
+				.hash_blake2_256_per_byte_cost
+				.checked_mul(byte_count.into())
+				.and_then(|cost| cost.checked_add(metadata.hash_blake2_256_base_cost)),
+			HashKeccak256(byte_count) => metadata
+				.hash_keccak_256_per_byte_cost
+				.checked_mul(byte_count.into())
+				.and_then(|cost| cost.checked_add(metadata.hash_keccak_256_base_cost)),
+			HashSha256(byte_count) => metadata
+				.hash_sha2_256_per_byte_cost
+				.checked_mul(byte_count.into())
+				.and_then(|cost| cost.checked_add(metadata.hash_sha2_256_base_cost)),
+			BlockNumber => Some(metadata.block_number_cost),
+			GetRuntimeStorage(byte_count) => metadata
+				.get_runtime_storage_per_byte_cost
+				.checked_mul(byte_count.into())
+				.and_then(|cost| cost.checked_add(metadata.get_runtime_storage_base_cost)),
+			ChainExtension(gas_cost) => metadata
+				.chain_extension_base_cost
+				.checked_add(gas_cost),
 		};
 
 		value.unwrap_or_else(|| Bounded::max_value())
@@ -271,6 +349,9 @@ define_env!(Env, <E: Ext>,
 			} else {
 				None
 			};
+		if ctx.ext.is_tracing() {
+			ctx.ext.trace(TraceEntry::StorageWrite { key, value: value.clone() });
+		}
 		ctx.ext.set_storage(key, value);
 
 		Ok(())
@@ -285,7 +366,11 @@ define_env!(Env, <E: Ext>,
 	ext_get_storage(ctx, key_ptr: u32) -> u32 => {
 		let mut key: StorageKey = [0; 32];
 		read_sandbox_memory_into_buf(ctx, key_ptr, &mut key)?;
-		if let Some(value) = ctx.ext.get_storage(&key) {
+		let value = ctx.ext.get_storage(&key);
+		if ctx.ext.is_tracing() {
+			ctx.ext.trace(TraceEntry::StorageRead { key, value: value.clone() });
+		}
+		if let Some(value) = value {
 			ctx.scratch_buf = value;
 			Ok(0)
 		} else {
@@ -480,6 +565,31 @@ define_env!(Env, <E: Ext>,
 		Err(sandbox::HostError)
 	},
 
+	// Remove the calling contract and transfer its remaining balance to the beneficiary.
+	//
+	// This function never returns. Either the termination was successful and the execution
+	// of the destroyed contract is halted, or the termination failed, which results in a trap.
+	// The termination fails if the contract is also executing further up the call stack.
+	//
+	// - beneficiary_ptr: a pointer to the address of the account which receives all the
+	//   remaining funds of the contract. Should be decodable as an `T::AccountId`. Traps otherwise.
+	// - beneficiary_len: length of the address buffer.
+	ext_terminate(ctx, beneficiary_ptr: u32, beneficiary_len: u32) => {
+		let beneficiary = {
+			let beneficiary_buf = read_sandbox_memory(ctx, beneficiary_ptr, beneficiary_len)?;
+			<<E as Ext>::T as system::Trait>::AccountId::decode(&mut &beneficiary_buf[..])
+				.ok_or_else(|| sandbox::HostError)?
+		};
+
+		if ctx.ext.terminate(&beneficiary, ctx.gas_meter).is_ok() {
+			ctx.special_trap = Some(SpecialTrap::Termination);
+		}
+
+		// The trap mechanism is used to immediately halt the execution. The special trap set
+		// above distinguishes a successful termination from a failed one.
+		Err(sandbox::HostError)
+	},
+
 	// Stores the address of the caller into the scratch buffer.
 	//
 	// If this is a top-level call (i.e. initiated by an extrinsic) the origin address of the extrinsic
@@ -558,9 +668,101 @@ define_env!(Env, <E: Ext>,
 		Ok(())
 	},
 
+	// Stores the current block number into the scratch buffer.
+	//
+	// The data is encoded as T::BlockNumber. The current contents of the scratch buffer are
+	// overwritten.
+	ext_block_number(ctx) => {
+		charge_gas(ctx.gas_meter, ctx.schedule, RuntimeToken::BlockNumber)?;
+		ctx.scratch_buf.clear();
+		ctx.ext.block_number().encode_to(&mut ctx.scratch_buf);
+		Ok(())
+	},
+
+	// Computes the BLAKE2 256-bit hash of the given input and writes it to the output buffer.
+	//
+	// - input_ptr: a pointer to the buffer to hash.
+	// - input_len: length of the input buffer.
+	// - output_ptr: a pointer to the 32 bytes of memory the hash is written to.
+	ext_hash_blake2_256(ctx, input_ptr: u32, input_len: u32, output_ptr: u32) => {
+		compute_hash_on_intermediate_buffer(
+			ctx, RuntimeToken::HashBlake256(input_len), runtime_io::blake2_256, input_ptr, input_len, output_ptr,
+		)
+	},
+
+	// Computes the KECCAK 256-bit hash of the given input and writes it to the output buffer.
+	//
+	// - input_ptr: a pointer to the buffer to hash.
+	// - input_len: length of the input buffer.
+	// - output_ptr: a pointer to the 32 bytes of memory the hash is written to.
+	ext_hash_keccak_256(ctx, input_ptr: u32, input_len: u32, output_ptr: u32) => {
+		compute_hash_on_intermediate_buffer(
+			ctx, RuntimeToken::HashKeccak256(input_len), runtime_io::keccak_256, input_ptr, input_len, output_ptr,
+		)
+	},
+
+	// Computes the SHA2 256-bit hash of the given input and writes it to the output buffer.
+	//
+	// - input_ptr: a pointer to the buffer to hash.
+	// - input_len: length of the input buffer.
+	// - output_ptr: a pointer to the 32 bytes of memory the hash is written to.
+	ext_hash_sha2_256(ctx, input_ptr: u32, input_len: u32, output_ptr: u32) => {
+		compute_hash_on_intermediate_buffer(
+			ctx, RuntimeToken::HashSha256(input_len), runtime_io::sha2_256, input_ptr, input_len, output_ptr,
+		)
+	},
+
+	// Retrieve the value under the given raw key from the runtime storage and return 0.
+	// If there is no entry under the given key then this function will return 1 and
+	// clear the scratch buffer.
+	//
+	// Only the keys whitelisted by `Trait::RuntimeStorageWhitelist` can be read. Traps otherwise.
+	//
+	// - key_ptr: pointer into the linear memory where the key is placed.
+	// - key_len: length of the key buffer.
+	ext_get_runtime_storage(ctx, key_ptr: u32, key_len: u32) -> u32 => {
+		let key = read_sandbox_memory(ctx, key_ptr, key_len)?;
+		if !<<E as Ext>::T as Trait>::RuntimeStorageWhitelist::contains(&key) {
+			return Err(sandbox::HostError);
+		}
+		match ctx.ext.get_runtime_storage(&key) {
+			Some(value) => {
+				charge_gas(ctx.gas_meter, ctx.schedule, RuntimeToken::GetRuntimeStorage(value.len() as u32))?;
+				ctx.scratch_buf = value;
+				Ok(0)
+			}
+			None => {
+				charge_gas(ctx.gas_meter, ctx.schedule, RuntimeToken::GetRuntimeStorage(0))?;
+				ctx.scratch_buf.clear();
+				Ok(1)
+			}
+		}
+	},
+
+	// Call a function of the chain extension provided by the runtime and
+	// put its output into the scratch buffer.
+	//
+	// Traps if the runtime doesn't provide the function, there is not enough gas to pay
+	// for it or the function fails. The gas is charged before the function is called.
+	//
+	// - func_id: the identifier of the function. Its meaning is defined by the runtime.
+	// - input_ptr: a pointer to the input of the function.
+	// - input_len: the length of the input.
+	ext_call_chain_extension(ctx, func_id: u32, input_ptr: u32, input_len: u32) => {
+		let input = read_sandbox_memory(ctx, input_ptr, input_len)?;
+		let gas_cost = ctx.ext.chain_extension_gas_cost(func_id, &input)
+			.ok_or_else(|| sandbox::HostError)?;
+		charge_gas(ctx.gas_meter, ctx.schedule, RuntimeToken::ChainExtension(gas_cost))?;
+		ctx.scratch_buf = ctx.ext.query_chain_extension(func_id, input)
+			.map_err(|_| sandbox::HostError)?;
+		Ok(())
+	},
+
 	// Decodes the given buffer as a `T::Call` and adds it to the list
 	// of to-be-dispatched calls.
 	//
+	// Traps if the call is not allowed by `T::CallFilter`.
+	//
 	// All calls made it to the top-level context will be dispatched before
 	// finishing the execution of the calling extrinsic.
 	ext_dispatch_call(ctx, call_ptr: u32, call_len: u32) => {
@@ -569,6 +771,9 @@ define_env!(Env, <E: Ext>,
 			<<<E as Ext>::T as Trait>::Call>::decode(&mut &call_buf[..])
 				.ok_or_else(|| sandbox::HostError)?
 		};
+		if !<<E as Ext>::T as Trait>::CallFilter::contains(&call) {
+			return Err(sandbox::HostError);
+		}
 
 		// Charge gas for dispatching this call.
 		let fee = {
@@ -703,6 +908,30 @@ define_env!(Env, <E: Ext>,
 	},
 );
 
+/// Computes the given hash function on the supplied input and writes the result to the
+/// sandbox memory, charging the given token for the hashing itself.
+///
+/// Reading the input and writing the output are charged as regular memory accesses.
+fn compute_hash_on_intermediate_buffer<E: Ext>(
+	ctx: &mut Runtime<E>,
+	token: RuntimeToken,
+	hash_fn: fn(&[u8]) -> [u8; 32],
+	input_ptr: u32,
+	input_len: u32,
+	output_ptr: u32,
+) -> Result<(), sandbox::HostError> {
+	charge_gas(ctx.gas_meter, ctx.schedule, token)?;
+	let input = read_sandbox_memory(ctx, input_ptr, input_len)?;
+	let hash = hash_fn(&input);
+	write_sandbox_memory(
+		ctx.schedule,
+		ctx.gas_meter,
+		&ctx.memory,
+		output_ptr,
+		&hash,
+	)
+}
+
 /// Finds duplicates in a given vector.
 ///
 /// This function has complexity of O(n log n) and no additional memory is required, although
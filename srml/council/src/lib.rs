@@ -19,21 +19,10 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![recursion_limit="128"]
 
-pub mod motions;
 pub mod seats;
 
 pub use crate::seats::{Trait, Module, RawEvent, Event, VoteIndex};
 
-/// Trait for type that can handle incremental changes to a set of account IDs.
-pub trait OnMembersChanged<AccountId> {
-	/// A number of members `new` just joined the set and replaced some `old` ones.
-	fn on_members_changed(new: &[AccountId], old: &[AccountId]);
-}
-
-impl<T> OnMembersChanged<T> for () {
-	fn on_members_changed(_new: &[T], _old: &[T]) {}
-}
-
 #[cfg(test)]
 mod tests {
 	// These re-exports are here for a reason, edit with care
@@ -44,18 +33,19 @@ mod tests {
 	pub use substrate_primitives::{H256, Blake2Hasher, u32_trait::{_1, _2, _3, _4}};
 	pub use primitives::traits::{BlakeTwo256, IdentityLookup};
 	pub use primitives::testing::{Digest, DigestItem, Header};
-	pub use {seats, motions};
+	pub use primitives::{Perbill, weights::Weight};
+	pub use seats;
 	use std::cell::RefCell;
 
 	impl_outer_origin! {
 		pub enum Origin for Test {
-			motions<T>
+			collective<T>
 		}
 	}
 
 	impl_outer_event! {
 		pub enum Event for Test {
-			balances<T>, democracy<T>, seats<T>, motions<T>,
+			balances<T>, democracy<T>, seats<T>, collective<T>,
 		}
 	}
 
@@ -96,6 +86,10 @@ mod tests {
 	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
 	#[derive(Clone, Eq, PartialEq, Debug)]
 	pub struct Test;
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
 	impl system::Trait for Test {
 		type Origin = Origin;
 		type Index = u64;
@@ -106,6 +100,9 @@ mod tests {
 		type Lookup = IdentityLookup<Self::AccountId>;
 		type Header = Header;
 		type Event = Event;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
 	}
 	parameter_types! {
 		pub const ExistentialDeposit: u64 = 0;
@@ -127,6 +124,7 @@ mod tests {
 		type CreationFee = CreationFee;
 		type TransactionBaseFee = TransactionBaseFee;
 		type TransactionByteFee = TransactionByteFee;
+		type WeightToFee = ();
 	}
 	parameter_types! {
 		pub const LaunchPeriod: u64 = 1;
@@ -144,11 +142,11 @@ mod tests {
 		type EmergencyVotingPeriod = VotingPeriod;
 		type VotingPeriod = VotingPeriod;
 		type MinimumDeposit = MinimumDeposit;
-		type ExternalOrigin = motions::EnsureProportionAtLeast<_1, _2, u64>;
-		type ExternalMajorityOrigin = motions::EnsureProportionAtLeast<_2, _3, u64>;
-		type EmergencyOrigin = motions::EnsureProportionAtLeast<_1, _1, u64>;
-		type CancellationOrigin = motions::EnsureProportionAtLeast<_2, _3, u64>;
-		type VetoOrigin = motions::EnsureMember<u64>;
+		type ExternalOrigin = collective::EnsureProportionAtLeast<_1, _2, u64>;
+		type ExternalMajorityOrigin = collective::EnsureProportionAtLeast<_2, _3, u64>;
+		type EmergencyOrigin = collective::EnsureProportionAtLeast<_1, _1, u64>;
+		type CancellationOrigin = collective::EnsureProportionAtLeast<_2, _3, u64>;
+		type VetoOrigin = collective::EnsureMember<u64>;
 		type CooloffPeriod = CooloffPeriod;
 	}
 	parameter_types! {
@@ -163,7 +161,7 @@ mod tests {
 		type BadReaper = ();
 		type BadVoterIndex = ();
 		type LoserCandidate = ();
-		type OnMembersChanged = CouncilMotions;
+		type ChangeMembers = CouncilCollective;
 		type CandidacyBond = CandidacyBond;
 		type VotingBond = VotingBond;
 		type VotingFee = VotingFee;
@@ -173,10 +171,14 @@ mod tests {
 		type CouncilVotingPeriod = CouncilVotingPeriod;
 		type DecayRatio = DecayRatio;
 	}
-	impl motions::Trait for Test {
+	parameter_types! {
+		pub const MotionDuration: u64 = 3;
+	}
+	impl collective::Trait for Test {
 		type Origin = Origin;
 		type Proposal = Call;
 		type Event = Event;
+		type MotionDuration = MotionDuration;
 	}
 
 	pub struct ExtBuilder {
@@ -246,6 +248,10 @@ mod tests {
 				],
 				vesting: vec![],
 			}.build_storage().unwrap().0);
+			t.extend(collective::GenesisConfig::<Test> {
+				members: if self.with_council { vec![1, 2, 3] } else { vec![] },
+				phantom: Default::default(),
+			}.build_storage().unwrap().0);
 			t.extend(seats::GenesisConfig::<Test> {
 				active_council: if self.with_council { vec![
 					(1, 10),
@@ -264,5 +270,5 @@ mod tests {
 	pub type Balances = balances::Module<Test>;
 	pub type Democracy = democracy::Module<Test>;
 	pub type Council = seats::Module<Test>;
-	pub type CouncilMotions = motions::Module<Test>;
+	pub type CouncilCollective = collective::Module<Test>;
 }
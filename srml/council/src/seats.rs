@@ -18,19 +18,18 @@
 
 use rstd::prelude::*;
 use primitives::traits::{Zero, One, StaticLookup, Bounded, Saturating};
-use runtime_io::print;
+use runtime_io::{print, blake2_256, twox_128};
 use srml_support::{
-	StorageValue, StorageMap,
+	StorageValue, StorageMap, storage::hashed,
 	dispatch::Result, decl_storage, decl_event, ensure, decl_module,
 	traits::{
 		Currency, ExistenceRequirement, Get, LockableCurrency, LockIdentifier,
-		OnUnbalanced, ReservableCurrency, WithdrawReason, WithdrawReasons,
+		OnUnbalanced, ReservableCurrency, WithdrawReason, WithdrawReasons, ChangeMembers,
 	}
 };
 use democracy;
 use parity_codec::{Encode, Decode};
 use system::{self, ensure_signed};
-use super::OnMembersChanged;
 
 // no polynomial attacks:
 //
@@ -155,7 +154,7 @@ pub trait Trait: democracy::Trait {
 	/// Handler for the unbalanced reduction when a candidate has lost (and is not a runner up)
 	type LoserCandidate: OnUnbalanced<NegativeImbalanceOf<Self>>;
 	/// What to do when the members change.
-	type OnMembersChanged: OnMembersChanged<Self::AccountId>;
+	type ChangeMembers: ChangeMembers<Self::AccountId>;
 
 	/// How much should be locked up in order to submit one's candidacy. A reasonable
 	/// default value is 9.
@@ -501,8 +500,9 @@ decl_module! {
 				.into_iter()
 				.filter(|i| i.0 != who)
 				.collect();
+			let new_members = new_council.iter().map(|(a, _)| a.clone()).collect();
 			<ActiveCouncil<T>>::put(new_council);
-			T::OnMembersChanged::on_members_changed(&[], &[who]);
+			T::ChangeMembers::change_members(&[], &[who], new_members);
 		}
 
 		/// Set the presentation duration. If there is currently a vote being presented for, will
@@ -518,6 +518,7 @@ decl_module! {
 		}
 
 		fn on_initialize(n: T::BlockNumber) {
+			Self::ensure_collective_migrated();
 			if let Err(e) = Self::end_block(n) {
 				print("Guru meditation");
 				print(e);
@@ -574,6 +575,11 @@ decl_storage! {
 		/// Get the leaderboard if we're in the presentation phase. The first element is the weight of each entry;
 		/// It may be the direct summed approval stakes, or a weighted version of it.
 		pub Leaderboard get(leaderboard): Option<Vec<(BalanceOf<T>, T::AccountId)> >; // ORDERED low -> high
+
+		// ---- migration
+		/// Whether the members of the `ChangeMembers` collective have been seeded from the current
+		/// council, and the motions of the former council motions module been removed.
+		pub CollectiveMigrated get(collective_migrated): bool;
 	}
 }
 
@@ -648,6 +654,32 @@ impl<T: Trait> Module<T> {
 	}
 
 	// Private
+
+	/// Migrate from the council motions module, which read the council members from this module,
+	/// to a `ChangeMembers` collective keeping its own members, if not done yet.
+	///
+	/// The collective is seeded with the current council. Motions still open in the former module
+	/// are dropped: their proposals were encoded with the former call indices, so they cannot be
+	/// carried over safely, and can simply be proposed again.
+	fn ensure_collective_migrated() {
+		if CollectiveMigrated::get() {
+			return
+		}
+
+		let members = Self::active_council().into_iter().map(|(who, _)| who).collect::<Vec<_>>();
+		T::ChangeMembers::change_members(&members, &[], members.clone());
+
+		let proposals: Vec<T::Hash> =
+			hashed::take_or_default(&twox_128, b"CouncilMotions Proposals");
+		for proposal in proposals {
+			let proposal = proposal.encode();
+			hashed::kill(&blake2_256, &[&b"CouncilMotions ProposalOf"[..], &proposal[..]].concat());
+			hashed::kill(&blake2_256, &[&b"CouncilMotions Voting"[..], &proposal[..]].concat());
+		}
+		hashed::kill(&twox_128, b"CouncilMotions ProposalCount");
+
+		CollectiveMigrated::put(true);
+	}
 	/// Check there's nothing to do this block
 	fn end_block(block_number: T::BlockNumber) -> Result {
 		if (block_number % T::CouncilVotingPeriod::get()).is_zero() {
@@ -829,9 +861,10 @@ impl<T: Trait> Module<T> {
 			.chain(incoming.iter().cloned().map(|a| (a, new_expiry)))
 			.collect();
 		new_council.sort_by_key(|&(_, expiry)| expiry);
+		let new_members = new_council.iter().map(|(a, _)| a.clone()).collect();
 		<ActiveCouncil<T>>::put(new_council);
 
-		T::OnMembersChanged::on_members_changed(&incoming, &outgoing);
+		T::ChangeMembers::change_members(&incoming, &outgoing, new_members);
 
 		// clear all except runners-up from candidate list.
 		let candidates = Self::candidates();
@@ -2058,6 +2091,7 @@ mod tests {
 
 			assert!(!Council::presentation_active());
 			assert_eq!(Council::active_council(), vec![(5, 11), (2, 11)]);
+			assert_eq!(CouncilCollective::members(), vec![2, 5]);
 
 			assert!(!Council::is_a_candidate(&2));
 			assert!(!Council::is_a_candidate(&5));
@@ -2067,6 +2101,16 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn removing_a_member_updates_the_collective() {
+		with_externalities(&mut ExtBuilder::default().with_council(true).build(), || {
+			assert_eq!(CouncilCollective::members(), vec![1, 2, 3]);
+			assert_ok!(Council::remove_member(2));
+			assert_eq!(Council::active_council(), vec![(1, 10), (3, 10)]);
+			assert_eq!(CouncilCollective::members(), vec![1, 3]);
+		});
+	}
+
 	#[test]
 	fn seats_should_be_released() {
 		with_externalities(&mut ExtBuilder::default().build(), || {
@@ -2635,4 +2679,35 @@ mod tests {
 			assert_eq!(Council::candidate_reg_info(4), Some((0, 3)));
 		});
 	}
+
+	#[test]
+	fn collective_is_migrated_from_council_motions() {
+		with_externalities(&mut ExtBuilder::default().with_council(true).build(), || {
+			use primitives::traits::OnInitialize;
+
+			// The collective knows no members yet, and a motion of the former module is open.
+			<collective::Members<Test>>::kill();
+			let proposal = H256::repeat_byte(1);
+			let proposal_of = [&b"CouncilMotions ProposalOf"[..], &proposal.encode()[..]].concat();
+			let voting = [&b"CouncilMotions Voting"[..], &proposal.encode()[..]].concat();
+			hashed::put(&twox_128, b"CouncilMotions Proposals", &vec![proposal]);
+			hashed::put(&twox_128, b"CouncilMotions ProposalCount", &1u32);
+			hashed::put_raw(&blake2_256, &proposal_of, &[0u8]);
+			hashed::put_raw(&blake2_256, &voting, &[0u8]);
+
+			System::set_block_number(1);
+			Council::on_initialize(1);
+			assert!(Council::collective_migrated());
+			assert_eq!(CouncilCollective::members(), vec![1, 2, 3]);
+			assert!(!hashed::exists(&twox_128, b"CouncilMotions Proposals"));
+			assert!(!hashed::exists(&twox_128, b"CouncilMotions ProposalCount"));
+			assert!(!hashed::exists(&blake2_256, &proposal_of));
+			assert!(!hashed::exists(&blake2_256, &voting));
+
+			// It only happens once.
+			<collective::Members<Test>>::put(vec![1, 2]);
+			Council::on_initialize(2);
+			assert_eq!(CouncilCollective::members(), vec![1, 2]);
+		});
+	}
 }
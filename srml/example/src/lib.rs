@@ -402,8 +402,11 @@ decl_module! {
 		//
 		// A basic weight is a tuple of `(base_weight, byte_weight)`. Upon including each transaction
 		// in a block, the final weight is calculated as `base_weight + byte_weight * tx_size`.
-		// If this value, added to the weight of all included transactions, exceeds `MAX_TRANSACTION_WEIGHT`,
-		// the transaction is not included. If no weight attribute is provided, the `::default()`
+		// If this value, added to the weight of all included transactions, exceeds the portion of
+		// the system module's `MaximumBlockWeight` given by `AvailableBlockRatio`, the transaction
+		// is not included. `TransactionWeight::Operational` transactions may use the whole
+		// `MaximumBlockWeight` instead, which is meant for calls that keep the chain operational,
+		// e.g. misbehavior reports. If no weight attribute is provided, the `::default()`
 		// implementation of `TransactionWeight` is used.
 		//
 		// The example below showcases a transaction which is relatively costly, but less dependent on
@@ -510,7 +513,8 @@ mod tests {
 	// The testing primitives are very useful for avoiding having to work with signatures
 	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are requried.
 	use sr_primitives::{
-		traits::{BlakeTwo256, OnInitialize, OnFinalize, IdentityLookup}, testing::Header
+		Perbill, traits::{BlakeTwo256, OnInitialize, OnFinalize, IdentityLookup}, testing::Header,
+		weights::Weight,
 	};
 
 	impl_outer_origin! {
@@ -522,6 +526,10 @@ mod tests {
 	// configuration traits of modules we want to use.
 	#[derive(Clone, Eq, PartialEq)]
 	pub struct Test;
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
 	impl system::Trait for Test {
 		type Origin = Origin;
 		type Index = u64;
@@ -532,6 +540,9 @@ mod tests {
 		type Lookup = IdentityLookup<Self::AccountId>;
 		type Header = Header;
 		type Event = ();
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
 	}
 	parameter_types! {
 		pub const ExistentialDeposit: u64 = 0;
@@ -553,6 +564,7 @@ mod tests {
 		type CreationFee = CreationFee;
 		type TransactionBaseFee = TransactionBaseFee;
 		type TransactionByteFee = TransactionByteFee;
+		type WeightToFee = ();
 	}
 	impl Trait for Test {
 		type Event = ();
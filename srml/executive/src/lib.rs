@@ -56,7 +56,6 @@
 //! # pub struct Header {};
 //! # type Context = system::ChainContext<Runtime>;
 //! # pub type Block = generic::Block<Header, UncheckedExtrinsic>;
-//! # pub type Balances = u64;
 //! # pub type AllModules = u64;
 //! # pub enum Runtime {};
 //! # use primitives::transaction_validity::TransactionValidity;
@@ -69,7 +68,7 @@
 //! # 	}
 //! # }
 //! /// Executive: handles dispatch to the various modules.
-//! pub type Executive = executive::Executive<Runtime, Block, Context, Balances, Runtime, AllModules>;
+//! pub type Executive = executive::Executive<Runtime, Block, Context, Runtime, AllModules>;
 //! ```
 
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -79,25 +78,36 @@ use rstd::marker::PhantomData;
 use rstd::result;
 use primitives::{generic::Digest, traits::{
 	self, Header, Zero, One, Checkable, Applyable, CheckEqual, OnFinalize,
-	OnInitialize, NumberFor, Block as BlockT, OffchainWorker,
-	ValidateUnsigned,
+	OnInitialize, NumberFor, Block as BlockT, OffchainWorker, ValidateUnsigned,
 }};
-use srml_support::{Dispatchable, traits::MakePayment};
+use srml_support::Dispatchable;
 use parity_codec::{Codec, Encode};
 use system::{extrinsics_root, DigestOf};
 use primitives::{ApplyOutcome, ApplyError};
-use primitives::transaction_validity::{TransactionValidity, TransactionPriority, TransactionLongevity};
-use primitives::weights::Weighable;
+use primitives::transaction_validity::{TransactionValidity, ValidTransaction};
+use primitives::weights::GetDispatchInfo;
 
 mod internal {
-	pub const MAX_TRANSACTIONS_WEIGHT: u32 = 4 * 1024 * 1024;
-
 	pub enum ApplyError {
 		BadSignature(&'static str),
 		Stale,
 		Future,
 		CantPay,
 		FullBlock,
+		Rejected,
+	}
+
+	impl From<primitives::ApplyError> for ApplyError {
+		fn from(e: primitives::ApplyError) -> Self {
+			match e {
+				primitives::ApplyError::BadSignature => ApplyError::BadSignature(primitives::BAD_SIGNATURE),
+				primitives::ApplyError::Stale => ApplyError::Stale,
+				primitives::ApplyError::Future => ApplyError::Future,
+				primitives::ApplyError::CantPay => ApplyError::CantPay,
+				primitives::ApplyError::FullBlock => ApplyError::FullBlock,
+				primitives::ApplyError::Rejected => ApplyError::Rejected,
+			}
+		}
 	}
 
 	pub enum ApplyOutcome {
@@ -116,27 +126,27 @@ pub type CheckedOf<E, C> = <E as Checkable<C>>::Checked;
 pub type CallOf<E, C> = <CheckedOf<E, C> as Applyable>::Call;
 pub type OriginOf<E, C> = <CallOf<E, C> as Dispatchable>::Origin;
 
-pub struct Executive<System, Block, Context, Payment, UnsignedValidator, AllModules>(
-	PhantomData<(System, Block, Context, Payment, UnsignedValidator, AllModules)>
+pub struct Executive<System, Block, Context, UnsignedValidator, AllModules>(
+	PhantomData<(System, Block, Context, UnsignedValidator, AllModules)>
 );
 
 impl<
 	System: system::Trait,
 	Block: traits::Block<Header=System::Header, Hash=System::Hash>,
 	Context: Default,
-	Payment: MakePayment<System::AccountId>,
 	UnsignedValidator,
 	AllModules: OnInitialize<System::BlockNumber> + OnFinalize<System::BlockNumber> + OffchainWorker<System::BlockNumber>,
-> ExecuteBlock<Block> for Executive<System, Block, Context, Payment, UnsignedValidator, AllModules>
+> ExecuteBlock<Block> for Executive<System, Block, Context, UnsignedValidator, AllModules>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
-	CheckedOf<Block::Extrinsic, Context>: Applyable<Index=System::Index, AccountId=System::AccountId> + Weighable,
+	CheckedOf<Block::Extrinsic, Context>: Applyable<Index=System::Index, AccountId=System::AccountId>
+		+ GetDispatchInfo,
 	CallOf<Block::Extrinsic, Context>: Dispatchable,
 	OriginOf<Block::Extrinsic, Context>: From<Option<System::AccountId>>,
 	UnsignedValidator: ValidateUnsigned<Call=CallOf<Block::Extrinsic, Context>>,
 {
 	fn execute_block(block: Block) {
-		Executive::<System, Block, Context, Payment, UnsignedValidator, AllModules>::execute_block(block);
+		Executive::<System, Block, Context, UnsignedValidator, AllModules>::execute_block(block);
 	}
 }
 
@@ -144,13 +154,13 @@ impl<
 	System: system::Trait,
 	Block: traits::Block<Header=System::Header, Hash=System::Hash>,
 	Context: Default,
-	Payment: MakePayment<System::AccountId>,
 	UnsignedValidator,
 	AllModules: OnInitialize<System::BlockNumber> + OnFinalize<System::BlockNumber> + OffchainWorker<System::BlockNumber>,
-> Executive<System, Block, Context, Payment, UnsignedValidator, AllModules>
+> Executive<System, Block, Context, UnsignedValidator, AllModules>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
-	CheckedOf<Block::Extrinsic, Context>: Applyable<Index=System::Index, AccountId=System::AccountId> + Weighable,
+	CheckedOf<Block::Extrinsic, Context>: Applyable<Index=System::Index, AccountId=System::AccountId>
+		+ GetDispatchInfo,
 	CallOf<Block::Extrinsic, Context>: Dispatchable,
 	OriginOf<Block::Extrinsic, Context>: From<Option<System::AccountId>>,
 	UnsignedValidator: ValidateUnsigned<Call=CallOf<Block::Extrinsic, Context>>,
@@ -211,6 +221,7 @@ where
 
 		// post-extrinsics book-keeping
 		<system::Module<System>>::note_finished_extrinsics();
+		<system::Module<System> as OnFinalize<System::BlockNumber>>::on_finalize(block_number);
 		<AllModules as OnFinalize<System::BlockNumber>>::on_finalize(block_number);
 	}
 
@@ -218,7 +229,9 @@ where
 	/// except state-root.
 	pub fn finalize_block() -> System::Header {
 		<system::Module<System>>::note_finished_extrinsics();
-		<AllModules as OnFinalize<System::BlockNumber>>::on_finalize(<system::Module<System>>::block_number());
+		let block_number = <system::Module<System>>::block_number();
+		<system::Module<System> as OnFinalize<System::BlockNumber>>::on_finalize(block_number);
+		<AllModules as OnFinalize<System::BlockNumber>>::on_finalize(block_number);
 
 		// set up extrinsics
 		<system::Module<System>>::derive_extrinsics();
@@ -239,6 +252,7 @@ where
 			Err(internal::ApplyError::Stale) => Err(ApplyError::Stale),
 			Err(internal::ApplyError::Future) => Err(ApplyError::Future),
 			Err(internal::ApplyError::FullBlock) => Err(ApplyError::FullBlock),
+			Err(internal::ApplyError::Rejected) => Err(ApplyError::Rejected),
 		}
 	}
 
@@ -252,6 +266,7 @@ where
 			Err(internal::ApplyError::BadSignature(_)) => panic!("All extrinsics should be properly signed"),
 			Err(internal::ApplyError::Stale) | Err(internal::ApplyError::Future) => panic!("All extrinsics should have the correct nonce"),
 			Err(internal::ApplyError::FullBlock) => panic!("Extrinsics should not exceed block limit"),
+			Err(internal::ApplyError::Rejected) => panic!("All extrinsics should pass their signed extensions"),
 		}
 	}
 
@@ -264,27 +279,14 @@ where
 		// Verify that the signature is good.
 		let xt = uxt.check(&Default::default()).map_err(internal::ApplyError::BadSignature)?;
 
-		// Check the weight of the block if that extrinsic is applied.
-		let weight = xt.weight(encoded_len);
-		if <system::Module<System>>::all_extrinsics_weight() + weight > internal::MAX_TRANSACTIONS_WEIGHT {
-			return Err(internal::ApplyError::FullBlock);
-		}
+		// Run the checks of the extensions, e.g. of the nonce, the fees and the weight of the block.
+		// Only if all of them pass, the extensions change the state, e.g. by bumping the nonce.
+		let dispatch_info = xt.get_dispatch_info(encoded_len);
+		xt.pre_dispatch(dispatch_info, encoded_len).map_err(internal::ApplyError::from)?;
 
-		if let (Some(sender), Some(index)) = (xt.sender(), xt.index()) {
-			// check index
-			let expected_index = <system::Module<System>>::account_nonce(sender);
-			if index != &expected_index { return Err(
-				if index < &expected_index { internal::ApplyError::Stale } else { internal::ApplyError::Future }
-			) }
-			// pay any fees
-			Payment::make_payment(sender, encoded_len).map_err(|_| internal::ApplyError::CantPay)?;
-
-			// AUDIT: Under no circumstances may this function panic from here onwards.
-			// FIXME: ensure this at compile-time (such as by not defining a panic function, forcing
-			// a linker error unless the compiler can prove it cannot be called).
-			// increment nonce in storage
-			<system::Module<System>>::inc_account_nonce(sender);
-		}
+		// AUDIT: Under no circumstances may this function panic from here onwards.
+		// FIXME: ensure this at compile-time (such as by not defining a panic function, forcing
+		// a linker error unless the compiler can prove it cannot be called).
 
 		// Make sure to `note_extrinsic` only after we know it's going to be executed
 		// to prevent it from leaking in storage.
@@ -295,7 +297,7 @@ where
 		// Decode parameters and dispatch
 		let (f, s) = xt.deconstruct();
 		let r = f.dispatch(s.into());
-		<system::Module<System>>::note_applied_extrinsic(&r, encoded_len as u32);
+		<system::Module<System>>::note_applied_extrinsic(&r, dispatch_info.weight);
 
 		r.map(|_| internal::ApplyOutcome::Success).or_else(|e| match e {
 			primitives::BLOCK_FULL => Err(internal::ApplyError::FullBlock),
@@ -332,7 +334,6 @@ where
 	pub fn validate_transaction(uxt: Block::Extrinsic) -> TransactionValidity {
 		// Note errors > 0 are from ApplyError
 		const UNKNOWN_ERROR: i8 = -127;
-		const MISSING_SENDER: i8 = -20;
 		const INVALID_INDEX: i8 = -10;
 
 		let encoded_len = uxt.encode().len();
@@ -348,38 +349,24 @@ where
 			Err(_) => return TransactionValidity::Invalid(UNKNOWN_ERROR),
 		};
 
-		match (xt.sender(), xt.index()) {
-			(Some(sender), Some(index)) => {
-				// pay any fees
-				if Payment::make_payment(sender, encoded_len).is_err() {
-					return TransactionValidity::Invalid(ApplyError::CantPay as i8)
-				}
+		// Validate the extensions, e.g. of the nonce, the fees and the weight of the extrinsic.
+		let dispatch_info = xt.get_dispatch_info(encoded_len);
+		let extensions_validity = match xt.validate(dispatch_info, encoded_len) {
+			Ok(validity) => validity,
+			Err(e) => return TransactionValidity::Invalid(e as i8),
+		};
 
-				// check index
-				let expected_index = <system::Module<System>>::account_nonce(sender);
-				if index < &expected_index {
-					return TransactionValidity::Invalid(ApplyError::Stale as i8)
-				}
+		if xt.sender().is_some() {
+			return extensions_validity.into()
+		}
 
-				let index = *index;
-				let provides = vec![(sender, index).encode()];
-				let requires = if expected_index < index {
-					vec![(sender, index - One::one()).encode()]
-				} else {
-					vec![]
-				};
-
-				TransactionValidity::Valid {
-					priority: encoded_len as TransactionPriority,
-					requires,
-					provides,
-					longevity: TransactionLongevity::max_value(),
-					propagate: true,
-				}
-			},
-			(None, None) => UnsignedValidator::validate_unsigned(&xt.deconstruct().0),
-			(Some(_), None) => TransactionValidity::Invalid(INVALID_INDEX),
-			(None, Some(_)) => TransactionValidity::Invalid(MISSING_SENDER),
+		match UnsignedValidator::validate_unsigned(&xt.deconstruct().0) {
+			TransactionValidity::Valid { priority, requires, provides, longevity, propagate } => {
+				ValidTransaction { priority, requires, provides, longevity, propagate }
+					.combine_with(extensions_validity)
+					.into()
+			}
+			validity => validity,
 		}
 	}
 
@@ -395,8 +382,9 @@ mod tests {
 	use balances::Call;
 	use runtime_io::with_externalities;
 	use substrate_primitives::{H256, Blake2Hasher};
-	use primitives::BuildStorage;
+	use primitives::{BuildStorage, Perbill};
 	use primitives::traits::{Header as HeaderT, BlakeTwo256, IdentityLookup};
+	use primitives::weights::Weight;
 	use primitives::testing::{Digest, Header, Block};
 	use srml_support::{impl_outer_event, impl_outer_origin, parameter_types};
 	use srml_support::traits::Currency;
@@ -427,8 +415,13 @@ mod tests {
 		type Lookup = IdentityLookup<u64>;
 		type Header = Header;
 		type Event = MetaEvent;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
 	}
 	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
 		pub const ExistentialDeposit: u64 = 0;
 		pub const TransferFee: u64 = 0;
 		pub const CreationFee: u64 = 0;
@@ -448,6 +441,7 @@ mod tests {
 		type CreationFee = CreationFee;
 		type TransactionBaseFee = TransactionBaseFee;
 		type TransactionByteFee = TransactionByteFee;
+		type WeightToFee = ();
 	}
 
 	impl ValidateUnsigned for Runtime {
@@ -467,15 +461,13 @@ mod tests {
 		}
 	}
 
-	type TestXt = primitives::testing::TestXt<Call<Runtime>>;
-	type Executive = super::Executive<
-		Runtime,
-		Block<TestXt>,
-		system::ChainContext<Runtime>,
-		balances::Module<Runtime>,
-		Runtime,
-		()
-	>;
+	type SignedExtra = (system::CheckNonce<Runtime>, system::CheckWeight<Runtime>, balances::TakeFees<Runtime>);
+	type TestXt = primitives::testing::TestXt<Call<Runtime>, SignedExtra>;
+	type Executive = super::Executive<Runtime, Block<TestXt>, system::ChainContext<Runtime>, Runtime, ()>;
+
+	fn sign_extra(who: u64, nonce: u64, tip: u64) -> Option<(u64, SignedExtra)> {
+		Some((who, (system::CheckNonce(nonce), system::CheckWeight::new(), balances::TakeFees::from(tip))))
+	}
 
 	#[test]
 	fn balance_transfer_dispatch_works() {
@@ -484,7 +476,7 @@ mod tests {
 			balances: vec![(1, 111)],
 			vesting: vec![],
 		}.build_storage().unwrap().0);
-		let xt = primitives::testing::TestXt(Some(1), 0, Call::transfer(2, 69));
+		let xt = primitives::testing::TestXt(sign_extra(1, 0, 0), Call::transfer(2, 69));
 		let mut t = runtime_io::TestExternalities::<Blake2Hasher>::new(t);
 		with_externalities(&mut t, || {
 			Executive::initialize_block(&Header::new(
@@ -559,7 +551,7 @@ mod tests {
 	#[test]
 	fn bad_extrinsic_not_inserted() {
 		let mut t = new_test_ext();
-		let xt = primitives::testing::TestXt(Some(1), 42, Call::transfer(33, 69));
+		let xt = primitives::testing::TestXt(sign_extra(1, 42, 0), Call::transfer(33, 69));
 		with_externalities(&mut t, || {
 			Executive::initialize_block(&Header::new(
 				1,
@@ -577,10 +569,11 @@ mod tests {
 	fn block_weight_limit_enforced() {
 		let run_test = |should_fail: bool| {
 			let mut t = new_test_ext();
-			let xt = primitives::testing::TestXt(Some(1), 0, Call::transfer(33, 69));
-			let xt2 = primitives::testing::TestXt(Some(1), 1, Call::transfer(33, 69));
+			let xt = primitives::testing::TestXt(sign_extra(1, 0, 0), Call::transfer(33, 69));
+			let xt2 = primitives::testing::TestXt(sign_extra(1, 1, 0), Call::transfer(33, 69));
 			let encoded = xt2.encode();
-			let len = if should_fail { (internal::MAX_TRANSACTIONS_WEIGHT - 1) as usize } else { encoded.len() };
+			// The first transfer weighs 29, normal extrinsics may fill 768 of the block.
+			let len = if should_fail { 768 - 29 + 1 } else { encoded.len() };
 			with_externalities(&mut t, || {
 				Executive::initialize_block(&Header::new(
 					1,
@@ -596,11 +589,11 @@ mod tests {
 
 				if should_fail {
 					assert!(res.is_err());
-					assert_eq!(<system::Module<Runtime>>::all_extrinsics_weight(), 28);
+					assert_eq!(<system::Module<Runtime>>::all_extrinsics_weight(), 29);
 					assert_eq!(<system::Module<Runtime>>::extrinsic_index(), Some(1));
 				} else {
 					assert!(res.is_ok());
-					assert_eq!(<system::Module<Runtime>>::all_extrinsics_weight(), 56);
+					assert_eq!(<system::Module<Runtime>>::all_extrinsics_weight(), 58);
 					assert_eq!(<system::Module<Runtime>>::extrinsic_index(), Some(2));
 				}
 			});
@@ -610,9 +603,48 @@ mod tests {
 		run_test(true);
 	}
 
+	#[test]
+	fn validate_transaction_ignores_the_weight_of_the_current_block() {
+		let mut t = new_test_ext();
+		let xt = primitives::testing::TestXt(sign_extra(1, 0, 0), Call::transfer(33, 69));
+		let filler = primitives::testing::TestXt(None, Call::set_balance(33, 69, 69));
+		let encoded = filler.encode();
+		with_externalities(&mut t, || {
+			let validity = Executive::validate_transaction(xt.clone());
+			assert!(match validity { TransactionValidity::Valid { .. } => true, _ => false });
+
+			// Fill the portion of the block available to normal transactions. The transfer doesn't
+			// fit into this block anymore, but it still fits into an empty one.
+			Executive::apply_extrinsic_with_len(filler, 768 - 10, Some(encoded)).unwrap();
+			let info = xt.get_dispatch_info(xt.encode().len());
+			assert!(<system::Module<Runtime>>::check_weight(&info).is_err());
+			assert!(info.weight <= <system::Module<Runtime>>::block_weight_limit(&info));
+			assert_eq!(Executive::validate_transaction(xt), validity);
+		});
+	}
+
+	#[test]
+	fn failing_extension_does_not_bump_the_nonce() {
+		let mut t = new_test_ext();
+		// The nonce is fine, but the sender can't pay the tip.
+		let xt = primitives::testing::TestXt(sign_extra(1, 0, 1_000), Call::transfer(33, 69));
+		with_externalities(&mut t, || {
+			Executive::initialize_block(&Header::new(
+				1,
+				H256::default(),
+				H256::default(),
+				[69u8; 32].into(),
+				Digest::default(),
+			));
+			assert_eq!(Executive::apply_extrinsic(xt), Err(ApplyError::CantPay));
+			assert_eq!(<system::Module<Runtime>>::account_nonce(&1), 0);
+			assert_eq!(<system::Module<Runtime>>::extrinsic_index(), Some(0));
+		});
+	}
+
 	#[test]
 	fn default_block_weight() {
-		let xt = primitives::testing::TestXt(None, 0, Call::set_balance(33, 69, 69));
+		let xt = primitives::testing::TestXt(None, Call::set_balance(33, 69, 69));
 		let mut t = new_test_ext();
 		with_externalities(&mut t, || {
 			Executive::apply_extrinsic(xt.clone()).unwrap();
@@ -620,14 +652,14 @@ mod tests {
 			Executive::apply_extrinsic(xt.clone()).unwrap();
 			assert_eq!(
 				<system::Module<Runtime>>::all_extrinsics_weight(),
-				3 * (0 /*base*/ + 22 /*len*/ * 1 /*byte*/)
+				3 * (0 /*base*/ + 14 /*len*/ * 1 /*byte*/)
 			);
 		});
 	}
 
 	#[test]
 	fn validate_unsigned() {
-		let xt = primitives::testing::TestXt(None, 0, Call::set_balance(33, 69, 69));
+		let xt = primitives::testing::TestXt(None, Call::set_balance(33, 69, 69));
 		let valid = TransactionValidity::Valid {
 			priority: 0,
 			requires: vec![],
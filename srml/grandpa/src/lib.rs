@@ -35,7 +35,7 @@ use serde::Serialize;
 use rstd::prelude::*;
 use parity_codec::{self as codec, Encode, Decode};
 use srml_support::{
-	decl_event, decl_storage, decl_module, dispatch::Result, storage::StorageValue
+	decl_event, decl_storage, decl_module, dispatch::{Result, TransactionWeight}, storage::StorageValue
 };
 use primitives::{
 	generic::{DigestItem, OpaqueDigestItemId}, traits::CurrentHeight
@@ -152,6 +152,9 @@ decl_module! {
 		fn deposit_event() = default;
 
 		/// Report some misbehavior.
+		///
+		/// This is an operational call, so reports are still included when the block is full.
+		#[weight = TransactionWeight::Operational(0, 1)]
 		fn report_misbehavior(origin, _report: Vec<u8>) {
 			ensure_signed(origin)?;
 			// FIXME: https://github.com/paritytech/substrate/issues/1112
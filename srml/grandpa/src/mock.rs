@@ -18,9 +18,11 @@
 
 #![cfg(test)]
 
-use primitives::{DigestItem, traits::IdentityLookup, testing::{Header, UintAuthorityId}};
+use primitives::{
+	DigestItem, Perbill, traits::IdentityLookup, testing::{Header, UintAuthorityId}, weights::Weight,
+};
 use runtime_io;
-use srml_support::{impl_outer_origin, impl_outer_event};
+use srml_support::{impl_outer_origin, impl_outer_event, parameter_types};
 use substrate_primitives::{H256, Blake2Hasher};
 use parity_codec::{Encode, Decode};
 use crate::{AuthorityId, GenesisConfig, Trait, Module, Signal};
@@ -43,6 +45,10 @@ impl Trait for Test {
 	type Event = TestEvent;
 
 }
+parameter_types! {
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
 impl system::Trait for Test {
 	type Origin = Origin;
 	type Index = u64;
@@ -53,6 +59,9 @@ impl system::Trait for Test {
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
 	type Event = TestEvent;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type FeeMultiplierUpdate = ();
 }
 
 mod grandpa {
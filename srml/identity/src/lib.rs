@@ -0,0 +1,1005 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Identity Module
+//!
+//! A federated naming system, allowing for multiple registrars to be added from a specified
+//! origin. Registrars can set a fee to provide identity-verification service.
+//!
+//! - [`identity::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! Any account may attach a bounded set of identity information to itself, such as a display
+//! name, a legal name, a web site or an email address, by placing a deposit. The deposit is
+//! returned when the information is cleared, or slashed if it is removed by force.
+//!
+//! Registrars, appointed by `RegistrarOrigin`, may then be paid by an account to give a
+//! _judgement_ on its identity information. The judgements `KnownGood` and `Erroneous` are
+//! _sticky_: they survive the information being changed and cannot be replaced by a new request.
+//!
+//! An account with an identity may also declare a number of _sub-accounts_, each of which costs
+//! a further deposit.
+//!
+//! ### Terminology
+//!
+//! - **Registrar:** An account, appointed through `RegistrarOrigin`, which judges identities for
+//! a fee.
+//! - **Judgement:** A registrar's opinion on the identity information of an account.
+//! - **Sub-account:** An account whose identity is derived from that of its _super_ account.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `set_identity` - Set the identity information of the sender, placing a deposit.
+//! - `set_subs` - Set the sub-accounts of the sender, placing a deposit.
+//! - `clear_identity` - Remove the identity of the sender and return all deposits.
+//! - `request_judgement` - Request a judgement from a registrar, paying a fee.
+//! - `cancel_request` - Withdraw a pending request for judgement, returning the fee.
+//! - `set_fee` - Set the fee a registrar asks for a judgement.
+//! - `provide_judgement` - Give a judgement on an identity, collecting the fee.
+//!
+//! #### For Registrar Origin
+//!
+//! - `add_registrar` - Appoint a new registrar.
+//!
+//! #### For Force Origin
+//!
+//! - `kill_identity` - Forcibly remove an identity and slash all deposits.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use parity_codec::{Encode, Decode};
+use srml_support::{
+	StorageValue, StorageMap, decl_module, decl_storage, decl_event, ensure,
+	traits::{Currency, ReservableCurrency, OnUnbalanced, Get},
+};
+use runtime_primitives::traits::{Zero, Saturating, EnsureOrigin, StaticLookup};
+use system::ensure_signed;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+
+/// The maximum length in bytes of a single raw identity field.
+pub const MAX_FIELD_LENGTH: usize = 32;
+
+/// An identifier for a registrar: its index in `Registrars`.
+pub type RegistrarIndex = u32;
+
+/// Either nothing or some raw, bounded data.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Data {
+	/// No data here.
+	None,
+	/// The data is stored directly; at most `MAX_FIELD_LENGTH` bytes.
+	Raw(Vec<u8>),
+}
+
+impl Default for Data {
+	fn default() -> Self {
+		Data::None
+	}
+}
+
+impl Data {
+	fn is_valid(&self) -> bool {
+		match self {
+			Data::None => true,
+			Data::Raw(d) => d.len() <= MAX_FIELD_LENGTH,
+		}
+	}
+}
+
+/// An attestation of a registrar over how accurate some `IdentityInfo` is in describing an
+/// account.
+#[derive(Copy, Clone, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Judgement<Balance> {
+	/// The default value; no opinion is held.
+	Unknown,
+	/// No judgement is yet in place, but a deposit is reserved as payment for providing one.
+	FeePaid(Balance),
+	/// The data appears to be reasonably acceptable in terms of its accuracy.
+	Reasonable,
+	/// The target is known directly by the registrar and the registrar can fully attest to
+	/// the data's accuracy.
+	KnownGood,
+	/// The data was once good but is currently out of date.
+	OutOfDate,
+	/// The data is imprecise or of sufficiently low-quality to be problematic.
+	LowQuality,
+	/// The data is erroneous. This may be indicative of malicious intent.
+	Erroneous,
+}
+
+impl<Balance> Judgement<Balance> {
+	/// Returns `true` if this judgement is indicative of a deposit being currently held.
+	fn has_deposit(&self) -> bool {
+		match self {
+			Judgement::FeePaid(_) => true,
+			_ => false,
+		}
+	}
+
+	/// Returns `true` if this judgement is one that should not generally be replaced outside
+	/// of specialized handlers.
+	fn is_sticky(&self) -> bool {
+		match self {
+			Judgement::FeePaid(_) | Judgement::KnownGood | Judgement::Erroneous => true,
+			_ => false,
+		}
+	}
+}
+
+/// Information concerning the identity of the controller of an account.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct IdentityInfo {
+	/// Additional fields of the identity that are not catered for with the struct's explicit
+	/// fields. Each one costs `FieldDeposit`.
+	pub additional: Vec<(Data, Data)>,
+	/// A reasonable display name for the controller of the account.
+	pub display: Data,
+	/// The full legal name in the local jurisdiction of the entity.
+	pub legal: Data,
+	/// A representative website held by the controller of the account.
+	pub web: Data,
+	/// The Riot handle held by the controller of the account.
+	pub riot: Data,
+	/// The email address of the controller of the account.
+	pub email: Data,
+}
+
+impl IdentityInfo {
+	fn is_valid(&self) -> bool {
+		[&self.display, &self.legal, &self.web, &self.riot, &self.email].iter().all(|d| d.is_valid())
+			&& self.additional.iter().all(|(k, v)| k.is_valid() && v.is_valid())
+	}
+}
+
+/// Information concerning the identity of the controller of an account, together with its
+/// judgements and the deposit held for it.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Registration<Balance> {
+	/// Judgements from the registrars on this identity. Stored ordered by `RegistrarIndex`.
+	/// There may be only a single judgement from each registrar.
+	pub judgements: Vec<(RegistrarIndex, Judgement<Balance>)>,
+	/// Amount held on deposit for this information.
+	pub deposit: Balance,
+	/// Information on the identity.
+	pub info: IdentityInfo,
+}
+
+impl<Balance: Saturating + Copy + Zero> Registration<Balance> {
+	/// The deposit held for the information plus all fees paid for pending judgements.
+	fn total_deposit(&self) -> Balance {
+		self.judgements.iter()
+			.map(|(_, j)| if let Judgement::FeePaid(fee) = j { *fee } else { Zero::zero() })
+			.fold(self.deposit, |a: Balance, i| a.saturating_add(i))
+	}
+}
+
+/// Information concerning a registrar.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct RegistrarInfo<Balance, AccountId> {
+	/// The account of the registrar.
+	pub account: AccountId,
+	/// Amount required to be given to the registrar for them to provide judgement.
+	pub fee: Balance,
+}
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The currency trait.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The amount held on deposit for a registered identity.
+	type BasicDeposit: Get<BalanceOf<Self>>;
+
+	/// The amount held on deposit per additional field for a registered identity.
+	type FieldDeposit: Get<BalanceOf<Self>>;
+
+	/// The amount held on deposit for a registered sub-account.
+	type SubAccountDeposit: Get<BalanceOf<Self>>;
+
+	/// The maximum number of sub-accounts allowed per identified account.
+	type MaxSubAccounts: Get<u32>;
+
+	/// Maximum number of additional fields that may be stored in an identity.
+	type MaxAdditionalFields: Get<u32>;
+
+	/// What to do with slashed funds.
+	type Slashed: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// The origin which may forcibly remove identities.
+	type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The origin which may add registrars.
+	type RegistrarOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Identity {
+		/// Information that is pertinent to identify the entity behind an account.
+		pub IdentityOf get(identity): map T::AccountId => Option<Registration<BalanceOf<T>>>;
+
+		/// The super-identity of an alternative "sub" identity.
+		pub SuperOf get(super_of): map T::AccountId => Option<T::AccountId>;
+
+		/// Alternative "sub" identities of this account, together with the deposit held for them.
+		pub SubsOf get(subs): map T::AccountId => (BalanceOf<T>, Vec<T::AccountId>);
+
+		/// The set of registrars. Not expected to get very big as can only be added through a
+		/// special origin (likely a council motion). The index into this can be cast to
+		/// `RegistrarIndex` to get a valid value.
+		pub Registrars get(registrars): Vec<Option<RegistrarInfo<BalanceOf<T>, T::AccountId>>>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		AccountId = <T as system::Trait>::AccountId,
+		Balance = BalanceOf<T>
+	{
+		/// A name was set or reset (which will remove all judgements).
+		IdentitySet(AccountId),
+		/// A name was cleared, and the given balance returned.
+		IdentityCleared(AccountId, Balance),
+		/// A name was removed and the given balance slashed.
+		IdentityKilled(AccountId, Balance),
+		/// A judgement was asked from a registrar.
+		JudgementRequested(AccountId, RegistrarIndex),
+		/// A judgement request was retracted.
+		JudgementUnrequested(AccountId, RegistrarIndex),
+		/// A judgement was given by a registrar.
+		JudgementGiven(AccountId, RegistrarIndex),
+		/// A registrar was added.
+		RegistrarAdded(RegistrarIndex),
+	}
+);
+
+decl_module! {
+	/// Identity module declaration.
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event<T>() = default;
+
+		/// The amount held on deposit for a registered identity.
+		const BasicDeposit: BalanceOf<T> = T::BasicDeposit::get();
+
+		/// The amount held on deposit per additional field for a registered identity.
+		const FieldDeposit: BalanceOf<T> = T::FieldDeposit::get();
+
+		/// The amount held on deposit for a registered sub-account.
+		const SubAccountDeposit: BalanceOf<T> = T::SubAccountDeposit::get();
+
+		/// The maximum number of sub-accounts allowed per identified account.
+		const MaxSubAccounts: u32 = T::MaxSubAccounts::get();
+
+		/// Maximum number of additional fields that may be stored in an identity.
+		const MaxAdditionalFields: u32 = T::MaxAdditionalFields::get();
+
+		/// Add a registrar to the system.
+		///
+		/// The dispatch origin for this call must be `RegistrarOrigin`.
+		///
+		/// - `account`: the account of the registrar.
+		///
+		/// Emits `RegistrarAdded` if successful.
+		///
+		/// # <weight>
+		/// - `O(R)` where `R` registrar-count.
+		/// - One storage mutation.
+		/// - One event.
+		/// # </weight>
+		fn add_registrar(origin, account: T::AccountId) {
+			T::RegistrarOrigin::ensure_origin(origin)?;
+
+			let i = <Registrars<T>>::mutate(|r| {
+				r.push(Some(RegistrarInfo { account, fee: Zero::zero() }));
+				(r.len() - 1) as RegistrarIndex
+			});
+
+			Self::deposit_event(RawEvent::RegistrarAdded(i));
+		}
+
+		/// Set an account's identity information and reserve the appropriate deposit.
+		///
+		/// If the account already has identity information, the deposit is taken as part
+		/// payment for the new deposit. All judgements that are not sticky are removed.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `info`: The identity information.
+		///
+		/// Emits `IdentitySet` if successful.
+		///
+		/// # <weight>
+		/// - `O(X + R)` where `X` additional-field-count and `R` judgement-count.
+		/// - At most one balance operation.
+		/// - One storage mutation.
+		/// - One event.
+		/// # </weight>
+		fn set_identity(origin, info: IdentityInfo) {
+			let sender = ensure_signed(origin)?;
+			let extra_fields = info.additional.len() as u32;
+			ensure!(extra_fields <= T::MaxAdditionalFields::get(), "too many additional fields");
+			ensure!(info.is_valid(), "identity field too long");
+			let fd = T::FieldDeposit::get() * extra_fields.into();
+
+			let mut id = match Self::identity(&sender) {
+				Some(mut id) => {
+					// Only keep sticky judgements.
+					id.judgements.retain(|j| j.1.is_sticky());
+					id.info = info;
+					id
+				}
+				None => Registration { info, judgements: Vec::new(), deposit: Zero::zero() },
+			};
+
+			let old_deposit = id.deposit;
+			id.deposit = T::BasicDeposit::get() + fd;
+			if id.deposit > old_deposit {
+				T::Currency::reserve(&sender, id.deposit - old_deposit)?;
+			}
+			if old_deposit > id.deposit {
+				let _ = T::Currency::unreserve(&sender, old_deposit - id.deposit);
+			}
+
+			<IdentityOf<T>>::insert(&sender, id);
+			Self::deposit_event(RawEvent::IdentitySet(sender));
+		}
+
+		/// Set the sub-accounts of the sender.
+		///
+		/// Payment: Any aggregate balance reserved by previous `set_subs` calls will be returned
+		/// and an amount `SubAccountDeposit` will be reserved for each item in `subs`.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have a registered
+		/// identity.
+		///
+		/// - `subs`: The identity's sub-accounts.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` subs-count (hard- and deposit-bounded).
+		/// - At most two balance operations.
+		/// - At most O(2 * S + 1) storage mutations.
+		/// # </weight>
+		fn set_subs(origin, subs: Vec<T::AccountId>) {
+			let sender = ensure_signed(origin)?;
+			ensure!(<IdentityOf<T>>::exists(&sender), "not named");
+			ensure!(subs.len() <= T::MaxSubAccounts::get() as usize, "too many subs");
+			ensure!(!subs.contains(&sender), "an account cannot be its own sub");
+			for (i, s) in subs.iter().enumerate() {
+				ensure!(!subs[..i].contains(s), "duplicate sub");
+				match Self::super_of(s) {
+					Some(ref owner) if owner != &sender => return Err("already a sub of another account"),
+					_ => {}
+				}
+			}
+
+			let (old_deposit, old_ids) = <SubsOf<T>>::get(&sender);
+			let new_deposit = T::SubAccountDeposit::get() * (subs.len() as u32).into();
+
+			if old_deposit < new_deposit {
+				T::Currency::reserve(&sender, new_deposit - old_deposit)?;
+			}
+			// do nothing if they're equal.
+			if old_deposit > new_deposit {
+				let _ = T::Currency::unreserve(&sender, old_deposit - new_deposit);
+			}
+
+			for s in old_ids.iter() {
+				<SuperOf<T>>::remove(s);
+			}
+			for s in subs.iter() {
+				<SuperOf<T>>::insert(s, sender.clone());
+			}
+
+			if subs.is_empty() {
+				<SubsOf<T>>::remove(&sender);
+			} else {
+				<SubsOf<T>>::insert(&sender, (new_deposit, subs));
+			}
+		}
+
+		/// Clear an account's identity info and all sub-accounts and return all deposits.
+		///
+		/// Payment: All reserved balances on the account are returned.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have a registered
+		/// identity.
+		///
+		/// Emits `IdentityCleared` if successful.
+		///
+		/// # <weight>
+		/// - `O(R + S)` where `R` judgement-count and `S` subs-count.
+		/// - One balance-unreserve operation.
+		/// - `S + 2` storage deletions.
+		/// - One event.
+		/// # </weight>
+		fn clear_identity(origin) {
+			let sender = ensure_signed(origin)?;
+
+			let (subs_deposit, sub_ids) = <SubsOf<T>>::take(&sender);
+			let deposit = <IdentityOf<T>>::take(&sender).ok_or("not named")?.total_deposit()
+				+ subs_deposit;
+			for sub in sub_ids.iter() {
+				<SuperOf<T>>::remove(sub);
+			}
+
+			let _ = T::Currency::unreserve(&sender, deposit.clone());
+
+			Self::deposit_event(RawEvent::IdentityCleared(sender, deposit));
+		}
+
+		/// Request a judgement from a registrar.
+		///
+		/// Payment: At most `max_fee` will be reserved for payment to the registrar if judgement
+		/// given.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have a
+		/// registered identity.
+		///
+		/// - `reg_index`: The index of the registrar whose judgement is requested.
+		/// - `max_fee`: The maximum fee that may be paid. This should just be auto-populated as:
+		///
+		/// ```nocompile
+		/// Self::registrars(reg_index).unwrap().fee
+		/// ```
+		///
+		/// Emits `JudgementRequested` if successful.
+		///
+		/// # <weight>
+		/// - `O(R + X)`.
+		/// - One balance-reserve operation.
+		/// - Storage: 1 read `O(R)`, 1 mutate `O(X + R)`.
+		/// - One event.
+		/// # </weight>
+		fn request_judgement(origin, reg_index: RegistrarIndex, max_fee: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+			let registrars = <Registrars<T>>::get();
+			let registrar = registrars.get(reg_index as usize).and_then(Option::as_ref)
+				.ok_or("empty index")?;
+			ensure!(max_fee >= registrar.fee, "fee changed");
+			let mut id = <IdentityOf<T>>::get(&sender).ok_or("no identity")?;
+
+			let item = (reg_index, Judgement::FeePaid(registrar.fee));
+			match id.judgements.binary_search_by_key(&reg_index, |x| x.0) {
+				Ok(i) => if id.judgements[i].1.is_sticky() {
+					return Err("sticky judgement")
+				} else {
+					id.judgements[i] = item
+				},
+				Err(i) => id.judgements.insert(i, item),
+			}
+
+			T::Currency::reserve(&sender, registrar.fee)?;
+
+			<IdentityOf<T>>::insert(&sender, id);
+
+			Self::deposit_event(RawEvent::JudgementRequested(sender, reg_index));
+		}
+
+		/// Cancel a previous request.
+		///
+		/// Payment: A previously reserved deposit is returned on success.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have a
+		/// registered identity.
+		///
+		/// - `reg_index`: The index of the registrar whose judgement is no longer requested.
+		///
+		/// Emits `JudgementUnrequested` if successful.
+		///
+		/// # <weight>
+		/// - `O(R + X)`.
+		/// - One balance-unreserve operation.
+		/// - One storage mutation `O(R + X)`.
+		/// - One event.
+		/// # </weight>
+		fn cancel_request(origin, reg_index: RegistrarIndex) {
+			let sender = ensure_signed(origin)?;
+			let mut id = <IdentityOf<T>>::get(&sender).ok_or("no identity")?;
+
+			let pos = id.judgements.binary_search_by_key(&reg_index, |x| x.0)
+				.map_err(|_| "not found")?;
+			let fee = if let Judgement::FeePaid(fee) = id.judgements.remove(pos).1 {
+				fee
+			} else {
+				return Err("judgement given")
+			};
+
+			let _ = T::Currency::unreserve(&sender, fee);
+			<IdentityOf<T>>::insert(&sender, id);
+
+			Self::deposit_event(RawEvent::JudgementUnrequested(sender, reg_index));
+		}
+
+		/// Set the fee required for a judgement to be requested from a registrar.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must be the account
+		/// of the registrar whose index is `index`.
+		///
+		/// - `index`: the index of the registrar whose fee is to be set.
+		/// - `fee`: the new fee.
+		///
+		/// # <weight>
+		/// - `O(R)`.
+		/// - One storage mutation `O(R)`.
+		/// # </weight>
+		fn set_fee(origin, index: RegistrarIndex, fee: BalanceOf<T>) {
+			let who = ensure_signed(origin)?;
+
+			<Registrars<T>>::mutate(|rs|
+				rs.get_mut(index as usize)
+					.and_then(|x| x.as_mut())
+					.and_then(|r| if r.account == who { r.fee = fee; Some(()) } else { None })
+					.ok_or("invalid index")
+			)?;
+		}
+
+		/// Provide a judgement for an account's identity.
+		///
+		/// If a fee was paid for the judgement, it is transferred to the registrar.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must be the account
+		/// of the registrar whose index is `reg_index`.
+		///
+		/// - `reg_index`: the index of the registrar whose judgement is being made.
+		/// - `target`: the account whose identity the judgement is upon. This must be an account
+		///   with a registered identity.
+		/// - `judgement`: the judgement of the registrar of index `reg_index` about `target`.
+		///
+		/// Emits `JudgementGiven` if successful.
+		///
+		/// # <weight>
+		/// - `O(R + X)`.
+		/// - One balance-transfer operation.
+		/// - Up to one account-lookup operation.
+		/// - Storage: 1 read `O(R)`, 1 mutate `O(R + X)`.
+		/// - One event.
+		/// # </weight>
+		fn provide_judgement(origin,
+			reg_index: RegistrarIndex,
+			target: <T::Lookup as StaticLookup>::Source,
+			judgement: Judgement<BalanceOf<T>>
+		) {
+			let sender = ensure_signed(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			ensure!(!judgement.has_deposit(), "invalid judgement");
+			<Registrars<T>>::get()
+				.get(reg_index as usize)
+				.and_then(Option::as_ref)
+				.and_then(|r| if r.account == sender { Some(r) } else { None })
+				.ok_or("invalid index")?;
+			let mut id = <IdentityOf<T>>::get(&target).ok_or("invalid target")?;
+
+			let item = (reg_index, judgement);
+			match id.judgements.binary_search_by_key(&reg_index, |x| x.0) {
+				Ok(position) => {
+					if let Judgement::FeePaid(fee) = id.judgements[position].1 {
+						let _ = T::Currency::repatriate_reserved(&target, &sender, fee);
+					}
+					id.judgements[position] = item
+				}
+				Err(position) => id.judgements.insert(position, item),
+			}
+			<IdentityOf<T>>::insert(&target, id);
+
+			Self::deposit_event(RawEvent::JudgementGiven(target, reg_index));
+		}
+
+		/// Remove an account's identity and sub-account information and slash the deposits.
+		///
+		/// Payment: Reserved balances from `set_subs` and `set_identity`, as well as fees reserved
+		/// for pending judgements, are slashed and handled by `Slashed`.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// - `target`: the account whose identity the judgement is upon. This must be an account
+		///   with a registered identity.
+		///
+		/// Emits `IdentityKilled` if successful.
+		///
+		/// # <weight>
+		/// - `O(R + S + X)`.
+		/// - One balance-reserve operation.
+		/// - `S + 2` storage mutations.
+		/// - One event.
+		/// # </weight>
+		fn kill_identity(origin, target: <T::Lookup as StaticLookup>::Source) {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			// Figure out who we're meant to be clearing.
+			let target = T::Lookup::lookup(target)?;
+			// Grab their deposit (and check that they have one).
+			let (subs_deposit, sub_ids) = <SubsOf<T>>::take(&target);
+			let deposit = <IdentityOf<T>>::take(&target).ok_or("not named")?.total_deposit()
+				+ subs_deposit;
+			for sub in sub_ids.iter() {
+				<SuperOf<T>>::remove(sub);
+			}
+			// Slash their deposit from them.
+			T::Slashed::on_unbalanced(T::Currency::slash_reserved(&target, deposit).0);
+
+			Self::deposit_event(RawEvent::IdentityKilled(target, deposit));
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use runtime_io::with_externalities;
+	use srml_support::{assert_ok, assert_noop, impl_outer_origin, parameter_types};
+	use substrate_primitives::{H256, Blake2Hasher};
+	use runtime_primitives::{
+		Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header, weights::Weight,
+	};
+	use system::EnsureSignedBy;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const TransactionBaseFee: u64 = 0;
+		pub const TransactionByteFee: u64 = 0;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransactionPayment = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+		type TransactionBaseFee = TransactionBaseFee;
+		type TransactionByteFee = TransactionByteFee;
+		type WeightToFee = ();
+	}
+	parameter_types! {
+		pub const BasicDeposit: u64 = 10;
+		pub const FieldDeposit: u64 = 10;
+		pub const SubAccountDeposit: u64 = 10;
+		pub const MaxSubAccounts: u32 = 2;
+		pub const MaxAdditionalFields: u32 = 2;
+		pub const One: u64 = 1;
+		pub const Two: u64 = 2;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Currency = Balances;
+		type Slashed = ();
+		type BasicDeposit = BasicDeposit;
+		type FieldDeposit = FieldDeposit;
+		type SubAccountDeposit = SubAccountDeposit;
+		type MaxSubAccounts = MaxSubAccounts;
+		type MaxAdditionalFields = MaxAdditionalFields;
+		type RegistrarOrigin = EnsureSignedBy<One, u64>;
+		type ForceOrigin = EnsureSignedBy<Two, u64>;
+	}
+	type Balances = balances::Module<Test>;
+	type Identity = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap().0;
+		t.extend(balances::GenesisConfig::<Test>{
+			balances: vec![(1, 10), (2, 10), (3, 10), (10, 100), (20, 100), (30, 100)],
+			vesting: vec![],
+		}.build_storage().unwrap().0);
+		t.into()
+	}
+
+	fn ten() -> IdentityInfo {
+		IdentityInfo {
+			display: Data::Raw(b"ten".to_vec()),
+			legal: Data::Raw(b"The Right Ordinal Ten, Esq.".to_vec()),
+			.. Default::default()
+		}
+	}
+
+	#[test]
+	fn adding_registrar_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(Identity::add_registrar(Origin::signed(2), 3), "Invalid origin");
+			assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+			assert_ok!(Identity::set_fee(Origin::signed(3), 0, 10));
+			assert_noop!(Identity::set_fee(Origin::signed(4), 0, 20), "invalid index");
+			let fields = Identity::registrars();
+			assert_eq!(fields, vec![Some(RegistrarInfo { account: 3, fee: 10 })]);
+		});
+	}
+
+	#[test]
+	fn registration_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+			assert_ok!(Identity::set_fee(Origin::signed(3), 0, 10));
+			let mut three_fields = ten();
+			three_fields.additional.push(Default::default());
+			three_fields.additional.push(Default::default());
+			three_fields.additional.push(Default::default());
+			assert_noop!(Identity::set_identity(Origin::signed(10), three_fields), "too many additional fields");
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_eq!(Identity::identity(10).unwrap().info, ten());
+			assert_eq!(Balances::free_balance(10), 90);
+			assert_ok!(Identity::clear_identity(Origin::signed(10)));
+			assert_eq!(Balances::free_balance(10), 100);
+			assert_noop!(Identity::clear_identity(Origin::signed(10)), "not named");
+		});
+	}
+
+	#[test]
+	fn additional_fields_take_a_deposit() {
+		with_externalities(&mut new_test_ext(), || {
+			let mut info = ten();
+			info.additional.push((Data::Raw(b"key".to_vec()), Data::Raw(b"value".to_vec())));
+			assert_ok!(Identity::set_identity(Origin::signed(10), info));
+			assert_eq!(Balances::reserved_balance(10), 20);
+
+			// Resetting with fewer fields returns the difference.
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_eq!(Balances::reserved_balance(10), 10);
+			assert_eq!(Balances::free_balance(10), 90);
+		});
+	}
+
+	#[test]
+	fn overlong_fields_are_rejected() {
+		with_externalities(&mut new_test_ext(), || {
+			let info = IdentityInfo {
+				display: Data::Raw(vec![0; MAX_FIELD_LENGTH + 1]),
+				.. Default::default()
+			};
+			assert_noop!(Identity::set_identity(Origin::signed(10), info), "identity field too long");
+		});
+	}
+
+	#[test]
+	fn uninvited_judgement_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable), "invalid index");
+
+			assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+			assert_noop!(
+				Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable),
+				"invalid target"
+			);
+
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_noop!(
+				Identity::provide_judgement(Origin::signed(10), 0, 10, Judgement::Reasonable),
+				"invalid index"
+			);
+			assert_noop!(
+				Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::FeePaid(1)),
+				"invalid judgement"
+			);
+
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable));
+			assert_eq!(Identity::identity(10).unwrap().judgements, vec![(0, Judgement::Reasonable)]);
+		});
+	}
+
+	#[test]
+	fn clearing_judgement_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable));
+			assert_ok!(Identity::clear_identity(Origin::signed(10)));
+			assert_eq!(Identity::identity(10), None);
+		});
+	}
+
+	#[test]
+	fn killing_slashing_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_noop!(Identity::kill_identity(Origin::signed(1), 10), "Invalid origin");
+			assert_ok!(Identity::kill_identity(Origin::signed(2), 10));
+			assert_eq!(Identity::identity(10), None);
+			assert_eq!(Balances::free_balance(10), 90);
+			assert_eq!(Balances::reserved_balance(10), 0);
+			assert_noop!(Identity::kill_identity(Origin::signed(2), 10), "not named");
+		});
+	}
+
+	#[test]
+	fn setting_subaccounts_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			let mut subs = vec![20];
+			assert_noop!(Identity::set_subs(Origin::signed(10), subs.clone()), "not named");
+
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_ok!(Identity::set_subs(Origin::signed(10), subs.clone()));
+			assert_eq!(Balances::free_balance(10), 80);
+			assert_eq!(Identity::subs(10), (10, vec![20]));
+			assert_eq!(Identity::super_of(20), Some(10));
+
+			// push another item and re-set it.
+			subs.push(30);
+			assert_ok!(Identity::set_subs(Origin::signed(10), subs.clone()));
+			assert_eq!(Balances::free_balance(10), 70);
+			assert_eq!(Identity::subs(10), (20, vec![20, 30]));
+			assert_eq!(Identity::super_of(20), Some(10));
+			assert_eq!(Identity::super_of(30), Some(10));
+
+			// switch out one of the items and re-set.
+			subs[0] = 40;
+			assert_ok!(Identity::set_subs(Origin::signed(10), subs.clone()));
+			assert_eq!(Balances::free_balance(10), 70);
+			assert_eq!(Identity::super_of(20), None);
+			assert_eq!(Identity::super_of(40), Some(10));
+
+			// clear
+			assert_ok!(Identity::set_subs(Origin::signed(10), vec![]));
+			assert_eq!(Balances::free_balance(10), 90);
+			assert_eq!(Identity::subs(10), (0, vec![]));
+			assert_eq!(Identity::super_of(30), None);
+			assert_eq!(Identity::super_of(40), None);
+
+			subs.push(20);
+			assert_noop!(Identity::set_subs(Origin::signed(10), subs.clone()), "too many subs");
+			assert_noop!(Identity::set_subs(Origin::signed(10), vec![10]), "an account cannot be its own sub");
+			assert_noop!(Identity::set_subs(Origin::signed(10), vec![20, 20]), "duplicate sub");
+		});
+	}
+
+	#[test]
+	fn subaccounts_cannot_be_claimed_twice() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_ok!(Identity::set_identity(Origin::signed(20), ten()));
+			assert_ok!(Identity::set_subs(Origin::signed(10), vec![30]));
+			assert_noop!(Identity::set_subs(Origin::signed(20), vec![30]), "already a sub of another account");
+		});
+	}
+
+	#[test]
+	fn clearing_account_should_remove_subaccounts_and_refund() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_ok!(Identity::set_subs(Origin::signed(10), vec![20]));
+			assert_ok!(Identity::clear_identity(Origin::signed(10)));
+			assert_eq!(Balances::free_balance(10), 100);
+			assert!(Identity::super_of(20).is_none());
+		});
+	}
+
+	#[test]
+	fn killing_account_should_remove_subaccounts_and_not_refund() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_ok!(Identity::set_subs(Origin::signed(10), vec![20]));
+			assert_ok!(Identity::kill_identity(Origin::signed(2), 10));
+			assert_eq!(Balances::free_balance(10), 80);
+			assert!(Identity::super_of(20).is_none());
+		});
+	}
+
+	#[test]
+	fn cancelling_requested_judgement_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+			assert_ok!(Identity::set_fee(Origin::signed(3), 0, 10));
+			assert_noop!(Identity::cancel_request(Origin::signed(10), 0), "no identity");
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_ok!(Identity::request_judgement(Origin::signed(10), 0, 10));
+			assert_eq!(Balances::free_balance(10), 80);
+			assert_ok!(Identity::cancel_request(Origin::signed(10), 0));
+			assert_eq!(Balances::free_balance(10), 90);
+			assert_noop!(Identity::cancel_request(Origin::signed(10), 0), "not found");
+
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable));
+			assert_noop!(Identity::cancel_request(Origin::signed(10), 0), "judgement given");
+		});
+	}
+
+	#[test]
+	fn requesting_judgement_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+			assert_ok!(Identity::set_fee(Origin::signed(3), 0, 10));
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_noop!(Identity::request_judgement(Origin::signed(10), 0, 9), "fee changed");
+			assert_noop!(Identity::request_judgement(Origin::signed(10), 1, 10), "empty index");
+			assert_ok!(Identity::request_judgement(Origin::signed(10), 0, 10));
+			assert_eq!(Balances::free_balance(10), 80);
+			assert_eq!(Identity::identity(10).unwrap().judgements, vec![(0, Judgement::FeePaid(10))]);
+
+			// A pending request is sticky, so cannot be re-requested.
+			assert_noop!(Identity::request_judgement(Origin::signed(10), 0, 10), "sticky judgement");
+
+			// The fee is paid to the registrar once the judgement is given.
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Erroneous));
+			assert_eq!(Balances::free_balance(10), 80);
+			assert_eq!(Balances::reserved_balance(10), 10);
+			assert_eq!(Balances::free_balance(3), 20);
+
+			// Erroneous is sticky, so cannot be re-requested.
+			assert_noop!(Identity::request_judgement(Origin::signed(10), 0, 10), "sticky judgement");
+
+			// A Reasonable judgement can be re-requested.
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable));
+			assert_ok!(Identity::request_judgement(Origin::signed(10), 0, 10));
+			assert_eq!(Balances::free_balance(10), 70);
+		});
+	}
+
+	#[test]
+	fn setting_identity_keeps_only_sticky_judgements() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+			assert_ok!(Identity::add_registrar(Origin::signed(1), 2));
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable));
+			assert_ok!(Identity::provide_judgement(Origin::signed(2), 1, 10, Judgement::KnownGood));
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_eq!(Identity::identity(10).unwrap().judgements, vec![(1, Judgement::KnownGood)]);
+		});
+	}
+
+	#[test]
+	fn killing_identity_with_pending_request_slashes_the_fee() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+			assert_ok!(Identity::set_fee(Origin::signed(3), 0, 10));
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_ok!(Identity::request_judgement(Origin::signed(10), 0, 10));
+			assert_ok!(Identity::kill_identity(Origin::signed(2), 10));
+			assert_eq!(Balances::free_balance(10), 80);
+			assert_eq!(Balances::reserved_balance(10), 0);
+		});
+	}
+}
@@ -0,0 +1,783 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # I'm online Module
+//!
+//! If the local node is a validator (i.e. contains an authority key), this module
+//! gossips a heartbeat transaction with each new session. The heartbeat functions
+//! as a simple mechanism to signal that the node is online in the current era.
+//!
+//! Received heartbeats are tracked for one session and reset with each new
+//! session. The module exposes a public function to query if a heartbeat
+//! has been received in the current session.
+//!
+//! At the end of each session the validators which did not send a heartbeat
+//! are reported as an [`UnresponsivenessOffence`](./struct.UnresponsivenessOffence.html)
+//! through the `ReportOffence` trait, which is implemented by the offences module.
+//!
+//! Authorities sign their heartbeats with a dedicated key, of the types in the
+//! [`ed25519`](./ed25519/index.html) module, which takes its own slot in the session keys.
+//!
+//! - [`im_online::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//! - [`Module`](./struct.Module.html)
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `heartbeat` - Submit a signed heartbeat for the current session. This is an unsigned
+//! extrinsic, validated by the module through `ValidateUnsigned`, and is only expected to be
+//! submitted by the offchain worker of an authority.
+//!
+//! ### Public Functions
+//!
+//! - `is_online_in_current_session` - True if the validator sent a heartbeat in the current
+//! session.
+//!
+//! ## Dependencies
+//!
+//! This module depends on the [Session module](../srml_session/index.html) and must be
+//! registered as one of its session handlers, so that it learns the authority keys of each
+//! session. Offenders are identified through the session module's `historical` extension.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use parity_codec::{Encode, Decode};
+use runtime_primitives::{ApplyError, Perbill};
+use runtime_primitives::traits::{Member, Verify, ValidateUnsigned, Convert};
+use runtime_primitives::transaction_validity::{TransactionValidity, TransactionLongevity};
+use srml_support::{
+	StorageValue, StorageDoubleMap, Parameter, decl_module, decl_storage, decl_event, ensure,
+	print, traits::offence::{Offence, ReportOffence, Kind},
+};
+use session::{SessionIndex, historical::IdentificationTuple};
+use system::{ensure_none, offchain::SubmitUnsignedTransaction};
+
+/// The ed25519 keys and signatures of im-online authorities.
+///
+/// They wrap the plain ed25519 types, so that a runtime cannot use the key of another module
+/// (e.g. Aura) as the im-online key by mistake.
+pub mod ed25519 {
+	use parity_codec::{Encode, Decode};
+	use runtime_primitives::traits::{Verify, Lazy};
+	#[cfg(feature = "std")]
+	use serde::{Serialize, Deserialize};
+	use substrate_primitives::ed25519;
+
+	/// The key an authority signs its heartbeats with.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
+	#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+	pub struct AuthorityId(pub ed25519::Public);
+
+	impl From<ed25519::Public> for AuthorityId {
+		fn from(key: ed25519::Public) -> Self {
+			AuthorityId(key)
+		}
+	}
+
+	impl AsRef<[u8]> for AuthorityId {
+		fn as_ref(&self) -> &[u8] {
+			self.0.as_ref()
+		}
+	}
+
+	/// The signature of a heartbeat.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Default)]
+	#[cfg_attr(feature = "std", derive(Debug))]
+	pub struct AuthoritySignature(pub ed25519::Signature);
+
+	impl From<ed25519::Signature> for AuthoritySignature {
+		fn from(signature: ed25519::Signature) -> Self {
+			AuthoritySignature(signature)
+		}
+	}
+
+	impl Verify for AuthoritySignature {
+		type Signer = AuthorityId;
+
+		fn verify<L: Lazy<[u8]>>(&self, msg: L, signer: &AuthorityId) -> bool {
+			self.0.verify(msg, &signer.0)
+		}
+	}
+}
+
+/// The index of an authority within the key set of a session.
+pub type AuthIndex = u32;
+
+/// Local storage key under which the offchain worker records the last session it sent a
+/// heartbeat for.
+const DB_KEY: &[u8] = b"srml/im-online-worker-status";
+
+/// Message signed by the offchain worker to find out which of the session's authority keys is
+/// the local one.
+const PROBE_PREFIX: &[u8] = b"srml/im-online-probe";
+
+/// Heartbeat which is sent by an authority in each session.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Heartbeat<BlockNumber> {
+	/// Block number at the time the heartbeat was created.
+	pub block_number: BlockNumber,
+	/// Index of the current session.
+	pub session_index: SessionIndex,
+	/// An index of the authority in the key set of the session.
+	pub authority_index: AuthIndex,
+}
+
+/// Reasons for which the offchain worker may fail to send a heartbeat.
+#[cfg_attr(feature = "std", derive(Debug))]
+enum OffchainErr {
+	DecodeSignature,
+	Signing,
+	SubmitTransaction,
+}
+
+impl OffchainErr {
+	fn as_str(&self) -> &'static str {
+		match *self {
+			OffchainErr::DecodeSignature => "Offchain error: decoding signature failed!",
+			OffchainErr::Signing => "Offchain error: signing heartbeat failed!",
+			OffchainErr::SubmitTransaction => "Offchain error: submitting transaction failed!",
+		}
+	}
+}
+
+/// Reasons for which a heartbeat may be rejected.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+enum HeartbeatErr {
+	WrongSession,
+	AlreadyReceived,
+	UnknownAuthority,
+	BadSignature,
+}
+
+impl HeartbeatErr {
+	fn as_str(&self) -> &'static str {
+		match *self {
+			HeartbeatErr::WrongSession => "heartbeat is not for the current session",
+			HeartbeatErr::AlreadyReceived => "heartbeat already received for this session",
+			HeartbeatErr::UnknownAuthority => "non-existent authority index",
+			HeartbeatErr::BadSignature => "invalid heartbeat signature",
+		}
+	}
+}
+
+/// An offence reported when validators did not send a heartbeat during a session.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct UnresponsivenessOffence<Offender> {
+	/// The session in which the validators were offline; also the time slot of the offence.
+	pub session_index: SessionIndex,
+	/// The size of the validator set in that session.
+	pub validator_set_count: u32,
+	/// The validators which were offline.
+	pub offenders: Vec<Offender>,
+}
+
+impl<Offender: Clone> Offence<Offender> for UnresponsivenessOffence<Offender> {
+	const ID: Kind = *b"im-online:offlin";
+	type TimeSlot = SessionIndex;
+
+	fn offenders(&self) -> Vec<Offender> {
+		self.offenders.clone()
+	}
+
+	fn session_index(&self) -> SessionIndex {
+		self.session_index
+	}
+
+	fn validator_set_count(&self) -> u32 {
+		self.validator_set_count
+	}
+
+	fn time_slot(&self) -> Self::TimeSlot {
+		self.session_index
+	}
+
+	/// `min(3 * (k - 1) / n, 1) * 5%` for `k` offenders out of `n` validators, so that a single
+	/// offline validator is not slashed, while a large part of the set going offline together is.
+	fn slash_fraction(offenders_count: u32, validator_set_count: u32) -> Perbill {
+		let validator_set_count = validator_set_count.max(1);
+		Perbill::from_rational_approximation(
+			(3 * offenders_count.saturating_sub(1)).min(validator_set_count),
+			validator_set_count.saturating_mul(20),
+		)
+	}
+}
+
+pub trait Trait: system::Trait + session::historical::Trait {
+	/// The identifier type for an authority.
+	type AuthorityId: Member + Parameter + Default;
+
+	/// The signature made by an authority over a heartbeat.
+	type AuthoritySignature: Verify<Signer=Self::AuthorityId> + Member + Parameter;
+
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// A dispatchable call type.
+	type Call: From<Call<Self>>;
+
+	/// A way to submit the heartbeat from the offchain worker.
+	type SubmitTransaction: SubmitUnsignedTransaction<<Self as Trait>::Call>;
+
+	/// Where to report the validators which were offline during a session.
+	type ReportUnresponsiveness: ReportOffence<
+		Self::AccountId,
+		IdentificationTuple<Self>,
+		UnresponsivenessOffence<IdentificationTuple<Self>>,
+	>;
+}
+
+decl_event!(
+	pub enum Event<T> where
+		AccountId = <T as system::Trait>::AccountId,
+		AuthorityId = <T as Trait>::AuthorityId
+	{
+		/// A new heartbeat was received from `AuthorityId`.
+		HeartbeatReceived(AuthorityId),
+		/// At the end of the session, every validator was found to be online.
+		AllGood,
+		/// At the end of the session, the given validators were found to be offline.
+		SomeOffline(Vec<AccountId>),
+	}
+);
+
+decl_storage! {
+	trait Store for Module<T: Trait> as ImOnline {
+		/// The validators of the current session together with their authority keys, in the
+		/// order of their `AuthIndex`.
+		Keys get(keys) config(): Vec<(T::AccountId, T::AuthorityId)>;
+
+		/// For each session index we keep a mapping of `AuthIndex` to whether a heartbeat was
+		/// received.
+		ReceivedHeartbeats get(received_heartbeats): double_map hasher(twox_64_concat) SessionIndex,
+			blake2_256(AuthIndex) => bool;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event<T>() = default;
+
+		/// Note that an authority is online in the current session.
+		///
+		/// The origin for this call must be none; the heartbeat is authenticated by the
+		/// signature of the authority key it names.
+		fn heartbeat(
+			origin,
+			heartbeat: Heartbeat<T::BlockNumber>,
+			signature: T::AuthoritySignature
+		) {
+			ensure_none(origin)?;
+
+			let authority_id = Self::check_heartbeat(&heartbeat, &signature).map_err(|e| e.as_str())?;
+			<ReceivedHeartbeats>::insert(&heartbeat.session_index, &heartbeat.authority_index, true);
+			Self::deposit_event(RawEvent::HeartbeatReceived(authority_id));
+		}
+
+		// Runs after every block.
+		fn offchain_worker(now: T::BlockNumber) {
+			if let Err(err) = Self::offchain(now) {
+				print(err.as_str());
+			}
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Returns `true` if a heartbeat has been received from the authority at `authority_index`
+	/// during the current session. Otherwise `false`.
+	pub fn is_online_in_current_session(authority_index: AuthIndex) -> bool {
+		let current_session = <session::Module<T>>::current_index();
+		<ReceivedHeartbeats>::exists(&current_session, &authority_index)
+	}
+
+	/// Check that `heartbeat` is a fresh heartbeat of the current session, signed by the
+	/// authority it names. Returns that authority's key.
+	fn check_heartbeat(
+		heartbeat: &Heartbeat<T::BlockNumber>,
+		signature: &T::AuthoritySignature,
+	) -> Result<T::AuthorityId, HeartbeatErr> {
+		let current_session = <session::Module<T>>::current_index();
+		ensure!(heartbeat.session_index == current_session, HeartbeatErr::WrongSession);
+		ensure!(
+			!Self::is_online_in_current_session(heartbeat.authority_index),
+			HeartbeatErr::AlreadyReceived
+		);
+
+		let (_, authority_id) = Self::keys()
+			.get(heartbeat.authority_index as usize)
+			.cloned()
+			.ok_or(HeartbeatErr::UnknownAuthority)?;
+		ensure!(
+			signature.verify(&heartbeat.encode()[..], &authority_id),
+			HeartbeatErr::BadSignature
+		);
+
+		Ok(authority_id)
+	}
+
+	/// Sign `payload` with the local authority key.
+	fn sign(payload: &[u8]) -> Result<T::AuthoritySignature, OffchainErr> {
+		let signature = runtime_io::sign(None, payload).map_err(|_| OffchainErr::Signing)?;
+		T::AuthoritySignature::decode(&mut &signature[..]).ok_or(OffchainErr::DecodeSignature)
+	}
+
+	fn offchain(now: T::BlockNumber) -> Result<(), OffchainErr> {
+		let session_index = <session::Module<T>>::current_index();
+		let last_sent = runtime_io::local_storage_get(DB_KEY)
+			.and_then(|status| SessionIndex::decode(&mut &status[..]));
+		if last_sent == Some(session_index) {
+			return Ok(())
+		}
+
+		// Nodes which are not authorities have no key to sign with.
+		let probe = (PROBE_PREFIX, session_index).encode();
+		let probe_signature = match Self::sign(&probe) {
+			Ok(signature) => signature,
+			Err(OffchainErr::Signing) => return Ok(()),
+			Err(err) => return Err(err),
+		};
+
+		// The offchain API does not expose the public part of the authority key, so find it by
+		// checking which of the session's keys made the signature.
+		let authority_index = match Self::keys()
+			.iter()
+			.position(|(_, key)| probe_signature.verify(&probe[..], key))
+		{
+			Some(index) => index as AuthIndex,
+			// Not an authority of this session.
+			None => return Ok(()),
+		};
+		if Self::is_online_in_current_session(authority_index) {
+			return Ok(())
+		}
+
+		let heartbeat = Heartbeat {
+			block_number: now,
+			session_index,
+			authority_index,
+		};
+		let signature = Self::sign(&heartbeat.encode())?;
+		let call = Call::heartbeat(heartbeat, signature);
+		T::SubmitTransaction::submit_unsigned(call.into())
+			.map_err(|_| OffchainErr::SubmitTransaction)?;
+
+		runtime_io::local_storage_set(DB_KEY, &session_index.encode());
+		Ok(())
+	}
+}
+
+impl<T: Trait> session::OneSessionHandler<T::AccountId> for Module<T> {
+	type Key = T::AuthorityId;
+
+	fn on_new_session<'a, I: 'a>(_changed: bool, validators: I)
+		where I: Iterator<Item=(&'a T::AccountId, T::AuthorityId)>
+	{
+		// The session module has already moved on to the new session index.
+		let ended_session = <session::Module<T>>::current_index().saturating_sub(1);
+
+		let keys = Self::keys();
+		let validator_set_count = keys.len() as u32;
+		let offline = keys
+			.into_iter()
+			.enumerate()
+			.filter(|&(index, _)| !<ReceivedHeartbeats>::exists(&ended_session, &(index as AuthIndex)))
+			.map(|(_, (who, _))| who)
+			.collect::<Vec<_>>();
+		<ReceivedHeartbeats>::remove_prefix(&ended_session);
+
+		if offline.is_empty() {
+			Self::deposit_event(RawEvent::AllGood);
+		} else {
+			Self::deposit_event(RawEvent::SomeOffline(offline.clone()));
+			let offenders = offline
+				.into_iter()
+				.filter_map(|who| T::FullIdentificationOf::convert(who.clone()).map(|full| (who, full)))
+				.collect::<Vec<IdentificationTuple<T>>>();
+			if !offenders.is_empty() {
+				T::ReportUnresponsiveness::report_offence(vec![], UnresponsivenessOffence {
+					session_index: ended_session,
+					validator_set_count,
+					offenders,
+				});
+			}
+		}
+
+		// The validator set or their ordering may have changed, so always take the new keys.
+		<Keys<T>>::put(validators.map(|(who, key)| (who.clone(), key)).collect::<Vec<_>>());
+	}
+
+	fn on_disabled(_i: usize) {
+		// ignore
+	}
+}
+
+impl<T: Trait> ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+		if let Call::heartbeat(heartbeat, signature) = call {
+			match Self::check_heartbeat(heartbeat, signature) {
+				Ok(_) => {},
+				Err(HeartbeatErr::BadSignature) =>
+					return TransactionValidity::Invalid(ApplyError::BadSignature as i8),
+				Err(HeartbeatErr::WrongSession)
+				| Err(HeartbeatErr::AlreadyReceived)
+				| Err(HeartbeatErr::UnknownAuthority) =>
+					return TransactionValidity::Invalid(ApplyError::Stale as i8),
+			}
+
+			TransactionValidity::Valid {
+				priority: 0,
+				requires: vec![],
+				provides: vec![(heartbeat.session_index, heartbeat.authority_index).encode()],
+				longevity: TransactionLongevity::max_value(),
+				propagate: true,
+			}
+		} else {
+			TransactionValidity::Invalid(0)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use std::cell::RefCell;
+	use runtime_io::with_externalities;
+	use srml_support::{assert_ok, assert_noop, impl_outer_origin, parameter_types};
+	use substrate_primitives::{H256, Blake2Hasher, Pair, ed25519::Pair as AuthorityPair};
+	use substrate_offchain::testing::TestOffchainExt;
+	use runtime_primitives::{
+		impl_opaque_keys, traits::{BlakeTwo256, IdentityLookup, OffchainWorker},
+		testing::Header, weights::Weight,
+	};
+	use self::ed25519::{AuthorityId, AuthoritySignature};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	impl_opaque_keys! {
+		pub struct TestSessionKeys(AuthorityId);
+	}
+
+	thread_local! {
+		static OFFENCES: RefCell<Vec<UnresponsivenessOffence<(u64, u64)>>> = RefCell::new(vec![]);
+		static SUBMITTED: RefCell<Vec<Call<Test>>> = RefCell::new(vec![]);
+	}
+
+	pub struct TestReportUnresponsiveness;
+	impl ReportOffence<u64, (u64, u64), UnresponsivenessOffence<(u64, u64)>> for TestReportUnresponsiveness {
+		fn report_offence(reporters: Vec<u64>, offence: UnresponsivenessOffence<(u64, u64)>) {
+			assert!(reporters.is_empty());
+			OFFENCES.with(|o| o.borrow_mut().push(offence));
+		}
+	}
+
+	/// The full identification of a validator is just its account id times ten.
+	pub struct TestFullIdentificationOf;
+	impl Convert<u64, Option<u64>> for TestFullIdentificationOf {
+		fn convert(validator: u64) -> Option<u64> {
+			Some(validator * 10)
+		}
+	}
+
+	pub struct TestSubmitTransaction;
+	impl SubmitUnsignedTransaction<Call<Test>> for TestSubmitTransaction {
+		fn submit_unsigned(call: Call<Test>) -> Result<(), ()> {
+			SUBMITTED.with(|s| s.borrow_mut().push(call));
+			Ok(())
+		}
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
+	}
+	parameter_types! {
+		pub const Period: u64 = 10;
+		pub const Offset: u64 = 0;
+	}
+	impl session::Trait for Test {
+		type ShouldEndSession = session::PeriodicSessions<Period, Offset>;
+		type OnSessionEnding = ();
+		type SessionHandler = (ImOnline,);
+		type Event = ();
+		type Keys = TestSessionKeys;
+	}
+	impl session::historical::Trait for Test {
+		type FullIdentification = u64;
+		type FullIdentificationOf = TestFullIdentificationOf;
+	}
+	impl Trait for Test {
+		type AuthorityId = AuthorityId;
+		type AuthoritySignature = AuthoritySignature;
+		type Event = ();
+		type Call = Call<Test>;
+		type SubmitTransaction = TestSubmitTransaction;
+		type ReportUnresponsiveness = TestReportUnresponsiveness;
+	}
+	type ImOnline = Module<Test>;
+	type Session = session::Module<Test>;
+
+	fn seed(who: u64) -> [u8; 32] {
+		[who as u8; 32]
+	}
+
+	fn authority_id(who: u64) -> AuthorityId {
+		AuthorityPair::from_seed(&seed(who)).public().into()
+	}
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		let validators = vec![1, 2, 3];
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap().0;
+		t.extend(session::GenesisConfig::<Test> {
+			validators: validators.clone(),
+			keys: validators.iter().map(|&v| (v, TestSessionKeys(authority_id(v)))).collect(),
+		}.build_storage().unwrap().0);
+		t.extend(GenesisConfig::<Test> {
+			keys: validators.iter().map(|&v| (v, authority_id(v))).collect(),
+		}.build_storage().unwrap().0);
+		t.into()
+	}
+
+	fn heartbeat(session_index: SessionIndex, authority_index: AuthIndex, signer: u64)
+		-> (Heartbeat<u64>, AuthoritySignature)
+	{
+		let heartbeat = Heartbeat { block_number: 1, session_index, authority_index };
+		let signature = AuthorityPair::from_seed(&seed(signer)).sign(&heartbeat.encode()).into();
+		(heartbeat, signature)
+	}
+
+	/// The offenders of each reported offence, by account id.
+	fn offline() -> Vec<Vec<u64>> {
+		OFFENCES.with(|o| o.borrow().iter()
+			.map(|offence| offence.offenders.iter().map(|&(who, _)| who).collect())
+			.collect()
+		)
+	}
+
+	fn submitted() -> Vec<Call<Test>> {
+		SUBMITTED.with(|s| s.borrow().clone())
+	}
+
+	/// Test externalities whose offchain worker signs with the key of validator `who`, if any.
+	fn new_offchain_test_ext(who: Option<u64>) -> runtime_io::TestExternalities<Blake2Hasher> {
+		let mut ext = new_test_ext();
+		let offchain = TestOffchainExt::default();
+		offchain.0.write().authority_key = who.map(seed);
+		ext.set_offchain_externalities(offchain);
+		ext
+	}
+
+	#[test]
+	fn heartbeat_is_validated_and_recorded() {
+		with_externalities(&mut new_test_ext(), || {
+			let (hb, signature) = heartbeat(0, 1, 2);
+			let call = Call::heartbeat(hb.clone(), signature.clone());
+			match ImOnline::validate_unsigned(&call) {
+				TransactionValidity::Valid { provides, .. } => assert_eq!(provides.len(), 1),
+				_ => panic!("heartbeat should be valid"),
+			}
+
+			assert!(!ImOnline::is_online_in_current_session(1));
+			assert_ok!(ImOnline::heartbeat(Origin::NONE, hb.clone(), signature.clone()));
+			assert!(ImOnline::is_online_in_current_session(1));
+			assert!(!ImOnline::is_online_in_current_session(0));
+
+			// A second heartbeat in the same session is rejected.
+			assert_eq!(
+				ImOnline::validate_unsigned(&call),
+				TransactionValidity::Invalid(ApplyError::Stale as i8)
+			);
+			assert_noop!(
+				ImOnline::heartbeat(Origin::NONE, hb, signature),
+				"heartbeat already received for this session"
+			);
+		});
+	}
+
+	#[test]
+	fn heartbeat_must_be_unsigned() {
+		with_externalities(&mut new_test_ext(), || {
+			let (hb, signature) = heartbeat(0, 0, 1);
+			assert_noop!(
+				ImOnline::heartbeat(Origin::signed(1), hb, signature),
+				"bad origin: expected to be no origin"
+			);
+		});
+	}
+
+	#[test]
+	fn heartbeat_with_invalid_signature_is_rejected() {
+		with_externalities(&mut new_test_ext(), || {
+			// Authority 0 is validator 1, but the heartbeat is signed by validator 2.
+			let (hb, signature) = heartbeat(0, 0, 2);
+			assert_eq!(
+				ImOnline::validate_unsigned(&Call::heartbeat(hb.clone(), signature.clone())),
+				TransactionValidity::Invalid(ApplyError::BadSignature as i8)
+			);
+			assert_noop!(
+				ImOnline::heartbeat(Origin::NONE, hb, signature),
+				"invalid heartbeat signature"
+			);
+
+			let (hb, signature) = heartbeat(0, 3, 1);
+			assert_noop!(
+				ImOnline::heartbeat(Origin::NONE, hb, signature),
+				"non-existent authority index"
+			);
+		});
+	}
+
+	#[test]
+	fn heartbeat_for_another_session_is_rejected() {
+		with_externalities(&mut new_test_ext(), || {
+			Session::rotate_session();
+			assert_eq!(Session::current_index(), 1);
+
+			let (hb, signature) = heartbeat(0, 0, 1);
+			assert_noop!(
+				ImOnline::heartbeat(Origin::NONE, hb, signature),
+				"heartbeat is not for the current session"
+			);
+			let (hb, signature) = heartbeat(2, 0, 1);
+			assert_noop!(
+				ImOnline::heartbeat(Origin::NONE, hb, signature),
+				"heartbeat is not for the current session"
+			);
+
+			let (hb, signature) = heartbeat(1, 0, 1);
+			assert_ok!(ImOnline::heartbeat(Origin::NONE, hb, signature));
+		});
+	}
+
+	#[test]
+	fn offline_validators_are_reported_at_session_end() {
+		with_externalities(&mut new_test_ext(), || {
+			let (hb, signature) = heartbeat(0, 0, 1);
+			assert_ok!(ImOnline::heartbeat(Origin::NONE, hb, signature));
+			let (hb, signature) = heartbeat(0, 2, 3);
+			assert_ok!(ImOnline::heartbeat(Origin::NONE, hb, signature));
+
+			Session::rotate_session();
+			assert_eq!(offline(), vec![vec![2]]);
+			assert_eq!(OFFENCES.with(|o| o.borrow()[0].clone()), UnresponsivenessOffence {
+				session_index: 0,
+				validator_set_count: 3,
+				offenders: vec![(2, 20)],
+			});
+			// Heartbeats of the ended session are cleared.
+			assert!(!<ReceivedHeartbeats>::exists(&0, &0));
+
+			// Nobody sent anything in session 1.
+			Session::rotate_session();
+			assert_eq!(offline(), vec![vec![2], vec![1, 2, 3]]);
+
+			// Everyone is online in session 2.
+			for (index, who) in vec![(0, 1), (1, 2), (2, 3)] {
+				let (hb, signature) = heartbeat(2, index, who);
+				assert_ok!(ImOnline::heartbeat(Origin::NONE, hb, signature));
+			}
+			Session::rotate_session();
+			assert_eq!(offline(), vec![vec![2], vec![1, 2, 3]]);
+		});
+	}
+
+	#[test]
+	fn offchain_worker_submits_one_heartbeat_per_session() {
+		with_externalities(&mut new_offchain_test_ext(Some(2)), || {
+			ImOnline::generate_extrinsics(1);
+			let calls = submitted();
+			assert_eq!(calls.len(), 1);
+			let (hb, signature) = match calls[0].clone() {
+				Call::heartbeat(hb, signature) => (hb, signature),
+				_ => panic!("expected a heartbeat"),
+			};
+			assert_eq!(hb, Heartbeat { block_number: 1, session_index: 0, authority_index: 1 });
+
+			// The submitted heartbeat is accepted by the module.
+			match ImOnline::validate_unsigned(&calls[0]) {
+				TransactionValidity::Valid { .. } => {},
+				_ => panic!("heartbeat should be valid"),
+			}
+			assert_ok!(ImOnline::heartbeat(Origin::NONE, hb, signature));
+
+			// Nothing more is sent until the next session.
+			ImOnline::generate_extrinsics(2);
+			assert_eq!(submitted().len(), 1);
+
+			Session::rotate_session();
+			ImOnline::generate_extrinsics(3);
+			let calls = submitted();
+			assert_eq!(calls.len(), 2);
+			match &calls[1] {
+				Call::heartbeat(hb, _) => assert_eq!((hb.session_index, hb.authority_index), (1, 1)),
+				_ => panic!("expected a heartbeat"),
+			}
+		});
+	}
+
+	#[test]
+	fn offchain_worker_of_non_authority_submits_nothing() {
+		// No local key at all.
+		with_externalities(&mut new_offchain_test_ext(None), || {
+			ImOnline::generate_extrinsics(1);
+			assert!(submitted().is_empty());
+		});
+		// A key which is not among the session's authority keys.
+		with_externalities(&mut new_offchain_test_ext(Some(4)), || {
+			ImOnline::generate_extrinsics(1);
+			assert!(submitted().is_empty());
+		});
+	}
+
+	#[test]
+	fn slash_fraction_grows_with_the_number_of_offenders() {
+		type O = UnresponsivenessOffence<(u64, u64)>;
+		assert_eq!(O::slash_fraction(1, 50), Perbill::zero());
+		assert_eq!(O::slash_fraction(2, 50), Perbill::from_rational_approximation(3u32, 1000));
+		assert_eq!(O::slash_fraction(11, 50), Perbill::from_rational_approximation(3u32, 100));
+		assert_eq!(O::slash_fraction(17, 50), Perbill::from_rational_approximation(48u32, 1000));
+		// With more than a third of the validators offline the full 5% is slashed.
+		assert_eq!(O::slash_fraction(18, 50), Perbill::from_percent(5));
+		assert_eq!(O::slash_fraction(50, 50), Perbill::from_percent(5));
+	}
+}
@@ -20,9 +20,9 @@
 
 use std::collections::HashSet;
 use ref_thread_local::{ref_thread_local, RefThreadLocal};
-use primitives::testing::Header;
+use primitives::{Perbill, testing::Header, weights::Weight};
 use substrate_primitives::{H256, Blake2Hasher};
-use srml_support::impl_outer_origin;
+use srml_support::{impl_outer_origin, parameter_types};
 use {runtime_io, system};
 use crate::{GenesisConfig, Module, Trait, IsDeadAccount, OnNewAccount, ResolveHint};
 
@@ -64,6 +64,10 @@ impl ResolveHint<u64, u64> for TestResolveHint {
 // Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Runtime;
+parameter_types! {
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
 impl system::Trait for Runtime {
 	type Origin = Origin;
 	type Index = u64;
@@ -74,6 +78,9 @@ impl system::Trait for Runtime {
 	type Lookup = Indices;
 	type Header = Header;
 	type Event = ();
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type FeeMultiplierUpdate = ();
 }
 impl Trait for Runtime {
 	type AccountIndex = u64;
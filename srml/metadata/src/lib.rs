@@ -309,8 +309,10 @@ pub enum RuntimeMetadata {
 	V4(RuntimeMetadataDeprecated),
 	/// Version 5 for runtime metadata. No longer used.
 	V5(RuntimeMetadataDeprecated),
-	/// Version 6 for runtime metadata.
-	V6(RuntimeMetadataV6),
+	/// Version 6 for runtime metadata. No longer used.
+	V6(RuntimeMetadataDeprecated),
+	/// Version 7 for runtime metadata.
+	V7(RuntimeMetadataV7),
 }
 
 /// Enum that should fail.
@@ -332,8 +334,19 @@ impl Decode for RuntimeMetadataDeprecated {
 /// The metadata of a runtime.
 #[derive(Eq, Encode, PartialEq)]
 #[cfg_attr(feature = "std", derive(Decode, Debug, Serialize))]
-pub struct RuntimeMetadataV6 {
+pub struct RuntimeMetadataV7 {
 	pub modules: DecodeDifferentArray<ModuleMetadata>,
+	pub extrinsic: ExtrinsicMetadata,
+}
+
+/// Metadata of the extrinsic format of a runtime.
+#[derive(Clone, PartialEq, Eq, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Debug, Serialize))]
+pub struct ExtrinsicMetadata {
+	/// The version of the extrinsic format.
+	pub version: u8,
+	/// The names of the signed extensions, in the order they appear in a signed extrinsic.
+	pub signed_extensions: Vec<DecodeDifferentStr>,
 }
 
 /// All metadata about an runtime module.
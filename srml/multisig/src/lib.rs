@@ -0,0 +1,657 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Multisig Module
+//!
+//! A module for doing multisig dispatch.
+//!
+//! - [`multisig::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! This module contains functionality for multi-signature dispatch, a (potentially) stateful
+//! operation, allowing multiple signed origins (accounts) to coordinate and dispatch a call from
+//! a well-known origin, derivable deterministically from the set of account IDs and the threshold
+//! number of accounts from the set that must approve it. In the case that the threshold is just
+//! one then this is a stateless operation. This is useful for multisig wallets where cryptographic
+//! threshold signatures are not available or desired.
+//!
+//! ### Terminology
+//!
+//! - **Signatories:** The sorted set of accounts that may approve operations of a multisig
+//! account.
+//! - **Threshold:** The number of distinct signatories that must approve an operation before it
+//! is dispatched.
+//! - **Timepoint:** The block number and extrinsic index at which an operation was opened. Every
+//! further approval must name it, so that an approval cannot be replayed against a later
+//! operation that happens to wrap the same call.
+//! - **Deposit:** Funds reserved from the account which opened an operation. They are returned
+//! when the operation is either executed or cancelled.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `as_multi` - Approve and, if the threshold is reached, dispatch a call from a composite
+//! origin.
+//! - `approve_as_multi` - Approve a call from a composite origin, identifying it by its hash.
+//! - `cancel_as_multi` - Cancel a call from a composite origin and return the deposit.
+//!
+//! ### Public Functions
+//!
+//! - `multi_account_id` - Derive the account of a given signatory set and threshold.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use parity_codec::{Encode, Decode};
+use srml_support::{
+	StorageDoubleMap, Parameter, decl_module, decl_storage, decl_event, ensure,
+	traits::{Currency, ReservableCurrency, Get},
+	dispatch::{Dispatchable, GetDispatchInfo, TransactionWeight},
+};
+use runtime_primitives::traits::Zero;
+use system::ensure_signed;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// Domain separator for the derivation of multisig accounts.
+const MULTI_ACCOUNT_PREFIX: &[u8] = b"modlpy/multisig";
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The overarching call type.
+	type Call: Parameter + Dispatchable<Origin=Self::Origin> + GetDispatchInfo;
+
+	/// The currency mechanism.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The base amount of currency needed to reserve for creating a multisig execution.
+	type DepositBase: Get<BalanceOf<Self>>;
+
+	/// The amount of currency needed per unit threshold when creating a multisig execution.
+	type DepositFactor: Get<BalanceOf<Self>>;
+
+	/// The maximum amount of signatories allowed in a multisig.
+	type MaxSignatories: Get<u16>;
+}
+
+/// A global extrinsic index, formed as the extrinsic index within a block, together with that
+/// block's height. This allows a transaction in which a multisig operation of a particular
+/// composite was created to be uniquely identified.
+#[derive(Copy, Clone, Eq, PartialEq, Encode, Decode, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Timepoint<BlockNumber> {
+	/// The height of the chain at the point in time.
+	pub height: BlockNumber,
+	/// The index of the extrinsic at the point in time.
+	pub index: u32,
+}
+
+/// An open multisig operation.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Multisig<BlockNumber, Balance, AccountId> {
+	/// The extrinsic when the multisig operation was opened.
+	pub when: Timepoint<BlockNumber>,
+	/// The amount held in reserve of the `depositor`, to be returned once the operation ends.
+	pub deposit: Balance,
+	/// The account who opened it (i.e. the first to approve it).
+	pub depositor: AccountId,
+	/// The approvals achieved so far, including the depositor. Always sorted.
+	pub approvals: Vec<AccountId>,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Multisig {
+		/// The set of open multisig operations, keyed by the multisig account and the hash of
+		/// the call they wrap.
+		pub Multisigs get(multisigs): double_map hasher(twox_64_concat) T::AccountId, blake2_256([u8; 32])
+			=> Option<Multisig<T::BlockNumber, BalanceOf<T>, T::AccountId>>;
+	}
+}
+
+decl_event!(
+	/// Events type.
+	pub enum Event<T> where
+		AccountId = <T as system::Trait>::AccountId,
+		BlockNumber = <T as system::Trait>::BlockNumber
+	{
+		/// A new multisig operation has begun. First param is the account that is approving,
+		/// second is the multisig account.
+		NewMultisig(AccountId, AccountId),
+		/// A multisig operation has been approved by someone. First param is the account that is
+		/// approving, third is the multisig account.
+		MultisigApproval(AccountId, Timepoint<BlockNumber>, AccountId),
+		/// A multisig operation has been executed. First param is the account that is
+		/// approving, third is the multisig account. The final param is whether the dispatched
+		/// call succeeded.
+		MultisigExecuted(AccountId, Timepoint<BlockNumber>, AccountId, bool),
+		/// A multisig operation has been cancelled. First param is the account that is
+		/// cancelling, third is the multisig account.
+		MultisigCancelled(AccountId, Timepoint<BlockNumber>, AccountId),
+	}
+);
+
+/// The call an approval refers to: either the call itself, which may then be dispatched, or
+/// only its hash.
+enum CallOrHash<Call> {
+	Call(Call),
+	Hash([u8; 32]),
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event<T>() = default;
+
+		/// The base amount of currency needed to reserve for creating a multisig execution.
+		const DepositBase: BalanceOf<T> = T::DepositBase::get();
+
+		/// The amount of currency needed per unit threshold when creating a multisig execution.
+		const DepositFactor: BalanceOf<T> = T::DepositFactor::get();
+
+		/// The maximum amount of signatories allowed in a multisig.
+		const MaxSignatories: u16 = T::MaxSignatories::get();
+
+		/// Register approval for a dispatch to be made from a deterministic composite account if
+		/// approved by a total of `threshold - 1` of `other_signatories`.
+		///
+		/// If there are enough approvals, then dispatch the call.
+		///
+		/// Payment: `DepositBase` will be reserved if this is the first approval, plus
+		/// `threshold` times `DepositFactor`. It is returned once this dispatch happens or
+		/// is cancelled.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `threshold`: The total number of approvals for this dispatch before it is executed.
+		/// - `other_signatories`: The accounts (other than the sender) who can approve this
+		/// dispatch. Must be sorted and free of duplicates.
+		/// - `maybe_timepoint`: If this is the first approval, then this must be `None`. If it is
+		/// not the first approval, then it must be `Some`, with the timepoint (block number and
+		/// transaction index) of the first approval transaction.
+		/// - `call`: The call to be executed.
+		///
+		/// # <weight>
+		/// - `O(S + Z)` where `S` is the number of signatories and `Z` is the size of the call.
+		/// - Up to one balance-reserve or unreserve operation.
+		/// - One storage read and one storage write or removal.
+		/// - One event.
+		/// - The weight of the `call`.
+		/// # </weight>
+		#[weight = TransactionWeight::Basic(call.get_dispatch_info(0).weight.saturating_add(1000), 1)]
+		fn as_multi(origin,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			maybe_timepoint: Option<Timepoint<T::BlockNumber>>,
+			call: Box<<T as Trait>::Call>
+		) {
+			let who = ensure_signed(origin)?;
+			Self::operate(who, threshold, other_signatories, maybe_timepoint, CallOrHash::Call(*call))?;
+		}
+
+		/// Register approval for a dispatch to be made from a deterministic composite account if
+		/// approved by a total of `threshold - 1` of `other_signatories`.
+		///
+		/// The final approval must be given through `as_multi`, since only that carries the call
+		/// to be dispatched.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `threshold`: The total number of approvals for this dispatch before it is executed.
+		/// - `other_signatories`: The accounts (other than the sender) who can approve this
+		/// dispatch. Must be sorted and free of duplicates.
+		/// - `maybe_timepoint`: If this is the first approval, then this must be `None`. If it is
+		/// not the first approval, then it must be `Some`, with the timepoint (block number and
+		/// transaction index) of the first approval transaction.
+		/// - `call_hash`: The hash of the call to be executed.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` is the number of signatories.
+		/// - Up to one balance-reserve operation.
+		/// - One storage read and one storage write.
+		/// - One event.
+		/// # </weight>
+		fn approve_as_multi(origin,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			maybe_timepoint: Option<Timepoint<T::BlockNumber>>,
+			call_hash: [u8; 32]
+		) {
+			let who = ensure_signed(origin)?;
+			Self::operate(who, threshold, other_signatories, maybe_timepoint, CallOrHash::Hash(call_hash))?;
+		}
+
+		/// Cancel a pre-existing, on-going multisig transaction. Any deposit reserved previously
+		/// for this operation will be unreserved on success.
+		///
+		/// The dispatch origin for this call must be _Signed_ and must be the account which
+		/// opened the operation.
+		///
+		/// - `threshold`: The total number of approvals for this dispatch before it is executed.
+		/// - `other_signatories`: The accounts (other than the sender) who can approve this
+		/// dispatch. Must be sorted and free of duplicates.
+		/// - `timepoint`: The timepoint (block number and transaction index) of the first approval
+		/// transaction for this dispatch.
+		/// - `call_hash`: The hash of the call to be executed.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` is the number of signatories.
+		/// - One balance-unreserve operation.
+		/// - One storage read and one storage removal.
+		/// - One event.
+		/// # </weight>
+		fn cancel_as_multi(origin,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			timepoint: Timepoint<T::BlockNumber>,
+			call_hash: [u8; 32]
+		) {
+			let who = ensure_signed(origin)?;
+			ensure!(threshold >= 1, "threshold must be at least one");
+			let signatories = Self::ensure_sorted_and_insert(other_signatories, who.clone())?;
+			let id = Self::multi_account_id(&signatories, threshold);
+
+			let m = Self::multisigs(&id, call_hash).ok_or("multisig operation not found")?;
+			ensure!(m.when == timepoint, "timepoint does not match that of the operation");
+			ensure!(m.depositor == who, "only the depositor can cancel a multisig operation");
+
+			T::Currency::unreserve(&m.depositor, m.deposit);
+			<Multisigs<T>>::remove(&id, call_hash);
+
+			Self::deposit_event(RawEvent::MultisigCancelled(who, timepoint, id));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Derive a multi-account ID from the sorted list of accounts and the threshold that are
+	/// required.
+	///
+	/// NOTE: `who` must be sorted. If it is not, then you'll get the wrong answer.
+	pub fn multi_account_id(who: &[T::AccountId], threshold: u16) -> T::AccountId {
+		let entropy = (MULTI_ACCOUNT_PREFIX, who, threshold).using_encoded(runtime_io::blake2_256);
+		T::AccountId::decode(&mut &entropy[..]).unwrap_or_default()
+	}
+
+	/// The current timepoint.
+	pub fn timepoint() -> Timepoint<T::BlockNumber> {
+		Timepoint {
+			height: <system::Module<T>>::block_number(),
+			index: <system::Module<T>>::extrinsic_index().unwrap_or_default(),
+		}
+	}
+
+	fn operate(
+		who: T::AccountId,
+		threshold: u16,
+		other_signatories: Vec<T::AccountId>,
+		maybe_timepoint: Option<Timepoint<T::BlockNumber>>,
+		call_or_hash: CallOrHash<<T as Trait>::Call>,
+	) -> srml_support::dispatch::Result {
+		ensure!(threshold >= 1, "threshold must be at least one");
+		let max_signatories = T::MaxSignatories::get() as usize;
+		ensure!(other_signatories.len() < max_signatories, "too many signatories");
+		ensure!(other_signatories.len() >= threshold as usize - 1, "threshold exceeds the number of signatories");
+		let signatories = Self::ensure_sorted_and_insert(other_signatories, who.clone())?;
+		let id = Self::multi_account_id(&signatories, threshold);
+
+		let (call_hash, maybe_call) = match call_or_hash {
+			CallOrHash::Call(call) => (call.using_encoded(runtime_io::blake2_256), Some(call)),
+			CallOrHash::Hash(h) => (h, None),
+		};
+
+		if let Some(mut m) = Self::multisigs(&id, call_hash) {
+			let timepoint = maybe_timepoint.ok_or("an open multisig operation requires its timepoint")?;
+			ensure!(m.when == timepoint, "timepoint does not match that of the operation");
+
+			let already_approved = m.approvals.binary_search(&who);
+			let approvals = m.approvals.len() + if already_approved.is_ok() { 0 } else { 1 };
+			match maybe_call {
+				Some(call) if approvals >= threshold as usize => {
+					T::Currency::unreserve(&m.depositor, m.deposit);
+					<Multisigs<T>>::remove(&id, call_hash);
+					let ok = call.dispatch(system::RawOrigin::Signed(id.clone()).into()).is_ok();
+					Self::deposit_event(RawEvent::MultisigExecuted(who, timepoint, id, ok));
+				}
+				_ => {
+					let pos = already_approved.err().ok_or("the sender has already approved")?;
+					ensure!(approvals < threshold as usize, "the final approval must be made with as_multi");
+					m.approvals.insert(pos, who.clone());
+					<Multisigs<T>>::insert(&id, call_hash, m);
+					Self::deposit_event(RawEvent::MultisigApproval(who, timepoint, id));
+				}
+			}
+		} else {
+			ensure!(maybe_timepoint.is_none(), "a new multisig operation must not give a timepoint");
+			match maybe_call {
+				Some(call) if threshold == 1 => {
+					let ok = call.dispatch(system::RawOrigin::Signed(id.clone()).into()).is_ok();
+					Self::deposit_event(RawEvent::MultisigExecuted(who, Self::timepoint(), id, ok));
+				}
+				_ => {
+					ensure!(threshold > 1, "the final approval must be made with as_multi");
+					let deposit = T::DepositBase::get() + T::DepositFactor::get() * threshold.into();
+					T::Currency::reserve(&who, deposit)
+						.map_err(|_| "not enough free funds to reserve the deposit")?;
+					<Multisigs<T>>::insert(&id, call_hash, Multisig {
+						when: Self::timepoint(),
+						deposit,
+						depositor: who.clone(),
+						approvals: vec![who.clone()],
+					});
+					Self::deposit_event(RawEvent::NewMultisig(who, id));
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// The deposit currently held for the operation wrapping `call_hash` of multisig `id`.
+	pub fn deposit_of(id: &T::AccountId, call_hash: [u8; 32]) -> BalanceOf<T> {
+		Self::multisigs(id, call_hash).map_or_else(Zero::zero, |m| m.deposit)
+	}
+
+	/// Check that signatories is sorted and doesn't contain sender, then insert sender.
+	fn ensure_sorted_and_insert(other_signatories: Vec<T::AccountId>, who: T::AccountId)
+		-> Result<Vec<T::AccountId>, &'static str>
+	{
+		let mut signatories = other_signatories;
+		let mut maybe_last = None;
+		let mut index = 0;
+		for item in signatories.iter() {
+			if let Some(last) = maybe_last {
+				ensure!(last < item, "signatories must be sorted and unique");
+			}
+			if item <= &who {
+				ensure!(item != &who, "the sender must not be among the other signatories");
+				index += 1;
+			}
+			maybe_last = Some(item);
+		}
+		signatories.insert(index, who);
+		Ok(signatories)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use runtime_io::with_externalities;
+	use srml_support::{
+		assert_ok, assert_noop, impl_outer_origin, impl_outer_dispatch, parameter_types,
+	};
+	use substrate_primitives::{H256, Blake2Hasher};
+	use runtime_primitives::{
+		Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header, weights::Weight,
+	};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			balances::Balances,
+			multisig::Multisig,
+		}
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const TransactionBaseFee: u64 = 0;
+		pub const TransactionByteFee: u64 = 0;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransactionPayment = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+		type TransactionBaseFee = TransactionBaseFee;
+		type TransactionByteFee = TransactionByteFee;
+		type WeightToFee = ();
+	}
+	parameter_types! {
+		pub const DepositBase: u64 = 1;
+		pub const DepositFactor: u64 = 1;
+		pub const MaxSignatories: u16 = 3;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Call = Call;
+		type Currency = Balances;
+		type DepositBase = DepositBase;
+		type DepositFactor = DepositFactor;
+		type MaxSignatories = MaxSignatories;
+	}
+	type Balances = balances::Module<Test>;
+	type Multisig = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap().0;
+		t.extend(balances::GenesisConfig::<Test>{
+			balances: vec![(1, 10), (2, 10), (3, 10), (4, 10), (5, 2)],
+			vesting: vec![],
+		}.build_storage().unwrap().0);
+		t.into()
+	}
+
+	fn now() -> Timepoint<u64> {
+		Multisig::timepoint()
+	}
+
+	fn transfer_call(dest: u64, value: u64) -> Box<Call> {
+		Box::new(Call::Balances(balances::Call::transfer(dest, value)))
+	}
+
+	#[test]
+	fn as_multi_weight_includes_the_weight_of_the_call() {
+		let call = transfer_call(6, 15);
+		let as_multi = |call| Box::new(Call::Multisig(crate::Call::as_multi(2, vec![2, 3], None, call)));
+		assert_eq!(as_multi(call.clone()).get_dispatch_info(0).weight, 1000);
+		assert_eq!(as_multi(as_multi(call)).get_dispatch_info(0).weight, 2000);
+	}
+
+	#[test]
+	fn multi_account_id_is_deterministic_and_threshold_sensitive() {
+		with_externalities(&mut new_test_ext(), || {
+			let a = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+			assert_eq!(a, Multisig::multi_account_id(&[1, 2, 3][..], 2));
+			assert_ne!(a, Multisig::multi_account_id(&[1, 2, 3][..], 3));
+			assert_ne!(a, Multisig::multi_account_id(&[1, 2, 4][..], 2));
+		});
+	}
+
+	#[test]
+	fn multisig_deposit_is_taken_and_returned() {
+		with_externalities(&mut new_test_ext(), || {
+			let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+			assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+			assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+			assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+			let call = transfer_call(6, 15);
+			let hash = call.using_encoded(runtime_io::blake2_256);
+			assert_ok!(Multisig::as_multi(Origin::signed(1), 2, vec![2, 3], None, call.clone()));
+			assert_eq!(Balances::free_balance(&1), 2);
+			assert_eq!(Balances::reserved_balance(&1), 3);
+			assert_eq!(Multisig::deposit_of(&multi, hash), 3);
+
+			assert_ok!(Multisig::as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), call));
+			assert_eq!(Balances::free_balance(&1), 5);
+			assert_eq!(Balances::reserved_balance(&1), 0);
+			assert_eq!(Balances::free_balance(&6), 15);
+			assert!(Multisig::multisigs(&multi, hash).is_none());
+		});
+	}
+
+	#[test]
+	fn cancel_multisig_returns_deposit() {
+		with_externalities(&mut new_test_ext(), || {
+			let call = transfer_call(6, 15);
+			let hash = call.using_encoded(runtime_io::blake2_256);
+			assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 3, vec![2, 3], None, hash));
+			assert_ok!(Multisig::approve_as_multi(Origin::signed(2), 3, vec![1, 3], Some(now()), hash));
+			assert_eq!(Balances::free_balance(&1), 6);
+			assert_eq!(Balances::reserved_balance(&1), 4);
+
+			assert_noop!(
+				Multisig::cancel_as_multi(Origin::signed(2), 3, vec![1, 3], now(), hash),
+				"only the depositor can cancel a multisig operation"
+			);
+			assert_ok!(Multisig::cancel_as_multi(Origin::signed(1), 3, vec![2, 3], now(), hash));
+			assert_eq!(Balances::free_balance(&1), 10);
+			assert_eq!(Balances::reserved_balance(&1), 0);
+		});
+	}
+
+	#[test]
+	fn multisig_3_of_3_works() {
+		with_externalities(&mut new_test_ext(), || {
+			let multi = Multisig::multi_account_id(&[1, 2, 3][..], 3);
+			assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+			assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+			assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+			let call = transfer_call(6, 15);
+			let hash = call.using_encoded(runtime_io::blake2_256);
+			assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 3, vec![2, 3], None, hash));
+			assert_ok!(Multisig::approve_as_multi(Origin::signed(2), 3, vec![1, 3], Some(now()), hash));
+			assert_noop!(
+				Multisig::approve_as_multi(Origin::signed(3), 3, vec![1, 2], Some(now()), hash),
+				"the final approval must be made with as_multi"
+			);
+			assert_eq!(Balances::free_balance(&6), 0);
+
+			assert_ok!(Multisig::as_multi(Origin::signed(3), 3, vec![1, 2], Some(now()), call));
+			assert_eq!(Balances::free_balance(&6), 15);
+		});
+	}
+
+	#[test]
+	fn threshold_one_dispatches_immediately() {
+		with_externalities(&mut new_test_ext(), || {
+			let multi = Multisig::multi_account_id(&[1, 2][..], 1);
+			assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+
+			assert_ok!(Multisig::as_multi(Origin::signed(2), 1, vec![1], None, transfer_call(6, 5)));
+			assert_eq!(Balances::free_balance(&6), 5);
+			assert_eq!(Balances::reserved_balance(&2), 0);
+		});
+	}
+
+	#[test]
+	fn timepoint_checking_works() {
+		with_externalities(&mut new_test_ext(), || {
+			let call = transfer_call(6, 15);
+			let hash = call.using_encoded(runtime_io::blake2_256);
+
+			assert_noop!(
+				Multisig::approve_as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), hash),
+				"a new multisig operation must not give a timepoint"
+			);
+			assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 2, vec![2, 3], None, hash));
+
+			assert_noop!(
+				Multisig::as_multi(Origin::signed(2), 2, vec![1, 3], None, call.clone()),
+				"an open multisig operation requires its timepoint"
+			);
+			let later = Timepoint { index: 1, .. now() };
+			assert_noop!(
+				Multisig::as_multi(Origin::signed(2), 2, vec![1, 3], Some(later), call),
+				"timepoint does not match that of the operation"
+			);
+		});
+	}
+
+	#[test]
+	fn duplicate_approvals_are_rejected() {
+		with_externalities(&mut new_test_ext(), || {
+			let hash = transfer_call(6, 15).using_encoded(runtime_io::blake2_256);
+			assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 3, vec![2, 3], None, hash));
+			assert_noop!(
+				Multisig::approve_as_multi(Origin::signed(1), 3, vec![2, 3], Some(now()), hash),
+				"the sender has already approved"
+			);
+		});
+	}
+
+	#[test]
+	fn signatories_must_be_sorted_and_bounded() {
+		with_externalities(&mut new_test_ext(), || {
+			let call = transfer_call(6, 15);
+			assert_noop!(
+				Multisig::as_multi(Origin::signed(1), 2, vec![3, 2], None, call.clone()),
+				"signatories must be sorted and unique"
+			);
+			assert_noop!(
+				Multisig::as_multi(Origin::signed(1), 2, vec![1, 2], None, call.clone()),
+				"the sender must not be among the other signatories"
+			);
+			assert_noop!(
+				Multisig::as_multi(Origin::signed(1), 2, vec![2, 3, 4], None, call.clone()),
+				"too many signatories"
+			);
+			assert_noop!(
+				Multisig::as_multi(Origin::signed(1), 3, vec![2], None, call),
+				"threshold exceeds the number of signatories"
+			);
+		});
+	}
+
+	#[test]
+	fn deposit_must_be_affordable() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(
+				Multisig::as_multi(Origin::signed(5), 2, vec![1, 2], None, transfer_call(6, 1)),
+				"not enough free funds to reserve the deposit"
+			);
+		});
+	}
+}
@@ -0,0 +1,502 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Offences Module
+//!
+//! Tracks reported offences.
+//!
+//! - [`offences::Trait`](./trait.Trait.html)
+//! - [`Module`](./struct.Module.html)
+//!
+//! ## Overview
+//!
+//! Other modules (e.g. GRANDPA or BABE equivocation handling, or an I'm-online tracker) report
+//! misbehaviour of validators to this module through the
+//! [`ReportOffence`](../srml_support/traits/offence/trait.ReportOffence.html) trait. Each report
+//! names a kind of offence, the time slot in which it happened and the offending validators.
+//!
+//! Reports are deduplicated: an offender which was already reported for an offence of the same
+//! kind in the same time slot is ignored, and so is a report which contains no new offenders.
+//! Otherwise the slash fraction is recomputed from the number of concurrent offenders of that
+//! kind and time slot, and every one of them is passed on to the `OnOffenceHandler` (e.g. the
+//! staking module), which is responsible for actually punishing them.
+//!
+//! Reports are kept for `SlashingWindow` sessions after the session of the offence, after which
+//! they are pruned, one session per block. Offences older than that are no longer accepted.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use parity_codec::Encode;
+use srml_support::{
+	StorageValue, StorageMap, StorageDoubleMap, Parameter, decl_module, decl_event, decl_storage,
+	traits::{
+		Get,
+		offence::{Offence, ReportOffence, Kind, OnOffenceHandler, OffenceDetails, SessionIndex},
+	},
+};
+use runtime_primitives::traits::Hash;
+
+/// A binary blob which represents a SCALE codec-encoded `(SessionIndex, O::TimeSlot)`.
+type OpaqueTimeSlot = Vec<u8>;
+
+/// A type alias for a report identifier.
+type ReportIdOf<T> = <T as system::Trait>::Hash;
+
+/// Offences trait
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+	/// Full identification of the validator.
+	type IdentificationTuple: Parameter + Ord;
+	/// A handler called for every offence report.
+	type OnOffenceHandler: OnOffenceHandler<Self::AccountId, Self::IdentificationTuple>;
+	/// The index of the current session.
+	type CurrentSession: Get<SessionIndex>;
+	/// The number of sessions for which the reports of an offence are kept after the session of
+	/// the offence. This should cover the period during which an offender may still be slashed.
+	type SlashingWindow: Get<SessionIndex>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Offences {
+		/// The primary structure that holds all offence records keyed by report identifiers.
+		Reports get(reports): map ReportIdOf<T> => Option<OffenceDetails<T::AccountId, T::IdentificationTuple>>;
+
+		/// A vector of reports of the same kind that happened at the same time slot.
+		ConcurrentReportsIndex: double_map hasher(twox_64_concat) Kind, blake2_256(OpaqueTimeSlot)
+			=> Vec<ReportIdOf<T>>;
+
+		/// The keys of `ConcurrentReportsIndex` used by the offences of each session, so that
+		/// they can be pruned once the session is past the slashing window.
+		SessionReports: map SessionIndex => Vec<(Kind, OpaqueTimeSlot)>;
+
+		/// The oldest session whose reports have not been pruned yet.
+		OldestUnprunedSession get(oldest_unpruned_session): SessionIndex;
+	}
+}
+
+decl_event!(
+	pub enum Event {
+		/// There is an offence reported of the given `kind` happened at the time slot.
+		Offence(Kind, OpaqueTimeSlot),
+	}
+);
+
+decl_module! {
+	/// Offences module, currently just responsible for taking offence reports.
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// The number of sessions for which the reports of an offence are kept.
+		const SlashingWindow: SessionIndex = T::SlashingWindow::get();
+
+		fn on_initialize(_now: T::BlockNumber) {
+			Self::prune_oldest_session();
+		}
+	}
+}
+
+impl<T: Trait, O: Offence<T::IdentificationTuple>>
+	ReportOffence<T::AccountId, T::IdentificationTuple, O> for Module<T>
+{
+	fn report_offence(reporters: Vec<T::AccountId>, offence: O) {
+		let session_index = offence.session_index();
+		// Reports of such an old session would never be pruned, and it is too late to slash
+		// anyway.
+		if session_index < Self::oldest_unpruned_session() {
+			return
+		}
+
+		let offenders = offence.offenders();
+		let time_slot = offence.time_slot();
+		let validator_set_count = offence.validator_set_count();
+
+		// Go through all offenders in the offence report and find all offenders that were
+		// spotted in unique reports.
+		let concurrent_offenders = match Self::triage_offence_report::<O>(
+			reporters,
+			session_index,
+			&time_slot,
+			offenders,
+		) {
+			Some(concurrent_offenders) => concurrent_offenders,
+			// The report contained only duplicates, so there is no need to slash again.
+			None => return,
+		};
+
+		// Deposit the event.
+		Self::deposit_event(Event::Offence(O::ID, time_slot.encode()));
+
+		let offenders_count = concurrent_offenders.len() as u32;
+
+		// The amount new offenders are slashed
+		let new_fraction = O::slash_fraction(offenders_count, validator_set_count);
+
+		let slash_perbill: Vec<_> = (0..concurrent_offenders.len())
+			.map(|_| new_fraction)
+			.collect();
+
+		T::OnOffenceHandler::on_offence(
+			&concurrent_offenders,
+			&slash_perbill,
+			session_index,
+		);
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Compute the ID for the given report properties.
+	///
+	/// The report id depends on the offence kind, session, time slot and the id of offender.
+	fn report_id<O: Offence<T::IdentificationTuple>>(
+		opaque_time_slot: &OpaqueTimeSlot,
+		offender: &T::IdentificationTuple,
+	) -> ReportIdOf<T> {
+		(O::ID, opaque_time_slot, offender).using_encoded(T::Hashing::hash)
+	}
+
+	/// Remove the reports of the oldest unpruned session, if it is past the slashing window.
+	fn prune_oldest_session() {
+		let oldest = Self::oldest_unpruned_session();
+		if oldest.saturating_add(T::SlashingWindow::get()) >= T::CurrentSession::get() {
+			return
+		}
+
+		for (kind, opaque_time_slot) in <SessionReports>::take(oldest) {
+			for report_id in <ConcurrentReportsIndex<T>>::take(&kind, &opaque_time_slot) {
+				<Reports<T>>::remove(&report_id);
+			}
+		}
+		<OldestUnprunedSession>::put(oldest + 1);
+	}
+
+	/// Triages the offence report and returns the set of offenders that was involved in unique
+	/// reports along with the list of the concurrent offences.
+	///
+	/// Returns `None` if none of the offenders of the report are new.
+	fn triage_offence_report<O: Offence<T::IdentificationTuple>>(
+		reporters: Vec<T::AccountId>,
+		session_index: SessionIndex,
+		time_slot: &O::TimeSlot,
+		offenders: Vec<T::IdentificationTuple>,
+	) -> Option<Vec<OffenceDetails<T::AccountId, T::IdentificationTuple>>> {
+		let opaque_time_slot = (session_index, time_slot).encode();
+		let mut concurrent_reports = <ConcurrentReportsIndex<T>>::get(&O::ID, &opaque_time_slot);
+		let first_report = concurrent_reports.is_empty();
+
+		let mut any_new = false;
+		for offender in offenders {
+			let report_id = Self::report_id::<O>(&opaque_time_slot, &offender);
+
+			if !<Reports<T>>::exists(&report_id) {
+				any_new = true;
+				<Reports<T>>::insert(
+					&report_id,
+					OffenceDetails {
+						offender,
+						reporters: reporters.clone(),
+					},
+				);
+
+				concurrent_reports.push(report_id);
+			}
+		}
+
+		if !any_new {
+			return None
+		}
+
+		<ConcurrentReportsIndex<T>>::insert(&O::ID, &opaque_time_slot, &concurrent_reports);
+		if first_report {
+			<SessionReports>::mutate(session_index, |keys| keys.push((O::ID, opaque_time_slot)));
+		}
+
+		// Load report details for the all reports happened at the same time.
+		Some(
+			concurrent_reports
+				.iter()
+				.filter_map(|report_id| <Reports<T>>::get(report_id))
+				.collect()
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use std::cell::RefCell;
+	use runtime_io::with_externalities;
+	use srml_support::{impl_outer_origin, impl_outer_event, parameter_types};
+	use substrate_primitives::{H256, Blake2Hasher};
+	use runtime_primitives::{
+		Perbill, traits::{BlakeTwo256, IdentityLookup, OnInitialize}, testing::Header,
+		weights::Weight,
+	};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	mod offences {
+		pub use crate::Event;
+	}
+
+	impl_outer_event! {
+		pub enum TestEvent for Test {
+			offences,
+		}
+	}
+
+	thread_local! {
+		static ON_OFFENCE_PERBILL: RefCell<Vec<Perbill>> = RefCell::new(Default::default());
+		static OFFENDERS: RefCell<Vec<Vec<u64>>> = RefCell::new(Default::default());
+	}
+
+	pub struct OnOffenceHandler;
+	impl<Reporter, Offender: Clone + Into<u64>>
+		srml_support::traits::offence::OnOffenceHandler<Reporter, Offender> for OnOffenceHandler
+	{
+		fn on_offence(
+			offenders: &[OffenceDetails<Reporter, Offender>],
+			slash_fraction: &[Perbill],
+			_session: SessionIndex,
+		) {
+			ON_OFFENCE_PERBILL.with(|f| *f.borrow_mut() = slash_fraction.to_vec());
+			OFFENDERS.with(|o| o.borrow_mut().push(
+				offenders.iter().map(|d| d.offender.clone().into()).collect()
+			));
+		}
+	}
+
+	fn with_on_offence_fractions<R, F: FnOnce(&mut Vec<Perbill>) -> R>(f: F) -> R {
+		ON_OFFENCE_PERBILL.with(|fractions| f(&mut *fractions.borrow_mut()))
+	}
+
+	fn offenders() -> Vec<Vec<u64>> {
+		OFFENDERS.with(|o| o.borrow().clone())
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, PartialEq, Eq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = TestEvent;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
+	}
+	thread_local! {
+		static CURRENT_SESSION: RefCell<SessionIndex> = RefCell::new(0);
+	}
+	pub struct CurrentSession;
+	impl Get<SessionIndex> for CurrentSession {
+		fn get() -> SessionIndex { CURRENT_SESSION.with(|v| *v.borrow()) }
+	}
+	fn set_current_session(session: SessionIndex) {
+		CURRENT_SESSION.with(|v| *v.borrow_mut() = session);
+	}
+	parameter_types! {
+		pub const SlashingWindow: SessionIndex = 2;
+	}
+	impl Trait for Test {
+		type Event = TestEvent;
+		type IdentificationTuple = u64;
+		type OnOffenceHandler = OnOffenceHandler;
+		type CurrentSession = CurrentSession;
+		type SlashingWindow = SlashingWindow;
+	}
+	type Offences = Module<Test>;
+	type System = system::Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().0.into()
+	}
+
+	const KIND: [u8; 16] = *b"test_report_1234";
+
+	/// A simple offence for testing, slashing 5% of the exposure per concurrent offender.
+	#[derive(Clone, PartialEq, Eq, Debug)]
+	struct TestOffence {
+		validator_set_count: u32,
+		session_index: SessionIndex,
+		time_slot: u128,
+		offenders: Vec<u64>,
+	}
+
+	impl Offence<u64> for TestOffence {
+		const ID: Kind = KIND;
+		type TimeSlot = u128;
+
+		fn offenders(&self) -> Vec<u64> {
+			self.offenders.clone()
+		}
+
+		fn validator_set_count(&self) -> u32 {
+			self.validator_set_count
+		}
+
+		fn time_slot(&self) -> u128 {
+			self.time_slot
+		}
+
+		fn session_index(&self) -> SessionIndex {
+			self.session_index
+		}
+
+		fn slash_fraction(offenders_count: u32, validator_set_count: u32) -> Perbill {
+			let _ = validator_set_count;
+			Perbill::from_percent(5 * offenders_count)
+		}
+	}
+
+	fn offence(time_slot: u128, offenders: Vec<u64>) -> TestOffence {
+		TestOffence { validator_set_count: 5, session_index: 0, time_slot, offenders }
+	}
+
+	#[test]
+	fn should_report_an_authority_and_trigger_on_offence() {
+		with_externalities(&mut new_test_ext(), || {
+			Offences::report_offence(vec![], offence(42, vec![5]));
+
+			with_on_offence_fractions(|f| {
+				assert_eq!(f.clone(), vec![Perbill::from_percent(5)]);
+			});
+			assert_eq!(offenders(), vec![vec![5]]);
+		});
+	}
+
+	#[test]
+	fn should_not_report_the_same_authority_twice_in_the_same_slot() {
+		with_externalities(&mut new_test_ext(), || {
+			Offences::report_offence(vec![], offence(42, vec![5]));
+			with_on_offence_fractions(|f| f.clear());
+
+			// A second report of the same offender is ignored.
+			Offences::report_offence(vec![1], offence(42, vec![5]));
+			with_on_offence_fractions(|f| assert!(f.is_empty()));
+			assert_eq!(offenders(), vec![vec![5]]);
+		});
+	}
+
+	#[test]
+	fn should_report_in_different_time_slot() {
+		with_externalities(&mut new_test_ext(), || {
+			Offences::report_offence(vec![], offence(42, vec![5]));
+			Offences::report_offence(vec![], offence(43, vec![5]));
+
+			with_on_offence_fractions(|f| {
+				assert_eq!(f.clone(), vec![Perbill::from_percent(5)]);
+			});
+			assert_eq!(offenders(), vec![vec![5], vec![5]]);
+		});
+	}
+
+	#[test]
+	fn should_reslash_all_concurrent_offenders() {
+		with_externalities(&mut new_test_ext(), || {
+			Offences::report_offence(vec![], offence(42, vec![5]));
+			// A second offender in the same slot; the first one is already known.
+			Offences::report_offence(vec![], offence(42, vec![5, 6]));
+
+			with_on_offence_fractions(|f| {
+				assert_eq!(f.clone(), vec![Perbill::from_percent(10), Perbill::from_percent(10)]);
+			});
+			assert_eq!(offenders(), vec![vec![5], vec![5, 6]]);
+		});
+	}
+
+	#[test]
+	fn should_keep_the_first_reporters() {
+		with_externalities(&mut new_test_ext(), || {
+			Offences::report_offence(vec![1], offence(42, vec![5]));
+			Offences::report_offence(vec![2], offence(42, vec![5, 6]));
+
+			let details = <ConcurrentReportsIndex<Test>>::get(&KIND, &(0u32, 42u128).encode())
+				.into_iter()
+				.filter_map(|id| Offences::reports(id))
+				.collect::<Vec<_>>();
+			assert_eq!(details, vec![
+				OffenceDetails { offender: 5, reporters: vec![1] },
+				OffenceDetails { offender: 6, reporters: vec![2] },
+			]);
+		});
+	}
+
+	#[test]
+	fn should_deposit_event() {
+		with_externalities(&mut new_test_ext(), || {
+			Offences::report_offence(vec![], offence(42, vec![5]));
+
+			assert_eq!(
+				System::events().into_iter().map(|r| r.event).collect::<Vec<_>>(),
+				vec![TestEvent::offences(Event::Offence(KIND, 42u128.encode()))]
+			);
+		});
+	}
+
+	#[test]
+	fn should_report_in_different_sessions() {
+		with_externalities(&mut new_test_ext(), || {
+			Offences::report_offence(vec![], offence(42, vec![5]));
+			// The same time slot in another session is another time.
+			Offences::report_offence(vec![], TestOffence { session_index: 1, ..offence(42, vec![5]) });
+			assert_eq!(offenders(), vec![vec![5], vec![5]]);
+		});
+	}
+
+	#[test]
+	fn should_prune_reports_past_the_slashing_window() {
+		with_externalities(&mut new_test_ext(), || {
+			Offences::report_offence(vec![], offence(42, vec![5]));
+			Offences::report_offence(vec![], offence(43, vec![6]));
+			let opaque_time_slot = (0u32, 42u128).encode();
+			let report_id = <ConcurrentReportsIndex<Test>>::get(&KIND, &opaque_time_slot)[0];
+
+			// Session 0 is still within the window of session 2.
+			set_current_session(2);
+			Offences::on_initialize(1);
+			assert!(<Reports<Test>>::exists(&report_id));
+			assert_eq!(Offences::oldest_unpruned_session(), 0);
+
+			set_current_session(3);
+			Offences::on_initialize(2);
+			assert!(!<Reports<Test>>::exists(&report_id));
+			assert!(!<ConcurrentReportsIndex<Test>>::exists(&KIND, &opaque_time_slot));
+			assert!(!<ConcurrentReportsIndex<Test>>::exists(&KIND, &(0u32, 43u128).encode()));
+			assert!(!<SessionReports>::exists(&0));
+			assert_eq!(Offences::oldest_unpruned_session(), 1);
+
+			// Offences of a pruned session are not accepted anymore.
+			Offences::report_offence(vec![], offence(42, vec![5]));
+			assert_eq!(offenders(), vec![vec![5], vec![6]]);
+			assert!(!<ConcurrentReportsIndex<Test>>::exists(&KIND, &opaque_time_slot));
+		});
+	}
+}
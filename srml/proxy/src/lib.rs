@@ -0,0 +1,592 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Proxy Module
+//!
+//! A module allowing accounts to give permission to other accounts to dispatch types of calls
+//! from their signed origin, and to dispatch several calls in one go.
+//!
+//! - [`proxy::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! An account (the _delegator_) may register any number of _proxies_, each with a
+//! [`ProxyType`](./enum.ProxyType.html) restricting which calls it may make on the delegator's
+//! behalf. This lets a stash key stay cold while a hot key performs the everyday actions that
+//! the proxy type permits. Registering a proxy reserves a deposit from the delegator, which is
+//! returned when the proxy is removed.
+//!
+//! Which calls each proxy type permits is decided by the runtime through the
+//! [`ProxyFilter`](./trait.ProxyFilter.html) trait, since only the runtime knows its full `Call`
+//! type.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `add_proxy` - Register a proxy account for the sender.
+//! - `remove_proxy` - Unregister a proxy account for the sender.
+//! - `remove_proxies` - Unregister all proxy accounts for the sender.
+//! - `proxy` - Dispatch a call on behalf of a delegator for which the sender is a proxy.
+//! - `batch_until_failure` - Dispatch a sequence of calls from the sender, stopping at the first
+//!   failure.
+//!
+//! ### Batches are not atomic
+//!
+//! Unlike an all-or-nothing `batch`, `batch_until_failure` keeps the effects of the calls
+//! dispatched before the failing one. The runtime cannot roll back storage changes made by a
+//! successful dispatch, so an atomic batch could only be offered by checking every call up front,
+//! which is not possible for arbitrary calls. The name of the call makes this explicit, and the
+//! events it deposits tell exactly which calls took effect.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use parity_codec::{Encode, Decode};
+use srml_support::{
+	StorageMap, Parameter, decl_module, decl_storage, decl_event, ensure,
+	traits::{Currency, ReservableCurrency, Get},
+	dispatch::{Dispatchable, GetDispatchInfo, TransactionWeight},
+};
+use runtime_primitives::traits::Zero;
+use system::ensure_signed;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// The kinds of calls a proxy may make on behalf of its delegator.
+#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum ProxyType {
+	/// Any call at all.
+	Any,
+	/// Any call that cannot move funds out of the delegator's account.
+	NonTransfer,
+	/// Calls of the governance modules (e.g. voting in `democracy` or `council`).
+	Governance,
+	/// Calls of the `staking` module.
+	Staking,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self {
+		ProxyType::Any
+	}
+}
+
+/// Decides which calls a proxy of a given type may dispatch.
+///
+/// Implementations must take care with calls that wrap further calls (such as `proxy`,
+/// `batch_until_failure` or `sudo`): allowing them allows whatever they wrap.
+pub trait ProxyFilter<Call> {
+	/// Whether a proxy of type `proxy_type` may dispatch `call`.
+	fn filter(proxy_type: ProxyType, call: &Call) -> bool;
+}
+
+/// Only `ProxyType::Any` may dispatch anything; all other proxy types may dispatch nothing.
+impl<Call> ProxyFilter<Call> for () {
+	fn filter(proxy_type: ProxyType, _call: &Call) -> bool {
+		proxy_type == ProxyType::Any
+	}
+}
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The overarching call type.
+	type Call: Parameter + Dispatchable<Origin=Self::Origin> + GetDispatchInfo;
+
+	/// The currency mechanism.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// Which calls each proxy type may dispatch.
+	type ProxyFilter: ProxyFilter<<Self as Trait>::Call>;
+
+	/// The base amount of currency needed to reserve for having any proxies.
+	type ProxyDepositBase: Get<BalanceOf<Self>>;
+
+	/// The amount of currency needed to reserve per registered proxy.
+	type ProxyDepositFactor: Get<BalanceOf<Self>>;
+
+	/// The maximum amount of proxies allowed for a single account.
+	type MaxProxies: Get<u16>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Proxy {
+		/// The set of account proxies, together with the amount held in reserve for them. The
+		/// list of proxies is always sorted.
+		pub Proxies get(proxies): map T::AccountId => (Vec<(T::AccountId, ProxyType)>, BalanceOf<T>);
+	}
+}
+
+decl_event!(
+	/// Events type.
+	pub enum Event<T> where AccountId = <T as system::Trait>::AccountId {
+		/// A proxy was registered. First param is the delegator, second the proxy.
+		ProxyAdded(AccountId, AccountId, ProxyType),
+		/// A proxy was unregistered. First param is the delegator, second the proxy.
+		ProxyRemoved(AccountId, AccountId, ProxyType),
+		/// All proxies of the account were unregistered and its whole deposit was returned.
+		ProxiesRemoved(AccountId),
+		/// A call was dispatched by a proxy; the param is whether it succeeded.
+		ProxyExecuted(bool),
+		/// An item of a batch was dispatched successfully; the param is its index.
+		BatchItemCompleted(u32),
+		/// An item of a batch failed, so the remainder of the batch was not dispatched. The
+		/// params are the index of the failed item and its error.
+		BatchInterrupted(u32, Vec<u8>),
+		/// All items of a batch were dispatched successfully.
+		BatchCompleted,
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event<T>() = default;
+
+		/// The base amount of currency needed to reserve for having any proxies.
+		const ProxyDepositBase: BalanceOf<T> = T::ProxyDepositBase::get();
+
+		/// The amount of currency needed to reserve per registered proxy.
+		const ProxyDepositFactor: BalanceOf<T> = T::ProxyDepositFactor::get();
+
+		/// The maximum amount of proxies allowed for a single account.
+		const MaxProxies: u16 = T::MaxProxies::get();
+
+		/// Register a proxy account for the sender that is able to make calls on its behalf.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `proxy`: The account that the sender would like to make a proxy.
+		/// - `proxy_type`: The permissions allowed for this proxy account.
+		///
+		/// # <weight>
+		/// - `O(P)` where `P` is the number of proxies the sender has.
+		/// - One balance-reserve operation.
+		/// - One storage read and one storage write.
+		/// - One event.
+		/// # </weight>
+		fn add_proxy(origin, proxy: T::AccountId, proxy_type: ProxyType) {
+			let who = ensure_signed(origin)?;
+			let (mut proxies, deposit) = Self::proxies(&who);
+			ensure!(proxies.len() < T::MaxProxies::get() as usize, "too many proxies");
+			let entry = (proxy.clone(), proxy_type);
+			let pos = proxies.binary_search(&entry).err().ok_or("proxy already registered")?;
+			proxies.insert(pos, entry);
+
+			let new_deposit = Self::deposit(proxies.len());
+			if new_deposit > deposit {
+				T::Currency::reserve(&who, new_deposit - deposit)
+					.map_err(|_| "not enough free funds to reserve the deposit")?;
+			}
+			<Proxies<T>>::insert(&who, (proxies, new_deposit));
+
+			Self::deposit_event(RawEvent::ProxyAdded(who, proxy, proxy_type));
+		}
+
+		/// Unregister a proxy account for the sender.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `proxy`: The account that the sender would like to remove as a proxy.
+		/// - `proxy_type`: The permissions currently enabled for the removed proxy account.
+		///
+		/// # <weight>
+		/// - `O(P)` where `P` is the number of proxies the sender has.
+		/// - One balance-unreserve operation.
+		/// - One storage read and one storage write or removal.
+		/// - One event.
+		/// # </weight>
+		fn remove_proxy(origin, proxy: T::AccountId, proxy_type: ProxyType) {
+			let who = ensure_signed(origin)?;
+			let (mut proxies, deposit) = Self::proxies(&who);
+			let entry = (proxy.clone(), proxy_type);
+			let pos = proxies.binary_search(&entry).map_err(|_| "proxy not registered")?;
+			proxies.remove(pos);
+
+			let new_deposit = Self::deposit(proxies.len());
+			T::Currency::unreserve(&who, deposit - new_deposit);
+			if proxies.is_empty() {
+				<Proxies<T>>::remove(&who);
+			} else {
+				<Proxies<T>>::insert(&who, (proxies, new_deposit));
+			}
+
+			Self::deposit_event(RawEvent::ProxyRemoved(who, proxy, proxy_type));
+		}
+
+		/// Unregister all proxy accounts for the sender and return the whole deposit.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// # <weight>
+		/// - `O(P)` where `P` is the number of proxies the sender has.
+		/// - One balance-unreserve operation.
+		/// - One storage removal.
+		/// # </weight>
+		fn remove_proxies(origin) {
+			let who = ensure_signed(origin)?;
+			let (_, deposit) = <Proxies<T>>::take(&who);
+			T::Currency::unreserve(&who, deposit);
+			Self::deposit_event(RawEvent::ProxiesRemoved(who));
+		}
+
+		/// Dispatch the given `call` from an account that the sender is authorised for through
+		/// `add_proxy`.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `real`: The account that the proxy will make a call on behalf of.
+		/// - `force_proxy_type`: Specify the exact proxy type to be used and checked for this
+		/// call.
+		/// - `call`: The call to be made by the `real` account.
+		///
+		/// # <weight>
+		/// - `O(P)` where `P` is the number of proxies `real` has.
+		/// - One storage read.
+		/// - One event.
+		/// - The weight of the `call`.
+		/// # </weight>
+		#[weight = TransactionWeight::Basic(call.get_dispatch_info(0).weight.saturating_add(1000), 1)]
+		fn proxy(origin,
+			real: T::AccountId,
+			force_proxy_type: Option<ProxyType>,
+			call: Box<<T as Trait>::Call>
+		) {
+			let who = ensure_signed(origin)?;
+			let (proxies, _) = Self::proxies(&real);
+			let allowed = proxies.iter()
+				.filter(|(delegate, proxy_type)| delegate == &who
+					&& force_proxy_type.map_or(true, |t| t == *proxy_type)
+				)
+				.any(|(_, proxy_type)| T::ProxyFilter::filter(*proxy_type, &call));
+			ensure!(allowed, "not a proxy for this call");
+
+			let ok = call.dispatch(system::RawOrigin::Signed(real).into()).is_ok();
+			Self::deposit_event(RawEvent::ProxyExecuted(ok));
+		}
+
+		/// Dispatch a batch of calls from the sender, in order.
+		///
+		/// Dispatching stops at the first call which fails; calls dispatched before it are not
+		/// reverted, as the runtime has no way to roll back their storage changes. Callers needing
+		/// all-or-nothing semantics must not rely on this call. An event is deposited for every
+		/// call that succeeds, and a final one either for the completion or the interruption of
+		/// the batch.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `calls`: The calls to be dispatched from the same origin.
+		///
+		/// # <weight>
+		/// - `O(C)` where `C` is the number of calls.
+		/// - One event per call, plus one.
+		/// - The weight of the `calls`.
+		/// # </weight>
+		#[weight = TransactionWeight::Basic(
+			calls.iter().fold(1000, |weight, call| weight.saturating_add(call.get_dispatch_info(0).weight)),
+			1,
+		)]
+		fn batch_until_failure(origin, calls: Vec<<T as Trait>::Call>) {
+			let who = ensure_signed(origin)?;
+			for (index, call) in calls.into_iter().enumerate() {
+				let index = index as u32;
+				if let Err(e) = call.dispatch(system::RawOrigin::Signed(who.clone()).into()) {
+					Self::deposit_event(RawEvent::BatchInterrupted(index, e.as_bytes().to_vec()));
+					return Ok(());
+				}
+				Self::deposit_event(RawEvent::BatchItemCompleted(index));
+			}
+			Self::deposit_event(RawEvent::BatchCompleted);
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Whether `delegate` is registered as a proxy of `real` with the given type.
+	pub fn is_proxy(real: &T::AccountId, delegate: &T::AccountId, proxy_type: ProxyType) -> bool {
+		Self::proxies(real).0.binary_search(&(delegate.clone(), proxy_type)).is_ok()
+	}
+
+	/// The deposit held for an account with `count` proxies.
+	fn deposit(count: usize) -> BalanceOf<T> {
+		if count == 0 {
+			Zero::zero()
+		} else {
+			T::ProxyDepositBase::get() + T::ProxyDepositFactor::get() * (count as u32).into()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use runtime_io::with_externalities;
+	use srml_support::{
+		assert_ok, assert_noop, impl_outer_origin, impl_outer_dispatch, impl_outer_event, parameter_types,
+	};
+	use substrate_primitives::{H256, Blake2Hasher};
+	use runtime_primitives::{
+		Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header, weights::Weight,
+	};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	mod proxy {
+		pub use crate::Event;
+	}
+
+	impl_outer_event! {
+		pub enum TestEvent for Test {
+			balances<T>, proxy<T>,
+		}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			balances::Balances,
+			proxy::Proxy,
+		}
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = TestEvent;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
+	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 0;
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const TransactionBaseFee: u64 = 0;
+		pub const TransactionByteFee: u64 = 0;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = TestEvent;
+		type TransactionPayment = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+		type TransactionBaseFee = TransactionBaseFee;
+		type TransactionByteFee = TransactionByteFee;
+		type WeightToFee = ();
+	}
+	parameter_types! {
+		pub const ProxyDepositBase: u64 = 1;
+		pub const ProxyDepositFactor: u64 = 1;
+		pub const MaxProxies: u16 = 2;
+	}
+	pub struct TestProxyFilter;
+	impl ProxyFilter<Call> for TestProxyFilter {
+		fn filter(proxy_type: ProxyType, call: &Call) -> bool {
+			match (proxy_type, call) {
+				(ProxyType::Any, _) => true,
+				// Nested calls could smuggle a transfer through.
+				(ProxyType::NonTransfer, Call::Proxy(_)) => false,
+				(ProxyType::NonTransfer, Call::Balances(balances::Call::transfer(..))) => false,
+				(ProxyType::NonTransfer, _) => true,
+				_ => false,
+			}
+		}
+	}
+	impl Trait for Test {
+		type Event = TestEvent;
+		type Call = Call;
+		type Currency = Balances;
+		type ProxyFilter = TestProxyFilter;
+		type ProxyDepositBase = ProxyDepositBase;
+		type ProxyDepositFactor = ProxyDepositFactor;
+		type MaxProxies = MaxProxies;
+	}
+	type System = system::Module<Test>;
+	type Balances = balances::Module<Test>;
+	type Proxy = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap().0;
+		t.extend(balances::GenesisConfig::<Test>{
+			balances: vec![(1, 10), (2, 10), (3, 10), (4, 10), (5, 2)],
+			vesting: vec![],
+		}.build_storage().unwrap().0);
+		t.into()
+	}
+
+	fn last_event() -> TestEvent {
+		System::events().pop().expect("an event was deposited").event
+	}
+
+	fn proxy_events() -> Vec<Event<Test>> {
+		System::events().into_iter().filter_map(|r| match r.event {
+			TestEvent::proxy(e) => Some(e),
+			_ => None,
+		}).collect()
+	}
+
+	#[test]
+	fn add_and_remove_proxies_reserves_deposit() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any));
+			assert_eq!(Balances::reserved_balance(&1), 2);
+			assert_noop!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any), "proxy already registered");
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::NonTransfer));
+			assert_eq!(Balances::reserved_balance(&1), 3);
+			assert_noop!(Proxy::add_proxy(Origin::signed(1), 4, ProxyType::Any), "too many proxies");
+			assert!(Proxy::is_proxy(&1, &3, ProxyType::NonTransfer));
+			assert!(!Proxy::is_proxy(&1, &3, ProxyType::Any));
+
+			assert_ok!(Proxy::remove_proxy(Origin::signed(1), 2, ProxyType::Any));
+			assert_eq!(Balances::reserved_balance(&1), 2);
+			assert_noop!(Proxy::remove_proxy(Origin::signed(1), 2, ProxyType::Any), "proxy not registered");
+			assert_ok!(Proxy::remove_proxy(Origin::signed(1), 3, ProxyType::NonTransfer));
+			assert_eq!(Balances::reserved_balance(&1), 0);
+			assert!(!<Proxies<Test>>::exists(&1));
+		});
+	}
+
+	#[test]
+	fn remove_proxies_returns_whole_deposit() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any));
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Staking));
+			assert_ok!(Proxy::remove_proxies(Origin::signed(1)));
+			assert_eq!(Balances::reserved_balance(&1), 0);
+			assert_eq!(Balances::free_balance(&1), 10);
+			assert!(!Proxy::is_proxy(&1, &2, ProxyType::Any));
+			assert_eq!(last_event(), TestEvent::proxy(RawEvent::ProxiesRemoved(1)));
+		});
+	}
+
+	#[test]
+	fn deposit_must_be_affordable() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Proxy::add_proxy(Origin::signed(5), 2, ProxyType::Any));
+			assert_noop!(
+				Proxy::add_proxy(Origin::signed(5), 3, ProxyType::Any),
+				"not enough free funds to reserve the deposit"
+			);
+		});
+	}
+
+	#[test]
+	fn proxy_dispatches_as_delegator_within_filter() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any));
+			assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::NonTransfer));
+
+			let call = Box::new(Call::Balances(balances::Call::transfer(6, 1)));
+			assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, call.clone()));
+			assert_eq!(last_event(), TestEvent::proxy(RawEvent::ProxyExecuted(true)));
+			assert_eq!(Balances::free_balance(&6), 1);
+
+			assert_noop!(Proxy::proxy(Origin::signed(3), 1, None, call.clone()), "not a proxy for this call");
+			assert_noop!(Proxy::proxy(Origin::signed(4), 1, None, call.clone()), "not a proxy for this call");
+			assert_noop!(
+				Proxy::proxy(Origin::signed(2), 1, Some(ProxyType::NonTransfer), call.clone()),
+				"not a proxy for this call"
+			);
+
+			// A non-transfer proxy cannot wrap a transfer in a batch.
+			let batch = Box::new(Call::Proxy(super::Call::batch_until_failure(vec![*call])));
+			assert_noop!(Proxy::proxy(Origin::signed(3), 1, None, batch), "not a proxy for this call");
+
+			// Permitted calls are dispatched even if they then fail.
+			let call = Box::new(Call::Balances(balances::Call::set_balance(6, 0, 0)));
+			assert_ok!(Proxy::proxy(Origin::signed(3), 1, None, call));
+			assert_eq!(last_event(), TestEvent::proxy(RawEvent::ProxyExecuted(false)));
+		});
+	}
+
+	#[test]
+	fn weight_includes_the_weight_of_the_calls() {
+		let call = Call::Balances(balances::Call::transfer(2, 5));
+		let proxy = |call| Call::Proxy(super::Call::proxy(1, None, Box::new(call)));
+		let batch = |calls| Call::Proxy(super::Call::batch_until_failure(calls));
+		assert_eq!(call.get_dispatch_info(0).weight, 0);
+		assert_eq!(proxy(call.clone()).get_dispatch_info(0).weight, 1000);
+		assert_eq!(proxy(proxy(call.clone())).get_dispatch_info(0).weight, 2000);
+		assert_eq!(batch(vec![]).get_dispatch_info(0).weight, 1000);
+		assert_eq!(batch(vec![proxy(call.clone()), proxy(call.clone())]).get_dispatch_info(0).weight, 3000);
+		// The length of the inner calls is only charged once, as part of the outer call.
+		assert_eq!(proxy(call).get_dispatch_info(10).weight, 1010);
+	}
+
+	#[test]
+	fn batch_until_failure_reports_each_item() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Proxy::batch_until_failure(Origin::signed(1), vec![
+				Call::Balances(balances::Call::transfer(2, 5)),
+				Call::Balances(balances::Call::transfer(3, 5)),
+			]));
+			assert_eq!(Balances::free_balance(&1), 0);
+			assert_eq!(Balances::free_balance(&2), 15);
+			assert_eq!(Balances::free_balance(&3), 15);
+			assert_eq!(proxy_events(), vec![
+				RawEvent::BatchItemCompleted(0),
+				RawEvent::BatchItemCompleted(1),
+				RawEvent::BatchCompleted,
+			]);
+		});
+	}
+
+	#[test]
+	fn batch_until_failure_keeps_calls_before_the_failure() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Proxy::batch_until_failure(Origin::signed(1), vec![
+				Call::Balances(balances::Call::transfer(2, 5)),
+				Call::Balances(balances::Call::transfer(3, 50)),
+				Call::Balances(balances::Call::transfer(4, 5)),
+			]));
+			// The first transfer is not reverted by the failure of the second.
+			assert_eq!(Balances::free_balance(&1), 5);
+			assert_eq!(Balances::free_balance(&2), 15);
+			assert_eq!(Balances::free_balance(&3), 10);
+			assert_eq!(Balances::free_balance(&4), 10);
+			let events = proxy_events();
+			assert_eq!(events[0], RawEvent::BatchItemCompleted(0));
+			match &events[1] {
+				RawEvent::BatchInterrupted(1, _) => {},
+				e => panic!("unexpected event {:?}", e),
+			}
+			assert_eq!(events.len(), 2);
+		});
+	}
+}
@@ -0,0 +1,683 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Scheduler Module
+//!
+//! A module for scheduling dispatches.
+//!
+//! - [`scheduler::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//! - [`Module`](./struct.Module.html)
+//!
+//! ## Overview
+//!
+//! This module exposes capabilities for scheduling dispatches to occur at a specified block
+//! number or at a specified period. These scheduled dispatches may be named or anonymous and may
+//! be canceled.
+//!
+//! Scheduled dispatches are made from the root origin at the beginning of the block in which they
+//! are due. Tasks are dispatched in order of priority, and the total weight dispatched in a single
+//! block is bounded by `MaximumWeight`. Tasks whose priority is no greater than `HARD_DEADLINE` are
+//! always dispatched in the block they ask for; any other task that would take the block over the
+//! limit is deferred to the following block. The first task of a block is always dispatched, so
+//! that a single task heavier than the limit cannot stall the agenda forever.
+//!
+//! A task keeps the address it was given when scheduled for as long as it lives, even when it is
+//! deferred or repeats periodically, so it can always be canceled by that address.
+//!
+//! Other modules may schedule dispatches without going through an extrinsic by way of the
+//! [`schedule::Anon`](../srml_support/traits/schedule/trait.Anon.html) and
+//! [`schedule::Named`](../srml_support/traits/schedule/trait.Named.html) traits.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `schedule` - schedule a dispatch, which may be periodic, to occur at a specified block and
+//! with a specified priority.
+//! - `cancel` - cancel a scheduled dispatch, specified by block number and index.
+//! - `schedule_named` - augments the `schedule` interface with an additional `Vec<u8>` parameter
+//! that can be used for identification.
+//! - `cancel_named` - the named complement to the cancel function.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use parity_codec::{Encode, Decode};
+use srml_support::{
+	StorageMap, Parameter, decl_module, decl_storage, decl_event, ensure,
+	traits::{Get, EnsureOrigin, schedule::{self, Period, Priority, HARD_DEADLINE}},
+	dispatch::{Dispatchable, Weighable, Weight},
+};
+use runtime_primitives::traits::{One, Zero, Saturating};
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The aggregated call type.
+	type Call: Parameter + Dispatchable<Origin=<Self as system::Trait>::Origin> + Weighable;
+
+	/// The maximum weight that may be scheduled per block for any dispatchables of less priority
+	/// than `HARD_DEADLINE`.
+	type MaximumWeight: Get<Weight>;
+
+	/// Required origin to schedule or cancel calls.
+	type ScheduleOrigin: EnsureOrigin<<Self as system::Trait>::Origin>;
+}
+
+/// Just a simple index for naming period tasks.
+pub type PeriodicIndex = u32;
+/// The location of a scheduled task that can be used to remove it.
+pub type TaskAddress<BlockNumber> = (BlockNumber, u32);
+
+/// Information regarding an item to be executed in the future.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Scheduled<Call, BlockNumber> {
+	/// The unique identity for this task, if there is one.
+	pub maybe_id: Option<Vec<u8>>,
+	/// The address the task was given when it was scheduled, by which it may be canceled
+	/// wherever it has moved since.
+	pub address: TaskAddress<BlockNumber>,
+	/// This task's priority.
+	pub priority: Priority,
+	/// The call to be dispatched.
+	pub call: Call,
+	/// If the call is periodic, then this points to the information concerning that. The count is
+	/// the number of further dispatches still to come after the next one.
+	pub maybe_periodic: Option<Period<BlockNumber>>,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Scheduler {
+		/// Items to be executed, indexed by the block number that they should be executed on.
+		pub Agenda get(agenda): map T::BlockNumber
+			=> Vec<Option<Scheduled<<T as Trait>::Call, T::BlockNumber>>>;
+
+		/// Lookup from identity to the block number and index of the task.
+		Lookup: map Vec<u8> => Option<TaskAddress<T::BlockNumber>>;
+
+		/// Lookup from the address a task was given when it was scheduled to its current address,
+		/// for tasks that have been deferred or rescheduled for their next period.
+		Moved: map TaskAddress<T::BlockNumber> => Option<TaskAddress<T::BlockNumber>>;
+	}
+}
+
+decl_event!(
+	/// Events type.
+	pub enum Event<T> where BlockNumber = <T as system::Trait>::BlockNumber {
+		/// A task has been scheduled at the given block number and index.
+		Scheduled(BlockNumber, u32),
+		/// The task scheduled at the given block number and index has been canceled.
+		Canceled(BlockNumber, u32),
+		/// A scheduled task, given by the address it was scheduled with, has been dispatched. The
+		/// last parameter is whether it succeeded.
+		Dispatched(TaskAddress<BlockNumber>, Option<Vec<u8>>, bool),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: <T as system::Trait>::Origin {
+		fn deposit_event<T>() = default;
+
+		/// The maximum weight that may be dispatched per block by tasks of less priority than
+		/// `HARD_DEADLINE`.
+		const MaximumWeight: Weight = T::MaximumWeight::get();
+
+		/// Anonymously schedule a task.
+		///
+		/// The origin for this call must be `ScheduleOrigin`.
+		///
+		/// - `when`: The block at which the task should be dispatched. A block which is not in the
+		/// future is taken to mean the next block.
+		/// - `maybe_periodic`: If `Some`, the length of the period and the total number of times the
+		/// task should be dispatched.
+		/// - `priority`: The priority of the task. Lower values are dispatched first.
+		/// - `call`: The call to dispatch from the root origin.
+		///
+		/// Emits `Scheduled`.
+		fn schedule(origin,
+			when: T::BlockNumber,
+			maybe_periodic: Option<Period<T::BlockNumber>>,
+			priority: Priority,
+			call: Box<<T as Trait>::Call>
+		) {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			Self::do_schedule(when, maybe_periodic, priority, *call);
+		}
+
+		/// Cancel an anonymously scheduled task.
+		///
+		/// The origin for this call must be `ScheduleOrigin`.
+		///
+		/// Emits `Canceled`.
+		fn cancel(origin, when: T::BlockNumber, index: u32) {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			Self::do_cancel((when, index))?;
+		}
+
+		/// Schedule a named task.
+		///
+		/// The origin for this call must be `ScheduleOrigin`.
+		///
+		/// - `id`: The identity of the task. It must not already be in use by another task.
+		///
+		/// See `schedule` for the other parameters.
+		///
+		/// Emits `Scheduled`.
+		fn schedule_named(origin,
+			id: Vec<u8>,
+			when: T::BlockNumber,
+			maybe_periodic: Option<Period<T::BlockNumber>>,
+			priority: Priority,
+			call: Box<<T as Trait>::Call>
+		) {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			Self::do_schedule_named(id, when, maybe_periodic, priority, *call)?;
+		}
+
+		/// Cancel a named scheduled task.
+		///
+		/// The origin for this call must be `ScheduleOrigin`.
+		///
+		/// Emits `Canceled`.
+		fn cancel_named(origin, id: Vec<u8>) {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			Self::do_cancel_named(id)?;
+		}
+
+		fn on_initialize(now: T::BlockNumber) {
+			let limit = T::MaximumWeight::get();
+			let mut queued = <Agenda<T>>::take(now).into_iter()
+				.filter_map(|s| s)
+				.collect::<Vec<_>>();
+			queued.sort_by_key(|s| s.priority);
+
+			let next = now + One::one();
+			let mut total_weight: Weight = 0;
+			for (order, mut s) in queued.into_iter().enumerate() {
+				let call_weight = s.call.weight(s.call.encode().len());
+				let within_limit = total_weight.saturating_add(call_weight) <= limit;
+				if order > 0 && s.priority > HARD_DEADLINE && !within_limit {
+					// Out of room in this block; try again in the next one.
+					Self::enqueue(next, s);
+					continue;
+				}
+				total_weight = total_weight.saturating_add(call_weight);
+
+				let ok = s.call.clone().dispatch(system::RawOrigin::Root.into()).is_ok();
+				Self::deposit_event(RawEvent::Dispatched(s.address, s.maybe_id.clone(), ok));
+
+				if let Some((period, count)) = s.maybe_periodic {
+					s.maybe_periodic = if count > 1 { Some((period, count - 1)) } else { None };
+					Self::enqueue(now + period, s);
+				} else {
+					<Moved<T>>::remove(&s.address);
+					if let Some(ref id) = s.maybe_id {
+						<Lookup<T>>::remove(id);
+					}
+				}
+			}
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Normalise the period of a task into the form kept in storage: the count of dispatches still
+	/// to come after the first. A period which never repeats is dropped.
+	fn normalize_period(
+		maybe_periodic: Option<Period<T::BlockNumber>>
+	) -> Option<Period<T::BlockNumber>> {
+		maybe_periodic
+			.filter(|&(period, count)| !period.is_zero() && count > 1)
+			.map(|(period, count)| (period, count - 1))
+	}
+
+	/// The earliest block at which a newly scheduled task may be dispatched.
+	fn earliest(when: T::BlockNumber) -> T::BlockNumber {
+		let next = <system::Module<T>>::block_number().saturating_add(One::one());
+		if when < next { next } else { when }
+	}
+
+	/// The address a task would get if it were scheduled at block `when` now.
+	fn next_address(when: T::BlockNumber) -> TaskAddress<T::BlockNumber> {
+		(when, Self::agenda(when).len() as u32)
+	}
+
+	/// Place `s` at the end of the agenda of block `when`, keeping the lookup of a named task and
+	/// that of a moved task in sync, and return its new address.
+	fn enqueue(
+		when: T::BlockNumber,
+		s: Scheduled<<T as Trait>::Call, T::BlockNumber>,
+	) -> TaskAddress<T::BlockNumber> {
+		let maybe_id = s.maybe_id.clone();
+		let original = s.address;
+		let index = <Agenda<T>>::mutate(when, |agenda| {
+			agenda.push(Some(s));
+			agenda.len() as u32 - 1
+		});
+		if let Some(id) = maybe_id {
+			<Lookup<T>>::insert(id, (when, index));
+		}
+		if original != (when, index) {
+			<Moved<T>>::insert(original, (when, index));
+		}
+		(when, index)
+	}
+
+	fn do_schedule(
+		when: T::BlockNumber,
+		maybe_periodic: Option<Period<T::BlockNumber>>,
+		priority: Priority,
+		call: <T as Trait>::Call,
+	) -> TaskAddress<T::BlockNumber> {
+		let when = Self::earliest(when);
+		let s = Scheduled {
+			maybe_id: None,
+			address: Self::next_address(when),
+			priority,
+			call,
+			maybe_periodic: Self::normalize_period(maybe_periodic),
+		};
+		let address = Self::enqueue(when, s);
+		Self::deposit_event(RawEvent::Scheduled(address.0, address.1));
+		address
+	}
+
+	/// Cancel the task at `address`, which may be either the address it was given when scheduled
+	/// or its current one.
+	fn do_cancel(address: TaskAddress<T::BlockNumber>) -> Result<(), &'static str> {
+		let (when, index) = <Moved<T>>::get(&address).unwrap_or(address);
+		let scheduled = <Agenda<T>>::mutate(when, |agenda| {
+			agenda.get_mut(index as usize).and_then(Option::take)
+		});
+		let s = scheduled.ok_or("no scheduled task at the given address")?;
+		<Moved<T>>::remove(&s.address);
+		if let Some(id) = s.maybe_id {
+			<Lookup<T>>::remove(id);
+		}
+		Self::deposit_event(RawEvent::Canceled(s.address.0, s.address.1));
+		Ok(())
+	}
+
+	fn do_schedule_named(
+		id: Vec<u8>,
+		when: T::BlockNumber,
+		maybe_periodic: Option<Period<T::BlockNumber>>,
+		priority: Priority,
+		call: <T as Trait>::Call,
+	) -> Result<TaskAddress<T::BlockNumber>, &'static str> {
+		ensure!(!<Lookup<T>>::exists(&id), "a task with this id is already scheduled");
+		let when = Self::earliest(when);
+		let s = Scheduled {
+			maybe_id: Some(id),
+			address: Self::next_address(when),
+			priority,
+			call,
+			maybe_periodic: Self::normalize_period(maybe_periodic),
+		};
+		let address = Self::enqueue(when, s);
+		Self::deposit_event(RawEvent::Scheduled(address.0, address.1));
+		Ok(address)
+	}
+
+	fn do_cancel_named(id: Vec<u8>) -> Result<(), &'static str> {
+		let address = <Lookup<T>>::get(&id).ok_or("no scheduled task with the given id")?;
+		Self::do_cancel(address)
+	}
+}
+
+impl<T: Trait> schedule::Anon<T::BlockNumber, <T as Trait>::Call> for Module<T> {
+	type Address = TaskAddress<T::BlockNumber>;
+
+	fn schedule(
+		when: T::BlockNumber,
+		maybe_periodic: Option<Period<T::BlockNumber>>,
+		priority: Priority,
+		call: <T as Trait>::Call,
+	) -> Self::Address {
+		Self::do_schedule(when, maybe_periodic, priority, call)
+	}
+
+	fn cancel(address: Self::Address) -> Result<(), ()> {
+		Self::do_cancel(address).map_err(|_| ())
+	}
+}
+
+impl<T: Trait> schedule::Named<T::BlockNumber, <T as Trait>::Call> for Module<T> {
+	type Address = TaskAddress<T::BlockNumber>;
+
+	fn schedule_named(
+		id: Vec<u8>,
+		when: T::BlockNumber,
+		maybe_periodic: Option<Period<T::BlockNumber>>,
+		priority: Priority,
+		call: <T as Trait>::Call,
+	) -> Result<Self::Address, ()> {
+		Self::do_schedule_named(id, when, maybe_periodic, priority, call).map_err(|_| ())
+	}
+
+	fn cancel_named(id: Vec<u8>) -> Result<(), ()> {
+		Self::do_cancel_named(id).map_err(|_| ())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use runtime_io::with_externalities;
+	use srml_support::{
+		assert_ok, assert_noop, impl_outer_origin, impl_outer_dispatch, parameter_types,
+		traits::OnInitialize,
+	};
+	use substrate_primitives::{H256, Blake2Hasher};
+	use runtime_primitives::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use system::EnsureRoot;
+
+	mod logger {
+		use super::*;
+		use std::cell::RefCell;
+		use srml_support::dispatch::TransactionWeight;
+		use system::ensure_root;
+
+		thread_local! {
+			static LOG: RefCell<Vec<u32>> = RefCell::new(Vec::new());
+		}
+		pub fn log() -> Vec<u32> {
+			LOG.with(|log| log.borrow().clone())
+		}
+		pub trait Trait: system::Trait {}
+		decl_module! {
+			pub struct Module<T: Trait> for enum Call where origin: <T as system::Trait>::Origin {
+				fn log(origin, i: u32) {
+					ensure_root(origin)?;
+					LOG.with(|log| log.borrow_mut().push(i));
+				}
+
+				#[weight = TransactionWeight::Basic(1000, 0)]
+				fn log_heavy(origin, i: u32) {
+					ensure_root(origin)?;
+					LOG.with(|log| log.borrow_mut().push(i));
+				}
+			}
+		}
+	}
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			logger::Logger,
+		}
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
+	}
+	impl logger::Trait for Test {}
+	parameter_types! {
+		pub const MaximumWeight: Weight = 1500;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Call = Call;
+		type MaximumWeight = MaximumWeight;
+		type ScheduleOrigin = EnsureRoot<u64>;
+	}
+	type Logger = logger::Module<Test>;
+	type Scheduler = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		system::GenesisConfig::default().build_storage::<Test>().unwrap().0.into()
+	}
+
+	fn run_to_block(n: u64) {
+		while <system::Module<Test>>::block_number() < n {
+			let next = <system::Module<Test>>::block_number() + 1;
+			<system::Module<Test>>::set_block_number(next);
+			Scheduler::on_initialize(next);
+		}
+	}
+
+	fn log_call(i: u32) -> Call {
+		Call::Logger(logger::Call::log(i))
+	}
+
+	fn heavy_call(i: u32) -> Call {
+		Call::Logger(logger::Call::log_heavy(i))
+	}
+
+	#[test]
+	fn basic_scheduling_works() {
+		with_externalities(&mut new_test_ext(), || {
+			<Scheduler as schedule::Anon<_, _>>::schedule(4, None, 127, log_call(42));
+			run_to_block(3);
+			assert!(logger::log().is_empty());
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![42]);
+			assert!(Scheduler::agenda(4).is_empty());
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![42]);
+		});
+	}
+
+	#[test]
+	fn scheduling_requires_the_schedule_origin() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(
+				Scheduler::schedule(Origin::signed(1), 4, None, 127, Box::new(log_call(42))),
+				"Invalid origin"
+			);
+			assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, 127, Box::new(log_call(42))));
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![42]);
+		});
+	}
+
+	#[test]
+	fn scheduling_in_the_past_dispatches_in_the_next_block() {
+		with_externalities(&mut new_test_ext(), || {
+			run_to_block(3);
+			assert_eq!(<Scheduler as schedule::Anon<_, _>>::schedule(2, None, 127, log_call(42)), (4, 0));
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![42]);
+		});
+	}
+
+	#[test]
+	fn periodic_scheduling_works() {
+		with_externalities(&mut new_test_ext(), || {
+			// at #4, every 3 blocks, 3 times.
+			<Scheduler as schedule::Anon<_, _>>::schedule(4, Some((3, 3)), 127, log_call(42));
+			run_to_block(3);
+			assert!(logger::log().is_empty());
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![42]);
+			run_to_block(6);
+			assert_eq!(logger::log(), vec![42]);
+			run_to_block(7);
+			assert_eq!(logger::log(), vec![42, 42]);
+			run_to_block(10);
+			assert_eq!(logger::log(), vec![42, 42, 42]);
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![42, 42, 42]);
+		});
+	}
+
+	#[test]
+	fn cancel_works() {
+		with_externalities(&mut new_test_ext(), || {
+			let address = <Scheduler as schedule::Anon<_, _>>::schedule(4, None, 127, log_call(69));
+			<Scheduler as schedule::Anon<_, _>>::schedule(4, None, 127, log_call(42));
+			assert_ok!(Scheduler::cancel(Origin::ROOT, address.0, address.1));
+			assert_noop!(
+				Scheduler::cancel(Origin::ROOT, address.0, address.1),
+				"no scheduled task at the given address"
+			);
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![42]);
+		});
+	}
+
+	#[test]
+	fn named_scheduling_and_cancel_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Scheduler::schedule_named(
+				Origin::ROOT, b"alpha".to_vec(), 4, Some((3, 3)), 127, Box::new(log_call(69))
+			));
+			assert_noop!(
+				Scheduler::schedule_named(
+					Origin::ROOT, b"alpha".to_vec(), 5, None, 127, Box::new(log_call(42))
+				),
+				"a task with this id is already scheduled"
+			);
+			assert_ok!(Scheduler::schedule_named(
+				Origin::ROOT, b"beta".to_vec(), 4, None, 127, Box::new(log_call(42))
+			));
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![69, 42]);
+
+			// A finished named task releases its id.
+			assert!(!<Lookup<Test>>::exists(&b"beta".to_vec()));
+			// A periodic one follows its task to the next period.
+			assert_eq!(<Lookup<Test>>::get(&b"alpha".to_vec()), Some((7, 0)));
+
+			assert_ok!(Scheduler::cancel_named(Origin::ROOT, b"alpha".to_vec()));
+			assert_noop!(
+				Scheduler::cancel_named(Origin::ROOT, b"alpha".to_vec()),
+				"no scheduled task with the given id"
+			);
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![69, 42]);
+		});
+	}
+
+	#[test]
+	fn scheduler_respects_priority_ordering() {
+		with_externalities(&mut new_test_ext(), || {
+			<Scheduler as schedule::Anon<_, _>>::schedule(4, None, 1, log_call(42));
+			<Scheduler as schedule::Anon<_, _>>::schedule(4, None, 0, log_call(69));
+			<Scheduler as schedule::Anon<_, _>>::schedule(4, None, 255, log_call(2600));
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![69, 42, 2600]);
+		});
+	}
+
+	#[test]
+	fn scheduler_defers_tasks_over_the_weight_limit() {
+		with_externalities(&mut new_test_ext(), || {
+			<Scheduler as schedule::Anon<_, _>>::schedule(4, None, 127, heavy_call(42));
+			<Scheduler as schedule::Anon<_, _>>::schedule(4, None, 127, heavy_call(69));
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![42]);
+			run_to_block(5);
+			assert_eq!(logger::log(), vec![42, 69]);
+		});
+	}
+
+	#[test]
+	fn scheduler_always_dispatches_hard_deadlines() {
+		with_externalities(&mut new_test_ext(), || {
+			<Scheduler as schedule::Anon<_, _>>::schedule(4, None, HARD_DEADLINE, heavy_call(42));
+			<Scheduler as schedule::Anon<_, _>>::schedule(4, None, HARD_DEADLINE, heavy_call(69));
+			<Scheduler as schedule::Anon<_, _>>::schedule(4, None, 255, heavy_call(2600));
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![42, 69]);
+			run_to_block(5);
+			assert_eq!(logger::log(), vec![42, 69, 2600]);
+		});
+	}
+
+	#[test]
+	fn deferred_named_task_can_still_be_canceled() {
+		with_externalities(&mut new_test_ext(), || {
+			<Scheduler as schedule::Anon<_, _>>::schedule(4, None, 127, heavy_call(42));
+			assert_ok!(<Scheduler as schedule::Named<_, _>>::schedule_named(
+				b"beta".to_vec(), 4, None, 127, heavy_call(69)
+			));
+			run_to_block(4);
+			assert_eq!(<Lookup<Test>>::get(&b"beta".to_vec()), Some((5, 0)));
+			assert_ok!(<Scheduler as schedule::Named<_, _>>::cancel_named(b"beta".to_vec()));
+			run_to_block(5);
+			assert_eq!(logger::log(), vec![42]);
+		});
+	}
+
+	#[test]
+	fn deferred_task_can_be_canceled_by_its_address() {
+		with_externalities(&mut new_test_ext(), || {
+			<Scheduler as schedule::Anon<_, _>>::schedule(4, None, 127, heavy_call(42));
+			let address = <Scheduler as schedule::Anon<_, _>>::schedule(4, None, 127, heavy_call(69));
+			assert_eq!(address, (4, 1));
+			run_to_block(4);
+			assert_eq!(<Moved<Test>>::get(&address), Some((5, 0)));
+			assert_ok!(<Scheduler as schedule::Anon<_, _>>::cancel(address));
+			assert!(!<Moved<Test>>::exists(&address));
+			run_to_block(5);
+			assert_eq!(logger::log(), vec![42]);
+		});
+	}
+
+	#[test]
+	fn periodic_task_can_be_canceled_by_its_address() {
+		with_externalities(&mut new_test_ext(), || {
+			let address = <Scheduler as schedule::Anon<_, _>>::schedule(4, Some((3, 3)), 127, log_call(42));
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![42]);
+			assert_ok!(Scheduler::cancel(Origin::ROOT, address.0, address.1));
+			assert_noop!(
+				Scheduler::cancel(Origin::ROOT, address.0, address.1),
+				"no scheduled task at the given address"
+			);
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![42]);
+		});
+	}
+
+	#[test]
+	fn finished_tasks_leave_no_moved_address_behind() {
+		with_externalities(&mut new_test_ext(), || {
+			let address = <Scheduler as schedule::Anon<_, _>>::schedule(4, Some((3, 2)), 127, log_call(42));
+			run_to_block(4);
+			assert!(<Moved<Test>>::exists(&address));
+			run_to_block(7);
+			assert_eq!(logger::log(), vec![42, 42]);
+			assert!(!<Moved<Test>>::exists(&address));
+		});
+	}
+}
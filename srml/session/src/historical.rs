@@ -0,0 +1,477 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An opt-in utility for tracking historical sessions in SRML-session.
+//!
+//! This is generally useful when implementing blockchains that require accountable
+//! safety where validators from some amount f prior sessions must remain slashable.
+//!
+//! Rather than store the full session data for any given session, we instead commit
+//! to the roots of merkle tries containing the session data. A root is noted for every
+//! session when it starts, by wrapping the `OnSessionEnding` of the session module in
+//! [`NoteHistoricalRoot`](./struct.NoteHistoricalRoot.html).
+//!
+//! Proofs of inclusion can be generated at any time during the session, and are checked
+//! against the stored roots afterwards, e.g. by a consensus module when reporting
+//! misbehavior. Roots are kept until [`prune_up_to`](./struct.Module.html#method.prune_up_to)
+//! is called, typically by the staking module once the bonding period of a session is over.
+
+use rstd::prelude::*;
+use parity_codec::{Encode, Decode};
+use primitives::traits::{Convert, OpaqueKeys};
+use srml_support::{
+	StorageValue, StorageMap, decl_module, decl_storage, Parameter, print,
+	traits::KeyOwnerProofSystem,
+};
+use substrate_trie::{MemoryDB, Trie, TrieMut, TrieDBMut, TrieDB, Recorder, HashDBT};
+use super::{SessionIndex, Module as SessionModule, NextKeyFor};
+
+type ValidatorCount = u32;
+
+type HasherOf<T> = <<T as system::Trait>::Hashing as primitives::traits::Hash>::Hasher;
+
+/// Trait necessary for the historical module.
+pub trait Trait: super::Trait {
+	/// Full identification of the validator.
+	type FullIdentification: Parameter;
+
+	/// A conversion from validator ID to full identification.
+	///
+	/// This should contain any references to economic actors associated with the
+	/// validator, since they may be outdated by the time this is queried from a
+	/// historical trie.
+	///
+	/// It must return the identification with which the validator should be known for a session
+	/// once the `OnSessionEnding` handler of the previous session has been called.
+	type FullIdentificationOf: Convert<Self::AccountId, Option<Self::FullIdentification>>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Session {
+		/// Mapping from historical session indices to session-data root hash and validator count.
+		HistoricalSessions get(historical_root): map SessionIndex => Option<(T::Hash, ValidatorCount)>;
+		/// The range of historical sessions we store. [first, last)
+		StoredRange: Option<(SessionIndex, SessionIndex)>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin { }
+}
+
+impl<T: Trait> Module<T> {
+	/// Prune historical stored session roots up to (but not including)
+	/// `up_to`.
+	pub fn prune_up_to(up_to: SessionIndex) {
+		<Self as Store>::StoredRange::mutate(|range| {
+			let (start, end) = match *range {
+				Some(range) => range,
+				None => return, // nothing to prune.
+			};
+
+			let up_to = rstd::cmp::min(up_to, end);
+
+			if up_to < start {
+				return // out of bounds. harmless.
+			}
+
+			for session in start..up_to {
+				<Self as Store>::HistoricalSessions::remove(session);
+			}
+
+			let new_start = up_to;
+			*range = if new_start == end {
+				None // nothing is stored.
+			} else {
+				Some((new_start, end))
+			}
+		})
+	}
+
+	/// The identifications of the given validators, skipping those without one.
+	fn full_identifications(validators: Vec<T::AccountId>) -> Vec<IdentificationTuple<T>> {
+		validators.into_iter()
+			.filter_map(|validator| T::FullIdentificationOf::convert(validator.clone())
+				.map(|full_id| (validator, full_id))
+			)
+			.collect()
+	}
+
+	/// Commit to the validator set of the session `index`.
+	fn note_root(index: SessionIndex, validators: Vec<T::AccountId>) {
+		let validators = Self::full_identifications(validators);
+		let count = validators.len() as ValidatorCount;
+
+		match ProvingTrie::<T>::generate_for(validators) {
+			Ok(trie) => {
+				<Self as Store>::HistoricalSessions::insert(index, &(trie.root, count));
+				<Self as Store>::StoredRange::mutate(|range| {
+					let start = range.map_or(index, |(start, _)| start);
+					*range = Some((start, index + 1));
+				});
+			}
+			Err(reason) => {
+				print("Failed to generate historical ancestry-inclusion proof.");
+				print(reason);
+			}
+		}
+	}
+}
+
+/// Wraps an `OnSessionEnding` implementation and commits to the validator set of every session
+/// that starts, with the keys it will use and their full identifications.
+pub struct NoteHistoricalRoot<T, I>(rstd::marker::PhantomData<(T, I)>);
+
+impl<T: Trait, I> super::OnSessionEnding<T::AccountId> for NoteHistoricalRoot<T, I>
+	where I: super::OnSessionEnding<T::AccountId>
+{
+	fn on_session_ending(ending: SessionIndex) -> Option<Vec<T::AccountId>> {
+		let new_validators = I::on_session_ending(ending);
+
+		let validators = new_validators.clone().unwrap_or_else(<SessionModule<T>>::validators);
+		<Module<T>>::note_root(ending + 1, validators);
+
+		new_validators
+	}
+}
+
+/// A tuple of the validator's ID and their full identification.
+pub type IdentificationTuple<T> = (<T as system::Trait>::AccountId, <T as Trait>::FullIdentification);
+
+/// A trie instance for checking and generating proofs.
+pub struct ProvingTrie<T: Trait> {
+	db: MemoryDB<HasherOf<T>>,
+	root: T::Hash,
+}
+
+impl<T: Trait> ProvingTrie<T> {
+	fn generate_for<I>(validators: I) -> Result<Self, &'static str>
+		where I: IntoIterator<Item=IdentificationTuple<T>>
+	{
+		let mut db = MemoryDB::default();
+		let mut root = Default::default();
+
+		{
+			let mut trie = TrieDBMut::new(&mut db, &mut root);
+			for (i, (validator, full_id)) in validators.into_iter().enumerate() {
+				let i = i as u32;
+				let keys = match <NextKeyFor<T>>::get(&validator) {
+					None => continue,
+					Some(k) => k,
+				};
+
+				let full_id = (validator, full_id);
+
+				// map each key to the owner index.
+				for key_index in 0..T::Keys::count() {
+					let key = keys.get_raw(key_index);
+					let res = (key_index as u32, key).using_encoded(|k|
+						i.using_encoded(|v| trie.insert(k, v))
+					);
+
+					let _ = res.map_err(|_| "failed to insert into trie")?;
+				}
+
+				// map each owner index to the full identification.
+				let _ = i.using_encoded(|k| full_id.using_encoded(|v| trie.insert(k, v)))
+					.map_err(|_| "failed to insert into trie")?;
+			}
+		}
+
+		Ok(ProvingTrie { db, root })
+	}
+
+	fn from_nodes(root: T::Hash, nodes: &[Vec<u8>]) -> Self {
+		let mut memory_db = MemoryDB::default();
+		for node in nodes {
+			HashDBT::insert(&mut memory_db, &[], &node[..]);
+		}
+
+		ProvingTrie { db: memory_db, root }
+	}
+
+	/// Prove the full verification data for a given key and key index.
+	pub fn prove(&self, key_index: u32, key_data: &[u8]) -> Option<Vec<Vec<u8>>> {
+		let trie = TrieDB::new(&self.db, &self.root).ok()?;
+		let mut recorder = Recorder::new();
+		let val_idx = (key_index, key_data).using_encoded(|s| {
+			trie.get_with(s, &mut recorder)
+				.ok()?
+				.and_then(|raw| u32::decode(&mut &*raw))
+		})?;
+
+		val_idx.using_encoded(|s| {
+			trie.get_with(s, &mut recorder)
+				.ok()?
+				.and_then(|raw| <IdentificationTuple<T>>::decode(&mut &*raw))
+		})?;
+
+		Some(recorder.drain().into_iter().map(|r| r.data.to_vec()).collect())
+	}
+
+	/// Access the underlying trie root.
+	pub fn root(&self) -> &T::Hash {
+		&self.root
+	}
+
+	// Check a proof contained within the current memory-db. Returns `None` if the
+	// nodes within the current `MemoryDB` are insufficient to query the item.
+	fn query(&self, key_index: u32, key_data: &[u8]) -> Option<IdentificationTuple<T>> {
+		let trie = TrieDB::new(&self.db, &self.root).ok()?;
+		let val_idx = (key_index, key_data).using_encoded(|s| trie.get(s))
+			.ok()?
+			.and_then(|raw| u32::decode(&mut &*raw))?;
+
+		val_idx.using_encoded(|s| trie.get(s))
+			.ok()?
+			.and_then(|raw| <IdentificationTuple<T>>::decode(&mut &*raw))
+	}
+}
+
+/// Proof of ownership of a specific key.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct MembershipProof {
+	/// The session the proof is for.
+	pub session: SessionIndex,
+	/// The trie nodes proving the ownership.
+	pub trie_nodes: Vec<Vec<u8>>,
+}
+
+/// Keys are identified by the index of the key within the session keys of the validator, as in
+/// `OpaqueKeys::get_raw`, and their raw encoding.
+impl<T: Trait, D: AsRef<[u8]>> KeyOwnerProofSystem<(u32, D)> for Module<T> {
+	type Proof = MembershipProof;
+	type FullIdentification = IdentificationTuple<T>;
+
+	/// The proof is generated from the current validator set and their next keys. If any of them
+	/// changed their keys in the course of the session, no proofs can be generated until the
+	/// next session.
+	fn prove(key: (u32, D)) -> Option<Self::Proof> {
+		let session = <SessionModule<T>>::current_index();
+		let (root, _) = Self::historical_root(session)?;
+
+		let validators = Self::full_identifications(<SessionModule<T>>::validators());
+		let trie = ProvingTrie::<T>::generate_for(validators).ok()?;
+		if trie.root() != &root {
+			return None
+		}
+
+		let (key_index, key_data) = key;
+		trie.prove(key_index, key_data.as_ref())
+			.map(|trie_nodes| MembershipProof { session, trie_nodes })
+	}
+
+	fn check_proof(key: (u32, D), proof: Self::Proof) -> Option<IdentificationTuple<T>> {
+		let (root, _) = Self::historical_root(proof.session)?;
+		let trie = ProvingTrie::<T>::from_nodes(root, &proof.trie_nodes);
+
+		let (key_index, key_data) = key;
+		trie.query(key_index, key_data.as_ref())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::cell::RefCell;
+	use srml_support::{impl_outer_origin, assert_ok, parameter_types};
+	use runtime_io::with_externalities;
+	use substrate_primitives::{H256, Blake2Hasher};
+	use primitives::{
+		Perbill, traits::{BlakeTwo256, IdentityLookup, OnInitialize}, testing::{Header, UintAuthorityId},
+		weights::Weight,
+	};
+	use crate::{GenesisConfig, ShouldEndSession, OnSessionEnding as _};
+
+	impl_outer_origin!{
+		pub enum Origin for Test {}
+	}
+
+	thread_local!{
+		static NEXT_VALIDATORS: RefCell<Vec<u64>> = RefCell::new(vec![1, 2, 3]);
+	}
+
+	pub struct TestShouldEndSession;
+	impl ShouldEndSession<u64> for TestShouldEndSession {
+		fn should_end_session(_: u64) -> bool {
+			true
+		}
+	}
+
+	pub struct TestOnSessionEnding;
+	impl crate::OnSessionEnding<u64> for TestOnSessionEnding {
+		fn on_session_ending(_: SessionIndex) -> Option<Vec<u64>> {
+			Some(NEXT_VALIDATORS.with(|l| l.borrow().clone()))
+		}
+	}
+
+	/// The full identification of a validator is just its account id times ten.
+	pub struct TestFullIdentificationOf;
+	impl Convert<u64, Option<u64>> for TestFullIdentificationOf {
+		fn convert(validator: u64) -> Option<u64> {
+			Some(validator * 10)
+		}
+	}
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
+	}
+	impl timestamp::Trait for Test {
+		type Moment = u64;
+		type OnTimestampSet = ();
+	}
+	impl crate::Trait for Test {
+		type ShouldEndSession = TestShouldEndSession;
+		type OnSessionEnding = NoteHistoricalRoot<Test, TestOnSessionEnding>;
+		type SessionHandler = ();
+		type Keys = UintAuthorityId;
+		type Event = ();
+	}
+	impl Trait for Test {
+		type FullIdentification = u64;
+		type FullIdentificationOf = TestFullIdentificationOf;
+	}
+
+	type System = system::Module<Test>;
+	type Session = SessionModule<Test>;
+	type Historical = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap().0;
+		t.extend(GenesisConfig::<Test> {
+			validators: NEXT_VALIDATORS.with(|l| l.borrow().clone()),
+			keys: NEXT_VALIDATORS.with(|l|
+				l.borrow().iter().cloned().map(|i| (i, UintAuthorityId(i))).collect()
+			),
+		}.build_storage().unwrap().0);
+		runtime_io::TestExternalities::new(t)
+	}
+
+	fn start_session(index: SessionIndex) {
+		for i in Session::current_index()..index {
+			System::set_block_number(i as u64 + 1);
+			Session::on_initialize(System::block_number());
+		}
+		assert_eq!(Session::current_index(), index);
+	}
+
+	fn key(id: u64) -> (u32, Vec<u8>) {
+		(0, UintAuthorityId(id).encode())
+	}
+
+	#[test]
+	fn generated_proof_is_good() {
+		with_externalities(&mut new_test_ext(), || {
+			start_session(1);
+
+			let proof = Historical::prove(key(2)).unwrap();
+			assert_eq!(proof.session, 1);
+			assert_eq!(Historical::check_proof(key(2), proof.clone()), Some((2, 20)));
+
+			// The proof doesn't hold for any other key.
+			assert_eq!(Historical::check_proof(key(1), proof), None);
+
+			// Unknown keys cannot be proven.
+			assert!(Historical::prove(key(4)).is_none());
+		});
+	}
+
+	#[test]
+	fn proofs_remain_valid_in_later_sessions() {
+		with_externalities(&mut new_test_ext(), || {
+			start_session(1);
+			let proof = Historical::prove(key(3)).unwrap();
+
+			// Validator 3 leaves the set and changes its keys.
+			NEXT_VALIDATORS.with(|v| *v.borrow_mut() = vec![1, 2]);
+			assert_ok!(Session::set_keys(Origin::signed(3), UintAuthorityId(30), vec![]));
+			start_session(3);
+
+			assert!(Historical::prove(key(3)).is_none());
+			assert_eq!(Historical::check_proof(key(3), proof), Some((3, 30)));
+			assert_eq!(Historical::historical_root(3).map(|(_, count)| count), Some(2));
+		});
+	}
+
+	#[test]
+	fn no_proofs_after_keys_changed_in_session() {
+		with_externalities(&mut new_test_ext(), || {
+			start_session(1);
+			assert_ok!(Session::set_keys(Origin::signed(3), UintAuthorityId(30), vec![]));
+
+			// The trie of the ongoing session can't be rebuilt anymore.
+			assert!(Historical::prove(key(1)).is_none());
+
+			start_session(2);
+			assert!(Historical::prove(key(30)).is_some());
+		});
+	}
+
+	#[test]
+	fn prune_up_to_works() {
+		with_externalities(&mut new_test_ext(), || {
+			start_session(5);
+			assert_eq!(StoredRange::get(), Some((1, 6)));
+
+			Historical::prune_up_to(3);
+			assert_eq!(StoredRange::get(), Some((3, 6)));
+			assert!(Historical::historical_root(2).is_none());
+			assert!(Historical::historical_root(3).is_some());
+
+			// Pruning out of range is harmless.
+			Historical::prune_up_to(1);
+			assert_eq!(StoredRange::get(), Some((3, 6)));
+
+			Historical::prune_up_to(10);
+			assert_eq!(StoredRange::get(), None);
+			assert!(Historical::historical_root(5).is_none());
+
+			// Newly started sessions are recorded again.
+			start_session(6);
+			assert_eq!(StoredRange::get(), Some((6, 7)));
+		});
+	}
+
+	#[test]
+	fn note_historical_root_passes_the_new_validators_through() {
+		with_externalities(&mut new_test_ext(), || {
+			NEXT_VALIDATORS.with(|v| *v.borrow_mut() = vec![1]);
+			assert_eq!(
+				<NoteHistoricalRoot<Test, TestOnSessionEnding>>::on_session_ending(0),
+				Some(vec![1]),
+			);
+			assert_eq!(Historical::historical_root(1).map(|(_, count)| count), Some(1));
+		});
+	}
+}
@@ -88,10 +88,15 @@
 //! module updates the authorities to the new validator set.
 //! - `rotate_session` - Change to the next session. Register the new authority set. Update session keys.
 //! Enact session length change if applicable.
+//! - `upgrade_keys` - Upgrade the stored session keys after the runtime changed the layout of `Keys`.
 //! - `ideal_session_duration` - Get the time of an ideal session.
 //! - `blocks_remaining` - Get the number of blocks remaining in the current session,
 //! excluding the current block.
 //!
+//! The [`historical`](./historical/index.html) extension, enabled by the `historical` feature,
+//! additionally commits to the validator set of every session, so that membership of a key in a past
+//! validator set can be proven.
+//!
 //! ## Usage
 //!
 //! ### Example from the SRML
@@ -122,11 +127,15 @@ use parity_codec::Decode;
 use primitives::traits::{Zero, Saturating, Member, OpaqueKeys};
 use srml_support::{
 	ConsensusEngineId, StorageValue, StorageMap, for_each_tuple, decl_module,
-	decl_event, decl_storage,
+	decl_event, decl_storage, Blake2_256,
+	storage::hashed::{self, generator::StorageHasher},
 };
 use srml_support::{ensure, traits::{OnFreeBalanceZero, Get, FindAuthor}, Parameter, print};
 use system::ensure_signed;
 
+#[cfg(feature = "historical")]
+pub mod historical;
+
 /// Simple index type with which we can count sessions.
 pub type SessionIndex = u32;
 
@@ -249,6 +258,9 @@ decl_storage! {
 					.collect::<Vec<OpaqueKey>>(),
 			)).collect::<Vec<(u32, Vec<OpaqueKey>)>>()
 		}): map u32 => Vec<OpaqueKey>;
+
+		/// The version of the layout of the stored keys, as last set by `upgrade_keys`.
+		KeysVersion get(keys_version): u32;
 	}
 	add_extra_genesis {
 		config(keys): Vec<(T::AccountId, T::Keys)>;
@@ -361,6 +373,52 @@ impl<T: Trait> Module<T> {
 		T::SessionHandler::on_new_session::<T::Keys>(changed, &amalgamated);
 	}
 
+	/// Upgrade the keys stored for `accounts` from the `Old` layout to the layout of `T::Keys`,
+	/// unless the stored keys are already at layout `version`.
+	///
+	/// This is meant to be called once, at the start of a block, by a runtime which changed its
+	/// `Keys`. The keys are kept in a map which cannot be iterated, so the accounts whose keys are
+	/// upgraded must be given; any other account has to set its keys again. Keys which already
+	/// decode as `T::Keys` are left alone, so running the upgrade on a chain which started with
+	/// the new layout does no harm.
+	///
+	/// Returns the number of accounts whose keys were upgraded.
+	pub fn upgrade_keys<Old, I, F>(version: u32, accounts: I, upgrade: F) -> usize where
+		Old: Decode,
+		I: IntoIterator<Item=T::AccountId>,
+		F: Fn(&T::AccountId, Old) -> T::Keys,
+	{
+		if KeysVersion::get() >= version {
+			return 0
+		}
+		KeysVersion::put(version);
+
+		let mut upgraded = 0;
+		for who in accounts {
+			if <NextKeyFor<T>>::get(&who).is_some() {
+				continue
+			}
+			let old = match hashed::get::<Old, _, _>(&Blake2_256::hash, &<NextKeyFor<T>>::key_for(&who)) {
+				Some(old) => old,
+				None => continue,
+			};
+			let keys = upgrade(&who, old);
+
+			// Keys which are new in the layout must also be known to be in use.
+			for i in 0..T::Keys::count() {
+				let key = keys.get_raw(i);
+				let mut active = Active::get(i as u32);
+				if let Err(pos) = active.binary_search_by(|k| k[..].cmp(key)) {
+					active.insert(pos, key.to_owned());
+					Active::insert(i as u32, active);
+				}
+			}
+			<NextKeyFor<T>>::insert(&who, keys);
+			upgraded += 1;
+		}
+		upgraded
+	}
+
 	/// Disable the validator of index `i`.
 	pub fn disable_index(i: usize) {
 		T::SessionHandler::on_disabled(i);
@@ -402,11 +460,13 @@ impl<T: Trait, Inner: FindAuthor<u32>> FindAuthor<T::AccountId>
 mod tests {
 	use super::*;
 	use std::cell::RefCell;
-	use srml_support::{impl_outer_origin, assert_ok};
+	use srml_support::{impl_outer_origin, assert_ok, parameter_types};
+	use parity_codec::Encode;
 	use runtime_io::with_externalities;
 	use substrate_primitives::{H256, Blake2Hasher};
 	use primitives::{
-		traits::{BlakeTwo256, IdentityLookup, OnInitialize}, testing::{Header, UintAuthorityId}
+		Perbill, traits::{BlakeTwo256, IdentityLookup, OnInitialize}, testing::{Header, UintAuthorityId},
+		weights::Weight,
 	};
 
 	impl_outer_origin!{
@@ -460,6 +520,10 @@ mod tests {
 
 	#[derive(Clone, Eq, PartialEq)]
 	pub struct Test;
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
 	impl system::Trait for Test {
 		type Origin = Origin;
 		type Index = u64;
@@ -470,6 +534,9 @@ mod tests {
 		type Lookup = IdentityLookup<Self::AccountId>;
 		type Header = Header;
 		type Event = ();
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
 	}
 	impl timestamp::Trait for Test {
 		type Moment = u64;
@@ -590,4 +657,31 @@ mod tests {
 			assert_eq!(authorities(), vec![UintAuthorityId(1), UintAuthorityId(5), UintAuthorityId(3)]);
 		});
 	}
+
+	#[test]
+	fn upgrade_keys_converts_keys_of_the_old_layout_once() {
+		with_externalities(&mut new_test_ext(), || {
+			// Validator 1 still has its keys in an old layout, as a `u32`.
+			hashed::put(&Blake2_256::hash, &<NextKeyFor<Test>>::key_for(&1), &1u32);
+			assert_eq!(<NextKeyFor<Test>>::get(&1), None);
+
+			let upgrade = |_: &u64, old: u32| UintAuthorityId(old as u64 + 10);
+			assert_eq!(Session::upgrade_keys::<u32, _, _>(1, vec![1, 2, 3, 4], upgrade), 1);
+			assert_eq!(Session::keys_version(), 1);
+			assert_eq!(<NextKeyFor<Test>>::get(&1), Some(UintAuthorityId(11)));
+			// Keys already in the new layout are untouched.
+			assert_eq!(<NextKeyFor<Test>>::get(&2), Some(UintAuthorityId(2)));
+			assert!(Active::get(0).contains(&UintAuthorityId(11).encode()));
+
+			// The upgrade only happens once.
+			hashed::put(&Blake2_256::hash, &<NextKeyFor<Test>>::key_for(&2), &2u32);
+			assert_eq!(Session::upgrade_keys::<u32, _, _>(1, vec![2], upgrade), 0);
+			assert_eq!(<NextKeyFor<Test>>::get(&2), None);
+
+			force_new_session();
+			System::set_block_number(1);
+			Session::on_initialize(1);
+			assert_eq!(authorities(), vec![UintAuthorityId(11), UintAuthorityId(0), UintAuthorityId(3)]);
+		});
+	}
 }
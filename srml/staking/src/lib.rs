@@ -167,12 +167,24 @@
 //!
 //! ### Reward Calculation
 //!
-//! Rewards are recorded **per-session** and paid **per-era**. The value of the reward for each
-//! session is calculated at the end of the session based on the timeliness of the session, then
-//! accumulated to be paid later. The value of the new _per-session-reward_ is calculated at the end
-//! of each era by multiplying `SlotStake` and `SessionReward`  (`SessionReward` is the
-//! multiplication factor, represented by a number between 0 and 1). Once a new era is triggered,
-//! rewards are paid to the validators and their associated nominators.
+//! Rewards are recorded **per-session** and made claimable **per-era**. The value of the reward for
+//! each session is calculated at the end of the session based on the timeliness of the session,
+//! then accumulated to be paid later. The value of the new _per-session-reward_ is calculated at the
+//! end of each era by multiplying `SlotStake` and `SessionReward`  (`SessionReward` is the
+//! multiplication factor, represented by a number between 0 and 1).
+//!
+//! Once a new era is triggered, the accumulated reward of all validators becomes the payout of the
+//! era that ended. It is split among the validators of the era according to the
+//! [reward points](./struct.EraRewardPoints.html) they have earned for authoring blocks (see the
+//! [`EventHandler`](../srml_authorship/trait.EventHandler.html) implementation of this module). If
+//! no points were earned in an era, the payout is split evenly among its validators.
+//!
+//! Rewards are not paid automatically. Instead, the exposures and preferences of the validators of
+//! each era are stored, and each validator and nominator claims its own reward through
+//! [`payout_validator`](./enum.Call.html#variant.payout_validator) and
+//! [`payout_nominator`](./enum.Call.html#variant.payout_nominator). The reward of an era can be
+//! claimed for as long as the era is bonded, i.e. for [`BondingDuration`](./struct.BondingDuration.html)
+//! eras.
 //!
 //! The validator can declare an amount, named
 //! [`validator_payment`](./struct.ValidatorPrefs.html#structfield.validator_payment), that does not
@@ -214,6 +226,19 @@
 //! individual accounts' punishments are capped at their total stake (NOTE: This cap should never
 //! come into force in a correctly implemented, non-corrupted, well-configured system).
 //!
+//! Offences reported through the offences module are handled by the
+//! [`OnOffenceHandler`](../srml_support/traits/offence/trait.OnOffenceHandler.html)
+//! implementation of this module. The reported fraction is slashed from the validator's own stake
+//! and from each of its nominators' exposed stake. Within a _slashing span_ a staker is only ever
+//! slashed for the maximum of the offences it is found guilty of, rather than their sum, and a
+//! validator slashed for an offence in its ongoing span is chilled and disabled. A part of the
+//! slash, bounded by [`SlashRewardFraction`](./struct.Module.html#method.slash_reward_fraction),
+//! is paid out to the reporters of the offence.
+//!
+//! Slashes are applied [`SlashDeferDuration`](./trait.Trait.html#associatedtype.SlashDeferDuration)
+//! eras after they were reported, leaving time for `SlashCancelOrigin` to cancel them via
+//! [`cancel_deferred_slash`](./enum.Call.html#variant.cancel_deferred_slash).
+//!
 //! ### Additional Fund Management Operations
 //!
 //! Any funds already placed into stash can be the target of the following operations:
@@ -266,6 +291,7 @@ mod mock;
 mod tests;
 
 mod phragmen;
+mod slashing;
 
 #[cfg(all(feature = "bench", test))]
 mod benches;
@@ -275,16 +301,18 @@ use runtime_io::with_storage;
 use rstd::{prelude::*, result, collections::btree_map::BTreeMap};
 use parity_codec::{HasCompact, Encode, Decode};
 use srml_support::{
-	StorageValue, StorageMap, EnumerableStorageMap, decl_module, decl_event,
+	StorageValue, StorageMap, EnumerableStorageMap, StorageDoubleMap, decl_module, decl_event,
 	decl_storage, ensure, traits::{
 		Currency, OnFreeBalanceZero, OnDilution, LockIdentifier, LockableCurrency,
-		WithdrawReasons, OnUnbalanced, Imbalance, Get
+		WithdrawReasons, OnUnbalanced, Imbalance, Get,
+		offence::{OnOffenceHandler, OffenceDetails},
 	}
 };
-use session::{OnSessionEnding, SessionIndex};
-use primitives::Perbill;
+use session::{OnSessionEnding, SessionIndex, historical::IdentificationTuple};
+use primitives::{Perbill, weights::Weight};
 use primitives::traits::{
-	Convert, Zero, One, StaticLookup, CheckedSub, CheckedShl, Saturating, Bounded
+	Convert, Zero, One, StaticLookup, CheckedSub, CheckedShl, Saturating, Bounded,
+	SimpleArithmetic, EnsureOrigin,
 };
 #[cfg(feature = "std")]
 use primitives::{Serialize, Deserialize};
@@ -292,9 +320,14 @@ use system::ensure_signed;
 
 use phragmen::{elect, ACCURACY, ExtendedBalance, equalize};
 
+pub use slashing::UnappliedSlash;
+
 const RECENT_OFFLINE_COUNT: usize = 32;
 const DEFAULT_MINIMUM_VALIDATOR_COUNT: u32 = 4;
 const MAX_NOMINATIONS: usize = 16;
+const MAX_NOMINATORS_REWARDED_PER_VALIDATOR: usize = 64;
+const STORAGE_VERSION: u32 = 2;
+const MIGRATION_WEIGHT_PER_WRITE: Weight = 1_000;
 const MAX_UNSTAKE_THRESHOLD: u32 = 10;
 const MAX_UNLOCKING_CHUNKS: usize = 32;
 const STAKING_ID: LockIdentifier = *b"staking ";
@@ -331,6 +364,19 @@ impl Default for RewardDestination {
 	}
 }
 
+/// Reward points of a validator.
+pub type RewardPoint = u32;
+
+/// Reward points of an era. Used to split the era payout between validators.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Default)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct EraRewardPoints<AccountId> {
+	/// Total number of points. Equals the sum of reward points of each validator.
+	pub total: RewardPoint,
+	/// The reward points earned by each validator elected in the era, keyed by stash.
+	pub individual: Vec<(AccountId, RewardPoint)>,
+}
+
 /// Preference of what happens on a slash event.
 #[derive(PartialEq, Eq, Clone, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Debug))]
@@ -404,6 +450,57 @@ impl<
 	}
 }
 
+impl<AccountId, Balance> StakingLedger<AccountId, Balance> where
+	Balance: HasCompact + SimpleArithmetic + Saturating + Copy,
+{
+	/// Slash the validator for a given amount of balance. This can grow the value
+	/// of the slash in the case that the validator has less than `minimum_balance`
+	/// active funds. Returns the amount of funds actually slashed.
+	///
+	/// Slashes from `active` funds first, and then `unlocking`, starting with the
+	/// chunks that are closest to unlocking.
+	fn slash(&mut self, mut value: Balance, minimum_balance: Balance) -> Balance {
+		let pre_total = self.total;
+		let total = &mut self.total;
+		let active = &mut self.active;
+
+		let slash_out_of = |
+			total_remaining: &mut Balance,
+			target: &mut Balance,
+			value: &mut Balance,
+		| {
+			let mut slash_from_target = (*value).min(*target);
+
+			if !slash_from_target.is_zero() {
+				*target -= slash_from_target;
+
+				// don't leave a dust balance in the staking system.
+				if *target <= minimum_balance {
+					slash_from_target += *target;
+					*target = Zero::zero();
+				}
+
+				*total_remaining = total_remaining.saturating_sub(slash_from_target);
+				*value = value.saturating_sub(slash_from_target);
+			}
+		};
+
+		slash_out_of(total, active, &mut value);
+
+		for chunk in self.unlocking.iter_mut() {
+			if value.is_zero() {
+				break
+			}
+			slash_out_of(total, &mut chunk.value, &mut value);
+		}
+
+		// kill all drained chunks.
+		self.unlocking.retain(|chunk| !chunk.value.is_zero());
+
+		pre_total.saturating_sub(*total)
+	}
+}
+
 /// The amount of exposure (to slashing) than an individual nominator has.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Debug))]
@@ -445,7 +542,7 @@ type ExpoMap<T> = BTreeMap<
 pub const DEFAULT_SESSIONS_PER_ERA: u32 = 3;
 pub const DEFAULT_BONDING_DURATION: u32 = 1;
 
-pub trait Trait: system::Trait + session::Trait {
+pub trait Trait: system::Trait + session::historical::Trait {
 	/// The staking balance.
 	type Currency: LockableCurrency<Self::AccountId, Moment=Self::BlockNumber>;
 
@@ -473,6 +570,14 @@ pub trait Trait: system::Trait + session::Trait {
 
 	/// Number of eras that staked funds must remain bonded for.
 	type BondingDuration: Get<EraIndex>;
+
+	/// Number of eras that slashes are deferred by, after computation. This should be
+	/// less than the bonding duration. Set to 0 if slashes should be applied immediately,
+	/// without opportunity for intervention.
+	type SlashDeferDuration: Get<EraIndex>;
+
+	/// The origin which can cancel a deferred slash.
+	type SlashCancelOrigin: EnsureOrigin<Self::Origin>;
 }
 
 decl_storage! {
@@ -538,6 +643,36 @@ decl_storage! {
 		/// and increased for every successfully finished session.
 		pub CurrentEraReward get(current_era_reward): BalanceOf<T>;
 
+		/// Exposure of the validators elected in an era, keyed by era and validator stash.
+		///
+		/// This is a snapshot of `Stakers` at the beginning of the era, kept while the era is
+		/// bonded. Only the `MAX_NOMINATORS_REWARDED_PER_VALIDATOR` biggest nominators of each
+		/// validator are kept, and rewarded; `total` and `own` are not clipped.
+		pub ErasStakers get(eras_stakers): double_map hasher(twox_64_concat) EraIndex,
+			blake2_256(T::AccountId) => Exposure<T::AccountId, BalanceOf<T>>;
+
+		/// Preferences of the validators elected in an era, keyed by era and validator stash.
+		pub ErasValidatorPrefs get(eras_validator_prefs): double_map hasher(twox_64_concat) EraIndex,
+			blake2_256(T::AccountId) => ValidatorPrefs<BalanceOf<T>>;
+
+		/// The total reward to be paid out for a finished era.
+		pub ErasValidatorReward get(eras_validator_reward): map EraIndex => Option<BalanceOf<T>>;
+
+		/// Reward points of the validators elected in an era.
+		pub ErasRewardPoints get(eras_reward_points): map EraIndex => EraRewardPoints<T::AccountId>;
+
+		/// The `(validator, staker)` pairs whose reward of an era has been claimed. The validator's
+		/// own reward is keyed by `(validator, validator)`.
+		ClaimedRewards: double_map hasher(twox_64_concat) EraIndex,
+			blake2_256((T::AccountId, T::AccountId)) => bool;
+
+		/// The era from which rewards are claimed through `payout_validator` and
+		/// `payout_nominator`. Rewards of earlier eras were paid out when the era ended.
+		pub MigrateEra get(migrate_era): Option<EraIndex>;
+
+		/// The version of the storage layout, as last set by `migrate`.
+		StorageVersion build(|_| STORAGE_VERSION): u32;
+
 		/// The amount of balance actively at stake for each validator slot, currently.
 		///
 		/// This is used to derive rewards and punishments.
@@ -555,6 +690,40 @@ decl_storage! {
 
 		/// True if the next session change will be a new era regardless of index.
 		pub ForceNewEra get(forcing_new_era): bool;
+
+		/// The percentage of the slash that is distributed to reporters.
+		///
+		/// The rest of the slashed value is handled by the `Slash`.
+		pub SlashRewardFraction get(slash_reward_fraction): Perbill = Perbill::from_percent(10);
+
+		/// All unapplied slashes that are queued for later, keyed by the era they were
+		/// reported in.
+		pub UnappliedSlashes get(unapplied_slashes):
+			map EraIndex => Vec<UnappliedSlash<T::AccountId, BalanceOf<T>>>;
+
+		/// The earliest era for which we have a pending, unapplied slash.
+		EarliestUnappliedSlash: Option<EraIndex>;
+
+		/// A mapping from still-bonded eras to the first session index of that era.
+		BondedEras get(bonded_eras) build(|config: &GenesisConfig<T>| {
+			vec![(config.current_era, 0)]
+		}): Vec<(EraIndex, SessionIndex)>;
+
+		/// All slashing events on validators, mapped by era to the highest slash proportion
+		/// and slash value of the era.
+		ValidatorSlashInEra: double_map hasher(twox_64_concat) EraIndex, blake2_256(T::AccountId)
+			=> Option<(Perbill, BalanceOf<T>)>;
+
+		/// All slashing events on nominators, mapped by era to the highest slash value of the era.
+		NominatorSlashInEra: double_map hasher(twox_64_concat) EraIndex, blake2_256(T::AccountId)
+			=> Option<BalanceOf<T>>;
+
+		/// Slashing spans for stash accounts.
+		SlashingSpans: map T::AccountId => Option<slashing::SlashingSpans>;
+
+		/// Records information about the maximum slash of a stash within a slashing span,
+		/// as well as how much reward has been paid out.
+		SpanSlash: map (T::AccountId, slashing::SpanIndex) => slashing::SpanRecord<BalanceOf<T>>;
 	}
 	add_extra_genesis {
 		config(stakers):
@@ -591,6 +760,9 @@ decl_storage! {
 				if let (_, Some(validators)) = <Module<T>>::select_validators() {
 					<session::Validators<T>>::put(&validators);
 				}
+
+				<Module<T>>::snapshot_era(config.current_era);
+				MigrateEra::put(config.current_era);
 			});
 		});
 	}
@@ -598,13 +770,19 @@ decl_storage! {
 
 decl_event!(
 	pub enum Event<T> where Balance = BalanceOf<T>, <T as system::Trait>::AccountId {
-		/// All validators have been rewarded by the given balance.
+		/// An era has ended and the given balance is its total reward, to be claimed by its
+		/// validators and their nominators.
 		Reward(Balance),
+		/// A staker (validator or nominator) has claimed the given reward.
+		Rewarded(AccountId, Balance),
 		/// One validator (and its nominators) has been given an offline-warning (it is still
 		/// within its grace). The accrued number of slashes is recorded, too.
 		OfflineWarning(AccountId, u32),
 		/// One validator (and its nominators) has been slashed by the given amount.
 		OfflineSlash(AccountId, Balance),
+		/// One staker (validator or nominator) has been slashed by the given amount as the
+		/// result of a reported offence.
+		Slash(AccountId, Balance),
 	}
 );
 
@@ -616,8 +794,17 @@ decl_module! {
 		/// Number of eras that staked funds must remain bonded for.
 		const BondingDuration: EraIndex = T::BondingDuration::get();
 
+		/// Number of eras that slashes are deferred by, after computation.
+		const SlashDeferDuration: EraIndex = T::SlashDeferDuration::get();
+
 		fn deposit_event<T>() = default;
 
+		fn on_initialize() {
+			if StorageVersion::get() < STORAGE_VERSION {
+				Self::migrate();
+			}
+		}
+
 		/// Take the origin account as a stash and lock up `value` of its balance. `controller` will
 		/// be the  account that controls it.
 		///
@@ -873,6 +1060,79 @@ decl_module! {
 			}
 		}
 
+		/// Claim the reward of a validator for a finished era.
+		///
+		/// The reward is made of the validator's up-front payment and its share, proportional to
+		/// its own stake, of the rest of its era reward.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
+		///
+		/// # <weight>
+		/// - Independent of the arguments. Limited complexity.
+		/// - Contains a limited number of reads.
+		/// - Writes are limited to the `origin` account key and the rewarded account.
+		/// # </weight>
+		fn payout_validator(origin, era: EraIndex) {
+			let controller = ensure_signed(origin)?;
+			let stash = Self::ledger(&controller).ok_or("not a controller")?.stash;
+
+			let (validator_payment, shared, exposure) = Self::validator_reward_in_era(era, &stash)?;
+			let claim = (stash.clone(), stash.clone());
+			ensure!(!<ClaimedRewards<T>>::exists(&era, &claim), "reward already claimed");
+
+			let total = exposure.total.max(One::one());
+			let own_cut = Perbill::from_rational_approximation(exposure.own, total) * shared;
+			Self::reward_staker(&stash, own_cut + validator_payment)?;
+			<ClaimedRewards<T>>::insert(&era, &claim, true);
+		}
+
+		/// Claim the reward of a nominator for a finished era, from the given `validators`.
+		///
+		/// The reward from each validator is claimed separately, so the reward from a validator
+		/// missing from `validators` can be claimed later. Validators whose reward has already
+		/// been claimed are skipped; it is an error if there is nothing left to claim, which is
+		/// also the case for a nominator who is not among the rewarded nominators of any of them.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
+		///
+		/// # <weight>
+		/// - The transaction's complexity is proportional to the size of `validators`, which is
+		/// capped at `MAX_NOMINATIONS`, times the number of rewarded nominators of each of them,
+		/// which is capped at `MAX_NOMINATORS_REWARDED_PER_VALIDATOR`.
+		/// - Writes are limited to the `origin` account key and the rewarded account.
+		/// # </weight>
+		fn payout_nominator(origin, era: EraIndex, validators: Vec<T::AccountId>) {
+			let controller = ensure_signed(origin)?;
+			let stash = Self::ledger(&controller).ok_or("not a controller")?.stash;
+			ensure!(validators.len() <= MAX_NOMINATIONS, "too many validators");
+			ensure!(<ErasValidatorReward<T>>::exists(&era), "no reward for era");
+
+			let mut validators = validators;
+			validators.sort();
+			validators.dedup();
+
+			let mut reward = <BalanceOf<T>>::zero();
+			let mut claims = Vec::new();
+			for validator in validators {
+				let (_, shared, exposure) = Self::validator_reward_in_era(era, &validator)?;
+				let claim = (validator, stash.clone());
+				if <ClaimedRewards<T>>::exists(&era, &claim) {
+					continue
+				}
+				let total = exposure.total.max(One::one());
+				if let Some(nominator) = exposure.others.iter().find(|i| i.who == stash) {
+					reward += Perbill::from_rational_approximation(nominator.value, total) * shared;
+					claims.push(claim);
+				}
+			}
+			ensure!(!claims.is_empty(), "no reward to claim");
+
+			Self::reward_staker(&stash, reward)?;
+			for claim in &claims {
+				<ClaimedRewards<T>>::insert(&era, claim, true);
+			}
+		}
+
 		/// The ideal number of validators.
 		fn set_validator_count(#[compact] new: u32) {
 			ValidatorCount::put(new);
@@ -901,6 +1161,33 @@ decl_module! {
 		fn set_invulnerables(validators: Vec<T::AccountId>) {
 			<Invulnerables<T>>::put(validators);
 		}
+
+		/// Cancel enactment of deferred slashes reported in `era`.
+		///
+		/// The dispatch origin for this call must be `T::SlashCancelOrigin`. `slash_indices` are
+		/// the indices into `UnappliedSlashes` of that era and must be sorted and unique.
+		///
+		/// # <weight>
+		/// - One storage read and write, linear in the number of unapplied slashes of the era.
+		/// # </weight>
+		fn cancel_deferred_slash(origin, era: EraIndex, slash_indices: Vec<u32>) {
+			T::SlashCancelOrigin::ensure_origin(origin)?;
+
+			let last = *slash_indices.last().ok_or("slash indices must not be empty")?;
+			ensure!(
+				slash_indices.windows(2).all(|w| w[0] < w[1]),
+				"slash indices must be sorted and unique"
+			);
+
+			let mut unapplied = <Self as Store>::UnappliedSlashes::get(&era);
+			ensure!((last as usize) < unapplied.len(), "slash index out of bounds");
+
+			for index in slash_indices.into_iter().rev() {
+				unapplied.remove(index as usize);
+			}
+
+			<Self as Store>::UnappliedSlashes::insert(&era, &unapplied);
+		}
 	}
 }
 
@@ -961,6 +1248,9 @@ impl<T: Trait> Module<T> {
 
 	/// Actually make a payment to a staker. This uses the currency's reward function
 	/// to pay the right payee for the given staker account.
+	///
+	/// Returns `None` without changing anything if the payee cannot be paid, e.g. because it has
+	/// no ledger or its account does not exist.
 	fn make_payout(stash: &T::AccountId, amount: BalanceOf<T>) -> Option<PositiveImbalanceOf<T>> {
 		let dest = Self::payee(stash);
 		match dest {
@@ -973,38 +1263,136 @@ impl<T: Trait> Module<T> {
 			RewardDestination::Staked => Self::bonded(stash)
 				.and_then(|c| Self::ledger(&c).map(|l| (c, l)))
 				.and_then(|(controller, mut l)| {
+					let r = T::Currency::deposit_into_existing(stash, amount).ok()?;
 					l.active += amount;
 					l.total += amount;
-					let r = T::Currency::deposit_into_existing(stash, amount).ok();
 					Self::update_ledger(&controller, &l);
-					r
+					Some(r)
 				}),
 		}
 	}
 
-	/// Reward a given validator by a specific amount. Add the reward to the validator's, and its
-	/// nominators' balance, pro-rata based on their exposure, after having removed the validator's
-	/// pre-payout cut.
-	fn reward_validator(stash: &T::AccountId, reward: BalanceOf<T>) {
-		let off_the_table = reward.min(Self::validators(stash).validator_payment);
-		let reward = reward - off_the_table;
-		let mut imbalance = <PositiveImbalanceOf<T>>::zero();
-		let validator_cut = if reward.is_zero() {
-			Zero::zero()
+	/// Pay a claimed reward to a staker.
+	///
+	/// Fails without changing anything if the reward cannot be paid, so that it can be claimed
+	/// again later.
+	fn reward_staker(stash: &T::AccountId, amount: BalanceOf<T>) -> result::Result<(), &'static str> {
+		if amount.is_zero() {
+			return Ok(())
+		}
+
+		let imbalance = Self::make_payout(stash, amount).ok_or("reward payee cannot be paid")?;
+		Self::deposit_event(RawEvent::Rewarded(stash.clone(), imbalance.peek()));
+		T::Reward::on_unbalanced(imbalance);
+		Ok(())
+	}
+
+	/// The reward of the validator `stash` in the finished era `era`, along with its exposure
+	/// in that era.
+	///
+	/// The reward is returned in two parts: the validator's up-front payment, and the rest that
+	/// is shared between the validator and its nominators pro-rata based on their exposure.
+	fn validator_reward_in_era(era: EraIndex, stash: &T::AccountId) -> result::Result<
+		(BalanceOf<T>, BalanceOf<T>, Exposure<T::AccountId, BalanceOf<T>>),
+		&'static str,
+	> {
+		let era_payout = Self::eras_validator_reward(&era).ok_or("no reward for era")?;
+		let era_points = Self::eras_reward_points(&era);
+		let validator_points = era_points.individual.iter()
+			.find(|(validator, _)| validator == stash)
+			.map(|&(_, points)| points)
+			.ok_or("not a validator of the era")?;
+
+		let reward = if era_points.total.is_zero() {
+			// no points were earned in the era; split the payout evenly.
+			let len: BalanceOf<T> = (era_points.individual.len() as u32).into();
+			era_payout / len
 		} else {
-			let exposure = Self::stakers(stash);
-			let total = exposure.total.max(One::one());
+			Perbill::from_rational_approximation(validator_points, era_points.total) * era_payout
+		};
 
-			for i in &exposure.others {
-				let per_u64 = Perbill::from_rational_approximation(i.value, total);
-				imbalance.maybe_subsume(Self::make_payout(&i.who, per_u64 * reward));
+		let validator_payment = reward.min(Self::eras_validator_prefs(&era, stash).validator_payment);
+		Ok((validator_payment, reward - validator_payment, Self::eras_stakers(&era, stash)))
+	}
+
+	/// Add reward points to validators, identified by their controller account as in the session
+	/// module. Points credited to accounts that are not elected in the current era are ignored.
+	pub fn reward_by_ids(validators_points: impl IntoIterator<Item = (T::AccountId, RewardPoint)>) {
+		<ErasRewardPoints<T>>::mutate(&Self::current_era(), |era_points| {
+			for (controller, points) in validators_points.into_iter() {
+				let stash = match Self::ledger(&controller) {
+					Some(ledger) => ledger.stash,
+					None => continue,
+				};
+
+				if let Some(entry) = era_points.individual.iter_mut().find(|(s, _)| *s == stash) {
+					entry.1 = entry.1.saturating_add(points);
+					era_points.total = era_points.total.saturating_add(points);
+				}
 			}
+		});
+	}
 
-			let per_u64 = Perbill::from_rational_approximation(exposure.own, total);
-			per_u64 * reward
-		};
-		imbalance.maybe_subsume(Self::make_payout(stash, validator_cut + off_the_table));
-		T::Reward::on_unbalanced(imbalance);
+	/// Store the exposures and preferences of the currently elected validators as the ones of
+	/// `era`, and start counting their reward points.
+	///
+	/// Returns the number of storage writes.
+	fn snapshot_era(era: EraIndex) -> usize {
+		let elected = Self::current_elected();
+		for stash in &elected {
+			let mut exposure = Self::stakers(stash);
+			// keep the nominators with the biggest stake, so that payouts are bounded.
+			if exposure.others.len() > MAX_NOMINATORS_REWARDED_PER_VALIDATOR {
+				exposure.others.sort_by(|a, b| b.value.cmp(&a.value));
+				exposure.others.truncate(MAX_NOMINATORS_REWARDED_PER_VALIDATOR);
+			}
+			<ErasStakers<T>>::insert(&era, stash, exposure);
+			<ErasValidatorPrefs<T>>::insert(&era, stash, Self::validators(stash));
+		}
+
+		let writes = elected.len() * 2 + 1;
+		<ErasRewardPoints<T>>::insert(&era, EraRewardPoints {
+			total: 0,
+			individual: elected.into_iter().map(|stash| (stash, 0)).collect(),
+		});
+		writes
+	}
+
+	/// Remove all the reward information of an era that is no longer bonded.
+	fn clear_era_information(era: EraIndex) {
+		<ErasStakers<T>>::remove_prefix(&era);
+		<ErasValidatorPrefs<T>>::remove_prefix(&era);
+		<ClaimedRewards<T>>::remove_prefix(&era);
+		<ErasValidatorReward<T>>::remove(&era);
+		<ErasRewardPoints<T>>::remove(&era);
+	}
+
+	/// Upgrade the storage of a chain which started with an older version of the module, once.
+	///
+	/// The work is charged to the weight of the current block.
+	fn migrate() {
+		let mut writes = 1;
+
+		// Migrate from paying rewards when an era ends to claiming them. Rewards of the current
+		// era become claimable once it ends, split according to the reward points earned from
+		// the migration onwards.
+		if !MigrateEra::exists() {
+			let current_era = Self::current_era();
+			writes += Self::snapshot_era(current_era) + 1;
+			MigrateEra::put(current_era);
+		}
+
+		// Seed `BondedEras`. The start of the current era is not known, so it is taken to start
+		// at the current session; offences from earlier sessions cannot be slashed.
+		if !BondedEras::exists() {
+			BondedEras::put(vec![(Self::current_era(), <session::Module<T>>::current_index())]);
+			writes += 1;
+		}
+
+		StorageVersion::put(STORAGE_VERSION);
+		<system::Module<T>>::register_extra_weight_unchecked(
+			(writes as Weight).saturating_mul(MIGRATION_WEIGHT_PER_WRITE)
+		);
 	}
 
 	/// Session has just ended. Provide the validator set for the next session if it's an era-end.
@@ -1014,7 +1402,7 @@ impl<T: Trait> Module<T> {
 		<CurrentEraReward<T>>::mutate(|r| *r += reward);
 
 		if ForceNewEra::take() || session_index % T::SessionsPerEra::get() == 0 {
-			Self::new_era()
+			Self::new_era(session_index)
 		} else {
 			None
 		}
@@ -1024,27 +1412,51 @@ impl<T: Trait> Module<T> {
 	///
 	/// NOTE: This always happens immediately before a session change to ensure that new validators
 	/// get a chance to set their session keys.
-	fn new_era() -> Option<Vec<T::AccountId>> {
-		// Payout
+	fn new_era(start_session_index: SessionIndex) -> Option<Vec<T::AccountId>> {
+		// Set the payout of the ending era, to be claimed by its stakers.
 		let reward = <CurrentEraReward<T>>::take();
 		if !reward.is_zero() {
 			let validators = Self::current_elected();
-			for v in validators.iter() {
-				Self::reward_validator(v, reward);
-			}
-			Self::deposit_event(RawEvent::Reward(reward));
 			let len = validators.len() as u32; // validators length can never overflow u64
 			let len: BalanceOf<T> = len.into();
-			let total_minted = reward * len;
+			let total_payout = reward * len;
+			<ErasValidatorReward<T>>::insert(&Self::current_era(), &total_payout);
+			Self::deposit_event(RawEvent::Reward(total_payout));
 			let total_rewarded_stake = Self::slot_stake() * len;
-			T::OnRewardMinted::on_dilution(total_minted, total_rewarded_stake);
+			T::OnRewardMinted::on_dilution(total_payout, total_rewarded_stake);
 		}
 
 		// Increment current era.
-		CurrentEra::mutate(|s| *s += 1);
+		let current_era = CurrentEra::mutate(|s| { *s += 1; *s });
+
+		let bonding_duration = T::BondingDuration::get();
+		BondedEras::mutate(|bonded| {
+			bonded.push((current_era, start_session_index));
+
+			if current_era > bonding_duration {
+				let first_kept = current_era - bonding_duration;
+
+				// prune out everything that's from before the first-kept index.
+				let n_to_prune = bonded.iter()
+					.take_while(|&&(era_idx, _)| era_idx < first_kept)
+					.count();
+
+				for (pruned_era, _) in bonded.drain(..n_to_prune) {
+					slashing::clear_era_metadata::<T>(pruned_era);
+					Self::clear_era_information(pruned_era);
+				}
+
+				if let Some(&(_, first_session)) = bonded.first() {
+					<session::historical::Module<T>>::prune_up_to(first_session);
+				}
+			}
+		});
+
+		Self::apply_unapplied_slashes(current_era);
 
 		// Reassign all Stakers.
 		let (slot_stake, maybe_new_validators) = Self::select_validators();
+		Self::snapshot_era(current_era);
 
 		// Update the balances for rewarding according to the stakes.
 		<CurrentSessionReward<T>>::put(Self::session_reward() * slot_stake);
@@ -1180,6 +1592,26 @@ impl<T: Trait> Module<T> {
 		ForceNewEra::put(true);
 	}
 
+	/// Apply all the deferred slashes which are due at the start of `current_era`.
+	///
+	/// Slashes reported in era `E` are applied at the start of era `E + SlashDeferDuration`.
+	fn apply_unapplied_slashes(current_era: EraIndex) {
+		let slash_defer_duration = T::SlashDeferDuration::get();
+		<Self as Store>::EarliestUnappliedSlash::mutate(|earliest| {
+			if let Some(ref mut earliest) = earliest {
+				let keep_from = (current_era + 1).saturating_sub(slash_defer_duration);
+				for era in (*earliest)..keep_from {
+					let era_slashes = <Self as Store>::UnappliedSlashes::take(&era);
+					for slash in era_slashes {
+						slashing::apply_slash::<T>(slash);
+					}
+				}
+
+				*earliest = (*earliest).max(keep_from)
+			}
+		})
+	}
+
 	/// Call when a validator is determined to be offline. `count` is the
 	/// number of offenses the validator has committed.
 	///
@@ -1253,5 +1685,117 @@ impl<T: Trait> OnFreeBalanceZero<T::AccountId> for Module<T> {
 		<SlashCount<T>>::remove(stash);
 		<Validators<T>>::remove(stash);
 		<Nominators<T>>::remove(stash);
+
+		slashing::clear_stash_metadata::<T>(stash);
+	}
+}
+
+/// Add reward points to block authors:
+/// * 20 points to the block producer for producing a block in the chain,
+/// * 2 points to the block producer for each reference to a previously unreferenced uncle, and
+/// * 1 point to the producer of each referenced uncle block.
+impl<T: Trait + authorship::Trait> authorship::EventHandler<T::AccountId, T::BlockNumber>
+	for Module<T>
+{
+	fn note_author(author: T::AccountId) {
+		Self::reward_by_ids(vec![(author, 20)]);
+	}
+
+	fn note_uncle(author: T::AccountId, _age: T::BlockNumber) {
+		Self::reward_by_ids(vec![
+			(<authorship::Module<T>>::author(), 2),
+			(author, 1),
+		]);
+	}
+}
+
+/// A `Convert` implementation that finds the exposure of a validator, given the controller account
+/// the session module knows it by.
+pub struct ExposureOf<T>(rstd::marker::PhantomData<T>);
+
+impl<T: Trait> Convert<T::AccountId, Option<Exposure<T::AccountId, BalanceOf<T>>>>
+	for ExposureOf<T>
+{
+	fn convert(controller: T::AccountId) -> Option<Exposure<T::AccountId, BalanceOf<T>>> {
+		<Module<T>>::ledger(&controller).map(|l| <Module<T>>::stakers(&l.stash))
+	}
+}
+
+/// The offender is identified by the controller account of the validator, i.e. the account the
+/// session module knows the validator by, and its exposure in the session of the offence as
+/// recorded by the historical session module.
+impl<T: Trait> OnOffenceHandler<T::AccountId, IdentificationTuple<T>> for Module<T> where
+	T: session::historical::Trait<
+		FullIdentification = Exposure<<T as system::Trait>::AccountId, BalanceOf<T>>,
+		FullIdentificationOf = ExposureOf<T>,
+	>,
+{
+	fn on_offence(
+		offenders: &[OffenceDetails<T::AccountId, IdentificationTuple<T>>],
+		slash_fraction: &[Perbill],
+		slash_session: SessionIndex,
+	) {
+		let reward_proportion = Self::slash_reward_fraction();
+		let era_now = Self::current_era();
+		let window_start = era_now.saturating_sub(T::BondingDuration::get());
+
+		// reverse because it's more likely to find reports from recent eras.
+		let slash_era = match Self::bonded_eras()
+			.into_iter()
+			.rev()
+			.find(|&(_, sesh)| sesh <= slash_session)
+		{
+			Some((slash_era, _)) => slash_era,
+			// before bonding period. defensive - should be filtered out.
+			None => return,
+		};
+
+		if slash_era < window_start {
+			return
+		}
+
+		let slash_defer_duration = T::SlashDeferDuration::get();
+
+		for (details, slash_fraction) in offenders.iter().zip(slash_fraction) {
+			let (controller, exposure) = &details.offender;
+			let stash = match Self::ledger(controller) {
+				Some(ledger) => ledger.stash,
+				None => continue,
+			};
+
+			// Skip if the validator is invulnerable.
+			if Self::invulnerables().contains(&stash) {
+				continue
+			}
+
+			let unapplied = slashing::compute_slash::<T>(slashing::SlashParams {
+				stash: &stash,
+				slash: *slash_fraction,
+				exposure,
+				slash_era,
+				window_start,
+				now: era_now,
+				reward_proportion,
+			});
+
+			if let Some(mut unapplied) = unapplied {
+				unapplied.reporters = details.reporters.clone();
+				if slash_defer_duration == 0 {
+					// apply right away.
+					slashing::apply_slash::<T>(unapplied);
+				} else {
+					// defer to end of some `slash_defer_duration` from now.
+					<Self as Store>::UnappliedSlashes::mutate(
+						era_now,
+						move |for_later| for_later.push(unapplied),
+					);
+					<Self as Store>::EarliestUnappliedSlash::mutate(|earliest| {
+						if earliest.is_none() {
+							*earliest = Some(era_now)
+						}
+					});
+				}
+			}
+		}
 	}
 }
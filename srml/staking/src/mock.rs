@@ -16,14 +16,14 @@
 
 //! Test utilities
 
-use std::{collections::HashSet, cell::RefCell};
-use primitives::Perbill;
+use std::{collections::{HashSet, BTreeMap}, cell::RefCell};
+use primitives::{Perbill, weights::Weight};
 use primitives::traits::{IdentityLookup, Convert, OpaqueKeys, OnInitialize};
 use primitives::testing::{Header, UintAuthorityId};
 use substrate_primitives::{H256, Blake2Hasher};
 use runtime_io;
 use srml_support::{assert_ok, impl_outer_origin, parameter_types, EnumerableStorageMap};
-use srml_support::traits::{Currency, Get};
+use srml_support::traits::{Currency, Get, offence::{OffenceDetails, OnOffenceHandler}};
 use crate::{EraIndex, GenesisConfig, Module, Trait, StakerStatus,
 	ValidatorPrefs, RewardDestination, Nominators
 };
@@ -47,6 +47,7 @@ impl Convert<u128, u64> for CurrencyToVoteHandler {
 thread_local! {
 	static SESSION: RefCell<(Vec<AccountId>, HashSet<AccountId>)> = RefCell::new(Default::default());
 	static EXISTENTIAL_DEPOSIT: RefCell<u64> = RefCell::new(0);
+	static SLASH_DEFER_DURATION: RefCell<EraIndex> = RefCell::new(0);
 }
 
 pub struct TestSessionHandler;
@@ -78,6 +79,13 @@ impl Get<u64> for ExistentialDeposit {
 	}
 }
 
+pub struct SlashDeferDuration;
+impl Get<EraIndex> for SlashDeferDuration {
+	fn get() -> EraIndex {
+		SLASH_DEFER_DURATION.with(|v| *v.borrow())
+	}
+}
+
 impl_outer_origin!{
 	pub enum Origin for Test {}
 }
@@ -85,6 +93,10 @@ impl_outer_origin!{
 // Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Test;
+parameter_types! {
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
 impl system::Trait for Test {
 	type Origin = Origin;
 	type Index = u64;
@@ -95,6 +107,9 @@ impl system::Trait for Test {
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
 	type Event = ();
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type FeeMultiplierUpdate = ();
 }
 parameter_types! {
 	pub const TransferFee: u64 = 0;
@@ -115,18 +130,23 @@ impl balances::Trait for Test {
 	type CreationFee = CreationFee;
 	type TransactionBaseFee = TransactionBaseFee;
 	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = ();
 }
 parameter_types! {
 	pub const Period: BlockNumber = 1;
 	pub const Offset: BlockNumber = 0;
 }
 impl session::Trait for Test {
-	type OnSessionEnding = Staking;
+	type OnSessionEnding = session::historical::NoteHistoricalRoot<Test, Staking>;
 	type Keys = UintAuthorityId;
 	type ShouldEndSession = session::PeriodicSessions<Period, Offset>;
 	type SessionHandler = TestSessionHandler;
 	type Event = ();
 }
+impl session::historical::Trait for Test {
+	type FullIdentification = crate::Exposure<AccountId, Balance>;
+	type FullIdentificationOf = crate::ExposureOf<Test>;
+}
 impl timestamp::Trait for Test {
 	type Moment = u64;
 	type OnTimestampSet = ();
@@ -144,6 +164,8 @@ impl Trait for Test {
 	type Reward = ();
 	type SessionsPerEra = SessionsPerEra;
 	type BondingDuration = BondingDuration;
+	type SlashDeferDuration = SlashDeferDuration;
+	type SlashCancelOrigin = system::EnsureRoot<Self::AccountId>;
 }
 
 pub struct ExtBuilder {
@@ -155,6 +177,7 @@ pub struct ExtBuilder {
 	validator_count: u32,
 	minimum_validator_count: u32,
 	fair: bool,
+	slash_defer_duration: EraIndex,
 }
 
 impl Default for ExtBuilder {
@@ -167,7 +190,8 @@ impl Default for ExtBuilder {
 			nominate: true,
 			validator_count: 2,
 			minimum_validator_count: 0,
-			fair: true
+			fair: true,
+			slash_defer_duration: 0,
 		}
 	}
 }
@@ -201,8 +225,13 @@ impl ExtBuilder {
 		self.fair = is_fair;
 		self
 	}
+	pub fn slash_defer_duration(mut self, eras: EraIndex) -> Self {
+		self.slash_defer_duration = eras;
+		self
+	}
 	pub fn set_associated_consts(&self) {
 		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.existential_deposit);
+		SLASH_DEFER_DURATION.with(|v| *v.borrow_mut() = self.slash_defer_duration);
 	}
 	pub fn build(self) -> runtime_io::TestExternalities<Blake2Hasher> {
 		self.set_associated_consts();
@@ -357,3 +386,35 @@ pub fn start_era(era_index: EraIndex) {
 	start_session((era_index * 3).into());
 	assert_eq!(Staking::current_era(), era_index);
 }
+
+pub fn on_offence_now(
+	offenders: &[OffenceDetails<AccountId, session::historical::IdentificationTuple<Test>>],
+	slash_fraction: &[Perbill],
+) {
+	let now = Session::current_index();
+	Staking::on_offence(offenders, slash_fraction, now)
+}
+
+/// Claim the reward of `era` for all of its validators and the nominators backing them.
+pub fn make_all_reward_payment(era: EraIndex) {
+	let validators = Staking::eras_reward_points(era).individual.into_iter()
+		.map(|(validator, _)| validator)
+		.collect::<Vec<_>>();
+
+	let mut nominators: BTreeMap<AccountId, Vec<AccountId>> = BTreeMap::new();
+	for validator in &validators {
+		for individual in Staking::eras_stakers(era, validator).others {
+			nominators.entry(individual.who).or_insert_with(Vec::new).push(*validator);
+		}
+	}
+
+	for validator in validators {
+		let controller = Staking::bonded(&validator).unwrap();
+		assert_ok!(Staking::payout_validator(Origin::signed(controller), era));
+	}
+
+	for (nominator, validators) in nominators {
+		let controller = Staking::bonded(&nominator).unwrap();
+		assert_ok!(Staking::payout_nominator(Origin::signed(controller), era, validators));
+	}
+}
@@ -0,0 +1,617 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A slashing implementation for NPoS systems.
+//!
+//! For the purposes of the economic model, it is easiest to think of each validator as a nominator
+//! which nominates only its own identity.
+//!
+//! The act of nomination signals intent to unify economic identity with the validator - to take
+//! part in the rewards of a job well done, and to take part in the punishment of a job done badly.
+//!
+//! There are 3 main difficulties to account for with slashing in NPoS:
+//!   - A nominator can nominate multiple validators and be slashed via any of them.
+//!   - Until slashed, stake is reused from era to era. Nominating with N coins for E eras in a row
+//!     does not mean you have N*E coins to be slashed - you've only ever had N.
+//!   - Slashable offences can be found after the fact and out of order.
+//!
+//! The algorithm implemented in this module tries to balance these 3 difficulties.
+//!
+//! First, we only slash participants for the _maximum_ slash they receive in some time period,
+//! rather than the sum. This ensures a protection from overslashing.
+//!
+//! Second, we do not want the time period (or "span") that the maximum is computed
+//! over to last indefinitely. That would allow participants to begin acting with
+//! impunity after some point, fearing no further repercussions. For that reason, we
+//! automatically chill validators and end the slashing span of a stash whenever it
+//! is slashed for an offence in its current span.
+//!
+//! Every slashing span has an associated reward for reporters, which is a fraction of
+//! the slash, bounded above by `REWARD_F1` times `SlashRewardFraction`. Any further
+//! slash within the same span only pays out the difference to what has already been paid.
+
+use super::{
+	EraIndex, Trait, Module, BalanceOf, Exposure, Perbill, NegativeImbalanceOf, RawEvent,
+	Validators, Nominators, ValidatorSlashInEra, NominatorSlashInEra, SlashingSpans as Spans,
+	SpanSlash,
+};
+use primitives::traits::{Zero, Saturating};
+use srml_support::{
+	StorageMap, StorageDoubleMap,
+	traits::{Currency, OnUnbalanced, Imbalance},
+};
+use rstd::prelude::*;
+use parity_codec::{Encode, Decode};
+
+/// The proportion of the slashing reward to be paid out on the first slashing detection,
+/// in percent. The remainder is paid out only if the slash is later found to be larger.
+const REWARD_F1: u32 = 50;
+
+/// The index of a slashing span - unique to each stash.
+pub type SpanIndex = u32;
+
+// A range of start..end eras for a slashing span.
+#[cfg_attr(feature = "std", derive(Debug, PartialEq))]
+pub(crate) struct SlashingSpan {
+	pub(crate) index: SpanIndex,
+	pub(crate) start: EraIndex,
+	pub(crate) length: Option<EraIndex>, // the ongoing slashing span has indeterminate length.
+}
+
+impl SlashingSpan {
+	fn contains_era(&self, era: EraIndex) -> bool {
+		self.start <= era && self.length.map_or(true, |l| self.start + l > era)
+	}
+}
+
+/// An encoding of all of a nominator's slashing spans.
+#[derive(Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, PartialEq))]
+pub struct SlashingSpans {
+	// the index of the current slashing span of the nominator. different for
+	// every stash, resets when the account hits free balance 0.
+	span_index: SpanIndex,
+	// the start era of the most recent (ongoing) slashing span.
+	last_start: EraIndex,
+	// all prior slashing spans' lengths, most recent first.
+	prior: Vec<EraIndex>,
+}
+
+impl SlashingSpans {
+	// creates a new record of slashing spans for a stash, starting at the beginning
+	// of the bonding period, relative to now.
+	fn new(window_start: EraIndex) -> Self {
+		SlashingSpans {
+			span_index: 0,
+			last_start: window_start,
+			prior: Vec::new(),
+		}
+	}
+
+	// update the slashing spans to reflect the start of a new span at the era after `now`
+	// returns `true` if a new span was started, `false` otherwise. `false` indicates
+	// that internal state is unchanged.
+	fn end_span(&mut self, now: EraIndex) -> bool {
+		let next_start = now + 1;
+		if next_start <= self.last_start { return false }
+
+		let last_length = next_start - self.last_start;
+		self.prior.insert(0, last_length);
+		self.last_start = next_start;
+		self.span_index += 1;
+		true
+	}
+
+	// an iterator over all slashing spans in _reverse_ order - most recent first.
+	pub(crate) fn iter(&'_ self) -> impl Iterator<Item = SlashingSpan> + '_ {
+		let mut last_start = self.last_start;
+		let mut index = self.span_index;
+		let last = SlashingSpan { index, start: last_start, length: None };
+		let prior = self.prior.iter().cloned().map(move |length| {
+			let start = last_start - length;
+			last_start = start;
+			index -= 1;
+
+			SlashingSpan { index, start, length: Some(length) }
+		});
+
+		rstd::iter::once(last).chain(prior)
+	}
+
+	// prune the slashing spans against a window, whose start era index is given.
+	//
+	// If this returns `Some`, then it includes a range start..end of all the span
+	// indices which were pruned.
+	fn prune(&mut self, window_start: EraIndex) -> Option<(SpanIndex, SpanIndex)> {
+		let old_idx = self.iter()
+			.skip(1) // skip ongoing span.
+			.position(|span| span.length.map_or(false, |len| span.start + len <= window_start));
+
+		let earliest_span_index = self.span_index - self.prior.len() as SpanIndex;
+		let pruned = match old_idx {
+			Some(o) => {
+				self.prior.truncate(o);
+				let new_earliest = self.span_index - self.prior.len() as SpanIndex;
+				Some((earliest_span_index, new_earliest))
+			}
+			None => None,
+		};
+
+		// readjust the ongoing span, if it started before the beginning of the window.
+		self.last_start = rstd::cmp::max(self.last_start, window_start);
+		pruned
+	}
+}
+
+/// A slashing-span record for a particular stash.
+#[derive(Encode, Decode, Default)]
+#[cfg_attr(feature = "std", derive(Debug, PartialEq))]
+pub struct SpanRecord<Balance> {
+	slashed: Balance,
+	paid_out: Balance,
+}
+
+/// A pending slash record. The value of the slash has been computed but not applied yet,
+/// rather deferred for several eras.
+#[derive(Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, PartialEq))]
+pub struct UnappliedSlash<AccountId, Balance> {
+	/// The stash ID of the offending validator.
+	pub validator: AccountId,
+	/// The validator's own slash.
+	pub own: Balance,
+	/// All other slashed stakers and amounts.
+	pub others: Vec<(AccountId, Balance)>,
+	/// Reporters of the offence; bounty payout recipients.
+	pub reporters: Vec<AccountId>,
+	/// The amount of payout.
+	pub payout: Balance,
+}
+
+/// Parameters for performing a slash.
+#[derive(Clone)]
+pub(crate) struct SlashParams<'a, T: 'a + Trait> {
+	/// The stash account being slashed.
+	pub(crate) stash: &'a T::AccountId,
+	/// The proportion of the slash.
+	pub(crate) slash: Perbill,
+	/// The exposure of the stash and all nominators.
+	pub(crate) exposure: &'a Exposure<T::AccountId, BalanceOf<T>>,
+	/// The era where the offence occurred.
+	pub(crate) slash_era: EraIndex,
+	/// The first era in the current bonding period.
+	pub(crate) window_start: EraIndex,
+	/// The current era.
+	pub(crate) now: EraIndex,
+	/// The maximum percentage of a slash that ever gets paid out.
+	/// This is f_inf in the paper.
+	pub(crate) reward_proportion: Perbill,
+}
+
+/// Computes a slash of a validator and nominators. It returns an unapplied
+/// record to be applied at some later point. Slashing metadata is updated in storage,
+/// since unapplied records are only rarely intended to be dropped.
+///
+/// The pending slash record returned does not have initialized reporters. Those have
+/// to be set at a higher level, if any.
+pub(crate) fn compute_slash<T: Trait>(params: SlashParams<T>)
+	-> Option<UnappliedSlash<T::AccountId, BalanceOf<T>>>
+{
+	let SlashParams {
+		stash,
+		slash,
+		exposure,
+		slash_era,
+		window_start,
+		now,
+		reward_proportion,
+	} = params.clone();
+
+	let own_slash = slash * exposure.own;
+	if (slash * exposure.total).is_zero() {
+		// nothing to slash.
+		return None;
+	}
+
+	let prior_slash_p = <ValidatorSlashInEra<T>>::get(&slash_era, stash)
+		.map_or(Perbill::zero(), |(p, _)| p);
+
+	// compare slash proportions rather than slash values to avoid issues due to rounding
+	// error.
+	if slash > prior_slash_p {
+		<ValidatorSlashInEra<T>>::insert(&slash_era, stash, &(slash, own_slash));
+	} else {
+		// we slash based on the max in era - this new event is not the max,
+		// so neither the validator or any nominators will need an update.
+		return None;
+	}
+
+	// apply slash to validator.
+	let (val_slashed, mut reward_payout, in_current_span) = update_spans::<T>(
+		stash,
+		window_start,
+		now,
+		slash_era,
+		own_slash,
+		reward_proportion,
+	)?;
+
+	if in_current_span {
+		// misbehavior occurred within the current slashing span - the validator
+		// should not continue in the next election.
+		<Validators<T>>::remove(stash);
+		<Nominators<T>>::remove(stash);
+
+		// make sure to disable validator till the end of this session.
+		if let Some(controller) = <Module<T>>::bonded(stash) {
+			let _ = <session::Module<T>>::disable(&controller);
+		}
+	}
+
+	let mut nominators_slashed = Vec::new();
+	reward_payout += slash_nominators::<T>(params, prior_slash_p, &mut nominators_slashed);
+
+	Some(UnappliedSlash {
+		validator: stash.clone(),
+		own: val_slashed,
+		others: nominators_slashed,
+		reporters: Vec::new(),
+		payout: reward_payout,
+	})
+}
+
+/// Slash nominators. Accepts general parameters and the prior slash percentage of the validator.
+///
+/// Returns the amount of reward to pay out.
+fn slash_nominators<T: Trait>(
+	params: SlashParams<T>,
+	prior_slash_p: Perbill,
+	nominators_slashed: &mut Vec<(T::AccountId, BalanceOf<T>)>,
+) -> BalanceOf<T> {
+	let SlashParams {
+		slash,
+		exposure,
+		slash_era,
+		window_start,
+		now,
+		reward_proportion,
+		..
+	} = params;
+
+	let mut reward_payout = Zero::zero();
+
+	nominators_slashed.reserve(exposure.others.len());
+	for nominator in &exposure.others {
+		let stash = &nominator.who;
+
+		// the era slash of a nominator always grows, as it is the sum of the slashes
+		// of all the validators it backed in that era.
+		let era_slash = {
+			let own_slash_prior = prior_slash_p * nominator.value;
+			let own_slash_by_validator = slash * nominator.value;
+			let own_slash_difference = own_slash_by_validator.saturating_sub(own_slash_prior);
+
+			let mut era_slash = <NominatorSlashInEra<T>>::get(&slash_era, stash)
+				.unwrap_or_else(|| Zero::zero());
+
+			era_slash += own_slash_difference;
+
+			<NominatorSlashInEra<T>>::insert(&slash_era, stash, &era_slash);
+
+			era_slash
+		};
+
+		// compare the era slash against other eras in the same span.
+		if let Some((slashed, reward, _)) = update_spans::<T>(
+			stash,
+			window_start,
+			now,
+			slash_era,
+			era_slash,
+			reward_proportion,
+		) {
+			if !slashed.is_zero() {
+				nominators_slashed.push((stash.clone(), slashed));
+			}
+			reward_payout += reward;
+		}
+	}
+
+	reward_payout
+}
+
+/// Compares `slash` against the maximum slash of the span containing `slash_era` and
+/// records it if it is higher.
+///
+/// Returns the amount which is newly to be slashed from the stash, the newly earned reporter
+/// reward and whether the offence happened in the ongoing span of the stash. In the latter case,
+/// the ongoing span is ended. Returns `None` if the era lies before the slashing window.
+fn update_spans<T: Trait>(
+	stash: &T::AccountId,
+	window_start: EraIndex,
+	now: EraIndex,
+	slash_era: EraIndex,
+	slash: BalanceOf<T>,
+	reward_proportion: Perbill,
+) -> Option<(BalanceOf<T>, BalanceOf<T>, bool)> {
+	let mut spans = <Spans<T>>::get(stash).unwrap_or_else(|| SlashingSpans::new(window_start));
+	if let Some((start, end)) = spans.prune(window_start) {
+		for span_index in start..end {
+			<SpanSlash<T>>::remove(&(stash.clone(), span_index));
+		}
+	}
+
+	let target_span = spans.iter().find(|span| span.contains_era(slash_era))?;
+	let span_slash_key = (stash.clone(), target_span.index);
+	let mut span_record = <SpanSlash<T>>::get(&span_slash_key);
+
+	let mut slashed = Zero::zero();
+	let mut reward = Zero::zero();
+	if span_record.slashed < slash {
+		// new maximum span slash. apply the difference.
+		slashed = slash - span_record.slashed;
+		span_record.slashed = slash;
+
+		// compute reward.
+		let target_reward = reward_proportion * (Perbill::from_percent(REWARD_F1) * slash);
+		reward = target_reward.saturating_sub(span_record.paid_out);
+		span_record.paid_out += reward;
+
+		<SpanSlash<T>>::insert(&span_slash_key, &span_record);
+	}
+
+	let in_current_span = target_span.index == spans.span_index;
+	if in_current_span {
+		spans.end_span(now);
+	}
+	<Spans<T>>::insert(stash, &spans);
+
+	Some((slashed, reward, in_current_span))
+}
+
+/// Clear slashing metadata for an obsolete era.
+pub(crate) fn clear_era_metadata<T: Trait>(obsolete_era: EraIndex) {
+	<ValidatorSlashInEra<T>>::remove_prefix(&obsolete_era);
+	<NominatorSlashInEra<T>>::remove_prefix(&obsolete_era);
+}
+
+/// Clear slashing metadata for a dead account.
+pub(crate) fn clear_stash_metadata<T: Trait>(stash: &T::AccountId) {
+	let spans = match <Spans<T>>::take(stash) {
+		None => return,
+		Some(s) => s,
+	};
+
+	// kill slashing-span metadata for account.
+	//
+	// this can only happen while the account is staked _if_ they are completely slashed.
+	// in that case, they may re-bond, but it would count again as span 0. Further ancient
+	// slashes would slash into this new bond, since metadata has now been cleared.
+	for span in spans.iter() {
+		<SpanSlash<T>>::remove(&(stash.clone(), span.index));
+	}
+}
+
+// apply the slash to a stash account, deducting any missing funds from the reward
+// payout, saturating at 0. this is mildly unfair but also an edge-case that
+// can only occur when overlapping locked funds have been slashed.
+fn do_slash<T: Trait>(
+	stash: &T::AccountId,
+	value: BalanceOf<T>,
+	reward_payout: &mut BalanceOf<T>,
+	slashed_imbalance: &mut NegativeImbalanceOf<T>,
+) {
+	let controller = match <Module<T>>::bonded(stash) {
+		None => return, // defensive: should always exist.
+		Some(c) => c,
+	};
+
+	let mut ledger = match <Module<T>>::ledger(&controller) {
+		Some(ledger) => ledger,
+		None => return, // nothing to do.
+	};
+
+	let value = ledger.slash(value, T::Currency::minimum_balance());
+
+	if !value.is_zero() {
+		let (imbalance, missing) = T::Currency::slash(stash, value);
+		slashed_imbalance.subsume(imbalance);
+
+		if !missing.is_zero() {
+			// deduct overslash from the reward payout
+			*reward_payout = reward_payout.saturating_sub(missing);
+		}
+
+		<Module<T>>::update_ledger(&controller, &ledger);
+
+		// trigger the event
+		<Module<T>>::deposit_event(RawEvent::Slash(stash.clone(), value));
+	}
+}
+
+/// Apply a previously-unapplied slash.
+pub(crate) fn apply_slash<T: Trait>(unapplied_slash: UnappliedSlash<T::AccountId, BalanceOf<T>>) {
+	let mut slashed_imbalance = NegativeImbalanceOf::<T>::zero();
+	let mut reward_payout = unapplied_slash.payout;
+
+	do_slash::<T>(
+		&unapplied_slash.validator,
+		unapplied_slash.own,
+		&mut reward_payout,
+		&mut slashed_imbalance,
+	);
+
+	for &(ref nominator, nominator_slash) in &unapplied_slash.others {
+		do_slash::<T>(
+			&nominator,
+			nominator_slash,
+			&mut reward_payout,
+			&mut slashed_imbalance,
+		);
+	}
+
+	pay_reporters::<T>(reward_payout, slashed_imbalance, &unapplied_slash.reporters);
+}
+
+/// Apply a reward payout to some reporters, paying the rewards out of the slashed imbalance.
+fn pay_reporters<T: Trait>(
+	reward_payout: BalanceOf<T>,
+	slashed_imbalance: NegativeImbalanceOf<T>,
+	reporters: &[T::AccountId],
+) {
+	if reward_payout.is_zero() || reporters.is_empty() {
+		// nobody to pay out to or nothing to pay;
+		// just treat the whole value as slashed.
+		T::Slash::on_unbalanced(slashed_imbalance);
+		return
+	}
+
+	// take rewards out of the slashed imbalance.
+	let reward_payout = reward_payout.min(slashed_imbalance.peek());
+	let (mut reward_payout, mut value_slashed) = slashed_imbalance.split(reward_payout);
+
+	let per_reporter = reward_payout.peek() / (reporters.len() as u32).into();
+	for reporter in reporters {
+		let (reporter_reward, rest) = reward_payout.split(per_reporter);
+		reward_payout = rest;
+
+		// this cancels out the reporter reward imbalance internally, leading
+		// to no change in total issuance.
+		T::Currency::resolve_creating(reporter, reporter_reward);
+	}
+
+	// the rest goes to the on-slash imbalance handler (e.g. treasury)
+	value_slashed.subsume(reward_payout); // remainder of reward division remains.
+	T::Slash::on_unbalanced(value_slashed);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn single_slashing_span() {
+		let spans = SlashingSpans {
+			span_index: 0,
+			last_start: 1000,
+			prior: Vec::new(),
+		};
+
+		assert_eq!(
+			spans.iter().collect::<Vec<_>>(),
+			vec![SlashingSpan { index: 0, start: 1000, length: None }],
+		);
+	}
+
+	#[test]
+	fn many_prior_spans() {
+		let spans = SlashingSpans {
+			span_index: 10,
+			last_start: 1000,
+			prior: vec![10, 9, 8, 10],
+		};
+
+		assert_eq!(
+			spans.iter().collect::<Vec<_>>(),
+			vec![
+				SlashingSpan { index: 10, start: 1000, length: None },
+				SlashingSpan { index: 9, start: 990, length: Some(10) },
+				SlashingSpan { index: 8, start: 981, length: Some(9) },
+				SlashingSpan { index: 7, start: 973, length: Some(8) },
+				SlashingSpan { index: 6, start: 963, length: Some(10) },
+			],
+		)
+	}
+
+	#[test]
+	fn pruning_spans() {
+		let mut spans = SlashingSpans {
+			span_index: 10,
+			last_start: 1000,
+			prior: vec![10, 9, 8, 10],
+		};
+
+		assert_eq!(spans.prune(981), Some((6, 8)));
+		assert_eq!(
+			spans.iter().collect::<Vec<_>>(),
+			vec![
+				SlashingSpan { index: 10, start: 1000, length: None },
+				SlashingSpan { index: 9, start: 990, length: Some(10) },
+				SlashingSpan { index: 8, start: 981, length: Some(9) },
+			],
+		);
+
+		assert_eq!(spans.prune(982), None);
+		assert_eq!(spans.prune(989), None);
+		assert_eq!(spans.prune(1000), Some((8, 10)));
+		assert_eq!(
+			spans.iter().collect::<Vec<_>>(),
+			vec![SlashingSpan { index: 10, start: 1000, length: None }],
+		);
+
+		// now all in one shot.
+		let mut spans = SlashingSpans {
+			span_index: 10,
+			last_start: 1000,
+			prior: vec![10, 9, 8, 10],
+		};
+		assert_eq!(spans.prune(2000), Some((6, 10)));
+		assert_eq!(
+			spans.iter().collect::<Vec<_>>(),
+			vec![SlashingSpan { index: 10, start: 2000, length: None }],
+		);
+	}
+
+	#[test]
+	fn ending_span() {
+		let mut spans = SlashingSpans {
+			span_index: 1,
+			last_start: 10,
+			prior: Vec::new(),
+		};
+
+		assert!(spans.end_span(10));
+
+		assert_eq!(
+			spans.iter().collect::<Vec<_>>(),
+			vec![
+				SlashingSpan { index: 2, start: 11, length: None },
+				SlashingSpan { index: 1, start: 10, length: Some(1) },
+			],
+		);
+
+		assert!(spans.end_span(15));
+		assert_eq!(
+			spans.iter().collect::<Vec<_>>(),
+			vec![
+				SlashingSpan { index: 3, start: 16, length: None },
+				SlashingSpan { index: 2, start: 11, length: Some(5) },
+				SlashingSpan { index: 1, start: 10, length: Some(1) },
+			],
+		);
+
+		// does nothing if not a valid end.
+		assert!(!spans.end_span(15));
+		assert_eq!(
+			spans.iter().collect::<Vec<_>>(),
+			vec![
+				SlashingSpan { index: 3, start: 16, length: None },
+				SlashingSpan { index: 2, start: 11, length: Some(5) },
+				SlashingSpan { index: 1, start: 10, length: Some(1) },
+			],
+		);
+	}
+}
@@ -321,7 +321,7 @@ fn slashing_does_not_cause_underflow() {
 fn rewards_should_work() {
 	// should check that:
 	// * rewards get recorded per session
-	// * rewards get paid per Era
+	// * rewards get claimed per Era
 	// * Check that nominators are also rewarded
 	with_externalities(&mut ExtBuilder::default()
 	.build(),
@@ -341,18 +341,20 @@ fn rewards_should_work() {
 
 		// check the balance of a validator accounts.
 		assert_eq!(Balances::total_balance(&11), 1000);
-		// and the nominator (to-be)
+		// and the nominator (to-be), controlled by 1.
 		let _ = Balances::make_free_balance_be(&2, 500);
 		assert_eq!(Balances::total_balance(&2), 500);
+		assert_ok!(Staking::bond(Origin::signed(2), 1, 500, RewardDestination::Stash));
 
 		// add a dummy nominator.
-		<Stakers<Test>>::insert(&11, Exposure {
+		let exposure = Exposure {
 			own: 500, // equal division indicates that the reward will be equally divided among validator and nominator.
 			total: 1000,
 			others: vec![IndividualExposure {who: 2, value: 500 }]
-		});
+		};
+		<Stakers<Test>>::insert(&11, &exposure);
+		<ErasStakers<Test>>::insert(0, 11, exposure);
 
-		<Payee<Test>>::insert(&2, RewardDestination::Stash);
 		assert_eq!(Staking::payee(2), RewardDestination::Stash);
 		assert_eq!(Staking::payee(11), RewardDestination::Controller);
 
@@ -386,6 +388,12 @@ fn rewards_should_work() {
 		assert_eq!(Staking::current_era(), 1);
 		assert_eq!(Session::current_index(), 3);
 
+		// nothing is paid until claimed.
+		assert_eq!(Balances::total_balance(&10), 1);
+		assert_eq!(Balances::total_balance(&2), 500);
+
+		make_all_reward_payment(0);
+
 		assert_eq!(Balances::total_balance(&10), 1 + (3*session_reward)/2);
 		assert_eq!(Balances::total_balance(&2), 500 + (3*session_reward)/2);
 	});
@@ -423,6 +431,7 @@ fn multi_era_reward_should_work() {
 		assert_eq!(Staking::current_era_reward(), 2*session_reward);
 
 		start_session(3);
+		make_all_reward_payment(0);
 
 		// 1 + sum of of the session rewards accumulated
 		let recorded_balance = 1 + 3*session_reward;
@@ -440,6 +449,7 @@ fn multi_era_reward_should_work() {
 
 		// new era is triggered here.
 		start_session(6);
+		make_all_reward_payment(1);
 
 		// pay time
 		assert_eq!(Balances::total_balance(&10), 3*new_session_reward + recorded_balance);
@@ -660,7 +670,8 @@ fn nominating_and_rewards_should_work() {
 		// 10 and 20 have more votes, they will be chosen by phragmen.
 		assert_eq_uvec!(Session::validators(), vec![20, 10]);
 
-		// OLD validators must have already received some rewards.
+		// OLD validators can claim their rewards.
+		make_all_reward_payment(0);
 		assert_eq!(Balances::total_balance(&40), 1 + 3 * session_reward);
 		assert_eq!(Balances::total_balance(&30), 1 + 3 * session_reward);
 
@@ -724,6 +735,7 @@ fn nominating_and_rewards_should_work() {
 
 
 		start_era(2);
+		make_all_reward_payment(1);
 		// next session reward.
 		let new_session_reward = Staking::session_reward() * 3 * Staking::slot_stake();
 
@@ -785,6 +797,7 @@ fn nominators_also_get_slashed() {
 
 		// new era, pay rewards,
 		start_era(1);
+		make_all_reward_payment(0);
 
 		// Nominator stash didn't collect any.
 		assert_eq!(Balances::total_balance(&2), initial_balance);
@@ -978,6 +991,7 @@ fn reward_destination_works() {
 		// Move forward the system for payment
 		Timestamp::set_timestamp(5);
 		start_era(1);
+		make_all_reward_payment(0);
 
 		// Check that RewardDestination is Staked (default)
 		assert_eq!(Staking::payee(&11), RewardDestination::Staked);
@@ -999,6 +1013,7 @@ fn reward_destination_works() {
 		// Move forward the system for payment
 		Timestamp::set_timestamp(10);
 		start_era(2);
+		make_all_reward_payment(1);
 
 		// Check that RewardDestination is Stash
 		assert_eq!(Staking::payee(&11), RewardDestination::Stash);
@@ -1023,6 +1038,7 @@ fn reward_destination_works() {
 		// Move forward the system for payment
 		Timestamp::set_timestamp(15);
 		start_era(3);
+		make_all_reward_payment(2);
 		let session_reward2 = 3 * Staking::current_session_reward(); // 1010 (1* slot_stake)
 
 		// Check that RewardDestination is Controller
@@ -1059,20 +1075,24 @@ fn validator_payment_prefs_work() {
 		assert_eq!(Balances::total_balance(&10), 1);
 		// check the balance of a validator's stash accounts.
 		assert_eq!(Balances::total_balance(&11), stash_initial_balance);
-		// and the nominator (to-be)
+		// and the nominator (to-be), controlled by 1.
 		let _ = Balances::make_free_balance_be(&2, 500);
+		assert_ok!(Staking::bond(Origin::signed(2), 1, 500, RewardDestination::Stash));
 
 		// add a dummy nominator.
-		<Stakers<Test>>::insert(&11, Exposure {
+		let exposure = Exposure {
 			own: 500, // equal division indicates that the reward will be equally divided among validator and nominator.
 			total: 1000,
 			others: vec![IndividualExposure {who: 2, value: 500 }]
-		});
-		<Payee<Test>>::insert(&2, RewardDestination::Stash);
-		<Validators<Test>>::insert(&11, ValidatorPrefs {
+		};
+		<Stakers<Test>>::insert(&11, &exposure);
+		<ErasStakers<Test>>::insert(0, 11, exposure);
+		let prefs = ValidatorPrefs {
 			unstake_threshold: 3,
 			validator_payment: validator_cut
-		});
+		};
+		<Validators<Test>>::insert(&11, &prefs);
+		<ErasValidatorPrefs<Test>>::insert(0, 11, prefs);
 
 		// ------------ Fast forward
 		// Block 3 => Session 1 => Era 0
@@ -1100,6 +1120,7 @@ fn validator_payment_prefs_work() {
 		Session::on_initialize(System::block_number());
 		assert_eq!(Staking::current_era(), 1);
 		assert_eq!(Session::current_index(), 3);
+		make_all_reward_payment(0);
 
 		// whats left to be shared is the sum of 3 rounds minus the validator's cut.
 		let shared_cut = 3 * session_reward - validator_cut;
@@ -1307,10 +1328,12 @@ fn slot_stake_is_least_staked_validator_and_exposure_defines_maximum_punishment(
 		// Now lets lower account 20 stake
 		<Stakers<Test>>::insert(&21, Exposure { total: 69, own: 69, others: vec![] });
 		assert_eq!(Staking::stakers(&21).total, 69);
-		<Ledger<Test>>::insert(&20, StakingLedger { stash: 22, total: 69, active: 69, unlocking: vec![] });
+		<Ledger<Test>>::insert(&20, StakingLedger { stash: 21, total: 69, active: 69, unlocking: vec![] });
 
-		// New era --> rewards are paid --> stakes are changed
+		// New era --> rewards are paid --> stakes are changed in the next election
 		start_era(1);
+		make_all_reward_payment(0);
+		start_era(2);
 
 		// -- new balances + reward
 		assert_eq!(Staking::stakers(&11).total, 1000 + 30);
@@ -1744,16 +1767,20 @@ fn bond_with_no_staked_value() {
 		let initial_balance_2 = Balances::free_balance(&2);
 		let initial_balance_4 = Balances::free_balance(&4);
 
-		// Stingy validator.
-		assert_ok!(Staking::bond(Origin::signed(1), 2, 1, RewardDestination::Controller));
-		assert_ok!(Staking::validate(Origin::signed(2), ValidatorPrefs::default()));
-
 		start_era(1);
 
 		assert_eq_uvec!(Session::validators(), vec![30, 20, 10]);
 
-		// min of 10, 20 and 30 (30 got a payout into staking so it raised it from 1 to 31).
-		assert_eq!(Staking::slot_stake(), 31);
+		// min of 10, 20 and 30.
+		assert_eq!(Staking::slot_stake(), 1);
+
+		// 30 gets a payout into staking so it raises its stake from 1 to 31.
+		make_all_reward_payment(0);
+		assert_eq!(Staking::slashable_balance_of(&31), 31);
+
+		// Stingy validator.
+		assert_ok!(Staking::bond(Origin::signed(1), 2, 1, RewardDestination::Controller));
+		assert_ok!(Staking::validate(Origin::signed(2), ValidatorPrefs::default()));
 
 		// make the stingy one elected.
 		assert_ok!(Staking::bond(Origin::signed(3), 4, 500, RewardDestination::Controller));
@@ -1764,6 +1791,7 @@ fn bond_with_no_staked_value() {
 		assert_eq!(Balances::free_balance(&4), initial_balance_4);
 
 		start_era(2);
+		make_all_reward_payment(1);
 
 		// Stingy one is selected
 		assert_eq_uvec!(Session::validators(), vec![20, 10, 2]);
@@ -1780,6 +1808,7 @@ fn bond_with_no_staked_value() {
 		assert_eq!(Balances::free_balance(&4), initial_balance_4);
 
 		start_era(3);
+		make_all_reward_payment(2);
 
 		// Approximation resulting from Perbill conversion
 		let approximation = 1;
@@ -1820,11 +1849,13 @@ fn bond_with_little_staked_value_bounded_by_slot_stake() {
 		assert_eq!(Staking::slot_stake(), 1);
 
 		// Old ones are rewarded.
+		make_all_reward_payment(0);
 		assert_eq!(Balances::free_balance(&10), initial_balance_10 + 30);
 		// no rewards paid to 2. This was initial election.
 		assert_eq!(Balances::free_balance(&2), initial_balance_2);
 
 		start_era(2);
+		make_all_reward_payment(1);
 
 		assert_eq_uvec!(Session::validators(), vec![20, 10, 2]);
 		assert_eq!(Staking::slot_stake(), 1);
@@ -2091,10 +2122,12 @@ fn reward_validator_slashing_validator_doesnt_overflow() {
 
 		// Set staker
 		let _ = Balances::make_free_balance_be(&11, stake);
-		<Stakers<Test>>::insert(&11, Exposure { total: stake, own: stake, others: vec![] });
+		<ErasStakers<Test>>::insert(0, 11, Exposure { total: stake, own: stake, others: vec![] });
+		// Split evenly between the two validators of the era.
+		<ErasValidatorReward<Test>>::insert(0, reward_slash * 2);
 
 		// Check reward
-		Staking::reward_validator(&11, reward_slash);
+		assert_ok!(Staking::payout_validator(Origin::signed(10), 0));
 		assert_eq!(Balances::total_balance(&11), stake * 2);
 
 		// Set staker
@@ -2110,3 +2143,410 @@ fn reward_validator_slashing_validator_doesnt_overflow() {
 		assert_eq!(Balances::total_balance(&2), 1);
 	})
 }
+
+/// Removes session rewards so that balances only change through slashing.
+fn disable_rewards() {
+	<SessionReward<Test>>::put(Perbill::zero());
+	<CurrentSessionReward<Test>>::put(0);
+}
+
+fn exposure_of_nominator(validator: AccountId, nominator: AccountId) -> Balance {
+	Staking::stakers(&validator).others.iter()
+		.find(|i| i.who == nominator)
+		.map(|i| i.value)
+		.unwrap_or_default()
+}
+
+#[test]
+fn offence_slashes_validator_and_nominators_proportionally() {
+	with_externalities(&mut ExtBuilder::default().build(), || {
+		let nominated = exposure_of_nominator(11, 101);
+		assert!(nominated > 0);
+		let balance_101 = Balances::free_balance(&101);
+
+		on_offence_now(
+			&[OffenceDetails { offender: (10, Staking::stakers(&11)), reporters: vec![] }],
+			&[Perbill::from_percent(10)],
+		);
+
+		// The validator loses 10% of its own exposure, the nominator 10% of its exposure.
+		assert_eq!(Balances::free_balance(&11), 900);
+		assert_eq!(Staking::ledger(&10).unwrap().active, 900);
+		assert_eq!(Balances::free_balance(&101), balance_101 - nominated / 10);
+
+		// The validator is chilled and disabled.
+		assert!(!<Validators<Test>>::exists(&11));
+		assert!(is_disabled(10));
+
+		// Other validators are untouched.
+		assert_eq!(Balances::free_balance(&21), 2000);
+	});
+}
+
+#[test]
+fn offence_rewards_reporters() {
+	with_externalities(&mut ExtBuilder::default().build(), || {
+		let nominated = exposure_of_nominator(11, 101);
+		let reward_of = |slash: Balance| {
+			Staking::slash_reward_fraction() * (Perbill::from_percent(50) * slash)
+		};
+		let total_reward = reward_of(100) + reward_of(nominated / 10);
+
+		on_offence_now(
+			&[OffenceDetails { offender: (10, Staking::stakers(&11)), reporters: vec![1, 2] }],
+			&[Perbill::from_percent(10)],
+		);
+
+		assert_eq!(Balances::free_balance(&1), 10 + total_reward / 2);
+		assert_eq!(Balances::free_balance(&2), 20 + total_reward / 2);
+	});
+}
+
+#[test]
+fn only_the_maximum_slash_of_an_era_is_applied() {
+	with_externalities(&mut ExtBuilder::default().build(), || {
+		on_offence_now(
+			&[OffenceDetails { offender: (10, Staking::stakers(&11)), reporters: vec![] }],
+			&[Perbill::from_percent(10)],
+		);
+		assert_eq!(Balances::free_balance(&11), 900);
+
+		// A lesser slash in the same era is a no-op.
+		on_offence_now(
+			&[OffenceDetails { offender: (10, Staking::stakers(&11)), reporters: vec![] }],
+			&[Perbill::from_percent(5)],
+		);
+		assert_eq!(Balances::free_balance(&11), 900);
+
+		// A greater slash only slashes the difference.
+		on_offence_now(
+			&[OffenceDetails { offender: (10, Staking::stakers(&11)), reporters: vec![] }],
+			&[Perbill::from_percent(20)],
+		);
+		assert_eq!(Balances::free_balance(&11), 800);
+	});
+}
+
+#[test]
+fn slashes_are_deferred() {
+	with_externalities(&mut ExtBuilder::default().slash_defer_duration(2).build(), || {
+		disable_rewards();
+		let balance_101 = Balances::free_balance(&101);
+		let nominated = exposure_of_nominator(11, 101);
+
+		on_offence_now(
+			&[OffenceDetails { offender: (10, Staking::stakers(&11)), reporters: vec![] }],
+			&[Perbill::from_percent(10)],
+		);
+
+		// Nothing is slashed yet, but the validator is chilled right away.
+		assert_eq!(Balances::free_balance(&11), 1000);
+		assert_eq!(Staking::unapplied_slashes(0).len(), 1);
+		assert!(!<Validators<Test>>::exists(&11));
+
+		start_era(1);
+		assert_eq!(Balances::free_balance(&11), 1000);
+
+		start_era(2);
+		assert_eq!(Balances::free_balance(&11), 900);
+		assert_eq!(Balances::free_balance(&101), balance_101 - nominated / 10);
+		assert!(Staking::unapplied_slashes(0).is_empty());
+	});
+}
+
+#[test]
+fn deferred_slashes_can_be_cancelled() {
+	with_externalities(&mut ExtBuilder::default().slash_defer_duration(2).build(), || {
+		disable_rewards();
+
+		on_offence_now(
+			&[
+				OffenceDetails { offender: (10, Staking::stakers(&11)), reporters: vec![] },
+				OffenceDetails { offender: (20, Staking::stakers(&21)), reporters: vec![] },
+			],
+			&[Perbill::from_percent(10), Perbill::from_percent(10)],
+		);
+		assert_eq!(Staking::unapplied_slashes(0).len(), 2);
+
+		assert_noop!(
+			Staking::cancel_deferred_slash(Origin::signed(1), 0, vec![0]),
+			"Invalid origin"
+		);
+		assert_noop!(
+			Staking::cancel_deferred_slash(Origin::ROOT, 0, vec![]),
+			"slash indices must not be empty"
+		);
+		assert_noop!(
+			Staking::cancel_deferred_slash(Origin::ROOT, 0, vec![1, 0]),
+			"slash indices must be sorted and unique"
+		);
+		assert_noop!(
+			Staking::cancel_deferred_slash(Origin::ROOT, 0, vec![2]),
+			"slash index out of bounds"
+		);
+
+		assert_ok!(Staking::cancel_deferred_slash(Origin::ROOT, 0, vec![0]));
+		assert_eq!(Staking::unapplied_slashes(0).len(), 1);
+
+		start_era(2);
+
+		// Only the slash of the second validator is applied.
+		assert_eq!(Balances::free_balance(&11), 1000);
+		assert_eq!(Balances::free_balance(&21), 1900);
+	});
+}
+
+#[test]
+fn invulnerables_are_not_slashed_by_offences() {
+	with_externalities(&mut ExtBuilder::default().build(), || {
+		<Invulnerables<Test>>::put(vec![11]);
+
+		on_offence_now(
+			&[OffenceDetails { offender: (10, Staking::stakers(&11)), reporters: vec![] }],
+			&[Perbill::from_percent(50)],
+		);
+
+		assert_eq!(Balances::free_balance(&11), 1000);
+		assert!(<Validators<Test>>::exists(&11));
+		assert!(!is_disabled(10));
+	});
+}
+
+#[test]
+fn ledger_slash_takes_from_active_then_unlocking() {
+	let mut ledger = StakingLedger {
+		stash: 11u64,
+		total: 100u64,
+		active: 40,
+		unlocking: vec![UnlockChunk { value: 30, era: 1 }, UnlockChunk { value: 30, era: 2 }],
+	};
+
+	assert_eq!(ledger.slash(50, 0), 50);
+	assert_eq!(ledger.active, 0);
+	assert_eq!(ledger.total, 50);
+	assert_eq!(ledger.unlocking, vec![UnlockChunk { value: 20, era: 1 }, UnlockChunk { value: 30, era: 2 }]);
+
+	// Dust below the minimum balance is slashed too.
+	assert_eq!(ledger.slash(15, 5), 20);
+	assert_eq!(ledger.total, 30);
+	assert_eq!(ledger.unlocking, vec![UnlockChunk { value: 30, era: 2 }]);
+}
+
+#[test]
+fn reward_points_split_the_era_payout() {
+	with_externalities(&mut ExtBuilder::default().nominate(false).build(), || {
+		assert_ok!(Staking::set_payee(Origin::signed(10), RewardDestination::Controller));
+		assert_ok!(Staking::set_payee(Origin::signed(20), RewardDestination::Controller));
+
+		// 30 is not elected, so its points are ignored.
+		Staking::reward_by_ids(vec![(10, 3), (20, 1), (30, 5)]);
+		let points = Staking::eras_reward_points(0);
+		assert_eq!(points.total, 4);
+		assert_eq_uvec!(points.individual, vec![(11, 3), (21, 1)]);
+
+		start_era(1);
+		let era_payout = Staking::eras_validator_reward(0).unwrap();
+		assert_eq!(era_payout, 2 * 3 * 10);
+
+		make_all_reward_payment(0);
+		assert_eq!(Balances::total_balance(&10), 1 + era_payout * 3 / 4);
+		assert_eq!(Balances::total_balance(&20), 1 + era_payout / 4);
+	});
+}
+
+#[test]
+fn rewards_can_only_be_claimed_once_for_finished_eras() {
+	with_externalities(&mut ExtBuilder::default().build(), || {
+		// the era is not finished yet.
+		assert_noop!(Staking::payout_validator(Origin::signed(10), 0), "no reward for era");
+		assert_noop!(
+			Staking::payout_nominator(Origin::signed(100), 0, vec![11, 21]),
+			"no reward for era"
+		);
+
+		start_era(1);
+
+		// 30 is not a validator of era 0.
+		assert_noop!(Staking::payout_validator(Origin::signed(30), 0), "not a validator of the era");
+		// only controllers can claim.
+		assert_noop!(Staking::payout_validator(Origin::signed(11), 0), "not a controller");
+
+		assert_ok!(Staking::payout_validator(Origin::signed(10), 0));
+		assert_noop!(Staking::payout_validator(Origin::signed(10), 0), "reward already claimed");
+
+		// duplicated validators are only counted once.
+		let balance_101 = Balances::total_balance(&101);
+		assert_ok!(Staking::payout_nominator(Origin::signed(100), 0, vec![11, 11, 21]));
+		let claimed = Balances::total_balance(&101) - balance_101;
+		assert!(claimed > 0);
+		assert_noop!(
+			Staking::payout_nominator(Origin::signed(100), 0, vec![11, 21]),
+			"no reward to claim"
+		);
+		assert_noop!(Staking::payout_nominator(Origin::signed(100), 0, vec![21]), "no reward to claim");
+
+		let reward = Staking::eras_validator_reward(0).unwrap() / 2;
+		let expected = [11, 21].iter()
+			.map(|v| {
+				let exposure = Staking::eras_stakers(0, v);
+				Perbill::from_rational_approximation(exposure_of(&exposure, 101), exposure.total) * reward
+			})
+			.sum::<Balance>();
+		assert_eq!(claimed, expected);
+	});
+}
+
+#[test]
+fn reward_is_not_claimed_if_the_payee_cannot_be_paid() {
+	with_externalities(&mut ExtBuilder::default().build(), || {
+		assert_ok!(Staking::set_payee(Origin::signed(10), RewardDestination::Controller));
+		start_era(1);
+
+		// The controller account does not exist, so nothing can be deposited into it.
+		let _ = Balances::make_free_balance_be(&10, 0);
+		assert_noop!(Staking::payout_validator(Origin::signed(10), 0), "reward payee cannot be paid");
+
+		// Once it exists again, the reward can still be claimed.
+		let _ = Balances::make_free_balance_be(&10, 1);
+		assert_ok!(Staking::payout_validator(Origin::signed(10), 0));
+		assert!(Balances::total_balance(&10) > 1);
+		assert_noop!(Staking::payout_validator(Origin::signed(10), 0), "reward already claimed");
+	});
+}
+
+#[test]
+fn nominator_rewards_are_claimed_per_validator() {
+	with_externalities(&mut ExtBuilder::default().build(), || {
+		start_era(1);
+		let reward = Staking::eras_validator_reward(0).unwrap() / 2;
+		let reward_from = |v| {
+			let exposure = Staking::eras_stakers(0, v);
+			Perbill::from_rational_approximation(exposure_of(&exposure, 101), exposure.total) * reward
+		};
+
+		// the reward from 21 can still be claimed after the one from 11.
+		let balance_101 = Balances::total_balance(&101);
+		assert_ok!(Staking::payout_nominator(Origin::signed(100), 0, vec![11]));
+		assert_eq!(Balances::total_balance(&101) - balance_101, reward_from(&11));
+
+		// already claimed validators are skipped.
+		assert_ok!(Staking::payout_nominator(Origin::signed(100), 0, vec![11, 21]));
+		assert_eq!(Balances::total_balance(&101) - balance_101, reward_from(&11) + reward_from(&21));
+		assert_noop!(
+			Staking::payout_nominator(Origin::signed(100), 0, vec![11, 21]),
+			"no reward to claim"
+		);
+	});
+}
+
+#[test]
+fn only_the_biggest_nominators_are_rewarded() {
+	with_externalities(&mut ExtBuilder::default().build(), || {
+		let count = MAX_NOMINATORS_REWARDED_PER_VALIDATOR as u64 + 1;
+		for i in 0..count {
+			let stash = 1000 + 2 * i;
+			let _ = Balances::make_free_balance_be(&stash, 1000);
+			assert_ok!(Staking::bond(Origin::signed(stash), stash + 1, 100 + i, RewardDestination::Stash));
+			assert_ok!(Staking::nominate(Origin::signed(stash + 1), vec![11]));
+		}
+		start_era(1);
+
+		let stakers = Staking::stakers(&11);
+		assert!(stakers.others.len() > MAX_NOMINATORS_REWARDED_PER_VALIDATOR);
+		let exposure = Staking::eras_stakers(1, 11);
+		assert_eq!(exposure.others.len(), MAX_NOMINATORS_REWARDED_PER_VALIDATOR);
+		assert_eq!((exposure.total, exposure.own), (stakers.total, stakers.own));
+
+		// the nominators left out have no more stake than any rewarded one.
+		let smallest_rewarded = exposure.others.iter().map(|i| i.value).min().unwrap();
+		let left_out = stakers.others.iter().find(|i| !exposure.others.contains(i)).unwrap();
+		assert!(left_out.value <= smallest_rewarded);
+
+		start_era(2);
+		let left_out_controller = Staking::bonded(&left_out.who).unwrap();
+		assert_noop!(
+			Staking::payout_nominator(Origin::signed(left_out_controller), 1, vec![11]),
+			"no reward to claim"
+		);
+		let rewarded_controller = Staking::bonded(&exposure.others[0].who).unwrap();
+		assert_ok!(Staking::payout_nominator(Origin::signed(rewarded_controller), 1, vec![11]));
+	});
+}
+
+fn exposure_of(exposure: &Exposure<AccountId, Balance>, nominator: AccountId) -> Balance {
+	exposure.others.iter()
+		.find(|i| i.who == nominator)
+		.map(|i| i.value)
+		.unwrap_or_default()
+}
+
+#[test]
+fn era_rewards_are_pruned_after_bonding_duration() {
+	with_externalities(&mut ExtBuilder::default().build(), || {
+		start_era(1);
+		assert!(Staking::eras_validator_reward(0).is_some());
+		assert_eq!(Staking::eras_stakers(0, 11), Staking::stakers(&11));
+
+		start_era(BondingDuration::get() + 1);
+		assert!(Staking::eras_validator_reward(0).is_none());
+		assert_eq!(Staking::eras_stakers(0, 11), Default::default());
+		assert_eq!(Staking::eras_reward_points(0), Default::default());
+		assert_noop!(Staking::payout_validator(Origin::signed(10), 0), "no reward for era");
+
+		// the next era can still be claimed.
+		assert_ok!(Staking::payout_validator(Origin::signed(10), 1));
+	});
+}
+
+#[test]
+fn lazy_payouts_migration_snapshots_the_current_era() {
+	with_externalities(&mut ExtBuilder::default().build(), || {
+		start_era(1);
+
+		// the state of a chain before lazy payouts.
+		StorageVersion::kill();
+		MigrateEra::kill();
+		<ErasStakers<Test>>::remove_prefix(1);
+		<ErasValidatorPrefs<Test>>::remove_prefix(1);
+		<ErasRewardPoints<Test>>::remove(1);
+
+		let weight = System::all_extrinsics_weight();
+		Staking::on_initialize(System::block_number());
+		assert_eq!(Staking::migrate_era(), Some(1));
+		assert_eq!(Staking::eras_stakers(1, 11), Staking::stakers(&11));
+		assert_eq!(Staking::eras_reward_points(1).total, 0);
+		assert_eq!(Staking::eras_reward_points(1).individual.len(), 2);
+		// the version, the migration era and the snapshot of two validators are charged for.
+		assert_eq!(System::all_extrinsics_weight(), weight + 7 * MIGRATION_WEIGHT_PER_WRITE);
+		assert_eq!(StorageVersion::get(), STORAGE_VERSION);
+
+		// migrating only happens once.
+		Staking::reward_by_ids(vec![(10, 1)]);
+		Staking::on_initialize(System::block_number());
+		assert_eq!(Staking::eras_reward_points(1).total, 1);
+
+		start_era(2);
+		make_all_reward_payment(1);
+	});
+}
+
+#[test]
+fn bonded_eras_migration_seeds_the_current_era() {
+	with_externalities(&mut ExtBuilder::default().build(), || {
+		start_era(1);
+		assert_eq!(Staking::bonded_eras(), vec![(0, 0), (1, 3)]);
+		start_session(4);
+
+		// the state of a chain before slashes were deferred.
+		StorageVersion::put(1);
+		BondedEras::kill();
+
+		Staking::on_initialize(System::block_number());
+		assert_eq!(Staking::bonded_eras(), vec![(1, 4)]);
+
+		// seeding only happens once, eras keep being added on top.
+		start_era(2);
+		Staking::on_initialize(System::block_number());
+		assert_eq!(Staking::bonded_eras(), vec![(1, 4), (2, 6)]);
+	});
+}
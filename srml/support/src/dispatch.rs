@@ -25,7 +25,9 @@ pub use srml_metadata::{
 	FunctionMetadata, DecodeDifferent, DecodeDifferentArray, FunctionArgumentMetadata,
 	ModuleConstantMetadata, DefaultByte, DefaultByteGetter,
 };
-pub use sr_primitives::weights::{TransactionWeight, Weighable, Weight};
+pub use sr_primitives::weights::{
+	TransactionWeight, Weighable, Weight, ClassifyDispatch, DispatchClass, DispatchInfo, GetDispatchInfo,
+};
 
 /// A type that cannot be instantiated.
 pub enum Never {}
@@ -1085,9 +1087,34 @@ macro_rules! decl_module {
 		impl<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?> $crate::dispatch::Weighable
 			for $call_type<$trait_instance $(, $instance)?>
 		{
+			#[allow(unused_variables)]
 			fn weight(&self, _len: usize) -> $crate::dispatch::Weight {
 				match self {
-					$( $call_type::$fn_name(..) => $crate::dispatch::Weighable::weight(&$weight, _len), )*
+					$(
+						$call_type::$fn_name( $( $param_name ),* ) =>
+							$crate::dispatch::Weighable::weight(&$weight, _len),
+					)*
+					$call_type::__PhantomItem(_, _) => { unreachable!("__PhantomItem should never be used.") },
+				}
+			}
+		}
+
+		// Implement dispatch info function for Call
+		impl<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?> $crate::dispatch::GetDispatchInfo
+			for $call_type<$trait_instance $(, $instance)?>
+		{
+			#[allow(unused_variables)]
+			fn get_dispatch_info(&self, _len: usize) -> $crate::dispatch::DispatchInfo {
+				match self {
+					$(
+						$call_type::$fn_name( $( $param_name ),* ) => {
+							let weight = $weight;
+							$crate::dispatch::DispatchInfo {
+								weight: $crate::dispatch::Weighable::weight(&weight, _len),
+								class: $crate::dispatch::ClassifyDispatch::class(&weight),
+							}
+						},
+					)*
 					$call_type::__PhantomItem(_, _) => { unreachable!("__PhantomItem should never be used.") },
 				}
 			}
@@ -1231,6 +1258,13 @@ macro_rules! impl_outer_dispatch {
 				}
 			}
 		}
+		impl $crate::dispatch::GetDispatchInfo for $call_type {
+			fn get_dispatch_info(&self, len: usize) -> $crate::dispatch::DispatchInfo {
+				match self {
+					$( $call_type::$camelcase(call) => call.get_dispatch_info(len), )*
+				}
+			}
+		}
 		impl $crate::dispatch::Dispatchable for $call_type {
 			type Origin = $origin;
 			type Trait = $call_type;
@@ -1548,6 +1582,12 @@ mod tests {
 
 			#[weight = TransactionWeight::Max]
 			fn weighted() { unreachable!() }
+
+			#[weight = TransactionWeight::Operational(5, 1)]
+			fn operational() { unreachable!() }
+
+			#[weight = TransactionWeight::Basic(*_count * 10, 0)]
+			fn counted(_origin, _count: u32) { unreachable!() }
 		}
 	}
 
@@ -1617,6 +1657,21 @@ mod tests {
 					arguments: DecodeDifferent::Encode(&[]),
 					documentation: DecodeDifferent::Encode(&[]),
 				},
+				FunctionMetadata {
+					name: DecodeDifferent::Encode("operational"),
+					arguments: DecodeDifferent::Encode(&[]),
+					documentation: DecodeDifferent::Encode(&[]),
+				},
+				FunctionMetadata {
+					name: DecodeDifferent::Encode("counted"),
+					arguments: DecodeDifferent::Encode(&[
+						FunctionArgumentMetadata {
+							name: DecodeDifferent::Encode("_count"),
+							ty: DecodeDifferent::Encode("u32"),
+						}
+					]),
+					documentation: DecodeDifferent::Encode(&[]),
+				},
 			];
 
 	struct TraitImpl {}
@@ -1680,5 +1735,26 @@ mod tests {
 		assert_eq!(Call::<TraitImpl>::aux_0().weight(5), 5 /*tx-len*/);
 		// custom basic
 		assert_eq!(Call::<TraitImpl>::aux_3().weight(5), 10 + 100 * 5 );
+		// dependent on the parameters.
+		assert_eq!(Call::<TraitImpl>::counted(3).weight(5), 30);
+	}
+
+	#[test]
+	fn dispatch_info_should_attach_to_call_enum() {
+		// default weight and class.
+		assert_eq!(
+			Call::<TraitImpl>::aux_0().get_dispatch_info(5),
+			DispatchInfo { weight: 5, class: DispatchClass::Normal },
+		);
+		// max weight is still a normal dispatch.
+		assert_eq!(
+			Call::<TraitImpl>::weighted().get_dispatch_info(100),
+			DispatchInfo { weight: 3 * 1024 * 1024, class: DispatchClass::Normal },
+		);
+		// operational.
+		assert_eq!(
+			Call::<TraitImpl>::operational().get_dispatch_info(5),
+			DispatchInfo { weight: 5 + 5, class: DispatchClass::Operational },
+		);
 	}
 }
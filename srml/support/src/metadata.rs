@@ -15,30 +15,44 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 pub use srml_metadata::{
-	DecodeDifferent, FnEncode, RuntimeMetadata, ModuleMetadata, RuntimeMetadataV6,
+	DecodeDifferent, FnEncode, RuntimeMetadata, ModuleMetadata, RuntimeMetadataV7, ExtrinsicMetadata,
 	DefaultByteGetter, RuntimeMetadataPrefixed, StorageEntryMetadata,
 	StorageEntryType, StorageEntryModifier, DefaultByte, StorageHasher
 };
 
-/// Implements the metadata support for the given runtime and all its modules.
+/// Implements the metadata support for the given runtime, its extrinsic format and all its modules.
 ///
 /// Example:
 /// ```compile_fail
-/// impl_runtime_metadata!(for RUNTIME_NAME with modules MODULE0, MODULE2, MODULE3 with Storage);
+/// impl_runtime_metadata!(
+/// 	for RUNTIME_NAME with modules where Extrinsic = UNCHECKED_EXTRINSIC
+/// 		MODULE0, MODULE2, MODULE3 with Storage
+/// );
 /// ```
 ///
-/// In this example, just `MODULE3` implements the `Storage` trait.
+/// In this example, just `MODULE3` implements the `Storage` trait. `UNCHECKED_EXTRINSIC` must
+/// implement `ExtrinsicMetadata`, which describes its version and its signed extensions.
 #[macro_export]
 macro_rules! impl_runtime_metadata {
 	(
-		for $runtime:ident with modules
+		for $runtime:ident with modules where Extrinsic = $ext:ident
 			$( $rest:tt )*
 	) => {
 		impl $runtime {
 			pub fn metadata() -> $crate::metadata::RuntimeMetadataPrefixed {
-				$crate::metadata::RuntimeMetadata::V6 (
-					$crate::metadata::RuntimeMetadataV6 {
+				$crate::metadata::RuntimeMetadata::V7 (
+					$crate::metadata::RuntimeMetadataV7 {
 						modules: $crate::__runtime_modules_to_metadata!($runtime;; $( $rest )*),
+						extrinsic: $crate::metadata::ExtrinsicMetadata {
+							version: <$ext as $crate::runtime_primitives::traits::ExtrinsicMetadata>::VERSION,
+							signed_extensions: <
+								<$ext as $crate::runtime_primitives::traits::ExtrinsicMetadata>::SignedExtensions
+									as $crate::runtime_primitives::traits::SignedExtension
+							>::identifier()
+								.into_iter()
+								.map($crate::metadata::DecodeDifferent::Encode)
+								.collect(),
+						},
 					}
 				).into()
 			}
@@ -230,6 +244,7 @@ mod tests {
 	use srml_metadata::{
 		EventMetadata, StorageEntryModifier, StorageEntryType, FunctionMetadata, StorageEntryMetadata,
 		ModuleMetadata, RuntimeMetadataPrefixed, DefaultByte, ModuleConstantMetadata, DefaultByteGetter,
+		DecodeDifferentArray,
 	};
 	use codec::{Encode, Decode};
 	use crate::traits::Get;
@@ -379,8 +394,34 @@ mod tests {
 		type SomeValue = SystemValue;
 	}
 
+	#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+	pub struct TestExtension;
+	impl crate::runtime_primitives::traits::SignedExtension for TestExtension {
+		type AccountId = u32;
+		type AdditionalSigned = u32;
+
+		fn additional_signed(&self) -> Result<u32, &'static str> { Ok(1) }
+
+		fn identifier() -> Vec<&'static str> { vec!["testextension"] }
+	}
+
+	#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+	pub struct TestExtension2;
+	impl crate::runtime_primitives::traits::SignedExtension for TestExtension2 {
+		type AccountId = u32;
+		type AdditionalSigned = u32;
+
+		fn additional_signed(&self) -> Result<u32, &'static str> { Ok(1) }
+
+		fn identifier() -> Vec<&'static str> { vec!["testextension2"] }
+	}
+
+	type TestExtrinsic = crate::runtime_primitives::generic::UncheckedExtensibleExtrinsic<
+		u32, u32, Call, (), (TestExtension, TestExtension2)
+	>;
+
 	impl_runtime_metadata!(
-		for TestRuntime with modules
+		for TestRuntime with modules where Extrinsic = TestExtrinsic
 			system::Module with Event,
 			event_module::Module with Event Call,
 			event_module2::Module with Event Storage Call,
@@ -407,116 +448,123 @@ mod tests {
 		}
 	}
 
-	const EXPECTED_METADATA: RuntimeMetadata = RuntimeMetadata::V6(
-		RuntimeMetadataV6 {
-			modules: DecodeDifferent::Encode(&[
-				ModuleMetadata {
-					name: DecodeDifferent::Encode("system"),
-					prefix: DecodeDifferent::Encode(FnEncode(|| "")),
-					storage: None,
-					calls: None,
-					event: Some(DecodeDifferent::Encode(
-						FnEncode(||&[
-							EventMetadata {
-								name: DecodeDifferent::Encode("SystemEvent"),
-								arguments: DecodeDifferent::Encode(&[]),
-								documentation: DecodeDifferent::Encode(&[])
-							}
-						])
-					)),
-					constants: DecodeDifferent::Encode(
-						FnEncode(|| &[
-							ModuleConstantMetadata {
-								name: DecodeDifferent::Encode("BlockNumber"),
-								ty: DecodeDifferent::Encode("T::BlockNumber"),
-								value: DecodeDifferent::Encode(
-									DefaultByteGetter(&ConstantBlockNumberByteGetter)
-								),
-								documentation: DecodeDifferent::Encode(&[" Hi, I am a comment."]),
-							},
-							ModuleConstantMetadata {
-								name: DecodeDifferent::Encode("GetType"),
-								ty: DecodeDifferent::Encode("T::AccountId"),
-								value: DecodeDifferent::Encode(
-									DefaultByteGetter(&ConstantGetTypeByteGetter)
-								),
-								documentation: DecodeDifferent::Encode(&[]),
-							},
-							ModuleConstantMetadata {
-								name: DecodeDifferent::Encode("ASSOCIATED_CONST"),
-								ty: DecodeDifferent::Encode("u64"),
-								value: DecodeDifferent::Encode(
-									DefaultByteGetter(&ConstantAssociatedConstByteGetter)
-								),
-								documentation: DecodeDifferent::Encode(&[]),
-							}
-						])
-					),
-				},
-				ModuleMetadata {
-					name: DecodeDifferent::Encode("event_module"),
-					prefix: DecodeDifferent::Encode(FnEncode(|| "")),
-					storage: None,
-					calls: Some(
-						DecodeDifferent::Encode(FnEncode(|| &[
-							FunctionMetadata {
-								name: DecodeDifferent::Encode("aux_0"),
-								arguments: DecodeDifferent::Encode(&[]),
-								documentation: DecodeDifferent::Encode(&[]),
-							}
-						]))),
-					event: Some(DecodeDifferent::Encode(
-						FnEncode(||&[
-							EventMetadata {
-								name: DecodeDifferent::Encode("TestEvent"),
-								arguments: DecodeDifferent::Encode(&["Balance"]),
-								documentation: DecodeDifferent::Encode(&[" Hi, I am a comment."])
-							}
-						])
-					)),
-					constants: DecodeDifferent::Encode(FnEncode(|| &[])),
-				},
-				ModuleMetadata {
-					name: DecodeDifferent::Encode("event_module2"),
-					prefix: DecodeDifferent::Encode(FnEncode(||"TestStorage")),
-					storage: Some(DecodeDifferent::Encode(
-						FnEncode(||&[
-							StorageEntryMetadata {
-								name: DecodeDifferent::Encode("StorageMethod"),
-								modifier: StorageEntryModifier::Optional,
-								ty: StorageEntryType::Plain(DecodeDifferent::Encode("u32")),
-								default: DecodeDifferent::Encode(
-									DefaultByteGetter(
-										&event_module2::__GetByteStructStorageMethod(
-											std::marker::PhantomData::<TestRuntime>
-										)
-									)
-								),
-								documentation: DecodeDifferent::Encode(&[]),
-							}
-						])
-					)),
-					calls: Some(DecodeDifferent::Encode(FnEncode(|| &[]))),
-					event: Some(DecodeDifferent::Encode(
-						FnEncode(||&[
-							EventMetadata {
-								name: DecodeDifferent::Encode("TestEvent"),
-								arguments: DecodeDifferent::Encode(&["Balance"]),
-								documentation: DecodeDifferent::Encode(&[])
-							}
-						])
-					)),
-					constants: DecodeDifferent::Encode(FnEncode(|| &[])),
-				},
-			])
-		}
-	);
+	const EXPECTED_MODULES: DecodeDifferentArray<ModuleMetadata> = DecodeDifferent::Encode(&[
+		ModuleMetadata {
+			name: DecodeDifferent::Encode("system"),
+			prefix: DecodeDifferent::Encode(FnEncode(|| "")),
+			storage: None,
+			calls: None,
+			event: Some(DecodeDifferent::Encode(
+				FnEncode(||&[
+					EventMetadata {
+						name: DecodeDifferent::Encode("SystemEvent"),
+						arguments: DecodeDifferent::Encode(&[]),
+						documentation: DecodeDifferent::Encode(&[])
+					}
+				])
+			)),
+			constants: DecodeDifferent::Encode(
+				FnEncode(|| &[
+					ModuleConstantMetadata {
+						name: DecodeDifferent::Encode("BlockNumber"),
+						ty: DecodeDifferent::Encode("T::BlockNumber"),
+						value: DecodeDifferent::Encode(
+							DefaultByteGetter(&ConstantBlockNumberByteGetter)
+						),
+						documentation: DecodeDifferent::Encode(&[" Hi, I am a comment."]),
+					},
+					ModuleConstantMetadata {
+						name: DecodeDifferent::Encode("GetType"),
+						ty: DecodeDifferent::Encode("T::AccountId"),
+						value: DecodeDifferent::Encode(
+							DefaultByteGetter(&ConstantGetTypeByteGetter)
+						),
+						documentation: DecodeDifferent::Encode(&[]),
+					},
+					ModuleConstantMetadata {
+						name: DecodeDifferent::Encode("ASSOCIATED_CONST"),
+						ty: DecodeDifferent::Encode("u64"),
+						value: DecodeDifferent::Encode(
+							DefaultByteGetter(&ConstantAssociatedConstByteGetter)
+						),
+						documentation: DecodeDifferent::Encode(&[]),
+					}
+				])
+			),
+		},
+		ModuleMetadata {
+			name: DecodeDifferent::Encode("event_module"),
+			prefix: DecodeDifferent::Encode(FnEncode(|| "")),
+			storage: None,
+			calls: Some(
+				DecodeDifferent::Encode(FnEncode(|| &[
+					FunctionMetadata {
+						name: DecodeDifferent::Encode("aux_0"),
+						arguments: DecodeDifferent::Encode(&[]),
+						documentation: DecodeDifferent::Encode(&[]),
+					}
+				]))),
+			event: Some(DecodeDifferent::Encode(
+				FnEncode(||&[
+					EventMetadata {
+						name: DecodeDifferent::Encode("TestEvent"),
+						arguments: DecodeDifferent::Encode(&["Balance"]),
+						documentation: DecodeDifferent::Encode(&[" Hi, I am a comment."])
+					}
+				])
+			)),
+			constants: DecodeDifferent::Encode(FnEncode(|| &[])),
+		},
+		ModuleMetadata {
+			name: DecodeDifferent::Encode("event_module2"),
+			prefix: DecodeDifferent::Encode(FnEncode(||"TestStorage")),
+			storage: Some(DecodeDifferent::Encode(
+				FnEncode(||&[
+					StorageEntryMetadata {
+						name: DecodeDifferent::Encode("StorageMethod"),
+						modifier: StorageEntryModifier::Optional,
+						ty: StorageEntryType::Plain(DecodeDifferent::Encode("u32")),
+						default: DecodeDifferent::Encode(
+							DefaultByteGetter(
+								&event_module2::__GetByteStructStorageMethod(
+									std::marker::PhantomData::<TestRuntime>
+								)
+							)
+						),
+						documentation: DecodeDifferent::Encode(&[]),
+					}
+				])
+			)),
+			calls: Some(DecodeDifferent::Encode(FnEncode(|| &[]))),
+			event: Some(DecodeDifferent::Encode(
+				FnEncode(||&[
+					EventMetadata {
+						name: DecodeDifferent::Encode("TestEvent"),
+						arguments: DecodeDifferent::Encode(&["Balance"]),
+						documentation: DecodeDifferent::Encode(&[])
+					}
+				])
+			)),
+			constants: DecodeDifferent::Encode(FnEncode(|| &[])),
+		},
+	]);
 
 	#[test]
 	fn runtime_metadata() {
 		let metadata_encoded = TestRuntime::metadata().encode();
 		let metadata_decoded = RuntimeMetadataPrefixed::decode(&mut &metadata_encoded[..]);
-		let expected_metadata: RuntimeMetadataPrefixed = EXPECTED_METADATA.into();
+		let expected_metadata: RuntimeMetadataPrefixed = RuntimeMetadata::V7(
+			RuntimeMetadataV7 {
+				modules: EXPECTED_MODULES,
+				extrinsic: ExtrinsicMetadata {
+					version: 3,
+					signed_extensions: vec![
+						DecodeDifferent::Encode("testextension"),
+						DecodeDifferent::Encode("testextension2"),
+					],
+				},
+			}
+		).into();
 
 		pretty_assertions::assert_eq!(expected_metadata, metadata_decoded.unwrap());
 	}
@@ -233,6 +233,7 @@ macro_rules! construct_runtime {
 		);
 		$crate::__decl_runtime_metadata!(
 			$runtime;
+			$uncheckedextrinsic;
 			{};
 			$(
 				$name: $module:: $( < $module_instance >:: )? { $( $modules )* }
@@ -565,6 +566,7 @@ macro_rules! __decl_runtime_metadata {
 	// leading is Module : parse
 	(
 		$runtime:ident;
+		$uncheckedextrinsic:ident;
 		{ $( $parsed:tt )* };
 		$( { leading_module: $( $leading_module:ident )* } )?
 		$name:ident: $module:ident:: $( < $module_instance:ident >:: )? {
@@ -574,6 +576,7 @@ macro_rules! __decl_runtime_metadata {
 	) => {
 		$crate::__decl_runtime_metadata!(
 			$runtime;
+			$uncheckedextrinsic;
 			{
 				$( $parsed )*
 				$module $( < $module_instance > )?  { $( $( $leading_module )* )? $( $modules )* }
@@ -584,6 +587,7 @@ macro_rules! __decl_runtime_metadata {
 	// leading isn't Module : put it in leadings
 	(
 		$runtime:ident;
+		$uncheckedextrinsic:ident;
 		{ $( $parsed:tt )* };
 		$( { leading_module: $( $leading_module:ident )* } )?
 		$name:ident: $module:ident:: $( < $module_instance:ident >:: )? {
@@ -593,6 +597,7 @@ macro_rules! __decl_runtime_metadata {
 	) => {
 		$crate::__decl_runtime_metadata!(
 			$runtime;
+			$uncheckedextrinsic;
 			{ $( $parsed )* };
 			{ leading_module: $( $( $leading_module )* )? $other_module }
 			$name: $module:: $( < $module_instance >:: )? {
@@ -604,6 +609,7 @@ macro_rules! __decl_runtime_metadata {
 	// does not contain Module : skip
 	(
 		$runtime:ident;
+		$uncheckedextrinsic:ident;
 		{ $( $parsed:tt )* };
 		$( { leading_module: $( $leading_module:ident )* } )?
 		$name:ident: $module:ident:: $( < $module_instance:ident >:: )? {}
@@ -611,6 +617,7 @@ macro_rules! __decl_runtime_metadata {
 	) => {
 		$crate::__decl_runtime_metadata!(
 			$runtime;
+			$uncheckedextrinsic;
 			{ $( $parsed )* };
 			$( $rest )*
 		);
@@ -618,10 +625,11 @@ macro_rules! __decl_runtime_metadata {
 	// end of decl
 	(
 		$runtime:ident;
+		$uncheckedextrinsic:ident;
 		{ $( $parsed_modules:ident $( < $module_instance:ident > )? { $( $withs:ident )* } )* };
 	) => {
 		$crate::impl_runtime_metadata!(
-			for $runtime with modules
+			for $runtime with modules where Extrinsic = $uncheckedextrinsic
 				$( $parsed_modules::Module $( < $module_instance > )? with $( $withs )* , )*
 		);
 	}
@@ -18,13 +18,14 @@
 //!
 //! NOTE: If you're looking for `parameter_types`, it has moved in to the top-level module.
 
-use crate::rstd::{result, marker::PhantomData, ops::Div};
+use crate::rstd::{prelude::*, result, marker::PhantomData, ops::Div};
 use crate::codec::{Codec, Encode, Decode};
 use substrate_primitives::u32_trait::Value as U32;
 use crate::runtime_primitives::traits::{
 	MaybeSerializeDebug, SimpleArithmetic, Saturating
 };
 use crate::runtime_primitives::ConsensusEngineId;
+use crate::runtime_primitives::weights::Weight;
 
 use super::for_each_tuple;
 
@@ -83,6 +84,67 @@ impl<Balance> OnDilution<Balance> for () {
 	fn on_dilution(_minted: Balance, _portion: Balance) {}
 }
 
+/// Trait for type that can handle incremental changes to a set of account IDs.
+pub trait ChangeMembers<AccountId: Clone + Ord> {
+	/// A number of members `incoming` just joined the set and replaced some `outgoing` ones. The
+	/// new set is given by `new`, and need not be sorted.
+	fn change_members(incoming: &[AccountId], outgoing: &[AccountId], mut new: Vec<AccountId>) {
+		new.sort_unstable();
+		Self::change_members_sorted(incoming, outgoing, &new[..]);
+	}
+
+	/// A number of members `incoming` just joined the set and replaced some `outgoing` ones. The
+	/// new set is thus given by `sorted_new` and **must be sorted**.
+	fn change_members_sorted(
+		incoming: &[AccountId],
+		outgoing: &[AccountId],
+		sorted_new: &[AccountId],
+	);
+
+	/// Set the new members; they **must already be sorted**. This will compute the diff and use it
+	/// to call `change_members_sorted`.
+	fn set_members_sorted(new_members: &[AccountId], old_members: &[AccountId]) {
+		let mut old_iter = old_members.iter();
+		let mut new_iter = new_members.iter();
+		let mut incoming = Vec::new();
+		let mut outgoing = Vec::new();
+		let mut old_i = old_iter.next();
+		let mut new_i = new_iter.next();
+		loop {
+			match (old_i, new_i) {
+				(None, None) => break,
+				(Some(old), Some(new)) if old == new => {
+					old_i = old_iter.next();
+					new_i = new_iter.next();
+				}
+				(Some(old), Some(new)) if old < new => {
+					outgoing.push(old.clone());
+					old_i = old_iter.next();
+				}
+				(Some(old), None) => {
+					outgoing.push(old.clone());
+					old_i = old_iter.next();
+				}
+				(_, Some(new)) => {
+					incoming.push(new.clone());
+					new_i = new_iter.next();
+				}
+			}
+		}
+
+		Self::change_members_sorted(&incoming[..], &outgoing[..], new_members);
+	}
+
+	/// Set the prime member, whose vote (if any) counts as the default for members who abstain.
+	fn set_prime(_prime: Option<AccountId>) {}
+}
+
+impl<T: Clone + Ord> ChangeMembers<T> for () {
+	fn change_members(_: &[T], _: &[T], _: Vec<T>) {}
+	fn change_members_sorted(_: &[T], _: &[T], _: &[T]) {}
+	fn set_members_sorted(_: &[T], _: &[T]) {}
+}
+
 /// Outcome of a balance update.
 pub enum UpdateBalanceOutcome {
 	/// Account balance was simply updated.
@@ -95,13 +157,24 @@ pub enum UpdateBalanceOutcome {
 ///
 /// It operates over a single generic `AccountId` type.
 pub trait MakePayment<AccountId> {
+	/// The type in which payments are made.
+	type Balance;
+
 	/// Make transaction payment from `who` for an extrinsic of encoded length
-	/// `encoded_len` bytes. Return `Ok` iff the payment was successful.
-	fn make_payment(who: &AccountId, encoded_len: usize) -> Result<(), &'static str>;
+	/// `encoded_len` bytes and the given `weight`, along with the `tip` its sender chose
+	/// to pay, if any. Return `Ok` iff the payment was successful.
+	fn make_payment(
+		who: &AccountId,
+		encoded_len: usize,
+		weight: Weight,
+		tip: Option<Self::Balance>,
+	) -> Result<(), &'static str>;
 }
 
 impl<T> MakePayment<T> for () {
-	fn make_payment(_: &T, _: usize) -> Result<(), &'static str> { Ok(()) }
+	type Balance = u64;
+
+	fn make_payment(_: &T, _: usize, _: Weight, _: Option<u64>) -> Result<(), &'static str> { Ok(()) }
 }
 
 /// A trait for finding the author of a block header based on the `PreRuntime` digests contained
@@ -126,6 +199,27 @@ pub trait VerifySeal<Header, Author> {
 	fn verify_seal(header: &Header) -> Result<Option<Author>, &'static str>;
 }
 
+/// Something which can compute and check proofs of a historical key owner and return full
+/// identification data of that key owner.
+pub trait KeyOwnerProofSystem<Key> {
+	/// The proof of membership itself.
+	type Proof: Codec;
+	/// The full identification of a key owner.
+	type FullIdentification: Codec;
+
+	/// Prove membership of a key owner in the current block-state.
+	///
+	/// This should typically only be called off-chain, since it may be computationally heavy.
+	///
+	/// Returns `Some` iff the key owner referred to by the given `key` is a member of the current
+	/// set.
+	fn prove(key: Key) -> Option<Self::Proof>;
+
+	/// Check a proof of membership on-chain. Return `Some` iff the proof is valid and recent
+	/// enough to check.
+	fn check_proof(key: Key, proof: Self::Proof) -> Option<Self::FullIdentification>;
+}
+
 /// Handler for when some currency "account" decreased in balance for
 /// some reason.
 ///
@@ -607,3 +701,187 @@ bitmask! {
 	}
 }
 
+/// Traits for dispatching calls at some future block, implemented by a scheduler.
+pub mod schedule {
+	use crate::rstd::prelude::*;
+
+	/// Information relating to the period of a scheduled task. First item is the length of the
+	/// period and the second is the number of times it should be executed in total before the task
+	/// is considered finished and removed.
+	pub type Period<BlockNumber> = (BlockNumber, u32);
+
+	/// Priority with which a call is scheduled. It's just a linear amount with lowest values meaning
+	/// higher priority.
+	pub type Priority = u8;
+
+	/// The highest priority. We invert the value so that normal sorting will place the highest
+	/// priority at the beginning of the list.
+	pub const HIGHEST_PRIORITY: Priority = 0;
+	/// Anything of this value or lower will definitely be scheduled on the block that they ask for,
+	/// even if it breaches the `MaximumWeight` limitation.
+	pub const HARD_DEADLINE: Priority = 63;
+	/// The lowest priority. Most stuff should be around here.
+	pub const LOWEST_PRIORITY: Priority = 255;
+
+	/// A type that can be used as a scheduler.
+	pub trait Anon<BlockNumber, Call> {
+		/// An address which can be used for removing a scheduled task.
+		type Address;
+
+		/// Schedule a one-off dispatch to happen at the beginning of some block in the future.
+		///
+		/// This is not named.
+		fn schedule(
+			when: BlockNumber,
+			maybe_periodic: Option<Period<BlockNumber>>,
+			priority: Priority,
+			call: Call
+		) -> Self::Address;
+
+		/// Cancel a scheduled task. If periodic, then it will cancel all further instances of that,
+		/// also.
+		///
+		/// Will return an error if the `address` is invalid.
+		fn cancel(address: Self::Address) -> Result<(), ()>;
+	}
+
+	/// A type that can be used as a scheduler.
+	pub trait Named<BlockNumber, Call> {
+		/// An address which can be used for removing a scheduled task.
+		type Address;
+
+		/// Schedule a one-off dispatch to happen at the beginning of some block in the future.
+		///
+		/// - `id`: The identity of the task. This must be unique and will return an error if not.
+		fn schedule_named(
+			id: Vec<u8>,
+			when: BlockNumber,
+			maybe_periodic: Option<Period<BlockNumber>>,
+			priority: Priority,
+			call: Call
+		) -> Result<Self::Address, ()>;
+
+		/// Cancel a scheduled, named task. If periodic, then it will cancel all further instances
+		/// of that, also.
+		///
+		/// Will return an error if the `id` is invalid.
+		fn cancel_named(id: Vec<u8>) -> Result<(), ()>;
+	}
+}
+
+/// Common traits and types for reporting and punishing misbehaviour of validators.
+pub mod offence {
+	use crate::rstd::prelude::*;
+	use crate::codec::{Codec, Encode, Decode};
+	use crate::runtime_primitives::Perbill;
+
+	/// The kind of an offence, is a byte string representing some kind identifier
+	/// e.g. `b"im-online:offlin"`, `b"babe:equivocatio"`
+	pub type Kind = [u8; 16];
+
+	/// Simple index type with which we can count sessions.
+	pub type SessionIndex = u32;
+
+	/// A trait implemented by an offence report.
+	///
+	/// This trait assumes that the offence is legitimate and was validated already.
+	///
+	/// Examples of offences include: a BABE equivocation or a GRANDPA unjustified vote.
+	pub trait Offence<Offender> {
+		/// Identifier which is unique for this kind of an offence.
+		const ID: Kind;
+
+		/// A type that represents a point in time on an abstract timescale.
+		///
+		/// See `Offence::time_slot` for details. The only requirement is that such timescale could
+		/// be represented by a single `u128` value.
+		type TimeSlot: Clone + Codec + Ord;
+
+		/// The list of all offenders involved in this incident.
+		///
+		/// The list has no duplicates, so it is rather a set.
+		fn offenders(&self) -> Vec<Offender>;
+
+		/// The session index that is used for querying the validator set for the `slash_fraction`
+		/// function.
+		///
+		/// This is used for filtering historical sessions.
+		fn session_index(&self) -> SessionIndex;
+
+		/// Return a validator set count at the time when the offence took place.
+		fn validator_set_count(&self) -> u32;
+
+		/// A point in time when this offence happened.
+		///
+		/// This is used for looking up offences that happened at the "same time".
+		///
+		/// The timescale is abstract and doesn't have to be the same across different
+		/// implementations of this trait. The value doesn't represent absolute timescale though
+		/// since it is interpreted along with the `session_index`. Two offences are considered to
+		/// happen at the same time iff both `session_index` and `time_slot` are equal.
+		///
+		/// As an example, for GRANDPA timescale could be a round number and for BABE it could be a
+		/// slot number. Note that for GRANDPA the round number is reset each epoch.
+		fn time_slot(&self) -> Self::TimeSlot;
+
+		/// A slash fraction of the total exposure that should be slashed for this
+		/// particular offence kind for the given parameters that happened at a singular `TimeSlot`.
+		///
+		/// `offenders_count` - the count of unique offending authorities. It is >0.
+		/// `validator_set_count` - the cardinality of the validator set at the time of offence.
+		fn slash_fraction(offenders_count: u32, validator_set_count: u32) -> Perbill;
+	}
+
+	/// A trait for decoupling offence reporters from the actual handling of offence reports.
+	pub trait ReportOffence<Reporter, Offender, O: Offence<Offender>> {
+		/// Report an `offence` and reward given `reporters`.
+		fn report_offence(reporters: Vec<Reporter>, offence: O);
+	}
+
+	impl<Reporter, Offender, O: Offence<Offender>> ReportOffence<Reporter, Offender, O> for () {
+		fn report_offence(_reporters: Vec<Reporter>, _offence: O) {}
+	}
+
+	/// A trait to take action on an offence.
+	///
+	/// Used to decouple the module that handles offences and
+	/// the one that should punish for those offences.
+	pub trait OnOffenceHandler<Reporter, Offender> {
+		/// A handler for an offence of a particular kind.
+		///
+		/// Note that this contains all offenders of the same kind which were reported for the
+		/// same time slot, including those already handled by an earlier call. The implementer
+		/// should cater for a case where the same authorities are passed in again, possibly with a
+		/// higher slash fraction.
+		///
+		/// The vector of `slash_fraction` contains the `Perbill`s by which the authorities should
+		/// be slashed, computed from the number of concurrent offenders. It is of the same length
+		/// as `offenders`. Zero is a valid value for a fraction.
+		///
+		/// The `session` parameter is the session index of the offence.
+		fn on_offence(
+			offenders: &[OffenceDetails<Reporter, Offender>],
+			slash_fraction: &[Perbill],
+			session: SessionIndex,
+		);
+	}
+
+	impl<Reporter, Offender> OnOffenceHandler<Reporter, Offender> for () {
+		fn on_offence(
+			_offenders: &[OffenceDetails<Reporter, Offender>],
+			_slash_fraction: &[Perbill],
+			_session: SessionIndex,
+		) {}
+	}
+
+	/// A details about an offending authority for a particular kind of offence.
+	#[derive(Clone, PartialEq, Eq, Encode, Decode)]
+	#[cfg_attr(feature = "std", derive(Debug))]
+	pub struct OffenceDetails<Reporter, Offender> {
+		/// The offending authority id
+		pub offender: Offender,
+		/// A list of reporters of offences of this authority ID. Possibly empty where there are no
+		/// particular reporters.
+		pub reporters: Vec<Reporter>,
+	}
+}
@@ -16,12 +16,12 @@
 
 use criterion::{Criterion, criterion_group, criterion_main, black_box};
 use srml_system as system;
-use srml_support::{decl_module, decl_event, impl_outer_origin, impl_outer_event};
+use srml_support::{decl_module, decl_event, impl_outer_origin, impl_outer_event, parameter_types};
 use runtime_io::{with_externalities, Blake2Hasher};
 use substrate_primitives::H256;
 use primitives::{
-	BuildStorage, traits::{BlakeTwo256, IdentityLookup},
-	testing::Header,
+	BuildStorage, Perbill, traits::{BlakeTwo256, IdentityLookup},
+	testing::Header, weights::Weight,
 };
 
 mod module {
@@ -56,6 +56,10 @@ impl_outer_event! {
 
 #[derive(Clone, Eq, PartialEq)]
 pub struct Runtime;
+parameter_types! {
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
 impl system::Trait for Runtime {
 	type Origin = Origin;
 	type Index = u64;
@@ -66,6 +70,9 @@ impl system::Trait for Runtime {
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
 	type Event = Event;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type FeeMultiplierUpdate = ();
 }
 
 impl module::Trait for Runtime {
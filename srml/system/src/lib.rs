@@ -42,6 +42,16 @@
 //!
 //! See the [`Module`](./struct.Module.html) struct for details of publicly available functions.
 //!
+//! ### Signed Extensions
+//!
+//! The System module defines the following signed extensions, for runtimes whose extrinsic format
+//! supports them:
+//!
+//! - [`CheckGenesis`](./struct.CheckGenesis.html): Makes a transaction valid on this chain only.
+//! - [`CheckEra`](./struct.CheckEra.html): Limits the lifetime of a transaction to an era.
+//! - [`CheckNonce`](./struct.CheckNonce.html): Orders the transactions of an account by their nonce
+//!   and prevents replaying them.
+//!
 //! ## Usage
 //!
 //! ### Prerequisites
@@ -76,16 +86,17 @@ use serde::Serialize;
 use rstd::prelude::*;
 #[cfg(any(feature = "std", test))]
 use rstd::map;
-use primitives::{generic, traits::{self, CheckEqual, SimpleArithmetic,
+use primitives::{generic, ApplyError, Fixed64, Perbill, BLOCK_FULL, traits::{self, CheckEqual, SimpleArithmetic,
 	SimpleBitOps, Hash, Member, MaybeDisplay, EnsureOrigin, CurrentHeight, BlockNumberToHash,
 	MaybeSerializeDebugButNotDeserialize, MaybeSerializeDebug, StaticLookup, One, Bounded, Lookup,
+	Convert, Zero, SignedExtension, LegacyExtension, SaturatedConversion,
+}, weights::{DispatchInfo, Weight}, transaction_validity::{
+	ValidTransaction, TransactionLongevity, TransactionPriority,
 }};
-#[cfg(any(feature = "std", test))]
-use primitives::traits::Zero;
 use substrate_primitives::storage::well_known_keys;
 use srml_support::{
 	storage, decl_module, decl_event, decl_storage, StorageDoubleMap, StorageValue,
-	StorageMap, Parameter, for_each_tuple, traits::Contains
+	StorageMap, Parameter, for_each_tuple, traits::{Contains, Get},
 };
 use safe_mix::TripletMix;
 use parity_codec::{Encode, Decode};
@@ -97,6 +108,8 @@ use runtime_io::{twox_128, TestExternalities, Blake2Hasher};
 #[cfg(any(feature = "std", test))]
 use substrate_primitives::ChangesTrieConfiguration;
 
+pub mod offchain;
+
 /// Handler for when a new account has been created.
 pub trait OnNewAccount<AccountId> {
 	/// A new account `who` has been registered.
@@ -184,6 +197,20 @@ pub trait Trait: 'static + Eq + Clone {
 
 	/// The aggregated event type of the runtime.
 	type Event: Parameter + Member + From<Event>;
+
+	/// The maximum weight of a block.
+	type MaximumBlockWeight: Get<Weight>;
+
+	/// The portion of `MaximumBlockWeight` that normal extrinsics may fill. The rest of the block is
+	/// reserved for operational extrinsics.
+	type AvailableBlockRatio: Get<Perbill>;
+
+	/// Computes the fee multiplier of the next block from the weight of the current block and the
+	/// current multiplier.
+	///
+	/// This is the feedback mechanism that makes transaction fees rise when blocks are congested
+	/// and fall again when they are not. `()` keeps the multiplier at zero, i.e. fees unadjusted.
+	type FeeMultiplierUpdate: Convert<(Weight, Fixed64), Fixed64>;
 }
 
 pub type DigestOf<T> = generic::Digest<<T as Trait>::Hash>;
@@ -194,11 +221,24 @@ pub type KeyValue = (Vec<u8>, Vec<u8>);
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// The maximum weight of a block.
+		const MaximumBlockWeight: Weight = T::MaximumBlockWeight::get();
+
+		/// The portion of the maximum block weight that normal extrinsics may fill.
+		const AvailableBlockRatio: Perbill = T::AvailableBlockRatio::get();
+
 		/// Deposits an event into this block's event record.
 		pub fn deposit_event(event: T::Event) {
 			Self::deposit_event_indexed(&[], event);
 		}
 
+		fn on_finalize() {
+			let weight = Self::all_extrinsics_weight();
+			NextFeeMultiplier::mutate(|multiplier| {
+				*multiplier = T::FeeMultiplierUpdate::convert((weight, *multiplier))
+			});
+		}
+
 		/// Make some on-chain remark.
 		fn remark(origin, _remark: Vec<u8>) {
 			ensure_signed(origin)?;
@@ -310,7 +350,11 @@ decl_storage! {
 		/// Total extrinsics count for the current block.
 		ExtrinsicCount: Option<u32>;
 		/// Total weight for all extrinsics put together, for the current block.
-		AllExtrinsicsWeight: Option<u32>;
+		AllExtrinsicsWeight: Option<Weight>;
+		/// The adjustment applied to the transaction fees of the current block. A fee `f` is
+		/// charged as `f + f * multiplier`. Updated at the end of every block by
+		/// `FeeMultiplierUpdate`.
+		pub NextFeeMultiplier get(next_fee_multiplier): Fixed64;
 		/// Map of block numbers to block hashes.
 		pub BlockHash get(block_hash) build(|_| vec![(T::BlockNumber::zero(), hash69())]): map T::BlockNumber => T::Hash;
 		/// Extrinsics data for the current block (maps an extrinsic's index to its data).
@@ -528,10 +572,42 @@ impl<T: Trait> Module<T> {
 	}
 
 	/// Gets a total weight of all executed extrinsics.
-	pub fn all_extrinsics_weight() -> u32 {
+	pub fn all_extrinsics_weight() -> Weight {
 		AllExtrinsicsWeight::get().unwrap_or_default()
 	}
 
+	/// Add `weight` to the weight of the current block, e.g. for work done in `on_initialize`.
+	///
+	/// The weight is not checked against the block weight limit.
+	pub fn register_extra_weight_unchecked(weight: Weight) {
+		AllExtrinsicsWeight::put(Self::all_extrinsics_weight().saturating_add(weight));
+	}
+
+	/// The maximum weight of the block that extrinsics of the given dispatch class may fill.
+	///
+	/// Operational extrinsics may fill the whole block, normal ones only the portion given by
+	/// `AvailableBlockRatio`.
+	pub fn block_weight_limit(info: &DispatchInfo) -> Weight {
+		let maximum_weight = T::MaximumBlockWeight::get();
+		if info.is_operational() {
+			maximum_weight
+		} else {
+			T::AvailableBlockRatio::get() * maximum_weight
+		}
+	}
+
+	/// Checks that an extrinsic with the given dispatch info fits into the current block.
+	///
+	/// Returns `BLOCK_FULL` if the weight of the extrinsics applied so far together with the
+	/// weight of the extrinsic exceeds the limit for its dispatch class.
+	pub fn check_weight(info: &DispatchInfo) -> Result<(), &'static str> {
+		let next_weight = Self::all_extrinsics_weight().saturating_add(info.weight);
+		if next_weight > Self::block_weight_limit(info) {
+			return Err(BLOCK_FULL);
+		}
+		Ok(())
+	}
+
 	/// Start the execution of a particular block.
 	pub fn initialize(
 		number: &T::BlockNumber,
@@ -702,18 +778,18 @@ impl<T: Trait> Module<T> {
 		ExtrinsicData::insert(Self::extrinsic_index().unwrap_or_default(), encoded_xt);
 	}
 
-	/// To be called immediately after an extrinsic has been applied.
-	pub fn note_applied_extrinsic(r: &Result<(), &'static str>, encoded_len: u32) {
+	/// To be called immediately after an extrinsic of the given `weight` has been applied.
+	pub fn note_applied_extrinsic(r: &Result<(), &'static str>, weight: Weight) {
 		Self::deposit_event(match r {
 			Ok(_) => Event::ExtrinsicSuccess,
 			Err(_) => Event::ExtrinsicFailed,
 		}.into());
 
 		let next_extrinsic_index = Self::extrinsic_index().unwrap_or_default() + 1u32;
-		let total_length = encoded_len.saturating_add(Self::all_extrinsics_weight());
+		let total_weight = weight.saturating_add(Self::all_extrinsics_weight());
 
 		storage::unhashed::put(well_known_keys::EXTRINSIC_INDEX, &next_extrinsic_index);
-		AllExtrinsicsWeight::put(&total_length);
+		AllExtrinsicsWeight::put(&total_weight);
 	}
 
 	/// To be called immediately after `note_applied_extrinsic` of the last extrinsic of the block
@@ -761,18 +837,283 @@ impl<T: Trait> BlockNumberToHash for ChainContext<T> {
 	}
 }
 
+/// Signed extension that makes a transaction valid on this chain only, by adding the genesis
+/// hash to its signed payload.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct CheckGenesis<T: Trait + Send + Sync>(rstd::marker::PhantomData<T>);
+
+impl<T: Trait + Send + Sync> CheckGenesis<T> {
+	/// Creates a new instance of the extension.
+	pub fn new() -> Self {
+		CheckGenesis(rstd::marker::PhantomData)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<T: Trait + Send + Sync> rstd::fmt::Debug for CheckGenesis<T> {
+	fn fmt(&self, f: &mut rstd::fmt::Formatter) -> rstd::fmt::Result {
+		write!(f, "CheckGenesis")
+	}
+}
+
+impl<T: Trait + Send + Sync> SignedExtension for CheckGenesis<T> {
+	type AccountId = T::AccountId;
+	type AdditionalSigned = T::Hash;
+
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, &'static str> {
+		Ok(<Module<T>>::block_hash(T::BlockNumber::zero()))
+	}
+
+	fn identifier() -> Vec<&'static str> {
+		vec!["CheckGenesis"]
+	}
+}
+
+impl<T: Trait + Send + Sync, Index> LegacyExtension<Index> for CheckGenesis<T> {
+	fn from_legacy(_index: &Index, _era: generic::Era, _tip: u128) -> Option<Self> {
+		Some(Self::new())
+	}
+}
+
+/// Signed extension that limits the lifetime of a transaction to an era, by adding the hash of
+/// the block the era starts at to its signed payload.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct CheckEra<T: Trait + Send + Sync>(generic::Era, rstd::marker::PhantomData<T>);
+
+impl<T: Trait + Send + Sync> CheckEra<T> {
+	/// Creates a new instance of the extension for the given era.
+	pub fn from(era: generic::Era) -> Self {
+		CheckEra(era, rstd::marker::PhantomData)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<T: Trait + Send + Sync> rstd::fmt::Debug for CheckEra<T> {
+	fn fmt(&self, f: &mut rstd::fmt::Formatter) -> rstd::fmt::Result {
+		write!(f, "CheckEra({:?})", self.0)
+	}
+}
+
+impl<T: Trait + Send + Sync> SignedExtension for CheckEra<T> {
+	type AccountId = T::AccountId;
+	type AdditionalSigned = T::Hash;
+
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, &'static str> {
+		let current_u64 = <Module<T>>::block_number().saturated_into::<u64>();
+		let n = self.0.birth(current_u64).saturated_into::<T::BlockNumber>();
+		if !<BlockHash<T>>::exists(n) {
+			return Err("transaction birth block ancient")
+		}
+		Ok(<Module<T>>::block_hash(n))
+	}
+
+	fn validate(
+		&self,
+		_who: &Self::AccountId,
+		_info: DispatchInfo,
+		_len: usize,
+	) -> Result<ValidTransaction, ApplyError> {
+		let current_u64 = <Module<T>>::block_number().saturated_into::<u64>();
+		Ok(ValidTransaction {
+			longevity: self.0.death(current_u64).saturating_sub(current_u64) as TransactionLongevity,
+			..Default::default()
+		})
+	}
+
+	fn identifier() -> Vec<&'static str> {
+		vec!["CheckEra"]
+	}
+}
+
+impl<T: Trait + Send + Sync, Index> LegacyExtension<Index> for CheckEra<T> {
+	fn from_legacy(_index: &Index, era: generic::Era, _tip: u128) -> Option<Self> {
+		Some(Self::from(era))
+	}
+}
+
+/// Signed extension that checks the nonce of a transaction against the nonce of its sender, and
+/// increments the latter when the transaction is dispatched.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct CheckNonce<T: Trait + Send + Sync>(pub T::Index);
+
+impl<T: Trait + Send + Sync> CheckNonce<T> {
+	/// Creates a new instance of the extension for the given nonce.
+	pub fn from(nonce: T::Index) -> Self {
+		CheckNonce(nonce)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<T: Trait + Send + Sync> rstd::fmt::Debug for CheckNonce<T> {
+	fn fmt(&self, f: &mut rstd::fmt::Formatter) -> rstd::fmt::Result {
+		write!(f, "CheckNonce({:?})", self.0)
+	}
+}
+
+impl<T: Trait + Send + Sync> SignedExtension for CheckNonce<T> {
+	type AccountId = T::AccountId;
+	type AdditionalSigned = ();
+
+	fn additional_signed(&self) -> Result<(), &'static str> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		_info: DispatchInfo,
+		len: usize,
+	) -> Result<ValidTransaction, ApplyError> {
+		let expected = <Module<T>>::account_nonce(who);
+		if self.0 < expected {
+			return Err(ApplyError::Stale)
+		}
+
+		let provides = vec![(who, self.0).encode()];
+		let requires = if expected < self.0 {
+			vec![(who, self.0 - T::Index::one()).encode()]
+		} else {
+			vec![]
+		};
+
+		Ok(ValidTransaction {
+			priority: len as TransactionPriority,
+			requires,
+			provides,
+			..Default::default()
+		})
+	}
+
+	fn pre_dispatch(
+		&self,
+		who: &Self::AccountId,
+		_info: DispatchInfo,
+		_len: usize,
+	) -> Result<(), ApplyError> {
+		let expected = <Module<T>>::account_nonce(who);
+		if self.0 != expected {
+			return Err(if self.0 < expected { ApplyError::Stale } else { ApplyError::Future })
+		}
+		Ok(())
+	}
+
+	fn on_dispatch(&self, who: &Self::AccountId, _info: DispatchInfo, _len: usize) {
+		<Module<T>>::inc_account_nonce(who);
+	}
+
+	fn identifier() -> Vec<&'static str> {
+		vec!["CheckNonce"]
+	}
+}
+
+impl<T: Trait + Send + Sync> LegacyExtension<T::Index> for CheckNonce<T> {
+	fn from_legacy(index: &T::Index, _era: generic::Era, _tip: u128) -> Option<Self> {
+		Some(Self::from(*index))
+	}
+}
+
+/// Signed extension that makes sure that an extrinsic fits into the block, given the limit of its
+/// dispatch class. Transaction queue validation only rejects extrinsics that don't fit into any
+/// block.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct CheckWeight<T: Trait + Send + Sync>(rstd::marker::PhantomData<T>);
+
+impl<T: Trait + Send + Sync> CheckWeight<T> {
+	/// Creates a new instance of the extension.
+	pub fn new() -> Self {
+		CheckWeight(rstd::marker::PhantomData)
+	}
+
+	fn check_extrinsic_weight(info: DispatchInfo) -> Result<ValidTransaction, ApplyError> {
+		if info.weight > <Module<T>>::block_weight_limit(&info) {
+			return Err(ApplyError::FullBlock)
+		}
+		Ok(Default::default())
+	}
+
+	fn check_block_weight(info: DispatchInfo) -> Result<(), ApplyError> {
+		<Module<T>>::check_weight(&info).map_err(|_| ApplyError::FullBlock)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<T: Trait + Send + Sync> rstd::fmt::Debug for CheckWeight<T> {
+	fn fmt(&self, f: &mut rstd::fmt::Formatter) -> rstd::fmt::Result {
+		write!(f, "CheckWeight")
+	}
+}
+
+impl<T: Trait + Send + Sync> SignedExtension for CheckWeight<T> {
+	type AccountId = T::AccountId;
+	type AdditionalSigned = ();
+
+	fn additional_signed(&self) -> Result<(), &'static str> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		_who: &Self::AccountId,
+		info: DispatchInfo,
+		_len: usize,
+	) -> Result<ValidTransaction, ApplyError> {
+		Self::check_extrinsic_weight(info)
+	}
+
+	fn pre_dispatch(
+		&self,
+		_who: &Self::AccountId,
+		info: DispatchInfo,
+		_len: usize,
+	) -> Result<(), ApplyError> {
+		Self::check_block_weight(info)
+	}
+
+	fn validate_unsigned(info: DispatchInfo, _len: usize) -> Result<ValidTransaction, ApplyError> {
+		Self::check_extrinsic_weight(info)
+	}
+
+	fn pre_dispatch_unsigned(info: DispatchInfo, _len: usize) -> Result<(), ApplyError> {
+		Self::check_block_weight(info)
+	}
+
+	fn identifier() -> Vec<&'static str> {
+		vec!["CheckWeight"]
+	}
+}
+
+impl<T: Trait + Send + Sync, Index> LegacyExtension<Index> for CheckWeight<T> {
+	fn from_legacy(_index: &Index, _era: generic::Era, _tip: u128) -> Option<Self> {
+		Some(Self::new())
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use runtime_io::with_externalities;
 	use substrate_primitives::H256;
-	use primitives::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
-	use srml_support::impl_outer_origin;
+	use primitives::{traits::{BlakeTwo256, IdentityLookup, OnFinalize}, testing::Header};
+	use primitives::weights::DispatchClass;
+	use srml_support::{assert_ok, impl_outer_origin, parameter_types};
 
 	impl_outer_origin!{
 		pub enum Origin for Test where system = super {}
 	}
 
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+
+	/// Raises the multiplier by the portion of the block that was filled.
+	pub struct FeeMultiplierUpdate;
+	impl Convert<(Weight, Fixed64), Fixed64> for FeeMultiplierUpdate {
+		fn convert((weight, multiplier): (Weight, Fixed64)) -> Fixed64 {
+			multiplier.saturating_add(Fixed64::from_rational(weight as i64, MaximumBlockWeight::get() as u64))
+		}
+	}
+
 	#[derive(Clone, Eq, PartialEq)]
 	pub struct Test;
 	impl Trait for Test {
@@ -785,6 +1126,9 @@ mod tests {
 		type Lookup = IdentityLookup<Self::AccountId>;
 		type Header = Header;
 		type Event = u16;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = FeeMultiplierUpdate;
 	}
 
 	impl From<Event> for u16 {
@@ -843,6 +1187,50 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn normal_extrinsics_only_fill_available_block_weight() {
+		with_externalities(&mut new_test_ext(), || {
+			System::initialize(&1, &[0u8; 32].into(), &[0u8; 32].into(), &Default::default());
+			let normal = |weight| DispatchInfo { weight, class: DispatchClass::Normal };
+			let operational = |weight| DispatchInfo { weight, class: DispatchClass::Operational };
+
+			// 75% of 1024.
+			assert_eq!(System::block_weight_limit(&normal(0)), 768);
+			assert_eq!(System::block_weight_limit(&operational(0)), 1024);
+
+			assert_ok!(System::check_weight(&normal(768)));
+			assert_eq!(System::check_weight(&normal(769)), Err(BLOCK_FULL));
+
+			System::note_applied_extrinsic(&Ok(()), 700);
+			assert_eq!(System::all_extrinsics_weight(), 700);
+			assert_ok!(System::check_weight(&normal(68)));
+			assert_eq!(System::check_weight(&normal(69)), Err(BLOCK_FULL));
+
+			// Operational extrinsics can use the reserved portion of the block.
+			assert_ok!(System::check_weight(&operational(324)));
+			assert_eq!(System::check_weight(&operational(325)), Err(BLOCK_FULL));
+		});
+	}
+
+	#[test]
+	fn fee_multiplier_follows_block_weight() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_eq!(System::next_fee_multiplier(), Fixed64::default());
+
+			System::initialize(&1, &[0u8; 32].into(), &[0u8; 32].into(), &Default::default());
+			System::note_applied_extrinsic(&Ok(()), 256);
+			System::note_applied_extrinsic(&Ok(()), 256);
+			<System as OnFinalize<u64>>::on_finalize(1);
+			assert_eq!(System::next_fee_multiplier(), Fixed64::from_rational(1, 2));
+			System::finalize();
+
+			// An empty block leaves the multiplier untouched with this update function.
+			System::initialize(&2, &[0u8; 32].into(), &[0u8; 32].into(), &Default::default());
+			<System as OnFinalize<u64>>::on_finalize(2);
+			assert_eq!(System::next_fee_multiplier(), Fixed64::from_rational(1, 2));
+		});
+	}
+
 	#[test]
 	fn deposit_event_topics() {
 		with_externalities(&mut new_test_ext(), || {
@@ -902,4 +1290,90 @@ mod tests {
 			);
 		});
 	}
+
+	#[test]
+	fn check_genesis_signs_genesis_hash() {
+		with_externalities(&mut new_test_ext(), || {
+			System::initialize(&10, &[9u8; 32].into(), &[0u8; 32].into(), &Default::default());
+			assert_eq!(CheckGenesis::<Test>::new().additional_signed(), Ok([69u8; 32].into()));
+		});
+	}
+
+	#[test]
+	fn check_era_signs_birth_block_hash_and_limits_longevity() {
+		with_externalities(&mut new_test_ext(), || {
+			System::initialize(&10, &[9u8; 32].into(), &[0u8; 32].into(), &Default::default());
+			let info = DispatchInfo::default();
+
+			// Born at block 9, dies at block 13.
+			let era = CheckEra::<Test>::from(generic::Era::mortal(4, 9));
+			assert_eq!(era.additional_signed(), Ok([9u8; 32].into()));
+			assert_eq!(era.validate(&1, info, 0).unwrap().longevity, 3);
+
+			let era = CheckEra::<Test>::from(generic::Era::immortal());
+			assert_eq!(era.additional_signed(), Ok([69u8; 32].into()));
+			assert_eq!(era.validate(&1, info, 0).unwrap().longevity, u64::max_value() - 10);
+
+			// Born at block 4, whose hash is unknown.
+			let era = CheckEra::<Test>::from(generic::Era::mortal(16, 20));
+			assert_eq!(era.additional_signed(), Err("transaction birth block ancient"));
+		});
+	}
+
+	#[test]
+	fn check_nonce_orders_and_increments_nonces() {
+		with_externalities(&mut new_test_ext(), || {
+			<AccountNonce<Test>>::insert(1, 1);
+			let info = DispatchInfo::default();
+
+			assert_eq!(CheckNonce::<Test>::from(0).validate(&1, info, 0), Err(ApplyError::Stale));
+			let valid = CheckNonce::<Test>::from(1).validate(&1, info, 10).unwrap();
+			assert_eq!(valid.priority, 10);
+			assert_eq!(valid.provides, vec![(1u64, 1u64).encode()]);
+			assert!(valid.requires.is_empty());
+			let valid = CheckNonce::<Test>::from(3).validate(&1, info, 0).unwrap();
+			assert_eq!(valid.provides, vec![(1u64, 3u64).encode()]);
+			assert_eq!(valid.requires, vec![(1u64, 2u64).encode()]);
+
+			assert_eq!(CheckNonce::<Test>::from(0).pre_dispatch(&1, info, 0), Err(ApplyError::Stale));
+			assert_eq!(CheckNonce::<Test>::from(2).pre_dispatch(&1, info, 0), Err(ApplyError::Future));
+			assert_eq!(CheckNonce::<Test>::from(1).pre_dispatch(&1, info, 0), Ok(()));
+			assert_eq!(System::account_nonce(1), 1);
+			CheckNonce::<Test>::from(1).on_dispatch(&1, info, 0);
+			assert_eq!(System::account_nonce(1), 2);
+		});
+	}
+
+	#[test]
+	fn check_weight_only_rejects_transactions_that_never_fit() {
+		with_externalities(&mut new_test_ext(), || {
+			System::initialize(&1, &[0u8; 32].into(), &[0u8; 32].into(), &Default::default());
+			let normal = |weight| DispatchInfo { weight, class: DispatchClass::Normal };
+			let operational = |weight| DispatchInfo { weight, class: DispatchClass::Operational };
+			let check = CheckWeight::<Test>::new();
+
+			System::note_applied_extrinsic(&Ok(()), 700);
+			// The block is too full for the transaction, but an empty one isn't.
+			assert!(check.validate(&1, normal(100), 0).is_ok());
+			assert_eq!(check.pre_dispatch(&1, normal(100), 0), Err(ApplyError::FullBlock));
+			assert_ok!(check.pre_dispatch(&1, normal(68), 0));
+
+			assert_eq!(check.validate(&1, normal(769), 0), Err(ApplyError::FullBlock));
+			assert!(check.validate(&1, operational(1024), 0).is_ok());
+			assert_eq!(check.validate(&1, operational(1025), 0), Err(ApplyError::FullBlock));
+
+			// Unsigned extrinsics are checked alike.
+			assert!(CheckWeight::<Test>::validate_unsigned(normal(100), 0).is_ok());
+			assert_eq!(CheckWeight::<Test>::pre_dispatch_unsigned(normal(100), 0), Err(ApplyError::FullBlock));
+		});
+	}
+
+	#[test]
+	fn extensions_are_described_in_order() {
+		type Extra = (CheckGenesis<Test>, CheckEra<Test>, CheckNonce<Test>, CheckWeight<Test>);
+		assert_eq!(
+			<Extra as SignedExtension>::identifier(),
+			vec!["CheckGenesis", "CheckEra", "CheckNonce", "CheckWeight"],
+		);
+	}
 }
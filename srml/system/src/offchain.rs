@@ -0,0 +1,30 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Module helpers for offchain calls.
+
+/// Something that can wrap a call into an unsigned extrinsic and submit it to the transaction
+/// pool.
+///
+/// Only usable from within an offchain worker, since submitting a transaction is not available
+/// to the runtime while it is importing blocks. The runtime implements this, since only it knows
+/// how to build its extrinsic type.
+pub trait SubmitUnsignedTransaction<Call> {
+	/// Submit `call` as an unsigned extrinsic.
+	///
+	/// Returns an error if the extrinsic could not be submitted.
+	fn submit_unsigned(call: Call) -> Result<(), ()>;
+}
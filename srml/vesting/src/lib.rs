@@ -0,0 +1,732 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Vesting Module
+//!
+//! A simple module providing a means of placing a linear curve on an account's locked balance.
+//!
+//! - [`vesting::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! A vesting schedule locks some amount of an account's balance, and unlocks it linearly, by a
+//! fixed amount per block, from a given starting block. Optionally, a cliff may be given: then
+//! nothing unlocks until `cliff` blocks after the start, at which point everything that would have
+//! unlocked so far becomes available at once.
+//!
+//! The restriction is enforced through a lock of the underlying `LockableCurrency`. As the lock
+//! is not updated automatically, the `vest` call must be made to unlock the balance that has
+//! vested so far.
+//!
+//! Vesting schedules may be set at genesis, or created later by transferring funds with
+//! `vested_transfer`.
+//!
+//! ### Migration
+//!
+//! Vesting used to be a part of the Balances module, configured only at genesis. Schedules from
+//! the Balances module's storage are moved into this module (and turned into locks) the first
+//! time `vest` or `vest_other` is called for an account. Until then, the Balances module keeps
+//! enforcing them itself.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `vest` - Update the lock, reducing it in line with the amount "vested" so far.
+//! - `vest_other` - Update the lock of another account, reducing it in line with the amount
+//!   "vested" so far.
+//! - `vested_transfer` - Transfer funds to an account, placing them under a vesting schedule.
+//!
+//! ### Public Functions
+//!
+//! - `vesting_balance` - Get the amount that is currently being vested and cannot be transferred
+//!   out of an account.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::prelude::*;
+use parity_codec::{Encode, Decode};
+use srml_support::{
+	StorageMap, decl_module, decl_storage, decl_event, ensure,
+	storage::hashed,
+	traits::{
+		Currency, LockableCurrency, LockIdentifier, WithdrawReason, ExistenceRequirement, Get,
+	},
+};
+use runtime_primitives::traits::{
+	SimpleArithmetic, StaticLookup, Zero, Convert, Saturating, Bounded,
+};
+#[cfg(feature = "std")]
+use runtime_io::with_storage;
+use system::ensure_signed;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+const VESTING_ID: LockIdentifier = *b"vesting ";
+
+/// Storage prefix of the vesting schedules formerly kept by the Balances module.
+const LEGACY_VESTING_PREFIX: &[u8] = b"Balances Vesting";
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The currency trait.
+	type Currency: LockableCurrency<Self::AccountId, Moment=Self::BlockNumber>;
+
+	/// Convert the block number into a balance.
+	type BlockNumberToBalance: Convert<Self::BlockNumber, BalanceOf<Self>>;
+
+	/// The minimum amount to be transferred to create a new vesting schedule.
+	type MinVestedTransfer: Get<BalanceOf<Self>>;
+}
+
+/// Struct to encode the vesting schedule of an individual account.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct VestingInfo<Balance, BlockNumber> {
+	/// Locked amount at the starting block.
+	pub locked: Balance,
+	/// Amount that gets unlocked every block after `starting_block`.
+	pub per_block: Balance,
+	/// Starting block for unlocking (vesting).
+	pub starting_block: BlockNumber,
+	/// Number of blocks after `starting_block` before anything is unlocked.
+	pub cliff: BlockNumber,
+}
+
+impl<
+	Balance: SimpleArithmetic + Copy,
+	BlockNumber: SimpleArithmetic + Copy,
+> VestingInfo<Balance, BlockNumber> {
+	/// Amount locked at block `n`.
+	pub fn locked_at<BlockNumberToBalance: Convert<BlockNumber, Balance>>(&self, n: BlockNumber)
+		-> Balance
+	{
+		// Number of blocks that count toward vesting;
+		// saturating to 0 when n < starting_block.
+		let vested_block_count = n.saturating_sub(self.starting_block);
+		if vested_block_count < self.cliff {
+			return self.locked
+		}
+		let vested_block_count = BlockNumberToBalance::convert(vested_block_count);
+		// Return amount that is still locked in vesting.
+		vested_block_count.checked_mul(&self.per_block)
+			.map_or(Zero::zero(), |to_unlock| self.locked.saturating_sub(to_unlock))
+	}
+}
+
+/// A vesting schedule as it was stored by the Balances module.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+struct LegacyVestingSchedule<Balance> {
+	/// Locked amount at genesis.
+	offset: Balance,
+	/// Amount that gets unlocked every block from genesis.
+	per_block: Balance,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Vesting {
+		/// Information regarding the vesting of a given account.
+		pub Vesting get(vesting):
+			map T::AccountId => Option<VestingInfo<BalanceOf<T>, T::BlockNumber>>;
+	}
+	add_extra_genesis {
+		/// The accounts to put under a vesting schedule at genesis, as
+		/// `(who, begin, length, liquid)`. Everything but the `liquid` amount of the account's
+		/// balance at genesis unlocks linearly over `length` blocks from block `begin`.
+		config(vesting): Vec<(T::AccountId, T::BlockNumber, T::BlockNumber, BalanceOf<T>)>;
+		build(|
+			storage: &mut runtime_primitives::StorageOverlay,
+			_: &mut runtime_primitives::ChildrenStorageOverlay,
+			config: &GenesisConfig<T>
+		| {
+			with_storage(storage, || {
+				for &(ref who, begin, length, liquid) in config.vesting.iter() {
+					let balance = T::Currency::free_balance(who);
+					assert!(!balance.is_zero(), "Currencies must be init'd before vesting");
+					// Total genesis `balance` minus `liquid` equals funds locked for vesting
+					let locked = balance.saturating_sub(liquid);
+					let length_as_balance = T::BlockNumberToBalance::convert(length);
+					let per_block = locked / length_as_balance.max(runtime_primitives::traits::One::one());
+
+					<Vesting<T>>::insert(who, VestingInfo {
+						locked,
+						per_block,
+						starting_block: begin,
+						cliff: Zero::zero(),
+					});
+					let reasons = WithdrawReason::Transfer | WithdrawReason::Reserve;
+					T::Currency::set_lock(VESTING_ID, who, locked, T::BlockNumber::max_value(), reasons);
+				}
+			});
+		});
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where AccountId = <T as system::Trait>::AccountId, Balance = BalanceOf<T> {
+		/// The amount vested has been updated. This could indicate more funds are available. The
+		/// balance given is the amount which is left unvested (and thus locked).
+		VestingUpdated(AccountId, Balance),
+		/// An account (given) has become fully vested. No further vesting can happen.
+		VestingCompleted(AccountId),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event<T>() = default;
+
+		/// The minimum amount to be transferred to create a new vesting schedule.
+		const MinVestedTransfer: BalanceOf<T> = T::MinVestedTransfer::get();
+
+		/// Unlock any vested funds of the sender account.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
+		/// locked under this module.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - One balance-lock operation.
+		/// - One storage read (codec `O(1)`) and up to one removal.
+		/// - One event.
+		/// # </weight>
+		fn vest(origin) {
+			let who = ensure_signed(origin)?;
+			Self::update_lock(who)?;
+		}
+
+		/// Unlock any vested funds of a `target` account.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `target`: The account whose vested funds should be unlocked. Must have funds still
+		/// locked under this module.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - Up to one account lookup.
+		/// - One balance-lock operation.
+		/// - One storage read (codec `O(1)`) and up to one removal.
+		/// - One event.
+		/// # </weight>
+		fn vest_other(origin, target: <T::Lookup as StaticLookup>::Source) {
+			ensure_signed(origin)?;
+			Self::update_lock(T::Lookup::lookup(target)?)?;
+		}
+
+		/// Create a vested transfer.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `target`: The account that should be transferred the vested funds.
+		/// - `schedule`: The vesting schedule attached to the transfer. Its `locked` amount is
+		/// transferred and must be at least `MinVestedTransfer`.
+		///
+		/// Emits `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - One balance transfer and one balance-lock operation.
+		/// - Two storage reads (codec `O(1)`) and one storage write.
+		/// - One event.
+		/// # </weight>
+		fn vested_transfer(origin,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>
+		) {
+			let transactor = ensure_signed(origin)?;
+			ensure!(schedule.locked >= T::MinVestedTransfer::get(), "amount transferred is too low");
+			ensure!(!schedule.per_block.is_zero(), "schedule never unlocks");
+
+			let who = T::Lookup::lookup(target)?;
+			Self::migrate_legacy(&who);
+			ensure!(!<Vesting<T>>::exists(&who), "an existing vesting schedule already exists for this account that cannot be clobbered");
+
+			T::Currency::transfer(&transactor, &who, schedule.locked, ExistenceRequirement::AllowDeath)?;
+
+			<Vesting<T>>::insert(&who, schedule);
+			// Cannot fail since the schedule was just inserted.
+			Self::update_lock(who)?;
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Get the amount that is currently being vested and cannot be transferred out of this
+	/// account.
+	pub fn vesting_balance(who: &T::AccountId) -> BalanceOf<T> {
+		if let Some(v) = Self::vesting(who) {
+			let now = <system::Module<T>>::block_number();
+			let locked_now = v.locked_at::<T::BlockNumberToBalance>(now);
+			T::Currency::free_balance(who).min(locked_now)
+		} else {
+			Zero::zero()
+		}
+	}
+
+	/// (Re)set or remove the module's currency lock on `who`'s account in accordance with their
+	/// current unvested amount.
+	fn update_lock(who: T::AccountId) -> srml_support::dispatch::Result {
+		Self::migrate_legacy(&who);
+		let vesting = Self::vesting(&who).ok_or("the account given is not vesting with ANY assets")?;
+		let now = <system::Module<T>>::block_number();
+		let locked_now = vesting.locked_at::<T::BlockNumberToBalance>(now);
+
+		if locked_now.is_zero() {
+			T::Currency::remove_lock(VESTING_ID, &who);
+			<Vesting<T>>::remove(&who);
+			Self::deposit_event(RawEvent::VestingCompleted(who));
+		} else {
+			let reasons = WithdrawReason::Transfer | WithdrawReason::Reserve;
+			T::Currency::set_lock(VESTING_ID, &who, locked_now, T::BlockNumber::max_value(), reasons);
+			Self::deposit_event(RawEvent::VestingUpdated(who, locked_now));
+		}
+		Ok(())
+	}
+
+	/// Move a vesting schedule of `who` left behind by the Balances module, if any, into this
+	/// module.
+	///
+	/// The Balances module unlocked `per_block` every block from genesis, starting at `offset`,
+	/// which is a schedule starting at block zero with `offset` locked.
+	fn migrate_legacy(who: &T::AccountId) {
+		let key = [LEGACY_VESTING_PREFIX, &who.encode()[..]].concat();
+		let legacy: Option<LegacyVestingSchedule<BalanceOf<T>>> =
+			hashed::take(&runtime_io::blake2_256, &key);
+		if let Some(legacy) = legacy {
+			if !<Vesting<T>>::exists(who) {
+				<Vesting<T>>::insert(who, VestingInfo {
+					locked: legacy.offset,
+					per_block: legacy.per_block,
+					starting_block: Zero::zero(),
+					cliff: Zero::zero(),
+				});
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use runtime_io::with_externalities;
+	use srml_support::{assert_ok, assert_noop, assert_err, impl_outer_origin, parameter_types};
+	use substrate_primitives::{H256, Blake2Hasher};
+	use runtime_primitives::traits::{BlakeTwo256, IdentityLookup, Identity};
+	use runtime_primitives::testing::Header;
+	use runtime_primitives::{Perbill, weights::Weight};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	parameter_types! {
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type FeeMultiplierUpdate = ();
+	}
+	parameter_types! {
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const TransactionBaseFee: u64 = 0;
+		pub const TransactionByteFee: u64 = 0;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransactionPayment = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+		type TransactionBaseFee = TransactionBaseFee;
+		type TransactionByteFee = TransactionByteFee;
+		type WeightToFee = ();
+	}
+	parameter_types! {
+		pub const MinVestedTransfer: u64 = 256 * 2;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Currency = Balances;
+		type BlockNumberToBalance = Identity;
+		type MinVestedTransfer = MinVestedTransfer;
+	}
+	type System = system::Module<Test>;
+	type Balances = balances::Module<Test>;
+	type Vesting = Module<Test>;
+
+	thread_local! {
+		static EXISTENTIAL_DEPOSIT: std::cell::RefCell<u64> = std::cell::RefCell::new(0);
+	}
+	pub struct ExistentialDeposit;
+	impl Get<u64> for ExistentialDeposit {
+		fn get() -> u64 { EXISTENTIAL_DEPOSIT.with(|v| *v.borrow()) }
+	}
+
+	pub struct ExtBuilder {
+		existential_deposit: u64,
+	}
+	impl Default for ExtBuilder {
+		fn default() -> Self {
+			Self {
+				existential_deposit: 1,
+			}
+		}
+	}
+	impl ExtBuilder {
+		pub fn existential_deposit(mut self, existential_deposit: u64) -> Self {
+			self.existential_deposit = existential_deposit;
+			self
+		}
+		pub fn build(self) -> runtime_io::TestExternalities<Blake2Hasher> {
+			EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.existential_deposit);
+			let (mut t, mut c) = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+			let _ = balances::GenesisConfig::<Test> {
+				balances: vec![
+					(1, 10 * self.existential_deposit),
+					(2, 20 * self.existential_deposit),
+					(3, 30 * self.existential_deposit),
+					(4, 40 * self.existential_deposit),
+					(12, 10 * self.existential_deposit)
+				],
+				vesting: vec![],
+			}.assimilate_storage(&mut t, &mut c);
+			let _ = GenesisConfig::<Test> {
+				vesting: vec![
+					(1, 0, 10, 5 * self.existential_deposit),
+					(2, 10, 20, 0),
+					(12, 10, 20, 5 * self.existential_deposit)
+				],
+			}.assimilate_storage(&mut t, &mut c);
+			t.into()
+		}
+	}
+
+	#[test]
+	fn check_vesting_status() {
+		with_externalities(
+			&mut ExtBuilder::default()
+				.existential_deposit(256)
+				.build(),
+			|| {
+				assert_eq!(System::block_number(), 1);
+				let user1_free_balance = Balances::free_balance(&1);
+				let user2_free_balance = Balances::free_balance(&2);
+				let user12_free_balance = Balances::free_balance(&12);
+				assert_eq!(user1_free_balance, 256 * 10); // Account 1 has free balance
+				assert_eq!(user2_free_balance, 256 * 20); // Account 2 has free balance
+				assert_eq!(user12_free_balance, 256 * 10); // Account 12 has free balance
+				let user1_vesting_schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 128, // Vesting over 10 blocks
+					starting_block: 0,
+					cliff: 0,
+				};
+				let user2_vesting_schedule = VestingInfo {
+					locked: 256 * 20,
+					per_block: 256, // Vesting over 20 blocks
+					starting_block: 10,
+					cliff: 0,
+				};
+				let user12_vesting_schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 64, // Vesting over 20 blocks
+					starting_block: 10,
+					cliff: 0,
+				};
+				assert_eq!(Vesting::vesting(&1), Some(user1_vesting_schedule)); // Account 1 has a vesting schedule
+				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule)); // Account 2 has a vesting schedule
+				assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule)); // Account 12 has a vesting schedule
+
+				// Account 1 has only 128 units vested from their illiquid 256 * 5 units at block 1
+				assert_eq!(Vesting::vesting_balance(&1), 128 * 9);
+				// Account 2 has their full balance locked
+				assert_eq!(Vesting::vesting_balance(&2), user2_free_balance);
+				// Account 12 has only their illiquid funds locked
+				assert_eq!(Vesting::vesting_balance(&12), user12_free_balance - 256 * 5);
+
+				System::set_block_number(10);
+				assert_eq!(System::block_number(), 10);
+
+				// Account 1 has fully vested by block 10
+				assert_eq!(Vesting::vesting_balance(&1), 0);
+				// Account 2 has started vesting by block 10
+				assert_eq!(Vesting::vesting_balance(&2), user2_free_balance);
+				// Account 12 has started vesting by block 10
+				assert_eq!(Vesting::vesting_balance(&12), user12_free_balance - 256 * 5);
+
+				System::set_block_number(30);
+				assert_eq!(System::block_number(), 30);
+
+				assert_eq!(Vesting::vesting_balance(&1), 0); // Account 1 is still fully vested, and not negative
+				assert_eq!(Vesting::vesting_balance(&2), 0); // Account 2 has fully vested by block 30
+				assert_eq!(Vesting::vesting_balance(&12), 0); // Account 12 has fully vested by block 30
+			}
+		);
+	}
+
+	#[test]
+	fn unvested_balance_should_not_transfer() {
+		with_externalities(
+			&mut ExtBuilder::default()
+				.existential_deposit(10)
+				.build(),
+			|| {
+				assert_eq!(System::block_number(), 1);
+				let user1_free_balance = Balances::free_balance(&1);
+				assert_eq!(user1_free_balance, 100); // Account 1 has free balance
+				// Account 1 has only 5 units vested at block 1 (plus 50 unvested)
+				assert_eq!(Vesting::vesting_balance(&1), 45);
+				assert_noop!(
+					Balances::transfer(Some(1).into(), 2, 56),
+					"account liquidity restrictions prevent withdrawal"
+				); // Account 1 cannot send more than vested amount
+			}
+		);
+	}
+
+	#[test]
+	fn vested_balance_should_transfer() {
+		with_externalities(
+			&mut ExtBuilder::default()
+				.existential_deposit(10)
+				.build(),
+			|| {
+				assert_eq!(System::block_number(), 1);
+				let user1_free_balance = Balances::free_balance(&1);
+				assert_eq!(user1_free_balance, 100); // Account 1 has free balance
+				// Account 1 has only 5 units vested at block 1 (plus 50 unvested)
+				assert_eq!(Vesting::vesting_balance(&1), 45);
+				assert_ok!(Vesting::vest(Some(1).into()));
+				assert_ok!(Balances::transfer(Some(1).into(), 2, 55));
+			}
+		);
+	}
+
+	#[test]
+	fn vested_balance_should_transfer_using_vest_other() {
+		with_externalities(
+			&mut ExtBuilder::default()
+				.existential_deposit(10)
+				.build(),
+			|| {
+				assert_eq!(System::block_number(), 1);
+				assert_eq!(Vesting::vesting_balance(&1), 45);
+				assert_ok!(Vesting::vest_other(Some(2).into(), 1));
+				assert_ok!(Balances::transfer(Some(1).into(), 2, 55));
+			}
+		);
+	}
+
+	#[test]
+	fn fully_vested_accounts_lose_their_schedule() {
+		with_externalities(
+			&mut ExtBuilder::default()
+				.existential_deposit(10)
+				.build(),
+			|| {
+				System::set_block_number(10);
+				assert_ok!(Vesting::vest(Some(1).into()));
+				assert_eq!(Vesting::vesting(&1), None);
+				assert!(Balances::locks(&1).is_empty());
+				assert_ok!(Balances::transfer(Some(1).into(), 2, 90));
+				assert_noop!(Vesting::vest(Some(1).into()), "the account given is not vesting with ANY assets");
+			}
+		);
+	}
+
+	#[test]
+	fn vested_transfer_works() {
+		with_externalities(
+			&mut ExtBuilder::default()
+				.existential_deposit(256)
+				.build(),
+			|| {
+				assert_eq!(System::block_number(), 1);
+				let user3_free_balance = Balances::free_balance(&3);
+				let user4_free_balance = Balances::free_balance(&4);
+				assert_eq!(user3_free_balance, 256 * 30);
+				assert_eq!(user4_free_balance, 256 * 40);
+				// Account 4 should not have any vesting yet.
+				assert_eq!(Vesting::vesting(&4), None);
+				// Make the schedule for the new transfer.
+				let new_vesting_schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 64, // Vesting over 20 blocks
+					starting_block: 10,
+					cliff: 0,
+				};
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule));
+				// Now account 4 should have vesting.
+				assert_eq!(Vesting::vesting(&4), Some(new_vesting_schedule));
+				// Ensure the transfer happened correctly.
+				assert_eq!(Balances::free_balance(&3), user3_free_balance - 256 * 5);
+				assert_eq!(Balances::free_balance(&4), user4_free_balance + 256 * 5);
+				// Account 4 has 5 * 256 locked.
+				assert_eq!(Vesting::vesting_balance(&4), 256 * 5);
+
+				System::set_block_number(20);
+				// Account 4 has 10 * 64 units vested by block 20, and as many still locked.
+				assert_eq!(Vesting::vesting_balance(&4), 10 * 64);
+
+				System::set_block_number(30);
+				// Account 4 has fully vested.
+				assert_eq!(Vesting::vesting_balance(&4), 0);
+			}
+		);
+	}
+
+	#[test]
+	fn vested_transfer_correctly_fails() {
+		with_externalities(
+			&mut ExtBuilder::default()
+				.existential_deposit(256)
+				.build(),
+			|| {
+				let user2_free_balance = Balances::free_balance(&2);
+				let user4_free_balance = Balances::free_balance(&4);
+				// Account 2 already has a vesting schedule.
+				let new_vesting_schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 64, // Vesting over 20 blocks
+					starting_block: 10,
+					cliff: 0,
+				};
+				assert_noop!(
+					Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule),
+					"an existing vesting schedule already exists for this account that cannot be clobbered"
+				);
+
+				// Fails due to too low transfer amount.
+				let new_vesting_schedule_too_low = VestingInfo {
+					locked: 256 * 1,
+					per_block: 64,
+					starting_block: 10,
+					cliff: 0,
+				};
+				assert_noop!(
+					Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule_too_low),
+					"amount transferred is too low"
+				);
+
+				// Verify no currency transfer happened.
+				assert_eq!(user2_free_balance, Balances::free_balance(&2));
+				assert_eq!(user4_free_balance, Balances::free_balance(&4));
+			}
+		);
+	}
+
+	#[test]
+	fn cliff_holds_back_vesting() {
+		with_externalities(
+			&mut ExtBuilder::default()
+				.existential_deposit(256)
+				.build(),
+			|| {
+				let schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 64,
+					starting_block: 10,
+					cliff: 5,
+				};
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule));
+
+				System::set_block_number(14);
+				assert_eq!(Vesting::vesting_balance(&4), 256 * 5);
+				assert_ok!(Vesting::vest(Some(4).into()));
+				assert_err!(
+					Balances::transfer(Some(4).into(), 1, 256 * 40 + 1),
+					"account liquidity restrictions prevent withdrawal"
+				);
+
+				// Everything that vested during the cliff unlocks at once.
+				System::set_block_number(15);
+				assert_eq!(Vesting::vesting_balance(&4), 256 * 5 - 5 * 64);
+				assert_ok!(Vesting::vest(Some(4).into()));
+				assert_ok!(Balances::transfer(Some(4).into(), 1, 256 * 40 + 5 * 64));
+			}
+		);
+	}
+
+	#[test]
+	fn legacy_balances_vesting_is_migrated() {
+		with_externalities(
+			&mut ExtBuilder::default()
+				.existential_deposit(10)
+				.build(),
+			|| {
+				<balances::Vesting<Test>>::insert(3, balances::VestingSchedule {
+					offset: 300,
+					per_block: 10,
+				});
+				let key = [LEGACY_VESTING_PREFIX, &3u64.encode()[..]].concat();
+				assert!(hashed::exists(&runtime_io::blake2_256, &key));
+
+				// Until the schedule is migrated, the Balances module keeps enforcing it.
+				assert_eq!(Vesting::vesting(&3), None);
+				System::set_block_number(10);
+				assert_noop!(
+					Balances::transfer(Some(3).into(), 1, 101),
+					"vesting balance too high to send value"
+				);
+
+				assert_ok!(Vesting::vest(Some(3).into()));
+				assert_eq!(Vesting::vesting(&3), Some(VestingInfo {
+					locked: 300,
+					per_block: 10,
+					starting_block: 0,
+					cliff: 0,
+				}));
+				assert_eq!(Vesting::vesting_balance(&3), 200);
+				assert!(!hashed::exists(&runtime_io::blake2_256, &key));
+				assert_noop!(
+					Balances::transfer(Some(3).into(), 1, 101),
+					"account liquidity restrictions prevent withdrawal"
+				);
+				assert_ok!(Balances::transfer(Some(3).into(), 1, 100));
+			}
+		);
+	}
+}
@@ -23,10 +23,10 @@ use hex_literal::hex;
 use clap::load_yaml;
 use bip39::{Mnemonic, Language, MnemonicType};
 use substrate_primitives::{ed25519, sr25519, hexdisplay::HexDisplay, Pair, crypto::Ss58Codec, blake2_256};
-use parity_codec::{Encode, Decode, Compact};
+use parity_codec::{Encode, Decode};
 use sr_primitives::generic::Era;
 use node_primitives::{Balance, Index, Hash};
-use node_runtime::{Call, UncheckedExtrinsic, BalancesCall};
+use node_runtime::{Call, UncheckedExtrinsic, SignedExtra, BalancesCall};
 
 mod vanity;
 
@@ -152,8 +152,7 @@ fn execute<C: Crypto>(matches: clap::ArgMatches) where
 
 			println!("Using a genesis hash of {}", HexDisplay::from(&genesis_hash.as_ref()));
 
-			let era = Era::immortal();
-			let raw_payload = (Compact(index), function, era, genesis_hash);
+			let raw_payload = (function, signed_extra(index), (genesis_hash, genesis_hash, (), (), ()));
 			let signature = raw_payload.using_encoded(|payload| if payload.len() > 256 {
 				signer.sign(&blake2_256(payload)[..])
 			} else {
@@ -161,11 +160,10 @@ fn execute<C: Crypto>(matches: clap::ArgMatches) where
 				signer.sign(payload)
 			});
 			let extrinsic = UncheckedExtrinsic::new_signed(
-				index,
-				raw_payload.1,
+				raw_payload.0,
 				signer.public().into(),
 				signature.into(),
-				era,
+				raw_payload.1,
 			);
 			println!("0x{}", hex::encode(&extrinsic.encode()));
 		}
@@ -190,9 +188,8 @@ fn execute<C: Crypto>(matches: clap::ArgMatches) where
 				.and_then(|x| Decode::decode(&mut &x[..]))
 				.expect("Invalid prior block hash");
 
-			let era = Era::immortal();
-
-			let raw_payload = (Compact(index), function, era, prior_block_hash);
+			// The transaction is immortal, so the prior block is the genesis block.
+			let raw_payload = (function, signed_extra(index), (prior_block_hash, prior_block_hash, (), (), ()));
 			let signature = raw_payload.using_encoded(|payload|
 				if payload.len() > 256 {
 					signer.sign(&blake2_256(payload)[..])
@@ -202,11 +199,10 @@ fn execute<C: Crypto>(matches: clap::ArgMatches) where
 			);
 
 			let extrinsic = UncheckedExtrinsic::new_signed(
-				index,
-				raw_payload.1,
+				raw_payload.0,
 				signer.public().into(),
 				signature.into(),
-				era,
+				raw_payload.1,
 			);
 
 			println!("0x{}", hex::encode(&extrinsic.encode()));
@@ -240,6 +236,17 @@ fn execute<C: Crypto>(matches: clap::ArgMatches) where
 	}
 }
 
+/// The signed extensions of an immortal transaction with the given index and no tip.
+fn signed_extra(index: Index) -> SignedExtra {
+	(
+		srml_system::CheckGenesis::new(),
+		srml_system::CheckEra::from(Era::immortal()),
+		srml_system::CheckNonce::from(index),
+		srml_system::CheckWeight::new(),
+		srml_balances::TakeFees::from(0),
+	)
+}
+
 fn main() {
 	let yaml = load_yaml!("cli.yml");
 	let matches = clap::App::from_yaml(yaml)
@@ -78,6 +78,7 @@ pub trait RuntimeAdapter {
 		key: &Self::Secret,
 		destination: &Self::AccountId,
 		amount: &Self::Number,
+		genesis_hash: &<Self::Block as BlockT>::Hash,
 		prior_block_hash: &<Self::Block as BlockT>::Hash,
 	) -> <Self::Block as BlockT>::Extrinsic;
 
@@ -112,6 +113,7 @@ where
 	}
 
 	let client = new_client::<F>(&config)?;
+	let genesis_hash = client.info().chain.genesis_hash;
 
 	let select_chain = F::build_select_chain(&mut config, client.clone())?;
 
@@ -122,9 +124,13 @@ where
 
 	while let Some(block) = match factory_state.mode() {
 		Mode::MasterToNToM =>
-			complex_mode::next::<F, RA>(&mut factory_state, &client, best_hash.into(), best_block_id),
+			complex_mode::next::<F, RA>(
+				&mut factory_state, &client, genesis_hash.into(), best_hash.into(), best_block_id,
+			),
 		_ =>
-			simple_modes::next::<F, RA>(&mut factory_state, &client, best_hash.into(), best_block_id)
+			simple_modes::next::<F, RA>(
+				&mut factory_state, &client, genesis_hash.into(), best_hash.into(), best_block_id,
+			),
 	} {
 		best_hash = block.header().hash();
 		import_block::<F>(&client, block);